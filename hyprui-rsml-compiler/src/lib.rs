@@ -6,8 +6,8 @@
 //!
 //! The compiler follows a traditional compiler pipeline:
 //! 1. **Tokenization**: Raw RSML text → Stream of tokens
-//! 2. **Parsing**: Stream of tokens → DOM tree
-//! 3. **Code Generation**: DOM tree → Rust code string
+//! 2. **Parsing**: Stream of tokens → DOM tree, both done by `hyprui-rsml-parser`
+//! 3. **Code Generation**: DOM tree → Rust tokens, built with `quote!`
 //!
 //! ## Example Transformation
 //!
@@ -23,565 +23,160 @@
 /// ```rust,ignore
 /// Box::new(hyprui::Container::new().padding_all(16).center()
 ///     .child(Box::new(hyprui::Text::new("Hello World!").font_size(18)))
-///     .child(hyprui::Component::new(MyComponent, {
-///         let mut props = Default::default();
-///         props.name = "test";
-///         props.active = true;
-///         props
-///     })))
+///     .child(hyprui::Component::new(MyComponent, hyprui::props_for(&MyComponent, |props| {
+///         props.name = "test".into();
+///         props.active = true.into();
+///     }))))
 /// ```
+use hyprui_rsml_parser::{Attribute, AttributeValue, Element, Node, Parser, RustCode};
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, quote, quote_spanned};
+use syn::{Expr, Ident, LitStr, Pat, Token, parse::Parse, punctuated::Punctuated};
 
-// ============================================================================
-// DOM DATA STRUCTURES
-// ============================================================================
-
-/// A node in the RSML DOM tree.
-///
-/// The DOM represents the parsed structure before code generation.
-/// This allows for easy inspection, transformation, and debugging.
-#[derive(Debug, Clone, PartialEq)]
-enum Node {
-	/// An HTML-like element: `<tag attr="value">children</tag>`
-	Element(Element),
-	/// Plain text content between tags: `Hello World`
-	Text(String),
-	/// Rust expression in braces: `{some_variable + 1}`
-	Expression(String),
-}
-
-/// An RSML element with tag name, attributes, and children.
-///
-/// Examples:
-/// - `<container />` - self-closing with no attributes
-/// - `<text font_size={16}>Hello</text>` - with attributes and text content
-/// - `<MyComponent prop="value">...</MyComponent>` - component with children
-#[derive(Debug, Clone, PartialEq)]
-struct Element {
-	/// The tag name (e.g., "container", "text", "MyComponent")
-	tag_name: String,
-	/// All attributes on the element
-	attributes: Vec<Attribute>,
-	/// Child nodes (other elements, text, or expressions)
-	children: Vec<Node>,
-	/// Whether this is a self-closing tag like `<container />`
-	self_closing: bool,
-}
-
-/// An attribute on an RSML element.
-///
-/// Examples:
-/// - `disabled` - boolean attribute (no value)
-/// - `name="John"` - string literal value
-/// - `size={42}` - expression value
-#[derive(Debug, Clone, PartialEq)]
-struct Attribute {
-	/// The attribute name
-	name: String,
-	/// The attribute value (None for boolean attributes)
-	value: Option<AttributeValue>,
-}
-
-/// The value of an attribute.
-#[derive(Debug, Clone, PartialEq)]
-enum AttributeValue {
-	/// String literal: `name="value"`
-	String(String),
-	/// Rust expression: `size={variable + 1}`
-	Expression(String),
-}
+mod props;
+mod source;
 
 // ============================================================================
-// TOKENIZER
+// CODE GENERATOR
 // ============================================================================
 
-/// A token in the RSML token stream.
+/// Generates Rust code from a DOM tree.
 ///
-/// Tokens are the atomic units that the parser works with.
-/// They represent meaningful syntax elements like tags, attributes, etc.
-#[derive(Debug, Clone, PartialEq)]
-enum Token {
-	/// Opening tag bracket: `<`
-	OpenTag,
-	/// Closing tag bracket: `>`
-	CloseTag,
-	/// Self-closing tag: `/>`
-	SelfCloseTag,
-	/// End tag opening: `</`
-	EndOpenTag,
-	/// Identifier: tag names, attribute names, etc.
-	Identifier(String),
-	/// String literal in quotes: `"hello"` or `'hello'`
-	StringLiteral(String),
-	/// Rust expression in braces: `{code here}`
-	Expression(String),
-	/// Equals sign for attributes: `=`
-	Equals,
-	/// End of input
-	Eof,
-}
-
-/// Converts raw RSML text into a stream of tokens.
+/// The code generator traverses the DOM and produces idiomatic HyprUI Rust code.
+/// It handles:
+/// - Built-in elements (container, text) → Element constructors
+/// - Components (uppercase tags) → Component::new with props
+/// - Attributes → Method calls or prop assignments
+/// - Children → .child() calls or props.children vector
 ///
-/// The tokenizer handles:
-/// - Proper brace matching for expressions `{...}`
-/// - String literal parsing with escape sequences
-/// - JSX-style tag syntax `<`, `>`, `</`, `/>`
-/// - Identifier recognition for tag and attribute names
-struct Tokenizer {
-	/// Input text as a vector of characters for easy indexing
-	input: Vec<char>,
-	/// Current position in the input
-	position: usize,
-	/// Current character being processed (None at EOF)
-	current_char: Option<char>,
+/// The code is built with `quote!`, and the Rust code written in braces is parsed with `syn`
+/// first, so invalid code is reported where it was written in the input.
+struct CodeGenerator<'a> {
+	/// The macro input, to give the generated code the spans it was written with
+	source: Option<&'a source::Source>,
+	/// The path and text of the file read by `rsml_file!`, to tell where errors are in it
+	file: Option<(&'a str, &'a str)>,
 }
 
-impl Tokenizer {
-	/// Create a new tokenizer for the given input text.
-	fn new(input: &str) -> Self {
-		let chars: Vec<char> = input.chars().collect();
-		let current_char = chars.get(0).copied();
+impl<'a> CodeGenerator<'a> {
+	/// Without the macro input, generated code gets the call site span, as when compiling the test files.
+	#[cfg(test)]
+	fn new() -> Self {
 		Self {
-			input: chars,
-			position: 0,
-			current_char,
+			source: None,
+			file: None,
 		}
 	}
 
-	/// Advance to the next character in the input.
-	fn advance(&mut self) {
-		self.position += 1;
-		self.current_char = self.input.get(self.position).copied();
-	}
-
-	/// Look at the next character without advancing.
-	fn peek(&self) -> Option<char> {
-		self.input.get(self.position + 1).copied()
-	}
-
-	/// Skip over whitespace characters.
-	fn skip_whitespace(&mut self) {
-		while let Some(ch) = self.current_char {
-			if ch.is_whitespace() {
-				self.advance();
-			} else {
-				break;
-			}
+	fn with_source(source: &'a source::Source) -> Self {
+		Self {
+			source: Some(source),
+			file: None,
 		}
 	}
 
-	/// Read an identifier (tag name, attribute name, etc.).
-	///
-	/// Identifiers can contain letters, numbers, underscores, and hyphens.
-	/// Examples: `container`, `font_size`, `MyComponent`, `data-id`
-	fn read_identifier(&mut self) -> String {
-		let mut result = String::new();
-
-		while let Some(ch) = self.current_char {
-			if ch.is_alphanumeric() || ch == '_' || ch == '-' {
-				result.push(ch);
-				self.advance();
-			} else {
-				break;
-			}
+	/// Generated code gets the call site span, and errors tell their line and column in the file.
+	fn with_file(path: &'a str, text: &'a str) -> Self {
+		Self {
+			source: None,
+			file: Some((path, text)),
 		}
-
-		result
 	}
 
-	/// Read a string literal, handling escape sequences.
+	/// Generate Rust code for a DOM node.
 	///
-	/// Supports both double and single quotes: `"hello"` or `'hello'`
-	/// Handles escape sequences like `\"` and `\\`
-	fn read_string_literal(&mut self) -> String {
-		let quote_char = self.current_char.unwrap(); // " or '
-		self.advance(); // skip opening quote
-
-		let mut result = String::new();
-		let mut escaped = false;
-
-		while let Some(ch) = self.current_char {
-			if escaped {
-				result.push(ch);
-				escaped = false;
-			} else if ch == '\\' {
-				escaped = true;
-				result.push(ch);
-			} else if ch == quote_char {
-				self.advance(); // skip closing quote
-				break;
-			} else {
-				result.push(ch);
-			}
-			self.advance();
-		}
-
-		result
+	/// This is the main entry point that dispatches to specific
+	/// generation methods based on the node type.
+	fn generate(&self, node: &Node) -> syn::Result<TokenStream2> {
+		self.generate_with_box(node, true)
 	}
 
-	/// Read a Rust expression inside braces: `{expression here}`
-	///
-	/// This handles proper brace matching, so expressions like `{vec![1, 2, 3]}`
-	/// or `{if condition { "yes" } else { "no" }}` are parsed correctly.
-	///
-	/// Also handles string literals inside expressions to avoid false matches.
-	fn read_expression(&mut self) -> String {
-		self.advance(); // skip opening {
-
-		let mut result = String::new();
-		let mut brace_count = 1; // We're already inside one brace
-		let mut in_string = false;
-		let mut string_char = '"';
-		let mut escaped = false;
-
-		while let Some(ch) = self.current_char {
-			if escaped {
-				result.push(ch);
-				escaped = false;
-			} else if ch == '\\' && in_string {
-				result.push(ch);
-				escaped = true;
-			} else if (ch == '"' || ch == '\'') && !in_string {
-				// Entering a string
-				in_string = true;
-				string_char = ch;
-				result.push(ch);
-			} else if ch == string_char && in_string {
-				// Exiting a string
-				in_string = false;
-				result.push(ch);
-			} else if !in_string {
-				// Only count braces when not inside a string
-				if ch == '{' {
-					brace_count += 1;
-					result.push(ch);
-				} else if ch == '}' {
-					brace_count -= 1;
-					if brace_count == 0 {
-						self.advance(); // skip closing }
-						break;
-					}
-					result.push(ch);
-				} else {
-					result.push(ch);
-				}
-			} else {
-				result.push(ch);
-			}
-			self.advance();
-		}
-
-		result
+	/// Returns the span of the token at `position` in the input.
+	fn span_at(&self, position: usize) -> Span {
+		self
+			.source
+			.map_or_else(Span::call_site, |source| source.span_at(position))
 	}
 
-	/// Get the next token from the input stream.
-	///
-	/// This is the main tokenizer method that identifies and returns
-	/// the next meaningful token in the input.
-	fn next_token(&mut self) -> Token {
-		loop {
-			match self.current_char {
-				None => return Token::Eof,
-
-				Some(ch) if ch.is_whitespace() => {
-					self.skip_whitespace();
-					continue; // Skip whitespace and continue
-				}
-
-				Some('<') => {
-					if self.peek() == Some('/') {
-						// Closing tag: </
-						self.advance(); // skip <
-						self.advance(); // skip /
-						return Token::EndOpenTag;
-					} else {
-						// Opening tag: <
-						self.advance();
-						return Token::OpenTag;
-					}
-				}
-
-				Some('/') if self.peek() == Some('>') => {
-					// Self-closing tag: />
-					self.advance(); // skip /
-					self.advance(); // skip >
-					return Token::SelfCloseTag;
-				}
-
-				Some('>') => {
-					// End of opening tag: >
-					self.advance();
-					return Token::CloseTag;
-				}
-
-				Some('=') => {
-					// Attribute assignment: =
-					self.advance();
-					return Token::Equals;
-				}
-
-				Some('"') | Some('\'') => {
-					// String literal
-					let string_val = self.read_string_literal();
-					return Token::StringLiteral(string_val);
-				}
-
-				Some('{') => {
-					// Rust expression
-					let expr = self.read_expression();
-					return Token::Expression(expr);
-				}
-
-				Some(ch) if ch.is_alphabetic() || ch == '_' => {
-					// Identifier (tag name, attribute name, etc.)
-					let ident = self.read_identifier();
-					return Token::Identifier(ident);
-				}
-
-				Some(_) => {
-					// Unknown character - skip it
-					self.advance();
-					continue;
-				}
+	fn error(&self, position: usize, message: impl std::fmt::Display) -> syn::Error {
+		match self.file {
+			Some((path, text)) => {
+				let (line, column) = hyprui_rsml_parser::line_column(text, position);
+				syn::Error::new(
+					Span::call_site(),
+					format!("{message} ({path}:{line}:{column})"),
+				)
 			}
+			None => syn::Error::new(self.span_at(position), message),
 		}
 	}
-}
-
-// ============================================================================
-// PARSER
-// ============================================================================
 
-/// Converts a stream of tokens into a DOM tree.
-///
-/// The parser implements a recursive descent parser that recognizes
-/// the RSML grammar and builds a structured DOM representation.
-struct Parser {
-	/// The tokenizer that provides the token stream
-	tokenizer: Tokenizer,
-	/// The current token being processed
-	current_token: Token,
-}
-
-impl Parser {
-	/// Create a new parser for the given input text.
-	fn new(input: &str) -> Self {
-		let mut tokenizer = Tokenizer::new(input);
-		let current_token = tokenizer.next_token();
-		Self {
-			tokenizer,
-			current_token,
+	/// Parse Rust code written in braces with `parser`, like `Expr::parse`.
+	fn parse_code<T>(
+		&self,
+		code: &RustCode,
+		parser: impl syn::parse::Parser<Output = T>,
+	) -> syn::Result<T> {
+		if code.code.trim().is_empty() {
+			return Err(self.error(code.position, "expected Rust code in the braces"));
 		}
-	}
-
-	/// Advance to the next token.
-	fn advance(&mut self) {
-		self.current_token = self.tokenizer.next_token();
-	}
-
-	/// Expect a specific token and advance, or return an error.
-	///
-	/// This is used to enforce the grammar rules. For example,
-	/// after parsing a tag name, we expect to see either attributes or `>`.
-	fn expect_token(&mut self, expected: Token) -> Result<(), String> {
-		if std::mem::discriminant(&self.current_token) == std::mem::discriminant(&expected) {
-			self.advance();
-			Ok(())
-		} else {
-			Err(format!(
-				"Expected {:?}, found {:?}",
-				expected, self.current_token
-			))
-		}
-	}
-
-	/// Parse attributes from the current token position.
-	///
-	/// Attributes have the form:
-	/// - `name="value"` - string attribute
-	/// - `name={expression}` - expression attribute
-	/// - `name` - boolean attribute (no value)
-	///
-	/// Returns a vector of parsed attributes.
-	fn parse_attributes(&mut self) -> Result<Vec<Attribute>, String> {
-		let mut attributes = Vec::new();
-
-		// Keep parsing attributes while we see identifiers
-		while let Token::Identifier(name) = &self.current_token {
-			let attr_name = name.clone();
-			self.advance();
-
-			let value = if matches!(self.current_token, Token::Equals) {
-				self.advance(); // consume =
-
-				// Parse the attribute value
-				match &self.current_token {
-					Token::StringLiteral(s) => {
-						let val = Some(AttributeValue::String(s.clone()));
-						self.advance();
-						val
-					}
-					Token::Expression(e) => {
-						let val = Some(AttributeValue::Expression(e.clone()));
-						self.advance();
-						val
-					}
-					_ => return Err("Expected string literal or expression after =".to_string()),
-				}
-			} else {
-				// Boolean attribute (no value means true)
-				None
-			};
-
-			attributes.push(Attribute {
-				name: attr_name,
-				value,
-			});
-		}
-
-		Ok(attributes)
-	}
-
-	/// Parse an RSML element from the token stream.
-	///
-	/// Elements have the form:
-	/// - `<tag />` - self-closing element
-	/// - `<tag>children</tag>` - element with children
-	/// - `<tag attr="value">children</tag>` - element with attributes and children
-	///
-	/// Returns the parsed element as a Node::Element.
-	fn parse_element(&mut self) -> Result<Node, String> {
-		self.expect_token(Token::OpenTag)?; // consume <
-
-		// Get the tag name
-		let tag_name = match &self.current_token {
-			Token::Identifier(name) => name.clone(),
-			_ => return Err("Expected tag name after <".to_string()),
+		let tokens = code
+			.code
+			.parse::<TokenStream2>()
+			.map_err(|e| self.error(code.position, e))?;
+		let tokens = match self.source {
+			Some(source) => source.respan(tokens, code.position),
+			None => tokens,
 		};
-		self.advance();
-
-		// Parse attributes
-		let attributes = self.parse_attributes()?;
-
-		// Check for self-closing tag
-		let self_closing = matches!(self.current_token, Token::SelfCloseTag);
-
-		if self_closing {
-			self.advance(); // consume />
-			return Ok(Node::Element(Element {
-				tag_name,
-				attributes,
-				children: vec![],
-				self_closing: true,
-			}));
-		}
-
-		// Consume the closing > of the opening tag
-		self.expect_token(Token::CloseTag)?; // consume >
-
-		let mut children = Vec::new();
-
-		// Parse children until we hit the closing tag
-		while !matches!(self.current_token, Token::EndOpenTag) {
-			match &self.current_token {
-				Token::OpenTag => {
-					// Nested element
-					children.push(self.parse_element()?);
-				}
-				Token::Expression(expr) => {
-					// Expression child: {some_expression}
-					children.push(Node::Expression(expr.clone()));
-					self.advance();
-				}
-				Token::Identifier(_) => {
-					// Text content between tags
-					if let Token::Identifier(text) = &self.current_token {
-						children.push(Node::Text(text.clone()));
-						self.advance();
-					}
-				}
-				Token::Eof => {
-					return Err(format!("Unexpected EOF while parsing <{}>", tag_name));
-				}
-				_ => {
-					// Skip unknown tokens
-					self.advance();
-				}
-			}
-		}
-
-		// Parse the closing tag: </tagname>
-		self.expect_token(Token::EndOpenTag)?; // consume </
-
-		// Verify the closing tag name matches the opening tag
-		if let Token::Identifier(closing_name) = &self.current_token {
-			if *closing_name != tag_name {
-				return Err(format!(
-					"Mismatched closing tag: expected </{}>, found </{}>",
-					tag_name, closing_name
-				));
-			}
-			self.advance();
-		} else {
-			return Err("Expected tag name in closing tag".to_string());
-		}
-
-		self.expect_token(Token::CloseTag)?; // consume >
-
-		Ok(Node::Element(Element {
-			tag_name,
-			attributes,
-			children,
-			self_closing: false,
-		}))
+		parser.parse2(tokens).map_err(|e| match self.file {
+			// Without spans in the file, the error only says where it is
+			Some(_) => self.error(code.position, e),
+			None => e,
+		})
 	}
 
-	/// Parse the entire RSML input and return the root DOM node.
-	fn parse(&mut self) -> Result<Node, String> {
-		self.parse_element()
+	fn expression(&self, code: &RustCode) -> syn::Result<Expr> {
+		self.parse_code(code, Expr::parse)
 	}
-}
 
-// ============================================================================
-// CODE GENERATOR
-// ============================================================================
-
-/// Generates Rust code from a DOM tree.
-///
-/// The code generator traverses the DOM and produces idiomatic HyprUI Rust code.
-/// It handles:
-/// - Built-in elements (container, text) → Element constructors
-/// - Components (uppercase tags) → Component::new with props
-/// - Attributes → Method calls or prop assignments
-/// - Children → .child() calls or props.children vector
-struct CodeGenerator;
+	/// Parse the arguments of a method attribute, which can be several like `symmetric_padding={12, 8}`.
+	fn arguments(&self, code: &RustCode) -> syn::Result<Punctuated<Expr, Token![,]>> {
+		self.parse_code(code, Punctuated::parse_terminated)
+	}
 
-impl CodeGenerator {
-	fn new() -> Self {
-		Self
+	/// Turns the content of a string attribute back into a string literal, keeping its escapes.
+	fn string_literal(&self, value: &str, position: usize) -> syn::Result<LitStr> {
+		let mut literal = syn::parse_str::<LitStr>(&format!("\"{}\"", value))
+			.map_err(|_| self.error(position, "invalid string literal"))?;
+		literal.set_span(self.span_at(position));
+		Ok(literal)
 	}
 
-	/// Generate Rust code for a DOM node.
-	///
-	/// This is the main entry point that dispatches to specific
-	/// generation methods based on the node type.
-	fn generate(&self, node: &Node) -> String {
-		self.generate_with_box(node, true)
+	/// Returns the identifier of an attribute or a component, with the span it was written with.
+	fn ident(&self, name: &str, position: usize) -> syn::Result<Ident> {
+		let mut ident = syn::parse_str::<Ident>(name).map_err(|_| {
+			self.error(
+				position,
+				format!("`{}` is not a valid Rust identifier", name),
+			)
+		})?;
+		ident.set_span(self.span_at(position));
+		Ok(ident)
 	}
 
 	/// Generate Rust code for a DOM node, with option to wrap in Box::new().
-	fn generate_with_box(&self, node: &Node, wrap_in_box: bool) -> String {
+	fn generate_with_box(&self, node: &Node, wrap_in_box: bool) -> syn::Result<TokenStream2> {
 		let code = match node {
-			Node::Element(element) => self.generate_element_inner(element),
-			Node::Text(text) => format!("hyprui::Text::new(\"{}\")", text),
-			Node::Expression(expr) => expr.clone(),
+			Node::Element(element) => self.generate_element_inner(element)?,
+			Node::Text(text) => quote! { hyprui::Text::new(#text) },
+			Node::Expression(expr) => self.expression(expr)?.into_token_stream(),
 		};
 
 		if wrap_in_box && matches!(node, Node::Element(_)) {
-			format!("Box::new({})", code)
+			Ok(quote! { Box::new(#code) })
 		} else {
-			code
+			Ok(code)
 		}
 	}
 
@@ -589,183 +184,357 @@ impl CodeGenerator {
 	///
 	/// Determines whether the element is a component (uppercase) or
 	/// a built-in element (lowercase) and generates appropriate code.
-	fn generate_element_inner(&self, element: &Element) -> String {
-		// Components start with uppercase letters
-		if element.tag_name.chars().next().unwrap().is_uppercase() {
-			return self.generate_component(element);
+	fn generate_element_inner(&self, element: &Element) -> syn::Result<TokenStream2> {
+		if self.is_component(element) {
+			return self.generate_component(element, None);
+		}
+		if element.tag_name == "for" {
+			return self.generate_for(element);
 		}
 
 		// Map RSML tag names to HyprUI types
 		let element_type = match element.tag_name.as_str() {
-			"container" => "hyprui::Container",
-			"text" => "hyprui::Text",
-			_ => &element.tag_name,
+			"container" => quote! { hyprui::Container },
+			"text" => quote! { hyprui::Text },
+			"image" => quote! { hyprui::Image },
+			"svg" => quote! { hyprui::Svg },
+			tag_name => self.ident(tag_name, element.position)?.into_token_stream(),
 		};
 
-		let mut code = if element.tag_name == "text" {
-			// Text has special constructor: Text::new(content)
-			let format_string = element
-				.children
-				.iter()
-				.map(|child| match child {
-					Node::Text(text) => text.trim().to_string(),
-					Node::Expression(_) => "{}".to_string(),
-					Node::Element(element) => panic!(
-						"Text element cannot contain other elements, but found {:?}",
-						element
-					),
-				})
-				.collect::<Vec<String>>()
-				.join(" ");
-			let fmt_args = element
+		let is_rich_text = element.tag_name == "text"
+			&& element
 				.children
 				.iter()
-				.filter_map(|child| match child {
-					Node::Text(_) => None,
-					Node::Expression(expr) => Some(expr.clone()),
-					Node::Element(element) => panic!(
-						"Text element cannot contain other elements, but found {:?}",
-						element
-					),
-				})
-				.collect::<Vec<String>>()
-				.join(", ");
-			let format_call = format!("format!(\"{}\", {})", format_string, fmt_args);
-			format!(
-				"{}::new({})",
-				element_type,
-				if fmt_args.is_empty() {
-					format!("\"{format_string}\"")
-				} else {
-					format_call
-				}
-			)
+				.any(|child| matches!(child, Node::Element(_)));
+		let code = if is_rich_text {
+			self.generate_rich_text(element)?
+		} else if element.tag_name == "text" {
+			// Text has special constructor: Text::new(content)
+			let content = self.text_content("text", &element.children)?;
+			quote! { #element_type::new(#content) }
 		} else {
 			// Regular constructor: Element::new()
-			format!("{}::new()", element_type)
+			quote! { #element_type::new() }
 		};
+		let mut code = self.apply_attributes(code, &element.attributes)?;
 
-		// Convert attributes to method calls
-		for attr in &element.attributes {
-			match &attr.value {
+		// Add children as .child() calls (except for text which handle children differently)
+		if element.tag_name != "text" {
+			for child in &element.children {
+				match child {
+					Node::Text(text) if text.trim().is_empty() => {
+						// Skip whitespace-only text nodes
+						continue;
+					}
+					_ => {
+						let child_code = self.generate_with_box(child, false)?;
+						code = quote! { #code.child(#child_code) };
+					}
+				}
+			}
+		}
+
+		Ok(code)
+	}
+
+	/// Convert attributes to method calls on `code`.
+	fn apply_attributes(
+		&self,
+		mut code: TokenStream2,
+		attributes: &[Attribute],
+	) -> syn::Result<TokenStream2> {
+		for attr in attributes {
+			let method = self.ident(&attr.name, attr.position)?;
+			code = match &attr.value {
 				Some(AttributeValue::String(s)) => {
 					// String attribute: .method("value")
-					code = format!("{}.{}(\"{}\")", code, attr.name, s);
+					let value = self.string_literal(s, attr.position)?;
+					quote! { #code.#method(#value) }
+				}
+				Some(AttributeValue::Expression(e)) if self.is_boolean_method(&attr.name) => {
+					// Boolean method with expression: if expr { .method() } else { identity }
+					let value = self.expression(e)?;
+					let element = Ident::new("element", Span::mixed_site());
+					quote! {{
+						let #element = #code;
+						if #value { #element.#method() } else { #element }
+					}}
 				}
 				Some(AttributeValue::Expression(e)) => {
-					if self.is_boolean_method(&attr.name) {
-						// Boolean method with expression: if expr { .method() } else { identity }
-						code = format!(
-							"if {} {{ {}.{}() }} else {{ {} }}",
-							e, code, attr.name, code
-						);
-					} else {
-						// Regular method with expression: .method(expr)
-						code = format!("{}.{}({})", code, attr.name, e);
-					}
+					// Regular method with expression: .method(expr)
+					let arguments = self.arguments(e)?;
+					quote! { #code.#method(#arguments) }
+				}
+				None if self.is_binding_method(&attr.name) => {
+					// Binding attribute without value: .method(true)
+					quote! { #code.#method(true) }
 				}
 				None => {
 					// Boolean attribute without value: .method()
-					code = format!("{}.{}()", code, attr.name);
+					quote! { #code.#method() }
+				}
+			};
+		}
+		Ok(code)
+	}
+
+	/// Generate the string shown by a `<text>` or a `<span>`: a string literal, or a `format!` call
+	/// when the content has expressions.
+	fn text_content(&self, tag_name: &str, children: &[Node]) -> syn::Result<TokenStream2> {
+		let mut format_string = Vec::new();
+		let mut fmt_args = Vec::new();
+		for child in children {
+			match child {
+				// Braces in the text are escaped for format!
+				Node::Text(text) => format_string.push(text.replace('{', "{{").replace('}', "}}")),
+				Node::Expression(expr) => {
+					format_string.push("{}".to_string());
+					fmt_args.push(self.expression(expr)?);
+				}
+				Node::Element(child) => {
+					return Err(self.error(
+						child.position,
+						format!("<{tag_name}> can only contain text and expressions"),
+					));
 				}
 			}
 		}
+		if fmt_args.is_empty() {
+			let text = children
+				.iter()
+				.filter_map(|child| match child {
+					Node::Text(text) => Some(text.as_str()),
+					_ => None,
+				})
+				.collect::<String>();
+			Ok(quote! { #text })
+		} else {
+			let format_string = format_string.concat();
+			Ok(quote! { format!(#format_string, #(#fmt_args),*) })
+		}
+	}
 
-		// Add children as .child() calls (except for text which handle children differently)
-		if element.tag_name != "text" {
-			for child in &element.children {
-				match child {
-					Node::Text(text) if text.trim().is_empty() => {
-						// Skip whitespace-only text nodes
-						continue;
-					}
-					_ => {
-						let child_code = self.generate_with_box(child, false);
-						code = format!("{}.child({})", code, child_code);
+	/// Generate Rust code for a `<text>` with `<span>` children, which is a `RichText` made of one
+	/// span per `<span>` and per run of text between them:
+	///
+	/// ```rust,ignore
+	/// // <text>Hello <span bold color={accent}>world</span></text>
+	/// hyprui::RichText::new()
+	///     .span(hyprui::Span::new("Hello "))
+	///     .span(hyprui::Span::new("world").bold().color(accent))
+	/// ```
+	fn generate_rich_text(&self, element: &Element) -> syn::Result<TokenStream2> {
+		let mut code = quote! { hyprui::RichText::new() };
+		let mut run = Vec::new();
+		let mut children = element.children.iter().peekable();
+		while let Some(child) = children.next() {
+			match child {
+				Node::Element(child) if child.tag_name != "span" => {
+					return Err(self.error(
+						child.position,
+						"<text> can only contain text, expressions and <span>",
+					));
+				}
+				Node::Element(span) => {
+					let content = self.text_content("span", &span.children)?;
+					let span_code =
+						self.apply_attributes(quote! { hyprui::Span::new(#content) }, &span.attributes)?;
+					code = quote! { #code.span(#span_code) };
+				}
+				_ => {
+					run.push(child.clone());
+					if matches!(children.peek(), None | Some(Node::Element(_))) {
+						let content = self.text_content("text", &std::mem::take(&mut run))?;
+						code = quote! { #code.span(hyprui::Span::new(#content)) };
 					}
 				}
 			}
 		}
+		Ok(code)
+	}
 
-		code
+	/// Components start with uppercase letters.
+	fn is_component(&self, element: &Element) -> bool {
+		element.tag_name.chars().next().unwrap().is_uppercase()
+	}
+
+	/// Generate Rust code for a `<for>` loop.
+	///
+	/// The children are repeated for every item and collected into a `Vec<Box<dyn Element>>`, which
+	/// is itself an element:
+	///
+	/// ```rust,ignore
+	/// // <for item={user} in={users} key={user.id}><UserRow user={user} /></for>
+	/// (users).into_iter().flat_map(|user| {
+	///     let key = ToString::to_string(&(user.id));
+	///     let children: Vec<Box<dyn hyprui::Element>> = vec![Box::new(
+	///         hyprui::Component::new_with_key(UserRow, hyprui::props_for(&UserRow, |props| {
+	///             props.user = user.into();
+	///         }), key.clone())
+	///     )];
+	///     children
+	/// }).collect::<Vec<Box<dyn hyprui::Element>>>()
+	/// ```
+	///
+	/// The key is given to the components directly inside the loop, so each one keeps the hook
+	/// state of its item when items are added, removed or reordered. When there are several, the
+	/// position inside the loop is appended to tell them apart.
+	fn generate_for(&self, element: &Element) -> syn::Result<TokenStream2> {
+		let expression = |name: &str| {
+			element
+				.attributes
+				.iter()
+				.find_map(|attr| match &attr.value {
+					Some(AttributeValue::Expression(e)) if attr.name == name => Some(e),
+					_ => None,
+				})
+		};
+		// Checked by the parser
+		let item = self.parse_code(
+			expression("item").expect("<for> without item"),
+			Pat::parse_single,
+		)?;
+		let items = self.expression(expression("in").expect("<for> without in"))?;
+		let key = expression("key")
+			.map(|key| self.expression(key))
+			.transpose()?;
+		// Hygienic, so they can't clash with the names used in the loop
+		let key_ident = Ident::new("key", Span::mixed_site());
+		let children_ident = Ident::new("children", Span::mixed_site());
+
+		let children = element
+			.children
+			.iter()
+			.filter(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()))
+			.collect::<Vec<&Node>>();
+		let children_code = children
+			.iter()
+			.enumerate()
+			.map(|(index, child)| match child {
+				Node::Element(child) if self.is_component(child) => {
+					let child_key = match key {
+						None => None,
+						Some(_) if children.len() == 1 => Some(quote! { #key_ident.clone() }),
+						Some(_) => Some(quote! { ::std::format!("{}-{}", #key_ident, #index) }),
+					};
+					let component = self.generate_component(child, child_key)?;
+					Ok(quote! { Box::new(#component) })
+				}
+				Node::Text(text) => Ok(quote! { Box::new(hyprui::Text::new(#text)) }),
+				_ => self.generate_with_box(child, true),
+			})
+			.collect::<syn::Result<Vec<TokenStream2>>>()?;
+		let key_binding = key.map(|key| {
+			quote! { let #key_ident = ::std::string::ToString::to_string(&(#key)); }
+		});
+
+		Ok(quote! {
+			(#items).into_iter().flat_map(|#item| {
+				#key_binding
+				let #children_ident: ::std::vec::Vec<::std::boxed::Box<dyn hyprui::Element>> =
+					::std::vec![#(#children_code),*];
+				#children_ident
+			}).collect::<::std::vec::Vec<::std::boxed::Box<dyn hyprui::Element>>>()
+		})
 	}
 
 	/// Generate Rust code for a component (uppercase tag).
 	///
 	/// Components are generated as Component::new(ComponentName, props)
-	/// where props is built using the Default::default() pattern:
+	/// where props start from their `Default` impl:
 	///
 	/// ```rust,ignore
-	/// hyprui::Component::new(MyComponent, {
-	///     let mut props = Default::default();
-	///     props.name = "value";
-	///     props.active = true;
-	///     props.children = vec![/* child elements */];
-	///     props
-	/// })
+	/// {
+	///     let props = hyprui::props_for(&MyComponent, |props| {
+	///         props.name = "value".into();
+	///         props.active = true.into();
+	///         props.children = vec![/* child elements */];
+	///     });
+	///     struct __SetProps;
+	///     impl hyprui::PropNames for __SetProps {
+	///         const NAMES: &'static [&'static str] = &["name", "active", "children"];
+	///     }
+	///     (&hyprui::PropsCheck::<_, __SetProps>::new(&props)).check();
+	///     hyprui::Component::new(MyComponent, props)
+	/// }
 	/// ```
 	///
-	/// This allows Rust to infer the correct props type from the component function signature.
-	fn generate_component(&self, element: &Element) -> String {
+	/// `props_for` takes the props type from the component function signature, so the
+	/// field assignments are type checked against it. When the props derive `Props`, the check
+	/// fails to build if a required prop isn't set, see `hyprui::PropsCheck`.
+	///
+	/// With a `key` expression, `Component::new_with_key` is used instead.
+	fn generate_component(
+		&self,
+		element: &Element,
+		key: Option<TokenStream2>,
+	) -> syn::Result<TokenStream2> {
+		let component = self.ident(&element.tag_name, element.position)?;
 		let mut props_assignments = Vec::new();
+		let mut prop_names = Vec::new();
 
 		// Convert attributes to props assignments
 		for attr in &element.attributes {
-			let prop_assignment = match &attr.value {
+			let prop = self.ident(&attr.name, attr.position)?;
+			let value = match &attr.value {
+				// String prop: props.name = "value";
 				Some(AttributeValue::String(s)) => {
-					// String prop: props.name = "value";
-					format!("        props.{} = \"{}\".into();", attr.name, s)
-				}
-				Some(AttributeValue::Expression(e)) => {
-					// Expression prop: props.name = expression;
-					format!("        props.{} = {}.into();", attr.name, e)
-				}
-				None => {
-					// Boolean prop: props.name = true;
-					format!("        props.{} = true.into();", attr.name)
+					self.string_literal(s, attr.position)?.into_token_stream()
 				}
+				// Expression prop: props.name = expression;
+				Some(AttributeValue::Expression(e)) => self.expression(e)?.into_token_stream(),
+				// Boolean prop: props.name = true;
+				None => quote! { true },
 			};
-			props_assignments.push(prop_assignment);
+			props_assignments.push(quote! { props.#prop = (#value).into(); });
+			prop_names.push(attr.name.clone());
 		}
 
 		// Convert children to props.children vector
-		if !element.children.is_empty() {
-			let mut children_code = Vec::new();
-			for child in &element.children {
-				match child {
-					Node::Text(text) if text.trim().is_empty() => {
-						// Skip whitespace-only text nodes
-						continue;
-					}
-					_ => {
-						children_code.push(self.generate_with_box(child, true));
-					}
+		let mut children_code = Vec::new();
+		for child in &element.children {
+			match child {
+				Node::Text(text) if text.trim().is_empty() => {
+					// Skip whitespace-only text nodes
+					continue;
+				}
+				_ => {
+					children_code.push(self.generate_with_box(child, true)?);
 				}
-			}
-
-			if !children_code.is_empty() {
-				let children_vec = children_code.join(", ");
-				props_assignments.push(format!("        props.children = vec![{}];", children_vec));
 			}
 		}
+		if !children_code.is_empty() {
+			props_assignments.push(quote! { props.children = vec![#(#children_code),*]; });
+			prop_names.push("children".to_string());
+		}
 
-		if props_assignments.is_empty() {
-			// No props, use Default::default() directly
-			format!(
-				"hyprui::Component::new({}, Default::default())",
-				element.tag_name
-			)
+		// Hygienic, so the code in the attributes can't use it
+		let props = Ident::new("props", Span::mixed_site());
+		let component_code = match key {
+			Some(key) => quote! { hyprui::Component::new_with_key(#component, #props, #key) },
+			None => quote! { hyprui::Component::new(#component, #props) },
+		};
+		// The error of a missing prop points at the tag
+		let check = quote_spanned! {self.span_at(element.position)=>
+			(&hyprui::PropsCheck::<_, __SetProps>::new(&#props)).check();
+		};
+		let set_props = if props_assignments.is_empty() {
+			quote! { |_| {} }
 		} else {
-			// Build props using Default::default() pattern
-			let props_block = format!(
-				"{{\n        let mut props = Default::default();\n{}\n        props\n    }}",
-				props_assignments.join("\n")
-			);
-			format!(
-				"hyprui::Component::new({}, {})",
-				element.tag_name, props_block
-			)
-		}
+			quote! { |props| { #(#props_assignments)* } }
+		};
+		Ok(quote! {{
+			// Build props from the default props of the component
+			let #props = hyprui::props_for(&#component, #set_props);
+			#[allow(unused_imports)]
+			use hyprui::{CheckRequiredProps as _, SkipRequiredProps as _};
+			struct __SetProps;
+			impl hyprui::PropNames for __SetProps {
+				const NAMES: &'static [&'static str] = &[#(#prop_names),*];
+			}
+			#check
+			#component_code
+		}})
 	}
 
 	/// Check if a method name represents a boolean flag method.
@@ -776,9 +545,17 @@ impl CodeGenerator {
 	fn is_boolean_method(&self, method_name: &str) -> bool {
 		matches!(
 			method_name,
-			"h_expand" | "w_expand" | "w_fit" | "center" | "text_center" | "text_right" | "text_left" | "focusable" | "focus_container"
+			"h_expand" | "w_expand" | "w_fit" | "scroll_x" | "scroll_y" | "contain" | "cover" | "center" | "text_center" | "text_right" | "text_left" | "focusable" | "focus_container" | "focus_scope" | "hover_through" | "ellipsis" | "bold"
 		)
 	}
+
+	/// Check if a method name represents a binding that takes a `bool` argument.
+	///
+	/// Bindings like `focused={is_focused}` are passed through as regular method calls,
+	/// but when written without a value (`focused`) they are generated as `.focused(true)`.
+	fn is_binding_method(&self, method_name: &str) -> bool {
+		matches!(method_name, "focused" | "italic")
+	}
 }
 
 // ============================================================================
@@ -810,43 +587,130 @@ impl CodeGenerator {
 ///     .child(Box::new(hyprui::Text::new("Hello, World!").font_size(18)))
 ///     .child(Box::new(hyprui::Text::new("Click me!"))))
 /// ```
+///
+/// Collections are rendered with `<for>`, which repeats its children for every item. The `key`
+/// is passed to the components inside the loop, so their state follows their item:
+///
+/// ```rust,ignore
+/// rsml! {
+///     <container>
+///         <for item={user} in={users} key={user.id}>
+///             <UserRow name={user.name} />
+///         </for>
+///     </container>
+/// }
+/// ```
+///
+/// Text between tags is kept as written, with its punctuation, spaces and newlines. Only the
+/// indentation around the children is dropped. The text must still be made of Rust tokens, so
+/// text with an unclosed quote or bracket goes in an expression: `<text>{"Don't"} panic</text>`.
 #[proc_macro]
 pub fn rsml(input: TokenStream) -> TokenStream {
-	// Convert TokenStream to string
-	let input_str = input.to_string();
+	// Convert TokenStream to string, remembering where each token came from
+	let source = source::Source::new(input.into());
 
 	// Parse using our RSML compiler pipeline
-	let mut parser = Parser::new(&input_str);
-	let rust_code = match parser.parse() {
-		Ok(dom) => {
-			let generator = CodeGenerator::new();
-			generator.generate(&dom)
-		}
+	let mut parser = Parser::new(&source.text);
+	let dom = match parser.parse() {
+		Ok(dom) => dom,
 		Err(e) => {
 			return syn::Error::new(
-				proc_macro2::Span::call_site(),
-				format!("RSML parse error: {}", e),
+				source.span_at(e.position),
+				format!("RSML parse error: {}", e.message),
 			)
 			.to_compile_error()
 			.into();
 		}
 	};
 
-	// Parse the generated Rust code back into tokens
-	match rust_code.parse::<proc_macro2::TokenStream>() {
-		Ok(tokens) => tokens.into(),
-		Err(e) => {
-			return syn::Error::new(
-				proc_macro2::Span::call_site(),
-				format!(
-					"Generated invalid Rust code: {}. Generated code was: {}",
-					e, rust_code
-				),
-			)
-			.to_compile_error()
-			.into();
-		}
-	}
+	CodeGenerator::with_source(&source)
+		.generate(&dom)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Like [`rsml!`], with the RSML read from a file at compile time. The path is relative to the
+/// `Cargo.toml` of the crate.
+///
+/// The crate is rebuilt when the file changes. To see changes without rebuilding, the same file
+/// can be interpreted at runtime with `hyprui::rsml_interpreter::use_rsml_file` (feature
+/// `rsml-interpreter`) while prototyping a layout.
+///
+/// ```rust,ignore
+/// fn settings_page(_: ()) -> Box<dyn Element> {
+///     let (dark, set_dark) = use_state(false);
+///     rsml_file!("src/settings_page.rsml")
+/// }
+/// ```
+///
+/// Errors in the file are reported on the macro call, with their line and column in the file.
+#[proc_macro]
+pub fn rsml_file(input: TokenStream) -> TokenStream {
+	let path = syn::parse_macro_input!(input as LitStr);
+	expand_rsml_file(&path)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Generates the code of the RSML file at `path`, relative to the crate being compiled.
+fn expand_rsml_file(path: &LitStr) -> syn::Result<TokenStream2> {
+	let root = std::env::var("CARGO_MANIFEST_DIR")
+		.map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+	let full_path = std::path::Path::new(&root).join(path.value());
+	let text = std::fs::read_to_string(&full_path).map_err(|e| {
+		syn::Error::new(
+			path.span(),
+			format!("couldn't read {}: {e}", full_path.display()),
+		)
+	})?;
+	let dom = Parser::new(&text).parse().map_err(|e| {
+		let (line, column) = e.line_column(&text);
+		syn::Error::new(
+			path.span(),
+			format!(
+				"RSML parse error: {} ({}:{line}:{column})",
+				e.message,
+				path.value()
+			),
+		)
+	})?;
+	let code = CodeGenerator::with_file(&path.value(), &text).generate(&dom)?;
+	let full_path = full_path.to_string_lossy();
+	Ok(quote! {{
+		// Rebuilds the crate when the file changes
+		const _: &str = include_str!(#full_path);
+		#code
+	}})
+}
+
+/// Derives `Default`, a builder and `hyprui::Props` for the props of a component.
+///
+/// Fields default to their own `Default` impl, or to the expression in `#[prop(default = ...)]`,
+/// so props can hold types like `Rc<dyn Fn()>` that have no `Default` impl.
+/// A `children: Vec<Box<dyn Element>>` field also gets a `child()` method on the builder.
+///
+/// Fields marked `#[prop(required)]` must be set when the component is used in RSML, otherwise
+/// `cargo build` fails with "missing the required prop `label` of `ButtonProps`" on the tag. They
+/// still need a default, which the builder starts from.
+///
+/// ```rust,ignore
+/// #[derive(Props)]
+/// struct ButtonProps {
+///     #[prop(required)]
+///     label: String,
+///     #[prop(default = Rc::new(|| {}))]
+///     on_click: Rc<dyn Fn()>,
+///     children: Vec<Box<dyn Element>>,
+/// }
+///
+/// let props = ButtonProps::builder().label("Save").child(Text::new("💾")).build();
+/// ```
+#[proc_macro_derive(Props, attributes(prop))]
+pub fn derive_props(input: TokenStream) -> TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+	props::derive_props(input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
 }
 
 // ============================================================================
@@ -924,9 +788,9 @@ mod tests {
 					match parser.parse() {
 						Ok(dom) => {
 							let generator = CodeGenerator::new();
-							Ok(generator.generate(&dom))
+							generator.generate(&dom).map_err(|e| e.to_string())
 						}
-						Err(e) => Err(e),
+						Err(e) => Err(e.to_string()),
 					}
 				});
 
@@ -959,6 +823,127 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_for_loop_keys_components() {
+		let generate = |rsml: &str| {
+			Parser::new(rsml)
+				.parse()
+				.map(|dom| CodeGenerator::new().generate(&dom).unwrap().to_string())
+		};
+		let rust_code = generate("<for item={x} in={items} key={x.id}><Row /><Row /></for>").unwrap();
+		assert!(rust_code.contains("flat_map (| x |"));
+		assert!(rust_code.contains("let key = :: std :: string :: ToString :: to_string (& (x . id))"));
+		assert!(rust_code.contains(
+			"hyprui :: Component :: new_with_key (Row , props , :: std :: format ! (\"{}-{}\" , key , 1usize))"
+		));
+		assert!(generate("<for item={x}><Row /></for>").is_err());
+	}
+
+	#[test]
+	fn test_generated_code_escapes_text_and_checks_expressions() {
+		let generate = |rsml: &str| CodeGenerator::new().generate(&Parser::new(rsml).parse().unwrap());
+		let rust_code = generate(r#"<container label="say \"hi\"" gap={count + }>Hi</container>"#);
+		assert!(rust_code.is_err());
+		let rust_code = generate(r#"<text>{count} items</text>"#)
+			.unwrap()
+			.to_string();
+		assert!(rust_code.contains(r#"format ! ("{} items" , count)"#));
+		let rust_code = generate(r#"<container label="say \"hi\"" />"#)
+			.unwrap()
+			.to_string();
+		assert!(rust_code.contains(r#". label ("say \"hi\"")"#));
+	}
+
+	#[test]
+	fn test_text_keeps_punctuation_and_whitespace() {
+		let generate = |rsml: &str| {
+			let source = source::Source::new(rsml.parse().unwrap());
+			let dom = Parser::new(&source.text).parse().unwrap();
+			CodeGenerator::new().generate(&dom).unwrap().to_string()
+		};
+		let rust_code = generate("<text>Hello,  World!</text>");
+		assert!(rust_code.contains(r#"Text :: new ("Hello,  World!")"#));
+		let rust_code = generate("<text>\n\tFirst: {count}\n\tSecond line\n</text>");
+		assert!(rust_code.contains(r#"format ! ("First: {}\n Second line" , count)"#));
+		let rust_code = generate("<container>\n\t<text>a</text>\n\t<text>b</text>\n</container>");
+		assert_eq!(rust_code.matches(". child").count(), 2);
+	}
+
+	#[test]
+	fn test_text_with_spans_is_rich_text() {
+		// Nested spans are rejected by the code generator, after parsing
+		let generate = |rsml: &str| {
+			Parser::new(rsml)
+				.parse()
+				.map_err(|e| e.to_string())
+				.and_then(|dom| {
+					CodeGenerator::new()
+						.generate(&dom)
+						.map(|tokens| tokens.to_string())
+						.map_err(|e| e.to_string())
+				})
+		};
+		let rust_code =
+			generate("<text font_size={18}>Hello <span bold color={accent}>{name}</span>!</text>")
+				.unwrap();
+		assert!(rust_code.contains(
+			r#"hyprui :: RichText :: new () . span (hyprui :: Span :: new ("Hello ")) . span (hyprui :: Span :: new (format ! ("{}" , name)) . bold () . color (accent)) . span (hyprui :: Span :: new ("!")) . font_size (18)"#
+		));
+		assert!(generate("<text><span>a <span>b</span></span></text>").is_err());
+		// Spans take the rich text path, before the check that a plain <text> only holds text
+		let rust_code = generate(&fs::read_to_string("rsml_tests/14_rich_text.rsml").unwrap()).unwrap();
+		assert_eq!(rust_code.matches("hyprui :: RichText :: new ()").count(), 2);
+	}
+
+	#[test]
+	fn test_children_of_text_are_checked_by_the_code_generator() {
+		// The parser keeps any element in a <text>, the interpreter checks them too
+		let error = |rsml: &str| {
+			let dom = Parser::new(rsml).parse().unwrap();
+			CodeGenerator::with_file("test.rsml", rsml)
+				.generate(&dom)
+				.unwrap_err()
+				.to_string()
+		};
+		assert_eq!(
+			error("<text>Hi <container /></text>"),
+			"<text> can only contain text, expressions and <span> (test.rsml:1:11)"
+		);
+		assert_eq!(
+			error("<text><span>a <image /></span></text>"),
+			"<span> can only contain text and expressions (test.rsml:1:16)"
+		);
+	}
+
+	#[test]
+	fn test_components_check_the_props_they_set() {
+		let generate = |rsml: &str| {
+			let dom = Parser::new(rsml).parse().unwrap();
+			CodeGenerator::new().generate(&dom).unwrap().to_string()
+		};
+		let rust_code = generate(r#"<Button label="Save" primary><text>Hi</text></Button>"#);
+		assert!(rust_code.contains(
+			r#"const NAMES : & 'static [& 'static str] = & ["label" , "primary" , "children"]"#
+		));
+		assert!(
+			rust_code
+				.contains("(& hyprui :: PropsCheck :: < _ , __SetProps > :: new (& props)) . check ()")
+		);
+		let rust_code = generate("<Button />");
+		assert!(rust_code.contains("hyprui :: props_for (& Button , | _ | { })"));
+	}
+
+	#[test]
+	fn test_rsml_file_is_compiled_and_tracked() {
+		let expand = |path: &str| expand_rsml_file(&LitStr::new(path, Span::call_site()));
+		let rust_code = expand("rsml_tests/02_simple_container.rsml")
+			.unwrap()
+			.to_string();
+		assert!(rust_code.contains("include_str !"));
+		assert!(rust_code.contains("hyprui :: Container :: new ()"));
+		assert!(expand("rsml_tests/missing.rsml").is_err());
+	}
+
 	#[test]
 	fn test_debug_expression_handling() {
 		// Test expression handling specifically
@@ -968,7 +953,9 @@ mod tests {
 		match parser.parse() {
 			Ok(dom) => {
 				let generator = CodeGenerator::new();
-				let rust_code = generator.generate(&dom);
+				let rust_code = generator
+					.generate(&dom)
+					.map_or_else(|e| e.to_string(), |code| code.to_string());
 				println!("Expression test - Generated code: {}", rust_code);
 			}
 			Err(e) => {
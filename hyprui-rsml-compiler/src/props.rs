@@ -0,0 +1,233 @@
+//! # `#[derive(Props)]`
+//!
+//! Generates a `Default` impl, a builder and an impl of `hyprui::Props` for the props struct of a
+//! component.
+//!
+//! ```rust,ignore
+//! #[derive(Props)]
+//! struct ButtonProps {
+//!     #[prop(required)]
+//!     label: String,
+//!     #[prop(default = 8.)]
+//!     padding: f32,
+//!     #[prop(default = Rc::new(|| {}))]
+//!     on_click: Rc<dyn Fn()>,
+//!     children: Vec<Box<dyn Element>>,
+//! }
+//! ```
+//!
+//! Expands to:
+//!
+//! ```rust,ignore
+//! impl Default for ButtonProps {
+//!     fn default() -> Self {
+//!         Self {
+//!             label: Default::default(),
+//!             padding: 8.,
+//!             on_click: Rc::new(|| {}),
+//!             children: Default::default(),
+//!         }
+//!     }
+//! }
+//!
+//! impl hyprui::Props for ButtonProps {
+//!     const REQUIRED: &'static [(&'static str, &'static str)] =
+//!         &[("label", "missing the required prop `label` of `ButtonProps`")];
+//! }
+//!
+//! impl ButtonProps {
+//!     pub fn builder() -> ButtonPropsBuilder { ... }
+//! }
+//!
+//! // With one setter per field, `child()` to add a single child and `build()`.
+//! pub struct ButtonPropsBuilder { ... }
+//! ```
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Type};
+
+/// A named field of the props struct.
+struct PropField<'a> {
+	ident: &'a Ident,
+	ty: &'a Type,
+	/// The expression from `#[prop(default = ...)]`.
+	default: Option<Expr>,
+	/// `#[prop(required)]`: RSML using the component must set it.
+	required: bool,
+}
+
+impl PropField<'_> {
+	/// Whether this is the `children` field filled with the children of the RSML tag.
+	fn is_children(&self) -> bool {
+		self.ident == "children"
+	}
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<PropField<'_>>> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(
+			&input.ident,
+			"Props can only be derived for structs",
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(
+			&input.ident,
+			"Props can only be derived for structs with named fields",
+		));
+	};
+	fields
+		.named
+		.iter()
+		.map(|field| {
+			let mut default = None;
+			let mut required = false;
+			for attr in field
+				.attrs
+				.iter()
+				.filter(|attr| attr.path().is_ident("prop"))
+			{
+				attr.parse_nested_meta(|meta| {
+					if meta.path.is_ident("default") {
+						default = Some(meta.value()?.parse()?);
+						Ok(())
+					} else if meta.path.is_ident("required") {
+						required = true;
+						Ok(())
+					} else {
+						Err(meta.error("unknown prop attribute, expected `default = ...` or `required`"))
+					}
+				})?;
+			}
+			Ok(PropField {
+				ident: field.ident.as_ref().unwrap(),
+				ty: &field.ty,
+				default,
+				required,
+			})
+		})
+		.collect()
+}
+
+pub(crate) fn derive_props(input: DeriveInput) -> syn::Result<TokenStream> {
+	let fields = parse_fields(&input)?;
+	let name = &input.ident;
+	let vis = &input.vis;
+	let builder = format_ident!("{name}Builder");
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let generics = &input.generics;
+
+	let defaults = fields.iter().map(|field| {
+		let ident = field.ident;
+		match &field.default {
+			Some(default) => quote! { #ident: #default },
+			None => quote! { #ident: ::core::default::Default::default() },
+		}
+	});
+	let setters = fields.iter().map(|field| {
+		let ident = field.ident;
+		let ty = field.ty;
+		let doc = format!("Sets `{ident}`.");
+		quote! {
+			#[doc = #doc]
+			pub fn #ident(mut self, value: impl ::core::convert::Into<#ty>) -> Self {
+				self.props.#ident = value.into();
+				self
+			}
+		}
+	});
+	let child = fields.iter().find(|field| field.is_children()).map(|_| {
+		quote! {
+			/// Adds a child to `children`.
+			pub fn child(mut self, child: impl ::hyprui::Element + 'static) -> Self {
+				self.props.children.push(::std::boxed::Box::new(child));
+				self
+			}
+		}
+	});
+	let required = fields.iter().filter(|field| field.required).map(|field| {
+		let prop = field.ident.to_string();
+		let message = format!("missing the required prop `{prop}` of `{name}`");
+		quote! { (#prop, #message) }
+	});
+	let builder_doc = format!("Builder for [`{name}`], created with [`{name}::builder`].");
+
+	Ok(quote! {
+		impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+			fn default() -> Self {
+				Self {
+					#(#defaults,)*
+				}
+			}
+		}
+
+		impl #impl_generics ::hyprui::Props for #name #ty_generics #where_clause {
+			const REQUIRED: &'static [(&'static str, &'static str)] = &[#(#required),*];
+		}
+
+		impl #impl_generics #name #ty_generics #where_clause {
+			/// Creates a builder starting from the default props.
+			#vis fn builder() -> #builder #ty_generics {
+				#builder {
+					props: ::core::default::Default::default(),
+				}
+			}
+		}
+
+		#[doc = #builder_doc]
+		#vis struct #builder #generics #where_clause {
+			props: #name #ty_generics,
+		}
+
+		impl #impl_generics #builder #ty_generics #where_clause {
+			#(#setters)*
+			#child
+
+			pub fn build(self) -> #name #ty_generics {
+				self.props
+			}
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_derive_props() {
+		let input = syn::parse_quote! {
+			pub struct ButtonProps {
+				#[prop(required)]
+				label: String,
+				#[prop(default = 8.)]
+				padding: f32,
+				children: Vec<Box<dyn Element>>,
+			}
+		};
+		let output = derive_props(input).unwrap().to_string();
+		assert!(output.contains("padding : 8."));
+		assert!(output.contains("label : :: core :: default :: Default :: default ()"));
+		assert!(output.contains("pub struct ButtonPropsBuilder"));
+		assert!(output.contains("pub fn child"));
+		assert!(output.contains(
+			r#"const REQUIRED : & 'static [(& 'static str , & 'static str)] = & [("label" , "missing the required prop `label` of `ButtonProps`")]"#
+		));
+		syn::parse_file(&output).unwrap();
+	}
+
+	#[test]
+	fn test_derive_props_errors() {
+		let input = syn::parse_quote! {
+			struct Props(u32);
+		};
+		assert!(derive_props(input).is_err());
+		let input = syn::parse_quote! {
+			struct Props {
+				#[prop(optional)]
+				name: String,
+			}
+		};
+		assert!(derive_props(input).is_err());
+	}
+}
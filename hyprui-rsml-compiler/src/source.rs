@@ -0,0 +1,166 @@
+//! # Source map
+//!
+//! The tokenizer works on text, while errors must point at the tokens of the macro input.
+//! [`Source`] prints the input tokens with the whitespace they had in the file, so text between
+//! tags keeps its spaces and newlines, and remembers the span of the token each character comes
+//! from, so an offset in the text maps back to a span.
+//!
+//! The Rust code written in braces is parsed again from the text, then [`Source::respan`] gives
+//! its tokens back their spans, so the compiler reports errors in it where it was written.
+use proc_macro2::{Delimiter, Group, LineColumn, Span, TokenStream, TokenTree};
+
+/// The RSML input as text, with the spans of its tokens.
+pub(crate) struct Source {
+	pub(crate) text: String,
+	/// Character offset where each token starts in `text`, with its span.
+	spans: Vec<(usize, Span)>,
+	/// Length of `text` in characters, which are the offsets the tokenizer works with.
+	len: usize,
+	/// Where the last token ends in the file, and whether it is a punct joined to the next one.
+	last: Option<(LineColumn, bool)>,
+}
+
+impl Source {
+	pub(crate) fn new(tokens: TokenStream) -> Self {
+		let mut source = Self {
+			text: String::new(),
+			spans: Vec::new(),
+			len: 0,
+			last: None,
+		};
+		source.push_tokens(tokens);
+		source
+	}
+
+	fn push_tokens(&mut self, tokens: TokenStream) {
+		for token in tokens {
+			match token {
+				TokenTree::Group(group) => {
+					let (open, close) = match group.delimiter() {
+						Delimiter::Brace => ("{", "}"),
+						Delimiter::Parenthesis => ("(", ")"),
+						Delimiter::Bracket => ("[", "]"),
+						Delimiter::None => ("", ""),
+					};
+					self.push(open, group.span_open(), false);
+					self.push_tokens(group.stream());
+					self.push(close, group.span_close(), false);
+				}
+				TokenTree::Punct(punct) => self.push(
+					&punct.as_char().to_string(),
+					punct.span(),
+					// `/>` and `</` are two puncts that must stay together
+					punct.spacing() == proc_macro2::Spacing::Joint,
+				),
+				TokenTree::Ident(ident) => self.push(&ident.to_string(), ident.span(), false),
+				TokenTree::Literal(literal) => self.push(&literal.to_string(), literal.span(), false),
+			}
+		}
+	}
+
+	fn push(&mut self, text: &str, span: Span, joint: bool) {
+		if text.is_empty() {
+			return;
+		}
+		if let Some((end, joint)) = self.last {
+			let whitespace = whitespace_between(end, span.start(), joint);
+			self.len += whitespace.chars().count();
+			self.text.push_str(&whitespace);
+		}
+		self.spans.push((self.len, span));
+		self.text.push_str(text);
+		self.len += text.chars().count();
+		self.last = Some((span.end(), joint));
+	}
+
+	/// Returns the span of the token at the character offset `position`.
+	pub(crate) fn span_at(&self, position: usize) -> Span {
+		let index = self.spans.partition_point(|(start, _)| *start <= position);
+		match index.checked_sub(1) {
+			Some(index) => self.spans[index].1,
+			// The input is empty
+			None => Span::call_site(),
+		}
+	}
+
+	/// Gives `tokens`, parsed from the code in the braces opening at `position`, the spans of the
+	/// input tokens they were printed from.
+	pub(crate) fn respan(&self, tokens: TokenStream, position: usize) -> TokenStream {
+		// The code starts with the token after the opening brace
+		let first = self.spans.partition_point(|(start, _)| *start <= position);
+		let mut spans = self.spans[first..].iter().map(|(_, span)| *span);
+		respan_tokens(tokens, &mut spans)
+	}
+}
+
+/// Returns the whitespace between a token ending at `end` and the next one starting at `start`.
+///
+/// Tabs count as one column, so indentation comes back as spaces. Without locations, which some
+/// compilers don't give to macros, or when the tokens come from elsewhere, like the arguments of
+/// a `macro_rules!`, tokens are separated by a space unless they are joint puncts like `</`.
+fn whitespace_between(end: LineColumn, start: LineColumn, joint: bool) -> String {
+	let located = start.line != 0 && end.line != 0;
+	if !located || start < end {
+		let space = if joint { "" } else { " " };
+		return space.to_string();
+	}
+	if start.line == end.line {
+		" ".repeat(start.column - end.column)
+	} else {
+		"\n".repeat(start.line - end.line) + &" ".repeat(start.column)
+	}
+}
+
+/// Sets the spans of `tokens` in order, delimiters of groups included.
+fn respan_tokens(tokens: TokenStream, spans: &mut impl Iterator<Item = Span>) -> TokenStream {
+	tokens
+		.into_iter()
+		.map(|token| {
+			let Some(span) = spans.next() else {
+				return token;
+			};
+			match token {
+				TokenTree::Group(group) => {
+					let stream = respan_tokens(group.stream(), spans);
+					// Closing delimiter
+					spans.next();
+					let mut group = Group::new(group.delimiter(), stream);
+					group.set_span(span);
+					TokenTree::Group(group)
+				}
+				mut token => {
+					token.set_span(span);
+					token
+				}
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_source_keeps_the_whitespace_of_the_file() {
+		let rsml = "<text size={ 12 }>Hello,  World!\n\tBye</text>";
+		let source = Source::new(rsml.parse().unwrap());
+		assert_eq!(source.text, "<text size={ 12 }>Hello,  World!\n Bye</text>");
+		assert_eq!(source.spans.len(), 17);
+		assert_eq!(source.spans[13].0, rsml.find("</").unwrap());
+	}
+
+	#[test]
+	fn test_respan_walks_groups() {
+		let tokens: TokenStream = "<text size={ f(1) }>Hi</text>".parse().unwrap();
+		let source = Source::new(tokens);
+		let position = source.text.find('{').unwrap();
+		let code: TokenStream = "f(1)".parse().unwrap();
+		let respanned = source
+			.respan(code, position)
+			.into_iter()
+			.collect::<Vec<_>>();
+		assert_eq!(respanned.len(), 2);
+		assert!(matches!(&respanned[1], TokenTree::Group(group) if group.stream().to_string() == "1"));
+	}
+}
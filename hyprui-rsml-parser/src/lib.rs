@@ -0,0 +1,686 @@
+//! # RSML parser
+//!
+//! Turns RSML text into a DOM tree, shared by the `rsml!` macro of `hyprui-rsml-compiler`, which generates Rust code
+//! from it, and by the RSML interpreter of `hyprui`, which builds elements from it at runtime.
+//!
+//! ```rust,ignore
+//! let dom = Parser::new("<container gap={8}><text>Hello</text></container>").parse()?;
+//! ```
+
+// ============================================================================
+// DOM DATA STRUCTURES
+// ============================================================================
+
+/// A node in the RSML DOM tree.
+///
+/// The DOM represents the parsed structure before code generation.
+/// This allows for easy inspection, transformation, and debugging.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+	/// An HTML-like element: `<tag attr="value">children</tag>`
+	Element(Element),
+	/// Plain text content between tags, as written: `Hello, World!`
+	Text(String),
+	/// Rust expression in braces: `{some_variable + 1}`
+	Expression(RustCode),
+}
+
+/// Rust code written in braces, parsed with `syn` during code generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustCode {
+	/// The code between the braces
+	pub code: String,
+	/// Character offset of the opening brace in the input, to report errors there
+	pub position: usize,
+}
+
+/// An RSML element with tag name, attributes, and children.
+///
+/// Examples:
+/// - `<container />` - self-closing with no attributes
+/// - `<text font_size={16}>Hello</text>` - with attributes and text content
+/// - `<MyComponent prop="value">...</MyComponent>` - component with children
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+	/// The tag name (e.g., "container", "text", "MyComponent")
+	pub tag_name: String,
+	/// All attributes on the element
+	pub attributes: Vec<Attribute>,
+	/// Child nodes (other elements, text, or expressions)
+	pub children: Vec<Node>,
+	/// Whether this is a self-closing tag like `<container />`
+	pub self_closing: bool,
+	/// Character offset of the tag name in the input
+	pub position: usize,
+}
+
+/// An attribute on an RSML element.
+///
+/// Examples:
+/// - `disabled` - boolean attribute (no value)
+/// - `name="John"` - string literal value
+/// - `size={42}` - expression value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+	/// The attribute name
+	pub name: String,
+	/// The attribute value (None for boolean attributes)
+	pub value: Option<AttributeValue>,
+	/// Character offset of the attribute name in the input
+	pub position: usize,
+}
+
+/// The value of an attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+	/// String literal: `name="value"`
+	String(String),
+	/// Rust expression: `size={variable + 1}`
+	Expression(RustCode),
+}
+
+/// An error in the RSML input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	pub message: String,
+	/// Character offset of the offending token in the input
+	pub position: usize,
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} (at offset {})", self.message, self.position)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+	/// Returns the line and column of the error in `input`, the text that was parsed, both starting at 1.
+	pub fn line_column(&self, input: &str) -> (usize, usize) {
+		line_column(input, self.position)
+	}
+}
+
+/// Returns the line and column of the character offset `position` in `input`, both starting at 1.
+pub fn line_column(input: &str, position: usize) -> (usize, usize) {
+	let before = input.chars().take(position).collect::<String>();
+	let line = before.matches('\n').count() + 1;
+	let column = before.chars().rev().take_while(|&ch| ch != '\n').count() + 1;
+	(line, column)
+}
+
+// ============================================================================
+// TOKENIZER
+// ============================================================================
+
+/// A token in the RSML token stream.
+///
+/// Tokens are the atomic units that the parser works with.
+/// They represent meaningful syntax elements like tags, attributes, etc.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	/// Opening tag bracket: `<`
+	OpenTag,
+	/// Closing tag bracket: `>`
+	CloseTag,
+	/// Self-closing tag: `/>`
+	SelfCloseTag,
+	/// End tag opening: `</`
+	EndOpenTag,
+	/// Identifier: tag names, attribute names, etc.
+	Identifier(String),
+	/// String literal in quotes: `"hello"` or `'hello'`
+	StringLiteral(String),
+	/// Rust expression in braces: `{code here}`
+	Expression(String),
+	/// Text in the body of an element, up to the next `<` or `{`: `Hello, World!`
+	Text(String),
+	/// Equals sign for attributes: `=`
+	Equals,
+	/// End of input
+	Eof,
+}
+
+/// Converts raw RSML text into a stream of tokens.
+///
+/// The tokenizer handles:
+/// - Proper brace matching for expressions `{...}`
+/// - String literal parsing with escape sequences
+/// - JSX-style tag syntax `<`, `>`, `</`, `/>`
+/// - Identifier recognition for tag and attribute names
+struct Tokenizer {
+	/// Input text as a vector of characters for easy indexing
+	input: Vec<char>,
+	/// Current position in the input
+	position: usize,
+	/// Current character being processed (None at EOF)
+	current_char: Option<char>,
+	/// Position where the last returned token starts, to report errors there
+	token_start: usize,
+}
+
+impl Tokenizer {
+	/// Create a new tokenizer for the given input text.
+	fn new(input: &str) -> Self {
+		let chars: Vec<char> = input.chars().collect();
+		let current_char = chars.first().copied();
+		Self {
+			input: chars,
+			position: 0,
+			current_char,
+			token_start: 0,
+		}
+	}
+
+	/// Advance to the next character in the input.
+	fn advance(&mut self) {
+		self.position += 1;
+		self.current_char = self.input.get(self.position).copied();
+	}
+
+	/// Look at the next character without advancing.
+	fn peek(&self) -> Option<char> {
+		self.input.get(self.position + 1).copied()
+	}
+
+	/// Skip over whitespace characters.
+	fn skip_whitespace(&mut self) {
+		while let Some(ch) = self.current_char {
+			if ch.is_whitespace() {
+				self.advance();
+			} else {
+				break;
+			}
+		}
+	}
+
+	/// Read an identifier (tag name, attribute name, etc.).
+	///
+	/// Identifiers can contain letters, numbers, underscores, and hyphens.
+	/// Examples: `container`, `font_size`, `MyComponent`, `data-id`
+	fn read_identifier(&mut self) -> String {
+		let mut result = String::new();
+
+		while let Some(ch) = self.current_char {
+			if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+				result.push(ch);
+				self.advance();
+			} else {
+				break;
+			}
+		}
+
+		result
+	}
+
+	/// Read a string literal, handling escape sequences.
+	///
+	/// Supports both double and single quotes: `"hello"` or `'hello'`
+	/// Handles escape sequences like `\"` and `\\`
+	fn read_string_literal(&mut self) -> String {
+		let quote_char = self.current_char.unwrap(); // " or '
+		self.advance(); // skip opening quote
+
+		let mut result = String::new();
+		let mut escaped = false;
+
+		while let Some(ch) = self.current_char {
+			if escaped {
+				result.push(ch);
+				escaped = false;
+			} else if ch == '\\' {
+				escaped = true;
+				result.push(ch);
+			} else if ch == quote_char {
+				self.advance(); // skip closing quote
+				break;
+			} else {
+				result.push(ch);
+			}
+			self.advance();
+		}
+
+		result
+	}
+
+	/// Read a Rust expression inside braces: `{expression here}`
+	///
+	/// This handles proper brace matching, so expressions like `{vec![1, 2, 3]}`
+	/// or `{if condition { "yes" } else { "no" }}` are parsed correctly.
+	///
+	/// Also handles string literals inside expressions to avoid false matches.
+	fn read_expression(&mut self) -> String {
+		self.advance(); // skip opening {
+
+		let mut result = String::new();
+		let mut brace_count = 1; // We're already inside one brace
+		let mut in_string = false;
+		let mut string_char = '"';
+		let mut escaped = false;
+
+		while let Some(ch) = self.current_char {
+			if escaped {
+				result.push(ch);
+				escaped = false;
+			} else if ch == '\\' && in_string {
+				result.push(ch);
+				escaped = true;
+			} else if (ch == '"' || ch == '\'') && !in_string {
+				// Entering a string
+				in_string = true;
+				string_char = ch;
+				result.push(ch);
+			} else if ch == string_char && in_string {
+				// Exiting a string
+				in_string = false;
+				result.push(ch);
+			} else if !in_string {
+				// Only count braces when not inside a string
+				if ch == '{' {
+					brace_count += 1;
+					result.push(ch);
+				} else if ch == '}' {
+					brace_count -= 1;
+					if brace_count == 0 {
+						self.advance(); // skip closing }
+						break;
+					}
+					result.push(ch);
+				} else {
+					result.push(ch);
+				}
+			} else {
+				result.push(ch);
+			}
+			self.advance();
+		}
+
+		result
+	}
+
+	/// Get the next token from the input stream.
+	///
+	/// This is the main tokenizer method that identifies and returns
+	/// the next meaningful token in the input.
+	fn next_token(&mut self) -> Token {
+		loop {
+			self.token_start = self.position;
+			match self.current_char {
+				None => return Token::Eof,
+
+				Some(ch) if ch.is_whitespace() => {
+					self.skip_whitespace();
+					continue; // Skip whitespace and continue
+				}
+
+				Some('<') => {
+					if self.peek() == Some('/') {
+						// Closing tag: </
+						self.advance(); // skip <
+						self.advance(); // skip /
+						return Token::EndOpenTag;
+					} else {
+						// Opening tag: <
+						self.advance();
+						return Token::OpenTag;
+					}
+				}
+
+				Some('/') if self.peek() == Some('>') => {
+					// Self-closing tag: />
+					self.advance(); // skip /
+					self.advance(); // skip >
+					return Token::SelfCloseTag;
+				}
+
+				Some('>') => {
+					// End of opening tag: >
+					self.advance();
+					return Token::CloseTag;
+				}
+
+				Some('=') => {
+					// Attribute assignment: =
+					self.advance();
+					return Token::Equals;
+				}
+
+				Some('"') | Some('\'') => {
+					// String literal
+					let string_val = self.read_string_literal();
+					return Token::StringLiteral(string_val);
+				}
+
+				Some('{') => {
+					// Rust expression
+					let expr = self.read_expression();
+					return Token::Expression(expr);
+				}
+
+				Some(ch) if ch.is_alphabetic() || ch == '_' => {
+					// Identifier (tag name, attribute name, etc.)
+					let ident = self.read_identifier();
+					return Token::Identifier(ident);
+				}
+
+				Some(_) => {
+					// Unknown character - skip it
+					self.advance();
+					continue;
+				}
+			}
+		}
+	}
+
+	/// Get the next token in the body of an element.
+	///
+	/// Text is read as it is written, with its punctuation, spaces and newlines, until the next
+	/// `<` or `{`, where tokens are read as usual.
+	fn next_body_token(&mut self) -> Token {
+		self.token_start = self.position;
+		let mut text = String::new();
+		while let Some(ch) = self.current_char {
+			if ch == '<' || ch == '{' {
+				break;
+			}
+			text.push(ch);
+			self.advance();
+		}
+		if text.is_empty() {
+			self.next_token()
+		} else {
+			Token::Text(text)
+		}
+	}
+}
+
+/// Removes the whitespace that only lays out the RSML from the children of an element.
+///
+/// Whitespace with a newline is dropped when it is all there is between two children, or at the
+/// start or end of the body, like the indentation around the children. Other text is kept as is.
+fn trim_layout_whitespace(children: &mut Vec<Node>) {
+	children.retain(
+		|child| !matches!(child, Node::Text(text) if text.trim().is_empty() && text.contains('\n')),
+	);
+	if let Some(Node::Text(text)) = children.first_mut() {
+		let trimmed = text.trim_start();
+		if text[..text.len() - trimmed.len()].contains('\n') {
+			*text = trimmed.to_string();
+		}
+	}
+	if let Some(Node::Text(text)) = children.last_mut() {
+		let trimmed = text.trim_end();
+		if text[trimmed.len()..].contains('\n') {
+			*text = trimmed.to_string();
+		}
+	}
+}
+
+// ============================================================================
+// PARSER
+// ============================================================================
+
+/// Converts a stream of tokens into a DOM tree.
+///
+/// The parser implements a recursive descent parser that recognizes
+/// the RSML grammar and builds a structured DOM representation.
+pub struct Parser {
+	/// The tokenizer that provides the token stream
+	tokenizer: Tokenizer,
+	/// The current token being processed
+	current_token: Token,
+	/// Position where the current token starts in the input
+	position: usize,
+}
+
+impl Parser {
+	/// Create a new parser for the given input text.
+	pub fn new(input: &str) -> Self {
+		let mut tokenizer = Tokenizer::new(input);
+		let current_token = tokenizer.next_token();
+		Self {
+			position: tokenizer.token_start,
+			tokenizer,
+			current_token,
+		}
+	}
+
+	/// Advance to the next token.
+	fn advance(&mut self) {
+		self.current_token = self.tokenizer.next_token();
+		self.position = self.tokenizer.token_start;
+	}
+
+	/// Advance to the next token in the body of an element, where text is read as it is written.
+	fn advance_in_body(&mut self) {
+		self.current_token = self.tokenizer.next_body_token();
+		self.position = self.tokenizer.token_start;
+	}
+
+	/// Create an error pointing at the current token.
+	fn error(&self, message: impl Into<String>) -> ParseError {
+		ParseError {
+			message: message.into(),
+			position: self.position,
+		}
+	}
+
+	/// Expect a specific token and advance, or return an error.
+	///
+	/// This is used to enforce the grammar rules. For example,
+	/// after parsing a tag name, we expect to see either attributes or `>`.
+	fn expect_token(&mut self, expected: Token) -> Result<(), ParseError> {
+		self.check_token(expected)?;
+		self.advance();
+		Ok(())
+	}
+
+	/// Return an error if the current token is not the expected one, without advancing.
+	fn check_token(&self, expected: Token) -> Result<(), ParseError> {
+		if std::mem::discriminant(&self.current_token) == std::mem::discriminant(&expected) {
+			Ok(())
+		} else {
+			Err(self.error(format!(
+				"Expected {:?}, found {:?}",
+				expected, self.current_token
+			)))
+		}
+	}
+
+	/// Parse attributes from the current token position.
+	///
+	/// Attributes have the form:
+	/// - `name="value"` - string attribute
+	/// - `name={expression}` - expression attribute
+	/// - `name` - boolean attribute (no value)
+	///
+	/// Returns a vector of parsed attributes.
+	fn parse_attributes(&mut self) -> Result<Vec<Attribute>, ParseError> {
+		let mut attributes = Vec::new();
+
+		// Keep parsing attributes while we see identifiers
+		while let Token::Identifier(name) = &self.current_token {
+			let attr_name = name.clone();
+			let position = self.position;
+			self.advance();
+
+			let value = if matches!(self.current_token, Token::Equals) {
+				self.advance(); // consume =
+
+				// Parse the attribute value
+				match &self.current_token {
+					Token::StringLiteral(s) => {
+						let val = Some(AttributeValue::String(s.clone()));
+						self.advance();
+						val
+					}
+					Token::Expression(e) => {
+						let val = Some(AttributeValue::Expression(RustCode {
+							code: e.clone(),
+							position: self.position,
+						}));
+						self.advance();
+						val
+					}
+					_ => return Err(self.error("Expected string literal or expression after =")),
+				}
+			} else {
+				// Boolean attribute (no value means true)
+				None
+			};
+
+			attributes.push(Attribute {
+				name: attr_name,
+				value,
+				position,
+			});
+		}
+
+		Ok(attributes)
+	}
+
+	/// Parse an RSML element from the token stream.
+	///
+	/// Elements have the form:
+	/// - `<tag />` - self-closing element
+	/// - `<tag>children</tag>` - element with children
+	/// - `<tag attr="value">children</tag>` - element with attributes and children
+	///
+	/// Returns the parsed element as a Node::Element.
+	fn parse_element(&mut self) -> Result<Node, ParseError> {
+		self.expect_token(Token::OpenTag)?; // consume <
+
+		// Get the tag name
+		let tag_name = match &self.current_token {
+			Token::Identifier(name) => name.clone(),
+			_ => return Err(self.error("Expected tag name after <")),
+		};
+		let tag_position = self.position;
+		self.advance();
+
+		// Parse attributes
+		let attributes = self.parse_attributes()?;
+		if tag_name == "for" {
+			for required in ["item", "in"] {
+				let has_expression = attributes.iter().any(|attr| {
+					attr.name == required && matches!(attr.value, Some(AttributeValue::Expression(_)))
+				});
+				if !has_expression {
+					return Err(ParseError {
+						message: format!("<for> needs a `{required}={{...}}` attribute"),
+						position: tag_position,
+					});
+				}
+			}
+		}
+
+		// Check for self-closing tag
+		let self_closing = matches!(self.current_token, Token::SelfCloseTag);
+
+		if self_closing {
+			// Elements are in the body of their parent
+			self.advance_in_body(); // consume />
+			return Ok(Node::Element(Element {
+				tag_name,
+				attributes,
+				children: vec![],
+				self_closing: true,
+				position: tag_position,
+			}));
+		}
+
+		// Consume the closing > of the opening tag, the body starts after it
+		self.check_token(Token::CloseTag)?;
+		self.advance_in_body(); // consume >
+
+		let mut children = Vec::new();
+
+		// Parse children until we hit the closing tag
+		while !matches!(self.current_token, Token::EndOpenTag) {
+			match &self.current_token {
+				Token::OpenTag => {
+					// Nested element
+					children.push(self.parse_element()?);
+				}
+				Token::Expression(expr) => {
+					// Expression child: {some_expression}
+					children.push(Node::Expression(RustCode {
+						code: expr.clone(),
+						position: self.position,
+					}));
+					self.advance_in_body();
+				}
+				Token::Text(text) => {
+					// Text content between tags
+					children.push(Node::Text(text.clone()));
+					self.advance_in_body();
+				}
+				Token::Eof => {
+					return Err(ParseError {
+						message: format!("Unexpected EOF while parsing <{}>", tag_name),
+						position: tag_position,
+					});
+				}
+				_ => {
+					// Skip unknown tokens
+					self.advance_in_body();
+				}
+			}
+		}
+		trim_layout_whitespace(&mut children);
+
+		// Parse the closing tag: </tagname>
+		self.expect_token(Token::EndOpenTag)?; // consume </
+
+		// Verify the closing tag name matches the opening tag
+		if let Token::Identifier(closing_name) = &self.current_token {
+			if *closing_name != tag_name {
+				return Err(self.error(format!(
+					"Mismatched closing tag: expected </{}>, found </{}>",
+					tag_name, closing_name
+				)));
+			}
+			self.advance();
+		} else {
+			return Err(self.error("Expected tag name in closing tag"));
+		}
+
+		self.check_token(Token::CloseTag)?;
+		self.advance_in_body(); // consume >
+
+		Ok(Node::Element(Element {
+			tag_name,
+			attributes,
+			children,
+			self_closing: false,
+			position: tag_position,
+		}))
+	}
+
+	/// Parse the entire RSML input and return the root DOM node.
+	pub fn parse(&mut self) -> Result<Node, ParseError> {
+		self.parse_element()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_errors_point_at_the_offending_token() {
+		let error_at = |rsml: &str| Parser::new(rsml).parse().unwrap_err().position;
+		let rsml = "<container><text>Hi</text></contaner>";
+		assert_eq!(error_at(rsml), rsml.find("contaner").unwrap());
+		let rsml = "<container gap= ></container>";
+		assert_eq!(error_at(rsml), rsml.find('>').unwrap());
+	}
+
+	#[test]
+	fn test_errors_have_a_line_and_column() {
+		let rsml = "<container>\n\t<text>Hi</txet>\n</container>";
+		let error = Parser::new(rsml).parse().unwrap_err();
+		assert_eq!(error.line_column(rsml), (2, 12));
+	}
+}
@@ -0,0 +1,460 @@
+use std::cell::RefCell;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+use clay_layout::{id::Id, math::BoundingBox};
+use uuid::Uuid;
+
+/// The semantic role of an element, reported to assistive technologies such as screen readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role {
+	#[default]
+	Generic,
+	Group,
+	Button,
+	Link,
+	Label,
+	Heading,
+	Image,
+	CheckBox,
+	Switch,
+	RadioButton,
+	Slider,
+	SpinButton,
+	ProgressIndicator,
+	TextInput,
+	PasswordInput,
+	ComboBox,
+	List,
+	ListItem,
+	Tab,
+	TabList,
+	Menu,
+	MenuItem,
+	Dialog,
+	Tooltip,
+	Status,
+	ScrollView,
+}
+
+/// Accessibility information attached to a [`Container`](crate::Container).
+#[derive(Default)]
+pub(crate) struct AccessibilityProps {
+	pub role: Option<Role>,
+	pub label: Option<String>,
+	pub value: Option<String>,
+	/// Whether the element is a selected item, `None` for elements that can't be selected.
+	pub selected: Option<bool>,
+	pub on_value_change: Option<Box<dyn Fn(String)>>,
+}
+
+impl AccessibilityProps {
+	pub fn is_set(&self) -> bool {
+		self.role.is_some() || self.label.is_some() || self.value.is_some() || self.selected.is_some()
+	}
+}
+
+/// An action requested by an assistive technology, delivered to the targeted element on the next frame.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AccessibilityAction {
+	Focus,
+	Click,
+	SetValue(String),
+}
+
+/// A node of the accessibility tree built during a frame.
+#[derive(Clone)]
+pub(crate) struct AccessibilityNode {
+	pub id: u64,
+	pub role: Role,
+	pub label: Option<String>,
+	/// Whether the text content of the node should be appended to its label.
+	pub label_from_content: bool,
+	pub value: Option<String>,
+	pub selected: Option<bool>,
+	pub focus_node: Option<Uuid>,
+	pub clickable: bool,
+	pub editable: bool,
+	pub clay_id: Option<Id>,
+	pub bounds: Option<BoundingBox>,
+	pub children: Vec<usize>,
+}
+
+#[derive(Default)]
+pub(crate) struct AccessibilityTree {
+	pub nodes: Vec<AccessibilityNode>,
+	pub roots: Vec<usize>,
+	stack: Vec<usize>,
+}
+
+thread_local! {
+	static CURRENT_TREE: RefCell<AccessibilityTree> = RefCell::new(AccessibilityTree::default());
+	static LAST_TREE: RefCell<AccessibilityTree> = RefCell::new(AccessibilityTree::default());
+	static PENDING_ACTIONS: RefCell<Vec<(u64, AccessibilityAction)>> = RefCell::new(Vec::new());
+}
+
+/// Actions received from the accessibility backend, which may live on another thread.
+static INCOMING_ACTIONS: Mutex<Vec<(u64, AccessibilityAction)>> = Mutex::new(Vec::new());
+
+/// How urgently an [`announce`]d message should be spoken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Politeness {
+	/// Spoken when the screen reader is idle, after the current speech.
+	#[default]
+	Polite,
+	/// Interrupts the current speech.
+	Assertive,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Announcement {
+	pub message: String,
+	pub politeness: Politeness,
+	/// Incremented for every announcement so that repeating a message is spoken again.
+	pub serial: u64,
+}
+
+static LAST_ANNOUNCEMENT: Mutex<Option<Announcement>> = Mutex::new(None);
+
+/// Asks screen readers to speak `message` without moving the focus, like an ARIA live region.
+///
+/// Use it for events that happen outside of the focused element, such as "Copied to clipboard"
+/// or "3 new notifications". Can be called from any thread.
+pub fn announce(message: impl Into<String>, politeness: Politeness) {
+	let mut last = LAST_ANNOUNCEMENT.lock().unwrap();
+	let serial = last.as_ref().map_or(0, |a| a.serial + 1);
+	*last = Some(Announcement {
+		message: message.into(),
+		politeness,
+		serial,
+	});
+	drop(last);
+	crate::winit::wake_up();
+}
+
+/// Returns the most recent announcement, if any.
+pub(crate) fn last_announcement() -> Option<Announcement> {
+	LAST_ANNOUNCEMENT.lock().unwrap().clone()
+}
+
+/// Converts a stable element id into an accessibility node id.
+pub(crate) fn node_id(id: Uuid) -> u64 {
+	let (high, low) = id.as_u64_pair();
+	high ^ low
+}
+
+/// Queues an action for the node with the given id. Safe to call from any thread.
+pub(crate) fn request_action(id: u64, action: AccessibilityAction) {
+	INCOMING_ACTIONS.lock().unwrap().push((id, action));
+}
+
+/// Starts a new accessibility tree and makes the actions received since the last frame available to elements.
+pub(crate) fn begin_frame() {
+	CURRENT_TREE.with_borrow_mut(|tree| *tree = AccessibilityTree::default());
+	let incoming = std::mem::take(&mut *INCOMING_ACTIONS.lock().unwrap());
+	PENDING_ACTIONS.with_borrow_mut(|pending| {
+		pending.clear();
+		pending.extend(incoming);
+	});
+}
+
+/// Resolves the bounds of every node after layout and publishes the tree built during this frame.
+pub(crate) fn end_frame(bounding_box: impl Fn(Id) -> Option<BoundingBox>) {
+	let mut tree = CURRENT_TREE.with_borrow_mut(std::mem::take);
+	for node in &mut tree.nodes {
+		node.bounds = node.clay_id.clone().and_then(&bounding_box);
+	}
+	LAST_TREE.with_borrow_mut(|last| *last = tree);
+	PENDING_ACTIONS.with_borrow_mut(|pending| pending.clear());
+}
+
+/// Runs `f` with the accessibility tree of the last completed frame.
+pub(crate) fn with_last_tree<R>(f: impl FnOnce(&AccessibilityTree) -> R) -> R {
+	LAST_TREE.with_borrow(f)
+}
+
+/// Adds a node as a child of the innermost open node and opens it, so that nodes pushed until
+/// the matching [`pop_node`] become its children.
+pub(crate) fn push_node(node: AccessibilityNode) {
+	CURRENT_TREE.with_borrow_mut(|tree| {
+		let index = tree.nodes.len();
+		tree.nodes.push(node);
+		match tree.stack.last() {
+			Some(&parent) => tree.nodes[parent].children.push(index),
+			None => tree.roots.push(index),
+		}
+		tree.stack.push(index);
+	});
+}
+
+pub(crate) fn pop_node() {
+	CURRENT_TREE.with_borrow_mut(|tree| {
+		tree.stack.pop();
+	});
+}
+
+/// Adds a static text node to the innermost open node.
+///
+/// The text also becomes the label of the enclosing node if it doesn't have one,
+/// so that a button is announced by its content.
+pub(crate) fn add_text(text: &str) {
+	CURRENT_TREE.with_borrow_mut(|tree| {
+		let parent = tree.stack.last().copied();
+		let id = {
+			let mut hasher = DefaultHasher::new();
+			parent.map(|p| tree.nodes[p].id).hash(&mut hasher);
+			tree.nodes.len().hash(&mut hasher);
+			text.hash(&mut hasher);
+			hasher.finish()
+		};
+		let index = tree.nodes.len();
+		tree.nodes.push(AccessibilityNode {
+			id,
+			role: Role::Label,
+			label: None,
+			label_from_content: false,
+			value: Some(text.to_string()),
+			selected: None,
+			focus_node: None,
+			clickable: false,
+			editable: false,
+			clay_id: None,
+			bounds: None,
+			children: Vec::new(),
+		});
+		match parent {
+			Some(parent) => {
+				let parent = &mut tree.nodes[parent];
+				parent.children.push(index);
+				if parent.label_from_content {
+					let label = parent.label.get_or_insert_with(String::new);
+					if !label.is_empty() {
+						label.push(' ');
+					}
+					label.push_str(text);
+				}
+			}
+			None => tree.roots.push(index),
+		}
+	});
+}
+
+/// Removes and returns `true` if an action matching `action` is pending for the node `id`.
+pub(crate) fn take_action(id: u64, action: &AccessibilityAction) -> bool {
+	PENDING_ACTIONS.with_borrow_mut(|pending| {
+		let Some(index) = pending.iter().position(|(i, a)| *i == id && a == action) else {
+			return false;
+		};
+		pending.remove(index);
+		true
+	})
+}
+
+/// Removes and returns the value of a pending [`AccessibilityAction::SetValue`] for the node `id`.
+pub(crate) fn take_set_value(id: u64) -> Option<String> {
+	PENDING_ACTIONS.with_borrow_mut(|pending| {
+		let index = pending
+			.iter()
+			.position(|(i, a)| *i == id && matches!(a, AccessibilityAction::SetValue(_)))?;
+		match pending.remove(index).1 {
+			AccessibilityAction::SetValue(value) => Some(value),
+			_ => None,
+		}
+	})
+}
+
+#[cfg(feature = "accesskit")]
+pub(crate) use accesskit_backend::AccessKitAdapter;
+
+#[cfg(feature = "accesskit")]
+mod accesskit_backend {
+	use accesskit::{
+		Action, ActionData, ActionRequest, Live, Node, NodeId, Rect, Tree, TreeUpdate,
+	};
+
+	use super::{AccessibilityAction, AccessibilityTree, Politeness, Role};
+	use crate::focus_system::GLOBAL_FOCUS_MANAGER;
+
+	const ROOT_ID: NodeId = NodeId(0);
+	const LIVE_REGION_ID: NodeId = NodeId(1);
+
+	/// Publishes the accessibility tree to AT-SPI through AccessKit.
+	pub(crate) struct AccessKitAdapter {
+		adapter: accesskit_unix::Adapter,
+		title: String,
+	}
+
+	struct ActivationHandler;
+	impl accesskit::ActivationHandler for ActivationHandler {
+		fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+			// The tree is only available on the UI thread, it will be sent on the next frame.
+			crate::winit::wake_up();
+			None
+		}
+	}
+
+	struct ActionHandler;
+	impl accesskit::ActionHandler for ActionHandler {
+		fn do_action(&mut self, request: ActionRequest) {
+			let action = match (request.action, request.data) {
+				(Action::Focus, _) => AccessibilityAction::Focus,
+				(Action::Click, _) => AccessibilityAction::Click,
+				(Action::SetValue, Some(ActionData::Value(value))) => {
+					AccessibilityAction::SetValue(value.into())
+				}
+				(Action::SetValue, Some(ActionData::NumericValue(value))) => {
+					AccessibilityAction::SetValue(value.to_string())
+				}
+				_ => return,
+			};
+			super::request_action(request.target.0, action);
+			crate::winit::wake_up();
+		}
+	}
+
+	struct DeactivationHandler;
+	impl accesskit::DeactivationHandler for DeactivationHandler {
+		fn deactivate_accessibility(&mut self) {}
+	}
+
+	impl AccessKitAdapter {
+		pub(crate) fn new(title: String) -> Self {
+			Self {
+				adapter: accesskit_unix::Adapter::new(
+					ActivationHandler,
+					ActionHandler,
+					DeactivationHandler,
+				),
+				title,
+			}
+		}
+
+		pub(crate) fn set_window_focused(&mut self, focused: bool) {
+			self.adapter.update_window_focus_state(focused);
+		}
+
+		/// Sends the tree of the last completed frame to the assistive technologies, if any is listening.
+		pub(crate) fn update(&mut self) {
+			let title = self.title.clone();
+			self
+				.adapter
+				.update_if_active(|| super::with_last_tree(|tree| tree_update(tree, title)));
+		}
+	}
+
+	fn tree_update(tree: &AccessibilityTree, title: String) -> TreeUpdate {
+		let focused = GLOBAL_FOCUS_MANAGER.with_borrow(|f| f.focused());
+		let mut focus = ROOT_ID;
+		let mut nodes = Vec::with_capacity(tree.nodes.len() + 1);
+
+		let mut root = Node::new(accesskit::Role::Window);
+		root.set_label(title);
+		let mut root_children = tree
+			.roots
+			.iter()
+			.map(|&i| NodeId(tree.nodes[i].id))
+			.collect::<Vec<_>>();
+		root_children.push(LIVE_REGION_ID);
+		root.set_children(root_children);
+		nodes.push((ROOT_ID, root));
+
+		// Announcements are published as a new child of a live region, which screen readers speak when it appears.
+		let mut live_region = Node::new(accesskit::Role::Status);
+		if let Some(announcement) = super::last_announcement() {
+			live_region.set_live(match announcement.politeness {
+				Politeness::Polite => Live::Polite,
+				Politeness::Assertive => Live::Assertive,
+			});
+			let message_id = NodeId(u64::MAX - announcement.serial);
+			let mut message = Node::new(accesskit::Role::Label);
+			message.set_value(announcement.message);
+			live_region.set_children(vec![message_id]);
+			nodes.push((message_id, message));
+		} else {
+			live_region.set_live(Live::Polite);
+		}
+		nodes.push((LIVE_REGION_ID, live_region));
+
+		for node in &tree.nodes {
+			let mut n = Node::new(role(node.role));
+			if let Some(label) = &node.label {
+				n.set_label(label.clone());
+			}
+			if let Some(value) = &node.value {
+				n.set_value(value.clone());
+			}
+			if let Some(selected) = node.selected {
+				n.set_selected(selected);
+			}
+			if let Some(b) = node.bounds {
+				n.set_bounds(Rect {
+					x0: b.x as f64,
+					y0: b.y as f64,
+					x1: (b.x + b.width) as f64,
+					y1: (b.y + b.height) as f64,
+				});
+			}
+			if let Some(focus_node) = node.focus_node {
+				n.add_action(Action::Focus);
+				if focused == Some(focus_node) {
+					focus = NodeId(node.id);
+				}
+			}
+			if node.clickable {
+				n.add_action(Action::Click);
+			}
+			if node.editable {
+				n.add_action(Action::SetValue);
+			}
+			n.set_children(
+				node
+					.children
+					.iter()
+					.map(|&i| NodeId(tree.nodes[i].id))
+					.collect::<Vec<_>>(),
+			);
+			nodes.push((NodeId(node.id), n));
+		}
+
+		let mut tree = Tree::new(ROOT_ID);
+		tree.toolkit_name = Some("hyprui".to_string());
+		tree.toolkit_version = Some(env!("CARGO_PKG_VERSION").to_string());
+		TreeUpdate {
+			nodes,
+			tree: Some(tree),
+			focus,
+		}
+	}
+
+	fn role(role: Role) -> accesskit::Role {
+		use accesskit::Role as R;
+		match role {
+			Role::Generic => R::GenericContainer,
+			Role::Group => R::Group,
+			Role::Button => R::Button,
+			Role::Link => R::Link,
+			Role::Label => R::Label,
+			Role::Heading => R::Heading,
+			Role::Image => R::Image,
+			Role::CheckBox => R::CheckBox,
+			Role::Switch => R::Switch,
+			Role::RadioButton => R::RadioButton,
+			Role::Slider => R::Slider,
+			Role::SpinButton => R::SpinButton,
+			Role::ProgressIndicator => R::ProgressIndicator,
+			Role::TextInput => R::TextInput,
+			Role::PasswordInput => R::PasswordInput,
+			Role::ComboBox => R::ComboBox,
+			Role::List => R::List,
+			Role::ListItem => R::ListItem,
+			Role::Tab => R::Tab,
+			Role::TabList => R::TabList,
+			Role::Menu => R::Menu,
+			Role::MenuItem => R::MenuItem,
+			Role::Dialog => R::Dialog,
+			Role::Tooltip => R::Tooltip,
+			Role::Status => R::Status,
+			Role::ScrollView => R::ScrollView,
+		}
+	}
+}
@@ -1,3 +1,5 @@
+use std::{fmt, rc::Rc};
+
 use clay_layout::math::{BoundingBox, Dimensions};
 use clay_layout::render_commands::{Border, Custom, RenderCommand, RenderCommandConfig};
 use clay_layout::text::TextConfig;
@@ -7,6 +9,8 @@ use skia_safe::{
 	SamplingOptions, Typeface,
 };
 
+use crate::{Ring, Shadow};
+
 pub fn clay_to_skia_color(color: ClayColor) -> Color4f {
 	Color4f::new(
 		color.r / 255.,
@@ -19,6 +23,129 @@ pub fn clay_to_skia_color(color: ClayColor) -> Color4f {
 fn clay_to_skia_rect(rect: BoundingBox) -> Rect {
 	Rect::from_xywh(rect.x, rect.y, rect.width, rect.height)
 }
+
+/// Drawings clay has no render command for, declared as clay custom elements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomElement {
+	/// The background of a container with a shadow or a [`ring_progress`](crate::Container::ring_progress).
+	Background {
+		shadow: Option<Shadow>,
+		ring: Option<Ring>,
+	},
+	/// The drawing of a [`CustomDraw`](crate::CustomDraw).
+	Draw(DrawFn),
+}
+
+/// The closure of a [`CustomDraw`](crate::CustomDraw), and the key telling whether it draws the same as last frame.
+#[derive(Clone)]
+pub struct DrawFn {
+	pub(crate) draw: Rc<dyn Fn(&Canvas, Rect)>,
+	/// Hash of the key of the drawing, or a new number every frame if it has none.
+	pub(crate) key: u64,
+}
+
+impl fmt::Debug for DrawFn {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "DrawFn({})", self.key)
+	}
+}
+
+impl PartialEq for DrawFn {
+	fn eq(&self, other: &Self) -> bool {
+		self.key == other.key
+	}
+}
+
+/// Draws the custom elements of hyprui, passed to [`clay_skia_render`].
+pub fn draw_custom_element(
+	command: &RenderCommand<'_, Image, CustomElement>,
+	custom: &Custom<'_, CustomElement>,
+	canvas: &Canvas,
+) {
+	let bounds = clay_to_skia_rect(command.bounding_box);
+	let radii = &custom.corner_radii;
+	let rrect = RRect::new_rect_radii(
+		bounds,
+		&[
+			Point::new(radii.top_left, radii.top_left),
+			Point::new(radii.top_right, radii.top_right),
+			Point::new(radii.bottom_right, radii.bottom_right),
+			Point::new(radii.bottom_left, radii.bottom_left),
+		],
+	);
+	match custom.data {
+		CustomElement::Background { shadow, ring } => {
+			if let Some(shadow) = shadow {
+				draw_shadow(canvas, &rrect, shadow);
+			}
+			// Custom elements replace the background rectangle of their element
+			let mut paint = Paint::default();
+			paint.set_color4f(clay_to_skia_color(custom.background_color), None);
+			paint.set_anti_alias(true);
+			canvas.draw_rrect(rrect, &paint);
+			if let Some(ring) = ring {
+				draw_ring(canvas, bounds, ring);
+			}
+		}
+		CustomElement::Draw(draw) => {
+			canvas.save();
+			canvas.clip_rect(bounds, ClipOp::Intersect, true);
+			(draw.draw)(canvas, bounds);
+			canvas.restore();
+		}
+	}
+}
+
+/// Draws a blurred copy of `rrect` behind it, moved by the offset of the shadow and grown by its spread.
+fn draw_shadow(canvas: &Canvas, rrect: &RRect, shadow: &Shadow) {
+	let mut shadow_rrect = rrect.with_offset((shadow.offset.0, shadow.offset.1));
+	shadow_rrect.outset((shadow.spread, shadow.spread));
+	let mut paint = Paint::default();
+	paint.set_color4f(clay_to_skia_color(shadow.color), None);
+	paint.set_anti_alias(true);
+	if shadow.blur > 0. {
+		// Like CSS, the blur radius is twice the standard deviation
+		paint.set_mask_filter(skia_safe::MaskFilter::blur(
+			skia_safe::BlurStyle::Normal,
+			shadow.blur / 2.,
+			false,
+		));
+	}
+	canvas.draw_rrect(shadow_rrect, &paint);
+}
+
+/// Draws a ring starting at the top and going clockwise, or a pie if the ring has no thickness.
+fn draw_ring(canvas: &Canvas, bounds: Rect, ring: &Ring) {
+	let size = bounds.width().min(bounds.height());
+	let pie = ring.thickness <= 0.;
+	let inset = if pie {
+		0.
+	} else {
+		ring.thickness.min(size / 2.) / 2.
+	};
+	let oval = Rect::from_xywh(
+		bounds.center_x() - size / 2. + inset,
+		bounds.center_y() - size / 2. + inset,
+		size - inset * 2.,
+		size - inset * 2.,
+	);
+	let mut paint = Paint::default();
+	paint.set_anti_alias(true);
+	if pie {
+		paint.set_style(skia_safe::PaintStyle::Fill);
+	} else {
+		paint.set_style(skia_safe::PaintStyle::Stroke);
+		paint.set_stroke_width(inset * 2.);
+		paint.set_stroke_cap(PaintCap::Round);
+	}
+	paint.set_color4f(clay_to_skia_color(ring.track_color), None);
+	canvas.draw_oval(oval, &paint);
+	let sweep = ring.fraction.clamp(0., 1.) * 360.;
+	if sweep > 0. {
+		paint.set_color4f(clay_to_skia_color(ring.color), None);
+		canvas.draw_arc(oval, -90., sweep, pie, &paint);
+	}
+}
 /// This is a direct* port of Clay's raylib renderer using skia_safe as the drawing API.
 pub fn clay_skia_render<'a, CustomElementData: 'a>(
 	canvas: &Canvas,
@@ -50,7 +177,11 @@ pub fn clay_skia_render<'a, CustomElementData: 'a>(
 				paint.set_color(Color::WHITE);
 				paint.set_anti_alias(true);
 
-				let bounds = clay_to_skia_rect(command.bounding_box);
+				let (source, bounds) = crate::image::fit_rects(
+					crate::image::fit_of(skia_image),
+					(skia_image.width() as f32, skia_image.height() as f32),
+					clay_to_skia_rect(command.bounding_box),
+				);
 				let has_border_radius = image.corner_radii.top_left > 0.
 					|| image.corner_radii.top_right > 0.
 					|| image.corner_radii.bottom_left > 0.
@@ -77,7 +208,9 @@ pub fn clay_skia_render<'a, CustomElementData: 'a>(
 
 				canvas.draw_image_rect_with_sampling_options(
 					skia_image,
-					None,
+					source
+						.as_ref()
+						.map(|source| (source, skia_safe::canvas::SrcRectConstraint::Fast)),
 					bounds,
 					SamplingOptions::new(skia_safe::FilterMode::Linear, skia_safe::MipmapMode::Linear),
 					&paint,
@@ -0,0 +1,139 @@
+//! Copying and pasting text.
+//!
+//! The text editing widgets read and write the clipboard through a [`ClipboardProvider`]. On Wayland the default
+//! one runs `wl-copy` and `wl-paste` from wl-clipboard on a thread of their own, so text is shared with other apps
+//! without blocking the frames; elsewhere, or when they are not installed, text is only kept in the memory of the
+//! app. The [`TestHarness`](crate::testing::TestHarness) replaces it with a [`MemoryClipboard`].
+use std::{
+	cell::RefCell,
+	io::Write,
+	process::{Command, Stdio},
+	sync::{Arc, Mutex, OnceLock},
+};
+
+/// Text being read from the clipboard, which can arrive after the frame that asked for it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PendingText(Arc<OnceLock<Option<String>>>);
+
+impl PendingText {
+	fn ready(text: Option<String>) -> Self {
+		Self(Arc::new(OnceLock::from(text)))
+	}
+
+	/// Returns the text once it is read, `Some(None)` if the clipboard doesn't hold text.
+	pub(crate) fn get(&self) -> Option<Option<&str>> {
+		self.0.get().map(Option::as_deref)
+	}
+}
+
+impl PartialEq for PendingText {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+
+/// Where copied text goes and pasted text comes from.
+pub(crate) trait ClipboardProvider {
+	/// Starts reading the text on the clipboard.
+	fn read(&mut self) -> PendingText;
+	fn write(&mut self, text: String);
+}
+
+/// A clipboard only seen by the app itself.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryClipboard {
+	text: Option<String>,
+}
+
+impl ClipboardProvider for MemoryClipboard {
+	fn read(&mut self) -> PendingText {
+		PendingText::ready(self.text.clone())
+	}
+
+	fn write(&mut self, text: String) {
+		self.text = Some(text);
+	}
+}
+
+/// The default clipboard, going through wl-clipboard when running on Wayland.
+#[derive(Default)]
+struct DesktopClipboard {
+	/// Used when wl-clipboard can't be run, shared with the threads running it.
+	fallback: Arc<Mutex<MemoryClipboard>>,
+}
+
+impl ClipboardProvider for DesktopClipboard {
+	fn read(&mut self) -> PendingText {
+		if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+			return self.fallback.lock().unwrap().read();
+		}
+		let pending = PendingText::default();
+		let text = pending.0.clone();
+		let fallback = self.fallback.clone();
+		std::thread::spawn(move || {
+			let output = Command::new("wl-paste")
+				.args(["--no-newline", "--type", "text"])
+				.stdin(Stdio::null())
+				.stderr(Stdio::null())
+				.output();
+			let _ = text.set(match output {
+				// wl-paste fails when the clipboard is empty or holds something else than text
+				Ok(output) if output.status.success() => String::from_utf8(output.stdout).ok(),
+				Ok(_) => None,
+				Err(err) => {
+					log::warn!("failed to run wl-paste, using a clipboard local to the app: {err}");
+					fallback.lock().unwrap().text.clone()
+				}
+			});
+			crate::winit::wake_up();
+		});
+		pending
+	}
+
+	fn write(&mut self, text: String) {
+		self.fallback.lock().unwrap().write(text.clone());
+		if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+			return;
+		}
+		let child = Command::new("wl-copy")
+			.stdin(Stdio::piped())
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn();
+		match child {
+			// wl-copy keeps running in the background to serve the text, waiting avoids leaving a zombie process.
+			Ok(mut child) => {
+				let stdin = child.stdin.take();
+				std::thread::spawn(move || {
+					if let Some(mut stdin) = stdin
+						&& let Err(err) = stdin.write_all(text.as_bytes())
+					{
+						log::error!("failed to write to wl-copy: {err}");
+					}
+					child.wait()
+				});
+			}
+			Err(err) => log::warn!("failed to run wl-copy, using a clipboard local to the app: {err}"),
+		}
+	}
+}
+
+thread_local! {
+	static PROVIDER: RefCell<Box<dyn ClipboardProvider>> = RefCell::new(Box::new(DesktopClipboard::default()));
+}
+
+/// Replaces the clipboard used by the app.
+pub(crate) fn set_clipboard_provider(provider: impl ClipboardProvider + 'static) {
+	PROVIDER.set(Box::new(provider));
+}
+
+/// Starts reading the text on the clipboard.
+pub(crate) fn read_clipboard_text() -> PendingText {
+	PROVIDER.with_borrow_mut(|provider| provider.read())
+}
+
+/// Puts `text` on the clipboard.
+pub(crate) fn set_clipboard_text(text: impl Into<String>) {
+	let text = text.into();
+	PROVIDER.with_borrow_mut(|provider| provider.write(text));
+}
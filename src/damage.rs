@@ -0,0 +1,307 @@
+//! Finds the parts of the window that changed since the frame in the back buffer, so only they are repainted.
+//!
+//! The render commands of every frame are reduced to where they draw and a hash of what they draw. The areas of the
+//! commands that appeared or disappeared since the last frame are its damage, and the damage of the last few frames
+//! is kept to repaint back buffers that are more than one frame old.
+use std::{
+	collections::{HashMap, VecDeque},
+	hash::{DefaultHasher, Hash, Hasher},
+};
+
+use clay_layout::{
+	Color,
+	render_commands::{RenderCommand, RenderCommandConfig},
+};
+use skia_safe::{Canvas, IRect, ISize, Image, Rect, Region};
+
+use crate::clay_renderer::CustomElement;
+
+/// The number of frames whose damage is kept, the oldest back buffer that isn't repainted fully.
+const HISTORY: usize = 4;
+/// Beyond this many rectangles, the damage is repainted as the rectangle around them.
+const MAX_RECTS: usize = 8;
+/// Added around the area of every command, for antialiasing.
+const MARGIN: f32 = 2.;
+
+/// The parts of the window to repaint.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Damage {
+	/// The whole window, because what the back buffer holds is unknown.
+	Full,
+	/// Only these rectangles, in pixels from the top left corner. Empty when nothing changed.
+	Rects(Vec<IRect>),
+}
+
+/// A render command reduced to the pixels it may touch and a hash of what it draws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Drawn {
+	area: IRect,
+	hash: u64,
+}
+
+fn hash_floats(hasher: &mut DefaultHasher, floats: &[f32]) {
+	for float in floats {
+		float.to_bits().hash(hasher);
+	}
+}
+
+fn hash_color(hasher: &mut DefaultHasher, color: Color) {
+	hash_floats(hasher, &[color.r, color.g, color.b, color.a]);
+}
+
+macro_rules! radii {
+	($radii:expr) => {
+		[
+			$radii.top_left,
+			$radii.top_right,
+			$radii.bottom_left,
+			$radii.bottom_right,
+		]
+	};
+}
+
+fn drawn(command: &RenderCommand<'_, Image, CustomElement>) -> Drawn {
+	let mut hasher = DefaultHasher::new();
+	let bounds = command.bounding_box;
+	hash_floats(
+		&mut hasher,
+		&[bounds.x, bounds.y, bounds.width, bounds.height],
+	);
+	std::mem::discriminant(&command.config).hash(&mut hasher);
+	let mut outset = MARGIN;
+	match &command.config {
+		RenderCommandConfig::Rectangle(rect) => {
+			hash_color(&mut hasher, rect.color);
+			hash_floats(&mut hasher, &radii!(rect.corner_radii));
+		}
+		RenderCommandConfig::Border(border) => {
+			hash_color(&mut hasher, border.color);
+			hash_floats(&mut hasher, &radii!(border.corner_radii));
+			hash_floats(
+				&mut hasher,
+				&[
+					border.width.left as f32,
+					border.width.right as f32,
+					border.width.top as f32,
+					border.width.bottom as f32,
+				],
+			);
+		}
+		RenderCommandConfig::Text(text) => {
+			text.text.hash(&mut hasher);
+			hash_color(&mut hasher, text.color);
+			text.font_size.hash(&mut hasher);
+			text.font_id.hash(&mut hasher);
+			// Slanted and overhanging glyphs are drawn a little outside of the measured text
+			outset += text.font_size as f32 / 4.;
+		}
+		RenderCommandConfig::Image(image) => {
+			image.data.unique_id().hash(&mut hasher);
+			crate::image::fit_of(image.data).hash(&mut hasher);
+			hash_floats(&mut hasher, &radii!(image.corner_radii));
+		}
+		RenderCommandConfig::Custom(custom) => {
+			format!("{:?}", custom.data).hash(&mut hasher);
+			hash_color(&mut hasher, custom.background_color);
+			hash_floats(&mut hasher, &radii!(custom.corner_radii));
+			if let CustomElement::Background {
+				shadow: Some(shadow),
+				..
+			} = custom.data
+			{
+				outset +=
+					shadow.offset.0.abs().max(shadow.offset.1.abs()) + shadow.spread.max(0.) + shadow.blur;
+			}
+		}
+		RenderCommandConfig::ScissorStart()
+		| RenderCommandConfig::ScissorEnd()
+		| RenderCommandConfig::None() => {}
+	}
+	Drawn {
+		area: Rect::from_xywh(bounds.x, bounds.y, bounds.width, bounds.height)
+			.with_outset((outset, outset))
+			.round_out(),
+		hash: hasher.finish(),
+	}
+}
+
+/// Returns the areas of the commands drawn in only one of two frames, or `None` if the whole window changed
+/// because commands drawn in both are stacked in another order.
+fn changed_areas(previous: &[Drawn], current: &[Drawn]) -> Option<Vec<IRect>> {
+	// How many more times each command is drawn in the previous frame than in the current one
+	let mut surplus: HashMap<u64, isize> = HashMap::new();
+	for drawn in previous {
+		*surplus.entry(drawn.hash).or_default() += 1;
+	}
+	for drawn in current {
+		*surplus.entry(drawn.hash).or_default() -= 1;
+	}
+	let mut areas = Vec::new();
+	let mut kept = |frame: &[Drawn], removed: bool| -> Vec<u64> {
+		frame
+			.iter()
+			.filter(|drawn| {
+				let surplus = surplus.get_mut(&drawn.hash).unwrap();
+				let changed = if removed { *surplus > 0 } else { *surplus < 0 };
+				if changed {
+					*surplus -= surplus.signum();
+					areas.push(drawn.area);
+				}
+				!changed
+			})
+			.map(|drawn| drawn.hash)
+			.collect()
+	};
+	let kept_previous = kept(previous, true);
+	let kept_current = kept(current, false);
+	(kept_previous == kept_current).then_some(areas)
+}
+
+/// Clips `rects` to the window, and merges them when there are too many to repaint separately.
+fn simplify(rects: Vec<IRect>, size: ISize) -> Vec<IRect> {
+	let window = IRect::from_size(size);
+	let rects: Vec<IRect> = rects
+		.into_iter()
+		.filter_map(|rect| IRect::intersect(&rect, &window))
+		.filter(|rect| !rect.is_empty())
+		.collect();
+	if rects.len() <= MAX_RECTS {
+		return rects;
+	}
+	let mut around = rects[0];
+	for rect in &rects[1..] {
+		around.join(rect);
+	}
+	vec![around]
+}
+
+/// Paints a frame with `draw`, clipped to `damage` after clearing it.
+pub(crate) fn paint(canvas: &Canvas, damage: &Damage, draw: impl FnOnce()) {
+	canvas.save();
+	if let Damage::Rects(rects) = damage {
+		let mut region = Region::new();
+		region.set_rects(rects);
+		canvas.clip_region(&region, None);
+	}
+	canvas.clear(skia_safe::Color::TRANSPARENT);
+	draw();
+	canvas.restore();
+}
+
+/// Remembers what the last frames drew, to find what a new frame has to repaint.
+#[derive(Default)]
+pub(crate) struct DamageTracker {
+	previous: Vec<Drawn>,
+	size: ISize,
+	/// The damage of the last frames, the latest first, `None` for the frames that changed the whole window.
+	history: VecDeque<Option<Vec<IRect>>>,
+	/// Set when something was drawn outside of the render commands, which the next frame has to erase.
+	invalidated: bool,
+}
+
+impl DamageTracker {
+	/// Returns the parts of a window of `size` to repaint to draw `commands`, on a back buffer that holds the frame
+	/// drawn `buffer_age` frames ago, or 0 if that is unknown.
+	pub(crate) fn track(
+		&mut self,
+		commands: &[RenderCommand<'_, Image, CustomElement>],
+		size: ISize,
+		buffer_age: u32,
+	) -> Damage {
+		self.track_drawn(commands.iter().map(drawn).collect(), size, buffer_age)
+	}
+
+	fn track_drawn(&mut self, current: Vec<Drawn>, size: ISize, buffer_age: u32) -> Damage {
+		let changed = if size == self.size && !self.invalidated {
+			changed_areas(&self.previous, &current)
+		} else {
+			None
+		};
+		self.previous = current;
+		self.size = size;
+		self.invalidated = false;
+		self.history.push_front(changed);
+		self.history.truncate(HISTORY);
+		// The back buffer missed the changes of every frame drawn after it, including this one
+		let age = buffer_age as usize;
+		if age == 0 || age > self.history.len() {
+			return Damage::Full;
+		}
+		let mut rects = Vec::new();
+		for changed in self.history.iter().take(age) {
+			match changed {
+				Some(changed) => rects.extend_from_slice(changed),
+				None => return Damage::Full,
+			}
+		}
+		Damage::Rects(simplify(rects, size))
+	}
+
+	/// Repaints the whole window this frame and the next, for drawings on top of the render commands like the
+	/// inspector.
+	pub(crate) fn invalidate(&mut self) -> Damage {
+		if let Some(changed) = self.history.front_mut() {
+			*changed = None;
+		}
+		self.invalidated = true;
+		Damage::Full
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn command(hash: u64, x: i32) -> Drawn {
+		Drawn {
+			area: IRect::from_xywh(x, 0, 10, 10),
+			hash,
+		}
+	}
+
+	#[test]
+	fn test_only_the_changes_since_the_back_buffer_are_repainted() {
+		let size = ISize::new(100, 100);
+		let mut tracker = DamageTracker::default();
+		let frame = vec![command(1, 0), command(2, 20), command(3, 40)];
+		assert_eq!(tracker.track_drawn(frame.clone(), size, 1), Damage::Full);
+		assert_eq!(
+			tracker.track_drawn(frame.clone(), size, 1),
+			Damage::Rects(vec![])
+		);
+		// The second command moved
+		let moved = vec![command(1, 0), command(4, 60), command(3, 40)];
+		assert_eq!(
+			tracker.track_drawn(moved.clone(), size, 1),
+			Damage::Rects(vec![
+				IRect::from_xywh(20, 0, 10, 10),
+				IRect::from_xywh(60, 0, 10, 10)
+			])
+		);
+		// A back buffer two frames old also missed the move
+		assert_eq!(
+			tracker.track_drawn(moved.clone(), size, 2),
+			Damage::Rects(vec![
+				IRect::from_xywh(20, 0, 10, 10),
+				IRect::from_xywh(60, 0, 10, 10)
+			])
+		);
+		assert_eq!(tracker.track_drawn(moved.clone(), size, 0), Damage::Full);
+		assert_eq!(tracker.track_drawn(moved.clone(), size, 5), Damage::Full);
+		// Stacked in another order
+		let reordered = vec![command(3, 40), command(1, 0), command(4, 60)];
+		assert_eq!(
+			tracker.track_drawn(reordered.clone(), size, 1),
+			Damage::Full
+		);
+		tracker.invalidate();
+		assert_eq!(
+			tracker.track_drawn(reordered.clone(), size, 1),
+			Damage::Full
+		);
+		assert_eq!(
+			tracker.track_drawn(reordered, ISize::new(200, 100), 1),
+			Damage::Full
+		);
+	}
+}
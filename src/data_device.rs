@@ -0,0 +1,470 @@
+//! Dragging data between the app and other apps, through the Wayland data device.
+//!
+//! Winit only tells which files are dragged over a window, so the app follows drags on the data device itself, with
+//! an event queue of its own on the Wayland connection of winit. A drag from another app offers its data in several
+//! MIME types: the drop target under the pointer accepts one of them, and its data is read from a pipe once dropped.
+//! A container with [`drag_data`](crate::Container::drag_data) is dragged by the compositor instead of the app, so
+//! it can leave the window, and the data is written to the app it is dropped in in the MIME type that app picked.
+use std::{
+	cell::RefCell,
+	ffi::c_void,
+	fs::File,
+	io::{Read, Write},
+	os::fd::AsFd,
+	ptr::NonNull,
+	sync::{Arc, Mutex},
+};
+
+use wayland_backend::client::{Backend, ObjectId};
+use wayland_client::{
+	Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum, event_created_child,
+	globals::{GlobalListContents, registry_queue_init},
+	protocol::{
+		wl_data_device::{self, WlDataDevice},
+		wl_data_device_manager::{DndAction, WlDataDeviceManager},
+		wl_data_offer::{self, WlDataOffer},
+		wl_data_source::{self, WlDataSource},
+		wl_pointer::{self, WlPointer},
+		wl_registry::WlRegistry,
+		wl_seat::{self, WlSeat},
+		wl_surface::WlSurface,
+	},
+};
+
+use crate::element::container::drag_and_drop::PendingData;
+
+/// A drag over the window, handled like the drag events of winit.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DragEvent {
+	/// A drag entered the window, at a position in logical pixels. `mime_types` is `None` for a drag of the app.
+	Enter {
+		position: (f64, f64),
+		mime_types: Option<Vec<String>>,
+	},
+	Motion {
+		position: (f64, f64),
+	},
+	/// The drag of another app left the window without being dropped.
+	Leave,
+	/// The drag of another app was dropped on the window.
+	Drop,
+	/// The drag of the app ended, dropped on the window when `on_window`, or else in another app or cancelled.
+	Ended {
+		on_window: bool,
+	},
+}
+
+/// The MIME types of an offer, sent by the compositor right after the offer is created.
+#[derive(Default)]
+struct OfferMimeTypes(Mutex<Vec<String>>);
+
+#[derive(Default)]
+struct State {
+	surface: Option<WlSurface>,
+	pointer: Option<WlPointer>,
+	/// The serial of the last press of a pointer button, which drags start from.
+	press_serial: Option<u32>,
+	/// The offer of the drag over the window, with the serial of the event it entered with.
+	offer: Option<(WlDataOffer, u32)>,
+	/// The offer of the drag of another app dropped on the window, until its data is read.
+	dropped: Option<WlDataOffer>,
+	/// Offers whose data is being read, finished once it is.
+	receiving: Vec<(WlDataOffer, PendingData)>,
+	/// The source of the drag of the app, until it is dropped or cancelled.
+	source: Option<WlDataSource>,
+	events: Vec<DragEvent>,
+}
+
+struct DataDevice {
+	connection: Connection,
+	queue: EventQueue<State>,
+	manager: WlDataDeviceManager,
+	device: WlDataDevice,
+	state: State,
+}
+
+thread_local! {
+	static DEVICE: RefCell<Option<DataDevice>> = const { RefCell::new(None) };
+}
+
+/// Starts following the drags over the Wayland surface `surface`, on the connection `display` of winit.
+///
+/// Without a data device manager, drags stay in the app and only files can be dropped from other apps.
+///
+/// # Safety
+///
+/// `display` must be a `wl_display` and `surface` a `wl_surface` of it, which stay alive until [`disconnect`].
+pub(crate) unsafe fn connect(display: NonNull<c_void>, surface: NonNull<c_void>) {
+	disconnect();
+	match unsafe { try_connect(display, surface) } {
+		Ok(device) => DEVICE.set(Some(device)),
+		Err(err) => log::warn!("drag-and-drop with other apps is unavailable: {err}"),
+	}
+}
+
+unsafe fn try_connect(
+	display: NonNull<c_void>,
+	surface: NonNull<c_void>,
+) -> Result<DataDevice, String> {
+	let backend = unsafe { Backend::from_foreign_display(display.as_ptr().cast()) };
+	let connection = Connection::from_backend(backend);
+	let (globals, mut queue) =
+		registry_queue_init::<State>(&connection).map_err(|err| err.to_string())?;
+	let qh = queue.handle();
+	let manager: WlDataDeviceManager = globals
+		.bind(&qh, 1..=3, ())
+		.map_err(|err| err.to_string())?;
+	let seat: WlSeat = globals
+		.bind(&qh, 1..=5, ())
+		.map_err(|err| err.to_string())?;
+	let surface = unsafe { ObjectId::from_ptr(WlSurface::interface(), surface.as_ptr().cast()) }
+		.and_then(|id| WlSurface::from_id(&connection, id))
+		.map_err(|err| err.to_string())?;
+	let device = manager.get_data_device(&seat, &qh, ());
+	let mut state = State {
+		surface: Some(surface),
+		..State::default()
+	};
+	// Gets the capabilities of the seat, to follow the presses of its pointer
+	queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+	Ok(DataDevice {
+		connection,
+		queue,
+		manager,
+		device,
+		state,
+	})
+}
+
+/// Stops following the drags, before the surface is destroyed.
+pub(crate) fn disconnect() {
+	if let Some(mut device) = DEVICE.take() {
+		if device.device.version() >= 2 {
+			device.device.release();
+		}
+		if let Some(pointer) = device
+			.state
+			.pointer
+			.take()
+			.filter(|pointer| pointer.version() >= 3)
+		{
+			pointer.release();
+		}
+		let _ = device.connection.flush();
+	}
+}
+
+/// Returns `true` while the drags over the window are followed through the data device.
+pub(crate) fn is_connected() -> bool {
+	DEVICE.with_borrow(Option::is_some)
+}
+
+/// Handles the events the event loop of winit read for the data device since the last call, and returns the drags
+/// they moved.
+pub(crate) fn dispatch() -> Vec<DragEvent> {
+	DEVICE.with_borrow_mut(|device| {
+		let Some(device) = device else {
+			return Vec::new();
+		};
+		if let Err(err) = device.queue.dispatch_pending(&mut device.state) {
+			log::error!("failed to dispatch the events of the data device: {err}");
+		}
+		device.state.receiving.retain(|(offer, data)| {
+			if data.get().is_none() {
+				return true;
+			}
+			if offer.version() >= 3 {
+				offer.finish();
+			}
+			offer.destroy();
+			false
+		});
+		let _ = device.connection.flush();
+		std::mem::take(&mut device.state.events)
+	})
+}
+
+/// Tells the app whose drag is over the window which of its MIME types is accepted, if any.
+pub(crate) fn accept(mime_type: Option<&str>) {
+	DEVICE.with_borrow(|device| {
+		let Some(device) = device else {
+			return;
+		};
+		if let Some((offer, serial)) = &device.state.offer {
+			offer.accept(*serial, mime_type.map(str::to_string));
+			let _ = device.connection.flush();
+		}
+	});
+}
+
+/// Starts reading the data of the drag dropped on the window, as `mime_type`, or refuses it with `None`.
+pub(crate) fn receive(mime_type: Option<&str>) -> Option<PendingData> {
+	DEVICE.with_borrow_mut(|device| {
+		let device = device.as_mut()?;
+		let offer = device.state.dropped.take()?;
+		let Some(mime_type) = mime_type else {
+			offer.destroy();
+			let _ = device.connection.flush();
+			return None;
+		};
+		let (mut reader, writer) = match std::io::pipe() {
+			Ok(pipe) => pipe,
+			Err(err) => {
+				log::error!("failed to create a pipe for the dropped data: {err}");
+				offer.destroy();
+				return None;
+			}
+		};
+		offer.receive(mime_type.to_string(), writer.as_fd());
+		let _ = device.connection.flush();
+		// The other app writes to its copy of the pipe, the read ends once it closes it
+		drop(writer);
+		let data = PendingData::default();
+		let pending = data.clone();
+		let mime_type = mime_type.to_string();
+		std::thread::spawn(move || {
+			let mut bytes = Vec::new();
+			let read = match reader.read_to_end(&mut bytes) {
+				Ok(_) => Some(bytes),
+				Err(err) => {
+					log::error!("failed to read the dropped {mime_type}: {err}");
+					None
+				}
+			};
+			pending.set(read);
+			crate::winit::wake_up();
+		});
+		device.state.receiving.push((offer, data.clone()));
+		Some(data)
+	})
+}
+
+/// Starts dragging `offers`, data in MIME types, from the last press of a pointer button on the window. Returns
+/// `false` if the compositor can't drag it, so it stays in the app.
+pub(crate) fn start_drag(offers: Arc<[(String, Arc<[u8]>)]>) -> bool {
+	DEVICE.with_borrow_mut(|device| {
+		let Some(device) = device else {
+			return false;
+		};
+		let (Some(serial), Some(surface)) = (device.state.press_serial, &device.state.surface) else {
+			return false;
+		};
+		let qh = device.queue.handle();
+		let source = device.manager.create_data_source(&qh, offers.clone());
+		for (mime_type, _) in offers.iter() {
+			source.offer(mime_type.clone());
+		}
+		if source.version() >= 3 {
+			source.set_actions(DndAction::Copy);
+		}
+		device
+			.device
+			.start_drag(Some(&source), surface, None, serial);
+		if let Some(previous) = device.state.source.replace(source) {
+			previous.destroy();
+		}
+		device.connection.flush().is_ok()
+	})
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlRegistry,
+		_: <WlRegistry as Proxy>::Event,
+		_: &GlobalListContents,
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<WlDataDeviceManager, ()> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlDataDeviceManager,
+		_: <WlDataDeviceManager as Proxy>::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<WlSeat, ()> for State {
+	fn event(
+		state: &mut Self,
+		seat: &WlSeat,
+		event: wl_seat::Event,
+		_: &(),
+		_: &Connection,
+		qh: &QueueHandle<Self>,
+	) {
+		if let wl_seat::Event::Capabilities {
+			capabilities: WEnum::Value(capabilities),
+		} = event
+		{
+			let has_pointer = capabilities.contains(wl_seat::Capability::Pointer);
+			if has_pointer && state.pointer.is_none() {
+				state.pointer = Some(seat.get_pointer(qh, ()));
+			} else if !has_pointer
+				&& let Some(pointer) = state.pointer.take()
+				&& pointer.version() >= 3
+			{
+				pointer.release();
+			}
+		}
+	}
+}
+
+impl Dispatch<WlPointer, ()> for State {
+	fn event(
+		state: &mut Self,
+		_: &WlPointer,
+		event: wl_pointer::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+		if let wl_pointer::Event::Button {
+			serial,
+			state: WEnum::Value(wl_pointer::ButtonState::Pressed),
+			..
+		} = event
+		{
+			state.press_serial = Some(serial);
+		}
+	}
+}
+
+impl Dispatch<WlDataDevice, ()> for State {
+	fn event(
+		state: &mut Self,
+		_: &WlDataDevice,
+		event: wl_data_device::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+		match event {
+			wl_data_device::Event::Enter {
+				serial,
+				surface,
+				x,
+				y,
+				id: Some(offer),
+			} => {
+				if let Some((previous, _)) = state.offer.take() {
+					previous.destroy();
+				}
+				// A drag over a popup of winit, which isn't followed
+				if state.surface.as_ref() != Some(&surface) {
+					offer.destroy();
+					return;
+				}
+				let mime_types = offer
+					.data::<OfferMimeTypes>()
+					.map(|mime_types| mime_types.0.lock().unwrap().clone())
+					.unwrap_or_default();
+				if offer.version() >= 3 {
+					offer.set_actions(DndAction::Copy, DndAction::Copy);
+				}
+				let own = state.source.is_some();
+				if own {
+					// The drop lands on the window only if one of the types is accepted
+					offer.accept(serial, mime_types.first().cloned());
+				}
+				state.offer = Some((offer, serial));
+				state.events.push(DragEvent::Enter {
+					position: (x, y),
+					mime_types: (!own).then_some(mime_types),
+				});
+			}
+			wl_data_device::Event::Motion { x, y, .. } if state.offer.is_some() => {
+				state.events.push(DragEvent::Motion { position: (x, y) });
+			}
+			wl_data_device::Event::Leave => {
+				if let Some((offer, _)) = state.offer.take() {
+					offer.destroy();
+					if state.source.is_none() {
+						state.events.push(DragEvent::Leave);
+					}
+				}
+			}
+			wl_data_device::Event::Drop => {
+				let Some((offer, _)) = state.offer.take() else {
+					return;
+				};
+				if state.source.take().is_some() {
+					// The drop of the app is handled in the app, its source learns it is finished
+					if offer.version() >= 3 {
+						offer.finish();
+					}
+					offer.destroy();
+					state.events.push(DragEvent::Ended { on_window: true });
+				} else {
+					if let Some(previous) = state.dropped.replace(offer) {
+						previous.destroy();
+					}
+					state.events.push(DragEvent::Drop);
+				}
+			}
+			wl_data_device::Event::Selection { id: Some(offer) } => offer.destroy(),
+			_ => {}
+		}
+	}
+
+	event_created_child!(State, WlDataDevice, [
+		wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, OfferMimeTypes::default()),
+	]);
+}
+
+impl Dispatch<WlDataOffer, OfferMimeTypes> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlDataOffer,
+		event: wl_data_offer::Event,
+		mime_types: &OfferMimeTypes,
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+		if let wl_data_offer::Event::Offer { mime_type } = event {
+			mime_types.0.lock().unwrap().push(mime_type);
+		}
+	}
+}
+
+impl Dispatch<WlDataSource, Arc<[(String, Arc<[u8]>)]>> for State {
+	fn event(
+		state: &mut Self,
+		source: &WlDataSource,
+		event: wl_data_source::Event,
+		offers: &Arc<[(String, Arc<[u8]>)]>,
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+		match event {
+			wl_data_source::Event::Send { mime_type, fd } => {
+				let Some((_, data)) = offers.iter().find(|(offered, _)| *offered == mime_type) else {
+					return;
+				};
+				let data = data.clone();
+				// The other app may read slowly, or not at all
+				std::thread::spawn(move || {
+					if let Err(err) = File::from(fd).write_all(&data) {
+						log::warn!("failed to send the dragged {mime_type}: {err}");
+					}
+				});
+			}
+			wl_data_source::Event::Cancelled | wl_data_source::Event::DndFinished => {
+				source.destroy();
+				// Already ended if it was dropped on the window
+				if state.source.as_ref() == Some(source) {
+					state.source = None;
+					state.events.push(DragEvent::Ended { on_window: false });
+				}
+			}
+			_ => {}
+		}
+	}
+}
@@ -0,0 +1,662 @@
+//! Calendar dates and the local time, for clocks and calendars.
+//!
+//! The local time zone is read from the `TZ` environment variable or `/etc/localtime`, in the format of the tz
+//! database, so it follows the system settings without a date library.
+use std::{
+	fmt::Write,
+	path::PathBuf,
+	sync::Mutex,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+const MONTHS: [&str; 12] = [
+	"January",
+	"February",
+	"March",
+	"April",
+	"May",
+	"June",
+	"July",
+	"August",
+	"September",
+	"October",
+	"November",
+	"December",
+];
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+	Monday,
+	Tuesday,
+	Wednesday,
+	Thursday,
+	Friday,
+	Saturday,
+	Sunday,
+}
+
+impl Weekday {
+	const ALL: [Weekday; 7] = [
+		Weekday::Monday,
+		Weekday::Tuesday,
+		Weekday::Wednesday,
+		Weekday::Thursday,
+		Weekday::Friday,
+		Weekday::Saturday,
+		Weekday::Sunday,
+	];
+
+	/// Returns the day `days` after Monday, wrapping around the week.
+	pub fn from_monday(days: i64) -> Self {
+		Self::ALL[days.rem_euclid(7) as usize]
+	}
+
+	/// Returns how many days after Monday the day is, `0` for Monday.
+	pub fn days_from_monday(self) -> u32 {
+		self as u32
+	}
+
+	/// Returns the English name of the day, like `Monday`.
+	pub fn name(self) -> &'static str {
+		[
+			"Monday",
+			"Tuesday",
+			"Wednesday",
+			"Thursday",
+			"Friday",
+			"Saturday",
+			"Sunday",
+		][self as usize]
+	}
+
+	/// Returns the first three letters of the name of the day, like `Mon`.
+	pub fn short_name(self) -> &'static str {
+		&self.name()[..3]
+	}
+}
+
+/// A day of the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+	year: i32,
+	month: u32,
+	day: u32,
+}
+
+fn is_leap_year(year: i32) -> bool {
+	year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+impl Date {
+	/// Returns the date, or `None` if the month isn't between 1 and 12 or the day isn't in the month.
+	pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+		((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)).then_some(Self {
+			year,
+			month,
+			day,
+		})
+	}
+
+	/// Returns the date in the local time zone.
+	pub fn today() -> Self {
+		DateTime::now().date()
+	}
+
+	/// Returns the date `days` after the 1st of January 1970.
+	pub fn from_days(days: i64) -> Self {
+		// Counted from the 1st of March of year 0, so leap days end the years
+		let days = days + 719_468;
+		let era = days.div_euclid(146_097);
+		let day_of_era = days - era * 146_097;
+		let year_of_era =
+			(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+		let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+		let month_from_march = (5 * day_of_year + 2) / 153;
+		let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+		let month = if month_from_march < 10 {
+			month_from_march + 3
+		} else {
+			month_from_march - 9
+		};
+		let year = year_of_era + era * 400 + i64::from(month <= 2);
+		Self {
+			year: year as i32,
+			month: month as u32,
+			day: day as u32,
+		}
+	}
+
+	/// Returns how many days the date is after the 1st of January 1970, negative before it.
+	pub fn days(self) -> i64 {
+		let year = i64::from(self.year) - i64::from(self.month <= 2);
+		let era = year.div_euclid(400);
+		let year_of_era = year - era * 400;
+		let month_from_march = (i64::from(self.month) + 9) % 12;
+		let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(self.day) - 1;
+		let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+		era * 146_097 + day_of_era - 719_468
+	}
+
+	pub fn year(self) -> i32 {
+		self.year
+	}
+
+	/// Returns the month, from 1 for January to 12 for December.
+	pub fn month(self) -> u32 {
+		self.month
+	}
+
+	/// Returns the day of the month, starting at 1.
+	pub fn day(self) -> u32 {
+		self.day
+	}
+
+	pub fn weekday(self) -> Weekday {
+		// The 1st of January 1970 was a Thursday
+		Weekday::from_monday(self.days() + 3)
+	}
+
+	/// Returns the day of the year, from 1 for the 1st of January.
+	pub fn ordinal(self) -> u32 {
+		(self.days() - Self::new(self.year, 1, 1).unwrap().days()) as u32 + 1
+	}
+
+	pub fn days_in_month(self) -> u32 {
+		days_in_month(self.year, self.month)
+	}
+
+	/// Returns the year and the number of the week of the date, as in ISO 8601: weeks start on Monday and the
+	/// first week of a year is the one with its first Thursday, so the first days of January can be in the last
+	/// week of the year before.
+	pub fn iso_week(self) -> (i32, u32) {
+		let thursday = self.add_days(3 - i64::from(self.weekday().days_from_monday()));
+		(thursday.year, (thursday.ordinal() - 1) / 7 + 1)
+	}
+
+	pub fn add_days(self, days: i64) -> Self {
+		Self::from_days(self.days() + days)
+	}
+
+	/// Returns the same day `months` later, or the last day of that month if it is shorter, like the 28th of
+	/// February one month after the 31st of January.
+	pub fn add_months(self, months: i32) -> Self {
+		let month = i64::from(self.year) * 12 + i64::from(self.month) - 1 + i64::from(months);
+		let year = month.div_euclid(12) as i32;
+		let month = month.rem_euclid(12) as u32 + 1;
+		Self {
+			year,
+			month,
+			day: self.day.min(days_in_month(year, month)),
+		}
+	}
+
+	/// Returns the 1st of the month of the date.
+	pub fn first_of_month(self) -> Self {
+		Self { day: 1, ..self }
+	}
+
+	/// Returns the English name of the month, like `January`.
+	pub fn month_name(self) -> &'static str {
+		MONTHS[self.month as usize - 1]
+	}
+
+	/// Formats the date at midnight like [`DateTime::format`].
+	pub fn format(self, format: &str) -> String {
+		DateTime {
+			date: self,
+			seconds: 0,
+			utc_offset: 0,
+		}
+		.format(format)
+	}
+}
+
+/// A date and a time of the day, in a time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+	date: Date,
+	/// Seconds since midnight.
+	seconds: u32,
+	/// Seconds ahead of UTC.
+	utc_offset: i32,
+}
+
+impl DateTime {
+	/// Returns the current time in the local time zone.
+	pub fn now() -> Self {
+		Self::local(SystemTime::now())
+	}
+
+	/// Returns `time` in the local time zone.
+	pub fn local(time: SystemTime) -> Self {
+		let timestamp = match time.duration_since(UNIX_EPOCH) {
+			Ok(since) => since.as_secs() as i64,
+			Err(err) => -(err.duration().as_secs_f64().ceil() as i64),
+		};
+		Self::from_timestamp(timestamp, local_offset(timestamp))
+	}
+
+	/// Returns the time `timestamp` seconds after the 1st of January 1970 at midnight UTC, in the time zone
+	/// `utc_offset` seconds ahead of UTC.
+	pub fn from_timestamp(timestamp: i64, utc_offset: i32) -> Self {
+		let local = timestamp + i64::from(utc_offset);
+		Self {
+			date: Date::from_days(local.div_euclid(SECONDS_PER_DAY)),
+			seconds: local.rem_euclid(SECONDS_PER_DAY) as u32,
+			utc_offset,
+		}
+	}
+
+	/// Returns how many seconds the time is after the 1st of January 1970 at midnight UTC.
+	pub fn timestamp(self) -> i64 {
+		self.date.days() * SECONDS_PER_DAY + i64::from(self.seconds) - i64::from(self.utc_offset)
+	}
+
+	pub fn date(self) -> Date {
+		self.date
+	}
+
+	pub fn hour(self) -> u32 {
+		self.seconds / 3600
+	}
+
+	pub fn minute(self) -> u32 {
+		self.seconds / 60 % 60
+	}
+
+	pub fn second(self) -> u32 {
+		self.seconds % 60
+	}
+
+	/// Returns how many seconds the time zone is ahead of UTC, like `3600` for `+01:00`.
+	pub fn utc_offset(self) -> i32 {
+		self.utc_offset
+	}
+
+	/// Formats the time like `strftime` in C, with English names.
+	///
+	/// `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` and the other common conversions are supported, `%e` and `%k` pad with
+	/// a space and a `-` after the `%` drops the padding, like `%-d`. Conversions that aren't supported, like the
+	/// name of the time zone with `%Z`, are kept as they are.
+	///
+	/// ```rust
+	/// # use hyprui::DateTime;
+	/// let time = DateTime::from_timestamp(1_700_000_000, 3600);
+	/// assert_eq!(time.format("%a %-d %b, %H:%M"), "Tue 14 Nov, 23:13");
+	/// ```
+	pub fn format(self, format: &str) -> String {
+		let date = self.date;
+		let mut output = String::new();
+		let mut chars = format.chars();
+		while let Some(c) = chars.next() {
+			if c != '%' {
+				output.push(c);
+				continue;
+			}
+			let mut conversion = chars.next();
+			let padded = conversion != Some('-');
+			if !padded {
+				conversion = chars.next();
+			}
+			let mut number = |value: i64, width: usize, fill: char| {
+				let digits = value.abs().to_string();
+				if value < 0 {
+					output.push('-');
+				}
+				if padded {
+					for _ in digits.len()..width {
+						output.push(fill);
+					}
+				}
+				output.push_str(&digits);
+			};
+			let hour_12 = (self.hour() + 11) % 12 + 1;
+			match conversion {
+				Some('Y') => number(date.year.into(), 4, '0'),
+				Some('C') => number(i64::from(date.year).div_euclid(100), 2, '0'),
+				Some('y') => number(i64::from(date.year).rem_euclid(100), 2, '0'),
+				Some('G') => number(date.iso_week().0.into(), 4, '0'),
+				Some('g') => number(i64::from(date.iso_week().0).rem_euclid(100), 2, '0'),
+				Some('m') => number(date.month.into(), 2, '0'),
+				Some('d') => number(date.day.into(), 2, '0'),
+				Some('e') => number(date.day.into(), 2, ' '),
+				Some('j') => number(date.ordinal().into(), 3, '0'),
+				Some('V') => number(date.iso_week().1.into(), 2, '0'),
+				Some('u') => number(i64::from(date.weekday().days_from_monday()) + 1, 1, '0'),
+				Some('w') => number(
+					(i64::from(date.weekday().days_from_monday()) + 1) % 7,
+					1,
+					'0',
+				),
+				Some('H') => number(self.hour().into(), 2, '0'),
+				Some('k') => number(self.hour().into(), 2, ' '),
+				Some('I') => number(hour_12.into(), 2, '0'),
+				Some('l') => number(hour_12.into(), 2, ' '),
+				Some('M') => number(self.minute().into(), 2, '0'),
+				Some('S') => number(self.second().into(), 2, '0'),
+				Some('s') => number(self.timestamp(), 1, '0'),
+				Some('a') => output.push_str(date.weekday().short_name()),
+				Some('A') => output.push_str(date.weekday().name()),
+				Some('b' | 'h') => output.push_str(&date.month_name()[..3]),
+				Some('B') => output.push_str(date.month_name()),
+				Some('p') => output.push_str(if self.hour() < 12 { "AM" } else { "PM" }),
+				Some('P') => output.push_str(if self.hour() < 12 { "am" } else { "pm" }),
+				Some('z') => {
+					let offset = self.utc_offset.unsigned_abs() / 60;
+					let sign = if self.utc_offset < 0 { '-' } else { '+' };
+					let _ = write!(output, "{sign}{:02}{:02}", offset / 60, offset % 60);
+				}
+				Some('c') => output.push_str(&self.format("%a %b %e %H:%M:%S %Y")),
+				Some('D') => output.push_str(&self.format("%m/%d/%y")),
+				Some('F') => output.push_str(&self.format("%Y-%m-%d")),
+				Some('R') => output.push_str(&self.format("%H:%M")),
+				Some('T') => output.push_str(&self.format("%H:%M:%S")),
+				Some('r') => output.push_str(&self.format("%I:%M:%S %p")),
+				Some('n') => output.push('\n'),
+				Some('t') => output.push('\t'),
+				Some('%') => output.push('%'),
+				Some(other) => {
+					output.push('%');
+					if !padded {
+						output.push('-');
+					}
+					output.push(other);
+				}
+				None => output.push('%'),
+			}
+		}
+		output
+	}
+}
+
+/// The local time zone, as last read by [`local_offset`].
+struct LocalZone {
+	tz: Option<String>,
+	/// The file the zone was read from, with its links followed, and when it was last modified.
+	file: Option<(PathBuf, SystemTime)>,
+	data: Option<Vec<u8>>,
+}
+
+static LOCAL_ZONE: Mutex<Option<LocalZone>> = Mutex::new(None);
+
+/// Returns how many seconds the local time zone is ahead of UTC at `timestamp`, from the `TZ` environment
+/// variable or `/etc/localtime`. UTC is used if neither can be read.
+///
+/// The zone file is read again only when `TZ` changes, or when the file it points to changes, like when
+/// `timedatectl set-timezone` links `/etc/localtime` to another zone.
+fn local_offset(timestamp: i64) -> i32 {
+	let tz = std::env::var("TZ").ok();
+	let path = match tz.as_deref() {
+		None => PathBuf::from("/etc/localtime"),
+		// `TZ` names a file of the tz database, like `Europe/Lisbon`, or holds a rule, like `CET-1CEST,M3.5.0,M10.5.0/3`
+		Some(tz) => {
+			let name = tz.strip_prefix(':').unwrap_or(tz);
+			if name.starts_with('/') {
+				PathBuf::from(name)
+			} else {
+				std::env::var_os("TZDIR")
+					.map_or_else(|| PathBuf::from("/usr/share/zoneinfo"), PathBuf::from)
+					.join(name)
+			}
+		}
+	};
+	let file = std::fs::canonicalize(&path).ok().and_then(|path| {
+		let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+		Some((path, modified))
+	});
+
+	let mut zone = LOCAL_ZONE.lock().unwrap();
+	if zone
+		.as_ref()
+		.is_none_or(|zone| zone.tz != tz || zone.file != file)
+	{
+		*zone = Some(LocalZone {
+			data: std::fs::read(&path).ok(),
+			tz,
+			file,
+		});
+	}
+	let zone = zone.as_ref().unwrap();
+	zone
+		.data
+		.as_deref()
+		.and_then(|data| tzif_offset(data, timestamp))
+		.or_else(|| posix_offset(zone.tz.as_deref()?, timestamp))
+		.unwrap_or(0)
+}
+
+/// Returns the offset from UTC at `timestamp` of a time zone file of the tz database, see RFC 8536.
+fn tzif_offset(data: &[u8], timestamp: i64) -> Option<i32> {
+	// The numbers of UT indicators, standard indicators, leap seconds, transitions, local time types and
+	// characters of abbreviations
+	let read_counts = |data: &[u8]| -> Option<[usize; 6]> {
+		if !data.starts_with(b"TZif") {
+			return None;
+		}
+		let mut counts = [0; 6];
+		for (index, count) in counts.iter_mut().enumerate() {
+			let bytes = data.get(20 + index * 4..24 + index * 4)?;
+			*count = u32::from_be_bytes(bytes.try_into().ok()?) as usize;
+		}
+		Some(counts)
+	};
+	let size = |[ut, standard, leaps, transitions, types, characters]: [usize; 6],
+	            time_size: usize| {
+		44 + transitions * (time_size + 1)
+			+ types * 6
+			+ characters
+			+ leaps * (time_size + 4)
+			+ standard
+			+ ut
+	};
+	let version = *data.get(4)?;
+	let mut counts = read_counts(data)?;
+	// Version 2 and later repeat the data with 64 bits times, followed by a rule for the times after it
+	let (data, time_size) = if version >= b'2' {
+		let data = data.get(size(counts, 4)..)?;
+		counts = read_counts(data)?;
+		(data, 8)
+	} else {
+		(data, 4)
+	};
+	let [_, _, _, transitions, types, _] = counts;
+	let times = data.get(44..44 + transitions * time_size)?;
+	let indices = data.get(44 + transitions * time_size..44 + transitions * (time_size + 1))?;
+	let infos_start = 44 + transitions * (time_size + 1);
+	let infos = data.get(infos_start..infos_start + types * 6)?;
+	let time = |index: usize| {
+		let bytes = &times[index * time_size..(index + 1) * time_size];
+		match time_size {
+			8 => i64::from_be_bytes(bytes.try_into().unwrap()),
+			_ => i32::from_be_bytes(bytes.try_into().unwrap()).into(),
+		}
+	};
+	let offset = |kind: usize| {
+		let bytes = infos.get(kind * 6..kind * 6 + 4)?;
+		Some(i32::from_be_bytes(bytes.try_into().ok()?))
+	};
+	let passed = (0..transitions)
+		.take_while(|&index| time(index) <= timestamp)
+		.count();
+	if passed == transitions && time_size == 8 {
+		let footer = data.get(size(counts, 8)..).unwrap_or_default();
+		let rule = std::str::from_utf8(footer).ok()?.trim_matches('\n');
+		if let Some(offset) = posix_offset(rule, timestamp) {
+			return Some(offset);
+		}
+	}
+	match passed {
+		0 => offset(0),
+		passed => offset(*indices.get(passed - 1)? as usize),
+	}
+}
+
+/// Reads the name of a time zone at the start of `rule`, like `CET` or `<+03>`.
+fn take_name(rule: &mut &str) -> Option<()> {
+	let length = if let Some(quoted) = rule.strip_prefix('<') {
+		quoted.find('>')? + 2
+	} else {
+		rule
+			.find(|c: char| !c.is_ascii_alphabetic())
+			.unwrap_or(rule.len())
+	};
+	(length >= 3).then(|| *rule = &rule[length..])
+}
+
+/// Reads a time like `-1`, `5:30` or `2:00:00` at the start of `rule`, in seconds.
+fn take_time(rule: &mut &str) -> Option<i64> {
+	let sign = if let Some(rest) = rule.strip_prefix('-') {
+		*rule = rest;
+		-1
+	} else {
+		*rule = rule.strip_prefix('+').unwrap_or(rule);
+		1
+	};
+	let length = rule
+		.find(|c: char| !c.is_ascii_digit() && c != ':')
+		.unwrap_or(rule.len());
+	let mut seconds = 0;
+	for (part, unit) in rule[..length].split(':').zip([3600, 60, 1]) {
+		seconds += part.parse::<i64>().ok()? * unit;
+	}
+	*rule = &rule[length..];
+	Some(sign * seconds)
+}
+
+/// Returns the time of year `year` a daylight saving time rule like `M3.5.0/3` switches at, in seconds since
+/// 1970 in the local time it switches from.
+fn switch_time(rule: &str, year: i32) -> Option<i64> {
+	let (day, time) = rule.split_once('/').unwrap_or((rule, "2"));
+	let time = take_time(&mut { time })?;
+	let date = if let Some(day) = day.strip_prefix('M') {
+		// The day of the week of the nth week of a month, the 5th being the last one
+		let mut parts = day.split('.').map(|part| part.parse::<u32>().ok());
+		let (month, week, weekday) = (parts.next()??, parts.next()??, parts.next()??);
+		let first = Date::new(year, month, 1)?;
+		let sunday_based = (first.weekday().days_from_monday() + 1) % 7;
+		let mut day = 1 + (weekday + 7 - sunday_based) % 7 + (week.clamp(1, 5) - 1) * 7;
+		while day > first.days_in_month() {
+			day -= 7;
+		}
+		Date::new(year, month, day)?
+	} else if let Some(day) = day.strip_prefix('J') {
+		// From 1 to 365, leap days not counted
+		let day = day.parse::<i64>().ok()?;
+		let leap_day = i64::from(is_leap_year(year) && day >= 60);
+		Date::new(year, 1, 1)?.add_days(day - 1 + leap_day)
+	} else {
+		Date::new(year, 1, 1)?.add_days(day.parse().ok()?)
+	};
+	Some(date.days() * SECONDS_PER_DAY + time)
+}
+
+/// Returns the offset from UTC at `timestamp` of a time zone described as in the `TZ` environment variable of
+/// POSIX, like `CET-1CEST,M3.5.0,M10.5.0/3`.
+fn posix_offset(rule: &str, timestamp: i64) -> Option<i32> {
+	let mut rule = rule;
+	take_name(&mut rule)?;
+	// The offsets of the rule are behind UTC
+	let standard = -take_time(&mut rule)?;
+	if rule.is_empty() {
+		return Some(standard as i32);
+	}
+	take_name(&mut rule)?;
+	let daylight = if rule.is_empty() || rule.starts_with(',') {
+		standard + 3600
+	} else {
+		-take_time(&mut rule)?
+	};
+	let Some((start, end)) = rule.strip_prefix(',').and_then(|rule| rule.split_once(',')) else {
+		return Some(standard as i32);
+	};
+	let year = DateTime::from_timestamp(timestamp, standard as i32)
+		.date
+		.year;
+	let start = switch_time(start, year)? - standard;
+	let end = switch_time(end, year)? - daylight;
+	// Daylight saving time spans the new year in the southern hemisphere
+	let daylight_saving = if start < end {
+		(start..end).contains(&timestamp)
+	} else {
+		!(end..start).contains(&timestamp)
+	};
+	Some(if daylight_saving { daylight } else { standard } as i32)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_dates_are_counted_from_1970() {
+		let date = |year, month, day| Date::new(year, month, day).unwrap();
+		assert_eq!(Date::from_days(0), date(1970, 1, 1));
+		assert_eq!(date(2024, 2, 29).days(), 19_782);
+		assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+		for days in -800_000..800_000 {
+			assert_eq!(Date::from_days(days).days(), days);
+		}
+		assert_eq!(Date::new(2023, 2, 29), None);
+		assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+		assert_eq!(date(2024, 1, 15).add_months(-1), date(2023, 12, 15));
+		assert_eq!(date(2024, 7, 4).weekday(), Weekday::Thursday);
+	}
+
+	#[test]
+	fn test_iso_weeks() {
+		let week = |year, month, day| Date::new(year, month, day).unwrap().iso_week();
+		assert_eq!(week(2021, 1, 3), (2020, 53));
+		assert_eq!(week(2021, 1, 4), (2021, 1));
+		assert_eq!(week(2024, 12, 30), (2025, 1));
+		assert_eq!(week(2026, 10, 16), (2026, 42));
+	}
+
+	#[test]
+	fn test_times_are_formatted() {
+		let time = DateTime::from_timestamp(1_700_000_000, -5 * 3600);
+		assert_eq!(
+			time.format("%Y-%m-%d %H:%M:%S %z"),
+			"2023-11-14 17:13:20 -0500"
+		);
+		assert_eq!(
+			time.format("%A %e %B, %-I:%M %p"),
+			"Tuesday 14 November, 5:13 PM"
+		);
+		assert_eq!(
+			time.format("%F %T, week %V, %j"),
+			"2023-11-14 17:13:20, week 46, 318"
+		);
+		assert_eq!(time.format("100%% %Q"), "100% %Q");
+		assert_eq!(time.timestamp(), 1_700_000_000);
+	}
+
+	#[test]
+	fn test_daylight_saving_time_rules() {
+		let berlin = "CET-1CEST,M3.5.0,M10.5.0/3";
+		// The 31st of March 2024 at 00:59:59 and 01:00:00 UTC
+		assert_eq!(posix_offset(berlin, 1_711_846_799), Some(3600));
+		assert_eq!(posix_offset(berlin, 1_711_846_800), Some(7200));
+		// The 27th of October 2024 at 00:59:59 and 01:00:00 UTC
+		assert_eq!(posix_offset(berlin, 1_729_990_799), Some(7200));
+		assert_eq!(posix_offset(berlin, 1_729_990_800), Some(3600));
+		// The 1st of January 2024, in summer
+		assert_eq!(
+			posix_offset("AEST-10AEDT,M10.1.0,M4.1.0/3", 1_704_067_200),
+			Some(39_600)
+		);
+		assert_eq!(posix_offset("<+0530>-5:30", 0), Some(19_800));
+		assert_eq!(posix_offset("UTC0", 0), Some(0));
+	}
+}
@@ -0,0 +1,27 @@
+use std::sync::OnceLock;
+
+use zbus::blocking::Connection;
+
+static SESSION_BUS: OnceLock<Connection> = OnceLock::new();
+static SYSTEM_BUS: OnceLock<Connection> = OnceLock::new();
+
+fn shared(
+	bus: &OnceLock<Connection>,
+	connect: fn() -> zbus::Result<Connection>,
+) -> zbus::Result<Connection> {
+	if let Some(connection) = bus.get() {
+		return Ok(connection.clone());
+	}
+	let connection = connect()?;
+	Ok(bus.get_or_init(|| connection).clone())
+}
+
+/// Returns the connection to the session bus shared by every D-Bus integration, connecting on first use.
+pub(crate) fn session_bus() -> zbus::Result<Connection> {
+	shared(&SESSION_BUS, Connection::session)
+}
+
+/// Returns the shared connection to the system bus, connecting on first use.
+pub(crate) fn system_bus() -> zbus::Result<Connection> {
+	shared(&SYSTEM_BUS, Connection::system)
+}
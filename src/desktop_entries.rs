@@ -0,0 +1,494 @@
+//! The applications installed on the system, read from their XDG desktop entries (`.desktop` files), like the
+//! menu of a launcher lists them.
+//!
+//! Entries are read from the `applications` directory of `$XDG_DATA_HOME` and of every directory of
+//! `$XDG_DATA_DIRS`, following the [Desktop Entry Specification]: an entry of the user hides the one of the
+//! system with the same id, names are translated in the language of the user, and the field codes of the
+//! command are filled in when launching.
+//!
+//! [Desktop Entry Specification]: https://specifications.freedesktop.org/desktop-entry-spec/latest/
+use std::{
+	collections::{HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, Ordering},
+	},
+};
+
+/// An application installed on the system, see [`use_desktop_entries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopEntry {
+	/// The desktop file id, like `org.mozilla.firefox.desktop`, which identifies the application.
+	pub id: String,
+	/// The file the entry was read from.
+	pub path: PathBuf,
+	/// The name of the application, in the language of the user if translated.
+	pub name: String,
+	/// What kind of application it is, like `Web Browser`.
+	pub generic_name: Option<String>,
+	/// A tooltip describing the application, like `Browse the Web`.
+	pub comment: Option<String>,
+	/// The name of the icon in the icon theme, or the path of an image.
+	pub icon: Option<String>,
+	/// The command line starting the application, with its field codes, see [`command`](Self::command).
+	pub exec: Option<String>,
+	/// More words a launcher can match a search against, in the language of the user if translated.
+	pub keywords: Vec<String>,
+	/// The menu categories of the application, like `Network` or `WebBrowser`.
+	pub categories: Vec<String>,
+	/// Whether the application runs in a terminal.
+	pub terminal: bool,
+	/// Whether the application shouldn't be shown in menus, because the entry asks not to be or because it is
+	/// only meant for other desktops than `$XDG_CURRENT_DESKTOP`. Such entries still open files of their types.
+	pub no_display: bool,
+}
+
+impl DesktopEntry {
+	/// Returns the command line starting the application with `files`, the program first, or `None` if the
+	/// entry has no command or it is malformed.
+	///
+	/// The field codes are filled in: `%f` and `%u` with the first file, `%F` and `%U` with all of them, `%i`
+	/// with the icon, `%c` with the name and `%k` with the path of the entry.
+	pub fn command(&self, files: &[&str]) -> Option<Vec<String>> {
+		let mut command = Vec::new();
+		for arg in split_exec(self.exec.as_deref()?)? {
+			match arg.as_str() {
+				"%f" | "%u" => command.extend(files.first().map(|file| file.to_string())),
+				"%F" | "%U" => command.extend(files.iter().map(|file| file.to_string())),
+				"%i" => {
+					if let Some(icon) = &self.icon {
+						command.extend(["--icon".to_string(), icon.clone()]);
+					}
+				}
+				_ => command.push(self.expand_field_codes(&arg, files)),
+			}
+		}
+		(!command.is_empty()).then_some(command)
+	}
+
+	/// Replaces the field codes inside `arg`, dropping the deprecated ones.
+	fn expand_field_codes(&self, arg: &str, files: &[&str]) -> String {
+		let mut expanded = String::new();
+		let mut chars = arg.chars();
+		while let Some(c) = chars.next() {
+			if c != '%' {
+				expanded.push(c);
+				continue;
+			}
+			match chars.next() {
+				Some('%') => expanded.push('%'),
+				Some('f' | 'u' | 'F' | 'U') => {
+					expanded.push_str(files.first().copied().unwrap_or_default())
+				}
+				Some('c') => expanded.push_str(&self.name),
+				Some('k') => expanded.push_str(&self.path.to_string_lossy()),
+				_ => {}
+			}
+		}
+		expanded
+	}
+
+	/// Starts the application, opening `files` with it if any.
+	///
+	/// Terminal applications are started in `$TERMINAL`, or `xterm` if it isn't set. Like
+	/// [`open_url`](crate::open_url), the application is handed an activation token so it gets focused, and errors
+	/// are logged.
+	pub fn launch(&self, files: &[&str]) {
+		let Some(mut command) = self.command(files) else {
+			log::error!("{} has no command to launch", self.id);
+			return;
+		};
+		if self.terminal {
+			let terminal = std::env::var("TERMINAL").unwrap_or_else(|_| "xterm".to_string());
+			command.splice(0..0, [terminal, "-e".to_string()]);
+		}
+		crate::open::spawn(command);
+	}
+}
+
+/// Splits the command line of an entry into its arguments, removing the quotes, or `None` if a quote isn't closed.
+fn split_exec(exec: &str) -> Option<Vec<String>> {
+	let mut args = Vec::new();
+	let mut arg = None::<String>;
+	let mut chars = exec.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' => {
+				let arg = arg.get_or_insert_default();
+				loop {
+					match chars.next()? {
+						'"' => break,
+						'\\' => arg.push(chars.next()?),
+						c => arg.push(c),
+					}
+				}
+			}
+			' ' | '\t' => args.extend(arg.take()),
+			c => arg.get_or_insert_default().push(c),
+		}
+	}
+	args.extend(arg);
+	Some(args)
+}
+
+/// Replaces the escape sequences of a string value, like `\s` for a space.
+fn unescape(value: &str) -> String {
+	let mut unescaped = String::with_capacity(value.len());
+	let mut chars = value.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			unescaped.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('s') => unescaped.push(' '),
+			Some('n') => unescaped.push('\n'),
+			Some('t') => unescaped.push('\t'),
+			Some('r') => unescaped.push('\r'),
+			Some(c) => unescaped.push(c),
+			None => unescaped.push('\\'),
+		}
+	}
+	unescaped
+}
+
+/// Splits a list value, whose items end with `;`.
+fn split_list(value: &str) -> Vec<String> {
+	let mut items = Vec::new();
+	let mut item = String::new();
+	let mut chars = value.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			';' => items.push(unescape(&std::mem::take(&mut item))),
+			// An escaped `;` is part of the item
+			'\\' => {
+				item.push(c);
+				item.extend(chars.next());
+			}
+			c => item.push(c),
+		}
+	}
+	items.push(unescape(&item));
+	items.retain(|item| !item.is_empty());
+	items
+}
+
+/// Returns the locale suffixes to look translated keys up with, best first, like `pt_BR`, then `pt`, for the
+/// locale `pt_BR.UTF-8`.
+fn locale_keys(locale: &str) -> Vec<String> {
+	let (locale, modifier) = match locale.split_once('@') {
+		Some((locale, modifier)) => (locale, Some(modifier)),
+		None => (locale, None),
+	};
+	let locale = locale.split('.').next().unwrap_or_default();
+	let (lang, country) = match locale.split_once('_') {
+		Some((lang, country)) => (lang, Some(country)),
+		None => (locale, None),
+	};
+	if lang.is_empty() || lang == "C" || lang == "POSIX" {
+		return Vec::new();
+	}
+	let mut keys = Vec::new();
+	if let (Some(country), Some(modifier)) = (country, modifier) {
+		keys.push(format!("{lang}_{country}@{modifier}"));
+	}
+	if let Some(country) = country {
+		keys.push(format!("{lang}_{country}"));
+	}
+	if let Some(modifier) = modifier {
+		keys.push(format!("{lang}@{modifier}"));
+	}
+	keys.push(lang.to_string());
+	keys
+}
+
+/// Parses the entry `id` of the application in `text`, or returns `None` if it isn't an application shown on
+/// this system. `desktops` are the names of the current desktop, like `GNOME`.
+fn parse(
+	id: String,
+	path: PathBuf,
+	text: &str,
+	locales: &[String],
+	desktops: &[String],
+) -> Option<DesktopEntry> {
+	let mut values = HashMap::new();
+	let mut group = "";
+	for line in text.lines() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		if let Some(name) = line
+			.strip_prefix('[')
+			.and_then(|line| line.strip_suffix(']'))
+		{
+			group = name;
+			continue;
+		}
+		if group != "Desktop Entry" {
+			continue;
+		}
+		if let Some((key, value)) = line.split_once('=') {
+			values.entry(key.trim()).or_insert(value.trim());
+		}
+	}
+	let raw = |key: &str| values.get(key).copied();
+	let localized = |key: &str| {
+		locales
+			.iter()
+			.find_map(|locale| raw(&format!("{key}[{locale}]")))
+			.or_else(|| raw(key))
+	};
+	let flag = |key: &str| raw(key) == Some("true");
+	if raw("Type") != Some("Application") || flag("Hidden") {
+		return None;
+	}
+	if let Some(program) = raw("TryExec").map(unescape)
+		&& !is_installed(&program)
+	{
+		return None;
+	}
+	let shown_in = |key: &str| {
+		raw(key).map(|list| {
+			split_list(list)
+				.iter()
+				.any(|desktop| desktops.contains(desktop))
+		})
+	};
+	let hidden_here = shown_in("OnlyShowIn") == Some(false) || shown_in("NotShowIn") == Some(true);
+	Some(DesktopEntry {
+		name: localized("Name").map(unescape)?,
+		generic_name: localized("GenericName").map(unescape),
+		comment: localized("Comment").map(unescape),
+		icon: raw("Icon").map(unescape),
+		exec: raw("Exec").map(unescape),
+		keywords: localized("Keywords").map(split_list).unwrap_or_default(),
+		categories: raw("Categories").map(split_list).unwrap_or_default(),
+		terminal: flag("Terminal"),
+		no_display: flag("NoDisplay") || hidden_here,
+		id,
+		path,
+	})
+}
+
+/// Returns `true` if `program` is an executable path, or is found in `$PATH`.
+fn is_installed(program: &str) -> bool {
+	use std::os::unix::fs::PermissionsExt;
+	let is_executable = |path: &Path| {
+		path
+			.metadata()
+			.is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+	};
+	if program.contains('/') {
+		return is_executable(Path::new(program));
+	}
+	std::env::var_os("PATH").is_some_and(|paths| {
+		std::env::split_paths(&paths).any(|directory| is_executable(&directory.join(program)))
+	})
+}
+
+/// Returns the `applications` directories, the ones of the user first.
+fn application_directories() -> Vec<PathBuf> {
+	let home = std::env::var_os("XDG_DATA_HOME")
+		.filter(|path| !path.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| std::env::home_dir().map(|home| home.join(".local/share")));
+	let system = std::env::var("XDG_DATA_DIRS")
+		.ok()
+		.filter(|paths| !paths.is_empty())
+		.unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+	home
+		.into_iter()
+		.chain(system.split(':').map(PathBuf::from))
+		.map(|directory| directory.join("applications"))
+		.collect()
+}
+
+/// Adds the paths of the `.desktop` files in `directory` and its subdirectories to `files`, with their ids.
+fn find_entries(directory: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+	let Ok(dir) = std::fs::read_dir(directory) else {
+		return;
+	};
+	for entry in dir.flatten() {
+		let path = entry.path();
+		let name = entry.file_name().to_string_lossy().into_owned();
+		if path.is_dir() {
+			find_entries(&path, &format!("{prefix}{name}-"), files);
+		} else if name.ends_with(".desktop") {
+			files.push((format!("{prefix}{name}"), path));
+		}
+	}
+}
+
+/// Reads the entries of every application directory, sorted by name.
+fn scan() -> Vec<DesktopEntry> {
+	let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+		.into_iter()
+		.find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+		.unwrap_or_default();
+	let locales = locale_keys(&locale);
+	let desktops = std::env::var("XDG_CURRENT_DESKTOP")
+		.unwrap_or_default()
+		.split(':')
+		.map(str::to_string)
+		.collect::<Vec<_>>();
+	let mut seen = HashSet::new();
+	let mut entries = Vec::new();
+	for directory in application_directories() {
+		let mut files = Vec::new();
+		find_entries(&directory, "", &mut files);
+		for (id, path) in files {
+			// The first entry with an id hides the others, even if it is hidden itself
+			if !seen.insert(id.clone()) {
+				continue;
+			}
+			match std::fs::read_to_string(&path) {
+				Ok(text) => entries.extend(parse(id, path, &text, &locales, &desktops)),
+				Err(err) => log::warn!("can't read {}: {err}", path.display()),
+			}
+		}
+	}
+	entries.sort_by_cached_key(|entry| entry.name.to_lowercase());
+	entries
+}
+
+static ENTRIES: Mutex<Option<Arc<Vec<DesktopEntry>>>> = Mutex::new(None);
+static SCANNING: AtomicBool = AtomicBool::new(false);
+
+/// Reads the entries in a background thread, unless they are being read already.
+fn start_scan() {
+	if SCANNING.swap(true, Ordering::Relaxed) {
+		return;
+	}
+	std::thread::spawn(|| {
+		let entries = scan();
+		*ENTRIES.lock().unwrap() = Some(Arc::new(entries));
+		SCANNING.store(false, Ordering::Relaxed);
+		crate::winit::wake_up();
+	});
+}
+
+/// Returns the applications installed on the system, sorted by name.
+///
+/// The entries are read in the background the first time, returning an empty list until they are, and then
+/// kept for the whole app. Call [`reload_desktop_entries`] to read them again, like when a launcher is opened.
+/// Entries with [`no_display`](DesktopEntry::no_display) set are included, launchers usually leave them out.
+///
+/// ```rust,ignore
+/// let (query, set_query) = use_state(String::new());
+/// let apps = use_desktop_entries()
+///     .iter()
+///     .filter(|app| !app.no_display)
+///     .cloned()
+///     .collect::<Vec<_>>();
+/// let names = apps.iter().map(|app| app.name.clone()).collect::<Vec<_>>();
+/// FuzzyList::new(&names, &query, move |index| apps[index].launch(&[]))
+/// ```
+pub fn use_desktop_entries() -> Arc<Vec<DesktopEntry>> {
+	let entries = ENTRIES.lock().unwrap().clone();
+	entries.unwrap_or_else(|| {
+		start_scan();
+		Arc::default()
+	})
+}
+
+/// Reads the desktop entries again in the background, so newly installed applications show up in
+/// [`use_desktop_entries`]. The current ones are kept until then.
+pub fn reload_desktop_entries() {
+	start_scan();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FIREFOX: &str = r#"[Desktop Entry]
+Type=Application
+Name=Firefox
+Name[fr]=Firefox
+GenericName=Web Browser
+GenericName[fr]=Navigateur Web
+Keywords=Internet;WWW;Browser;
+Keywords[fr]=Internet;Navigateur;
+Icon=firefox
+Exec=firefox %u
+Categories=Network;WebBrowser;
+
+[Desktop Action new-window]
+Name=New Window
+Exec=firefox --new-window %u
+"#;
+
+	fn firefox(locale: &str) -> DesktopEntry {
+		parse(
+			"firefox.desktop".to_string(),
+			PathBuf::from("/usr/share/applications/firefox.desktop"),
+			FIREFOX,
+			&locale_keys(locale),
+			&["GNOME".to_string()],
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn test_entries_are_translated() {
+		let entry = firefox("fr_FR.UTF-8");
+		assert_eq!(entry.name, "Firefox");
+		assert_eq!(entry.generic_name.as_deref(), Some("Navigateur Web"));
+		assert_eq!(entry.keywords, vec!["Internet", "Navigateur"]);
+		assert_eq!(entry.categories, vec!["Network", "WebBrowser"]);
+		// Actions don't replace the values of the entry
+		assert_eq!(entry.exec.as_deref(), Some("firefox %u"));
+		assert_eq!(firefox("C").generic_name.as_deref(), Some("Web Browser"));
+		assert_eq!(
+			locale_keys("sr_RS.UTF-8@latin"),
+			vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]
+		);
+	}
+
+	#[test]
+	fn test_field_codes_are_filled_in() {
+		let mut entry = firefox("C");
+		assert_eq!(
+			entry.command(&["https://a.org", "https://b.org"]).unwrap(),
+			vec!["firefox", "https://a.org"]
+		);
+		assert_eq!(entry.command(&[]).unwrap(), vec!["firefox"]);
+		entry.exec = Some(r#""/opt/My App/run" --name="%c" %F %i 100%%"#.to_string());
+		assert_eq!(
+			entry.command(&["a.txt", "b.txt"]).unwrap(),
+			vec![
+				"/opt/My App/run",
+				"--name=Firefox",
+				"a.txt",
+				"b.txt",
+				"--icon",
+				"firefox",
+				"100%"
+			]
+		);
+		entry.exec = Some(r#"sh -c "echo \"hi"#.to_string());
+		assert_eq!(entry.command(&[]), None);
+	}
+
+	#[test]
+	fn test_entries_for_other_desktops_are_not_displayed() {
+		let parse = |extra: &str| {
+			let text = format!("[Desktop Entry]\nType=Application\nName=Files\n{extra}");
+			parse(
+				"files.desktop".to_string(),
+				PathBuf::new(),
+				&text,
+				&[],
+				&["GNOME".to_string()],
+			)
+		};
+		assert!(!parse("").unwrap().no_display);
+		assert!(parse("OnlyShowIn=KDE;").unwrap().no_display);
+		assert!(!parse("OnlyShowIn=KDE;GNOME;").unwrap().no_display);
+		assert!(parse("NotShowIn=GNOME;").unwrap().no_display);
+		assert!(parse("Hidden=true").is_none());
+		assert!(parse("TryExec=surely-not-an-installed-program").is_none());
+	}
+}
@@ -0,0 +1,75 @@
+use std::{
+	fmt,
+	sync::{Arc, Mutex},
+};
+
+type Task = Box<dyn FnOnce() + Send>;
+type Queue = Arc<Mutex<Vec<Task>>>;
+
+thread_local! {
+	/// Tasks posted to the event loop of this thread, run at the start of its next frame.
+	static TASKS: Queue = Queue::default();
+}
+
+/// Posts work onto the UI thread from any thread.
+///
+/// Hook setters and most of hyprui only live on the UI thread, so background threads hand them closures through
+/// a dispatcher instead. Posting wakes the event loop up, and the closures run before the next frame is built.
+///
+/// A dispatcher posts to the event loop of the thread it was created on, so it is created on the UI thread and
+/// then moved to the background thread.
+///
+/// ```rust,no_run
+/// # use hyprui::{Dispatcher, use_state_sender};
+/// let (status, send_status) = use_state_sender(String::from("Loading…"));
+/// let dispatcher = Dispatcher::new();
+/// std::thread::spawn(move || {
+///     let result = String::from("Done");
+///     send_status.send(result);
+///     dispatcher.dispatch(|| println!("running on the UI thread"));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Dispatcher {
+	tasks: Queue,
+}
+
+impl Dispatcher {
+	/// Creates a dispatcher posting to the event loop of the current thread, which must be the UI thread.
+	pub fn new() -> Self {
+		Self {
+			tasks: TASKS.with(Arc::clone),
+		}
+	}
+
+	/// Runs `task` on the UI thread before the next frame.
+	pub fn dispatch(&self, task: impl FnOnce() + Send + 'static) {
+		self.tasks.lock().unwrap().push(Box::new(task));
+		crate::winit::wake_up();
+	}
+
+	/// Schedules a redraw of the window.
+	pub fn request_redraw(&self) {
+		crate::winit::wake_up();
+	}
+}
+
+impl Default for Dispatcher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl fmt::Debug for Dispatcher {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Dispatcher").finish_non_exhaustive()
+	}
+}
+
+/// Runs the tasks posted to the event loop of the current thread since the last frame, in order.
+pub(crate) fn run_pending_tasks() {
+	let tasks = TASKS.with(|tasks| std::mem::take(&mut *tasks.lock().unwrap()));
+	for task in tasks {
+		task();
+	}
+}
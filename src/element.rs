@@ -1,6 +1,28 @@
+pub mod avatar;
+pub mod breadcrumbs;
+pub mod calendar;
+pub mod caret;
+pub mod clock;
 pub mod component;
 pub mod container;
+pub mod context_menu;
+pub mod custom_draw;
+pub mod dock;
+pub mod form;
+pub mod fuzzy_list;
+pub mod image;
+pub mod pagination;
+pub mod portal;
+pub mod rich_text;
+pub mod router;
+pub mod screen_capture;
+pub mod svg;
+pub mod taskbar;
 pub mod text;
+pub mod text_input;
+#[cfg(feature = "dbus")]
+pub mod tray_host;
+pub mod window;
 use std::collections::HashSet;
 
 use uuid::Uuid;
@@ -0,0 +1,148 @@
+//! The picture of a person, like the one of the user on a lock screen, showing their initials until it is loaded.
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use clay_layout::{Color, layout::Sizing};
+
+use crate::{Container, Element, Image, ImageLoadState, ImageSource, RenderContext, Role, Text};
+
+/// The backgrounds of the initials, picked from the name so a person always gets the same one.
+const COLORS: [(u8, u8, u8); 8] = [
+	(53, 132, 228),
+	(51, 209, 122),
+	(230, 97, 0),
+	(192, 28, 40),
+	(145, 65, 172),
+	(38, 162, 105),
+	(229, 165, 10),
+	(99, 69, 44),
+];
+
+/// Returns the initials of the first and last words of `name`, like `AL` for `Ada King Lovelace`.
+fn initials(name: &str) -> String {
+	let mut words = name
+		.split_whitespace()
+		.filter_map(|word| word.chars().next());
+	let first = words.next();
+	let last = words.last();
+	first
+		.into_iter()
+		.chain(last)
+		.flat_map(char::to_uppercase)
+		.collect()
+}
+
+fn color_of(name: &str) -> Color {
+	let mut hasher = DefaultHasher::new();
+	name.hash(&mut hasher);
+	let (r, g, b) = COLORS[hasher.finish() as usize % COLORS.len()];
+	Color::rgba(r.into(), g.into(), b.into(), 255.)
+}
+
+/// A round picture of a person, showing their initials on a colored background while the picture is loading,
+/// if it failed to load or if there is none.
+///
+/// The avatar is announced to screen readers with the name of the person.
+///
+/// ```rust,ignore
+/// Avatar::new(&user.full_name)
+///     .src(ImageSource::url(&user.avatar_url))
+///     .size(64.)
+/// ```
+pub struct Avatar {
+	name: String,
+	image: Option<skia_safe::Image>,
+	size: f32,
+	color: Color,
+	container: Container,
+}
+
+impl Avatar {
+	/// Creates a 40 pixels wide avatar of the person called `name`, without a picture.
+	pub fn new(name: impl Into<String>) -> Self {
+		let name = name.into();
+		let color = color_of(&name);
+		let container = Container::new()
+			.role(Role::Image)
+			.label(name.clone())
+			.center();
+		Self {
+			name,
+			image: None,
+			size: 40.,
+			color,
+			container,
+		}
+		.update()
+	}
+
+	/// Loads the picture from `source`, using the cache of [`ImageSource::load_state`].
+	pub fn src(mut self, source: impl Into<ImageSource>) -> Self {
+		self.image = match source.into().load_state() {
+			ImageLoadState::Loaded(image) => Some(image),
+			ImageLoadState::Loading | ImageLoadState::Failed => None,
+		};
+		self.update()
+	}
+
+	/// Sets the diameter of the avatar, the initials being scaled along.
+	pub fn size(mut self, size: f32) -> Self {
+		self.size = size;
+		self.update()
+	}
+
+	/// Sets the background of the initials, instead of the one picked from the name.
+	pub fn color(mut self, color: impl Into<Color>) -> Self {
+		self.color = color.into();
+		self.update()
+	}
+
+	/// Applies the fields to the container.
+	fn update(mut self) -> Self {
+		let radius = self.size / 2.;
+		let style = &mut self.container.style;
+		style.size = (Sizing::Fixed(self.size), Sizing::Fixed(self.size));
+		style.border_radius = (radius, radius, radius, radius);
+		let content: Box<dyn Element> = match &self.image {
+			Some(image) => {
+				style.background_color = Color::rgba(0., 0., 0., 0.);
+				Box::new(
+					Image::new()
+						.skia_image(image.clone())
+						.size(self.size, self.size)
+						.cover()
+						.corner_radius(radius),
+				)
+			}
+			None => {
+				style.background_color = self.color;
+				Box::new(
+					Text::new(initials(&self.name))
+						.font_size((self.size * 0.4).round() as u16)
+						.color((255, 255, 255, 255))
+						.wrap(false),
+				)
+			}
+		};
+		self.container.children = vec![content];
+		self
+	}
+}
+
+impl Element for Avatar {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.container.render(ctx);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_initials_of_the_first_and_last_names() {
+		assert_eq!(initials("Ada King Lovelace"), "AL");
+		assert_eq!(initials("  grace  "), "G");
+		assert_eq!(initials("élodie durand"), "ÉD");
+		assert_eq!(initials(""), "");
+	}
+}
@@ -0,0 +1,140 @@
+//! The trail of pages leading to the current one, like `Settings › Display › Night light`, going back to any of
+//! them with the [`Navigator`](crate::Navigator).
+use crate::{Container, Role, Text, use_navigate, use_state};
+
+/// A page of a [`breadcrumbs`] trail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breadcrumb {
+	pub label: String,
+	/// The location navigated to when the crumb is clicked.
+	pub path: String,
+}
+
+impl Breadcrumb {
+	pub fn new(label: impl Into<String>, path: impl Into<String>) -> Self {
+		Self {
+			label: label.into(),
+			path: path.into(),
+		}
+	}
+
+	/// Returns a crumb for every parent of `location` and for `location` itself, labeled with their last segment
+	/// after a `Home` crumb for `/`.
+	///
+	/// `/settings/display` gives `Home` (`/`), `settings` (`/settings`) and `display` (`/settings/display`).
+	pub fn trail(location: &str) -> Vec<Self> {
+		let mut path = String::new();
+		let mut trail = vec![Self::new("Home", "/")];
+		for segment in location.split('/').filter(|s| !s.is_empty()) {
+			path.push('/');
+			path.push_str(segment);
+			trail.push(Self::new(segment, path.clone()));
+		}
+		trail
+	}
+}
+
+/// Returns the crumbs shown out of `len`, `None` standing for the collapsed ones.
+///
+/// The first crumb and the last `max_items - 1` ones are kept, so the way home and the nearest parents stay
+/// visible.
+fn visible_crumbs(len: usize, max_items: usize) -> Vec<Option<usize>> {
+	let max_items = max_items.max(2);
+	if len <= max_items {
+		return (0..len).map(Some).collect();
+	}
+	let mut crumbs = vec![Some(0), None];
+	crumbs.extend((len - (max_items - 1)..len).map(Some));
+	crumbs
+}
+
+/// Builds a trail of `items`, ending with the current page, showing at most `max_items` of them.
+///
+/// Clicking a crumb navigates to its path, except for the last one, which is the current page. The crumbs in the
+/// middle of longer trails are collapsed into a `…` button showing them all.
+///
+/// ```rust,ignore
+/// let navigator = use_navigate();
+/// Container::column()
+///     .child(breadcrumbs(Breadcrumb::trail(&navigator.location()), 4))
+///     .child(Router::new().route("/settings/:section", |params| settings(params)))
+/// ```
+pub fn breadcrumbs(items: Vec<Breadcrumb>, max_items: usize) -> Container {
+	let (expanded, set_expanded) = use_state(false);
+	let navigator = use_navigate();
+	let shown = if expanded {
+		(0..items.len()).map(Some).collect()
+	} else {
+		visible_crumbs(items.len(), max_items)
+	};
+	let last = items.len().saturating_sub(1);
+	let mut set_expanded = Some(set_expanded);
+	let mut trail = Container::row()
+		.role(Role::Group)
+		.label("Breadcrumbs")
+		.gap(4);
+	for (i, crumb) in shown.into_iter().enumerate() {
+		if i > 0 {
+			trail = trail.child(Text::new("›").color((150, 150, 150, 255)));
+		}
+		let entry = Container::new().symmetric_padding(6, 2).rounded(4.);
+		let entry = match crumb {
+			None => {
+				let set_expanded = set_expanded.take().unwrap();
+				entry
+					.role(Role::Button)
+					.label("Show the whole path")
+					.style_if_hovered(|style| style.background_color((53, 132, 228, 40)))
+					.on_click(move || set_expanded(true))
+					.child(Text::new("…"))
+			}
+			Some(index) if index == last => {
+				let crumb = &items[index];
+				entry
+					.role(Role::Label)
+					.label(crumb.label.clone())
+					.child(Text::new(crumb.label.clone()))
+			}
+			Some(index) => {
+				let crumb = &items[index];
+				let path = crumb.path.clone();
+				entry
+					.role(Role::Link)
+					.label(crumb.label.clone())
+					.style_if_hovered(|style| style.background_color((53, 132, 228, 40)))
+					.on_click(move || navigator.navigate(path.clone()))
+					.child(Text::new(crumb.label.clone()).color((53, 132, 228, 255)))
+			}
+		};
+		trail = trail.child(entry);
+	}
+	trail
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_trail_follows_the_location() {
+		assert_eq!(
+			Breadcrumb::trail("/settings/display/"),
+			vec![
+				Breadcrumb::new("Home", "/"),
+				Breadcrumb::new("settings", "/settings"),
+				Breadcrumb::new("display", "/settings/display"),
+			]
+		);
+		assert_eq!(Breadcrumb::trail("/"), vec![Breadcrumb::new("Home", "/")]);
+	}
+
+	#[test]
+	fn test_long_trails_collapse_the_middle() {
+		assert_eq!(visible_crumbs(3, 4), vec![Some(0), Some(1), Some(2)]);
+		assert_eq!(
+			visible_crumbs(6, 4),
+			vec![Some(0), None, Some(3), Some(4), Some(5)]
+		);
+		assert_eq!(visible_crumbs(6, 0), vec![Some(0), None, Some(5)]);
+	}
+}
@@ -0,0 +1,196 @@
+//! A month of days to pick a date from, like the calendar opened from the clock of a panel.
+use std::{collections::HashSet, rc::Rc};
+
+use uuid::Uuid;
+
+use crate::{
+	Align, Container, Date, Element, RenderContext, Role, Text, Weekday, use_state_handle, use_theme,
+};
+
+/// The width and height of the days, and of the rows of the calendar.
+const CELL: f32 = 32.;
+/// The weeks shown, enough for every month so the calendar keeps its height.
+const WEEKS: i64 = 6;
+
+/// A month of days, starting on Monday, with buttons to show the months before and after it.
+///
+/// Today is circled and the `selected` date is highlighted. Clicking a day calls `on_select` with its date, and
+/// shows its month if it is one of the days of the months around the one shown. The days can be focused and
+/// moved through with the arrow keys. The month of the selected date is shown first, or the current month.
+///
+/// ```rust,ignore
+/// let (date, set_date) = use_state(Date::today());
+/// Calendar::new(Some(date), move |date| set_date(date)).week_numbers(true)
+/// ```
+pub struct Calendar {
+	root: Container,
+	/// The column of the numbers of the weeks, until it is shown with [`week_numbers`](Self::week_numbers).
+	week_numbers: Option<Container>,
+}
+
+impl Calendar {
+	pub fn new(selected: Option<Date>, on_select: impl Fn(Date) + 'static) -> Self {
+		let theme = use_theme();
+		let today = Date::today();
+		let (month, set_month) = use_state_handle(selected.unwrap_or(today).first_of_month());
+		let on_select: Rc<dyn Fn(Date)> = Rc::new(on_select);
+		let step = |months: i32| {
+			let set_month = set_month.clone();
+			move || set_month.update(|month| month.add_months(months))
+		};
+		let arrow = |label: &str, symbol: &str| {
+			Container::new()
+				.role(Role::Button)
+				.label(label)
+				.size(CELL, CELL)
+				.center()
+				.rounded(CELL / 2.)
+				.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+				.child(Text::new(symbol))
+		};
+		let header = Container::row()
+			.h_fixed(CELL)
+			.align(Align::Center)
+			.child(arrow("Previous month", "‹").on_click(step(-1)))
+			.child(
+				Container::new()
+					.role(Role::Heading)
+					.w_expand()
+					.center()
+					.child(Text::new(month.format("%B %Y"))),
+			)
+			.child(arrow("Next month", "›").on_click(step(1)));
+		let weekdays = (0..7).fold(Container::row().h_fixed(CELL), |row, day| {
+			row.child(
+				Container::new()
+					.size(CELL, CELL)
+					.center()
+					.label(Weekday::from_monday(day).name())
+					.child(
+						Text::new(&Weekday::from_monday(day).short_name()[..2])
+							.font_size(theme.small_font_size)
+							.color(theme.colors.secondary_text),
+					),
+			)
+		});
+		let first = month.add_days(-i64::from(month.weekday().days_from_monday()));
+		let weeks = (0..WEEKS).fold(Container::column().role(Role::Group), |weeks, week| {
+			weeks.child((0..7).fold(Container::row(), |row, day| {
+				let date = first.add_days(week * 7 + day);
+				let on_select = on_select.clone();
+				let set_month = set_month.clone();
+				let in_month = date.first_of_month() == month;
+				let cell = Container::new()
+					.role(Role::Button)
+					.label(date.format("%A %-d %B %Y"))
+					.size(CELL, CELL)
+					.center()
+					.rounded(CELL / 2.)
+					.selected(selected == Some(date))
+					.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+					.on_click(move || {
+						if !in_month {
+							set_month.set(date.first_of_month());
+						}
+						on_select(date);
+					})
+					.child(Text::new(date.day().to_string()).color(if in_month {
+						theme.colors.text
+					} else {
+						theme.colors.secondary_text
+					}));
+				row.child(if date == today {
+					cell.border_color(theme.colors.accent).border_width(1)
+				} else {
+					cell
+				})
+			}))
+		});
+		// Built with the rest so the hooks of the calendar don't depend on it, shown on demand
+		let week_numbers = (0..WEEKS).fold(
+			Container::column().child(Container::new().size(CELL, CELL * 2.)),
+			|column, week| {
+				let (_, number) = first.add_days(week * 7).iso_week();
+				column.child(
+					Container::new()
+						.label(format!("Week {number}"))
+						.size(CELL, CELL)
+						.center()
+						.child(
+							Text::new(number.to_string())
+								.font_size(theme.small_font_size)
+								.color(theme.colors.secondary_text),
+						),
+				)
+			},
+		);
+		let root = Container::row().child(
+			Container::column()
+				.child(header)
+				.child(weekdays)
+				.child(weeks),
+		);
+		Self {
+			root,
+			week_numbers: Some(week_numbers),
+		}
+	}
+
+	/// Shows the number of each week before it, as in ISO 8601.
+	pub fn week_numbers(mut self, show: bool) -> Self {
+		if show && let Some(week_numbers) = self.week_numbers.take() {
+			self.root.children.insert(0, Box::new(week_numbers));
+		}
+		self
+	}
+}
+
+impl Element for Calendar {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+
+	use super::*;
+	use crate::testing::TestHarness;
+
+	thread_local! {
+		static PICKED: RefCell<Vec<Date>> = const { RefCell::new(Vec::new()) };
+	}
+
+	fn calendar(_: ()) -> Box<dyn Element> {
+		let selected = Date::new(2024, 2, 14);
+		Box::new(
+			Calendar::new(selected, |date| {
+				PICKED.with_borrow_mut(|picked| picked.push(date))
+			})
+			.week_numbers(true),
+		)
+	}
+
+	#[test]
+	fn test_calendar_shows_the_month_of_the_selection() {
+		let mut harness = TestHarness::new(calendar, (), (400., 400.));
+		assert!(harness.expect_label("Wednesday 14 February 2024").selected);
+		// February 2024 starts on a Thursday, in week 5
+		harness.expect_label("Monday 29 January 2024");
+		harness.expect_label("Week 5");
+		harness.expect_label("Week 10");
+		harness.click_label("Thursday 29 February 2024");
+		assert_eq!(
+			PICKED.with_borrow(|picked| picked.clone()),
+			[Date::new(2024, 2, 29).unwrap()]
+		);
+		harness.click_label("Next month");
+		harness.expect_label("Sunday 31 March 2024");
+		assert!(harness.find_by_label("Monday 29 January 2024").is_none());
+	}
+}
@@ -0,0 +1,437 @@
+//! Caret and selection drawing shared by the text editing widgets.
+//!
+//! A widget lays its text out with [`TextLayout`] to map between byte offsets and positions, and declares a
+//! [`TextCursor`] next to its [`Text`] to draw the caret and the selection on top of it. The caret and the selection
+//! themselves are moved with the keyboard by a [`TextEditor`](crate::TextEditor).
+use std::{
+	hash::{DefaultHasher, Hash, Hasher},
+	ops::Range,
+	time::{Duration, Instant},
+};
+
+use clay_layout::{
+	Color, Declaration,
+	elements::FloatingAttachToElement,
+	layout::Sizing,
+	math::{BoundingBox, Vector2},
+};
+use skia_safe::Font;
+
+use crate::{Element, RenderContext, Text, font_manager::FontManager, use_ref};
+
+/// A line of a [`TextLayout`].
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+	/// Bytes of the line, without the line break.
+	range: Range<usize>,
+	y: f32,
+}
+
+/// Text broken into lines the way it is laid out, to find where each byte offset is drawn.
+///
+/// Lines break after `\n` and, when a wrap width is given, between words like [`Text`] does.
+pub struct TextLayout {
+	text: String,
+	font: Font,
+	lines: Vec<Line>,
+	line_height: f32,
+}
+
+impl TextLayout {
+	/// Lays `text` out with its own font, wrapping lines longer than `wrap_width`.
+	pub fn new(font_manager: &mut FontManager, text: &Text, wrap_width: Option<f32>) -> Self {
+		let font_id = font_manager.get(&text.font_family, text.skia_font_style());
+		let font = Font::new(
+			&font_manager.get_fonts()[font_id as usize],
+			text.scaled_font_size() as f32,
+		);
+		Self::with_font(font, &text.text, wrap_width)
+	}
+
+	fn with_font(font: Font, text: &str, wrap_width: Option<f32>) -> Self {
+		let metrics = font.metrics().1;
+		let line_height = metrics.bottom - metrics.top;
+		let measure = |s: &str| font.measure_str(s, None).0;
+		let mut lines = Vec::new();
+		let mut paragraph_start = 0;
+		for paragraph in text.split('\n') {
+			let mut line_start = paragraph_start;
+			let mut line_width = 0.;
+			let mut word_start = paragraph_start;
+			for word in paragraph.split_inclusive(' ') {
+				// Trailing spaces can hang past the wrap width
+				let width = measure(word.trim_end_matches(' '));
+				if let Some(wrap_width) = wrap_width
+					&& word_start > line_start
+					&& line_width + width > wrap_width
+				{
+					lines.push(line_start..word_start);
+					line_start = word_start;
+					line_width = 0.;
+				}
+				line_width += measure(word);
+				word_start += word.len();
+			}
+			lines.push(line_start..paragraph_start + paragraph.len());
+			paragraph_start += paragraph.len() + 1;
+		}
+		let lines = lines
+			.into_iter()
+			.enumerate()
+			.map(|(i, range)| Line {
+				range,
+				y: i as f32 * line_height,
+			})
+			.collect();
+		Self {
+			text: text.to_string(),
+			font,
+			lines,
+			line_height,
+		}
+	}
+
+	pub fn line_height(&self) -> f32 {
+		self.line_height
+	}
+
+	pub fn line_count(&self) -> usize {
+		self.lines.len()
+	}
+
+	/// Returns the first `max_lines` lines joined with `\n`, or `None` if the text already fits in them.
+	///
+	/// With `ellipsis`, the last line kept is shortened until it fits in `width` followed by "…", which is also done
+	/// when a single line is wider than `width`.
+	pub fn truncated(&self, max_lines: usize, ellipsis: bool, width: Option<f32>) -> Option<String> {
+		let measure = |s: &str| self.font.measure_str(s, None).0;
+		let mut lines = self
+			.lines
+			.iter()
+			.take(max_lines.max(1))
+			.map(|line| self.text[line.range.clone()].trim_end_matches(' '))
+			.collect::<Vec<_>>();
+		let cut = self.lines.len() > lines.len();
+		let overflows =
+			width.is_some_and(|width| lines.last().is_some_and(|line| measure(line) > width));
+		if !cut && !(ellipsis && overflows) {
+			return None;
+		}
+		if !ellipsis {
+			return Some(lines.join("\n"));
+		}
+		let last = lines.pop().unwrap_or_default();
+		let fits = |s: &str| width.is_none_or(|width| measure(&format!("{s}…")) <= width);
+		let mut end = last.len();
+		while end > 0 && !fits(&last[..end]) {
+			end = last[..end].char_indices().next_back().map_or(0, |(i, _)| i);
+		}
+		lines.push(&last[..end]);
+		Some(lines.join("\n").trim_end().to_string() + "…")
+	}
+
+	/// Returns the line that holds the byte offset `index`. At a wrap, that is the line starting there.
+	fn line_of(&self, index: usize) -> usize {
+		self
+			.lines
+			.iter()
+			.rposition(|line| line.range.start <= index)
+			.unwrap_or(0)
+	}
+
+	fn x_of(&self, line: &Line, index: usize) -> f32 {
+		let end = index.clamp(line.range.start, line.range.end);
+		self
+			.font
+			.measure_str(&self.text[line.range.start..end], None)
+			.0
+	}
+
+	/// Returns the bounds of a caret of the given width before the byte offset `index`, relative to the top left of the text.
+	pub fn caret_bounds(&self, index: usize, width: f32) -> BoundingBox {
+		let line = &self.lines[self.line_of(index)];
+		BoundingBox {
+			x: self.x_of(line, index),
+			y: line.y,
+			width,
+			height: self.line_height,
+		}
+	}
+
+	/// Returns one rectangle per line covered by the bytes of `range`.
+	///
+	/// Selected line breaks are shown as a space-wide rectangle at the end of their line.
+	pub fn selection_bounds(&self, range: Range<usize>) -> Vec<BoundingBox> {
+		if range.is_empty() {
+			return Vec::new();
+		}
+		let space = self.font.measure_str(" ", None).0;
+		self.lines[self.line_of(range.start)..=self.line_of(range.end)]
+			.iter()
+			.filter_map(|line| {
+				let start = self.x_of(line, range.start);
+				let mut end = self.x_of(line, range.end);
+				let selects_line_break =
+					range.end > line.range.end && self.text[line.range.end..].starts_with('\n');
+				if selects_line_break {
+					end += space;
+				}
+				(end > start).then(|| BoundingBox {
+					x: start,
+					y: line.y,
+					width: end - start,
+					height: self.line_height,
+				})
+			})
+			.collect()
+	}
+
+	/// Returns the byte offset closest to `(x, y)`, relative to the top left of the text.
+	pub fn index_at(&self, x: f32, y: f32) -> usize {
+		let line_index = ((y / self.line_height).max(0.) as usize).min(self.lines.len() - 1);
+		let line = &self.lines[line_index];
+		let mut previous = (line.range.start, 0.);
+		for (offset, c) in self.text[line.range.clone()].char_indices() {
+			let index = line.range.start + offset + c.len_utf8();
+			let char_x = self.x_of(line, index);
+			if char_x >= x {
+				return if x - previous.1 < char_x - x {
+					previous.0
+				} else {
+					index
+				};
+			}
+			previous = (index, char_x);
+		}
+		line.range.end
+	}
+}
+
+/// Colors and blinking of a caret.
+#[derive(Debug, Clone)]
+pub struct CaretStyle {
+	pub color: Color,
+	pub width: f32,
+	pub selection_color: Color,
+	/// Time the caret stays shown, then hidden. `Duration::ZERO` disables blinking.
+	pub blink_interval: Duration,
+}
+
+impl Default for CaretStyle {
+	fn default() -> Self {
+		Self {
+			color: Color::rgb(0., 0., 0.),
+			width: 1.5,
+			selection_color: Color::rgba(53., 132., 228., 90.),
+			blink_interval: Duration::from_millis(530),
+		}
+	}
+}
+
+/// Returns whether a blinking caret is shown this frame.
+///
+/// The caret is solid while `active` turns on and whenever `reset` changes, which should happen on every edit
+/// or caret move so it doesn't disappear while typing. It doesn't blink with reduced motion.
+pub fn use_caret_blink(active: bool, interval: Duration, reset: impl Hash) -> bool {
+	let hash = {
+		let mut hasher = DefaultHasher::new();
+		reset.hash(&mut hasher);
+		hasher.finish()
+	};
+	let shown_since = use_ref(None::<(u64, Instant)>);
+	if !active {
+		*shown_since.borrow_mut() = None;
+		return false;
+	}
+	let now = crate::frame_scheduler::now();
+	let mut shown_since = shown_since.borrow_mut();
+	let since = match *shown_since {
+		Some((last_hash, since)) if last_hash == hash => since,
+		_ => {
+			*shown_since = Some((hash, now));
+			now
+		}
+	};
+	if interval.is_zero() || crate::prefers_reduced_motion() {
+		return true;
+	}
+	let phase = (now - since).as_nanos() / interval.as_nanos();
+	crate::frame_scheduler::request_frame_at(since + interval * (phase as u32 + 1));
+	phase % 2 == 0
+}
+
+/// Draws a caret and a selection over a [`Text`] element.
+///
+/// Declare it in the same container as the text, after it: rectangles are placed from the top left of the container,
+/// so the text should start there.
+///
+/// ```rust,ignore
+/// let text = Text::new(value.clone());
+/// let visible = use_caret_blink(focused, CaretStyle::default().blink_interval, (&value, caret));
+/// Container::new()
+///     .child(TextCursor::new(&text).caret(caret, visible).selection(selection))
+///     .child(text)
+/// ```
+pub struct TextCursor {
+	text: Text,
+	caret: Option<usize>,
+	selection: Range<usize>,
+	wrap_width: Option<f32>,
+	style: CaretStyle,
+}
+
+impl TextCursor {
+	pub fn new(text: &Text) -> Self {
+		Self {
+			text: Text {
+				text: text.text.clone(),
+				font_family: text.font_family.clone(),
+				font_weight: text.font_weight,
+				italic: text.italic,
+				font_size: text.font_size,
+				color: text.color.clone(),
+				alignment: text.alignment,
+				wrap: text.wrap,
+				max_lines: text.max_lines,
+				ellipsis: text.ellipsis,
+				truncated: Default::default(),
+				measure_key: text.measure_key,
+			},
+			caret: None,
+			selection: 0..0,
+			wrap_width: None,
+			style: CaretStyle::default(),
+		}
+	}
+
+	/// Shows the caret before the byte offset `index` if `visible`, usually from [`use_caret_blink`].
+	pub fn caret(mut self, index: usize, visible: bool) -> Self {
+		self.caret = visible.then_some(index);
+		self
+	}
+
+	/// Highlights the bytes of `range`.
+	pub fn selection(mut self, range: Range<usize>) -> Self {
+		self.selection = range;
+		self
+	}
+
+	/// Wraps lines like the text does when it is laid out in this width.
+	pub fn wrap_width(mut self, width: f32) -> Self {
+		self.wrap_width = Some(width);
+		self
+	}
+
+	pub fn style(mut self, style: CaretStyle) -> Self {
+		self.style = style;
+		self
+	}
+}
+
+/// Declares a rectangle floating over the parent, at `bounds` from its top left corner.
+fn declare_rect<'clay: 'render, 'render>(
+	ctx: &mut RenderContext<'clay, 'render, '_>,
+	bounds: BoundingBox,
+	color: Color,
+) {
+	ctx.c.with_styling(
+		|_| {
+			let mut declaration = Declaration::new();
+			declaration
+				.layout()
+				.width(Sizing::Fixed(bounds.width))
+				.height(Sizing::Fixed(bounds.height))
+				.end()
+				.floating()
+				.offset(Vector2::new(bounds.x, bounds.y))
+				.attach_to(FloatingAttachToElement::Parent)
+				.end()
+				.background_color(color);
+			declaration
+		},
+		|_| {},
+	);
+}
+
+impl Element for TextCursor {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		if self.caret.is_none() && self.selection.is_empty() {
+			return;
+		}
+		let layout = TextLayout::new(ctx.font_manager, &self.text, self.wrap_width);
+		for bounds in layout.selection_bounds(self.selection.clone()) {
+			declare_rect(ctx, bounds, self.style.selection_color.clone());
+		}
+		if let Some(caret) = self.caret {
+			let bounds = layout.caret_bounds(caret, self.style.width);
+			declare_rect(ctx, bounds, self.style.color.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use skia_safe::FontMgr;
+
+	use super::*;
+
+	fn layout(text: &str, wrap_width: Option<f32>) -> TextLayout {
+		let typeface = FontMgr::new()
+			.legacy_make_typeface(None, skia_safe::FontStyle::normal())
+			.expect("no default font");
+		TextLayout::with_font(Font::new(typeface, 14.), text, wrap_width)
+	}
+
+	#[test]
+	fn test_lines_break_on_newlines_and_wrap() {
+		let text = layout("one two three\nfour", None);
+		assert_eq!(text.line_count(), 2);
+		let width = text.x_of(&text.lines[0], "one two".len());
+		let wrapped = layout("one two three\nfour", Some(width + 1.));
+		let ranges = wrapped
+			.lines
+			.iter()
+			.map(|l| l.range.clone())
+			.collect::<Vec<_>>();
+		assert_eq!(ranges, [0..8, 8..13, 14..18]);
+	}
+
+	#[test]
+	fn test_caret_and_selection_bounds() {
+		let text = layout("ab\ncd", None);
+		let caret = text.caret_bounds(4, 1.);
+		assert_eq!(caret.y, text.line_height());
+		assert!(caret.x > 0.);
+		let selection = text.selection_bounds(1..4);
+		assert_eq!(selection.len(), 2);
+		assert_eq!(selection[1].x, 0.);
+		assert_eq!(text.index_at(caret.x, caret.y + 1.), 4);
+		assert_eq!(text.index_at(-5., 0.), 0);
+		assert_eq!(text.index_at(1000., 100.), 5);
+	}
+
+	#[test]
+	fn test_truncated_lines_end_with_an_ellipsis() {
+		let text = layout("one two three\nfour", None);
+		assert_eq!(text.truncated(2, true, None), None);
+		assert_eq!(
+			text.truncated(1, false, None).as_deref(),
+			Some("one two three")
+		);
+		assert_eq!(
+			text.truncated(1, true, None).as_deref(),
+			Some("one two three…")
+		);
+		// Cut until the line and the ellipsis fit
+		let width = text.font.measure_str("one two…", None).0;
+		assert_eq!(
+			text.truncated(1, true, Some(width)).as_deref(),
+			Some("one two…")
+		);
+		let wrapped = layout("one two three four", Some(width));
+		assert_eq!(
+			wrapped.truncated(2, true, Some(width)).as_deref(),
+			Some("one two\nthree…")
+		);
+	}
+}
@@ -0,0 +1,101 @@
+//! The time of day as text, like the clock of a panel.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clay_layout::Color;
+
+use crate::{DateTime, Element, RenderContext, Text};
+
+/// Returns `true` if `format` shows the seconds, so the clock changes every second instead of every minute.
+fn shows_seconds(format: &str) -> bool {
+	let mut chars = format.chars();
+	while let Some(c) = chars.next() {
+		if c != '%' {
+			continue;
+		}
+		let conversion = match chars.next() {
+			Some('-') => chars.next(),
+			conversion => conversion,
+		};
+		if matches!(conversion, Some('S' | 's' | 'T' | 'c' | 'r')) {
+			return true;
+		}
+	}
+	false
+}
+
+/// Returns how long until the next second or minute starts after `since_epoch`.
+fn until_next_change(since_epoch: Duration, seconds: bool) -> Duration {
+	let period = Duration::from_secs(if seconds { 1 } else { 60 });
+	let elapsed = since_epoch.as_nanos() % period.as_nanos();
+	period - Duration::from_nanos(elapsed as u64)
+}
+
+/// The local time as text, formatted like `strftime` with [`DateTime::format`].
+///
+/// The window is redrawn when the text changes, once a second if the format shows the seconds and once a
+/// minute otherwise, and not in between.
+///
+/// ```rust,ignore
+/// Container::row()
+///     .gap(8)
+///     .child(Clock::new("%a %-d %b").color(theme.colors.secondary_text))
+///     .child(Clock::new("%H:%M"))
+/// ```
+pub struct Clock {
+	text: Text,
+}
+
+impl Clock {
+	pub fn new(format: &str) -> Self {
+		let now = SystemTime::now();
+		let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+		crate::frame_scheduler::request_frame_at(
+			crate::frame_scheduler::now() + until_next_change(since_epoch, shows_seconds(format)),
+		);
+		Self {
+			text: Text::new(DateTime::local(now).format(format)),
+		}
+	}
+
+	pub fn font_size(mut self, size: u16) -> Self {
+		self.text = self.text.font_size(size);
+		self
+	}
+
+	pub fn color(mut self, color: impl Into<Color>) -> Self {
+		self.text = self.text.color(color);
+		self
+	}
+
+	pub fn font_family(mut self, family: impl Into<String>) -> Self {
+		self.text = self.text.font_family(family);
+		self
+	}
+}
+
+impl Element for Clock {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.text.render(ctx);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_clocks_redraw_when_the_text_changes() {
+		assert!(shows_seconds("%H:%M:%S"));
+		assert!(shows_seconds("%T"));
+		assert!(!shows_seconds("%H:%M, 100%%S"));
+		let since_epoch = Duration::from_millis(1_700_000_012_250);
+		assert_eq!(
+			until_next_change(since_epoch, true),
+			Duration::from_millis(750)
+		);
+		assert_eq!(
+			until_next_change(since_epoch, false),
+			Duration::from_millis(27_750)
+		);
+	}
+}
@@ -1,6 +1,6 @@
-use std::any::type_name_of_val;
+use std::{any::type_name_of_val, marker::PhantomData};
 
-use crate::{Element, RenderContext, begin_component, end_component};
+use crate::{Element, RenderContext, begin_component, end_component, profiling::profile_span};
 
 // Function component wrapper
 pub struct Component {
@@ -15,6 +15,7 @@ impl Component {
 	pub fn new<Props>(func: impl FnOnce(Props) -> Box<dyn Element>, props: Props) -> Self {
 		Self {
 			child: {
+				profile_span!("component", name = type_name_of_val(&func));
 				begin_component(format!(
 					"{}({})",
 					type_name_of_val(&func),
@@ -34,6 +35,7 @@ impl Component {
 	) -> Self {
 		Self {
 			child: {
+				profile_span!("component", name = type_name_of_val(&func), key = %key);
 				begin_component(format!(
 					"{}({}) key = {key}",
 					type_name_of_val(&func),
@@ -46,6 +48,107 @@ impl Component {
 		}
 	}
 }
+/// Returns the default props of `component`, modified by `set`.
+///
+/// Used by `rsml!` to build props, since the type of a `Default::default()` value can't be
+/// inferred from field assignments alone.
+#[doc(hidden)]
+pub fn props_for<Props: Default>(
+	_component: &impl FnOnce(Props) -> Box<dyn Element>,
+	set: impl FnOnce(&mut Props),
+) -> Props {
+	let mut props = Props::default();
+	set(&mut props);
+	props
+}
+
+/// Props of a component, implemented by `#[derive(Props)]`.
+pub trait Props: Default {
+	/// The props that must be set when the component is used in RSML, with the error shown when one isn't.
+	const REQUIRED: &'static [(&'static str, &'static str)];
+}
+
+/// The props set by a component tag in RSML, declared by `rsml!` for [`PropsCheck`].
+#[doc(hidden)]
+pub trait PropNames {
+	const NAMES: &'static [&'static str];
+}
+
+/// Checks at compile time that a component tag in RSML sets the [required props](Props::REQUIRED) of `P`, the
+/// names in `S`.
+///
+/// `rsml!` calls `(&PropsCheck::<_, S>::new(&props)).check()` with both [`CheckRequiredProps`] and
+/// [`SkipRequiredProps`] in scope. Method resolution picks the first one when `P` implements [`Props`], and the second,
+/// which takes one more reference, for props structs written by hand.
+#[doc(hidden)]
+pub struct PropsCheck<P, S>(PhantomData<(P, S)>);
+
+impl<P, S> PropsCheck<P, S> {
+	pub fn new(_props: &P) -> Self {
+		Self(PhantomData)
+	}
+}
+
+#[doc(hidden)]
+pub trait CheckRequiredProps {
+	fn check(&self);
+}
+
+impl<P: Props, S: PropNames> CheckRequiredProps for PropsCheck<P, S> {
+	fn check(&self) {
+		// Evaluated when the crate is built, so a missing prop is a compile error
+		const {
+			if let Some(message) = missing_prop(P::REQUIRED, S::NAMES) {
+				panic!("{}", message);
+			}
+		}
+	}
+}
+
+#[doc(hidden)]
+pub trait SkipRequiredProps {
+	fn check(&self);
+}
+
+impl<P, S> SkipRequiredProps for &PropsCheck<P, S> {
+	fn check(&self) {}
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+	if a.len() != b.len() {
+		return false;
+	}
+	let mut i = 0;
+	while i < a.len() {
+		if a[i] != b[i] {
+			return false;
+		}
+		i += 1;
+	}
+	true
+}
+
+/// Returns the error of the first prop of `required` missing from `set`.
+const fn missing_prop(
+	required: &[(&'static str, &'static str)],
+	set: &[&str],
+) -> Option<&'static str> {
+	let mut i = 0;
+	while i < required.len() {
+		let (name, message) = required[i];
+		let mut j = 0;
+		while j < set.len() && !str_eq(name, set[j]) {
+			j += 1;
+		}
+		if j == set.len() {
+			return Some(message);
+		}
+		i += 1;
+	}
+	None
+}
+
 impl<F: FnOnce() -> Box<dyn Element>> From<F> for Component {
 	fn from(value: F) -> Self {
 		Self::new(|_| value(), ())
@@ -61,3 +164,26 @@ impl Element for Component {
 		self.child.render(ctx);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_missing_prop_is_the_first_one_not_set() {
+		let required = [
+			("label", "label is missing"),
+			("on_click", "on_click is missing"),
+		];
+		assert_eq!(missing_prop(&required, &["label", "on_click"]), None);
+		assert_eq!(
+			missing_prop(&required, &["on_click"]),
+			Some("label is missing")
+		);
+		assert_eq!(
+			missing_prop(&required, &["label", "on"]),
+			Some("on_click is missing")
+		);
+		assert_eq!(missing_prop(&[], &[]), None);
+	}
+}
@@ -1,18 +1,92 @@
-use std::cell::RefCell;
+use std::any::Any;
+use std::cell::{Cell, OnceCell, RefCell};
 use std::rc::Rc;
+use std::sync::Arc;
 mod clickable;
+pub(crate) mod drag_and_drop;
+mod keyboard;
+mod pull_to_refresh;
+mod selectable;
+mod tooltip;
+use crate::accessibility::{self, AccessibilityNode, AccessibilityProps, Role};
+use crate::clay_renderer::CustomElement;
+use crate::element_ids;
 use crate::focus_system::GLOBAL_FOCUS_MANAGER;
+use crate::hover::{self, PointerRef};
+use crate::inspector;
+use crate::overlay;
 use crate::render_context::RenderContext;
+use crate::scroll::{
+	self, ScrollContainer, ScrollHandle, ScrollPhysics, ScrollSnap, ScrollState, SnapPoints,
+};
+use crate::sticky;
+use crate::theme::Theme;
 use crate::{Component, element::Element};
-use crate::{begin_component, end_component, use_ref};
+use crate::{begin_component, end_component, use_memo, use_ref};
 use clay_layout::{
 	Color, Declaration,
+	elements::FloatingAttachToElement,
 	layout::{Alignment, LayoutDirection, Padding, Sizing},
+	math::Vector2,
 };
 use clickable::Clickable;
 pub use clickable::ClickableState;
+use drag_and_drop::DropTarget;
+pub use drag_and_drop::{DroppedData, DroppedFiles};
+use keyboard::KeyboardActivatable;
+pub use keyboard::KeyAction;
+pub(crate) use pull_to_refresh::PullGesture;
+use pull_to_refresh::PullToRefresh;
+pub use pull_to_refresh::PullState;
+pub use tooltip::Tooltip;
+use tooltip::TooltipLabel;
+use uuid::Uuid;
 pub type Justify = clay_layout::layout::LayoutAlignmentX;
 pub type Align = clay_layout::layout::LayoutAlignmentY;
+/// A corner, the middle of an edge or the center of an element, see [`AttachPoint`].
+pub type AttachPosition = clay_layout::elements::FloatingAttachPointType;
+
+/// Where a [floating](Container::floating) container is placed, by the point of the container placed on a point of
+/// its parent or of the window.
+#[derive(Debug, Clone, Copy)]
+pub struct AttachPoint {
+	/// Whether the container is placed relative to the window instead of its parent.
+	pub root: bool,
+	/// The point of the container placed on the target.
+	pub element: AttachPosition,
+	/// The point of the parent, or of the window, the container is placed on.
+	pub target: AttachPosition,
+}
+
+impl AttachPoint {
+	/// Places the `element` point of the container on the `parent` point of its parent.
+	pub fn parent(element: AttachPosition, parent: AttachPosition) -> Self {
+		Self {
+			root: false,
+			element,
+			target: parent,
+		}
+	}
+
+	/// Places the `element` point of the container on the `window` point of the window.
+	pub fn root(element: AttachPosition, window: AttachPosition) -> Self {
+		Self {
+			root: true,
+			element,
+			target: window,
+		}
+	}
+
+	/// Places the container below its parent, aligned to its left edge, like a dropdown.
+	pub fn below() -> Self {
+		Self::parent(AttachPosition::LeftTop, AttachPosition::LeftBottom)
+	}
+
+	/// Places the container above its parent, aligned to its left edge.
+	pub fn above() -> Self {
+		Self::parent(AttachPosition::LeftBottom, AttachPosition::LeftTop)
+	}
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Direction {
@@ -47,6 +121,75 @@ impl Default for Border {
 		}
 	}
 }
+/// A circular progress gauge drawn as the background of a container.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ring {
+	/// Part of the ring that is filled, from `0` to `1`, clockwise from the top.
+	pub fraction: f32,
+	/// Width of the ring. A ring without thickness is drawn as a pie.
+	pub thickness: f32,
+	pub color: Color,
+	/// Color of the part of the ring that isn't filled.
+	pub track_color: Color,
+}
+
+impl Ring {
+	pub fn new(fraction: f32, thickness: f32) -> Self {
+		Self {
+			fraction,
+			thickness,
+			color: Color::rgb(53., 132., 228.),
+			track_color: Color::rgba(0., 0., 0., 40.),
+		}
+	}
+}
+
+/// Highest elevation level, see [`Container::elevation`].
+pub const MAX_ELEVATION: u8 = 5;
+
+/// A shadow drawn behind a container, following its rounded corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+	pub offset: (f32, f32),
+	/// Blur radius, like in CSS.
+	pub blur: f32,
+	/// How much the shadow is larger than the container on every side.
+	pub spread: f32,
+	pub color: Color,
+}
+
+impl Shadow {
+	pub fn new(offset: (f32, f32), blur: f32, spread: f32, color: impl Into<Color>) -> Self {
+		Self {
+			offset,
+			blur,
+			spread,
+			color: color.into(),
+		}
+	}
+
+	/// Returns the shadow of an elevation level, or `None` for level `0`. Levels above [`MAX_ELEVATION`] look like
+	/// it.
+	///
+	/// Higher levels look further from the surface below, with a larger and softer shadow.
+	pub fn elevation(level: u8) -> Option<Self> {
+		let (y, blur, alpha) = match level {
+			0 => return None,
+			1 => (1., 3., 0.2),
+			2 => (2., 6., 0.22),
+			3 => (4., 10., 0.24),
+			4 => (6., 16., 0.26),
+			_ => (10., 24., 0.3),
+		};
+		Some(Self {
+			offset: (0., y),
+			blur,
+			spread: 0.,
+			color: Color::rgba(0., 0., 0., 255. * alpha),
+		})
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct ContainerStyle {
 	pub background_color: Color,
@@ -58,6 +201,11 @@ pub struct ContainerStyle {
 	pub direction: Direction,
 	pub padding: (u16, u16, u16, u16),
 	pub border: Border,
+	pub ring: Option<Ring>,
+	/// Elevation level, drawn as a shadow from [`Shadow::elevation`].
+	pub elevation: u8,
+	/// A shadow drawn instead of the one of the elevation.
+	pub shadow: Option<Shadow>,
 }
 impl Default for ContainerStyle {
 	fn default() -> Self {
@@ -71,6 +219,9 @@ impl Default for ContainerStyle {
 			justify: Justify::Left,
 			direction: Direction::Column,
 			border: Default::default(),
+			ring: None,
+			elevation: 0,
+			shadow: None,
 		}
 	}
 }
@@ -157,8 +308,77 @@ impl ContainerStyle {
   self.border.width.between_children = width;
   self
  }
+
+ pub fn elevation(mut self, level: u8) -> Self {
+  self.elevation = level;
+  self
+ }
+
+ pub fn shadow(mut self, offset: (f32, f32), blur: f32, spread: f32, color: impl Into<Color>) -> Self {
+  self.shadow = Some(Shadow::new(offset, blur, spread, color));
+  self
+ }
+
+ pub fn ring(mut self, ring: Ring) -> Self {
+  self.ring = Some(ring);
+  self
+ }
+
+ /// Sets the color of the filled part of the ring, if there is one.
+ pub fn ring_color(mut self, color: impl Into<Color>) -> Self {
+  if let Some(ring) = &mut self.ring {
+   ring.color = color.into();
+  }
+  self
+ }
+
+ /// Replaces the colors of this style with the high-contrast colors.
+ ///
+ /// Interactive containers always get a border, and the focused one a thicker outline in the focus color.
+ fn high_contrast(mut self, interactive: bool, focused: bool) -> Self {
+  let colors = crate::high_contrast_colors();
+  if self.background_color.a > 0. {
+   self.background_color = colors.background;
+  }
+  self.border.color = colors.foreground;
+  if let Some(ring) = &mut self.ring {
+   ring.color = colors.foreground;
+   ring.track_color = colors.background;
+  }
+  let min_width = if focused {
+   self.border.color = colors.focus;
+   3
+  } else if interactive {
+   1
+  } else {
+   0
+  };
+  let width = &mut self.border.width;
+  width.left = width.left.max(min_width);
+  width.right = width.right.max(min_width);
+  width.top = width.top.max(min_width);
+  width.bottom = width.bottom.max(min_width);
+  self
+ }
 }
 
+/// Short description of a sizing mode for the layout debug outlines.
+fn sizing_label(sizing: &Sizing) -> String {
+	match sizing {
+		Sizing::Fit(..) => "fit".to_string(),
+		Sizing::Grow(..) => "grow".to_string(),
+		Sizing::Fixed(size) => format!("{size}px"),
+		_ => "percent".to_string(),
+	}
+}
+
+/// Returns `size` within the minimum and maximum of `sizing`, if it has some.
+fn clamp_sizing(sizing: Sizing, size: f32) -> f32 {
+	match sizing {
+		Sizing::Fit(min, max) | Sizing::Grow(min, max) => size.min(max).max(min),
+		_ => size,
+	}
+}
 
 /// A generic container element that can hold other elements.
 ///
@@ -173,24 +393,87 @@ pub struct Container {
 	pub style_if_hovered: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
 	pub style_if_pressed: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
 	pub style_if_focused: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
+	pub style_if_focus_within: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
+	pub style_if_active: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
+	pub style_if_drag_over: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
+	pub style_if_selected: Box<dyn Fn(ContainerStyle) -> ContainerStyle>,
 	pub(crate) clickable: Option<Clickable>,
 	pub(crate) clickable_state: Rc<RefCell<ClickableState>>,
+	pub(crate) keyboard: Option<KeyboardActivatable>,
+	pub(crate) accessibility: AccessibilityProps,
+	/// Stable identifier of this container across frames.
+	pub(crate) id: Uuid,
+	pub(crate) clay_id: String,
+	/// The id set with [`Container::id`], to find the element after layout.
+	pub(crate) element_id: Option<String>,
+	/// Whether the content scrolls horizontally and vertically.
+	pub(crate) scroll: (bool, bool),
+	pub(crate) scroll_state: Rc<RefCell<ScrollState>>,
+	pub(crate) scroll_physics: ScrollPhysics,
+	pub(crate) scroll_snap: Option<ScrollSnap>,
+	pub(crate) on_reach_end: Option<(f32, Rc<dyn Fn()>)>,
+	/// Custom drawing of the background, which must outlive the declaration.
+	pub(crate) custom_element: OnceCell<CustomElement>,
+	/// Whether the containers below stay hovered, see [`Container::hover_through`].
+	pub(crate) hover_through: bool,
+	/// Hooks following the pointer over this container, see [`Container::pointer_ref`].
+	pub(crate) pointer_refs: Vec<PointerRef>,
+	pub(crate) pull_to_refresh: Option<PullToRefresh>,
+	/// Whether the container is pinned to the top of the scroll container around it, see [`Container::sticky`].
+	pub(crate) sticky: bool,
+	/// The payload of the container when it is dragged, see [`Container::draggable`].
+	pub(crate) draggable: Option<Rc<dyn Any>>,
+	/// The data dropped in other apps, in each of its MIME types, see [`Container::drag_data`].
+	pub(crate) drag_data: Vec<(String, Arc<[u8]>)>,
+	pub(crate) drag_preview: Option<Box<dyn Element>>,
+	pub(crate) drop_target: Option<DropTarget>,
+	/// Where the container is placed out of the layout, and its z-index, see [`Container::floating`].
+	pub(crate) floating: Option<((f32, f32), AttachPoint, i16)>,
+	pub(crate) tooltip: Option<TooltipLabel>,
 }
 
 impl Default for Container {
 	fn default() -> Self {
 		begin_component("container");
 		let clickable_state = use_ref(ClickableState::default());
+		let id = *use_memo(Uuid::new_v4, ());
+		let scroll_state = use_ref(ScrollState::default());
 		end_component();
+		let selected_color = Theme::with_alpha(crate::use_theme().colors.accent, 50.);
 		Self {
 			children: Vec::new(),
 			style: ContainerStyle::default(),
 			style_if_hovered: Box::new(|style| style),
 			style_if_pressed: Box::new(|style| style),
 			style_if_focused: Box::new(|style| style),
+			style_if_focus_within: Box::new(|style| style),
+			style_if_active: Box::new(|style| style),
+			style_if_drag_over: Box::new(|style| style),
+			style_if_selected: Box::new(move |style| style.background_color(selected_color)),
 
 			clickable: None,
 			clickable_state,
+			keyboard: None,
+			accessibility: Default::default(),
+			id,
+			clay_id: id.to_string(),
+			element_id: None,
+			scroll: (false, false),
+			scroll_state,
+			scroll_physics: ScrollPhysics::default(),
+			scroll_snap: None,
+			on_reach_end: None,
+			custom_element: OnceCell::new(),
+			hover_through: false,
+			pointer_refs: Vec::new(),
+			pull_to_refresh: None,
+			sticky: false,
+			draggable: None,
+			drag_data: Vec::new(),
+			drag_preview: None,
+			drop_target: None,
+			floating: None,
+			tooltip: None,
 		}
 	}
 }
@@ -199,6 +482,17 @@ impl Container {
 	pub fn new() -> Self {
 		Self::default()
 	}
+	/// Names the container, to find where it was laid out with [`RenderContext::bounding_box`] and
+	/// [`RenderContext::element_at`], or to click it in tests.
+	///
+	/// Ids should be unique in the window.
+	pub fn id(mut self, id: impl Into<String>) -> Self {
+		let id = id.into();
+		self.clay_id = id.clone();
+		self.element_id = Some(id);
+		self
+	}
+
 	pub fn clickable_ref(mut self, state: Rc<RefCell<ClickableState>>) -> Self {
 		self.clickable_state = state;
 		self
@@ -236,10 +530,25 @@ impl Container {
 		self.style.size.0 = Sizing::Fit(0., f32::MAX);
 		self
 	}
+	/// Sets the width to exactly `width` pixels, kept within the [`min_width`](Self::min_width) and
+	/// [`max_width`](Self::max_width) set before. Setting them afterwards clamps the width too.
+	pub fn w_fixed(mut self, width: f32) -> Self {
+		self.style.size.0 = Sizing::Fixed(clamp_sizing(self.style.size.0, width));
+		self
+	}
+	/// Sets the height to exactly `height` pixels, like [`w_fixed`](Self::w_fixed).
+	pub fn h_fixed(mut self, height: f32) -> Self {
+		self.style.size.1 = Sizing::Fixed(clamp_sizing(self.style.size.1, height));
+		self
+	}
+	/// Sets both the width and the height, like [`w_fixed`](Self::w_fixed) and [`h_fixed`](Self::h_fixed).
+	pub fn size(self, width: f32, height: f32) -> Self {
+		self.w_fixed(width).h_fixed(height)
+	}
 	pub fn min_width(mut self, width: f32) -> Self {
 		self.style.size.0 = match self.style.size.0 {
 			Sizing::Fit(_, max) => Sizing::Fit(width, max),
-			Sizing::Fixed(size) => Sizing::Fixed(size.min(width)),
+			Sizing::Fixed(size) => Sizing::Fixed(size.max(width)),
 			Sizing::Grow(_, max) => Sizing::Grow(width, max),
 			o => o,
 		};
@@ -249,7 +558,7 @@ impl Container {
 	pub fn min_height(mut self, height: f32) -> Self {
 		self.style.size.1 = match self.style.size.1 {
 			Sizing::Fit(_, max) => Sizing::Fit(height, max),
-			Sizing::Fixed(size) => Sizing::Fixed(size.min(height)),
+			Sizing::Fixed(size) => Sizing::Fixed(size.max(height)),
 			Sizing::Grow(_, max) => Sizing::Grow(height, max),
 			o => o,
 		};
@@ -297,6 +606,131 @@ impl Container {
 		self
 	}
 
+	/// Scrolls the content horizontally when it is wider than the container.
+	///
+	/// The container clips its content, and scrolls with the mouse wheel or the touchpad. Focused elements
+	/// inside it are scrolled into view.
+	pub fn scroll_x(mut self) -> Self {
+		self.scroll.0 = true;
+		self
+	}
+
+	/// Scrolls the content vertically when it is taller than the container. See [`scroll_x`](Self::scroll_x).
+	pub fn scroll_y(mut self) -> Self {
+		self.scroll.1 = true;
+		self
+	}
+
+	/// Changes how the content moves when scrolled, dragged or flung.
+	///
+	/// ```rust,ignore
+	/// Container::column()
+	///     .scroll_y()
+	///     .scroll_physics(ScrollPhysics::kinetic().overscroll(Overscroll::Clamp))
+	/// ```
+	pub fn scroll_physics(mut self, physics: ScrollPhysics) -> Self {
+		self.scroll_physics = physics;
+		self
+	}
+
+	/// Makes the content come to rest on snap points, like the items of a carousel or the pages of a pager.
+	///
+	/// ```rust,ignore
+	/// // A pager: each page is as wide as the container, and a swipe moves to the next one
+	/// Container::row()
+	///     .scroll_x()
+	///     .scroll_physics(ScrollPhysics::kinetic())
+	///     .scroll_snap(ScrollSnap::children())
+	///     .child(page(0))
+	///     .child(page(1))
+	/// ```
+	pub fn scroll_snap(mut self, snap: ScrollSnap) -> Self {
+		self.scroll_snap = Some(snap);
+		self
+	}
+
+	/// Calls `handler` when the content is scrolled within `threshold` logical pixels of its end, to load more
+	/// items of an infinite list.
+	///
+	/// The handler is called once, and again only after the content changed size, so it isn't called over and
+	/// over while the next items load. Content that fits the container is at its end, so items are loaded until
+	/// it is filled.
+	///
+	/// ```rust,ignore
+	/// let (pages, set_pages) = use_state(1);
+	/// let posts = use_future(move || async move { fetch_posts(pages).await }, &pages);
+	/// let mut feed = Container::column()
+	///     .scroll_y()
+	///     .on_reach_end(200., move || set_pages(pages + 1));
+	/// for post in posts.value().into_iter().flatten() {
+	///     feed = feed.child(PostCard::new(post));
+	/// }
+	/// if posts.is_pending() {
+	///     feed = feed.child(Text::new("Loading…"));
+	/// }
+	/// ```
+	pub fn on_reach_end(mut self, threshold: f32, handler: impl Fn() + 'static) -> Self {
+		self.on_reach_end = Some((threshold, Rc::new(handler)));
+		self
+	}
+
+	/// Pins the container to the top of the scroll container around it while its parent is in view, like the
+	/// header of a section of a list.
+	///
+	/// The container keeps its place in the layout, and is only drawn lower once its parent scrolled above the
+	/// top of the viewport, drawn over the rest of the content. It is pushed away when the bottom of its parent
+	/// reaches it, so the header of the next section takes its place. The pointer still reaches it where it
+	/// was laid out, so pinned headers shouldn't be clickable. Its content is clipped to its width.
+	///
+	/// ```rust,ignore
+	/// let mut list = Container::column().scroll_y();
+	/// for section in sections {
+	///     let mut rows = Container::column().child(
+	///         Container::new()
+	///             .sticky()
+	///             .background_color((30, 30, 30, 255))
+	///             .child(Text::new(section.title)),
+	///     );
+	///     for item in section.items {
+	///         rows = rows.child(Text::new(item));
+	///     }
+	///     list = list.child(rows);
+	/// }
+	/// ```
+	pub fn sticky(mut self) -> Self {
+		self.sticky = true;
+		self
+	}
+
+	/// Takes the container out of the layout and places it at `attach_point`, moved by `offset`, above its siblings,
+	/// like a popup or a badge over an icon.
+	///
+	/// The container doesn't take space in its parent, which keeps the size it had without it, and its own size is
+	/// computed as usual. Floating containers are drawn above the rest of the window, in the order of their
+	/// `z_index` raised by their [`elevation`](Self::elevation), and the containers below them don't get the pointer
+	/// where they are.
+	///
+	/// ```rust,ignore
+	/// Container::new()
+	///     .child(Image::new().src("mail.svg").size(24., 24.))
+	///     .child(
+	///         Container::new()
+	///             .floating((4., -4.), AttachPoint::parent(AttachPosition::CenterCenter, AttachPosition::RightTop), 1)
+	///             .background_color((192, 28, 40, 255))
+	///             .child(Text::new(unread.to_string())),
+	///     )
+	/// ```
+	pub fn floating(mut self, offset: (f32, f32), attach_point: AttachPoint, z_index: i16) -> Self {
+		self.floating = Some((offset, attach_point, z_index));
+		self
+	}
+
+	/// Shares the scroll position with a [`use_scroll_handle`](crate::use_scroll_handle) hook.
+	pub fn scroll_handle(mut self, handle: &ScrollHandle) -> Self {
+		self.scroll_state = handle.state.clone();
+		self
+	}
+
 	pub fn direction(mut self, direction: Direction) -> Self {
 		self.style.direction = direction;
 		self
@@ -353,6 +787,84 @@ impl Container {
 		self.style.border_radius.3 = radius;
 		self
 	}
+	/// Draws a circular gauge filled to `fraction`, from `0` to `1`, as the background of the container.
+	///
+	/// The ring fits in the container, so a square container with the value centered inside makes a
+	/// battery or CPU gauge. A `thickness` of `0` draws a pie instead.
+	pub fn ring_progress(mut self, fraction: f32, thickness: f32) -> Self {
+		let ring = match self.style.ring {
+			Some(ring) => Ring {
+				fraction,
+				thickness,
+				..ring
+			},
+			None => Ring::new(fraction, thickness),
+		};
+		self.style.ring = Some(ring);
+		self
+	}
+
+	/// Lifts the container above the surface below with a shadow, from `0` for none to [`MAX_ELEVATION`].
+	///
+	/// Use the same levels across widgets for a consistent depth, like `1` for cards, `3` for menus and
+	/// `5` for dialogs. See [`Shadow::elevation`].
+	///
+	/// The level is added to the z-index of a [floating](Self::floating) container, so of two popups over each
+	/// other the more elevated one is drawn above and gets the pointer. Containers in the layout don't overlap
+	/// their siblings, and are drawn in the order they are declared.
+	pub fn elevation(mut self, level: u8) -> Self {
+		self.style.elevation = level;
+		self
+	}
+
+	/// Draws a shadow behind the container, following its rounded corners, instead of the one of its
+	/// [`elevation`](Self::elevation).
+	///
+	/// Like a CSS `box-shadow`, the shadow is moved by `offset`, blurred by `blur` and grown by `spread` on every
+	/// side, which can be negative to tuck it under the container.
+	///
+	/// ```rust,ignore
+	/// Container::new()
+	///     .background_color((36, 36, 36, 255))
+	///     .rounded(12.)
+	///     .shadow((0., 8.), 24., -4., (0, 0, 0, 120))
+	/// ```
+	pub fn shadow(
+		mut self,
+		offset: (f32, f32),
+		blur: f32,
+		spread: f32,
+		color: impl Into<Color>,
+	) -> Self {
+		self.style.shadow = Some(Shadow::new(offset, blur, spread, color));
+		self
+	}
+
+	/// Sets the color of the ring of [`ring_progress`](Self::ring_progress) and of the part that isn't filled.
+	pub fn ring_colors(mut self, color: impl Into<Color>, track_color: impl Into<Color>) -> Self {
+		let ring = self.style.ring.get_or_insert(Ring::new(0., 4.));
+		ring.color = color.into();
+		ring.track_color = track_color.into();
+		self
+	}
+
+	/// Keeps the containers below this one hovered while the pointer is over it.
+	///
+	/// Only the topmost container under the pointer and the containers around it are hovered, so a
+	/// container drawn over another one takes its hover styles and clicks. This opts out of it for
+	/// decorations like badges or glows laid over other elements.
+	pub fn hover_through(mut self) -> Self {
+		self.hover_through = true;
+		self
+	}
+
+	/// Reports the pointer over this container to a [`use_hover`](crate::use_hover) or
+	/// [`use_pressed`](crate::use_pressed) hook, without making it clickable.
+	pub fn pointer_ref(mut self, pointer: &PointerRef) -> Self {
+		self.pointer_refs.push(pointer.clone());
+		self
+	}
+
 	pub fn style_if_hovered<F>(mut self, f: F) -> Self
 	where
 		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
@@ -367,6 +879,7 @@ impl Container {
 		self.style_if_pressed = Box::new(f);
 		self
 	}
+	/// Applied while the container has the keyboard focus.
 	pub fn style_if_focused<F>(mut self, f: F) -> Self
 	where
 		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
@@ -374,6 +887,22 @@ impl Container {
 		self.style_if_focused = Box::new(f);
 		self
 	}
+	/// Applied while the container or an element inside it has the keyboard focus.
+	pub fn style_if_focus_within<F>(mut self, f: F) -> Self
+	where
+		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
+	{
+		self.style_if_focus_within = Box::new(f);
+		self
+	}
+	/// Applied while the container is pressed with Enter or Space, after [`style_if_pressed`](Self::style_if_pressed).
+	pub fn style_if_active<F>(mut self, f: F) -> Self
+	where
+		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
+	{
+		self.style_if_active = Box::new(f);
+		self
+	}
 
 	pub fn border_color(mut self, color: impl Into<Color>) -> Self {
 		self.style.border.color = color.into();
@@ -412,28 +941,231 @@ impl Container {
 		self.style.border.width.between_children = width;
 		self
 	}
+
+	/// Sets the role reported to assistive technologies.
+	///
+	/// Clickable containers default to [`Role::Button`], focusable ones to [`Role::Group`].
+	pub fn role(mut self, role: Role) -> Self {
+		self.accessibility.role = Some(role);
+		self
+	}
+
+	/// Sets the name announced by assistive technologies.
+	///
+	/// When not set, the text content of the container is used instead.
+	pub fn label(mut self, label: impl Into<String>) -> Self {
+		self.accessibility.label = Some(label.into());
+		self
+	}
+
+	/// Sets the current value reported to assistive technologies (e.g. the position of a slider).
+	pub fn value(mut self, value: impl Into<String>) -> Self {
+		self.accessibility.value = Some(value.into());
+		self
+	}
+
+	/// Called when an assistive technology requests a new value for this container.
+	pub fn on_value_change(mut self, handler: impl Fn(String) + 'static) -> Self {
+		self.accessibility.on_value_change = Some(Box::new(handler));
+		self
+	}
+
+	fn accessibility_node(&self) -> Option<AccessibilityNode> {
+		if self.clickable.is_none() && !self.accessibility.is_set() {
+			return None;
+		}
+		let clickable = self
+			.clickable
+			.as_ref()
+			.is_some_and(|c| c.on_click.is_some());
+		Some(AccessibilityNode {
+			id: accessibility::node_id(self.id),
+			role: self.accessibility.role.unwrap_or(if clickable {
+				Role::Button
+			} else {
+				Role::Group
+			}),
+			label: self.accessibility.label.clone(),
+			label_from_content: self.accessibility.label.is_none(),
+			value: self.accessibility.value.clone(),
+			selected: self.accessibility.selected,
+			focus_node: self.clickable.as_ref().and_then(|c| c.focus_node_id),
+			clickable,
+			editable: self.accessibility.on_value_change.is_some(),
+			clay_id: None,
+			bounds: None,
+			children: Vec::new(),
+		})
+	}
 }
 
 impl Element for Container {
 	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let node_id = accessibility::node_id(self.id);
+		let accessibility_node = self.accessibility_node();
+		let inspecting = inspector::is_collecting();
+		let scrolls = self.scroll != (false, false);
+		let snap_child = scroll::take_snap_child();
+		let is_section = sticky::is_section(self.id);
+		let is_scroll_target = self
+			.clickable
+			.as_ref()
+			.and_then(|c| c.focus_node_id)
+			.is_some_and(scroll::is_target);
+		let clay_id = (accessibility_node.is_some()
+			|| inspecting
+			|| scrolls
+			|| is_scroll_target
+			|| snap_child
+			|| self.sticky
+			|| is_section
+			|| self.draggable.is_some()
+			|| self.tooltip.is_some()
+			|| self.element_id.is_some())
+		.then(|| ctx.c.id(&self.clay_id));
+		// Floating containers and their children are above the ones declared after them
+		let z_index = self.floating.map(|(_, _, z_index)| {
+			z_index.saturating_add(self.style.elevation.min(MAX_ELEVATION).into())
+		});
+		let outer_z_index = z_index.map(element_ids::enter_floating);
+		if let (Some(element_id), Some(clay_id)) = (&self.element_id, &clay_id) {
+			element_ids::register(element_id, clay_id.clone());
+		}
+		// Where focus nodes are laid out is used to move focus with the arrow keys
+		let focus_node = self.clickable.as_ref().and_then(|c| c.focus_node_id);
+		if let (Some(focus_node), Some(clay_id)) = (focus_node, &clay_id) {
+			GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.declare_element(focus_node, clay_id.clone()));
+		}
+		let is_accessibility_node = accessibility_node.is_some();
+		if let Some(mut node) = accessibility_node {
+			node.clay_id = clay_id.clone();
+			accessibility::push_node(node);
+		}
+		let inspector_entry = inspecting.then(|| {
+			let mut name = "Container".to_string();
+			if let Some(role) = self.accessibility.role {
+				name += &format!(" {role:?}");
+			}
+			if let Some(label) = &self.accessibility.label {
+				name += &format!(" {label:?}");
+			}
+			inspector::push_entry(name, Some(&self.clay_id), clay_id.clone())
+		});
+		if let Some(on_value_change) = &self.accessibility.on_value_change {
+			if let Some(value) = accessibility::take_set_value(node_id) {
+				on_value_change(value);
+			}
+		}
+		if is_scroll_target && let Some(clay_id) = &clay_id {
+			scroll::set_target_element(clay_id.clone());
+		}
+		if snap_child && let Some(clay_id) = &clay_id {
+			scroll::add_snap_child(clay_id.clone());
+		}
+		if self.sticky
+			&& let Some(clay_id) = &clay_id
+		{
+			sticky::declare(clay_id.clone());
+		}
+		// The children are wrapped in an element of their own, which is what scrolls
+		let content_id = match &clay_id {
+			Some(clay_id) if scrolls => {
+				let content_id = ctx.c.id(&format!("{}-content", self.clay_id));
+				scroll::push_container(ScrollContainer {
+					state: self.scroll_state.clone(),
+					axes: self.scroll,
+					viewport: clay_id.clone(),
+					content: content_id.clone(),
+					layer: overlay::current_layer(),
+					physics: self.scroll_physics,
+					snap: self.scroll_snap,
+					snap_children: Vec::new(),
+					on_reach_end: self.on_reach_end.clone(),
+				});
+				Some(content_id)
+			}
+			_ => None,
+		};
+		// Sticky children stay inside the element their siblings are laid out in
+		let parent = sticky::enter_parent(self.id, content_id.clone().or_else(|| clay_id.clone()));
+		let tooltip_shown = Cell::new(false);
 		ctx.c.with_styling(
 			|c| {
+				// Overlays above this element get the pointer first, then the containers drawn over it
+				let under_pointer = c.hovered() && !overlay::is_pointer_blocked();
+				overlay::hit(under_pointer);
+				let hovered = under_pointer && hover::enter(self.id, self.hover_through);
+				for pointer_ref in &self.pointer_refs {
+					pointer_ref.update(ctx.input_manager, hovered);
+				}
+				if scrolls && let Some(pull_to_refresh) = &self.pull_to_refresh {
+					let mut scroll_state = self.scroll_state.borrow_mut();
+					pull_to_refresh.update(ctx.input_manager, &mut scroll_state, hovered, self.id);
+				}
+				// Where the source was grabbed is measured from its bounds in the last frame
+				let bounds = clay_id
+					.as_ref()
+					.filter(|_| self.draggable.is_some())
+					.and_then(|clay_id| c.bounding_box(clay_id.clone()));
+				let drag_over = drag_and_drop::update(self, ctx.input_manager, hovered, bounds);
 				let mut clickable_state = self.clickable_state.borrow_mut();
 				if let Some(clickable) = &self.clickable {
-					clickable.update(ctx.input_manager, &mut clickable_state, c.hovered());
+					clickable.update(ctx.input_manager, &mut clickable_state, hovered, node_id);
+				}
+				if let Some(keyboard) = &self.keyboard {
+					keyboard.update(ctx.input_manager, clickable_state.is_focused());
+				}
+				if let Some(tooltip) = &self.tooltip {
+					tooltip_shown.set(tooltip.update(&mut clickable_state, hovered));
 				}
 				let mut declaration = Declaration::new();
+				if let Some(clay_id) = &clay_id {
+					declaration.id(clay_id.clone());
+				}
+				// Styles of the current interaction win over the focus styles
 				let mut effective_style = self.style.clone();
-				if c.hovered() {
+				if self.accessibility.selected == Some(true) {
+					effective_style = (self.style_if_selected)(effective_style);
+				}
+				if clickable_state.is_indirectly_focused() {
+					effective_style = (self.style_if_focus_within)(effective_style);
+				}
+				if clickable_state.is_focused() {
+					effective_style = (self.style_if_focused)(effective_style);
+				}
+				if hovered {
 					effective_style = (self.style_if_hovered)(effective_style);
 				}
-
 				if clickable_state.down {
 					effective_style = (self.style_if_pressed)(effective_style);
 				}
-				if clickable_state.is_focused() {
-					effective_style = (self.style_if_focused)(effective_style);
-					println!("is_focused")
+				if clickable_state.key_press {
+					effective_style = (self.style_if_active)(effective_style);
+				}
+				if drag_over {
+					effective_style = (self.style_if_drag_over)(effective_style);
+				}
+				if crate::prefers_high_contrast() {
+					effective_style = effective_style.high_contrast(
+						self.clickable.is_some(),
+						clickable_state.is_focused(),
+					);
+				}
+				if let Some(index) = inspector_entry {
+					inspector::set_details(
+						index,
+						format!("{effective_style:#?}").lines().map(String::from).collect(),
+						inspector::EntryLayout {
+							padding: effective_style.padding,
+							gap: effective_style.gap,
+							direction: Some(effective_style.direction),
+							sizing: Some(format!(
+								"{} × {}",
+								sizing_label(&effective_style.size.0),
+								sizing_label(&effective_style.size.1)
+							)),
+						},
+					);
 				}
 				declaration
 					.layout()
@@ -470,6 +1202,42 @@ impl Element for Container {
 					.left(effective_style.border.width.left)
 					.end()
 					.background_color(effective_style.background_color);
+				let shadow = effective_style
+					.shadow
+					.or_else(|| Shadow::elevation(effective_style.elevation));
+				if shadow.is_some() || effective_style.ring.is_some() {
+					let background = self
+						.custom_element
+						.get_or_init(|| CustomElement::Background {
+							shadow,
+							ring: effective_style.ring,
+						});
+					declaration.custom_element(background);
+				}
+				if let (Some((offset, attach_point, _)), Some(z_index)) = (self.floating, z_index) {
+					declaration
+						.floating()
+						.offset(Vector2::new(offset.0, offset.1))
+						.attach_points(attach_point.element, attach_point.target)
+						.attach_to(if attach_point.root {
+							FloatingAttachToElement::Root
+						} else {
+							FloatingAttachToElement::Parent
+						})
+						.z_index(z_index)
+						.end();
+				}
+				if scrolls {
+					let offset = self.scroll_state.borrow().offset;
+					declaration.clip(
+						self.scroll.0,
+						self.scroll.1,
+						Vector2::new(-offset.0, -offset.1),
+					);
+				} else if self.sticky {
+					// Brackets the render commands of the container, to pin them, see `sticky::apply`
+					declaration.clip(true, false, Vector2::new(0., 0.));
+				}
 				declaration
 			},
 			|c| {
@@ -478,11 +1246,78 @@ impl Element for Container {
 					font_manager: &mut *ctx.font_manager,
 					input_manager: ctx.input_manager,
 				};
-				for child in &self.children {
-					child.render(&mut child_ctx);
+				drag_and_drop::render_preview(self, &mut child_ctx);
+				if let (Some(tooltip), Some(clay_id)) = (&self.tooltip, &clay_id)
+					&& tooltip_shown.get()
+				{
+					tooltip.render(&mut child_ctx, clay_id.clone(), &self.clay_id);
 				}
+				let Some(content_id) = content_id else {
+					for child in &self.children {
+						child.render(&mut child_ctx);
+					}
+					return;
+				};
+				child_ctx.c.with_styling(
+					|_| {
+						let fit_or_grow = |scrolls| {
+							if scrolls {
+								Sizing::Fit(0., f32::MAX)
+							} else {
+								Sizing::Grow(0., f32::MAX)
+							}
+						};
+						let mut declaration = Declaration::new();
+						declaration
+							.id(content_id)
+							.layout()
+							.direction(match self.style.direction {
+								Direction::Row => LayoutDirection::LeftToRight,
+								Direction::Column => LayoutDirection::TopToBottom,
+							})
+							.width(fit_or_grow(self.scroll.0))
+							.height(fit_or_grow(self.scroll.1))
+							.child_gap(self.style.gap)
+							.child_alignment(Alignment::new(self.style.justify, self.style.align))
+							.end();
+						declaration
+					},
+					|c| {
+						let mut content_ctx = RenderContext {
+							c,
+							font_manager: &mut *child_ctx.font_manager,
+							input_manager: child_ctx.input_manager,
+						};
+						if let Some(pull_to_refresh) = &self.pull_to_refresh {
+							let scroll_state = *self.scroll_state.borrow();
+							pull_to_refresh.render(&mut content_ctx, &scroll_state);
+						}
+						let snaps_to_children = self
+							.scroll_snap
+							.is_some_and(|snap| snap.points == SnapPoints::Children);
+						for child in &self.children {
+							scroll::mark_snap_child(snaps_to_children);
+							child.render(&mut content_ctx);
+						}
+						scroll::mark_snap_child(false);
+					},
+				);
 			},
 		);
+		sticky::leave_parent(parent);
+		if let Some(outer_z_index) = outer_z_index {
+			element_ids::leave_floating(outer_z_index);
+		}
+		hover::leave(self.id);
+		if scrolls {
+			scroll::pop_container();
+		}
+		if inspector_entry.is_some() {
+			inspector::pop_entry();
+		}
+		if is_accessibility_node {
+			accessibility::pop_node();
+		}
 	}
 	fn focus_nodes(&self) -> std::collections::HashSet<uuid::Uuid> {
 		let mut nodes = self.children.focus_nodes();
@@ -492,3 +1327,175 @@ impl Element for Container {
 		nodes
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{AttachPoint, NamedKey, Text, testing::TestHarness, use_state};
+
+	fn fixed_tiles(_: ()) -> Box<dyn Element> {
+		Box::new(
+			Container::new()
+				.column()
+				.child(Container::new().id("tile").size(80., 40.))
+				.child(Container::new().id("narrow").max_width(50.).w_fixed(80.))
+				.child(Container::new().id("wide").w_fixed(80.).min_width(100.)),
+		)
+	}
+
+	#[test]
+	fn test_fixed_sizes_keep_within_the_min_and_max() {
+		let harness = TestHarness::new(fixed_tiles, (), (400., 300.));
+		let size = |id| {
+			let (_, _, width, height) = harness.bounds_of(id).unwrap();
+			(width, height)
+		};
+		assert_eq!(size("tile"), (80., 40.));
+		assert_eq!(size("narrow").0, 50.);
+		assert_eq!(size("wide").0, 100.);
+	}
+
+	fn dropdown(_: ()) -> Box<dyn Element> {
+		let menu = Container::new().id("menu").size(80., 200.);
+		let menu = menu.floating((0., 4.), AttachPoint::below(), 1);
+		Box::new(
+			Container::column()
+				.child(Container::new().id("button").size(100., 30.).child(menu))
+				.child(Container::new().id("next").size(100., 30.)),
+		)
+	}
+
+	#[test]
+	fn test_floating_containers_are_out_of_the_layout() {
+		let harness = TestHarness::new(dropdown, (), (400., 300.));
+		let (x, y, width, height) = harness.bounds_of("button").unwrap();
+		assert_eq!((width, height), (100., 30.));
+		assert_eq!(harness.bounds_of("menu").unwrap(), (x, y + 34., 80., 200.));
+		assert_eq!(harness.bounds_of("next").unwrap().1, y + 30.);
+	}
+
+	fn focus_styles(_: ()) -> Box<dyn Element> {
+		Box::new(
+			Container::new()
+				.focus_container()
+				.padding_all(4)
+				.style_if_focus_within(|style| style.background_color((0, 0, 255, 255)))
+				.child(
+					Container::new()
+						.focusable()
+						.on_click(|| {})
+						.style_if_focused(|style| style.background_color((0, 255, 0, 255)))
+						.style_if_active(|style| style.background_color((255, 0, 0, 255)))
+						.child(Text::new("Button")),
+				),
+		)
+	}
+
+	#[test]
+	fn test_focus_and_active_styles() {
+		let has_rect = |harness: &TestHarness<_, _>, color: &str| {
+			harness
+				.render_commands()
+				.iter()
+				.any(|command| command.starts_with("rect ") && command.contains(color))
+		};
+		let mut harness = TestHarness::new(focus_styles, (), (400., 300.));
+		assert!(!has_rect(&harness, "rgba(0, 255, 0, 255)"));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		assert!(has_rect(&harness, "rgba(0, 0, 255, 255)"));
+		assert!(has_rect(&harness, "rgba(0, 255, 0, 255)"));
+		harness.key_down(Key::Named(NamedKey::Space));
+		assert!(has_rect(&harness, "rgba(255, 0, 0, 255)"));
+		harness.key_up(Key::Named(NamedKey::Space));
+		assert!(!has_rect(&harness, "rgba(255, 0, 0, 255)"));
+	}
+
+	#[test]
+	fn test_ring_progress_is_drawn() {
+		let gauge = |_: ()| -> Box<dyn Element> {
+			Box::new(Container::new().padding_all(20).ring_progress(0.25, 4.))
+		};
+		let mut harness = TestHarness::new(gauge, (), (400., 300.));
+		assert!(
+			harness
+				.render_commands()
+				.iter()
+				.any(|command| command.starts_with("background ") && command.contains("ring=0.25"))
+		);
+		harness.render_png();
+	}
+
+	#[test]
+	fn test_elevation_draws_a_shadow() {
+		let card =
+			|_: ()| -> Box<dyn Element> { Box::new(Container::new().padding_all(20).elevation(3)) };
+		let mut harness = TestHarness::new(card, (), (400., 300.));
+		let commands = harness.render_commands();
+		assert!(
+			commands
+				.iter()
+				.any(|command| command.starts_with("background ") && command.contains("shadow=0 4 blur=10"))
+		);
+		assert_eq!(crate::Shadow::elevation(0), None);
+		assert_eq!(crate::Shadow::elevation(9), crate::Shadow::elevation(5));
+	}
+
+	/// Two popups over each other, the first one declared being the more elevated.
+	fn stacked_popups(_: ()) -> Box<dyn Element> {
+		let (clicked, set_clicked) = use_state("none");
+		let set_clicked = Rc::new(set_clicked);
+		let popup = |id: &'static str, elevation: u8| {
+			let set_clicked = set_clicked.clone();
+			Container::new()
+				.id(id)
+				.size(100., 100.)
+				.floating((0., 0.), AttachPoint::below(), 1)
+				.elevation(elevation)
+				.on_click(move || set_clicked(id))
+		};
+		Box::new(
+			Container::column()
+				.child(
+					Container::new()
+						.size(100., 20.)
+						.child(popup("dialog", 5))
+						.child(popup("menu", 3)),
+				)
+				.child(Text::new(format!("clicked {clicked}"))),
+		)
+	}
+
+	#[test]
+	fn test_elevation_orders_floating_containers() {
+		let mut harness = TestHarness::new(stacked_popups, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("dialog").unwrap();
+		assert_eq!(harness.bounds_of("menu").unwrap().0, x);
+		assert_eq!(
+			element_ids::element_at((x + 50., y + 50.)).as_deref(),
+			Some("dialog")
+		);
+		harness.click(x + 50., y + 50.);
+		harness.expect_label("clicked dialog");
+	}
+
+	#[test]
+	fn test_shadow_replaces_the_elevation() {
+		let popup = |_: ()| -> Box<dyn Element> {
+			Box::new(
+				Container::new()
+					.padding_all(20)
+					.rounded(8.)
+					.elevation(3)
+					.shadow((0., 8.), 24., -4., (0, 0, 0, 120)),
+			)
+		};
+		let mut harness = TestHarness::new(popup, (), (400., 300.));
+		let commands = harness.render_commands();
+		assert!(
+			commands
+				.iter()
+				.any(|command| command.contains("shadow=0 8 blur=24 spread=-4"))
+		);
+		harness.render_png();
+	}
+}
@@ -1,18 +1,43 @@
+use std::time::{Duration, Instant};
+
 use uuid::Uuid;
 
 use crate::{
-	begin_component, end_component, focus_system::GLOBAL_FOCUS_MANAGER, input::Key, use_entity, use_memo, use_state, Container, Element, InputManager, NamedKey
+	accessibility::{self, AccessibilityAction}, begin_component, end_component, focus_system::GLOBAL_FOCUS_MANAGER, input::Key, use_entity, use_memo, use_state, Container, Element, InputManager, NamedKey
 };
 
+/// Time a press is held before [`Container::repeat_while_held`] starts repeating clicks.
+const REPEAT_DELAY: Duration = Duration::from_millis(400);
+
 /// Estado interno do Clickable para tracking de hover/press
 #[derive(Default, Clone, Copy)]
 pub struct ClickableState {
 	pub hovered: bool,
+	/// `true` on the frame the element is clicked.
 	pub pressed: bool,
+	/// `true` while a press that would click the element is held.
 	pub down: bool,
 	pub right_down: bool,
 	pub right_pressed: bool,
+	pub focused: bool,
 	pub focus_node_id: Option<Uuid>,
+	pub(crate) focus_requested: bool,
+	/// The left button was pressed over the element and is still held.
+	pub(crate) pointer_press: bool,
+	/// Enter or Space was pressed while the element was focused and is still held.
+	pub(crate) key_press: bool,
+	/// When the current press started being held over the element.
+	pub(crate) hold_start: Option<Instant>,
+	/// Number of clicks repeated since `hold_start`.
+	pub(crate) repeats: u32,
+	/// The press was used by a long press or repeated clicks, so releasing it doesn't click.
+	pub(crate) press_handled: bool,
+	/// When the pointer entered the element, while waiting for the hover delay.
+	pub(crate) hover_start: Option<Instant>,
+	/// When the pointer entered the element, while waiting for the delay of its tooltip.
+	pub(crate) tooltip_hover_start: Option<Instant>,
+	/// Whether the element was focused with the keyboard, which shows its tooltip, while it is focused.
+	pub(crate) tooltip_keyboard_focus: Option<bool>,
 }
 
 impl ClickableState {
@@ -41,9 +66,16 @@ impl ClickableState {
 
 pub(crate) struct Clickable {
 	pub(crate) on_click: Option<Box<dyn Fn()>>,
+	pub(crate) on_press: Option<Box<dyn Fn()>>,
+	pub(crate) on_release: Option<Box<dyn Fn()>>,
+	pub(crate) on_long_press: Option<(Duration, Box<dyn Fn()>)>,
+	pub(crate) repeat_interval: Option<Duration>,
+	pub(crate) hover_delay: Option<Duration>,
 	pub(crate) on_mouse_enter: Option<Box<dyn Fn()>>,
 	pub(crate) on_mouse_leave: Option<Box<dyn Fn()>>,
 	pub(crate) on_right_click: Option<Box<dyn Fn()>>,
+	pub(crate) on_focus: Option<Box<dyn Fn()>>,
+	pub(crate) on_blur: Option<Box<dyn Fn()>>,
 	pub(crate) focus_node_id: Option<Uuid>,
 }
 
@@ -51,22 +83,120 @@ impl Clickable {
 	pub fn new() -> Self {
 		Self {
 			on_click: None,
+			on_press: None,
+			on_release: None,
+			on_long_press: None,
+			repeat_interval: None,
+			hover_delay: None,
 			on_mouse_enter: None,
 			on_mouse_leave: None,
 			on_right_click: None,
+			on_focus: None,
+			on_blur: None,
 			focus_node_id: None,
 		}
 	}
+	/// Fires the long press once it has been held long enough, and returns `true` when a held press repeats a click.
+	fn update_hold(&self, state: &mut ClickableState) -> bool {
+		if !state.down {
+			state.hold_start = None;
+			state.repeats = 0;
+			return false;
+		}
+		let now = crate::frame_scheduler::now();
+		let start = *state.hold_start.get_or_insert(now);
+		if let Some((duration, on_long_press)) = &self.on_long_press
+			&& !state.press_handled
+		{
+			if now - start >= *duration {
+				state.press_handled = true;
+				on_long_press();
+			} else {
+				crate::frame_scheduler::request_frame_at(start + *duration);
+			}
+		}
+		let Some(interval) = self.repeat_interval else {
+			return false;
+		};
+		// The first click happens right away, the next ones after a delay
+		let due = |repeats| match repeats {
+			0 => start,
+			repeats => start + REPEAT_DELAY + interval * (repeats - 1),
+		};
+		let repeated = now >= due(state.repeats);
+		if repeated {
+			state.repeats += 1;
+			state.press_handled = true;
+		}
+		crate::frame_scheduler::request_frame_at(due(state.repeats));
+		repeated
+	}
+
+	/// Returns whether the pointer counts as hovering, once it has rested on the element for the hover delay.
+	fn hover_intent(&self, state: &mut ClickableState, is_hovered: bool) -> bool {
+		if !is_hovered {
+			state.hover_start = None;
+			return false;
+		}
+		let Some(delay) = self.hover_delay.filter(|_| !state.hovered) else {
+			return true;
+		};
+		let now = crate::frame_scheduler::now();
+		let start = *state.hover_start.get_or_insert(now);
+		if now - start >= delay {
+			return true;
+		}
+		crate::frame_scheduler::request_frame_at(start + delay);
+		false
+	}
+
 	pub fn update(
 		&self,
 		input_manager: &dyn InputManager,
 		state: &mut ClickableState,
 		is_hovered: bool,
+		accessibility_node_id: u64,
 	) {
 		state.focus_node_id = self.focus_node_id;
-		state.down = (input_manager.is_mouse_button_pressed(0) && is_hovered) || (input_manager.is_key_pressed(Key::Named(NamedKey::Enter)) && state.is_focused());
-		state.right_down = (input_manager.is_mouse_button_pressed(1) && is_hovered) || (input_manager.is_key_pressed(Key::Named(NamedKey::ContextMenu)) && state.is_focused());
-		let is_clicked = (input_manager.is_mouse_button_just_pressed(0) && is_hovered) || (input_manager.is_key_just_pressed(Key::Named(NamedKey::Enter)) && state.is_focused());
+		if accessibility::take_action(accessibility_node_id, &AccessibilityAction::Focus) {
+			state.set_focus();
+		}
+		let accessibility_click =
+			accessibility::take_action(accessibility_node_id, &AccessibilityAction::Click);
+		let activation_down = input_manager.is_key_pressed(Key::Named(NamedKey::Enter)) || input_manager.is_key_pressed(Key::Named(NamedKey::Space));
+		let activation_pressed = input_manager.is_key_just_pressed(Key::Named(NamedKey::Enter)) || input_manager.is_key_just_pressed(Key::Named(NamedKey::Space));
+		let was_pressing = state.pointer_press || state.key_press;
+
+		// A click needs the press and the release to happen over the element, so dragging away cancels it
+		let mut released_over = false;
+		if input_manager.is_mouse_button_just_pressed(0) && is_hovered {
+			state.pointer_press = true;
+		} else if state.pointer_press && !input_manager.is_mouse_button_pressed(0) {
+			state.pointer_press = false;
+			released_over |= is_hovered;
+		}
+		// Keyboard presses are cancelled by moving the focus away
+		if activation_pressed && state.is_focused() {
+			state.key_press = true;
+		} else if state.key_press && (!activation_down || !state.is_focused()) {
+			state.key_press = false;
+			released_over |= state.is_focused();
+		}
+
+		let is_pressing = state.pointer_press || state.key_press;
+		if is_pressing != was_pressing {
+			let callback = if is_pressing { &self.on_press } else { &self.on_release };
+			if let Some(callback) = callback {
+				callback();
+			}
+		}
+		state.down = (state.pointer_press && is_hovered) || state.key_press;
+		let is_repeated = self.update_hold(state);
+		let is_clicked = accessibility_click || is_repeated || (released_over && !state.press_handled);
+		if !is_pressing {
+			state.press_handled = false;
+		}
+		state.right_down = (input_manager.is_mouse_button_pressed(1) && is_hovered) || (menu_key_pressed(input_manager, false) && state.is_focused());
 		if is_clicked != state.pressed {
 			state.pressed = is_clicked;
 		}
@@ -76,7 +206,7 @@ impl Clickable {
 				on_click();
 			}
 		}
-		let is_right_clicked = (input_manager.is_mouse_button_just_pressed(1) && is_hovered) || (input_manager.is_key_just_pressed(Key::Named(NamedKey::ContextMenu)) && state.is_focused());
+		let is_right_clicked = (input_manager.is_mouse_button_just_pressed(1) && is_hovered) || (menu_key_pressed(input_manager, true) && state.is_focused());
 		if is_right_clicked != state.right_pressed {
 			state.right_pressed = is_right_clicked;
 		}
@@ -87,6 +217,7 @@ impl Clickable {
 				on_right_click();
 			}
 		}
+		let is_hovered = self.hover_intent(state, is_hovered);
 		if is_hovered != state.hovered {
 			state.hovered = is_hovered;
 			if is_hovered {
@@ -99,20 +230,79 @@ impl Clickable {
 				}
 			}
 		}
+		let is_focused = state.is_focused();
+		if is_focused != state.focused {
+			state.focused = is_focused;
+			if is_focused {
+				if let Some(on_focus) = &self.on_focus {
+					on_focus();
+				}
+			} else if let Some(on_blur) = &self.on_blur {
+				on_blur();
+			}
+		}
 	}
 }
+
+/// Returns `true` while a key opening context menus is held, the menu key or Shift+F10, or only on the frame it is
+/// pressed if `just`.
+fn menu_key_pressed(input_manager: &dyn InputManager, just: bool) -> bool {
+	let pressed = |key| {
+		if just {
+			input_manager.is_key_just_pressed(Key::Named(key))
+		} else {
+			input_manager.is_key_pressed(Key::Named(key))
+		}
+	};
+	pressed(NamedKey::ContextMenu)
+		|| (pressed(NamedKey::F10) && input_manager.is_key_pressed(Key::Named(NamedKey::Shift)))
+}
+
 impl Container {
 	fn ensure_clickable(&mut self) {
 		if self.clickable.is_none() {
 			self.clickable = Some(Clickable::new());
 		}
 	}
+	/// Called when the element is released after being pressed, if the pointer is still over it.
+	///
+	/// Enter and Space click the focused element when they are released.
 	pub fn on_click(mut self, handler: impl Fn() + 'static) -> Self {
 		self.ensure_clickable();
 		self.clickable.as_mut().unwrap().on_click = Some(Box::new(handler));
 		self
 	}
 
+	/// Called when a press on the element starts, before it is known whether it will click.
+	pub fn on_press(mut self, handler: impl Fn() + 'static) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().on_press = Some(Box::new(handler));
+		self
+	}
+
+	/// Called instead of [`on_click`](Self::on_click) once a press is held over the element for `duration`.
+	pub fn on_long_press(mut self, duration: Duration, handler: impl Fn() + 'static) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().on_long_press = Some((duration, Box::new(handler)));
+		self
+	}
+
+	/// Clicks as soon as the element is pressed, then every `interval` while it is held, after a short delay.
+	///
+	/// Useful for steppers and volume buttons.
+	pub fn repeat_while_held(mut self, interval: Duration) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().repeat_interval = Some(interval);
+		self
+	}
+
+	/// Called when a press started with [`on_press`](Self::on_press) ends, whether it clicked or was cancelled.
+	pub fn on_release(mut self, handler: impl Fn() + 'static) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().on_release = Some(Box::new(handler));
+		self
+	}
+
 	pub fn on_mouse_enter(mut self, handler: impl Fn() + 'static) -> Self {
 		self.ensure_clickable();
 		self.clickable.as_mut().unwrap().on_mouse_enter = Some(Box::new(handler));
@@ -125,11 +315,59 @@ impl Container {
 		self
 	}
 
+	/// Waits until the pointer rests on the element for `ms` milliseconds before calling
+	/// [`on_mouse_enter`](Self::on_mouse_enter), so moving across the element doesn't trigger it.
+	///
+	/// [`ClickableState::hovered`] follows the delay too, while hover styles apply right away.
+	pub fn hover_delay(mut self, ms: u64) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().hover_delay = Some(Duration::from_millis(ms));
+		self
+	}
+
+	/// Called when the element is right clicked, or when the menu key or Shift+F10 is pressed while it is focused, to
+	/// open a context menu.
 	pub fn on_right_click(mut self, handler: impl Fn() + 'static) -> Self {
 		self.ensure_clickable();
 		self.clickable.as_mut().unwrap().on_right_click = Some(Box::new(handler));
 		self
 	}
+	pub fn on_focus(mut self, handler: impl Fn() + 'static) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().on_focus = Some(Box::new(handler));
+		self
+	}
+
+	pub fn on_blur(mut self, handler: impl Fn() + 'static) -> Self {
+		self.ensure_clickable();
+		self.clickable.as_mut().unwrap().on_blur = Some(Box::new(handler));
+		self
+	}
+
+	/// Binds the focus of this container to `focused`, making it focusable if it isn't already.
+	///
+	/// Focus is requested when the value changes to `true` and released when it changes to `false`,
+	/// so the binding can be kept in sync with component state through `on_focus`/`on_blur`.
+	pub fn focused(mut self, focused: bool) -> Self {
+		if self.clickable.as_ref().and_then(|c| c.focus_node_id).is_none() {
+			self = self.focusable();
+		}
+		let focus_node_id = self.clickable.as_ref().and_then(|c| c.focus_node_id).unwrap();
+		{
+			let mut state = self.clickable_state.borrow_mut();
+			if focused != state.focus_requested {
+				GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| {
+					if focused {
+						f.set_focus(focus_node_id);
+					} else if f.focused() == Some(focus_node_id) {
+						f.blur();
+					}
+				});
+				state.focus_requested = focused;
+			}
+		}
+		self
+	}
 	fn add_focus_node(mut self, skip: bool) -> Self {
 		self.ensure_clickable();
 		let clickable = self.clickable.as_mut().unwrap();
@@ -156,4 +394,144 @@ impl Container {
 	pub fn focus_container(mut self) -> Self {
 		self.add_focus_node(true)
 	}
+	/// Traps keyboard focus inside the container while it is shown, like a menu or a dialog.
+	///
+	/// When the container appears, its first focusable element is focused, and Tab and the arrow keys only move
+	/// focus between the elements inside it. When it goes away, the element that was focused before it appeared
+	/// gets focus back.
+	pub fn focus_scope(mut self) -> Self {
+		self = self.add_focus_node(true);
+		let focus_node_id = self.clickable.as_ref().and_then(|c| c.focus_node_id).unwrap();
+		GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.set_node_scope(focus_node_id));
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Text, testing::TestHarness};
+
+	fn counter(_: ()) -> Box<dyn Element> {
+		let (count, set_count) = use_state(0);
+		Box::new(
+			Container::new()
+				.label("increment")
+				.focusable()
+				.on_click(move || set_count(count + 1))
+				.child(Text::new(format!("Count: {count}"))),
+		)
+	}
+
+	#[test]
+	fn test_click_fires_on_release_over_the_element() {
+		let mut harness = TestHarness::new(counter, (), (400., 300.));
+		let (x, y) = harness.expect_label("increment").center().unwrap();
+		harness.move_pointer(x, y);
+		harness.mouse_down(0);
+		harness.frame();
+		assert!(harness.find_by_label("Count: 0").is_some());
+		// Dragging away and releasing cancels the click
+		harness.move_pointer(399., 299.);
+		harness.mouse_up(0);
+		harness.frame();
+		assert!(harness.find_by_label("Count: 0").is_some());
+		harness.click(x, y);
+		assert!(harness.find_by_label("Count: 1").is_some());
+	}
+
+	fn long_press(_: ()) -> Box<dyn Element> {
+		let (label, set_label) = use_state("idle");
+		Box::new(
+			Container::new()
+				.label("button")
+				.on_click(move || set_label("clicked"))
+				.on_long_press(Duration::from_millis(20), move || set_label("long pressed"))
+				.child(Text::new(label)),
+		)
+	}
+
+	#[test]
+	fn test_long_press_replaces_the_click() {
+		let mut harness = TestHarness::new(long_press, (), (400., 300.));
+		let (x, y) = harness.expect_label("button").center().unwrap();
+		harness.move_pointer(x, y);
+		harness.mouse_down(0);
+		harness.advance_time(Duration::from_millis(30));
+		harness.frame();
+		assert!(harness.find_by_label("long pressed").is_some());
+		harness.mouse_up(0);
+		harness.frame();
+		assert!(harness.find_by_label("long pressed").is_some());
+		harness.click(x, y);
+		assert!(harness.find_by_label("clicked").is_some());
+	}
+
+	fn delayed_hover(_: ()) -> Box<dyn Element> {
+		let (hovered, set_hovered) = use_state(false);
+		let set_hovered = std::rc::Rc::new(set_hovered);
+		let set_left = set_hovered.clone();
+		Box::new(
+			Container::new()
+				.label("target")
+				.hover_delay(20)
+				.on_mouse_enter(move || set_hovered(true))
+				.on_mouse_leave(move || set_left(false))
+				.child(Text::new(if hovered { "hovered" } else { "idle" })),
+		)
+	}
+
+	#[test]
+	fn test_hover_delay() {
+		let mut harness = TestHarness::new(delayed_hover, (), (400., 300.));
+		let (x, y) = harness.expect_label("target").center().unwrap();
+		harness.move_pointer(x, y);
+		harness.frame();
+		assert!(harness.find_by_label("idle").is_some());
+		harness.advance_time(Duration::from_millis(30));
+		harness.frame();
+		assert!(harness.find_by_label("hovered").is_some());
+		harness.move_pointer(399., 299.);
+		harness.frame();
+		assert!(harness.find_by_label("idle").is_some());
+	}
+
+	/// Two clickable boxes, the second one drawn over the overflowing end of the first one.
+	fn overlapping(hover_through: bool) -> Box<dyn Element> {
+		let (below, set_below) = use_state(0);
+		let (above, set_above) = use_state(0);
+		let sized = |width: f32| {
+			Container::new()
+				.min_width(width)
+				.max_width(width)
+				.min_height(20.)
+				.max_height(20.)
+		};
+		let above_box = sized(50.).on_click(move || set_above(above + 1));
+		Box::new(
+			Container::new()
+				.column()
+				.child(
+					Container::new()
+						.row()
+						.child(sized(50.).child(sized(100.).on_click(move || set_below(below + 1))))
+						.child(if hover_through {
+							above_box.hover_through()
+						} else {
+							above_box
+						}),
+				)
+				.child(Text::new(format!("below {below} above {above}"))),
+		)
+	}
+
+	#[test]
+	fn test_only_the_topmost_container_is_hovered() {
+		let mut harness = TestHarness::new(overlapping, false, (400., 300.));
+		harness.click(75., 10.);
+		assert!(harness.find_by_label("below 0 above 1").is_some());
+		let mut harness = TestHarness::new(overlapping, true, (400., 300.));
+		harness.click(75., 10.);
+		assert!(harness.find_by_label("below 1 above 1").is_some());
+	}
 }
@@ -0,0 +1,748 @@
+use std::{
+	any::{Any, TypeId},
+	cell::RefCell,
+	path::{Path, PathBuf},
+	rc::Rc,
+	sync::{Arc, OnceLock},
+};
+
+use clay_layout::{
+	Color, Declaration,
+	elements::{FloatingAttachToElement, PointerCaptureMode},
+	layout::Sizing,
+	math::{BoundingBox, Vector2},
+};
+use uuid::Uuid;
+
+use crate::{
+	Container, ContainerStyle, Element, GlobalClosure, InputManager, Key, NamedKey, RenderContext,
+};
+
+/// Distance the pointer moves with the button held before a press turns into a drag.
+const THRESHOLD: f32 = 4.;
+
+/// The MIME type of a list of files, one URI per line.
+const URI_LIST: &str = "text/uri-list";
+
+/// An element being dragged, or pressed and about to be.
+struct Drag {
+	source: Uuid,
+	payload: Rc<dyn Any>,
+	/// Where the button was pressed.
+	start: (f32, f32),
+	/// Where the source was grabbed, from its top left corner.
+	grab: (f32, f32),
+	/// Size of the source, for the default preview.
+	size: (f32, f32),
+	/// The pointer moved past the threshold, so this is a drag and not a click.
+	active: bool,
+	origin: Origin,
+	/// The MIME type of the [`Offer`] of another app taken by the drop target under the pointer.
+	accepted: Option<String>,
+}
+
+/// Where a drag started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Origin {
+	/// A container of the app, dragged with the left button.
+	App,
+	/// Another app, which tells when the payload was dropped. The payload is an [`Offer`].
+	OtherApp { dropped: bool },
+}
+
+/// The payload of a container with [`drag_data`](Container::drag_data) but no [`draggable`](Container::draggable)
+/// payload, which no container of the app accepts.
+struct DataOnly;
+
+/// The data a drag from another app offers, in several MIME types.
+pub(crate) struct Offer {
+	pub(crate) mime_types: Vec<String>,
+	/// The data of each of the MIME types when it is known before the drop, otherwise it is read from the data
+	/// device once dropped.
+	pub(crate) data: Option<Vec<Vec<u8>>>,
+}
+
+impl Offer {
+	/// Offers `paths` as a list of URIs, for the drag events of winit which only tell about files.
+	pub(crate) fn files(paths: &[PathBuf]) -> Self {
+		Self {
+			mime_types: vec![URI_LIST.to_string()],
+			data: Some(vec![uri_list(paths).into_bytes()]),
+		}
+	}
+}
+
+/// Data being read from the app it was dragged from, which can arrive after the frame it was dropped in.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PendingData(Arc<OnceLock<Option<Vec<u8>>>>);
+
+impl PendingData {
+	fn ready(data: Option<Vec<u8>>) -> Self {
+		Self(Arc::new(OnceLock::from(data)))
+	}
+
+	/// Returns the data once it is read, `Some(None)` if it couldn't be.
+	pub(crate) fn get(&self) -> Option<Option<&[u8]>> {
+		self.0.get().map(Option::as_deref)
+	}
+
+	pub(crate) fn set(&self, data: Option<Vec<u8>>) {
+		let _ = self.0.set(data);
+	}
+}
+
+/// Files dragged from another app, like a file manager, given to the containers with an
+/// [`on_drop`](Container::on_drop) for this type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedFiles {
+	pub paths: Vec<PathBuf>,
+}
+
+impl DroppedFiles {
+	/// Reads the local files of a `text/uri-list`.
+	fn from_uri_list(uri_list: &[u8]) -> Self {
+		let paths = String::from_utf8_lossy(uri_list)
+			.lines()
+			.filter(|line| !line.starts_with('#'))
+			.filter_map(|uri| {
+				let path = uri.trim().strip_prefix("file://")?;
+				// The host is empty or localhost for local files
+				let path = path.strip_prefix("localhost").unwrap_or(path);
+				path
+					.starts_with('/')
+					.then(|| PathBuf::from(percent_decode(path)))
+			})
+			.collect();
+		Self { paths }
+	}
+}
+
+/// Data in a MIME type dragged from another app, given to the containers with an
+/// [`on_drop_data`](Container::on_drop_data) for this type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedData {
+	pub mime_type: String,
+	pub data: Vec<u8>,
+}
+
+/// Accepts the payloads dropped on a container, see [`Container::on_drop`] and [`Container::on_drop_data`].
+pub(crate) struct DropTarget {
+	accepts: fn(&dyn Any) -> bool,
+	on_drop: Rc<dyn Fn(&dyn Any)>,
+	/// The MIME types taken from other apps, preferred first, and what gets their data.
+	from_other_apps: Option<(Vec<String>, Rc<dyn Fn(DroppedData)>)>,
+}
+
+impl Default for DropTarget {
+	fn default() -> Self {
+		Self {
+			accepts: |_| false,
+			on_drop: Rc::new(|_: &dyn Any| {}),
+			from_other_apps: None,
+		}
+	}
+}
+
+impl DropTarget {
+	/// Returns the first of the MIME types of `offer` this target takes, in its order of preference.
+	fn mime_type_for(&self, offer: &Offer) -> Option<&str> {
+		let (mime_types, _) = self.from_other_apps.as_ref()?;
+		mime_types
+			.iter()
+			.find(|mime_type| offer.mime_types.contains(mime_type))
+			.map(String::as_str)
+	}
+}
+
+/// What a drop target under the pointer does with the payload.
+#[derive(Clone)]
+enum DropHandler {
+	App(Rc<dyn Fn(&dyn Any)>),
+	/// Reads the data of the drag of another app as a MIME type.
+	OtherApp(String, Rc<dyn Fn(DroppedData)>),
+}
+
+thread_local! {
+	static DRAG: RefCell<Option<Drag>> = const { RefCell::new(None) };
+	/// Drop targets accepting the payload under the pointer this frame, innermost last.
+	static TARGETS: RefCell<Vec<DropHandler>> = const { RefCell::new(Vec::new()) };
+	/// Data dropped from other apps that is still being read, with the MIME type it is read as and its drop target.
+	static RECEIVING: RefCell<Vec<(PendingData, String, Rc<dyn Fn(DroppedData)>)>> =
+		const { RefCell::new(Vec::new()) };
+}
+
+/// Starts dragging the source under the pointer and records the drop targets it is over, called while a container
+/// is declared. Returns `true` if the container is a drop target for the dragged payload and the pointer is over it.
+pub(crate) fn update(
+	container: &Container,
+	input: &dyn InputManager,
+	hovered: bool,
+	bounds: Option<BoundingBox>,
+) -> bool {
+	let pointer = input.mouse_position();
+	if let Some(payload) = &container.draggable
+		&& hovered
+		&& input.is_mouse_button_just_pressed(0)
+		&& DRAG.with_borrow(Option::is_none)
+	{
+		let (grab, size) = bounds.map_or(((0., 0.), (0., 0.)), |bounds| {
+			(
+				(pointer.0 - bounds.x, pointer.1 - bounds.y),
+				(bounds.width, bounds.height),
+			)
+		});
+		DRAG.set(Some(Drag {
+			source: container.id,
+			payload: payload.clone(),
+			start: pointer,
+			grab,
+			size,
+			active: false,
+			origin: Origin::App,
+			accepted: None,
+		}));
+	}
+	DRAG.with_borrow_mut(|drag| {
+		let Some(drag) = drag else {
+			return false;
+		};
+		if drag.source == container.id && !drag.active {
+			let moved = (pointer.0 - drag.start.0).hypot(pointer.1 - drag.start.1);
+			if moved >= THRESHOLD {
+				drag.active = true;
+				// The compositor moves the drag from now on, so it can leave the window
+				#[cfg(feature = "wayland")]
+				if !container.drag_data.is_empty() {
+					crate::data_device::start_drag(container.drag_data.clone().into());
+				}
+				crate::REQUEST_REDRAW.call();
+			}
+		}
+		if drag.source == container.id && drag.active {
+			// Releasing a dragged element doesn't click it
+			container.clickable_state.borrow_mut().press_handled = true;
+		}
+		let Some(target) = &container.drop_target else {
+			return false;
+		};
+		if !drag.active || !hovered {
+			return false;
+		}
+		let handler = match drag.payload.downcast_ref::<Offer>() {
+			Some(offer) if drag.origin != Origin::App => {
+				let mime_type = target.mime_type_for(offer);
+				mime_type
+					.zip(target.from_other_apps.as_ref())
+					.map(|(mime_type, (_, on_drop))| {
+						DropHandler::OtherApp(mime_type.to_string(), on_drop.clone())
+					})
+			}
+			_ => {
+				(target.accepts)(drag.payload.as_ref()).then(|| DropHandler::App(target.on_drop.clone()))
+			}
+		};
+		let over = handler.is_some();
+		if let Some(handler) = handler {
+			TARGETS.with_borrow_mut(|targets| targets.push(handler));
+		}
+		over
+	})
+}
+
+/// Returns `true` while a payload of type `T` is dragged, past the few pixels that start a drag.
+pub(crate) fn is_dragging<T: 'static>() -> bool {
+	DRAG.with_borrow(|drag| {
+		drag
+			.as_ref()
+			.is_some_and(|drag| drag.active && drag.payload.is::<T>())
+	})
+}
+
+/// Starts a drag from another app, entering the window with `offer`.
+pub(crate) fn enter_from_other_app(offer: Offer) {
+	DRAG.set(Some(Drag {
+		// No container of the app is the source
+		source: Uuid::nil(),
+		payload: Rc::new(offer),
+		start: (0., 0.),
+		grab: (0., 0.),
+		size: (0., 0.),
+		active: true,
+		origin: Origin::OtherApp { dropped: false },
+		accepted: None,
+	}));
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Drops the drag from another app on the drop target under the pointer at the end of the frame.
+pub(crate) fn drop_from_other_app() {
+	DRAG.with_borrow_mut(|drag| {
+		if let Some(drag) = drag.as_mut().filter(|drag| drag.origin != Origin::App) {
+			drag.origin = Origin::OtherApp { dropped: true };
+		}
+	});
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Cancels the drag from another app, which left the window.
+pub(crate) fn leave_from_other_app() {
+	DRAG.with_borrow_mut(|drag| {
+		if drag.as_ref().is_some_and(|drag| drag.origin != Origin::App) {
+			*drag = None;
+		}
+	});
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Cancels the drag of the app, which was dropped in another app or cancelled out of the window.
+pub(crate) fn end_out_of_window() {
+	DRAG.with_borrow_mut(|drag| {
+		if drag.as_ref().is_some_and(|drag| drag.origin == Origin::App) {
+			*drag = None;
+		}
+	});
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Drops the payload on the innermost drop target under the pointer once the button is released, or cancels the
+/// drag with Escape. Data dropped from other apps is given to its drop target once it is read.
+pub(crate) fn end_frame(input: &dyn InputManager) {
+	let targets = TARGETS.with_borrow_mut(std::mem::take);
+	if let Some(origin) = DRAG.with_borrow(|drag| drag.as_ref().map(|drag| drag.origin)) {
+		end_drag(input, origin, targets.last());
+	}
+
+	let received = RECEIVING.with_borrow_mut(|receiving| {
+		receiving
+			.extract_if(.., |(data, _, _)| data.get().is_some())
+			.collect::<Vec<_>>()
+	});
+	for (data, mime_type, on_drop) in received {
+		if let Some(Some(data)) = data.get() {
+			on_drop(DroppedData {
+				mime_type,
+				data: data.to_vec(),
+			});
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+}
+
+fn end_drag(input: &dyn InputManager, origin: Origin, target: Option<&DropHandler>) {
+	#[cfg(feature = "wayland")]
+	if let Origin::OtherApp { .. } = origin {
+		// Tells the other app whether the data can be dropped under the pointer, in which type
+		let mime_type = match target {
+			Some(DropHandler::OtherApp(mime_type, _)) => Some(mime_type.clone()),
+			_ => None,
+		};
+		let changed = DRAG.with_borrow_mut(|drag| {
+			let drag = drag.as_mut()?;
+			(drag.accepted != mime_type).then(|| drag.accepted = mime_type.clone())
+		});
+		if changed.is_some() {
+			crate::data_device::accept(mime_type.as_deref());
+		}
+	}
+
+	let (released, cancelled) = match origin {
+		Origin::App => (
+			!input.is_mouse_button_pressed(0),
+			input.is_key_just_pressed(Key::Named(NamedKey::Escape)),
+		),
+		// The button is held in the other app, which tells when the payload is dropped
+		Origin::OtherApp { dropped } => (dropped, false),
+	};
+	if !released && !cancelled {
+		return;
+	}
+	let Some(drag) = DRAG.take() else {
+		return;
+	};
+	if !drag.active {
+		return;
+	}
+	let target = target.filter(|_| released && !cancelled);
+	if let Some(offer) = drag.payload.downcast_ref::<Offer>()
+		&& origin != Origin::App
+	{
+		let (mime_type, on_drop) = match target {
+			Some(DropHandler::OtherApp(mime_type, on_drop)) => (Some(mime_type.as_str()), Some(on_drop)),
+			_ => (None, None),
+		};
+		// Refused when there is no drop target for it
+		let data = receive(offer, mime_type);
+		if let (Some(data), Some(mime_type), Some(on_drop)) = (data, mime_type, on_drop) {
+			RECEIVING.with_borrow_mut(|receiving| {
+				receiving.push((data, mime_type.to_string(), on_drop.clone()));
+			});
+		}
+	} else if let Some(DropHandler::App(on_drop)) = target {
+		on_drop(drag.payload.as_ref());
+	}
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Starts reading the data of `offer` dropped on the window as `mime_type`, or refuses it with `None`.
+fn receive(offer: &Offer, mime_type: Option<&str>) -> Option<PendingData> {
+	match &offer.data {
+		Some(data) => {
+			let index = offer
+				.mime_types
+				.iter()
+				.position(|offered| Some(offered.as_str()) == mime_type)?;
+			Some(PendingData::ready(data.get(index).cloned()))
+		}
+		#[cfg(feature = "wayland")]
+		None => crate::data_device::receive(mime_type),
+		#[cfg(not(feature = "wayland"))]
+		None => None,
+	}
+}
+
+/// Returns `paths` as a `text/uri-list`.
+fn uri_list(paths: &[PathBuf]) -> String {
+	paths
+		.iter()
+		.map(|path| format!("file://{}\r\n", percent_encode(path)))
+		.collect()
+}
+
+/// Escapes the bytes of `path` that can't be in a URI.
+fn percent_encode(path: &Path) -> String {
+	path
+		.as_os_str()
+		.as_encoded_bytes()
+		.iter()
+		.map(|&byte| match byte {
+			b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+				char::from(byte).to_string()
+			}
+			_ => format!("%{byte:02X}"),
+		})
+		.collect()
+}
+
+/// Unescapes the `%XX` of a URI.
+fn percent_decode(uri: &str) -> String {
+	let mut bytes = Vec::with_capacity(uri.len());
+	let mut rest = uri.as_bytes();
+	while let Some((&byte, after)) = rest.split_first() {
+		let escaped = (byte == b'%')
+			.then(|| after.get(..2))
+			.flatten()
+			.and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+		match escaped {
+			Some(escaped) => {
+				bytes.push(escaped);
+				rest = &after[2..];
+			}
+			None => {
+				bytes.push(byte);
+				rest = after;
+			}
+		}
+	}
+	String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Draws the preview of the container under the pointer while it is dragged, above everything else.
+pub(crate) fn render_preview<'clay: 'render, 'render>(
+	container: &'render Container,
+	ctx: &mut RenderContext<'clay, 'render, '_>,
+) {
+	let pointer = ctx.input_manager.mouse_position();
+	let Some((position, size)) = DRAG.with_borrow(|drag| {
+		let drag = drag
+			.as_ref()
+			.filter(|drag| drag.active && drag.source == container.id)?;
+		Some((
+			(pointer.0 - drag.grab.0, pointer.1 - drag.grab.1),
+			drag.size,
+		))
+	}) else {
+		return;
+	};
+	ctx.c.with_styling(
+		|_| {
+			let mut declaration = Declaration::new();
+			declaration
+				.floating()
+				.offset(Vector2::new(position.0, position.1))
+				.attach_to(FloatingAttachToElement::Root)
+				.z_index(i16::MAX)
+				// Drop targets below the preview get the pointer
+				.pointer_capture_mode(PointerCaptureMode::Passthrough)
+				.end();
+			if container.drag_preview.is_none() {
+				declaration
+					.layout()
+					.width(Sizing::Fixed(size.0))
+					.height(Sizing::Fixed(size.1))
+					.end()
+					.corner_radius()
+					.top_left(6.)
+					.top_right(6.)
+					.bottom_left(6.)
+					.bottom_right(6.)
+					.end()
+					.background_color(Color::rgba(53., 132., 228., 80.));
+			}
+			declaration
+		},
+		|c| {
+			if let Some(preview) = &container.drag_preview {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				preview.render(&mut ctx);
+			}
+		},
+	);
+}
+
+impl Container {
+	/// Lets the container be dragged onto the containers with an [`on_drop`](Self::on_drop) for the type of
+	/// `payload`, like an item moved from a list to another.
+	///
+	/// The drag starts once the pointer moved a few pixels with the left button held, so the container can still be
+	/// clicked. A translucent box of its size follows the pointer, unless a [`drag_preview`](Self::drag_preview) is
+	/// given. Escape cancels the drag. The payload stays in the app, give it [`drag_data`](Self::drag_data) to drop
+	/// it in other apps too.
+	pub fn draggable<T: 'static>(mut self, payload: T) -> Self {
+		self.draggable = Some(Rc::new(payload));
+		self
+	}
+
+	/// Lets the container be dragged out of the window and dropped in other apps, which get `data` if they take
+	/// `mime_type`. Call it once for each type the data can be read as, preferred first.
+	///
+	/// The compositor moves the drag instead of the app, so it can leave the window, and the preview is only drawn
+	/// over the window. The container can be dropped on the containers of the app too, as its
+	/// [`draggable`](Self::draggable) payload. Without the `wayland` feature, or out of Wayland, the drag stays in
+	/// the app.
+	///
+	/// ```rust,ignore
+	/// let uri = format!("file://{}\r\n", path.display());
+	/// Container::row()
+	///     .draggable(path.clone())
+	///     .drag_data("text/uri-list", uri)
+	///     .child(Text::new(name))
+	/// ```
+	pub fn drag_data(mut self, mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+		let data: Vec<u8> = data.into();
+		self.drag_data.push((mime_type.into(), data.into()));
+		self.draggable.get_or_insert_with(|| Rc::new(DataOnly));
+		self
+	}
+
+	/// Draws `preview` under the pointer while the container is dragged, instead of a box of its size.
+	///
+	/// Build the preview on every render, it is only drawn during a drag.
+	pub fn drag_preview(mut self, preview: impl Element + 'static) -> Self {
+		self.drag_preview = Some(Box::new(preview));
+		self
+	}
+
+	/// Calls `handler` with the payload of a [`draggable`](Self::draggable) container of type `T` dropped on this
+	/// one. Payloads of other types are ignored.
+	///
+	/// When drop targets are nested, the innermost one under the pointer gets the payload. Files dragged from other
+	/// apps are dropped as [`DroppedFiles`].
+	///
+	/// ```rust,ignore
+	/// Container::column()
+	///     .style_if_drag_over(|style| style.background_color((53, 132, 228, 40)))
+	///     .on_drop(move |task: TaskId| move_task(task, Column::Done))
+	///     .child(Text::new("Done"))
+	/// ```
+	pub fn on_drop<T: Clone + 'static>(mut self, handler: impl Fn(T) + 'static) -> Self {
+		let handler = Rc::new(handler);
+		let target = self.drop_target.get_or_insert_default();
+		target.accepts = |payload| payload.is::<T>();
+		target.on_drop = Rc::new({
+			let handler = handler.clone();
+			move |payload: &dyn Any| {
+				if let Some(payload) = payload.downcast_ref::<T>() {
+					handler(payload.clone());
+				}
+			}
+		});
+		if TypeId::of::<T>() == TypeId::of::<DroppedFiles>() {
+			let on_drop: Rc<dyn Fn(DroppedData)> = Rc::new(move |data: DroppedData| {
+				let files = DroppedFiles::from_uri_list(&data.data);
+				if let Some(files) = (&files as &dyn Any).downcast_ref::<T>() {
+					handler(files.clone());
+				}
+			});
+			target.from_other_apps = Some((vec![URI_LIST.to_string()], on_drop));
+		}
+		self
+	}
+
+	/// Calls `handler` with the data dragged from another app and dropped on this container, read as the first of
+	/// `mime_types` the other app offers. Drags offering none of them can't be dropped here.
+	///
+	/// The data arrives a few frames after the drop, once the other app has written it. This can be combined with an
+	/// [`on_drop`](Self::on_drop) for the drags of the app.
+	///
+	/// ```rust,ignore
+	/// Container::new()
+	///     .on_drop_data(&["text/plain;charset=utf-8", "text/plain"], move |dropped| {
+	///         set_note(String::from_utf8_lossy(&dropped.data).into_owned())
+	///     })
+	///     .child(Text::new(note))
+	/// ```
+	pub fn on_drop_data(
+		mut self,
+		mime_types: &[&str],
+		handler: impl Fn(DroppedData) + 'static,
+	) -> Self {
+		let mime_types = mime_types
+			.iter()
+			.map(|mime_type| mime_type.to_string())
+			.collect();
+		let handler: Rc<dyn Fn(DroppedData)> = Rc::new(handler);
+		self.drop_target.get_or_insert_default().from_other_apps = Some((mime_types, handler));
+		self
+	}
+
+	/// Applied while a payload accepted by [`on_drop`](Self::on_drop) is dragged over the container.
+	pub fn style_if_drag_over<F>(mut self, f: F) -> Self
+	where
+		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
+	{
+		self.style_if_drag_over = Box::new(f);
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Text, testing::TestHarness, use_state};
+
+	fn fruit_basket(_: ()) -> Box<dyn Element> {
+		let (basket, set_basket) = use_state(String::new());
+		Box::new(
+			Container::new()
+				.column()
+				.child(
+					Container::new()
+						.id("apple")
+						.draggable("Apple".to_string())
+						.child(Text::new("Apple")),
+				)
+				.child(
+					Container::new()
+						.id("numbers")
+						.min_height(50.)
+						.on_drop(|_: u32| panic!("a fruit isn't a number")),
+				)
+				.child(
+					Container::new()
+						.id("basket")
+						.min_height(100.)
+						.on_drop(move |fruit: String| set_basket(fruit))
+						.child(Text::new(format!("basket {basket}"))),
+				),
+		)
+	}
+
+	#[test]
+	fn test_drag_and_drop() {
+		let mut harness = TestHarness::new(fruit_basket, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("apple").unwrap();
+		harness.move_pointer(x + 5., y + 5.);
+		harness.mouse_down(0);
+		// Over a drop target for another type of payload, then over the basket
+		let (x, y, _, _) = harness.bounds_of("numbers").unwrap();
+		harness.move_pointer(x + 5., y + 5.);
+		let (x, y, _, _) = harness.bounds_of("basket").unwrap();
+		harness.move_pointer(x + 5., y + 5.);
+		harness.mouse_up(0);
+		harness.frame();
+		harness.expect_label("basket Apple");
+	}
+
+	fn file_drop_zone(_: ()) -> Box<dyn Element> {
+		let (dropped, set_dropped) = use_state(String::new());
+		Box::new(
+			Container::new()
+				.id("drop-zone")
+				.min_width(200.)
+				.min_height(100.)
+				.on_drop(move |files: DroppedFiles| set_dropped(files.paths[0].display().to_string()))
+				.child(Text::new(format!("dropped {dropped}"))),
+		)
+	}
+
+	#[test]
+	fn test_files_dropped_from_other_apps() {
+		let mut harness = TestHarness::new(file_drop_zone, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("drop-zone").unwrap();
+		harness.drop_files(x + 5., y + 5., vec![PathBuf::from("/tmp/my notes.txt")]);
+		harness.expect_label("dropped /tmp/my notes.txt");
+	}
+
+	fn note_drop_zone(_: ()) -> Box<dyn Element> {
+		let (note, set_note) = use_state(String::new());
+		Box::new(
+			Container::new()
+				.id("note")
+				.min_width(200.)
+				.min_height(100.)
+				.on_drop_data(
+					&["text/plain;charset=utf-8", "text/plain"],
+					move |dropped| {
+						set_note(format!(
+							"{} {}",
+							dropped.mime_type,
+							String::from_utf8_lossy(&dropped.data)
+						))
+					},
+				)
+				.child(Text::new(format!("note {note}"))),
+		)
+	}
+
+	#[test]
+	fn test_data_dropped_from_other_apps_in_the_preferred_type() {
+		let mut harness = TestHarness::new(note_drop_zone, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("note").unwrap();
+		harness.drop_data(
+			x + 5.,
+			y + 5.,
+			&[
+				("text/html", b"<b>Hi</b>"),
+				("text/plain", b"Hi"),
+				("text/plain;charset=utf-8", b"Hi!"),
+			],
+		);
+		harness.expect_label("note text/plain;charset=utf-8 Hi!");
+
+		// Nothing it takes
+		harness.drop_data(x + 5., y + 5., &[("image/png", b"")]);
+		harness.expect_label("note text/plain;charset=utf-8 Hi!");
+	}
+
+	#[test]
+	fn test_uri_lists() {
+		let paths = vec![PathBuf::from("/home/me/a b%.txt"), PathBuf::from("/tmp/é")];
+		assert_eq!(
+			uri_list(&paths),
+			"file:///home/me/a%20b%25.txt\r\nfile:///tmp/%C3%A9\r\n"
+		);
+		assert_eq!(
+			DroppedFiles::from_uri_list(uri_list(&paths).as_bytes()).paths,
+			paths
+		);
+		assert_eq!(
+			DroppedFiles::from_uri_list(b"# comment\nfile://localhost/etc/hosts\nhttps://example.com/\n")
+				.paths,
+			vec![PathBuf::from("/etc/hosts")]
+		);
+	}
+}
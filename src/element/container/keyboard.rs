@@ -0,0 +1,105 @@
+use crate::{
+	Container, Direction, InputManager, NamedKey, focus_system::GLOBAL_FOCUS_MANAGER, input::Key,
+};
+
+/// A navigation command sent by the keyboard to the focused interactive element.
+///
+/// Built-in widgets (sliders, selects, tabs, lists, ...) react to these instead of reading keys
+/// directly, so every widget shares the same key bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+	Up,
+	Down,
+	Left,
+	Right,
+	/// `Home`: jump to the first item or the minimum value.
+	First,
+	/// `End`: jump to the last item or the maximum value.
+	Last,
+	PageUp,
+	PageDown,
+}
+
+impl KeyAction {
+	const BINDINGS: [(NamedKey, KeyAction); 8] = [
+		(NamedKey::ArrowUp, KeyAction::Up),
+		(NamedKey::ArrowDown, KeyAction::Down),
+		(NamedKey::ArrowLeft, KeyAction::Left),
+		(NamedKey::ArrowRight, KeyAction::Right),
+		(NamedKey::Home, KeyAction::First),
+		(NamedKey::End, KeyAction::Last),
+		(NamedKey::PageUp, KeyAction::PageUp),
+		(NamedKey::PageDown, KeyAction::PageDown),
+	];
+
+	/// Returns `-1` or `1` if this action moves along the given axis, `None` otherwise.
+	///
+	/// Useful for one-dimensional widgets such as a horizontal tab list (`Direction::Row`)
+	/// or a vertical list (`Direction::Column`).
+	pub fn step(self, direction: Direction) -> Option<isize> {
+		match (direction, self) {
+			(Direction::Row, KeyAction::Left) | (Direction::Column, KeyAction::Up) => Some(-1),
+			(Direction::Row, KeyAction::Right) | (Direction::Column, KeyAction::Down) => Some(1),
+			_ => None,
+		}
+	}
+}
+
+/// Dispatches [`KeyAction`]s and typed text to the parent container while it is focused.
+#[derive(Default)]
+pub(crate) struct KeyboardActivatable {
+	pub(crate) on_key_action: Option<Box<dyn Fn(KeyAction)>>,
+	pub(crate) on_type: Option<Box<dyn Fn(&str)>>,
+}
+
+impl KeyboardActivatable {
+	pub fn update(&self, input_manager: &dyn InputManager, is_focused: bool) {
+		if !is_focused {
+			return;
+		}
+		if let Some(on_key_action) = &self.on_key_action {
+			for (key, action) in KeyAction::BINDINGS {
+				if input_manager.is_key_just_pressed(Key::Named(key)) {
+					on_key_action(action);
+				}
+			}
+		}
+		if let Some(on_type) = &self.on_type
+			&& !input_manager.text_input().is_empty()
+		{
+			on_type(input_manager.text_input());
+		}
+	}
+}
+
+impl Container {
+	/// Called with the navigation keys (arrows, `Home`, `End`, `PageUp`, `PageDown`) pressed while this container is focused.
+	///
+	/// Makes the container focusable if it isn't already. Activation with `Enter`/`Space` goes through [`Container::on_click`].
+	/// The arrow keys go to the handler instead of moving focus to the neighbouring elements.
+	pub fn on_key_action(mut self, handler: impl Fn(KeyAction) + 'static) -> Self {
+		if self.clickable.as_ref().and_then(|c| c.focus_node_id).is_none() {
+			self = self.focusable();
+		}
+		let focus_node_id = self.clickable.as_ref().and_then(|c| c.focus_node_id).unwrap();
+		GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.set_node_captures_arrows(focus_node_id));
+		self.keyboard.get_or_insert_default().on_key_action = Some(Box::new(handler));
+		self
+	}
+
+	/// Called with the text typed while this container is focused, like the letters of a type-ahead search in a list.
+	///
+	/// Makes the container focusable if it isn't already.
+	pub fn on_type(mut self, handler: impl Fn(&str) + 'static) -> Self {
+		if self
+			.clickable
+			.as_ref()
+			.and_then(|c| c.focus_node_id)
+			.is_none()
+		{
+			self = self.focusable();
+		}
+		self.keyboard.get_or_insert_default().on_type = Some(Box::new(handler));
+		self
+	}
+}
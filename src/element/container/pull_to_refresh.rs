@@ -0,0 +1,248 @@
+use std::{cell::RefCell, collections::HashSet, future::Future, pin::Pin};
+
+use clay_layout::{
+	Declaration,
+	layout::{Alignment, Sizing},
+	math::Vector2,
+};
+use uuid::Uuid;
+
+use crate::{
+	Align, Container, Dispatcher, Element, GlobalClosure, InputManager, Justify, RenderContext,
+	scroll::ScrollState,
+};
+
+/// Distance the content is pulled down by, in logical pixels, to refresh when released.
+const THRESHOLD: f32 = 64.;
+/// Fraction of the pointer movement the content follows, so pulling feels elastic.
+const RESISTANCE: f32 = 0.5;
+
+type RefreshFn = Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>>>;
+
+thread_local! {
+	/// Containers whose refresh finished since their last frame.
+	static FINISHED: RefCell<HashSet<Uuid>> = RefCell::new(HashSet::new());
+}
+
+/// The pull of a container, kept in its [`ScrollState`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct PullGesture {
+	/// How far the content is pulled down.
+	pulled: f32,
+	/// Where the pointer was pressed, while the pull is held.
+	start: Option<f32>,
+	refreshing: bool,
+}
+
+/// How far a pull-to-refresh gesture went, given to the indicator of [`Container::refresh_indicator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PullState {
+	/// From `0` to `1`, where releasing refreshes.
+	pub progress: f32,
+	/// The `on_refresh` future is running.
+	pub refreshing: bool,
+}
+
+impl From<PullGesture> for PullState {
+	fn from(gesture: PullGesture) -> Self {
+		Self {
+			progress: (gesture.pulled / THRESHOLD).min(1.),
+			refreshing: gesture.refreshing,
+		}
+	}
+}
+
+/// Refreshes a scrollable container when its content is pulled down from the top.
+pub(crate) struct PullToRefresh {
+	on_refresh: RefreshFn,
+	indicator: Box<dyn Element>,
+}
+
+impl PullToRefresh {
+	/// Follows the pointer pulling the container, called on every frame with whether the container is hovered.
+	pub fn update(&self, input: &dyn InputManager, state: &mut ScrollState, hovered: bool, id: Uuid) {
+		let before = state.pull;
+		let pull = &mut state.pull;
+		if FINISHED.with_borrow_mut(|finished| finished.remove(&id)) {
+			*pull = PullGesture::default();
+		}
+		let y = input.mouse_position().1;
+		if pull.refreshing {
+			// The indicator stays until the refresh is done
+		} else if let Some(start) = pull.start {
+			if input.is_mouse_button_pressed(0) {
+				pull.pulled = ((y - start) * RESISTANCE).clamp(0., THRESHOLD * 2.);
+			} else if pull.pulled >= THRESHOLD {
+				*pull = PullGesture {
+					pulled: THRESHOLD,
+					start: None,
+					refreshing: true,
+				};
+				self.refresh(id);
+			} else {
+				*pull = PullGesture::default();
+			}
+		} else if hovered && state.offset.1 <= 0. && input.is_mouse_button_just_pressed(0) {
+			pull.start = Some(y);
+		}
+		if state.pull != before {
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+
+	/// Runs the `on_refresh` future with the ones of [`use_future`](crate::use_future), hiding the indicator once
+	/// it is done.
+	fn refresh(&self, id: Uuid) {
+		let future = (self.on_refresh)();
+		let dispatcher = Dispatcher::new();
+		crate::hooks::spawn(async move {
+			future.await;
+			dispatcher.dispatch(move || finish(id));
+		});
+	}
+
+	/// Draws the indicator above the content, as tall as the content is pulled.
+	pub fn render<'clay: 'render, 'render>(
+		&'render self,
+		ctx: &mut RenderContext<'clay, 'render, '_>,
+		state: &ScrollState,
+	) {
+		if state.pull.pulled <= 0. {
+			return;
+		}
+		let height = state.pull.pulled;
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.width(Sizing::Grow(0., f32::MAX))
+					.height(Sizing::Fixed(height))
+					.child_alignment(Alignment::new(Justify::Center, Align::Center))
+					.end()
+					.clip(false, true, Vector2::new(0., 0.));
+				declaration
+			},
+			|c| {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				self.indicator.render(&mut ctx);
+			},
+		);
+	}
+}
+
+fn finish(id: Uuid) {
+	FINISHED.with_borrow_mut(|finished| finished.insert(id));
+	crate::REQUEST_REDRAW.call();
+}
+
+/// The default indicator, a ring filling up as the content is pulled.
+fn default_indicator(state: PullState) -> Container {
+	let fraction = if state.refreshing {
+		0.75
+	} else {
+		state.progress
+	};
+	Container::new()
+		.label(if state.refreshing {
+			"Refreshing"
+		} else {
+			"Pull to refresh"
+		})
+		.min_width(24.)
+		.max_width(24.)
+		.min_height(24.)
+		.max_height(24.)
+		.ring_progress(fraction, 3.)
+}
+
+impl Container {
+	/// Refreshes the content when it is pulled down from the top and released, by dragging it with the mouse
+	/// or a finger.
+	///
+	/// The container must scroll vertically with [`scroll_y`](Self::scroll_y), and lay its children out in a
+	/// column. An indicator shows above the content while it is pulled, and stays until the future returned by
+	/// `on_refresh` finishes. The future runs on the threads of the ones of [`use_future`](crate::use_future). Call it after [`scroll_handle`](Self::scroll_handle), as the indicator
+	/// is built from the scroll state.
+	///
+	/// ```rust,ignore
+	/// Container::column()
+	///     .scroll_y()
+	///     .pull_to_refresh(move || {
+	///         let send_mails = send_mails.clone();
+	///         async move { send_mails.send(fetch_mails()) }
+	///     })
+	/// ```
+	pub fn pull_to_refresh<F, Fut>(mut self, on_refresh: F) -> Self
+	where
+		F: Fn() -> Fut + 'static,
+		Fut: Future<Output = ()> + Send + 'static,
+	{
+		let state = self.pull_state();
+		self.pull_to_refresh = Some(PullToRefresh {
+			on_refresh: Box::new(move || Box::pin(on_refresh())),
+			indicator: Box::new(default_indicator(state)),
+		});
+		self
+	}
+
+	/// Replaces the indicator of [`pull_to_refresh`](Self::pull_to_refresh), built from how far the content is
+	/// pulled.
+	pub fn refresh_indicator<E: Element + 'static>(
+		mut self,
+		indicator: impl FnOnce(PullState) -> E,
+	) -> Self {
+		let state = self.pull_state();
+		if let Some(pull_to_refresh) = &mut self.pull_to_refresh {
+			pull_to_refresh.indicator = Box::new(indicator(state));
+		}
+		self
+	}
+
+	/// Returns the pull of the last frame.
+	fn pull_state(&self) -> PullState {
+		self.scroll_state.borrow().pull.into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Text, testing::TestHarness};
+
+	fn refreshing_feed(_: ()) -> Box<dyn Element> {
+		let (refreshes, send_refreshes) = crate::use_state_sender(0);
+		Box::new(
+			Container::new()
+				.column()
+				.id("feed")
+				.scroll_y()
+				.min_height(200.)
+				.max_height(200.)
+				.pull_to_refresh(move || {
+					let send_refreshes = send_refreshes.clone();
+					async move { send_refreshes.send(refreshes + 1) }
+				})
+				.child(Text::new(format!("refreshed {refreshes}"))),
+		)
+	}
+
+	#[test]
+	fn test_pull_to_refresh() {
+		let mut harness = TestHarness::new(refreshing_feed, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("feed").unwrap();
+		harness.move_pointer(x + 10., y + 10.);
+		harness.mouse_down(0);
+		harness.move_pointer(x + 10., y + 150.);
+		assert!(harness.find_by_label("Pull to refresh").is_some());
+		harness.mouse_up(0);
+		harness.wait_for_futures();
+		harness.frame();
+		harness.expect_label("refreshed 1");
+		assert!(harness.find_by_label("Refreshing").is_none());
+	}
+}
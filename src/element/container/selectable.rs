@@ -0,0 +1,134 @@
+use crate::{Container, ContainerStyle, Selection};
+
+impl Container {
+	/// Makes the container the item at `index` of a list using `selection`.
+	///
+	/// Clicking the container selects it, with Ctrl and Shift adding to the selection like in
+	/// [`SelectionModel::click`](crate::SelectionModel::click). Selected items are styled with
+	/// [`style_if_selected`](Self::style_if_selected) and reported as selected to screen readers.
+	pub fn selectable(self, selection: &Selection, index: usize) -> Self {
+		let selected = selection.is_selected(index);
+		let selection = selection.clone();
+		self
+			.selected(selected)
+			.on_click(move || selection.click(index))
+	}
+
+	/// Lets the keyboard move through the items of the list in this container, selected with `selection`.
+	///
+	/// The arrow keys, `Home`, `End`, `PageUp` and `PageDown` move the selection, extending it with Shift, and typing
+	/// jumps to the first item whose label starts with the letters typed, see [`TypeAhead`](crate::TypeAhead). The
+	/// labels are the ones of the items, in order.
+	///
+	/// ```rust,ignore
+	/// let labels = files.iter().map(|file| file.name.clone()).collect();
+	/// Container::column()
+	///     .role(Role::List)
+	///     .list_navigation(&selection, labels)
+	/// ```
+	pub fn list_navigation(self, selection: &Selection, labels: Vec<String>) -> Self {
+		let len = labels.len();
+		let keys = selection.clone();
+		let selection = selection.clone();
+		self
+			.on_key_action(move |action| {
+				keys.key_action(action, len);
+			})
+			.on_type(move |text| {
+				selection.type_text(text, &labels);
+			})
+	}
+
+	/// Marks the container as selected or not, for items whose selection isn't kept by a [`Selection`].
+	pub fn selected(mut self, selected: bool) -> Self {
+		self.accessibility.selected = Some(selected);
+		self
+	}
+
+	/// Applied while the container is [`selected`](Self::selected), before the hover and press styles. Defaults to
+	/// a light accent background shared by every selectable item.
+	pub fn style_if_selected<F>(mut self, f: F) -> Self
+	where
+		F: Fn(ContainerStyle) -> ContainerStyle + 'static,
+	{
+		self.style_if_selected = Box::new(f);
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Element, Key, NamedKey, SelectionMode, Text, testing::TestHarness, use_selection};
+
+	fn selectable_list(_: ()) -> Box<dyn Element> {
+		let selection = use_selection(SelectionMode::Multiple);
+		Box::new((0..4).fold(Container::column(), |list, index| {
+			list.child(
+				Container::new()
+					.label(format!("item {index}"))
+					.selectable(&selection, index)
+					.child(Text::new(format!("Item {index}"))),
+			)
+		}))
+	}
+
+	#[test]
+	fn test_shift_click_selects_a_range() {
+		let mut harness = TestHarness::new(selectable_list, (), (400., 300.));
+		let selected = |harness: &TestHarness<_, _>| {
+			harness
+				.nodes()
+				.into_iter()
+				.filter(|node| node.selected)
+				.filter_map(|node| node.label)
+				.collect::<Vec<_>>()
+		};
+		harness.click_label("item 1");
+		assert_eq!(selected(&harness), ["item 1"]);
+		harness.key_down(Key::Named(NamedKey::Shift));
+		harness.click_label("item 3");
+		harness.key_up(Key::Named(NamedKey::Shift));
+		assert_eq!(selected(&harness), ["item 1", "item 2", "item 3"]);
+		harness.key_down(Key::Named(NamedKey::Control));
+		harness.click_label("item 2");
+		harness.key_up(Key::Named(NamedKey::Control));
+		assert_eq!(selected(&harness), ["item 1", "item 3"]);
+	}
+
+	fn fruit_list(_: ()) -> Box<dyn Element> {
+		let selection = use_selection(SelectionMode::Single);
+		let fruits = ["Apple", "Banana", "Blueberry", "Cherry"];
+		let list = Container::column()
+			.label("fruits")
+			.list_navigation(&selection, fruits.map(String::from).to_vec());
+		Box::new(
+			fruits
+				.iter()
+				.enumerate()
+				.fold(list, |list, (index, fruit)| {
+					list.child(
+						Container::new()
+							.label(*fruit)
+							.selectable(&selection, index)
+							.child(Text::new(*fruit)),
+					)
+				}),
+		)
+	}
+
+	#[test]
+	fn test_typing_selects_the_matching_item() {
+		let mut harness = TestHarness::new(fruit_list, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		assert_eq!(
+			harness.focused().and_then(|node| node.label),
+			Some("fruits".to_string())
+		);
+		harness.type_text("bl");
+		assert!(harness.expect_label("Blueberry").selected);
+		harness.press_key(Key::Named(NamedKey::ArrowDown));
+		assert!(harness.expect_label("Cherry").selected);
+		assert!(!harness.expect_label("Blueberry").selected);
+	}
+}
@@ -0,0 +1,300 @@
+use std::time::Duration;
+
+use clay_layout::{
+	Color, Declaration,
+	elements::{FloatingAttachPointType, FloatingAttachToElement, PointerCaptureMode},
+	id::Id,
+	layout::{Padding, Sizing},
+	math::Vector2,
+};
+
+use crate::{ClickableState, Container, Element, RenderContext, Text};
+
+/// How long the pointer rests on a container before its tooltip shows, unless set with
+/// [`Container::tooltip_delay`].
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+/// Space between the tooltip and the container it describes.
+const GAP: f32 = 6.;
+/// Longer tooltips wrap.
+const MAX_WIDTH: f32 = 300.;
+/// Above overlays, below the preview of a drag.
+const Z_INDEX: i16 = i16::MAX - 1;
+
+/// The label shown next to a container, see [`Container::tooltip`].
+pub(crate) struct TooltipLabel {
+	text: Text,
+	background: Color,
+	delay: Duration,
+}
+
+impl TooltipLabel {
+	/// Returns whether the tooltip shows this frame: once the pointer rested on the container for the delay, or
+	/// while it is focused with the keyboard, until it is pressed.
+	pub(crate) fn update(&self, state: &mut ClickableState, hovered: bool) -> bool {
+		// Focus given by a click doesn't show the tooltip, it would stay after the pointer leaves
+		if !state.is_focused() {
+			state.tooltip_keyboard_focus = None;
+		} else if state.tooltip_keyboard_focus.is_none() {
+			state.tooltip_keyboard_focus = Some(!state.pressed && !state.pointer_press);
+		}
+		if state.down {
+			state.tooltip_hover_start = None;
+			return false;
+		}
+		if !hovered {
+			state.tooltip_hover_start = None;
+			return state.tooltip_keyboard_focus == Some(true);
+		}
+		let now = crate::frame_scheduler::now();
+		let start = *state.tooltip_hover_start.get_or_insert(now);
+		if now - start >= self.delay {
+			return true;
+		}
+		crate::frame_scheduler::request_frame_at(start + self.delay);
+		state.tooltip_keyboard_focus == Some(true)
+	}
+
+	/// Declares the tooltip below the container laid out at `anchor`, or above it if it doesn't fit below. `key`
+	/// names the elements of the tooltip.
+	pub(crate) fn render<'clay: 'render, 'render>(
+		&'render self,
+		ctx: &mut RenderContext<'clay, 'render, '_>,
+		anchor: Id,
+		key: &str,
+	) {
+		let layer = ctx.c.id(&format!("{key}-tooltip-layer"));
+		let label = ctx.c.id(&format!("{key}-tooltip"));
+		// Placed from the bounds of the last frame, the ones of this frame aren't known yet
+		let bounds = |id: Id| {
+			ctx
+				.c
+				.bounding_box(id)
+				.map(|bounds| (bounds.x, bounds.y, bounds.width, bounds.height))
+		};
+		let Some(anchor) = bounds(anchor) else {
+			return;
+		};
+		let window = bounds(layer.clone()).map(|(_, _, width, height)| (width, height));
+		let size = bounds(label.clone()).map(|(_, _, width, height)| (width, height));
+		let (position, above) = place(anchor, size, window);
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.id(layer)
+					.layout()
+					.width(Sizing::Grow(0., f32::MAX))
+					.height(Sizing::Grow(0., f32::MAX))
+					.end()
+					.floating()
+					.attach_to(FloatingAttachToElement::Root)
+					.z_index(Z_INDEX)
+					.pointer_capture_mode(PointerCaptureMode::Passthrough)
+					.end();
+				declaration
+			},
+			|c| {
+				c.with_styling(
+					|_| {
+						let mut declaration = Declaration::new();
+						declaration
+							.id(label)
+							.layout()
+							.width(Sizing::Fit(0., MAX_WIDTH))
+							.padding(Padding::new(8, 8, 4, 4))
+							.end()
+							.corner_radius()
+							.top_left(4.)
+							.top_right(4.)
+							.bottom_left(4.)
+							.bottom_right(4.)
+							.end()
+							.background_color(self.background)
+							.floating()
+							.offset(Vector2::new(position.0, position.1))
+							.attach_points(
+								if above {
+									FloatingAttachPointType::CenterBottom
+								} else {
+									FloatingAttachPointType::CenterTop
+								},
+								FloatingAttachPointType::LeftTop,
+							)
+							.attach_to(FloatingAttachToElement::Parent)
+							.z_index(Z_INDEX)
+							// The pointer stays on the container below
+							.pointer_capture_mode(PointerCaptureMode::Passthrough)
+							.end();
+						declaration
+					},
+					|c| {
+						let mut ctx = RenderContext {
+							c,
+							font_manager: &mut *ctx.font_manager,
+							input_manager: ctx.input_manager,
+						};
+						self.text.render(&mut ctx);
+					},
+				);
+			},
+		);
+	}
+}
+
+/// Returns where to place the middle of the top edge of a tooltip of `size` below `anchor`, or the middle of its
+/// bottom edge and `true` if it only fits above, keeping it inside the `window`. Both sizes are unknown on the first
+/// frame the tooltip shows.
+fn place(
+	anchor: (f32, f32, f32, f32),
+	size: Option<(f32, f32)>,
+	window: Option<(f32, f32)>,
+) -> ((f32, f32), bool) {
+	let (x, y, width, height) = anchor;
+	let center = x + width / 2.;
+	let below = y + height + GAP;
+	let above = y - GAP;
+	let (Some((tooltip_width, tooltip_height)), Some((window_width, window_height))) = (size, window)
+	else {
+		return ((center, below), false);
+	};
+	let center = if tooltip_width < window_width {
+		center.clamp(tooltip_width / 2., window_width - tooltip_width / 2.)
+	} else {
+		window_width / 2.
+	};
+	if below + tooltip_height > window_height && above - tooltip_height >= 0. {
+		((center, above), true)
+	} else {
+		((center, below), false)
+	}
+}
+
+impl Container {
+	/// Shows `text` in a small label next to the container once the pointer rests on it, like the name of an icon
+	/// button.
+	///
+	/// The tooltip shows below the container, or above it near the bottom of the window, after
+	/// [`tooltip_delay`](Self::tooltip_delay). It also shows while the container is focused with the keyboard, and
+	/// hides when the pointer leaves, when the container is pressed or when it loses the focus. The text is the
+	/// label of the container for screen readers if it has none.
+	///
+	/// ```rust,ignore
+	/// Container::new()
+	///     .on_click(move || save())
+	///     .tooltip("Save (Ctrl+S)")
+	///     .child(Image::new().src("document-save.svg").size(16., 16.))
+	/// ```
+	pub fn tooltip(mut self, text: impl Into<String>) -> Self {
+		let text = text.into();
+		if self.accessibility.label.is_none() {
+			self.accessibility.label = Some(text.clone());
+		}
+		let theme = crate::use_theme();
+		let delay = self
+			.tooltip
+			.as_ref()
+			.map_or(DEFAULT_DELAY, |tooltip| tooltip.delay);
+		self.tooltip = Some(TooltipLabel {
+			text: Text::new(text)
+				.font_size(theme.small_font_size)
+				.color(theme.colors.background),
+			background: theme.colors.text,
+			delay,
+		});
+		self
+	}
+
+	/// Waits until the pointer rests on the container for `ms` milliseconds before showing its
+	/// [`tooltip`](Self::tooltip), instead of half a second. Call it after `tooltip`.
+	pub fn tooltip_delay(mut self, ms: u64) -> Self {
+		if let Some(tooltip) = &mut self.tooltip {
+			tooltip.delay = Duration::from_millis(ms);
+		}
+		self
+	}
+}
+
+/// Wraps an element to show a tooltip when the pointer rests on it, see [`Container::tooltip`].
+///
+/// ```rust,ignore
+/// Tooltip::new("Battery: 84%", BatteryIcon::new(battery)).delay(200)
+/// ```
+pub struct Tooltip {
+	container: Container,
+}
+
+impl Tooltip {
+	pub fn new(text: impl Into<String>, child: impl Element + 'static) -> Self {
+		Self {
+			container: Container::new().w_fit().child(child).tooltip(text),
+		}
+	}
+
+	/// Waits `ms` milliseconds before showing the tooltip, see [`Container::tooltip_delay`].
+	pub fn delay(mut self, ms: u64) -> Self {
+		self.container = self.container.tooltip_delay(ms);
+		self
+	}
+}
+
+impl Element for Tooltip {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.container.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> std::collections::HashSet<uuid::Uuid> {
+		self.container.focus_nodes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::TestHarness;
+
+	#[test]
+	fn test_tooltips_stay_inside_the_window() {
+		let button = (100., 100., 40., 20.);
+		let window = Some((400., 300.));
+		// Below, centered on the button
+		assert_eq!(
+			place(button, Some((60., 20.)), window),
+			((120., 126.), false)
+		);
+		// Until it is laid out
+		assert_eq!(place(button, None, window), ((120., 126.), false));
+		// Pushed away from the edges
+		let corner = (0., 270., 20., 20.);
+		assert_eq!(place(corner, Some((60., 20.)), window), ((30., 264.), true));
+	}
+
+	fn icon_button(_: ()) -> Box<dyn Element> {
+		Box::new(
+			Container::new()
+				.id("save")
+				.size(24., 24.)
+				.tooltip("Save")
+				.tooltip_delay(20),
+		)
+	}
+
+	#[test]
+	fn test_tooltip_shows_after_a_delay() {
+		let mut harness = TestHarness::new(icon_button, (), (400., 300.));
+		let has_tooltip = |commands: &[String]| {
+			commands
+				.iter()
+				.any(|command| command.starts_with("text ") && command.contains("Save"))
+		};
+		let (x, y, _, _) = harness.bounds_of("save").unwrap();
+		harness.move_pointer(x + 10., y + 10.);
+		harness.frame();
+		assert!(!has_tooltip(harness.render_commands()));
+		harness.advance_time(Duration::from_millis(30));
+		harness.frame();
+		assert!(has_tooltip(harness.render_commands()));
+		harness.move_pointer(399., 299.);
+		harness.frame();
+		assert!(!has_tooltip(harness.render_commands()));
+	}
+}
@@ -0,0 +1,95 @@
+//! Menus opened with a right click or the menu key, like the Cut, Copy and Paste menu of a [`TextInput`](crate::TextInput).
+use std::rc::Rc;
+
+use crate::{Container, Role, Text};
+
+/// An entry of a [`context_menu`].
+#[derive(Clone)]
+pub struct MenuItem {
+	pub label: String,
+	/// Disabled items are grayed out and can't be picked.
+	pub enabled: bool,
+	on_select: Rc<dyn Fn()>,
+}
+
+impl MenuItem {
+	pub fn new(label: impl Into<String>, on_select: impl Fn() + 'static) -> Self {
+		Self {
+			label: label.into(),
+			enabled: true,
+			on_select: Rc::new(on_select),
+		}
+	}
+
+	pub fn enabled(mut self, enabled: bool) -> Self {
+		self.enabled = enabled;
+		self
+	}
+}
+
+/// Builds a menu of `items` with its top left corner at `position`, to show with
+/// [`push_overlay`](crate::push_overlay) on every render while it is open.
+///
+/// Picking an item or clicking outside of the menu calls `on_close`, which should stop showing it.
+///
+/// ```rust,ignore
+/// if let Some(position) = menu {
+///     push_overlay(context_menu(
+///         position,
+///         vec![MenuItem::new("Rename", move || rename(file))],
+///         move || set_menu(None),
+///     ));
+/// }
+/// ```
+pub fn context_menu(
+	position: (f32, f32),
+	items: Vec<MenuItem>,
+	on_close: impl Fn() + 'static,
+) -> Container {
+	let on_close: Rc<dyn Fn()> = Rc::new(on_close);
+	let menu = items.into_iter().fold(
+		Container::column()
+			.role(Role::Menu)
+			.min_width(160.)
+			.padding_all(4)
+			.rounded(6.)
+			.background_color((255, 255, 255, 255))
+			.border_color((200, 200, 200, 255))
+			.border_width(1)
+			.elevation(2),
+		|menu, item| {
+			let entry = Container::new()
+				.role(Role::MenuItem)
+				.label(item.label.clone())
+				.w_expand()
+				.symmetric_padding(10, 6)
+				.rounded(4.)
+				.child(Text::new(item.label).color(if item.enabled {
+					(0, 0, 0, 255)
+				} else {
+					(150, 150, 150, 255)
+				}));
+			let entry = if item.enabled {
+				let on_close = on_close.clone();
+				entry
+					.style_if_hovered(|style| style.background_color((53, 132, 228, 40)))
+					.on_click(move || {
+						on_close();
+						(item.on_select)();
+					})
+			} else {
+				entry
+			};
+			menu.child(entry)
+		},
+	);
+	// Covers the window to close the menu when clicking outside of it
+	let close = on_close.clone();
+	Container::new()
+		.w_expand()
+		.h_expand()
+		.weird_padding(position.1.max(0.) as u16, 0, 0, position.0.max(0.) as u16)
+		.on_click(move || close())
+		.on_right_click(move || on_close())
+		.child(menu)
+}
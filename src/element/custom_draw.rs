@@ -0,0 +1,150 @@
+//! Drawing with skia inside the layout, for what the other elements can't draw, like charts and waveforms.
+use std::{
+	cell::{Cell, OnceCell},
+	hash::{DefaultHasher, Hash, Hasher},
+	rc::Rc,
+};
+
+use clay_layout::{Declaration, layout::Sizing};
+use skia_safe::{Canvas, Rect};
+
+use crate::{
+	Element, RenderContext,
+	clay_renderer::{CustomElement, DrawFn},
+};
+
+thread_local! {
+	/// Counts the drawings without a key, so each of them is a new drawing that is repainted.
+	static UNKEYED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Draws on the canvas of the window with a closure, in the area the layout gives the element.
+///
+/// The closure is called with the canvas and the bounds of the element in the window, and what it draws is clipped
+/// to those bounds. The element fills its parent unless a size is set. The area is repainted every frame, unless
+/// the drawing is given a [`key`](Self::key) that only changes when it does.
+///
+/// ```rust,ignore
+/// let samples = use_waveform();
+/// CustomDraw::new({
+///     let samples = samples.clone();
+///     move |canvas, bounds| {
+///         let mut paint = Paint::default();
+///         paint.set_color(Color::from_rgb(53, 132, 228));
+///         let step = bounds.width() / samples.len() as f32;
+///         for (i, sample) in samples.iter().enumerate() {
+///             let x = bounds.left + i as f32 * step;
+///             canvas.draw_line((x, bounds.bottom), (x, bounds.bottom - sample * bounds.height()), &paint);
+///         }
+///     }
+/// })
+/// .key(&samples)
+/// .height(40.)
+/// ```
+pub struct CustomDraw {
+	draw: Rc<dyn Fn(&Canvas, Rect)>,
+	key: Option<u64>,
+	width: Sizing,
+	height: Sizing,
+	custom_element: OnceCell<CustomElement>,
+}
+
+impl CustomDraw {
+	pub fn new(draw: impl Fn(&Canvas, Rect) + 'static) -> Self {
+		Self {
+			draw: Rc::new(draw),
+			key: None,
+			width: Sizing::Grow(0., f32::MAX),
+			height: Sizing::Grow(0., f32::MAX),
+			custom_element: OnceCell::new(),
+		}
+	}
+
+	/// Repaints the drawing only when `key` changes, or when the element moves or is resized, instead of every
+	/// frame. The key should hold everything the closure draws from, like the samples of a chart.
+	pub fn key(mut self, key: impl Hash) -> Self {
+		let mut hasher = DefaultHasher::new();
+		key.hash(&mut hasher);
+		self.key = Some(hasher.finish());
+		self
+	}
+
+	pub fn width(mut self, width: f32) -> Self {
+		self.width = Sizing::Fixed(width);
+		self
+	}
+
+	pub fn height(mut self, height: f32) -> Self {
+		self.height = Sizing::Fixed(height);
+		self
+	}
+
+	pub fn size(self, width: f32, height: f32) -> Self {
+		self.width(width).height(height)
+	}
+}
+
+impl Element for CustomDraw {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let custom_element = self.custom_element.get_or_init(|| {
+			let mut hasher = DefaultHasher::new();
+			match self.key {
+				Some(key) => (true, key).hash(&mut hasher),
+				None => {
+					let unkeyed = UNKEYED.replace(UNKEYED.get().wrapping_add(1));
+					(false, unkeyed).hash(&mut hasher);
+				}
+			}
+			CustomElement::Draw(DrawFn {
+				draw: self.draw.clone(),
+				key: hasher.finish(),
+			})
+		});
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.width(self.width)
+					.height(self.height)
+					.end()
+					.custom_element(custom_element);
+				declaration
+			},
+			|_| {},
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+
+	use super::*;
+	use crate::{Container, testing::TestHarness};
+
+	thread_local! {
+		static DRAWN: RefCell<Vec<Rect>> = const { RefCell::new(Vec::new()) };
+	}
+
+	fn chart(_: ()) -> Box<dyn Element> {
+		Box::new(Container::new().padding_all(10).child(
+			CustomDraw::new(|_, bounds| DRAWN.with_borrow_mut(|drawn| drawn.push(bounds))).size(80., 20.),
+		))
+	}
+
+	#[test]
+	fn test_custom_draws_get_their_bounds() {
+		let mut harness = TestHarness::new(chart, (), (200., 100.));
+		assert!(
+			harness
+				.render_commands()
+				.contains(&"draw 10 10 80x20".to_string())
+		);
+		harness.render_png();
+		assert_eq!(
+			DRAWN.with_borrow(|drawn| drawn.last().copied()),
+			Some(Rect::from_xywh(10., 10., 80., 20.))
+		);
+	}
+}
@@ -0,0 +1,674 @@
+//! Panels docked side by side or stacked in tabs, rearranged by dragging their tabs, like the tool windows of an IDE.
+//!
+//! A [`DockLayout`] is a tree of splits whose leaves are groups of tabs. It is kept by [`use_dock_layout`] and
+//! shown by a [`DockArea`]. Dragging a tab over a group shows where the panel would go: along one of its sides,
+//! splitting it, or in its center, among its tabs. The layout can be saved as text and restored with
+//! [`DockLayout::parse`].
+use std::{
+	cell::RefCell,
+	collections::{HashMap, HashSet},
+	fmt,
+	rc::Rc,
+};
+
+use clay_layout::{
+	Declaration,
+	elements::FloatingAttachToElement,
+	layout::{LayoutDirection, Sizing},
+};
+use uuid::Uuid;
+
+use crate::{
+	Container, Direction, Element, GlobalClosure, RenderContext, Role, Text,
+	element::container::drag_and_drop, use_ref,
+};
+
+/// Part of a group of tabs covered by the zones docking along its sides.
+const SIDE_ZONE: f32 = 0.25;
+
+/// Where a panel is docked, relative to a group of tabs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockSide {
+	Left,
+	Right,
+	Top,
+	Bottom,
+	/// Among the tabs of the group.
+	Center,
+}
+
+/// A node of a [`DockLayout`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DockNode {
+	/// Panels stacked in tabs, showing the active one.
+	Tabs { panels: Vec<String>, active: usize },
+	/// Two nodes side by side, the first one taking `ratio` of the space.
+	Split {
+		direction: Direction,
+		ratio: f32,
+		first: Box<DockNode>,
+		second: Box<DockNode>,
+	},
+}
+
+impl DockNode {
+	/// A group of tabs showing the first panel.
+	pub fn tabs(panels: &[&str]) -> Self {
+		Self::Tabs {
+			panels: panels.iter().map(|panel| panel.to_string()).collect(),
+			active: 0,
+		}
+	}
+
+	/// `first` on the left of `second`, taking `ratio` of the width.
+	pub fn row(ratio: f32, first: DockNode, second: DockNode) -> Self {
+		Self::split(Direction::Row, ratio, first, second)
+	}
+
+	/// `first` above `second`, taking `ratio` of the height.
+	pub fn column(ratio: f32, first: DockNode, second: DockNode) -> Self {
+		Self::split(Direction::Column, ratio, first, second)
+	}
+
+	fn split(direction: Direction, ratio: f32, first: DockNode, second: DockNode) -> Self {
+		Self::Split {
+			direction,
+			ratio: ratio.clamp(0., 1.),
+			first: Box::new(first),
+			second: Box::new(second),
+		}
+	}
+
+	fn collect_panels<'a>(&'a self, panels: &mut Vec<&'a str>) {
+		match self {
+			Self::Tabs { panels: tabs, .. } => panels.extend(tabs.iter().map(String::as_str)),
+			Self::Split { first, second, .. } => {
+				first.collect_panels(panels);
+				second.collect_panels(panels);
+			}
+		}
+	}
+
+	/// Returns the group of tabs holding `panel`.
+	fn tabs_of(&mut self, panel: &str) -> Option<&mut DockNode> {
+		match self {
+			Self::Tabs { panels, .. } if panels.iter().any(|other| other == panel) => Some(self),
+			Self::Tabs { .. } => None,
+			Self::Split { first, second, .. } => first.tabs_of(panel).or_else(|| second.tabs_of(panel)),
+		}
+	}
+
+	/// Returns the node left once `panel` is removed, or `None` if it was the last panel. Splits left with one
+	/// side are replaced by that side.
+	fn without(self, panel: &str) -> Option<Self> {
+		match self {
+			Self::Tabs { mut panels, active } => {
+				let index = panels.iter().position(|other| other == panel);
+				if let Some(index) = index {
+					panels.remove(index);
+				}
+				if panels.is_empty() {
+					return None;
+				}
+				let active = match index {
+					Some(index) if index < active => active - 1,
+					_ => active,
+				};
+				Some(Self::Tabs {
+					active: active.min(panels.len() - 1),
+					panels,
+				})
+			}
+			Self::Split {
+				direction,
+				ratio,
+				first,
+				second,
+			} => match (first.without(panel), second.without(panel)) {
+				(Some(first), Some(second)) => Some(Self::split(direction, ratio, first, second)),
+				(node, None) | (None, node) => node,
+			},
+		}
+	}
+
+	/// Parses a node at the start of `text`, returning it with the rest of the text.
+	fn parse(text: &str) -> Option<(Self, &str)> {
+		let text = text.trim_start();
+		let open = text.find('(')?;
+		let rest = &text[open + 1..];
+		match text[..open].trim() {
+			"tabs" => {
+				let close = rest.find(')')?;
+				let (active, panels) = rest[..close].split_once(':')?;
+				let panels = panels
+					.split(',')
+					.map(|panel| panel.trim().to_string())
+					.collect::<Vec<_>>();
+				if panels.iter().any(String::is_empty) {
+					return None;
+				}
+				let active = active
+					.trim()
+					.parse()
+					.ok()
+					.filter(|&active| active < panels.len())?;
+				Some((Self::Tabs { panels, active }, &rest[close + 1..]))
+			}
+			name @ ("row" | "column") => {
+				let (ratio, rest) = rest.split_once(',')?;
+				let ratio = ratio.trim().parse::<f32>().ok()?;
+				let (first, rest) = Self::parse(rest)?;
+				let (second, rest) = Self::parse(rest.trim_start().strip_prefix(',')?)?;
+				let rest = rest.trim_start().strip_prefix(')')?;
+				let direction = if name == "row" {
+					Direction::Row
+				} else {
+					Direction::Column
+				};
+				Some((Self::split(direction, ratio, first, second), rest))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for DockNode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Tabs { panels, active } => write!(f, "tabs({active}:{})", panels.join(",")),
+			Self::Split {
+				direction,
+				ratio,
+				first,
+				second,
+			} => {
+				let name = match direction {
+					Direction::Row => "row",
+					Direction::Column => "column",
+				};
+				write!(f, "{name}({ratio},{first},{second})")
+			}
+		}
+	}
+}
+
+/// How the panels of a [`DockArea`] are arranged.
+///
+/// Its text form, given by [`to_string`](ToString::to_string) and read back by [`parse`](Self::parse), is meant to
+/// be saved with the settings of the app, like `row(0.25,tabs(0:files,search),tabs(0:editor))`. Panel ids can't
+/// contain `(`, `)`, `,` or `:`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DockLayout {
+	/// `None` once every panel is closed.
+	pub root: Option<DockNode>,
+}
+
+impl DockLayout {
+	pub fn new(root: DockNode) -> Self {
+		Self { root: Some(root) }
+	}
+
+	/// Returns the ids of the panels in the layout.
+	pub fn panels(&self) -> Vec<&str> {
+		let mut panels = Vec::new();
+		if let Some(root) = &self.root {
+			root.collect_panels(&mut panels);
+		}
+		panels
+	}
+
+	pub fn contains(&self, panel: &str) -> bool {
+		self.panels().contains(&panel)
+	}
+
+	/// Moves `panel` next to the group of tabs holding `target`, or among its tabs with [`DockSide::Center`].
+	/// Panels that aren't in the layout yet are opened there. Docking at the center shows the panel.
+	///
+	/// Does nothing if `target` isn't in the layout, or if `panel` is alone in the group of `target`.
+	pub fn dock(&mut self, panel: &str, target: &str, side: DockSide) {
+		let Some(DockNode::Tabs { panels, .. }) =
+			self.root.as_mut().and_then(|root| root.tabs_of(target))
+		else {
+			return;
+		};
+		// The group is found again after the panel left it, through one of the panels it keeps
+		let Some(anchor) = panels.iter().find(|other| *other != panel).cloned() else {
+			return;
+		};
+		let root = self.root.take().and_then(|root| root.without(panel));
+		self.root = root;
+		let Some(group) = self.root.as_mut().and_then(|root| root.tabs_of(&anchor)) else {
+			return;
+		};
+		let tabs = DockNode::tabs(&[panel]);
+		match side {
+			DockSide::Center => {
+				if let DockNode::Tabs { panels, active } = group {
+					panels.push(panel.to_string());
+					*active = panels.len() - 1;
+				}
+			}
+			side => {
+				let old = std::mem::replace(group, DockNode::tabs(&[]));
+				*group = match side {
+					DockSide::Left => DockNode::row(0.5, tabs, old),
+					DockSide::Right => DockNode::row(0.5, old, tabs),
+					DockSide::Top => DockNode::column(0.5, tabs, old),
+					_ => DockNode::column(0.5, old, tabs),
+				};
+			}
+		}
+	}
+
+	/// Shows `panel` in its group of tabs.
+	pub fn activate(&mut self, panel: &str) {
+		if let Some(DockNode::Tabs { panels, active }) =
+			self.root.as_mut().and_then(|root| root.tabs_of(panel))
+		{
+			*active = panels
+				.iter()
+				.position(|other| other == panel)
+				.unwrap_or(*active);
+		}
+	}
+
+	/// Removes `panel` from the layout.
+	pub fn close(&mut self, panel: &str) {
+		self.root = self.root.take().and_then(|root| root.without(panel));
+	}
+
+	/// Reads a layout saved with [`to_string`](ToString::to_string). Returns `None` if `text` isn't one.
+	pub fn parse(text: &str) -> Option<Self> {
+		if text.trim().is_empty() {
+			return Some(Self::default());
+		}
+		let (root, rest) = DockNode::parse(text)?;
+		rest.trim().is_empty().then(|| Self::new(root))
+	}
+}
+
+impl fmt::Display for DockLayout {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.root {
+			Some(root) => root.fmt(f),
+			None => Ok(()),
+		}
+	}
+}
+
+/// The [`DockLayout`] of a [`DockArea`], shared with the component that declares it.
+///
+/// Created with [`use_dock_layout`]. Changing the layout through it renders the component again.
+#[derive(Clone)]
+pub struct DockHandle {
+	layout: Rc<RefCell<DockLayout>>,
+}
+
+impl DockHandle {
+	/// Returns the current layout, to save it.
+	pub fn layout(&self) -> DockLayout {
+		self.layout.borrow().clone()
+	}
+
+	pub fn set_layout(&self, layout: DockLayout) {
+		self.update(|current| *current = layout);
+	}
+
+	/// See [`DockLayout::dock`].
+	pub fn dock(&self, panel: &str, target: &str, side: DockSide) {
+		self.update(|layout| layout.dock(panel, target, side));
+	}
+
+	/// See [`DockLayout::activate`].
+	pub fn activate(&self, panel: &str) {
+		self.update(|layout| layout.activate(panel));
+	}
+
+	/// See [`DockLayout::close`].
+	pub fn close(&self, panel: &str) {
+		self.update(|layout| layout.close(panel));
+	}
+
+	fn update(&self, f: impl FnOnce(&mut DockLayout)) {
+		let mut layout = self.layout.borrow_mut();
+		let before = layout.clone();
+		f(&mut layout);
+		if *layout != before {
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+}
+
+/// Keeps the layout of a [`DockArea`] across renders, starting with `initial`.
+///
+/// ```rust,ignore
+/// let dock = use_dock_layout(
+///     saved_layout
+///         .and_then(|saved| DockLayout::parse(&saved))
+///         .unwrap_or_else(|| {
+///             DockLayout::new(DockNode::row(0.25, DockNode::tabs(&["files"]), DockNode::tabs(&["editor"])))
+///         }),
+/// );
+/// ```
+pub fn use_dock_layout(initial: DockLayout) -> DockHandle {
+	DockHandle {
+		layout: use_ref(initial),
+	}
+}
+
+/// A panel of a [`DockArea`], shown in a tab titled `title`.
+pub struct DockPanel {
+	id: String,
+	title: String,
+	content: Box<dyn Element>,
+}
+
+impl DockPanel {
+	pub fn new(
+		id: impl Into<String>,
+		title: impl Into<String>,
+		content: impl Element + 'static,
+	) -> Self {
+		Self {
+			id: id.into(),
+			title: title.into(),
+			content: Box::new(content),
+		}
+	}
+}
+
+/// The payload of a dragged tab.
+#[derive(Debug, Clone)]
+struct DraggedPanel(String);
+
+/// Shows `panels` as arranged by the layout of `dock`, filling its parent.
+///
+/// Panels missing from the layout aren't shown, and the ones of the layout missing from `panels` are skipped. Tabs
+/// are dragged onto a group of tabs to dock their panel along one of its sides or among its tabs.
+///
+/// ```rust,ignore
+/// DockArea::new(
+///     &dock,
+///     vec![
+///         DockPanel::new("files", "Files", file_tree),
+///         DockPanel::new("editor", "Editor", editor),
+///     ],
+/// )
+/// ```
+pub struct DockArea {
+	root: Container,
+}
+
+impl DockArea {
+	pub fn new(dock: &DockHandle, panels: Vec<DockPanel>) -> Self {
+		let titles = panels
+			.iter()
+			.map(|panel| (panel.id.clone(), panel.title.clone()))
+			.collect::<HashMap<_, _>>();
+		let mut contents = panels
+			.into_iter()
+			.map(|panel| (panel.id, panel.content))
+			.collect::<HashMap<_, _>>();
+		let layout = dock.layout();
+		let root = Container::new().w_expand().h_expand();
+		let root = match &layout.root {
+			Some(node) => root.child(build_node(node, dock, &titles, &mut contents)),
+			None => root,
+		};
+		Self { root }
+	}
+}
+
+fn build_node(
+	node: &DockNode,
+	dock: &DockHandle,
+	titles: &HashMap<String, String>,
+	contents: &mut HashMap<String, Box<dyn Element>>,
+) -> Container {
+	match node {
+		DockNode::Tabs { panels, active } => build_tabs(panels, *active, dock, titles, contents),
+		DockNode::Split {
+			direction,
+			ratio,
+			first,
+			second,
+		} => {
+			let mut first = build_node(first, dock, titles, contents);
+			let mut second = build_node(second, dock, titles, contents);
+			let split = match direction {
+				Direction::Row => {
+					first.style.size.0 = Sizing::Percent(*ratio);
+					second.style.size.0 = Sizing::Percent(1. - ratio);
+					Container::row()
+				}
+				Direction::Column => {
+					first.style.size.1 = Sizing::Percent(*ratio);
+					second.style.size.1 = Sizing::Percent(1. - ratio);
+					Container::column()
+				}
+			};
+			split.w_expand().h_expand().child(first).child(second)
+		}
+	}
+}
+
+fn build_tabs(
+	panels: &[String],
+	active: usize,
+	dock: &DockHandle,
+	titles: &HashMap<String, String>,
+	contents: &mut HashMap<String, Box<dyn Element>>,
+) -> Container {
+	if panels.is_empty() {
+		return Container::new();
+	}
+	let tab_bar = panels.iter().enumerate().fold(
+		Container::row()
+			.role(Role::TabList)
+			.w_expand()
+			.background_color((240, 240, 242, 255)),
+		|tab_bar, (index, panel)| {
+			let Some(title) = titles.get(panel) else {
+				return tab_bar;
+			};
+			let activate = dock.clone();
+			let id = panel.clone();
+			tab_bar.child(
+				Container::new()
+					.role(Role::Tab)
+					.label(title.clone())
+					.symmetric_padding(12, 6)
+					.selected(index == active)
+					.style_if_hovered(|style| style.background_color((0, 0, 0, 15)))
+					.on_click(move || activate.activate(&id))
+					.draggable(DraggedPanel(panel.clone()))
+					.child(Text::new(title.clone())),
+			)
+		},
+	);
+	let content = panels
+		.get(active)
+		.and_then(|panel| contents.remove(panel))
+		.into_iter()
+		.fold(Container::column().w_expand().h_expand(), Container::child);
+	let zone = |side| {
+		let dock = dock.clone();
+		let target = panels[active.min(panels.len() - 1)].clone();
+		Container::new()
+			.w_expand()
+			.h_expand()
+			.style_if_drag_over(|style| {
+				style
+					.background_color((53, 132, 228, 60))
+					.border_color((53, 132, 228, 255))
+					.border_width(2)
+			})
+			.on_drop(move |dragged: DraggedPanel| dock.dock(&dragged.0, &target, side))
+	};
+	let mut top = zone(DockSide::Top);
+	top.style.size.1 = Sizing::Percent(SIDE_ZONE);
+	let mut bottom = zone(DockSide::Bottom);
+	bottom.style.size.1 = Sizing::Percent(SIDE_ZONE);
+	let mut left = zone(DockSide::Left);
+	left.style.size.0 = Sizing::Percent(SIDE_ZONE);
+	let mut right = zone(DockSide::Right);
+	right.style.size.0 = Sizing::Percent(SIDE_ZONE);
+	let zones = Container::column()
+		.w_expand()
+		.h_expand()
+		.child(top)
+		.child(
+			Container::row()
+				.w_expand()
+				.h_expand()
+				.child(left)
+				.child(zone(DockSide::Center))
+				.child(right),
+		)
+		.child(bottom);
+	Container::column()
+		.w_expand()
+		.h_expand()
+		.border_color((200, 200, 200, 255))
+		.border_width(1)
+		.child(tab_bar)
+		.child(DropZones { content, zones })
+}
+
+/// The content of a group of tabs, with the zones to dock a dragged tab drawn over it during the drag.
+struct DropZones {
+	content: Container,
+	zones: Container,
+}
+
+impl Element for DropZones {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let dragging = drag_and_drop::is_dragging::<DraggedPanel>();
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.direction(LayoutDirection::LeftToRight)
+					.width(Sizing::Grow(0., f32::MAX))
+					.height(Sizing::Grow(0., f32::MAX))
+					.end();
+				declaration
+			},
+			|c| {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				self.content.render(&mut ctx);
+				if !dragging {
+					return;
+				}
+				ctx.c.with_styling(
+					|_| {
+						let mut declaration = Declaration::new();
+						declaration
+							.layout()
+							.width(Sizing::Percent(1.))
+							.height(Sizing::Percent(1.))
+							.end()
+							.floating()
+							.attach_to(FloatingAttachToElement::Parent)
+							.end();
+						declaration
+					},
+					|c| {
+						let mut ctx = RenderContext {
+							c,
+							font_manager: &mut *ctx.font_manager,
+							input_manager: ctx.input_manager,
+						};
+						self.zones.render(&mut ctx);
+					},
+				);
+			},
+		);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.content.focus_nodes()
+	}
+}
+
+impl Element for DockArea {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ide() -> DockLayout {
+		DockLayout::new(DockNode::row(
+			0.25,
+			DockNode::tabs(&["files", "search"]),
+			DockNode::column(
+				0.7,
+				DockNode::tabs(&["editor"]),
+				DockNode::tabs(&["terminal"]),
+			),
+		))
+	}
+
+	#[test]
+	fn test_docking_splits_and_collapses_groups() {
+		let mut layout = ide();
+		layout.dock("terminal", "files", DockSide::Center);
+		assert_eq!(
+			layout.to_string(),
+			"row(0.25,tabs(2:files,search,terminal),tabs(0:editor))"
+		);
+		layout.dock("search", "editor", DockSide::Right);
+		assert_eq!(
+			layout.to_string(),
+			"row(0.25,tabs(1:files,terminal),row(0.5,tabs(0:editor),tabs(0:search)))"
+		);
+		// A panel alone in its group can't be docked next to itself
+		layout.dock("editor", "editor", DockSide::Left);
+		assert_eq!(layout.panels(), ["files", "terminal", "editor", "search"]);
+		layout.dock("files", "files", DockSide::Top);
+		assert_eq!(
+			layout.to_string(),
+			"row(0.25,column(0.5,tabs(0:files),tabs(0:terminal)),row(0.5,tabs(0:editor),tabs(0:search)))"
+		);
+		layout.close("files");
+		layout.close("terminal");
+		assert_eq!(layout.to_string(), "row(0.5,tabs(0:editor),tabs(0:search))");
+	}
+
+	#[test]
+	fn test_layouts_are_saved_as_text() {
+		let mut layout = ide();
+		layout.activate("search");
+		let saved = layout.to_string();
+		assert_eq!(
+			saved,
+			"row(0.25,tabs(1:files,search),column(0.7,tabs(0:editor),tabs(0:terminal)))"
+		);
+		assert_eq!(DockLayout::parse(&saved), Some(layout));
+		assert_eq!(
+			DockLayout::parse(" row( 0.5 , tabs(0: a, b) , tabs(0:c) ) "),
+			Some(DockLayout::new(DockNode::row(
+				0.5,
+				DockNode::tabs(&["a", "b"]),
+				DockNode::tabs(&["c"])
+			)))
+		);
+		assert_eq!(DockLayout::parse(""), Some(DockLayout::default()));
+		assert_eq!(DockLayout::parse("tabs(2:a,b)"), None);
+		assert_eq!(DockLayout::parse("row(0.5,tabs(0:a))"), None);
+		assert_eq!(DockLayout::parse("tabs(0:a) tabs(0:b)"), None);
+	}
+}
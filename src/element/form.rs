@@ -0,0 +1,457 @@
+//! Forms: fields with validators, submitted together.
+//!
+//! [`use_form`] keeps the values of the fields of a form across renders. On every render, the component registers
+//! its fields with [`FormState::field`], with their validators, and binds them to inputs like
+//! [`TextInput::for_field`](crate::TextInput::for_field). A [`Form`] around the inputs submits when Enter is pressed
+//! in one of its text fields, and a [`FieldError`] shows the error of a field once it was edited or the form was
+//! submitted. Fields that are no longer registered, like the ones of a hidden section, are dropped with their value
+//! when the form renders, so they don't block submitting.
+use std::{
+	cell::{Cell, RefCell},
+	collections::{BTreeMap, HashSet},
+	rc::Rc,
+};
+
+use uuid::Uuid;
+
+use crate::{Element, GlobalClosure, RenderContext, Text, use_ref};
+
+type Validator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+struct FieldData {
+	value: String,
+	/// The value the field is dirty against.
+	initial: String,
+	/// The initial value last given by the component, which may be newer than `initial`.
+	given: String,
+	touched: bool,
+	validators: Vec<Validator>,
+}
+
+#[derive(Default)]
+struct FormData {
+	fields: BTreeMap<String, FieldData>,
+	/// Fields registered since the last render of the [`Form`].
+	declared: HashSet<String>,
+	/// Submitting was attempted, so every error is shown.
+	submitted: bool,
+	/// [`FormState::submit`] was called since the last render of the [`Form`].
+	submit_requested: bool,
+}
+
+/// Returns the message of the first failing validator of the field `name`.
+///
+/// The form isn't borrowed while validators run, so they can read other fields, like a password confirmation.
+fn validate(data: &RefCell<FormData>, name: &str) -> Option<String> {
+	let (value, validators) = {
+		let data = data.borrow();
+		let field = data.fields.get(name)?;
+		(field.value.clone(), field.validators.clone())
+	};
+	validators
+		.iter()
+		.find_map(|validator| validator(&value).err())
+}
+
+/// The values of the fields of a form and their validation, returned by [`use_form`].
+#[derive(Clone)]
+pub struct FormState {
+	data: Rc<RefCell<FormData>>,
+}
+
+impl FormState {
+	/// Registers the field `name`, starting with the value `initial`, and returns it to add validators.
+	///
+	/// Call it on every render, a field that isn't registered is dropped. When `initial` changes while the field has no edits, the field takes the new value,
+	/// so a settings dialog can show values that are loaded later.
+	pub fn field(&self, name: impl Into<String>, initial: impl Into<String>) -> FormField {
+		let name = name.into();
+		let initial = initial.into();
+		let mut data = self.data.borrow_mut();
+		data.declared.insert(name.clone());
+		let field = data
+			.fields
+			.entry(name.clone())
+			.or_insert_with(|| FieldData {
+				value: initial.clone(),
+				initial: initial.clone(),
+				given: initial.clone(),
+				touched: false,
+				validators: Vec::new(),
+			});
+		if field.given != initial {
+			if field.value == field.initial {
+				field.value = initial.clone();
+				field.initial = initial.clone();
+			}
+			field.given = initial;
+		}
+		// Validators are given again with the field on every render
+		field.validators.clear();
+		FormField {
+			form: self.clone(),
+			name,
+		}
+	}
+
+	pub fn value(&self, name: &str) -> Option<String> {
+		self
+			.data
+			.borrow()
+			.fields
+			.get(name)
+			.map(|field| field.value.clone())
+	}
+
+	/// Returns the values of every field by name.
+	pub fn values(&self) -> BTreeMap<String, String> {
+		self
+			.data
+			.borrow()
+			.fields
+			.iter()
+			.map(|(name, field)| (name.clone(), field.value.clone()))
+			.collect()
+	}
+
+	/// Returns `true` if a field differs from its initial value.
+	pub fn is_dirty(&self) -> bool {
+		self
+			.data
+			.borrow()
+			.fields
+			.values()
+			.any(|field| field.value != field.initial)
+	}
+
+	/// Returns `true` if every validator of every field passes.
+	pub fn is_valid(&self) -> bool {
+		self.errors().is_empty()
+	}
+
+	/// Returns the error of every invalid field by name, whether it is shown yet or not.
+	pub fn errors(&self) -> BTreeMap<String, String> {
+		let names = self
+			.data
+			.borrow()
+			.fields
+			.keys()
+			.cloned()
+			.collect::<Vec<_>>();
+		names
+			.into_iter()
+			.filter_map(|name| {
+				let error = validate(&self.data, &name)?;
+				Some((name, error))
+			})
+			.collect()
+	}
+
+	/// Submits the form like pressing Enter in one of its fields, for a submit button.
+	pub fn submit(&self) {
+		self.data.borrow_mut().submit_requested = true;
+		crate::REQUEST_REDRAW.call();
+	}
+
+	/// Puts the initial values back and hides the errors.
+	pub fn reset(&self) {
+		let mut data = self.data.borrow_mut();
+		for field in data.fields.values_mut() {
+			field.value = field.initial.clone();
+			field.touched = false;
+		}
+		data.submitted = false;
+		crate::REQUEST_REDRAW.call();
+	}
+
+	/// Makes the current values the initial ones, once they are saved, so the form is no longer dirty.
+	pub fn mark_clean(&self) {
+		let mut data = self.data.borrow_mut();
+		for field in data.fields.values_mut() {
+			field.initial = field.value.clone();
+			field.touched = false;
+		}
+		data.submitted = false;
+		crate::REQUEST_REDRAW.call();
+	}
+}
+
+/// A field of a [`FormState`], returned by [`FormState::field`].
+#[derive(Clone)]
+pub struct FormField {
+	form: FormState,
+	name: String,
+}
+
+impl FormField {
+	/// Adds a validator, which returns the message to show when the value is invalid.
+	///
+	/// Validators run in the order they are added and the first error is shown.
+	pub fn validate(self, validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+		self.with_data(|field| field.validators.push(Rc::new(validator)));
+		self
+	}
+
+	/// Shows `message` when the value is empty or only whitespace.
+	pub fn required(self, message: impl Into<String>) -> Self {
+		let message = message.into();
+		self.validate(move |value| {
+			if value.trim().is_empty() {
+				Err(message.clone())
+			} else {
+				Ok(())
+			}
+		})
+	}
+
+	/// Runs `f` with the data of the field, or returns `None` if it was dropped.
+	fn with_data<R>(&self, f: impl FnOnce(&mut FieldData) -> R) -> Option<R> {
+		let mut data = self.form.data.borrow_mut();
+		data.fields.get_mut(&self.name).map(f)
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn value(&self) -> String {
+		self
+			.with_data(|field| field.value.clone())
+			.unwrap_or_default()
+	}
+
+	/// Changes the value, as the user editing the field.
+	pub fn set(&self, value: impl Into<String>) {
+		let value = value.into();
+		self.with_data(|field| {
+			field.value = value;
+			field.touched = true;
+		});
+		crate::REQUEST_REDRAW.call();
+	}
+
+	pub fn is_dirty(&self) -> bool {
+		self
+			.with_data(|field| field.value != field.initial)
+			.unwrap_or(false)
+	}
+
+	/// Returns the error to show for the field: the message of its first failing validator, once the field was
+	/// edited or the form was submitted.
+	pub fn error(&self) -> Option<String> {
+		let submitted = self.form.data.borrow().submitted;
+		if !submitted && !self.with_data(|field| field.touched).unwrap_or(false) {
+			return None;
+		}
+		validate(&self.form.data, &self.name)
+	}
+}
+
+/// Keeps the fields of a form across renders.
+///
+/// ```rust,ignore
+/// let form = use_form();
+/// let name = form.field("name", settings.name.clone()).required("Enter a name");
+/// Form::new(
+///     &form,
+///     Container::column()
+///         .child(TextInput::for_field(&name).placeholder("Name"))
+///         .child(FieldError::new(&name))
+///         .child(Text::new(if form.is_dirty() { "Unsaved changes" } else { "" })),
+/// )
+/// .on_submit(move |values| save(values))
+/// ```
+pub fn use_form() -> FormState {
+	FormState {
+		data: use_ref(FormData::default()),
+	}
+}
+
+thread_local! {
+	/// Enter was pressed in a text field inside the [`Form`] being rendered.
+	static SUBMIT_REQUESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Submits the innermost [`Form`] around the element being rendered, called by text fields on Enter.
+pub(crate) fn request_submit() {
+	SUBMIT_REQUESTED.set(true);
+}
+
+/// Submits the fields of a [`FormState`] when Enter is pressed in a text field inside it.
+///
+/// Submitting shows the errors of every field, and calls [`Form::on_submit`] with the values if they are all
+/// valid. The form doesn't draw anything itself, its content is laid out as if it wasn't there.
+pub struct Form {
+	state: FormState,
+	content: Box<dyn Element>,
+	on_submit: Option<Box<dyn Fn(BTreeMap<String, String>)>>,
+}
+
+impl Form {
+	pub fn new(state: &FormState, content: impl Element + 'static) -> Self {
+		Self {
+			state: state.clone(),
+			content: Box::new(content),
+			on_submit: None,
+		}
+	}
+
+	/// Called with the values of the fields by name when the form is submitted and valid.
+	pub fn on_submit(mut self, handler: impl Fn(BTreeMap<String, String>) + 'static) -> Self {
+		self.on_submit = Some(Box::new(handler));
+		self
+	}
+
+	fn submit(&self) {
+		self.state.data.borrow_mut().submitted = true;
+		crate::REQUEST_REDRAW.call();
+		if !self.state.is_valid() {
+			return;
+		}
+		if let Some(on_submit) = &self.on_submit {
+			on_submit(self.state.values());
+		}
+	}
+}
+
+impl Element for Form {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		// Forms inside forms submit on their own
+		let outer_request = SUBMIT_REQUESTED.replace(false);
+		{
+			let mut data = self.state.data.borrow_mut();
+			let declared = std::mem::take(&mut data.declared);
+			data.fields.retain(|name, _| declared.contains(name));
+		}
+		self.content.render(ctx);
+		let requested = SUBMIT_REQUESTED.replace(outer_request);
+		let requested = std::mem::take(&mut self.state.data.borrow_mut().submit_requested) || requested;
+		if requested {
+			self.submit();
+		}
+	}
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.content.focus_nodes()
+	}
+}
+
+/// Shows the error of a field of a form, or nothing while it has none.
+pub struct FieldError {
+	error: Option<String>,
+}
+
+impl FieldError {
+	pub fn new(field: &FormField) -> Self {
+		Self {
+			error: field.error(),
+		}
+	}
+}
+
+impl Element for FieldError {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		if let Some(error) = &self.error {
+			Text::new(error.clone())
+				.font_size(12)
+				.color((192, 28, 40, 255))
+				.render(ctx);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Key, NamedKey, TextInput, testing::TestHarness};
+
+	type Submitted = Rc<RefCell<Option<BTreeMap<String, String>>>>;
+
+	fn server_settings(submitted: Submitted) -> Box<dyn Element> {
+		let form = use_form();
+		let name = form.field("name", "").required("Enter a name");
+		let port = form.field("port", "8080").validate(|port| {
+			port
+				.parse::<u16>()
+				.map(drop)
+				.map_err(|_| "Not a port".to_string())
+		});
+		Box::new(
+			Form::new(
+				&form,
+				Container::column()
+					.child(TextInput::for_field(&name).placeholder("Name"))
+					.child(FieldError::new(&name))
+					.child(TextInput::for_field(&port).placeholder("Port"))
+					.child(FieldError::new(&port))
+					.child(Text::new(if form.is_dirty() { "Unsaved" } else { "Saved" })),
+			)
+			.on_submit(move |values| *submitted.borrow_mut() = Some(values)),
+		)
+	}
+
+	#[test]
+	fn test_form_validates_and_submits_on_enter() {
+		let submitted = Submitted::default();
+		let mut harness = TestHarness::new(server_settings, submitted.clone(), (400., 300.));
+		assert!(harness.find_by_label("Saved").is_some());
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.press_key(Key::Named(NamedKey::Enter));
+		assert!(submitted.borrow().is_none());
+		assert!(harness.find_by_label("Enter a name").is_some());
+
+		harness.type_text("box");
+		harness.frame();
+		assert!(harness.find_by_label("Enter a name").is_none());
+		assert!(harness.find_by_label("Unsaved").is_some());
+		harness.press_key(Key::Named(NamedKey::Enter));
+		let values = submitted.borrow_mut().take().unwrap();
+		assert_eq!(values["name"], "box");
+		assert_eq!(values["port"], "8080");
+	}
+
+	/// A form whose company field is only shown for business accounts.
+	fn signup(submitted: Submitted) -> Box<dyn Element> {
+		let form = use_form();
+		let (business, set_business) = crate::use_state(true);
+		let name = form.field("name", "Ada").required("Enter a name");
+		let mut content = Container::column()
+			.child(TextInput::for_field(&name))
+			.child(
+				Container::new()
+					.label("personal")
+					.on_click(move || set_business(false)),
+			);
+		if business {
+			let company = form.field("company", "").required("Enter a company");
+			content = content
+				.child(TextInput::for_field(&company))
+				.child(FieldError::new(&company));
+		}
+		let submit = form.clone();
+		Box::new(
+			Form::new(
+				&form,
+				content.child(
+					Container::new()
+						.label("submit")
+						.on_click(move || submit.submit()),
+				),
+			)
+			.on_submit(move |values| *submitted.borrow_mut() = Some(values)),
+		)
+	}
+
+	#[test]
+	fn test_hidden_fields_are_dropped() {
+		let submitted = Submitted::default();
+		let mut harness = TestHarness::new(signup, submitted.clone(), (400., 300.));
+		harness.click_label("submit");
+		assert!(submitted.borrow().is_none());
+		assert!(harness.find_by_label("Enter a company").is_some());
+
+		harness.click_label("personal");
+		harness.click_label("submit");
+		let values = submitted.borrow_mut().take().unwrap();
+		assert_eq!(values.keys().collect::<Vec<_>>(), ["name"]);
+	}
+}
@@ -0,0 +1,289 @@
+//! The results of a launcher: the items matching a fuzzy query, best first, with the matched letters
+//! highlighted.
+use std::{
+	cell::RefCell,
+	collections::HashSet,
+	hash::{DefaultHasher, Hash, Hasher},
+	rc::Rc,
+};
+
+use uuid::Uuid;
+
+use crate::{
+	Container, Element, FuzzyMatch, KeyAction, RenderContext, RichText, Role, SelectionMode, Span,
+	TextInput, focus_system::GLOBAL_FOCUS_MANAGER, fuzzy_match, shortcuts::use_shortcut_if, use_ref,
+	use_selection, use_theme,
+};
+
+/// How many results a [`FuzzyList`] shows, so lists of thousands of items stay fast to render.
+const MAX_RESULTS: usize = 50;
+
+/// The items of a list matching a query, filtered by [`use_fuzzy_filter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyResult {
+	/// The index of the item in the list.
+	pub index: usize,
+	pub matched: FuzzyMatch,
+}
+
+/// Matches `query` against the items at `candidates`, best matches first, then shorter items, then in list order.
+fn filter<S: AsRef<str>>(
+	items: &[S],
+	query: &str,
+	candidates: impl Iterator<Item = usize>,
+) -> Vec<FuzzyResult> {
+	let mut results = candidates
+		.filter_map(|index| {
+			let matched = fuzzy_match(query, items[index].as_ref())?;
+			Some(FuzzyResult { index, matched })
+		})
+		.collect::<Vec<_>>();
+	results.sort_by_key(|result| {
+		(
+			-result.matched.score,
+			items[result.index].as_ref().chars().count(),
+			result.index,
+		)
+	});
+	results
+}
+
+/// The results of the last query, reused while more letters are typed.
+struct FilterCache {
+	items: u64,
+	query: String,
+	results: Rc<Vec<FuzzyResult>>,
+}
+
+/// Returns the items matching `query` with [`fuzzy_match`], best first.
+///
+/// Typing more letters only matches the items that matched before against the longer query, so filtering
+/// thousands of items stays fast while typing. The results are kept until the items or the query change.
+pub fn use_fuzzy_filter<S: AsRef<str>>(items: &[S], query: &str) -> Rc<Vec<FuzzyResult>> {
+	let cache = use_ref(None::<FilterCache>);
+	let hash = {
+		let mut hasher = DefaultHasher::new();
+		for item in items {
+			item.as_ref().hash(&mut hasher);
+		}
+		hasher.finish()
+	};
+	let mut cache = cache.borrow_mut();
+	let results = match cache.as_ref().filter(|cache| cache.items == hash) {
+		Some(cache) if cache.query == query => return cache.results.clone(),
+		Some(cache) if query.starts_with(&cache.query) => {
+			let candidates = cache
+				.results
+				.iter()
+				.map(|result| result.index)
+				.collect::<Vec<_>>();
+			filter(items, query, candidates.into_iter())
+		}
+		_ => filter(items, query, 0..items.len()),
+	};
+	let results = Rc::new(results);
+	*cache = Some(FilterCache {
+		items: hash,
+		query: query.to_string(),
+		results: results.clone(),
+	});
+	results
+}
+
+/// Returns `text` with the characters at `indices` in bold and in `color`.
+fn highlight(text: &str, indices: &[usize], color: clay_layout::Color) -> RichText {
+	let mut rich_text = RichText::new();
+	let mut run = String::new();
+	let mut run_matched = false;
+	for (index, c) in text.chars().enumerate() {
+		let matched = indices.contains(&index);
+		if matched != run_matched && !run.is_empty() {
+			let span = Span::new(std::mem::take(&mut run));
+			rich_text = rich_text.span(if run_matched {
+				span.bold().color(color)
+			} else {
+				span
+			});
+		}
+		run_matched = matched;
+		run.push(c);
+	}
+	if !run.is_empty() {
+		let span = Span::new(run);
+		rich_text = rich_text.span(if run_matched {
+			span.bold().color(color)
+		} else {
+			span
+		});
+	}
+	rich_text
+}
+
+/// The items of a list matching a search query, like the apps of a launcher.
+///
+/// Items are matched with [`fuzzy_match`] and sorted with the best matches first, showing the letters that
+/// matched in bold. At most 50 results are shown. The first result is highlighted when the query changes, and
+/// `Up`, `Down` and `Enter` move the highlight and activate the highlighted item while a result or the
+/// [`search_input`](Self::search_input) has the focus, so they work while typing the query. Clicking an item
+/// activates it too.
+///
+/// ```rust,ignore
+/// let (query, set_query) = use_state(String::new());
+/// let names = apps.iter().map(|app| app.name.clone()).collect::<Vec<_>>();
+/// let input = TextInput::new(&query).on_change(move |text| set_query(text));
+/// let results = FuzzyList::new(&names, &query, move |index| launch(&apps[index])).search_input(&input);
+/// Container::column().child(input).child(results)
+/// ```
+pub struct FuzzyList {
+	root: Container,
+	/// The focus nodes the keys of the list are handled from.
+	scope: Rc<RefCell<HashSet<Uuid>>>,
+}
+
+impl FuzzyList {
+	/// Creates the list of the `items` matching `query`, calling `on_activate` with the index of the item picked.
+	pub fn new<S: AsRef<str>>(
+		items: &[S],
+		query: &str,
+		on_activate: impl Fn(usize) + 'static,
+	) -> Self {
+		let results = use_fuzzy_filter(items, query);
+		let shown = results.len().min(MAX_RESULTS);
+		// The highlighted result, the first one again when the query changes
+		let selection = use_selection(SelectionMode::Single);
+		let last_query = use_ref(None::<String>);
+		if last_query.borrow().as_deref() != Some(query) {
+			*last_query.borrow_mut() = Some(query.to_string());
+			selection.update(|model| model.select(0));
+		}
+		let highlighted = selection
+			.get()
+			.first()
+			.map(|index| index.min(shown.saturating_sub(1)));
+		let on_activate: Rc<dyn Fn(usize)> = Rc::new(on_activate);
+
+		// Filled once the list is built, the shortcuts are handled after that
+		let scope = Rc::new(RefCell::new(HashSet::new()));
+		let focused_in_scope = || {
+			let scope = scope.clone();
+			move || {
+				GLOBAL_FOCUS_MANAGER
+					.with_borrow(|f| f.focused())
+					.is_some_and(|focused| scope.borrow().contains(&focused))
+			}
+		};
+		let step = |action: KeyAction| {
+			let selection = selection.clone();
+			move || {
+				selection.update(|model| model.key_action(action, shown, false));
+			}
+		};
+		use_shortcut_if("up", focused_in_scope(), step(KeyAction::Up));
+		use_shortcut_if("down", focused_in_scope(), step(KeyAction::Down));
+		use_shortcut_if("enter", focused_in_scope(), {
+			let results = results.clone();
+			let on_activate = on_activate.clone();
+			let selection = selection.clone();
+			move || {
+				let highlighted = selection
+					.get()
+					.first()
+					.map(|index| index.min(shown.saturating_sub(1)));
+				if let Some(result) = highlighted.and_then(|index| results.get(index)) {
+					on_activate(result.index);
+				}
+			}
+		});
+
+		let accent = use_theme().colors.accent;
+		let root = results[..shown].iter().enumerate().fold(
+			Container::column().role(Role::List),
+			|list, (position, result)| {
+				let text = items[result.index].as_ref();
+				let index = result.index;
+				let on_activate = on_activate.clone();
+				list.child(
+					Container::new()
+						.role(Role::ListItem)
+						.label(text)
+						.symmetric_padding(10, 6)
+						.rounded(6.)
+						.selected(highlighted == Some(position))
+						.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+						.on_click(move || on_activate(index))
+						.child(highlight(text, &result.matched.indices, accent)),
+				)
+			},
+		);
+		scope.borrow_mut().extend(root.focus_nodes());
+		Self { root, scope }
+	}
+
+	/// Handles `Up`, `Down` and `Enter` while `input`, the field the query is typed in, has the focus too.
+	pub fn search_input(self, input: &TextInput) -> Self {
+		self.scope.borrow_mut().extend(input.focus_nodes());
+		self
+	}
+}
+
+impl Element for FuzzyList {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Key, NamedKey, Text, testing::TestHarness, use_state};
+
+	#[test]
+	fn test_results_are_ranked() {
+		let items = ["Color Designer", "Files", "Visual Studio Code", "Code"];
+		let order = |query| {
+			filter(&items, query, 0..items.len())
+				.iter()
+				.map(|result| result.index)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(order("code"), vec![3, 2, 0]);
+		assert_eq!(order("fil"), vec![1]);
+		assert_eq!(order(""), vec![3, 1, 0, 2]);
+	}
+
+	fn launcher(_: ()) -> Box<dyn Element> {
+		let (launched, set_launched) = use_state(None::<usize>);
+		let (query, set_query) = use_state(String::new());
+		let input = TextInput::new(&query).on_change(move |query| set_query(query));
+		let results = FuzzyList::new(&["Files", "Code", "Terminal"], &query, move |index| {
+			set_launched(Some(index))
+		})
+		.search_input(&input);
+		Box::new(
+			Container::column()
+				.child(Text::new(format!("launched {launched:?}")))
+				.child(input)
+				.child(results),
+		)
+	}
+
+	#[test]
+	fn test_arrow_keys_move_the_highlight() {
+		let mut harness = TestHarness::new(launcher, (), (400., 300.));
+		assert!(harness.expect_label("Code").selected);
+		// The keys are left to the rest of the app while the list and its input don't have the focus
+		harness.press_key(Key::Named(NamedKey::ArrowDown));
+		assert!(harness.expect_label("Code").selected);
+
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.press_key(Key::Named(NamedKey::ArrowDown));
+		assert!(harness.expect_label("Files").selected);
+		assert!(!harness.expect_label("Code").selected);
+		harness.press_key(Key::Named(NamedKey::Enter));
+		harness.expect_label("launched Some(0)");
+	}
+}
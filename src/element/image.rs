@@ -0,0 +1,154 @@
+use clay_layout::{Declaration, layout::Sizing};
+
+use crate::{
+	Element, ImageFit, ImageSource, RenderContext,
+	clay_renderer::get_source_dimensions_from_skia_image,
+};
+
+/// Shows an image, loaded from a path, bytes, a URL or an already decoded [`skia_safe::Image`].
+///
+/// The element has the size of the image in logical pixels unless a size is set, and nothing is
+/// drawn while the image is loading or if it failed to load.
+///
+/// ```rust,ignore
+/// Image::new()
+///     .src("/usr/share/backgrounds/default.webp")
+///     .size(320., 180.)
+///     .cover()
+///     .corner_radius(12.)
+/// ```
+pub struct Image {
+	image: Option<skia_safe::Image>,
+	width: Option<Sizing>,
+	height: Option<Sizing>,
+	fit: ImageFit,
+	corner_radius: f32,
+	alt: Option<String>,
+}
+
+impl Image {
+	pub fn new() -> Self {
+		Self {
+			image: None,
+			width: None,
+			height: None,
+			fit: ImageFit::default(),
+			corner_radius: 0.,
+			alt: None,
+		}
+	}
+
+	/// Loads the image from `source`, using the cache of [`ImageSource::load`].
+	pub fn src(mut self, source: impl Into<ImageSource>) -> Self {
+		self.image = source.into().load();
+		self
+	}
+
+	pub fn path(self, path: impl Into<std::path::PathBuf>) -> Self {
+		self.src(ImageSource::path(path))
+	}
+
+	/// Loads an encoded image, like one embedded with `include_bytes!`.
+	pub fn bytes(self, bytes: impl Into<std::sync::Arc<[u8]>>) -> Self {
+		self.src(ImageSource::bytes(bytes))
+	}
+
+	/// Shows an image that is already decoded, like one drawn with skia.
+	pub fn skia_image(mut self, image: skia_safe::Image) -> Self {
+		self.image = Some(image);
+		self
+	}
+
+	pub fn width(mut self, width: f32) -> Self {
+		self.width = Some(Sizing::Fixed(width));
+		self
+	}
+
+	pub fn height(mut self, height: f32) -> Self {
+		self.height = Some(Sizing::Fixed(height));
+		self
+	}
+
+	pub fn size(self, width: f32, height: f32) -> Self {
+		self.width(width).height(height)
+	}
+
+	pub fn w_expand(mut self) -> Self {
+		self.width = Some(Sizing::Grow(0., f32::MAX));
+		self
+	}
+
+	pub fn h_expand(mut self) -> Self {
+		self.height = Some(Sizing::Grow(0., f32::MAX));
+		self
+	}
+
+	pub fn fit(mut self, fit: ImageFit) -> Self {
+		self.fit = fit;
+		self
+	}
+
+	/// Scales the image to fit inside the element. See [`ImageFit::Contain`].
+	pub fn contain(self) -> Self {
+		self.fit(ImageFit::Contain)
+	}
+
+	/// Scales the image to cover the element. See [`ImageFit::Cover`].
+	pub fn cover(self) -> Self {
+		self.fit(ImageFit::Cover)
+	}
+
+	pub fn corner_radius(mut self, radius: f32) -> Self {
+		self.corner_radius = radius;
+		self
+	}
+
+	/// Describes the image to screen readers.
+	pub fn alt(mut self, alt: impl Into<String>) -> Self {
+		self.alt = Some(alt.into());
+		self
+	}
+}
+
+impl Default for Image {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Element for Image {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let natural_size = self.image.as_ref().map_or((0., 0.), |image| {
+			(image.width() as f32, image.height() as f32)
+		});
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.width(self.width.unwrap_or(Sizing::Fixed(natural_size.0)))
+					.height(self.height.unwrap_or(Sizing::Fixed(natural_size.1)))
+					.end()
+					.corner_radius()
+					.top_left(self.corner_radius)
+					.top_right(self.corner_radius)
+					.bottom_left(self.corner_radius)
+					.bottom_right(self.corner_radius)
+					.end();
+				if let Some(image) = &self.image {
+					crate::image::set_fit(image, self.fit);
+					declaration
+						.image()
+						.data(image)
+						.source_dimensions(get_source_dimensions_from_skia_image(image))
+						.end();
+				}
+				declaration
+			},
+			|_| {},
+		);
+		if let Some(alt) = &self.alt {
+			crate::accessibility::add_text(alt);
+		}
+	}
+}
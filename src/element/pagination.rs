@@ -0,0 +1,113 @@
+//! Page numbers with jump controls, for results split across pages like `/search/:page` routes.
+use std::rc::Rc;
+
+use crate::{Container, Role, Text};
+
+/// Returns the pages numbered out of `count`, `None` standing for the ones skipped.
+///
+/// The first and last pages and the neighbours of `current` are always shown. A gap of a single page shows that
+/// page, since it takes as much room as the `…` would.
+fn visible_pages(current: usize, count: usize) -> Vec<Option<usize>> {
+	let mut pages = Vec::new();
+	for page in 1..=count {
+		let shown = page == 1 || page == count || page.abs_diff(current) <= 1;
+		if shown {
+			if let Some(&Some(previous)) = pages.last() {
+				match page - previous {
+					1 => {}
+					2 => pages.push(Some(previous + 1)),
+					_ => pages.push(None),
+				}
+			}
+			pages.push(Some(page));
+		}
+	}
+	pages
+}
+
+/// Builds the controls of `count` pages with `current` showing, pages being numbered from `1`.
+///
+/// Picking a page number, or the first, previous, next and last page buttons, calls `on_change` with the page to
+/// show, usually to navigate to its route.
+///
+/// ```rust,ignore
+/// let navigator = use_navigate();
+/// pagination(page, page_count, move |page| navigator.navigate(format!("/search/{page}")))
+/// ```
+pub fn pagination(current: usize, count: usize, on_change: impl Fn(usize) + 'static) -> Container {
+	let count = count.max(1);
+	let current = current.clamp(1, count);
+	let on_change: Rc<dyn Fn(usize)> = Rc::new(on_change);
+	let button = |text: String, label: String, target: Option<usize>| {
+		let entry = Container::new()
+			.role(Role::Button)
+			.label(label)
+			.min_width(28.)
+			.symmetric_padding(6, 4)
+			.rounded(4.)
+			.center();
+		match target {
+			Some(page) => {
+				let on_change = on_change.clone();
+				entry
+					.style_if_hovered(|style| style.background_color((53, 132, 228, 40)))
+					.on_click(move || on_change(page))
+					.child(Text::new(text))
+			}
+			None => entry.child(Text::new(text).color((150, 150, 150, 255))),
+		}
+	};
+	let previous = (current > 1).then(|| current - 1);
+	let next = (current < count).then(|| current + 1);
+	let pages = visible_pages(current, count).into_iter().fold(
+		Container::row()
+			.role(Role::Group)
+			.label("Pagination")
+			.gap(2)
+			.child(button("«".into(), "First page".into(), previous.map(|_| 1)))
+			.child(button("‹".into(), "Previous page".into(), previous)),
+		|pages, page| match page {
+			Some(page) if page == current => pages.child(
+				Container::new()
+					.label(format!("Page {page}"))
+					.selected(true)
+					.min_width(28.)
+					.symmetric_padding(6, 4)
+					.rounded(4.)
+					.center()
+					.child(Text::new(page.to_string())),
+			),
+			Some(page) => pages.child(button(page.to_string(), format!("Page {page}"), Some(page))),
+			None => pages.child(
+				Container::new()
+					.symmetric_padding(6, 4)
+					.child(Text::new("…")),
+			),
+		},
+	);
+	pages
+		.child(button("›".into(), "Next page".into(), next))
+		.child(button("»".into(), "Last page".into(), next.map(|_| count)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_pages_around_the_current_one_are_shown() {
+		assert_eq!(
+			visible_pages(1, 4),
+			vec![Some(1), Some(2), Some(3), Some(4)]
+		);
+		assert_eq!(
+			visible_pages(5, 10),
+			vec![Some(1), None, Some(4), Some(5), Some(6), None, Some(10)]
+		);
+		assert_eq!(
+			visible_pages(4, 10),
+			vec![Some(1), Some(2), Some(3), Some(4), Some(5), None, Some(10)]
+		);
+		assert_eq!(visible_pages(1, 1), vec![Some(1)]);
+	}
+}
@@ -0,0 +1,230 @@
+//! Popups and modals drawn above the rest of the window from wherever they are declared.
+use std::{collections::HashSet, rc::Rc};
+
+use clay_layout::Color;
+use uuid::Uuid;
+
+use crate::{Align, Element, Justify, RenderContext, StateHandle, overlay, use_state_handle};
+
+/// Draws its child in a layer above the rest of the window, like a dialog or a popup declared next to the button
+/// that opens it.
+///
+/// The layer covers the whole window, with the child in its top left corner unless [`center`](Self::center)ed or
+/// aligned, and portals declared later are drawn above earlier ones. The containers of the child catch the
+/// pointer, and a [`backdrop`](Self::backdrop) keeps it from the rest of the window. With
+/// [`on_dismiss`](Self::on_dismiss), pressing the pointer outside of the child or pressing Escape closes the portal. Escape then closes the topmost portal only, and doesn't blur the focused element.
+///
+/// Most portals are opened with [`use_overlay`]:
+///
+/// ```rust,ignore
+/// let dialog = use_overlay();
+/// Container::column()
+///     .child(Container::new().on_click({
+///         let dialog = dialog.clone();
+///         move || dialog.open()
+///     }).child(Text::new("Delete…")))
+///     .child(
+///         dialog
+///             .portal(confirmation)
+///             .center()
+///             .backdrop((0, 0, 0, 120)),
+///     )
+/// ```
+pub struct Portal {
+	child: Box<dyn Element>,
+	open: bool,
+	justify: Justify,
+	align: Align,
+	backdrop: Option<Color>,
+	on_dismiss: Option<Rc<dyn Fn()>>,
+	dismiss_on_click_outside: bool,
+	dismiss_on_escape: bool,
+}
+
+impl Portal {
+	pub fn new(child: impl Element + 'static) -> Self {
+		Self {
+			child: Box::new(child),
+			open: true,
+			justify: Justify::Left,
+			align: Align::Top,
+			backdrop: None,
+			on_dismiss: None,
+			dismiss_on_click_outside: true,
+			dismiss_on_escape: true,
+		}
+	}
+
+	/// Shows the portal only while `open` is `true`, so it can be declared on every render. Defaults to `true`.
+	pub fn open(mut self, open: bool) -> Self {
+		self.open = open;
+		self
+	}
+
+	/// Places the child horizontally in the window.
+	pub fn justify(mut self, justify: Justify) -> Self {
+		self.justify = justify;
+		self
+	}
+
+	/// Places the child vertically in the window.
+	pub fn align(mut self, align: Align) -> Self {
+		self.align = align;
+		self
+	}
+
+	/// Places the child in the middle of the window, like a dialog.
+	pub fn center(mut self) -> Self {
+		self.justify = Justify::Center;
+		self.align = Align::Center;
+		self
+	}
+
+	/// Covers the window below the child with `color`, catching the pointer like the backdrop of a modal.
+	pub fn backdrop(mut self, color: impl Into<Color>) -> Self {
+		self.backdrop = Some(color.into());
+		self
+	}
+
+	/// Called when the pointer is pressed outside of the child, on the backdrop or not, or when Escape is pressed.
+	/// It should stop showing the portal.
+	pub fn on_dismiss(mut self, handler: impl Fn() + 'static) -> Self {
+		self.on_dismiss = Some(Rc::new(handler));
+		self
+	}
+
+	/// Whether pressing the pointer outside of the child calls [`on_dismiss`](Self::on_dismiss), like for menus.
+	/// Defaults to `true`.
+	pub fn dismiss_on_click_outside(mut self, dismiss: bool) -> Self {
+		self.dismiss_on_click_outside = dismiss;
+		self
+	}
+
+	/// Whether pressing Escape calls [`on_dismiss`](Self::on_dismiss). Defaults to `true`.
+	pub fn dismiss_on_escape(mut self, dismiss: bool) -> Self {
+		self.dismiss_on_escape = dismiss;
+		self
+	}
+}
+
+impl Element for Portal {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		if !self.open {
+			return;
+		}
+		if let Some(backdrop) = self.backdrop {
+			overlay::render_layer(ctx, Some(backdrop), (Justify::Left, Align::Top), None);
+		}
+		let layer = overlay::render_layer(
+			ctx,
+			None,
+			(self.justify, self.align),
+			Some(self.child.as_ref()),
+		);
+		if let Some(on_dismiss) = &self.on_dismiss {
+			overlay::dismissible(
+				layer,
+				on_dismiss.clone(),
+				self.dismiss_on_click_outside,
+				self.dismiss_on_escape,
+			);
+		}
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		if self.open {
+			self.child.focus_nodes()
+		} else {
+			HashSet::new()
+		}
+	}
+}
+
+/// Whether a popup or a modal is open, from [`use_overlay`].
+#[derive(Clone)]
+pub struct Overlay {
+	open: bool,
+	handle: StateHandle<bool>,
+}
+
+impl Overlay {
+	pub fn is_open(&self) -> bool {
+		self.open
+	}
+
+	pub fn open(&self) {
+		self.handle.set(true);
+	}
+
+	pub fn close(&self) {
+		self.handle.set(false);
+	}
+
+	pub fn toggle(&self) {
+		self.handle.update(|open| !open);
+	}
+
+	/// Returns a [`Portal`] showing `child` while the overlay is open, closed when it is dismissed.
+	pub fn portal(&self, child: impl Element + 'static) -> Portal {
+		let handle = self.handle.clone();
+		Portal::new(child)
+			.open(self.open)
+			.on_dismiss(move || handle.set(false))
+	}
+}
+
+/// Returns whether a popup or a modal of the component is open, closed at first, to show it with
+/// [`Overlay::portal`].
+pub fn use_overlay() -> Overlay {
+	let (open, handle) = use_state_handle(false);
+	Overlay { open, handle }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Key, NamedKey, Text, testing::TestHarness};
+
+	fn app(_: ()) -> Box<dyn Element> {
+		let dialog = use_overlay();
+		let open = dialog.clone();
+		Box::new(
+			Container::column()
+				.child(
+					Container::new()
+						.label("Open")
+						.on_click(move || open.open())
+						.child(Text::new("Open")),
+				)
+				.child(
+					dialog
+						.portal(
+							Container::new()
+								.label("Dialog")
+								.padding_all(20)
+								.child(Text::new("Hi")),
+						)
+						.center()
+						.backdrop((0, 0, 0, 120)),
+				),
+		)
+	}
+
+	#[test]
+	fn test_portals_are_dismissed() {
+		let mut harness = TestHarness::new(app, (), (400., 300.));
+		assert!(harness.find_by_label("Dialog").is_none());
+		harness.click_label("Open");
+		let (x, y) = harness.expect_label("Dialog").center().unwrap();
+		// Inside the dialog
+		harness.click(x, y);
+		assert!(harness.find_by_label("Dialog").is_some());
+		// On the backdrop, over the button
+		let (x, y) = harness.expect_label("Open").center().unwrap();
+		harness.click(x, y);
+		assert!(harness.find_by_label("Dialog").is_none());
+		harness.click_label("Open");
+		harness.press_key(Key::Named(NamedKey::Escape));
+		assert!(harness.find_by_label("Dialog").is_none());
+	}
+}
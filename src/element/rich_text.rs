@@ -0,0 +1,337 @@
+//! Text mixing font weights, colors and sizes within one paragraph.
+//!
+//! Clay lays every text element out on its own, so a [`RichText`] breaks its spans into lines itself, at the width
+//! it was laid out in the last frame, and declares each line as a row of text elements.
+use std::ops::Range;
+
+use clay_layout::{
+	Color, Declaration,
+	layout::{Alignment, LayoutDirection, Sizing},
+	math::Vector2,
+	text::{TextAlignment, TextConfig, TextElementConfigWrapMode},
+};
+use skia_safe::{Font, FontStyle, font_style::Width};
+use uuid::Uuid;
+
+use crate::{Align, Element, Justify, RenderContext};
+
+/// A run of text styled differently from the rest of a [`RichText`]. Styles left unset are the ones of the rich
+/// text.
+#[derive(Debug, Clone)]
+pub struct Span {
+	pub text: String,
+	pub font_weight: Option<i32>,
+	pub italic: Option<bool>,
+	pub font_size: Option<u16>,
+	pub color: Option<Color>,
+}
+
+impl Span {
+	pub fn new(text: impl Into<String>) -> Self {
+		Self {
+			text: text.into(),
+			font_weight: None,
+			italic: None,
+			font_size: None,
+			color: None,
+		}
+	}
+
+	pub fn bold(self) -> Self {
+		self.font_weight(700)
+	}
+
+	pub fn font_weight(mut self, weight: i32) -> Self {
+		self.font_weight = Some(weight);
+		self
+	}
+
+	pub fn italic(mut self, italic: bool) -> Self {
+		self.italic = Some(italic);
+		self
+	}
+
+	pub fn font_size(mut self, size: u16) -> Self {
+		self.font_size = Some(size);
+		self
+	}
+
+	pub fn color(mut self, color: impl Into<Color>) -> Self {
+		self.color = Some(color.into());
+		self
+	}
+}
+
+/// A paragraph made of [`Span`]s, wrapped between words to the width of its parent.
+///
+/// In RSML, a `<text>` with `<span>` children is a rich text:
+///
+/// ```rust,ignore
+/// rsml! {
+///     <text font_size={16}>Hello <span bold color={(53, 132, 228, 255)}>world</span></text>
+/// }
+/// // is
+/// RichText::new()
+///     .span(Span::new("Hello "))
+///     .span(Span::new("world").bold().color((53, 132, 228, 255)))
+///     .font_size(16)
+/// ```
+pub struct RichText {
+	pub spans: Vec<Span>,
+	pub font_family: String,
+	pub italic: bool,
+	pub font_size: u16,
+	pub color: Color,
+	pub alignment: TextAlignment,
+	/// Keeps the width the lines were broken at, see [`measured_width`](crate::element::text::measured_width).
+	pub(crate) measure_key: Uuid,
+}
+
+impl Default for RichText {
+	fn default() -> Self {
+		Self {
+			spans: Vec::new(),
+			font_family: "".to_string(),
+			italic: false,
+			font_size: 14,
+			color: (0, 0, 0, 255).into(),
+			alignment: TextAlignment::Left,
+			measure_key: crate::element::text::use_measure_key(),
+		}
+	}
+}
+
+impl RichText {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn span(mut self, span: Span) -> Self {
+		self.spans.push(span);
+		self
+	}
+
+	pub fn text_center(mut self) -> Self {
+		self.alignment = TextAlignment::Center;
+		self
+	}
+
+	pub fn text_right(mut self) -> Self {
+		self.alignment = TextAlignment::Right;
+		self
+	}
+
+	pub fn text_left(mut self) -> Self {
+		self.alignment = TextAlignment::Left;
+		self
+	}
+
+	pub fn font_size(mut self, size: u16) -> Self {
+		self.font_size = size;
+		self
+	}
+
+	pub fn color(mut self, color: impl Into<Color>) -> Self {
+		self.color = color.into();
+		self
+	}
+
+	pub fn italic(mut self, italic: bool) -> Self {
+		self.italic = italic;
+		self
+	}
+
+	pub fn font_family(mut self, family: impl Into<String>) -> Self {
+		self.font_family = family.into();
+		self
+	}
+
+	/// Returns the text of all the spans.
+	pub fn text(&self) -> String {
+		self.spans.iter().map(|span| span.text.as_str()).collect()
+	}
+}
+
+/// A piece of a line: the index of a span and a range of bytes of its text.
+type Fragment = (usize, Range<usize>);
+
+/// Breaks the texts of the spans into lines of fragments.
+///
+/// Lines break after `\n` and, when a wrap width is given, between words like [`Text`](crate::Text) does. A word
+/// styled by several spans is kept together.
+fn break_lines(
+	spans: &[&str],
+	measure: impl Fn(usize, &str) -> f32,
+	wrap_width: Option<f32>,
+) -> Vec<Vec<Fragment>> {
+	let mut lines = vec![Vec::new()];
+	let mut line_width = 0.;
+	// Lines only wrap after a space
+	let mut after_space = false;
+	for (span, text) in spans.iter().enumerate() {
+		let mut start = 0;
+		for (index, paragraph) in text.split('\n').enumerate() {
+			if index > 0 {
+				lines.push(Vec::new());
+				line_width = 0.;
+				after_space = false;
+			}
+			for word in paragraph.split_inclusive(' ') {
+				// Trailing spaces can hang past the wrap width
+				let width = measure(span, word.trim_end_matches(' '));
+				if let Some(wrap_width) = wrap_width
+					&& after_space
+					&& line_width + width > wrap_width
+				{
+					lines.push(Vec::new());
+					line_width = 0.;
+				}
+				line_width += measure(span, word);
+				let line = lines.last_mut().expect("there is always a line");
+				match line.last_mut() {
+					Some((last, range)) if *last == span && range.end == start => range.end += word.len(),
+					_ => line.push((span, start..start + word.len())),
+				}
+				after_space = word.ends_with(' ');
+				start += word.len();
+			}
+			// Skips the line break
+			start += 1;
+		}
+	}
+	lines
+}
+
+impl Element for RichText {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let styles = self
+			.spans
+			.iter()
+			.map(|span| {
+				let font_style = FontStyle::new(
+					span.font_weight.unwrap_or(400).into(),
+					Width::NORMAL,
+					if span.italic.unwrap_or(self.italic) {
+						skia_safe::font_style::Slant::Italic
+					} else {
+						skia_safe::font_style::Slant::Upright
+					},
+				);
+				let font_id = ctx.font_manager.get(&self.font_family, font_style);
+				let font_size = span.font_size.unwrap_or(self.font_size) as f32 * crate::text_scale();
+				let color = if crate::prefers_high_contrast() {
+					crate::high_contrast_colors().foreground
+				} else {
+					span.color.clone().unwrap_or_else(|| self.color.clone())
+				};
+				(font_id, font_size.round() as u16, color)
+			})
+			.collect::<Vec<_>>();
+		let (id, width) = crate::element::text::measured_width(ctx, self.measure_key);
+		let lines = {
+			let fonts = styles
+				.iter()
+				.map(|(font_id, font_size, _)| {
+					Font::new(
+						&ctx.font_manager.get_fonts()[*font_id as usize],
+						*font_size as f32,
+					)
+				})
+				.collect::<Vec<_>>();
+			let texts = self
+				.spans
+				.iter()
+				.map(|span| span.text.as_str())
+				.collect::<Vec<_>>();
+			break_lines(&texts, |span, s| fonts[span].measure_str(s, None).0, width)
+		};
+		let justify = match self.alignment {
+			TextAlignment::Left => Justify::Left,
+			TextAlignment::Center => Justify::Center,
+			TextAlignment::Right => Justify::Right,
+		};
+
+		ctx.font_manager.update_clay_measure_function(&mut ctx.c);
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration.id(id);
+				declaration
+					.layout()
+					.width(Sizing::Grow(0., f32::MAX))
+					.direction(LayoutDirection::TopToBottom)
+					.end()
+					// The lines don't widen the element, so they are broken at the width it is given
+					.clip(true, false, Vector2::new(0., 0.));
+				declaration
+			},
+			|c| {
+				for line in &lines {
+					c.with_styling(
+						|_| {
+							let mut declaration = Declaration::new();
+							declaration
+								.layout()
+								.width(Sizing::Grow(0., f32::MAX))
+								// Spans of different sizes share their bottom, close to a shared baseline
+								.child_alignment(Alignment::new(justify, Align::Bottom))
+								.end();
+							declaration
+						},
+						|c| {
+							for (span, range) in line {
+								let (font_id, font_size, color) = &styles[*span];
+								let text_config = TextConfig::new()
+									.font_size(*font_size)
+									.color(color.clone())
+									.font_id(*font_id)
+									.wrap_mode(TextElementConfigWrapMode::None)
+									.end();
+								c.text(&self.spans[*span].text[range.clone()], text_config);
+							}
+						},
+					);
+				}
+			},
+		);
+
+		let text = self.text();
+		crate::accessibility::add_text(&text);
+		if crate::inspector::is_collecting() {
+			let index = crate::inspector::push_entry(format!("RichText {text:?}"), None, None);
+			crate::inspector::set_details(
+				index,
+				vec![
+					format!("spans: {}", self.spans.len()),
+					format!("font_family: {:?}", self.font_family),
+					format!("italic: {}", self.italic),
+					format!("font_size: {}", self.font_size),
+					format!("color: {:?}", self.color),
+				],
+				Default::default(),
+			);
+			crate::inspector::pop_entry();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_lines_break_between_words_across_spans() {
+		let measure = |_: usize, s: &str| s.len() as f32 * 10.;
+		let lines = break_lines(&["Hello ", "wor", "ld and more"], measure, Some(80.));
+		assert_eq!(
+			lines,
+			[
+				vec![(0, 0..6)],
+				vec![(1, 0..3), (2, 0..3)],
+				vec![(2, 3..11)]
+			]
+		);
+		let lines = break_lines(&["one\ntwo ", "three"], measure, None);
+		assert_eq!(lines, [vec![(0, 0..3)], vec![(0, 4..8), (1, 0..5)]]);
+	}
+}
@@ -0,0 +1,377 @@
+use std::{
+	cell::RefCell,
+	collections::HashSet,
+	time::{Duration, Instant},
+};
+
+use clay_layout::{
+	Declaration,
+	layout::{LayoutDirection, Sizing},
+	math::Vector2,
+};
+use uuid::Uuid;
+
+use crate::{Component, Element, GlobalClosure, RenderContext};
+
+/// Which way the history moved, to slide pages in from the right side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+	Forward,
+	Back,
+}
+
+struct PageChange {
+	from: String,
+	direction: Direction,
+	start: Instant,
+}
+
+struct History {
+	entries: Vec<String>,
+	index: usize,
+	change: Option<PageChange>,
+}
+
+impl History {
+	fn location(&self) -> &str {
+		&self.entries[self.index]
+	}
+
+	fn go(&mut self, index: usize, direction: Direction) {
+		self.change = Some(PageChange {
+			from: self.location().to_string(),
+			direction,
+			start: crate::frame_scheduler::now(),
+		});
+		self.index = index;
+		crate::REQUEST_REDRAW.call();
+	}
+}
+
+thread_local! {
+	static HISTORY: RefCell<History> = RefCell::new(History {
+		entries: vec!["/".to_string()],
+		index: 0,
+		change: None,
+	});
+}
+
+/// Changes the location of the [`Router`]. Returned by [`use_navigate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Navigator {
+	_private: (),
+}
+
+impl Navigator {
+	/// Returns the current location, like `/settings/display`.
+	pub fn location(&self) -> String {
+		HISTORY.with_borrow(|history| history.location().to_string())
+	}
+
+	/// Goes to `path`, dropping the forward history.
+	pub fn navigate(&self, path: impl Into<String>) {
+		let path = path.into();
+		HISTORY.with_borrow_mut(|history| {
+			if history.location() == path {
+				return;
+			}
+			let index = history.index + 1;
+			history.entries.truncate(index);
+			history.entries.push(path);
+			history.go(index, Direction::Forward);
+		});
+	}
+
+	/// Replaces the current location with `path`, without a history entry or transition.
+	pub fn replace(&self, path: impl Into<String>) {
+		HISTORY.with_borrow_mut(|history| {
+			let index = history.index;
+			history.entries[index] = path.into();
+			history.change = None;
+		});
+		crate::REQUEST_REDRAW.call();
+	}
+
+	pub fn can_go_back(&self) -> bool {
+		HISTORY.with_borrow(|history| history.index > 0)
+	}
+
+	pub fn can_go_forward(&self) -> bool {
+		HISTORY.with_borrow(|history| history.index + 1 < history.entries.len())
+	}
+
+	/// Goes to the previous location, if any.
+	pub fn back(&self) {
+		HISTORY.with_borrow_mut(|history| {
+			if history.index > 0 {
+				history.go(history.index - 1, Direction::Back);
+			}
+		});
+	}
+
+	/// Goes to the next location after going back, if any.
+	pub fn forward(&self) {
+		HISTORY.with_borrow_mut(|history| {
+			if history.index + 1 < history.entries.len() {
+				history.go(history.index + 1, Direction::Forward);
+			}
+		});
+	}
+}
+
+/// Returns the navigator of the app, to change the page shown by the [`Router`].
+pub fn use_navigate() -> Navigator {
+	Navigator::default()
+}
+
+/// The values captured by the `:name` segments of a route.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RouteParams {
+	params: Vec<(String, String)>,
+}
+
+impl RouteParams {
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self
+			.params
+			.iter()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value.as_str())
+	}
+}
+
+/// Matches a path against a pattern like `/users/:id/*`, where `*` matches the rest of the path.
+fn match_route(pattern: &str, path: &str) -> Option<RouteParams> {
+	let mut params = Vec::new();
+	let mut segments = path.split('/').filter(|s| !s.is_empty());
+	for part in pattern.split('/').filter(|s| !s.is_empty()) {
+		if part == "*" {
+			return Some(RouteParams { params });
+		}
+		let segment = segments.next()?;
+		if let Some(name) = part.strip_prefix(':') {
+			params.push((name.to_string(), segment.to_string()));
+		} else if part != segment {
+			return None;
+		}
+	}
+	segments.next().is_none().then_some(RouteParams { params })
+}
+
+/// How the page changes when navigating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageTransition {
+	None,
+	/// The new page slides in from the right, or from the left when going back.
+	Slide(Duration),
+}
+
+/// Shows the page of the first route matching the location of the [`Navigator`].
+///
+/// Every route is a component with its own hook states, which are dropped when its page is left.
+///
+/// ```rust,ignore
+/// Router::new()
+///     .route("/", |_| home())
+///     .route("/settings/:section", |params| settings(params.get("section").unwrap()))
+///     .fallback(|_| not_found())
+/// ```
+pub struct Router {
+	location: String,
+	/// The page being left and how far the transition is, from `0` to `1`.
+	change: Option<(String, Direction, f32)>,
+	page: Option<Component>,
+	previous_page: Option<Component>,
+	/// Keeps the hook positions of the routes stable, whichever one matches.
+	placeholders: Vec<Component>,
+}
+
+impl Router {
+	/// Creates a router that slides pages in for 250 ms, or not at all with reduced motion.
+	pub fn new() -> Self {
+		Self::with_transition(PageTransition::Slide(Duration::from_millis(250)))
+	}
+
+	pub fn with_transition(transition: PageTransition) -> Self {
+		let (location, change) = HISTORY.with_borrow_mut(|history| {
+			let duration = match transition {
+				PageTransition::None => Duration::ZERO,
+				PageTransition::Slide(duration) => crate::animation_duration(duration),
+			};
+			let progress = history.change.as_ref().map(|change| {
+				(crate::frame_scheduler::elapsed(change.start).as_secs_f32() / duration.as_secs_f32())
+					.min(1.)
+			});
+			if progress.is_none_or(|p| p >= 1. || p.is_nan()) {
+				history.change = None;
+			}
+			let change = history
+				.change
+				.as_ref()
+				.zip(progress)
+				.map(|(change, progress)| (change.from.clone(), change.direction, progress));
+			(history.location().to_string(), change)
+		});
+		if change.is_some() {
+			crate::REQUEST_REDRAW.call();
+		}
+		Self {
+			location,
+			change,
+			page: None,
+			previous_page: None,
+			placeholders: Vec::new(),
+		}
+	}
+
+	/// Adds a route. `pattern` segments starting with `:` capture a parameter, and a final `*` matches any rest.
+	pub fn route(
+		mut self,
+		pattern: &str,
+		page: impl FnOnce(RouteParams) -> Box<dyn Element>,
+	) -> Self {
+		let current = self
+			.page
+			.is_none()
+			.then(|| match_route(pattern, &self.location))
+			.flatten();
+		let previous = self
+			.change
+			.as_ref()
+			.filter(|_| self.previous_page.is_none() && current.is_none())
+			.and_then(|(from, ..)| match_route(pattern, from));
+		let key = format!("route {pattern}");
+		match (current, previous) {
+			(Some(params), _) => self.page = Some(Component::new_with_key(page, params, key)),
+			(None, Some(params)) => self.previous_page = Some(Component::new_with_key(page, params, key)),
+			(None, None) => self.placeholders.push(Component::new_with_key(
+				|_| Box::new(Vec::<Box<dyn Element>>::new()),
+				(),
+				key,
+			)),
+		}
+		self
+	}
+
+	/// Adds the page shown when no route matches.
+	pub fn fallback(self, page: impl FnOnce(RouteParams) -> Box<dyn Element>) -> Self {
+		self.route("*", page)
+	}
+}
+
+impl Default for Router {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Declares a clipped page of the given width.
+fn render_page<'clay: 'render, 'render>(
+	ctx: &mut RenderContext<'clay, 'render, '_>,
+	page: &'render Component,
+	width: Sizing,
+) {
+	ctx.c.with_styling(
+		|_| {
+			let mut declaration = Declaration::new();
+			declaration
+				.layout()
+				.width(width)
+				.height(Sizing::Grow(0., f32::MAX))
+				.end()
+				.clip(true, true, Vector2::default());
+			declaration
+		},
+		|c| {
+			let mut page_ctx = RenderContext {
+				c,
+				font_manager: &mut *ctx.font_manager,
+				input_manager: ctx.input_manager,
+			};
+			page.render(&mut page_ctx);
+		},
+	);
+}
+
+impl Element for Router {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.direction(LayoutDirection::LeftToRight)
+					.width(Sizing::Grow(0., f32::MAX))
+					.height(Sizing::Grow(0., f32::MAX))
+					.end()
+					.clip(true, true, Vector2::default());
+				declaration
+			},
+			|c| {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				match (&self.page, &self.previous_page, &self.change) {
+					(Some(page), Some(previous), Some((_, direction, progress))) => {
+						// The leaving page shrinks while the new one grows from the side it comes from.
+						let (left, right, left_width) = match direction {
+							Direction::Forward => (previous, page, 1. - progress),
+							Direction::Back => (page, previous, *progress),
+						};
+						render_page(&mut ctx, left, Sizing::Percent(left_width));
+						render_page(&mut ctx, right, Sizing::Percent(1. - left_width));
+					}
+					(Some(page), ..) => render_page(&mut ctx, page, Sizing::Grow(0., f32::MAX)),
+					_ => {}
+				}
+			},
+		);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self
+			.page
+			.iter()
+			.flat_map(|page| page.focus_nodes())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_match_route() {
+		assert_eq!(match_route("/", "/"), Some(RouteParams::default()));
+		assert_eq!(
+			match_route("/settings", "/settings/"),
+			Some(RouteParams::default())
+		);
+		assert_eq!(match_route("/settings", "/settings/display"), None);
+		let params = match_route("/users/:id/posts/:post", "/users/42/posts/7").unwrap();
+		assert_eq!(params.get("id"), Some("42"));
+		assert_eq!(params.get("post"), Some("7"));
+		assert!(match_route("/files/*", "/files/a/b/c").is_some());
+		assert!(match_route("*", "/anything").is_some());
+	}
+
+	#[test]
+	fn test_history() {
+		let navigator = use_navigate();
+		navigator.replace("/");
+		navigator.navigate("/a");
+		navigator.navigate("/b");
+		navigator.back();
+		assert_eq!(navigator.location(), "/a");
+		assert!(navigator.can_go_forward());
+		navigator.navigate("/c");
+		assert!(!navigator.can_go_forward());
+		navigator.back();
+		navigator.back();
+		assert_eq!(navigator.location(), "/");
+		assert!(!navigator.can_go_back());
+	}
+}
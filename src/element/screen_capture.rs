@@ -0,0 +1,70 @@
+//! A live view of a monitor, a window or a part of the screen, fed by [`use_screen_capture`].
+use std::time::Duration;
+
+use crate::{CaptureSource, Element, Image, RenderContext, use_screen_capture};
+
+/// Shows what `source` shows on the screen, captured again every `interval`, like the monitors of a workspace
+/// overview or the choices of a screen-sharing picker.
+///
+/// The element fills its parent unless a size is set, and shows the capture without stretching it. Nothing is
+/// drawn until the first capture is taken, see [`use_screen_capture`] for what is needed to capture the screen.
+///
+/// ```rust,ignore
+/// monitors.iter().fold(Container::row().gap(8), |row, monitor| {
+///     row.child(
+///         ScreenCapture::new(&CaptureSource::Output(monitor.clone()), Duration::from_secs(1))
+///             .size(320., 180.)
+///             .corner_radius(8.),
+///     )
+/// })
+/// ```
+pub struct ScreenCapture {
+	image: Image,
+}
+
+impl ScreenCapture {
+	pub fn new(source: &CaptureSource, interval: Duration) -> Self {
+		let mut image = Image::new().w_expand().h_expand().contain();
+		if let Some(capture) = use_screen_capture(Some(source), interval) {
+			image = image.skia_image(capture);
+		}
+		Self { image }
+	}
+
+	pub fn width(mut self, width: f32) -> Self {
+		self.image = self.image.width(width);
+		self
+	}
+
+	pub fn height(mut self, height: f32) -> Self {
+		self.image = self.image.height(height);
+		self
+	}
+
+	pub fn size(self, width: f32, height: f32) -> Self {
+		self.width(width).height(height)
+	}
+
+	/// Crops the capture to fill the element instead of fitting it inside.
+	pub fn cover(mut self) -> Self {
+		self.image = self.image.cover();
+		self
+	}
+
+	pub fn corner_radius(mut self, radius: f32) -> Self {
+		self.image = self.image.corner_radius(radius);
+		self
+	}
+
+	/// Describes the capture to screen readers.
+	pub fn alt(mut self, alt: impl Into<String>) -> Self {
+		self.image = self.image.alt(alt);
+		self
+	}
+}
+
+impl Element for ScreenCapture {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.image.render(ctx);
+	}
+}
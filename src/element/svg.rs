@@ -0,0 +1,349 @@
+//! Vector images, like the icons of a shell, drawn with the SVG module of skia at the size they are shown.
+use std::{
+	cell::{OnceCell, RefCell},
+	collections::HashMap,
+};
+
+use clay_layout::Color;
+use skia_safe::{BlendMode, FontMgr, Image as SkiaImage, Paint, surfaces, svg::Dom};
+
+use crate::{
+	Element, Image, ImageFit, ImageSource, RenderContext, clay_renderer::clay_to_skia_color,
+};
+
+/// The size of the SVGs that set none, in logical pixels, the size of most icons.
+const DEFAULT_SIZE: f32 = 24.;
+
+/// A parsed SVG and its size in user units.
+struct Document {
+	dom: Dom,
+	size: (f32, f32),
+}
+
+thread_local! {
+	/// Parsed SVGs, or `None` for the ones that failed to load.
+	static DOCUMENTS: RefCell<HashMap<ImageSource, Option<Document>>> = RefCell::new(HashMap::new());
+	/// Drawn SVGs by source, size in pixels and tint.
+	static IMAGES: RefCell<HashMap<(ImageSource, (i32, i32), Option<[u32; 4]>), SkiaImage>> =
+		RefCell::new(HashMap::new());
+}
+
+/// Removes every SVG from the cache, see [`clear_image_cache`](crate::clear_image_cache).
+pub(crate) fn clear_cache() {
+	DOCUMENTS.with_borrow_mut(|documents| documents.clear());
+	IMAGES.with_borrow_mut(|images| images.clear());
+}
+
+/// Returns the value of the attribute `name` of the first tag of `tag`.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+	let mut rest = tag;
+	while let Some(start) = rest.find(name) {
+		let before = rest[..start].chars().next_back();
+		let after = rest[start + name.len()..].trim_start();
+		rest = &rest[start + name.len()..];
+		if !before.is_some_and(char::is_whitespace) {
+			continue;
+		}
+		let Some(value) = after.strip_prefix('=') else {
+			continue;
+		};
+		let value = value.trim_start();
+		let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+		let value = &value[1..];
+		return Some(&value[..value.find(quote)?]);
+	}
+	None
+}
+
+/// Returns the size of an SVG in user units, from the `width` and `height` of its root element or from its
+/// `viewBox`.
+fn intrinsic_size(svg: &str) -> Option<(f32, f32)> {
+	let start = svg.find("<svg")?;
+	let tag = &svg[start..start + svg[start..].find('>')?];
+	// Lengths in other units than pixels, like percentages, depend on where the SVG is shown
+	let length = |name: &str| {
+		let value = attribute(tag, name)?.trim();
+		value
+			.strip_suffix("px")
+			.unwrap_or(value)
+			.parse::<f32>()
+			.ok()
+	};
+	let view_box = attribute(tag, "viewBox").and_then(|view_box| {
+		let values = view_box
+			.split(|c: char| c.is_whitespace() || c == ',')
+			.filter(|value| !value.is_empty())
+			.map(|value| value.parse::<f32>().ok())
+			.collect::<Option<Vec<_>>>()?;
+		match values[..] {
+			[_, _, width, height] => Some((width, height)),
+			_ => None,
+		}
+	});
+	let size = match (length("width"), length("height"), view_box) {
+		(Some(width), Some(height), _) => (width, height),
+		(Some(width), None, Some((box_width, box_height))) => (width, width * box_height / box_width),
+		(None, Some(height), Some((box_width, box_height))) => {
+			(height * box_width / box_height, height)
+		}
+		(_, _, view_box) => view_box?,
+	};
+	(size.0 > 0. && size.1 > 0. && size.0.is_finite() && size.1.is_finite()).then_some(size)
+}
+
+/// Returns the size in pixels to draw an SVG of `size` at, to show it with `fit` in an element of `bounds`.
+fn raster_size(fit: ImageFit, size: (f32, f32), bounds: (f32, f32)) -> (i32, i32) {
+	let scale = match fit {
+		ImageFit::Fill => None,
+		ImageFit::Contain => Some((bounds.0 / size.0).min(bounds.1 / size.1)),
+		ImageFit::Cover => Some((bounds.0 / size.0).max(bounds.1 / size.1)),
+	};
+	let (width, height) = match scale {
+		Some(scale) => (size.0 * scale, size.1 * scale),
+		None => bounds,
+	};
+	(width.ceil().max(1.) as i32, height.ceil().max(1.) as i32)
+}
+
+fn parse(source: &ImageSource) -> Result<Document, String> {
+	let bytes = match source {
+		ImageSource::Path(path) => std::fs::read(path).map_err(|err| err.to_string())?,
+		ImageSource::Bytes(bytes) => bytes.to_vec(),
+		ImageSource::Url(_) => return Err("SVGs can't be loaded from URLs".to_string()),
+	};
+	let dom = Dom::from_bytes(&bytes, FontMgr::new()).map_err(|err| format!("{err:?}"))?;
+	let size =
+		intrinsic_size(&String::from_utf8_lossy(&bytes)).unwrap_or((DEFAULT_SIZE, DEFAULT_SIZE));
+	Ok(Document { dom, size })
+}
+
+/// Draws `document` stretched to `size` pixels, filled with `tint` where it isn't transparent.
+fn rasterize(document: &mut Document, size: (i32, i32), tint: Option<Color>) -> Option<SkiaImage> {
+	let mut surface = surfaces::raster_n32_premul(size)?;
+	let canvas = surface.canvas();
+	canvas.clear(skia_safe::Color::TRANSPARENT);
+	canvas.scale((
+		size.0 as f32 / document.size.0,
+		size.1 as f32 / document.size.1,
+	));
+	document.dom.set_container_size(document.size);
+	document.dom.render(canvas);
+	if let Some(tint) = tint {
+		canvas.reset_matrix();
+		let mut paint = Paint::new(clay_to_skia_color(tint), None);
+		paint.set_blend_mode(BlendMode::SrcIn);
+		canvas.draw_paint(&paint);
+	}
+	Some(surface.image_snapshot())
+}
+
+/// Shows an SVG, loaded from a path or bytes, drawn at the size it is shown so it stays sharp.
+///
+/// The element has the size of the SVG unless a size is set, and keeps its aspect ratio when only the width or
+/// the height is set. Parsed and drawn SVGs are cached, so the same SVG can be shown on every frame, at a few
+/// sizes, without drawing it again. Nothing is drawn if the SVG failed to load. The size is fixed, as the SVG is
+/// drawn before the layout.
+///
+/// ```rust,ignore
+/// Svg::new()
+///     .bytes(include_bytes!("icons/wifi.svg").as_slice())
+///     .size(16., 16.)
+///     .tint(theme.colors.text)
+/// ```
+pub struct Svg {
+	source: Option<ImageSource>,
+	width: Option<f32>,
+	height: Option<f32>,
+	fit: ImageFit,
+	tint: Option<Color>,
+	corner_radius: f32,
+	alt: Option<String>,
+	image: OnceCell<Image>,
+}
+
+impl Svg {
+	pub fn new() -> Self {
+		Self {
+			source: None,
+			width: None,
+			height: None,
+			fit: ImageFit::Contain,
+			tint: None,
+			corner_radius: 0.,
+			alt: None,
+			image: OnceCell::new(),
+		}
+	}
+
+	pub fn src(mut self, source: impl Into<ImageSource>) -> Self {
+		self.source = Some(source.into());
+		self
+	}
+
+	pub fn path(self, path: impl Into<std::path::PathBuf>) -> Self {
+		self.src(ImageSource::path(path))
+	}
+
+	/// Loads an SVG from bytes, like one embedded with `include_bytes!`.
+	pub fn bytes(self, bytes: impl Into<std::sync::Arc<[u8]>>) -> Self {
+		self.src(ImageSource::bytes(bytes))
+	}
+
+	pub fn width(mut self, width: f32) -> Self {
+		self.width = Some(width);
+		self
+	}
+
+	pub fn height(mut self, height: f32) -> Self {
+		self.height = Some(height);
+		self
+	}
+
+	pub fn size(self, width: f32, height: f32) -> Self {
+		self.width(width).height(height)
+	}
+
+	/// How the SVG is drawn when the element doesn't have its aspect ratio, [`ImageFit::Contain`] by default.
+	pub fn fit(mut self, fit: ImageFit) -> Self {
+		self.fit = fit;
+		self
+	}
+
+	pub fn contain(self) -> Self {
+		self.fit(ImageFit::Contain)
+	}
+
+	pub fn cover(self) -> Self {
+		self.fit(ImageFit::Cover)
+	}
+
+	/// Paints the SVG with `color`, keeping its transparency, like symbolic icons following the theme.
+	pub fn tint(mut self, color: impl Into<Color>) -> Self {
+		self.tint = Some(color.into());
+		self
+	}
+
+	pub fn corner_radius(mut self, radius: f32) -> Self {
+		self.corner_radius = radius;
+		self
+	}
+
+	/// Describes the SVG to screen readers.
+	pub fn alt(mut self, alt: impl Into<String>) -> Self {
+		self.alt = Some(alt.into());
+		self
+	}
+
+	/// Returns the image element showing the SVG, drawing the SVG if it isn't in the cache.
+	fn image(&self) -> Image {
+		let mut image = Image::new().fit(self.fit).corner_radius(self.corner_radius);
+		if let Some(alt) = &self.alt {
+			image = image.alt(alt.clone());
+		}
+		let Some(source) = &self.source else {
+			return image.size(self.width.unwrap_or(0.), self.height.unwrap_or(0.));
+		};
+		DOCUMENTS.with_borrow_mut(|documents| {
+			let document = documents.entry(source.clone()).or_insert_with(|| {
+				parse(source)
+					.inspect_err(|err| log::error!("failed to load SVG {source}: {err}"))
+					.ok()
+			});
+			// An SVG that failed to load keeps the space of an icon, so the layout doesn't jump
+			let size = document
+				.as_ref()
+				.map_or((DEFAULT_SIZE, DEFAULT_SIZE), |document| document.size);
+			let bounds = match (self.width, self.height) {
+				(Some(width), Some(height)) => (width, height),
+				(Some(width), None) => (width, width * size.1 / size.0),
+				(None, Some(height)) => (height * size.0 / size.1, height),
+				(None, None) => size,
+			};
+			let image = image.size(bounds.0, bounds.1);
+			let Some(document) = document else {
+				return image;
+			};
+			if bounds.0 <= 0. || bounds.1 <= 0. {
+				return image;
+			}
+			let pixels = raster_size(self.fit, document.size, bounds);
+			let tint = self
+				.tint
+				.map(|tint| [tint.r, tint.g, tint.b, tint.a].map(f32::to_bits));
+			let key = (source.clone(), pixels, tint);
+			let drawn = IMAGES
+				.with_borrow(|images| images.get(&key).cloned())
+				.or_else(|| {
+					let drawn = rasterize(document, pixels, self.tint)?;
+					IMAGES.with_borrow_mut(|images| images.insert(key, drawn.clone()));
+					Some(drawn)
+				});
+			match drawn {
+				Some(drawn) => image.skia_image(drawn),
+				None => image,
+			}
+		})
+	}
+}
+
+impl Default for Svg {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Element for Svg {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.image.get_or_init(|| self.image()).render(ctx);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SQUARE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><rect width="10" height="5" fill="#ff0000"/></svg>"##;
+
+	#[test]
+	fn test_svgs_are_drawn_at_their_size() {
+		assert_eq!(intrinsic_size(SQUARE), Some((10., 10.)));
+		assert_eq!(
+			intrinsic_size(r#"<?xml version="1.0"?><svg width="48px" height='32' viewBox="0,0,24,16">"#),
+			Some((48., 32.))
+		);
+		assert_eq!(
+			intrinsic_size(r#"<svg stroke-width="2" width="32" viewBox="0 0 24 12">"#),
+			Some((32., 16.))
+		);
+		assert_eq!(intrinsic_size(r#"<svg width="100%" height="100%">"#), None);
+		assert_eq!(
+			raster_size(ImageFit::Contain, (10., 5.), (40., 40.)),
+			(40, 20)
+		);
+		assert_eq!(
+			raster_size(ImageFit::Cover, (10., 5.), (40., 40.)),
+			(80, 40)
+		);
+		assert_eq!(raster_size(ImageFit::Fill, (10., 5.), (40., 40.)), (40, 40));
+
+		let mut document = parse(&ImageSource::bytes(SQUARE.as_bytes())).unwrap();
+		let image = rasterize(&mut document, (20, 20), Some(Color::rgb(0., 0., 255.))).unwrap();
+		let mut pixels = vec![0u8; 20 * 20 * 4];
+		let info = skia_safe::ImageInfo::new(
+			(20, 20),
+			skia_safe::ColorType::RGBA8888,
+			skia_safe::AlphaType::Unpremul,
+			None,
+		);
+		assert!(image.read_pixels(
+			&info,
+			&mut pixels,
+			20 * 4,
+			(0, 0),
+			skia_safe::image::CachingHint::Allow
+		));
+		let pixel = |x: usize, y: usize| &pixels[(y * 20 + x) * 4..(y * 20 + x) * 4 + 4];
+		assert_eq!(pixel(10, 4), [0, 0, 255, 255]);
+		assert_eq!(pixel(10, 15), [0, 0, 0, 0]);
+	}
+}
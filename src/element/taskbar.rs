@@ -0,0 +1,217 @@
+//! The workspace switcher and the window list of a panel, fed by [`use_hyprland`](crate::use_hyprland).
+use std::{collections::HashSet, time::Duration};
+
+use uuid::Uuid;
+
+use crate::{
+	AttachPoint, Container, Element, HyprlandState, Image, RenderContext, Role, Text,
+	hyprland_dispatch, use_state_handle, use_theme, use_window_thumbnail,
+};
+
+/// How often the preview of the hovered window is captured again.
+const PREVIEW_INTERVAL: Duration = Duration::from_secs(1);
+/// The width of window previews, their height following the shape of the window.
+const PREVIEW_WIDTH: f32 = 200.;
+
+/// A button per workspace, showing which one is active and switching to the one clicked.
+///
+/// Special workspaces, like the scratchpad, are left out. Empty workspaces are dimmed.
+///
+/// ```rust,ignore
+/// let Some(hyprland) = use_hyprland() else {
+///     return Box::new(Container::new());
+/// };
+/// Box::new(WorkspaceIndicator::new(&hyprland, Some("DP-1")))
+/// ```
+pub struct WorkspaceIndicator {
+	root: Container,
+}
+
+impl WorkspaceIndicator {
+	/// Creates the buttons of the workspaces of `state`, only the ones on `monitor` if given.
+	pub fn new(state: &HyprlandState, monitor: Option<&str>) -> Self {
+		let theme = use_theme();
+		let workspaces = state
+			.workspaces
+			.iter()
+			.filter(|workspace| workspace.id > 0)
+			.filter(|workspace| monitor.is_none_or(|monitor| workspace.monitor == monitor));
+		let root = workspaces.fold(
+			Container::row()
+				.role(Role::Group)
+				.label("Workspaces")
+				.gap(4),
+			|root, workspace| {
+				let id = workspace.id;
+				root.child(
+					Container::new()
+						.role(Role::Button)
+						.label(format!("Workspace {}", workspace.name))
+						.selected(id == state.active_workspace)
+						.min_width(24.)
+						.symmetric_padding(8, 4)
+						.rounded(4.)
+						.center()
+						.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+						.on_click(move || hyprland_dispatch(&format!("workspace {id}")))
+						.child(Text::new(&workspace.name).color(if workspace.windows == 0 {
+							theme.colors.secondary_text
+						} else {
+							theme.colors.text
+						})),
+				)
+			},
+		);
+		Self { root }
+	}
+}
+
+impl Element for WorkspaceIndicator {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
+
+/// A button per window, showing which one is focused and focusing the one clicked, like the window list of a
+/// panel.
+///
+/// The windows are listed by workspace, then in the order they were opened. With `previews`, resting the pointer on
+/// a button shows a live capture of its window above it, see [`use_window_thumbnail`]. Otherwise the whole title
+/// shows in a tooltip.
+///
+/// ```rust,ignore
+/// let Some(hyprland) = use_hyprland() else {
+///     return Box::new(Container::new());
+/// };
+/// Box::new(Taskbar::new(&hyprland, true))
+/// ```
+pub struct Taskbar {
+	root: Container,
+}
+
+impl Taskbar {
+	pub fn new(state: &HyprlandState, previews: bool) -> Self {
+		let (hovered, set_hovered) = use_state_handle(None::<String>);
+		let hovered_window = state
+			.windows
+			.iter()
+			.find(|window| Some(&window.address) == hovered.as_ref());
+		let thumbnail = use_window_thumbnail(hovered_window.filter(|_| previews), PREVIEW_INTERVAL);
+		// Built before the buttons, so the hooks of the buttons keep their order whether it shows or not
+		let mut preview = Some(
+			Container::new()
+				.padding_all(4)
+				.rounded(6.)
+				.background_color(use_theme().colors.surface)
+				.elevation(3)
+				.floating((0., -8.), AttachPoint::above(), 10),
+		);
+		let mut windows = state
+			.windows
+			.iter()
+			.filter(|window| window.workspace > 0)
+			.collect::<Vec<_>>();
+		windows.sort_by_key(|window| window.workspace);
+		let root = windows.into_iter().fold(
+			Container::row().role(Role::List).label("Windows").gap(4),
+			|root, window| {
+				let address = window.address.clone();
+				let entered = set_hovered.clone();
+				let left = set_hovered.clone();
+				let mut button = Container::new()
+					.role(Role::Button)
+					.label(&window.title)
+					.selected(state.active_window.as_ref() == Some(&window.address))
+					.max_width(200.)
+					.symmetric_padding(8, 4)
+					.rounded(4.)
+					.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+					.on_click(move || hyprland_dispatch(&format!("focuswindow address:{address}")))
+					.hover_delay(300)
+					.on_mouse_enter({
+						let address = window.address.clone();
+						move || entered.set(Some(address.clone()))
+					})
+					.on_mouse_leave({
+						let address = window.address.clone();
+						move || left.update(|current| current.clone().filter(|current| *current != address))
+					})
+					.child(Text::new(&window.title).max_lines(1).ellipsis());
+				if !previews {
+					button = button.tooltip(&window.title);
+				}
+				if let Some(thumbnail) = &thumbnail
+					&& Some(&window.address) == hovered.as_ref()
+					&& let Some(preview) = preview.take()
+				{
+					let height =
+						PREVIEW_WIDTH * thumbnail.height().max(1) as f32 / thumbnail.width().max(1) as f32;
+					button = button.child(
+						preview.child(
+							Image::new()
+								.skia_image(thumbnail.clone())
+								.size(PREVIEW_WIDTH, height)
+								.contain()
+								.corner_radius(4.),
+						),
+					);
+				}
+				root.child(button)
+			},
+		);
+		Self { root }
+	}
+}
+
+impl Element for Taskbar {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::TestHarness;
+
+	fn workspaces(_: ()) -> Box<dyn Element> {
+		let workspace = |id: i32, name: &str, monitor: &str| crate::HyprWorkspace {
+			id,
+			name: name.to_string(),
+			monitor: monitor.to_string(),
+			windows: 1,
+		};
+		let state = crate::HyprlandState {
+			workspaces: vec![
+				workspace(1, "1", "DP-1"),
+				workspace(2, "2", "DP-1"),
+				workspace(3, "3", "HDMI-A-1"),
+				workspace(-98, "special:scratchpad", "DP-1"),
+			],
+			active_workspace: 2,
+			..Default::default()
+		};
+		Box::new(crate::WorkspaceIndicator::new(&state, Some("DP-1")))
+	}
+
+	#[test]
+	fn test_workspace_indicator_shows_the_workspaces_of_the_monitor() {
+		let harness = TestHarness::new(workspaces, (), (400., 300.));
+		assert!(!harness.expect_label("Workspace 1").selected);
+		assert!(harness.expect_label("Workspace 2").selected);
+		assert!(harness.find_by_label("Workspace 3").is_none());
+		assert!(
+			harness
+				.find_by_label("Workspace special:scratchpad")
+				.is_none()
+		);
+	}
+}
@@ -1,7 +1,70 @@
+use std::{
+	cell::{OnceCell, RefCell},
+	collections::HashMap,
+};
+
+use clay_layout::{
+	Declaration,
+	id::Id,
+	layout::{Alignment, Sizing},
+	math::{BoundingBox, Vector2},
+	text::TextElementConfigWrapMode,
+};
 use skia_safe::{FontStyle, font_style::Width};
+use uuid::Uuid;
 
-use crate::{Element, RenderContext};
+use crate::{
+	Align, Element, GlobalClosure, Justify, RenderContext, begin_component,
+	element::caret::TextLayout, end_component, use_memo,
+};
 pub use clay_layout::text::TextAlignment;
+
+thread_local! {
+	/// Widths the cut texts and rich texts were laid out in the last frame, by the id of the element.
+	static WIDTHS: RefCell<HashMap<Uuid, f32>> = RefCell::new(HashMap::new());
+	/// The texts laid out by hand this frame, with their element and the width they were laid out for.
+	static DECLARED: RefCell<Vec<(Uuid, Id, Option<f32>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the widths of the texts laid out by hand, and renders again if one was laid out for another width.
+pub(crate) fn end_frame(bounding_box: impl Fn(Id) -> Option<BoundingBox>) {
+	let mut changed = false;
+	let widths = DECLARED
+		.with_borrow_mut(std::mem::take)
+		.into_iter()
+		.filter_map(|(key, id, cut_to)| {
+			let width = bounding_box(id).map(|bounds| bounds.width);
+			changed |= width != cut_to;
+			Some((key, width?))
+		})
+		.collect();
+	if changed {
+		crate::REQUEST_REDRAW.call();
+	}
+	WIDTHS.set(widths);
+}
+
+/// Returns the id of an element to declare around the text `key` laid out by hand, and the width it was laid out
+/// in the last frame.
+///
+/// The key is kept by the element across frames like the state of a component, so a text shown or hidden before
+/// it doesn't give it the width of another. A text is laid out twice when it first appears, once to measure it.
+pub(crate) fn measured_width(ctx: &mut RenderContext, key: Uuid) -> (Id, Option<f32>) {
+	let id = ctx.c.id(&format!("hyprui-text-{key}"));
+	let width = WIDTHS.with_borrow(|widths| widths.get(&key).copied());
+	DECLARED.with_borrow_mut(|declared| declared.push((key, id.clone(), width)));
+	(id, width)
+}
+
+/// Returns a key for [`measured_width`], kept across frames by the element being created. Like any hook, it must
+/// be called while a component is built.
+pub(crate) fn use_measure_key() -> Uuid {
+	begin_component("text");
+	let key = *use_memo(Uuid::new_v4, ());
+	end_component();
+	key
+}
+
 pub struct Text {
 	pub text: String,
 	pub font_family: String,
@@ -10,18 +73,34 @@ pub struct Text {
 	pub font_size: u16,
 	pub color: clay_layout::Color,
 	pub alignment: TextAlignment,
+	/// Breaks lines between words to fit the width of the parent.
+	pub wrap: bool,
+	pub max_lines: Option<u32>,
+	pub ellipsis: bool,
+	/// The text cut to `max_lines`, kept for as long as the element is rendered.
+	pub(crate) truncated: OnceCell<String>,
+	/// Keeps the width the text was laid out in, see [`measured_width`]. Set by [`max_lines`](Self::max_lines) and
+	/// [`ellipsis`](Self::ellipsis), a text cut without it is only cut at its line breaks.
+	pub(crate) measure_key: Option<Uuid>,
 }
 
 impl Text {
+	/// Creates a text with the font and color of the [theme](crate::use_theme).
 	pub fn new(text: impl Into<String>) -> Self {
+		let theme = crate::use_theme();
 		Self {
 			text: text.into(),
-			font_family: "".to_string(),
+			font_family: theme.font_family.clone(),
 			font_weight: 400,
-			font_size: 14,
-			color: (0, 0, 0, 255).into(),
+			font_size: theme.font_size,
+			color: theme.colors.text,
 			italic: false,
 			alignment: TextAlignment::Left,
+			wrap: true,
+			max_lines: None,
+			ellipsis: false,
+			truncated: OnceCell::new(),
+			measure_key: None,
 		}
 	}
 	pub fn text_center(mut self) -> Self {
@@ -55,11 +134,39 @@ impl Text {
 		self.font_family = family.into();
 		self
 	}
-}
 
-impl Element for Text {
-	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
-		let skia_font_style = FontStyle::new(
+	/// Breaks lines only after `\n` when `false`, instead of also between words to fit the width of the parent.
+	pub fn wrap(mut self, wrap: bool) -> Self {
+		self.wrap = wrap;
+		self
+	}
+
+	/// Shows at most `lines` lines of the text, cutting the rest.
+	///
+	/// A cut text takes the whole width of its parent. It is cut to the width it was laid out in the last frame, so
+	/// it is rendered again when that width changes.
+	pub fn max_lines(mut self, lines: u32) -> Self {
+		self.max_lines = Some(lines);
+		self.measure_key.get_or_insert_with(use_measure_key);
+		self
+	}
+
+	/// Ends the text with "…" where it is cut, shortening the last line until it fits. Without
+	/// [`max_lines`](Self::max_lines), the text is cut after its first line.
+	///
+	/// ```rust,ignore
+	/// Container::new()
+	///     .max_width(240.)
+	///     .child(Text::new(&mail.preview).max_lines(2).ellipsis())
+	/// ```
+	pub fn ellipsis(mut self) -> Self {
+		self.ellipsis = true;
+		self.measure_key.get_or_insert_with(use_measure_key);
+		self
+	}
+
+	pub(crate) fn skia_font_style(&self) -> FontStyle {
+		FontStyle::new(
 			self.font_weight.into(),
 			Width::NORMAL,
 			if self.italic {
@@ -67,14 +174,194 @@ impl Element for Text {
 			} else {
 				skia_safe::font_style::Slant::Upright
 			},
+		)
+	}
+
+	/// The font size after text scaling, as it is laid out.
+	pub(crate) fn scaled_font_size(&self) -> u16 {
+		(self.font_size as f32 * crate::text_scale()).round() as u16
+	}
+}
+
+impl Text {
+	/// Declares the text cut to [`max_lines`](Self::max_lines) in an element taking the width of the parent, which
+	/// tells how long the lines can be.
+	fn render_truncated<'clay: 'render, 'render>(
+		&'render self,
+		ctx: &mut RenderContext<'clay, 'render, '_>,
+		text_config: impl FnOnce(TextElementConfigWrapMode) -> clay_layout::text::TextElementConfig,
+		wrap_mode: TextElementConfigWrapMode,
+	) {
+		let (id, width) = match self.measure_key {
+			Some(key) => {
+				let (id, width) = measured_width(ctx, key);
+				(Some(id), width)
+			}
+			None => (None, None),
+		};
+		let layout = TextLayout::new(ctx.font_manager, self, width.filter(|_| self.wrap));
+		let max_lines = self.max_lines.map_or(1, |lines| lines as usize);
+		let (text, wrap_mode) = match layout.truncated(max_lines, self.ellipsis, width) {
+			// The lines are already broken where they wrap
+			Some(truncated) => (
+				self.truncated.get_or_init(|| truncated).as_str(),
+				TextElementConfigWrapMode::Newline,
+			),
+			None => (self.text.as_str(), wrap_mode),
+		};
+		let justify = match self.alignment {
+			TextAlignment::Left => Justify::Left,
+			TextAlignment::Center => Justify::Center,
+			TextAlignment::Right => Justify::Right,
+		};
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				if let Some(id) = id {
+					declaration.id(id);
+				}
+				declaration
+					.layout()
+					.width(Sizing::Grow(0., f32::MAX))
+					.child_alignment(Alignment::new(justify, Align::Top))
+					.end()
+					// The text doesn't widen the element, so it is cut to the width it is given
+					.clip(true, false, Vector2::new(0., 0.));
+				declaration
+			},
+			|c| {
+				c.text(text, text_config(wrap_mode));
+			},
 		);
-		let text_config = clay_layout::text::TextConfig::new()
-			.font_size(self.font_size)
-			.color(self.color.clone())
-			.alignment(self.alignment)
-			.font_id(ctx.font_manager.get(&self.font_family, skia_font_style))
-			.end();
+	}
+}
+
+impl Element for Text {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let skia_font_style = self.skia_font_style();
+		let font_size = self.scaled_font_size();
+		let font_id = ctx.font_manager.get(&self.font_family, skia_font_style);
+		let text_config = |wrap_mode| {
+			clay_layout::text::TextConfig::new()
+				.font_size(font_size)
+				.color(if crate::prefers_high_contrast() {
+					crate::high_contrast_colors().foreground
+				} else {
+					self.color.clone()
+				})
+				.alignment(self.alignment)
+				.font_id(font_id)
+				.wrap_mode(wrap_mode)
+				.end()
+		};
+		let wrap_mode = if self.wrap {
+			TextElementConfigWrapMode::Words
+		} else {
+			TextElementConfigWrapMode::None
+		};
 		ctx.font_manager.update_clay_measure_function(&mut ctx.c);
-		ctx.c.text(&self.text, text_config);
+		if self.max_lines.is_some() || self.ellipsis {
+			self.render_truncated(ctx, text_config, wrap_mode);
+		} else {
+			ctx.c.text(&self.text, text_config(wrap_mode));
+		}
+		crate::accessibility::add_text(&self.text);
+		if crate::inspector::is_collecting() {
+			let index = crate::inspector::push_entry(format!("Text {:?}", self.text), None, None);
+			crate::inspector::set_details(
+				index,
+				vec![
+					format!("font_family: {:?}", self.font_family),
+					format!("font_weight: {}", self.font_weight),
+					format!("italic: {}", self.italic),
+					format!("font_size: {font_size}"),
+					format!("wrap: {}", self.wrap),
+					format!("max_lines: {:?}", self.max_lines),
+					format!("ellipsis: {}", self.ellipsis),
+					format!("color: {:?}", self.color),
+					format!(
+						"alignment: {}",
+						match self.alignment {
+							TextAlignment::Left => "Left",
+							TextAlignment::Center => "Center",
+							TextAlignment::Right => "Right",
+						}
+					),
+				],
+				Default::default(),
+			);
+			crate::inspector::pop_entry();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Component, Container, testing::TestHarness};
+
+	fn mail_preview(_: ()) -> Box<dyn Element> {
+		Box::new(
+			Container::new().max_width(120.).child(
+				Text::new("Lunch on friday? The new place next to the station opens this week")
+					.max_lines(2)
+					.ellipsis(),
+			),
+		)
+	}
+
+	#[test]
+	fn test_text_is_cut_with_an_ellipsis() {
+		let mut harness = TestHarness::new(mail_preview, (), (400., 300.));
+		harness.frames(2);
+		let lines = harness
+			.render_commands()
+			.iter()
+			.filter(|command| command.starts_with("text "))
+			.collect::<Vec<_>>();
+		assert_eq!(lines.len(), 2, "{lines:?}");
+		assert!(lines[1].contains("…\""), "{lines:?}");
+		harness.expect_label("Lunch on friday? The new place next to the station opens this week");
+	}
+
+	fn inbox(draft: bool) -> Box<dyn Element> {
+		let drafts = Component::new(
+			|draft| {
+				let mut drafts = Container::new().column();
+				if draft {
+					drafts = drafts.child(Text::new("Re: lunch").max_lines(1));
+				}
+				Box::new(drafts)
+			},
+			draft,
+		);
+		Box::new(
+			Container::new()
+				.column()
+				.child(drafts)
+				.child(mail_preview(())),
+		)
+	}
+
+	/// Returns the lines of the mail preview among `commands`.
+	fn preview_lines(commands: &[String]) -> Vec<String> {
+		commands
+			.iter()
+			.filter(|command| command.starts_with("text ") && !command.contains("Re: lunch"))
+			.cloned()
+			.collect()
+	}
+
+	#[test]
+	fn test_texts_keep_their_width_when_one_is_added_before() {
+		let mut harness = TestHarness::new(inbox, false, (400., 300.));
+		harness.frames(2);
+		let lines = preview_lines(harness.render_commands());
+		assert_eq!(lines.len(), 2, "{lines:?}");
+
+		// The preview is cut right away, without waiting for the draft to be measured
+		harness.set_props(true);
+		harness.frame();
+		assert_eq!(preview_lines(harness.render_commands()), lines);
 	}
 }
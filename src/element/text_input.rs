@@ -0,0 +1,772 @@
+//! A single line text field.
+//!
+//! [`TextInput`] edits its text with a [`TextEditor`], so it follows the [`Keymap`](crate::Keymap) of the app, and
+//! draws it with a [`TextCursor`]. The value is owned by the component using it: the field shows the value it is
+//! given and reports edits through [`TextInput::on_change`], or edits a field of a [`Form`](crate::Form). Enter
+//! submits the form around the field. An [`InputMask`] formats the text as it is typed. A right click opens a menu
+//! to cut, copy and paste, which [`TextInput::context_menu`] changes.
+use std::{
+	cell::{OnceCell, RefCell},
+	rc::Rc,
+};
+
+use clay_layout::{Declaration, layout::Sizing, math::Vector2};
+use uuid::Uuid;
+
+use crate::{
+	CaretStyle, Container, EditAction, Element, FormField, GlobalClosure, InputManager, InputMask,
+	Key, MenuItem, NamedKey, RenderContext, Role, Text, TextCursor, TextEditor, TextLayout,
+	context_menu, focus_system::GLOBAL_FOCUS_MANAGER, font_manager::FontManager, push_overlay,
+	use_caret_blink, use_ref, use_state,
+};
+
+/// Drawn instead of each character of a hidden password.
+const BULLET: char = '•';
+/// Horizontal and vertical padding around the text.
+const PADDING: (u16, u16) = (8, 6);
+
+/// The text drawn in a field, which differs from the edited text for passwords and while composing text with an
+/// input method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shown<'a> {
+	Plain(&'a str),
+	Masked(&'a str),
+	/// The text being composed is drawn at the caret until it is committed.
+	Composing {
+		text: &'a str,
+		caret: usize,
+		preedit: &'a str,
+	},
+}
+
+impl Shown<'_> {
+	fn text(&self) -> String {
+		match *self {
+			Self::Plain(text) => text.to_string(),
+			Self::Masked(text) => BULLET.to_string().repeat(text.chars().count()),
+			Self::Composing {
+				text,
+				caret,
+				preedit,
+			} => {
+				format!("{}{preedit}{}", &text[..caret], &text[caret..])
+			}
+		}
+	}
+
+	/// Maps a byte offset of the edited text to the drawn text.
+	fn shown_offset(&self, index: usize) -> usize {
+		match *self {
+			Self::Plain(_) => index,
+			Self::Masked(text) => text[..index].chars().count() * BULLET.len_utf8(),
+			Self::Composing { caret, preedit, .. } if index >= caret => index + preedit.len(),
+			Self::Composing { .. } => index,
+		}
+	}
+
+	/// Maps a byte offset of the drawn text back to the edited text.
+	fn edited_offset(&self, index: usize) -> usize {
+		match *self {
+			Self::Plain(_) => index,
+			Self::Masked(text) => text
+				.char_indices()
+				.nth(index / BULLET.len_utf8())
+				.map_or(text.len(), |(i, _)| i),
+			Self::Composing { caret, .. } if index <= caret => index,
+			Self::Composing { caret, preedit, .. } => index.saturating_sub(preedit.len()).max(caret),
+		}
+	}
+}
+
+/// The context menu of a [`TextInput`], kept across renders.
+#[derive(Default)]
+struct FieldMenu {
+	/// The field was right clicked, and the menu opens where the pointer is once the field renders.
+	requested: bool,
+	position: Option<(f32, f32)>,
+	/// Picked in the menu, and applied to the editor once the field renders.
+	action: Option<EditAction>,
+}
+
+/// The context menu of a [`TextInput`] in the frames it is open, drawn above the window.
+struct OpenMenu {
+	position: (f32, f32),
+	/// Built with the default items by [`TextInput::new`], and rebuilt by [`TextInput::context_menu`].
+	menu: RefCell<Option<Container>>,
+	/// The menu drawn, taken from `menu` once the field is built.
+	drawn: OnceCell<Option<Container>>,
+}
+
+impl OpenMenu {
+	/// Builds the menu of `items`, or closes it if there are none.
+	fn build(&self, field_menu: &Rc<RefCell<FieldMenu>>, items: Vec<MenuItem>) {
+		let menu = if items.is_empty() {
+			field_menu.borrow_mut().position = None;
+			None
+		} else {
+			let field_menu = field_menu.clone();
+			Some(context_menu(self.position, items, move || {
+				field_menu.borrow_mut().position = None;
+				crate::REQUEST_REDRAW.call();
+			}))
+		};
+		*self.menu.borrow_mut() = menu;
+	}
+}
+
+/// Draws an [`OpenMenu`] as an overlay.
+struct OpenMenuOverlay(Rc<OpenMenu>);
+
+impl Element for OpenMenuOverlay {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let menu = self.0.drawn.get_or_init(|| self.0.menu.borrow_mut().take());
+		if let Some(menu) = menu {
+			menu.render(ctx);
+		}
+	}
+}
+
+/// What a [`TextInput`] shares with the element drawing its text.
+struct Field {
+	editor: Rc<RefCell<TextEditor>>,
+	placeholder: String,
+	font_size: u16,
+	on_change: Option<Box<dyn Fn(String)>>,
+	mask: Option<Box<dyn InputMask>>,
+	password: bool,
+	revealed: bool,
+	caret_visible: bool,
+	focus_node: Option<Uuid>,
+	/// A press started on the field and is still held, so moving the pointer selects text.
+	selecting: Rc<RefCell<bool>>,
+	menu: Rc<RefCell<FieldMenu>>,
+	/// The context menu while it is open.
+	open_menu: Option<Rc<OpenMenu>>,
+}
+
+impl Field {
+	fn is_focused(&self) -> bool {
+		self
+			.focus_node
+			.is_some_and(|id| GLOBAL_FOCUS_MANAGER.with_borrow(|f| f.focused() == Some(id)))
+	}
+
+	fn text(&self, content: &str) -> Text {
+		let text = if content.is_empty() {
+			Text::new(self.placeholder.clone()).color((120, 120, 120, 255))
+		} else {
+			Text::new(content)
+		};
+		text.font_size(self.font_size)
+	}
+}
+
+/// A text field of one line.
+///
+/// ```rust,ignore
+/// let (name, set_name) = use_state(String::new());
+/// TextInput::new(&name)
+///     .placeholder("Name")
+///     .on_change(move |name| set_name(name))
+/// ```
+pub struct TextInput {
+	container: Container,
+	field: Rc<RefCell<Field>>,
+	/// Shows or hides a password, added by [`TextInput::password`].
+	reveal_toggle: Option<Container>,
+}
+
+impl TextInput {
+	/// A field showing `value`. Edits replace the value on screen only once the component gives it back.
+	pub fn new(value: impl Into<String>) -> Self {
+		let value = value.into();
+		crate::begin_component("builtin/text_input");
+		let editor = use_ref(TextEditor::new(value.clone()));
+		let selecting = use_ref(false);
+		let menu = use_ref(FieldMenu::default());
+		let (revealed, set_revealed) = use_state(false);
+		{
+			let mut editor = editor.borrow_mut();
+			editor.set_single_line(true);
+			if editor.text() != value {
+				editor.set_text(value.clone());
+			}
+		}
+		let field = Rc::new(RefCell::new(Field {
+			editor: editor.clone(),
+			placeholder: String::new(),
+			font_size: 14,
+			on_change: None,
+			mask: None,
+			password: false,
+			revealed,
+			caret_visible: false,
+			focus_node: None,
+			selecting,
+			menu: menu.clone(),
+			open_menu: None,
+		}));
+		let accent = crate::use_accent_color().unwrap_or(clay_layout::Color::rgb(53., 132., 228.));
+		let container = Container::row()
+			.align(crate::Align::Center)
+			.gap(6)
+			.min_width(160.)
+			.symmetric_padding(PADDING.0, PADDING.1)
+			.rounded(6.)
+			.background_color((255, 255, 255, 255))
+			.border_color((200, 200, 200, 255))
+			.border_width(1)
+			.style_if_focused(move |style| style.border_color(accent).border_width(2))
+			.role(Role::TextInput)
+			.value(value)
+			.on_value_change({
+				let field = field.clone();
+				move |value| {
+					let field = field.borrow();
+					let value = match &field.mask {
+						Some(mask) => mask.format(&value),
+						None => value,
+					};
+					field.editor.borrow_mut().set_text(value.clone());
+					if let Some(on_change) = &field.on_change {
+						on_change(value);
+					}
+				}
+			})
+			.on_press({
+				let field = field.clone();
+				move || {
+					let field = field.borrow();
+					if let Some(focus_node) = field.focus_node {
+						GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.set_focus(focus_node));
+					}
+					*field.selecting.borrow_mut() = true;
+				}
+			})
+			.on_right_click({
+				let field = field.clone();
+				move || {
+					let field = field.borrow();
+					if let Some(focus_node) = field.focus_node {
+						GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.set_focus(focus_node));
+					}
+					field.menu.borrow_mut().requested = true;
+					crate::REQUEST_REDRAW.call();
+				}
+			})
+			.child(FieldText {
+				field: field.clone(),
+			})
+			.focusable();
+		let focus_node = container.clickable.as_ref().and_then(|c| c.focus_node_id);
+		if let Some(focus_node) = focus_node {
+			// The arrow keys move the caret
+			GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.set_node_captures_arrows(focus_node));
+		}
+		let reveal_toggle = Container::new()
+			.label(if revealed {
+				"Hide password"
+			} else {
+				"Show password"
+			})
+			.symmetric_padding(4, 0)
+			.on_click(move || set_revealed(!revealed))
+			.child(
+				Text::new(if revealed { "Hide" } else { "Show" })
+					.font_size(12)
+					.color((90, 90, 90, 255)),
+			);
+		{
+			let mut field = field.borrow_mut();
+			field.focus_node = focus_node;
+			let editor = editor.borrow();
+			field.caret_visible = use_caret_blink(
+				field.is_focused(),
+				CaretStyle::default().blink_interval,
+				(editor.text(), editor.caret(), editor.selection()),
+			);
+		}
+		// Last, as the containers of the menu only exist while it is open
+		let position = menu.borrow().position;
+		if let Some(position) = position {
+			let open_menu = Rc::new(OpenMenu {
+				position,
+				menu: RefCell::new(None),
+				drawn: OnceCell::new(),
+			});
+			open_menu.build(&menu, default_menu_items(&field.borrow()));
+			push_overlay(OpenMenuOverlay(open_menu.clone()));
+			field.borrow_mut().open_menu = Some(open_menu);
+		}
+		crate::end_component();
+		Self {
+			container,
+			field,
+			reveal_toggle: Some(reveal_toggle),
+		}
+	}
+
+	/// A field editing `field` of a [`Form`](crate::Form).
+	pub fn for_field(field: &FormField) -> Self {
+		let field = field.clone();
+		Self::new(field.value()).on_change(move |value| field.set(value))
+	}
+
+	/// Text shown in gray while the field is empty, also used as its accessible label.
+	pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+		let placeholder = placeholder.into();
+		if self.container.accessibility.label.is_none() {
+			self.container = self.container.label(placeholder.clone());
+		}
+		self.field.borrow_mut().placeholder = placeholder;
+		self
+	}
+
+	/// Called with the new text whenever it is edited.
+	pub fn on_change(self, handler: impl Fn(String) + 'static) -> Self {
+		self.field.borrow_mut().on_change = Some(Box::new(handler));
+		self
+	}
+
+	/// Formats the text after every edit, keeping the caret after the same character.
+	///
+	/// ```rust,ignore
+	/// TextInput::new(&amount).mask(ThousandsMask::new().decimals(2))
+	/// ```
+	pub fn mask(self, mask: impl InputMask + 'static) -> Self {
+		self.field.borrow_mut().mask = Some(Box::new(mask));
+		self
+	}
+
+	pub fn font_size(self, size: u16) -> Self {
+		self.field.borrow_mut().font_size = size;
+		self
+	}
+
+	pub fn min_width(mut self, width: f32) -> Self {
+		self.container = self.container.min_width(width);
+		self
+	}
+
+	/// Names the field for assistive technologies, replacing the placeholder.
+	pub fn label(mut self, label: impl Into<String>) -> Self {
+		self.container = self.container.label(label);
+		self
+	}
+
+	/// Changes the menu opened by a right click, the menu key or Shift+F10, given its Cut, Copy, Paste and Select All
+	/// items. The menu doesn't open when no items are left.
+	///
+	/// ```rust,ignore
+	/// TextInput::new(&query).context_menu(move |mut items| {
+	///     items.push(MenuItem::new("Clear history", move || clear_history()));
+	///     items
+	/// })
+	/// ```
+	pub fn context_menu(self, items: impl FnOnce(Vec<MenuItem>) -> Vec<MenuItem>) -> Self {
+		// A component of its own whether the menu is open or not, so the components after the field keep their keys
+		crate::begin_component("builtin/text_input_menu");
+		{
+			let field = self.field.borrow();
+			if let Some(open_menu) = &field.open_menu {
+				open_menu.build(&field.menu, items(default_menu_items(&field)));
+			}
+		}
+		crate::end_component();
+		self
+	}
+
+	/// Hides the text behind bullets, with a button to show it.
+	///
+	/// The text can't be copied or cut to the clipboard, and the text being composed with an input method isn't
+	/// drawn. Assistive technologies are told the field is a password and don't get its value.
+	pub fn password(mut self) -> Self {
+		{
+			let mut field = self.field.borrow_mut();
+			field.password = true;
+			field.editor.borrow_mut().set_secret(true);
+		}
+		self.container.accessibility.value = None;
+		self.container = self.container.role(Role::PasswordInput);
+		if let Some(reveal_toggle) = self.reveal_toggle.take() {
+			self.container = self.container.child(reveal_toggle);
+		}
+		self
+	}
+}
+
+impl Element for TextInput {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.container.render(ctx);
+	}
+	fn focus_nodes(&self) -> std::collections::HashSet<Uuid> {
+		self.container.focus_nodes()
+	}
+}
+
+/// Returns the items of the context menu of `field`, applying their action once the field renders.
+fn default_menu_items(field: &Field) -> Vec<MenuItem> {
+	let editor = field.editor.borrow();
+	let selected = !editor.selection().is_empty() && !field.password;
+	let item = |label: &str, action: EditAction| {
+		let menu = field.menu.clone();
+		MenuItem::new(label, move || {
+			menu.borrow_mut().action = Some(action);
+			crate::REQUEST_REDRAW.call();
+		})
+	};
+	vec![
+		item("Cut", EditAction::Cut).enabled(selected),
+		item("Copy", EditAction::Copy).enabled(selected),
+		item("Paste", EditAction::Paste),
+		item("Select All", EditAction::SelectAll).enabled(!editor.text().is_empty()),
+	]
+}
+
+/// The text of a [`TextInput`], with its caret. Edits happen here, where the input of the frame is known.
+struct FieldText {
+	field: Rc<RefCell<Field>>,
+}
+
+impl FieldText {
+	/// Moves the caret to the pointer while a press on the field is held.
+	fn follow_pointer(
+		&self,
+		font_manager: &mut FontManager,
+		input: &dyn InputManager,
+		editor: &mut TextEditor,
+		shown: Shown,
+	) {
+		let field = self.field.borrow();
+		if !*field.selecting.borrow() {
+			return;
+		}
+		if !input.is_mouse_button_pressed(0) {
+			*field.selecting.borrow_mut() = false;
+			return;
+		}
+		let Some(bounds) = field
+			.focus_node
+			.and_then(|id| GLOBAL_FOCUS_MANAGER.with_borrow(|f| f.node_bounds(id)))
+		else {
+			return;
+		};
+		let (x, y) = input.mouse_position();
+		let layout = TextLayout::new(font_manager, &field.text(&shown.text()), None);
+		let index = layout.index_at(
+			x - bounds.x - PADDING.0 as f32,
+			y - bounds.y - PADDING.1 as f32,
+		);
+		// The press extends the selection with Shift, dragging always does
+		let select =
+			!input.is_mouse_button_just_pressed(0) || input.is_key_pressed(Key::Named(NamedKey::Shift));
+		editor.set_caret(shown.edited_offset(index), select);
+	}
+
+	/// Opens the context menu where the field was right clicked, or below the field with the menu key or Shift+F10,
+	/// and closes it with Escape.
+	fn update_menu(&self, input: &dyn InputManager) {
+		let field = self.field.borrow();
+		let mut menu = field.menu.borrow_mut();
+		if std::mem::take(&mut menu.requested) {
+			let below_field = || {
+				let bounds = field
+					.focus_node
+					.and_then(|id| GLOBAL_FOCUS_MANAGER.with_borrow(|f| f.node_bounds(id)))?;
+				Some((bounds.x, bounds.y + bounds.height))
+			};
+			menu.position = if input.is_mouse_button_pressed(1) {
+				Some(input.mouse_position())
+			} else {
+				below_field()
+			};
+			crate::REQUEST_REDRAW.call();
+		} else if menu.position.is_some() && input.is_key_just_pressed(Key::Named(NamedKey::Escape)) {
+			menu.position = None;
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+}
+
+impl Element for FieldText {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let field = self.field.borrow();
+		let focused = field.is_focused();
+		let mut editor = field.editor.borrow_mut();
+		let before = editor.snapshot();
+		self.update_menu(ctx.input_manager);
+		if let Some(action) = field.menu.borrow_mut().action.take() {
+			editor.apply(action, false);
+		}
+		if focused {
+			let shown = if field.password && !field.revealed {
+				Shown::Masked(before.text.as_str())
+			} else {
+				Shown::Plain(before.text.as_str())
+			};
+			self.follow_pointer(ctx.font_manager, ctx.input_manager, &mut editor, shown);
+			if editor.handle_input(ctx.input_manager)
+				&& let Some(mask) = &field.mask
+			{
+				let (text, caret) = mask.apply(editor.text(), editor.caret());
+				editor.reformat(text, caret);
+			}
+			if ctx
+				.input_manager
+				.is_key_just_pressed(Key::Named(NamedKey::Enter))
+			{
+				crate::element::form::request_submit();
+			}
+		} else {
+			*field.selecting.borrow_mut() = false;
+		}
+		let after = editor.snapshot();
+		if after != before {
+			crate::REQUEST_REDRAW.call();
+			if after.text != before.text
+				&& let Some(on_change) = &field.on_change
+			{
+				on_change(after.text.clone());
+			}
+		}
+
+		let input = ctx.input_manager;
+		// Passwords are never drawn while being composed, even when revealed
+		let composing =
+			focused && !field.password && input.ime_is_editing() && !input.ime_buffer().is_empty();
+		let shown = if field.password && !field.revealed {
+			Shown::Masked(&after.text)
+		} else if composing {
+			Shown::Composing {
+				text: &after.text,
+				caret: after.caret,
+				preedit: input.ime_buffer(),
+			}
+		} else {
+			Shown::Plain(&after.text)
+		};
+		let content = shown.text();
+		let text = field.text(&content);
+		let selection = editor.selection();
+		let cursor = if content.is_empty() {
+			TextCursor::new(&text).caret(0, focused && field.caret_visible)
+		} else {
+			TextCursor::new(&text)
+				.caret(
+					shown.shown_offset(after.caret),
+					focused && field.caret_visible,
+				)
+				.selection(shown.shown_offset(selection.start)..shown.shown_offset(selection.end))
+		};
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration
+					.layout()
+					.width(Sizing::Grow(0., f32::MAX))
+					.end()
+					.clip(true, false, Vector2::new(0., 0.));
+				declaration
+			},
+			|c| {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				text.render(&mut ctx);
+				cursor.render(&mut ctx);
+			},
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{TestHarness, TestNode};
+
+	fn login(_: ()) -> Box<dyn Element> {
+		let (password, set_password) = use_state(String::new());
+		Box::new(
+			Container::column().child(
+				TextInput::new(&password)
+					.placeholder("Password")
+					.password()
+					.on_change(move |password| set_password(password)),
+			),
+		)
+	}
+
+	fn amount(_: ()) -> Box<dyn Element> {
+		let (amount, set_amount) = use_state(String::new());
+		Box::new(
+			Container::column().child(
+				TextInput::new(&amount)
+					.placeholder("Amount")
+					.mask(crate::ThousandsMask::new())
+					.on_change(move |amount| set_amount(amount)),
+			),
+		)
+	}
+
+	fn search(_: ()) -> Box<dyn Element> {
+		let (query, set_query) = use_state(String::new());
+		Box::new(
+			Container::column().child(
+				TextInput::new(&query)
+					.placeholder("Search")
+					.on_change(move |query| set_query(query)),
+			),
+		)
+	}
+
+	#[test]
+	fn test_context_menu_cuts_and_pastes() {
+		let mut harness = TestHarness::new(search, (), (400., 300.));
+		let (x, y) = harness.expect_label("Search").center().unwrap();
+		harness.click(x, y);
+		harness.type_text("hello");
+		harness.frame();
+
+		harness.right_click(x, y);
+		harness.press_key(Key::Named(NamedKey::Escape));
+		assert!(harness.find_by_label("Select All").is_none());
+
+		harness.right_click(x, y);
+		harness.click_label("Select All");
+		harness.right_click(x, y);
+		harness.click_label("Cut");
+		assert_eq!(
+			crate::clipboard::read_clipboard_text().get(),
+			Some(Some("hello"))
+		);
+		assert!(!shows(&harness.nodes(), "hello"));
+
+		harness.right_click(x, y);
+		harness.click_label("Paste");
+		assert!(shows(&harness.nodes(), "hello"));
+	}
+
+	#[test]
+	fn test_context_menu_opens_with_the_keyboard() {
+		let mut harness = TestHarness::new(search, (), (400., 300.));
+		harness.click_label("Search");
+		harness.press_key(Key::Named(NamedKey::ContextMenu));
+		harness.expect_label("Paste");
+		harness.press_key(Key::Named(NamedKey::Escape));
+		assert!(harness.find_by_label("Paste").is_none());
+
+		harness.key_down(Key::Named(NamedKey::Shift));
+		harness.press_key(Key::Named(NamedKey::F10));
+		harness.key_up(Key::Named(NamedKey::Shift));
+		harness.expect_label("Paste");
+	}
+
+	fn history(_: ()) -> Box<dyn Element> {
+		let renders = use_ref(0);
+		*renders.borrow_mut() += 1;
+		let renders = *renders.borrow();
+		Box::new(
+			Container::column()
+				.child(Text::new(format!("renders {renders}")))
+				.child(
+					TextInput::new("")
+						.placeholder("History")
+						.context_menu(move |mut items| {
+							items.push(MenuItem::new(format!("Clear {renders}"), || {}));
+							items
+						}),
+				),
+		)
+	}
+
+	#[test]
+	fn test_context_menu_items_are_built_in_the_frame_they_show() {
+		let mut harness = TestHarness::new(history, (), (400., 300.));
+		let (x, y) = harness.expect_label("History").center().unwrap();
+		harness.right_click(x, y);
+		let renders = harness
+			.nodes()
+			.into_iter()
+			.find_map(|node| node.value?.strip_prefix("renders ").map(str::to_string))
+			.unwrap();
+		harness.expect_label(&format!("Clear {renders}"));
+	}
+
+	#[test]
+	fn test_shown_offsets() {
+		let masked = Shown::Masked("pé!");
+		assert_eq!(masked.text(), "•••");
+		assert_eq!(masked.shown_offset(3), 2 * BULLET.len_utf8());
+		assert_eq!(masked.edited_offset(2 * BULLET.len_utf8()), 3);
+		let composing = Shown::Composing {
+			text: "ab",
+			caret: 1,
+			preedit: "xy",
+		};
+		assert_eq!(composing.text(), "axyb");
+		assert_eq!(composing.shown_offset(1), 3);
+		assert_eq!(composing.edited_offset(2), 1);
+		assert_eq!(composing.edited_offset(4), 2);
+	}
+
+	/// Returns `true` if `text` is drawn in the last frame.
+	fn shows(nodes: &[TestNode], text: &str) -> bool {
+		nodes
+			.iter()
+			.any(|node| node.role == Role::Label && node.value.as_deref() == Some(text))
+	}
+
+	#[test]
+	fn test_password_is_hidden_and_not_copied() {
+		let mut harness = TestHarness::new(login, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		assert_eq!(harness.focused().unwrap().role, Role::PasswordInput);
+		harness.type_text("hunter2");
+		harness.frame();
+		assert!(shows(&harness.nodes(), "•••••••"));
+		assert!(harness.focused().unwrap().value.is_none());
+
+		crate::clipboard::set_clipboard_text("before");
+		harness.key_down(Key::Named(NamedKey::Shift));
+		harness.press_key(Key::Named(NamedKey::Home));
+		harness.key_up(Key::Named(NamedKey::Shift));
+		harness.key_down(Key::Named(NamedKey::Control));
+		harness.press_key(Key::Character("c".into()));
+		harness.press_key(Key::Character("x".into()));
+		harness.key_up(Key::Named(NamedKey::Control));
+		assert_eq!(
+			crate::clipboard::read_clipboard_text().get(),
+			Some(Some("before"))
+		);
+		assert!(shows(&harness.nodes(), "•••••••"));
+
+		harness.click_label("Show password");
+		harness.frame();
+		assert!(shows(&harness.nodes(), "hunter2"));
+		assert!(harness.find_by_label("Hide password").is_some());
+	}
+
+	#[test]
+	fn test_mask_formats_while_typing() {
+		let mut harness = TestHarness::new(amount, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.type_text("1234x567");
+		harness.frame();
+		assert_eq!(
+			harness.focused().unwrap().value.as_deref(),
+			Some("1,234,567")
+		);
+
+		// The caret stays after the 9 typed at the start, even though the commas moved
+		harness.press_key(Key::Named(NamedKey::Home));
+		harness.type_text("9");
+		harness.type_text("8");
+		harness.frame();
+		assert_eq!(
+			harness.focused().unwrap().value.as_deref(),
+			Some("981,234,567")
+		);
+	}
+}
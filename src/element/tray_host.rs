@@ -0,0 +1,208 @@
+//! The system tray of a panel, showing the icons of other apps, fed by [`use_tray_items`](crate::use_tray_items).
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::{
+	Container, Element, Image, MenuItem, RenderContext, Role, Text, TrayItem, TrayMenuItem,
+	TrayStatus, context_menu, push_overlay, use_state_handle, use_theme, use_tray_items,
+	use_tray_menu,
+};
+
+/// The width and height of the icons.
+const ICON: f32 = 18.;
+
+/// The menu of a tray item that is open, and the ids of the submenus opened in it.
+#[derive(Clone, PartialEq)]
+struct OpenMenu {
+	service: String,
+	path: String,
+	submenu: Vec<i32>,
+}
+
+/// The id of the button of `item`, to place its menu next to it.
+fn button_id(item: &TrayItem) -> String {
+	format!("tray-item-{}{}", item.service, item.path)
+}
+
+/// Returns the center of the button of `item` in the last frame, which apps get with the clicks.
+fn button_center(item: &TrayItem) -> (i32, i32) {
+	crate::element_ids::bounding_box(&button_id(item)).map_or((0, 0), |bounds| {
+		(
+			(bounds.x + bounds.width / 2.) as i32,
+			(bounds.y + bounds.height / 2.) as i32,
+		)
+	})
+}
+
+/// Returns the entries of the submenu at `path` in `entries`, following the ids of `path`.
+fn submenu<'a>(entries: &'a [TrayMenuItem], path: &[i32]) -> Option<&'a [TrayMenuItem]> {
+	match path.split_first() {
+		None => Some(entries),
+		Some((id, rest)) => {
+			let entry = entries.iter().find(|entry| entry.id == *id)?;
+			submenu(&entry.children, rest)
+		}
+	}
+}
+
+/// Returns the label of a menu entry, marked when it is checked or opens a submenu.
+fn entry_label(entry: &TrayMenuItem) -> String {
+	let mark = match (entry.checked, entry.radio) {
+		(Some(true), true) => "● ",
+		(Some(true), false) => "✓ ",
+		_ => "",
+	};
+	let arrow = if entry.children.is_empty() {
+		""
+	} else {
+		" ›"
+	};
+	format!("{mark}{}{arrow}", entry.label)
+}
+
+/// A button per item of the system tray, like the icons of the network and chat apps in a panel.
+///
+/// Clicking an item activates it, usually showing the window of its app, or opens its menu if the app only gives
+/// a menu. Right-clicking an item opens its menu. Separators aren't shown in the menus, and submenus open in place
+/// of their menu with an entry to go back. Passive items, which the apps use when there is nothing to see, are
+/// hidden.
+///
+/// ```rust,ignore
+/// Container::row()
+///     .gap(8)
+///     .child(TrayHost::new(false))
+///     .child(Clock::new("%H:%M"))
+/// ```
+pub struct TrayHost {
+	root: Container,
+}
+
+impl TrayHost {
+	/// Creates the buttons of the items, opening the menus above them if `menu_above`, for panels at the bottom of
+	/// the screen, and below them otherwise.
+	pub fn new(menu_above: bool) -> Self {
+		let theme = use_theme();
+		let items = use_tray_items();
+		let (open, set_open) = use_state_handle(None::<OpenMenu>);
+		let open_item = open.as_ref().and_then(|open| {
+			items
+				.iter()
+				.find(|item| item.service == open.service && item.path == open.path)
+		});
+		let entries = use_tray_menu(open_item);
+		let root = items
+			.iter()
+			.filter(|item| item.status != TrayStatus::Passive)
+			.fold(
+				Container::row()
+					.role(Role::Group)
+					.label("System tray")
+					.gap(4),
+				|root, item| {
+					let open_menu = {
+						let set_open = set_open.clone();
+						let (service, path) = (item.service.clone(), item.path.clone());
+						move || {
+							set_open.set(Some(OpenMenu {
+								service: service.clone(),
+								path: path.clone(),
+								submenu: Vec::new(),
+							}))
+						}
+					};
+					let clicked = item.clone();
+					let left_click = open_menu.clone();
+					let right_clicked = item.clone();
+					let icon: Box<dyn Element> = match &item.icon {
+						Some(icon) => Box::new(Image::new().src(icon.clone()).size(ICON, ICON).contain()),
+						None => Box::new(
+							Text::new(item.title.chars().next().unwrap_or('?').to_string())
+								.font_size(theme.small_font_size),
+						),
+					};
+					root.child(
+						Container::new()
+							.id(button_id(item))
+							.role(Role::Button)
+							.label(&item.title)
+							.tooltip(&item.tooltip)
+							.padding_all(4)
+							.rounded(4.)
+							.center()
+							.style_if_hovered(|style| style.background_color((128, 128, 128, 30)))
+							.on_click(move || {
+								if clicked.item_is_menu && clicked.menu.is_some() {
+									left_click();
+								} else {
+									let (x, y) = button_center(&clicked);
+									clicked.activate(x, y);
+								}
+							})
+							.on_right_click(move || {
+								if right_clicked.menu.is_some() {
+									open_menu();
+								} else {
+									let (x, y) = button_center(&right_clicked);
+									right_clicked.context_menu(x, y);
+								}
+							})
+							.child(icon),
+					)
+				},
+			);
+		// Last, as the containers of the menu only exist while it is open
+		if let (Some(open), Some(item), Some(entries)) = (&open, open_item, &entries)
+			&& let Some(shown) = submenu(entries, &open.submenu)
+			&& let Some(bounds) = crate::element_ids::bounding_box(&button_id(item))
+		{
+			let mut menu_items = Vec::new();
+			if !open.submenu.is_empty() {
+				let set_open = set_open.clone();
+				let mut parent = open.clone();
+				parent.submenu.pop();
+				menu_items.push(MenuItem::new("‹ Back", move || {
+					set_open.set(Some(parent.clone()))
+				}));
+			}
+			for entry in shown.iter().filter(|entry| !entry.separator) {
+				let set_open = set_open.clone();
+				let mut child = open.clone();
+				child.submenu.push(entry.id);
+				let clicked = entry.clone();
+				menu_items.push(
+					MenuItem::new(entry_label(entry), move || {
+						if clicked.children.is_empty() {
+							clicked.activate();
+						} else {
+							set_open.set(Some(child.clone()));
+						}
+					})
+					.enabled(entry.enabled),
+				);
+			}
+			// Roughly the height of the entries of `context_menu`, to open the menu above the item
+			let height = menu_items.len() as f32 * (theme.font_size as f32 + 16.) + 10.;
+			let y = if menu_above {
+				bounds.y - height - 4.
+			} else {
+				bounds.y + bounds.height + 4.
+			};
+			let set_open = set_open.clone();
+			push_overlay(context_menu((bounds.x, y), menu_items, move || {
+				set_open.set(None)
+			}));
+		}
+		Self { root }
+	}
+}
+
+impl Element for TrayHost {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		self.root.render(ctx);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self.root.focus_nodes()
+	}
+}
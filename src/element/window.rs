@@ -0,0 +1,464 @@
+//! Windows floating inside the app, moved by their title bar and resized from their corner, like the panels of a
+//! dashboard.
+use std::{
+	cell::RefCell,
+	collections::{HashMap, HashSet},
+	rc::Rc,
+};
+
+use clay_layout::{
+	Declaration,
+	elements::FloatingAttachToElement,
+	layout::{LayoutDirection, Sizing},
+	math::Vector2,
+};
+use uuid::Uuid;
+
+use crate::{
+	Align, Container, Element, GlobalClosure, Justify, RenderContext, Role, Text, begin_component,
+	end_component, use_memo, use_ref,
+};
+
+/// Height of the title bar, which is kept inside the area so windows can always be moved back.
+const TITLE_BAR_HEIGHT: f32 = 32.;
+/// Size of the grip in the bottom right corner of resizable windows.
+const GRIP_SIZE: f32 = 12.;
+
+/// A window of a [`WindowArea`], with a title bar to move it and a grip in its bottom right corner to resize it.
+pub struct Window {
+	id: String,
+	pub title: String,
+	/// Where the window opens, from the top left corner of the area.
+	pub position: (f32, f32),
+	/// The size the window opens with.
+	pub size: (f32, f32),
+	pub min_size: (f32, f32),
+	pub resizable: bool,
+	on_close: Option<Rc<dyn Fn()>>,
+	children: Vec<Box<dyn Element>>,
+}
+
+impl Window {
+	/// A window identified by `id` among the windows of its area, which keeps its position, size and stacking
+	/// order across renders.
+	pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+		Self {
+			id: id.into(),
+			title: title.into(),
+			position: (24., 24.),
+			size: (320., 240.),
+			min_size: (120., 80.),
+			resizable: true,
+			on_close: None,
+			children: Vec::new(),
+		}
+	}
+
+	/// Where the window opens. Once the user moved it, the window stays where it was moved.
+	pub fn position(mut self, x: f32, y: f32) -> Self {
+		self.position = (x, y);
+		self
+	}
+
+	/// The size the window opens with. Once the user resized it, the window keeps the size it was given.
+	pub fn size(mut self, width: f32, height: f32) -> Self {
+		self.size = (width, height);
+		self
+	}
+
+	pub fn min_size(mut self, width: f32, height: f32) -> Self {
+		self.min_size = (width, height);
+		self
+	}
+
+	pub fn resizable(mut self, resizable: bool) -> Self {
+		self.resizable = resizable;
+		self
+	}
+
+	/// Shows a close button in the title bar, calling `handler`, which should stop rendering the window.
+	pub fn on_close(mut self, handler: impl Fn() + 'static) -> Self {
+		self.on_close = Some(Rc::new(handler));
+		self
+	}
+
+	pub fn child(mut self, element: impl Element + 'static) -> Self {
+		self.children.push(Box::new(element));
+		self
+	}
+}
+
+/// What a press on a window started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GestureKind {
+	Move,
+	Resize,
+}
+
+/// A window being moved or resized with the pointer.
+#[derive(Debug, Clone, PartialEq)]
+struct Gesture {
+	window: String,
+	kind: GestureKind,
+	/// Where the pointer was pressed, known once the frame of the press is declared.
+	start: Option<(f32, f32)>,
+	/// The position or size of the window when the press started.
+	from: (f32, f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Frame {
+	position: (f32, f32),
+	size: (f32, f32),
+	min_size: (f32, f32),
+}
+
+#[derive(Debug, Default)]
+struct AreaState {
+	frames: HashMap<String, Frame>,
+	/// The ids of the windows, from the back to the front.
+	order: Vec<String>,
+	gesture: Option<Gesture>,
+}
+
+impl AreaState {
+	/// Brings the window `id` to the front.
+	fn raise(&mut self, id: &str) {
+		if self.order.last().is_some_and(|last| last == id) {
+			return;
+		}
+		self.order.retain(|other| other != id);
+		self.order.push(id.to_string());
+		crate::REQUEST_REDRAW.call();
+	}
+
+	fn start(&mut self, id: &str, kind: GestureKind) {
+		let Some(frame) = self.frames.get(id) else {
+			return;
+		};
+		self.gesture = Some(Gesture {
+			window: id.to_string(),
+			kind,
+			start: None,
+			from: match kind {
+				GestureKind::Move => frame.position,
+				GestureKind::Resize => frame.size,
+			},
+		});
+	}
+
+	/// Moves or resizes the window of the gesture to follow the pointer, keeping its title bar inside an area of
+	/// size `area`.
+	fn update(&mut self, pointer: (f32, f32), pressed: bool, area: Option<(f32, f32)>) {
+		let Some(gesture) = &mut self.gesture else {
+			return;
+		};
+		if !pressed {
+			self.gesture = None;
+			return;
+		}
+		let start = *gesture.start.get_or_insert(pointer);
+		let Some(frame) = self.frames.get_mut(&gesture.window) else {
+			self.gesture = None;
+			return;
+		};
+		let delta = (pointer.0 - start.0, pointer.1 - start.1);
+		let moved = (gesture.from.0 + delta.0, gesture.from.1 + delta.1);
+		let before = *frame;
+		match gesture.kind {
+			GestureKind::Move => frame.position = moved,
+			GestureKind::Resize => {
+				frame.size = (moved.0.max(frame.min_size.0), moved.1.max(frame.min_size.1))
+			}
+		}
+		if let Some(area) = area {
+			frame.position = (
+				frame.position.0.clamp(0., (area.0 - frame.size.0).max(0.)),
+				frame
+					.position
+					.1
+					.clamp(0., (area.1 - TITLE_BAR_HEIGHT).max(0.)),
+			);
+		}
+		if *frame != before {
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+}
+
+/// An area hosting [`Window`]s that float over each other, like the panels of a dashboard inside one surface.
+///
+/// Windows are moved by dragging their title bar and resized by dragging their bottom right corner. Pressing a
+/// window brings it to the front. The area keeps the position, size and stacking order of each window across
+/// renders, by window id, so build the windows on every render, like any other element.
+///
+/// ```rust,ignore
+/// WindowArea::new()
+///     .window(Window::new("cpu", "CPU").child(cpu_graph))
+///     .window(
+///         Window::new("logs", "Logs")
+///             .position(360., 24.)
+///             .on_close(move || set_show_logs(false))
+///             .child(logs),
+///     )
+/// ```
+pub struct WindowArea {
+	state: Rc<RefCell<AreaState>>,
+	/// The windows by id, in the order they were added.
+	windows: Vec<(String, Container)>,
+	clay_id: String,
+}
+
+impl Default for WindowArea {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl WindowArea {
+	pub fn new() -> Self {
+		begin_component("window area");
+		let state = use_ref(AreaState::default());
+		let id = *use_memo(Uuid::new_v4, ());
+		end_component();
+		Self {
+			state,
+			windows: Vec::new(),
+			clay_id: id.to_string(),
+		}
+	}
+
+	/// Adds a window, in front of the windows that were already open.
+	pub fn window(mut self, window: Window) -> Self {
+		let id = window.id.clone();
+		let front = {
+			let mut state = self.state.borrow_mut();
+			let min_size = window.min_size;
+			state
+				.frames
+				.entry(id.clone())
+				.and_modify(|frame| frame.min_size = min_size)
+				.or_insert(Frame {
+					position: window.position,
+					size: window.size,
+					min_size,
+				});
+			if !state.order.contains(&id) {
+				state.order.push(id.clone());
+			}
+			state.order.last() == Some(&id)
+		};
+		let container = self.build_window(window, front);
+		self.windows.push((id, container));
+		self
+	}
+
+	fn build_window(&self, window: Window, front: bool) -> Container {
+		let gesture = |kind| {
+			let state = self.state.clone();
+			let id = window.id.clone();
+			move || state.borrow_mut().start(&id, kind)
+		};
+		let raise = {
+			let state = self.state.clone();
+			let id = window.id.clone();
+			move || state.borrow_mut().raise(&id)
+		};
+		let mut title_bar = Container::row()
+			.w_expand()
+			.min_height(TITLE_BAR_HEIGHT)
+			.max_height(TITLE_BAR_HEIGHT)
+			.align(Align::Center)
+			.gap(8)
+			.weird_padding(0, 4, 0, 12)
+			.rounded_t(8.)
+			.background_color(if front {
+				(232, 232, 236, 255)
+			} else {
+				(244, 244, 246, 255)
+			})
+			.on_press(gesture(GestureKind::Move))
+			.child(
+				Container::new()
+					.w_expand()
+					.child(Text::new(window.title.clone()).ellipsis().max_lines(1)),
+			);
+		if let Some(on_close) = window.on_close {
+			title_bar = title_bar.child(
+				Container::new()
+					.role(Role::Button)
+					.label("Close")
+					.focusable()
+					.center()
+					.min_width(24.)
+					.max_width(24.)
+					.min_height(24.)
+					.max_height(24.)
+					.rounded(4.)
+					.style_if_hovered(|style| style.background_color((224, 27, 36, 60)))
+					.on_click(move || on_close())
+					.child(Text::new("×").font_size(16)),
+			);
+		}
+		let content = window
+			.children
+			.into_iter()
+			.fold(Container::column().w_expand().h_expand(), Container::child);
+		let mut root = Container::column()
+			.role(Role::Dialog)
+			.label(window.title)
+			.w_expand()
+			.h_expand()
+			.rounded(8.)
+			.background_color((255, 255, 255, 255))
+			.border_color((200, 200, 200, 255))
+			.border_width(1)
+			.elevation(if front { 3 } else { 1 })
+			.on_press(raise)
+			.child(title_bar)
+			.child(content);
+		if window.resizable {
+			root = root.child(
+				Container::row().w_expand().justify(Justify::Right).child(
+					Container::new()
+						.min_width(GRIP_SIZE)
+						.max_width(GRIP_SIZE)
+						.min_height(GRIP_SIZE)
+						.max_height(GRIP_SIZE)
+						.rounded(2.)
+						.style_if_hovered(|style| style.background_color((53, 132, 228, 80)))
+						.on_press(gesture(GestureKind::Resize)),
+				),
+			);
+		}
+		root
+	}
+}
+
+impl Element for WindowArea {
+	fn render<'clay: 'render, 'render>(&'render self, ctx: &mut RenderContext<'clay, 'render, '_>) {
+		let id = ctx.c.id(&self.clay_id);
+		let area = ctx
+			.c
+			.bounding_box(id.clone())
+			.map(|bounds| (bounds.width, bounds.height));
+		let order = self.state.borrow().order.clone();
+		ctx.c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration.id(id);
+				declaration
+					.layout()
+					.direction(LayoutDirection::LeftToRight)
+					.width(Sizing::Grow(0., f32::MAX))
+					.height(Sizing::Grow(0., f32::MAX))
+					.end()
+					.clip(true, true, Vector2::default());
+				declaration
+			},
+			|c| {
+				// Declared from the back to the front, so the front window gets the pointer
+				for id in &order {
+					let Some((_, window)) = self.windows.iter().find(|(window, _)| window == id) else {
+						continue;
+					};
+					let Some(frame) = self.state.borrow().frames.get(id).copied() else {
+						continue;
+					};
+					c.with_styling(
+						|_| {
+							let mut declaration = Declaration::new();
+							declaration
+								.layout()
+								.width(Sizing::Fixed(frame.size.0))
+								.height(Sizing::Fixed(frame.size.1))
+								.end()
+								.floating()
+								.offset(Vector2::new(frame.position.0, frame.position.1))
+								.attach_to(FloatingAttachToElement::Parent)
+								.end();
+							declaration
+						},
+						|c| {
+							let mut ctx = RenderContext {
+								c,
+								font_manager: &mut *ctx.font_manager,
+								input_manager: ctx.input_manager,
+							};
+							window.render(&mut ctx);
+						},
+					);
+				}
+			},
+		);
+		let mut state = self.state.borrow_mut();
+		// Windows that are no longer rendered are forgotten, and open again where they were first placed
+		state
+			.order
+			.retain(|id| self.windows.iter().any(|(window, _)| window == id));
+		let open = state.order.iter().cloned().collect::<HashSet<_>>();
+		state.frames.retain(|id, _| open.contains(id));
+		state.update(
+			ctx.input_manager.mouse_position(),
+			ctx.input_manager.is_mouse_button_pressed(0),
+			area,
+		);
+	}
+
+	fn focus_nodes(&self) -> HashSet<Uuid> {
+		self
+			.windows
+			.iter()
+			.flat_map(|(_, window)| window.focus_nodes())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn area_with(windows: &[&str]) -> AreaState {
+		let mut state = AreaState::default();
+		for (index, id) in windows.iter().enumerate() {
+			state.frames.insert(
+				id.to_string(),
+				Frame {
+					position: (index as f32 * 10., 0.),
+					size: (200., 100.),
+					min_size: (120., 80.),
+				},
+			);
+			state.order.push(id.to_string());
+		}
+		state
+	}
+
+	#[test]
+	fn test_windows_move_and_resize_within_the_area() {
+		let mut state = area_with(&["a", "b"]);
+		state.start("a", GestureKind::Move);
+		state.update((50., 50.), true, Some((400., 300.)));
+		state.update((80., 70.), true, Some((400., 300.)));
+		assert_eq!(state.frames["a"].position, (30., 20.));
+		// The title bar stays inside the area
+		state.update((1000., 1000.), true, Some((400., 300.)));
+		assert_eq!(state.frames["a"].position, (200., 300. - TITLE_BAR_HEIGHT));
+		state.update((0., 0.), false, Some((400., 300.)));
+		assert_eq!(state.gesture, None);
+
+		state.start("b", GestureKind::Resize);
+		state.update((10., 10.), true, None);
+		state.update((-200., 40.), true, None);
+		assert_eq!(state.frames["b"].size, (120., 130.));
+	}
+
+	#[test]
+	fn test_raising_brings_a_window_to_the_front() {
+		let mut state = area_with(&["a", "b", "c"]);
+		state.raise("a");
+		assert_eq!(state.order, ["b", "c", "a"]);
+		state.raise("a");
+		assert_eq!(state.order, ["b", "c", "a"]);
+	}
+}
@@ -0,0 +1,153 @@
+use std::cell::{Cell, RefCell};
+
+use clay_layout::{id::Id, math::BoundingBox};
+
+/// An element given an id with [`Container::id`](crate::Container::id).
+struct IdentifiedElement<Bounds> {
+	name: String,
+	bounds: Bounds,
+	/// The overlay layer of the element, elements of higher layers are on top.
+	layer: usize,
+	/// The z-index of the floating container around the element, see [`enter_floating`].
+	z_index: i16,
+}
+
+thread_local! {
+	/// Elements with an id declared this frame, in tree order.
+	static DECLARED: RefCell<Vec<IdentifiedElement<Id>>> = const { RefCell::new(Vec::new()) };
+	/// Elements with an id of the last frame, with their bounds after layout.
+	static LAID_OUT: RefCell<Vec<IdentifiedElement<BoundingBox>>> = const { RefCell::new(Vec::new()) };
+	/// The z-index of the innermost floating container being declared, `0` outside of them.
+	static Z_INDEX: Cell<i16> = const { Cell::new(0) };
+}
+
+/// Declares the children of a floating container drawn at `z_index`, returning the z-index to restore with
+/// [`leave_floating`].
+pub(crate) fn enter_floating(z_index: i16) -> i16 {
+	Z_INDEX.replace(z_index)
+}
+
+pub(crate) fn leave_floating(previous: i16) {
+	Z_INDEX.set(previous);
+}
+
+/// Records an element with an id, called while it is declared.
+pub(crate) fn register(name: &str, id: Id) {
+	DECLARED.with_borrow_mut(|declared| {
+		declared.push(IdentifiedElement {
+			name: name.to_string(),
+			bounds: id,
+			layer: crate::overlay::current_layer(),
+			z_index: Z_INDEX.get(),
+		})
+	});
+}
+
+/// Resolves the bounds of the elements declared this frame, replacing the ones of the last frame.
+pub(crate) fn end_frame(bounding_box: impl Fn(Id) -> Option<BoundingBox>) {
+	let declared = DECLARED.with_borrow_mut(std::mem::take);
+	let laid_out = declared
+		.into_iter()
+		.filter_map(|element| {
+			Some(IdentifiedElement {
+				bounds: bounding_box(element.bounds)?,
+				name: element.name,
+				layer: element.layer,
+				z_index: element.z_index,
+			})
+		})
+		.collect();
+	LAID_OUT.set(laid_out);
+}
+
+/// Returns the bounds of the element with the id `name` in the last frame.
+pub(crate) fn bounding_box(name: &str) -> Option<BoundingBox> {
+	LAID_OUT.with_borrow(|elements| {
+		elements
+			.iter()
+			.find(|element| element.name == name)
+			.map(|element| element.bounds)
+	})
+}
+
+fn contains(bounds: &BoundingBox, (x, y): (f32, f32)) -> bool {
+	x >= bounds.x && y >= bounds.y && x < bounds.x + bounds.width && y < bounds.y + bounds.height
+}
+
+/// Returns the id of the topmost element with an id at `point` in the last frame.
+///
+/// Elements of overlays are above the main tree, then floating containers are above the rest in the order of their
+/// z-index, then children are above their parents and later siblings above earlier ones.
+pub(crate) fn element_at(point: (f32, f32)) -> Option<String> {
+	LAID_OUT.with_borrow(|elements| {
+		elements
+			.iter()
+			.enumerate()
+			.filter(|(_, element)| contains(&element.bounds, point))
+			.max_by_key(|(index, element)| (element.layer, element.z_index, *index))
+			.map(|(_, element)| element.name.clone())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Element, Text, testing::TestHarness, use_state};
+
+	fn element(
+		name: &str,
+		x: f32,
+		y: f32,
+		size: f32,
+		layer: usize,
+	) -> IdentifiedElement<BoundingBox> {
+		IdentifiedElement {
+			name: name.to_string(),
+			bounds: BoundingBox {
+				x,
+				y,
+				width: size,
+				height: size,
+			},
+			layer,
+			z_index: 0,
+		}
+	}
+
+	#[test]
+	fn test_element_at() {
+		LAID_OUT.set(vec![
+			element("popup", 50., 50., 20., 1),
+			element("root", 0., 0., 100., 0),
+			element("child", 10., 10., 20., 0),
+		]);
+		assert_eq!(element_at((15., 15.)).as_deref(), Some("child"));
+		assert_eq!(element_at((60., 60.)).as_deref(), Some("popup"));
+		assert_eq!(element_at((90., 5.)).as_deref(), Some("root"));
+		assert_eq!(element_at((150., 5.)), None);
+		assert_eq!(bounding_box("child").map(|bounds| bounds.x), Some(10.));
+	}
+
+	fn identified(_: ()) -> Box<dyn Element> {
+		let (count, set_count) = use_state(0);
+		Box::new(
+			Container::new().id("root").padding_all(10).child(
+				Container::new()
+					.id("button")
+					.on_click(move || set_count(count + 1))
+					.child(Text::new(format!("Count: {count}"))),
+			),
+		)
+	}
+
+	#[test]
+	fn test_element_ids() {
+		let mut harness = TestHarness::new(identified, (), (400., 300.));
+		let (x, y, ..) = harness.bounds_of("button").unwrap();
+		assert_eq!((x, y), (10., 10.));
+		assert_eq!(element_at((12., 12.)).as_deref(), Some("button"));
+		assert_eq!(element_at((5., 5.)).as_deref(), Some("root"));
+		harness.click_id("button");
+		assert!(harness.find_by_label("Count: 1").is_some());
+	}
+}
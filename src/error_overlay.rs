@@ -0,0 +1,92 @@
+use std::{
+	any::Any,
+	cell::RefCell,
+	panic::{AssertUnwindSafe, catch_unwind},
+	sync::Once,
+};
+
+use clay_layout::Color;
+
+use crate::{Component, Container, Element, Text, hooks};
+
+/// Where a panic happened, captured by the panic hook before the stack unwinds.
+struct PanicContext {
+	location: Option<String>,
+	component_path: Vec<String>,
+}
+
+thread_local! {
+	static LAST_PANIC: RefCell<Option<PanicContext>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Records the component path of every panic, then runs the previous hook so the panic is still printed.
+fn install_panic_hook() {
+	INSTALL_HOOK.call_once(|| {
+		let previous = std::panic::take_hook();
+		std::panic::set_hook(Box::new(move |info| {
+			let context = PanicContext {
+				location: info.location().map(|l| l.to_string()),
+				component_path: hooks::current_component_path(),
+			};
+			LAST_PANIC.set(Some(context));
+			previous(info);
+		}));
+	});
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+	if let Some(message) = payload.downcast_ref::<&str>() {
+		message.to_string()
+	} else if let Some(message) = payload.downcast_ref::<String>() {
+		message.clone()
+	} else {
+		"Box<dyn Any>".to_string()
+	}
+}
+
+fn line(text: impl Into<String>) -> Text {
+	Text::new(text).color(Color::rgb(255., 255., 255.))
+}
+
+fn error_overlay(message: String, context: Option<PanicContext>) -> Box<dyn Element> {
+	let mut overlay = Container::column()
+		.w_expand()
+		.h_expand()
+		.padding_all(24)
+		.gap(8)
+		.background_color(Color::rgb(127., 29., 29.))
+		.role(crate::Role::Dialog)
+		.child(line("A component panicked").font_size(20))
+		.child(line(message));
+	if let Some(context) = context {
+		if let Some(location) = context.location {
+			overlay = overlay.child(line(format!("at {location}")));
+		}
+		overlay = overlay.child(line("Component path:"));
+		for (depth, component) in context.component_path.into_iter().enumerate() {
+			overlay = overlay.child(line(format!("{}{component}", "  ".repeat(depth))));
+		}
+	}
+	Box::new(overlay)
+}
+
+/// Builds the root component, replacing it with an error overlay if it panics.
+///
+/// Only debug builds catch panics, release builds keep the default behavior of aborting the window.
+pub(crate) fn build_root(build: impl FnOnce() -> Component) -> Component {
+	if !cfg!(debug_assertions) {
+		return build();
+	}
+	install_panic_hook();
+	match catch_unwind(AssertUnwindSafe(build)) {
+		Ok(component) => component,
+		Err(payload) => {
+			hooks::recover_from_panic();
+			let message = panic_message(&*payload);
+			let context = LAST_PANIC.take();
+			Component::new(move |_| error_overlay(message, context), ())
+		}
+	}
+}
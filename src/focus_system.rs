@@ -2,6 +2,8 @@ use std::{
 	cell::RefCell,
 	collections::{HashMap, HashSet},
 };
+
+use clay_layout::{id::Id, math::BoundingBox};
 use uuid::Uuid;
 
 #[derive(Clone, Copy)]
@@ -14,9 +16,27 @@ enum Parent {
 #[derive(Clone, Copy)]
 struct Node {
 	parent: Parent,
-	prev: Option<Uuid>,
 	next: Option<Uuid>,
 	skip: bool,
+	/// Whether the node traps focus while it is open, see [`Container::focus_scope`](crate::Container::focus_scope).
+	scope: bool,
+	/// Whether the node handles the arrow keys itself, so they don't move focus away from it.
+	captures_arrows: bool,
+}
+
+/// A direction to move focus in with [`FocusManager::focus_in_direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+	Up,
+	Down,
+	Left,
+	Right,
+}
+
+/// A focus scope that is open, with the node that was focused before it opened.
+struct OpenScope {
+	node: Uuid,
+	restore: Option<Uuid>,
 }
 
 pub struct FocusManager {
@@ -24,6 +44,13 @@ pub struct FocusManager {
 	current: Option<Uuid>,
 	first: Option<Uuid>,
 	last: Option<Uuid>,
+	wrapped: bool,
+	/// Open focus scopes, innermost last.
+	scopes: Vec<OpenScope>,
+	/// Elements of the focus nodes declared this frame.
+	declared: Vec<(Uuid, Id)>,
+	/// Bounds of the focus nodes in the last frame, for directional navigation.
+	bounds: HashMap<Uuid, BoundingBox>,
 }
 
 impl FocusManager {
@@ -33,6 +60,10 @@ impl FocusManager {
 			current: None,
 			last: None,
 			first: None,
+			wrapped: false,
+			scopes: Vec::new(),
+			declared: Vec::new(),
+			bounds: HashMap::new(),
 		}
 	}
 	pub fn blur(&mut self) {
@@ -48,6 +79,11 @@ impl FocusManager {
 		}
 	}
 
+	/// Forgets whether the focus wrapped in the last frame, before this frame's Tab is handled.
+	pub(crate) fn begin_frame(&mut self) {
+		self.wrapped = false;
+	}
+
 	pub(crate) fn new_frame(&mut self) {
 		self.remove_dangling_nodes();
 
@@ -68,9 +104,10 @@ impl FocusManager {
 				node_id,
 				Node {
 					parent: Parent::Undefined,
-					prev: self.last,
 					next: None,
 					skip,
+					scope: false,
+					captures_arrows: false,
 				},
 			);
 			if let Some(prev) = self.last {
@@ -91,6 +128,86 @@ impl FocusManager {
 		}
 	}
 
+	/// Makes the node `id` a focus scope, see [`Container::focus_scope`](crate::Container::focus_scope).
+	pub fn set_node_scope(&mut self, id: Uuid) {
+		if let Some(node) = self.focus_nodes.get_mut(&id) {
+			node.scope = true;
+		}
+	}
+
+	/// Keeps the arrow keys for the node `id` while it is focused, instead of moving focus with them.
+	pub fn set_node_captures_arrows(&mut self, id: Uuid) {
+		if let Some(node) = self.focus_nodes.get_mut(&id) {
+			node.captures_arrows = true;
+		}
+	}
+
+	/// Records the element of the focus node `id`, to know where it is after layout.
+	pub(crate) fn declare_element(&mut self, id: Uuid, element: Id) {
+		self.declared.push((id, element));
+	}
+
+	/// Resolves the bounds of the focus nodes declared this frame.
+	pub(crate) fn end_frame(&mut self, bounding_box: impl Fn(Id) -> Option<BoundingBox>) {
+		self.bounds = self
+			.declared
+			.drain(..)
+			.filter_map(|(id, element)| Some((id, bounding_box(element)?)))
+			.collect();
+	}
+
+	/// Returns where the element of the focus node `id` was laid out in the last frame.
+	pub(crate) fn node_bounds(&self, id: Uuid) -> Option<BoundingBox> {
+		self.bounds.get(&id).copied()
+	}
+
+	/// Opens the focus scopes declared in the last frame and closes the ones that are gone.
+	///
+	/// Opening a scope focuses its first node, closing it focuses the node that was focused before it opened.
+	pub(crate) fn update_scopes(&mut self) {
+		while let Some(scope) = self.scopes.last() {
+			if self
+				.focus_nodes
+				.get(&scope.node)
+				.is_some_and(|node| node.scope)
+			{
+				break;
+			}
+			let restore = self.scopes.pop().unwrap().restore;
+			if let Some(restore) = restore.filter(|id| self.focus_nodes.contains_key(id)) {
+				self.current = Some(restore);
+			}
+		}
+		// Scopes declared later are on top, like the overlays of nested menus
+		let opened = self
+			.focus_order()
+			.into_iter()
+			.filter(|id| self.focus_nodes[id].scope && !self.scopes.iter().any(|scope| scope.node == *id))
+			.collect::<Vec<_>>();
+		for node in opened {
+			self.scopes.push(OpenScope {
+				node,
+				restore: self.current,
+			});
+			if let Some(first) = self
+				.focus_order()
+				.into_iter()
+				.find(|&id| self.is_navigable(id))
+			{
+				self.current = Some(first);
+			}
+		}
+	}
+
+	/// Returns `true` if `id` can be focused with the keyboard, which is only inside the innermost open scope.
+	fn is_navigable(&self, id: Uuid) -> bool {
+		let in_scope = self
+			.scopes
+			.last()
+			.is_none_or(|scope| self.ancestors(id).contains(&scope.node));
+		!self.focus_nodes[&id].skip && in_scope
+	}
+
 	pub fn set_parent(&mut self, children: impl IntoIterator<Item = Uuid>, parent: Uuid) -> Uuid {
 		for child_id in children {
 			if let Some(node) = self.focus_nodes.get_mut(&child_id) {
@@ -116,60 +233,82 @@ impl FocusManager {
 		}
 	}
 
+	/// Focuses the next node in Tab order, wrapping around the end of the order or of the open focus scope.
 	pub fn focus_next(&mut self) {
-		println!("focus_next");
+		self.focus_step(true);
+	}
 
-		let mut next = self
-			.current
-			.and_then(|cur| self.focus_nodes[&cur].next)
-			.or(self.first);
+	/// Focuses the previous node in Tab order, wrapping around the start of the order or of the open focus scope.
+	pub fn focus_prev(&mut self) {
+		self.focus_step(false);
+	}
 
-		while let Some(id) = next {
-			if let Some(node) = self.focus_nodes.get(&id) {
-				if !node.skip {
-					self.current = Some(id);
-					return;
-				}
-				next = node.next.or(self.first); // wrap-around
-				if Some(id) == self.first {
-					break; // ciclo completo
-				}
-			} else {
-				break;
-			}
+	fn focus_step(&mut self, forward: bool) {
+		let mut order = self.focus_order();
+		if !forward {
+			order.reverse();
 		}
-
-		self.current = None;
+		let start = self
+			.current
+			.and_then(|current| order.iter().position(|&id| id == current))
+			.map_or(0, |index| index + 1);
+		let next = (start..order.len())
+			.map(|index| (index, false))
+			.chain((0..start).map(|index| (index, true)))
+			.find(|&(index, _)| self.is_navigable(order[index]));
+		self.wrapped = next.is_some_and(|(_, wrapped)| wrapped);
+		self.current = next.map(|(index, _)| order[index]);
 	}
 
-	pub fn focus_prev(&mut self) {
-		let mut prev = self
-			.current
-			.and_then(|cur| self.focus_nodes[&cur].prev)
-			.or(self.last);
-
-		while let Some(id) = prev {
-			if let Some(node) = self.focus_nodes.get(&id) {
-				if !node.skip {
-					self.current = Some(id);
-					return;
-				}
-				prev = node.prev.or(self.last); // wrap-around
-				if Some(id) == self.last {
-					break; // ciclo completo
-				}
-			} else {
-				break;
-			}
+	/// Focuses the closest node in `direction` from the focused one, by where they were laid out in the last frame.
+	///
+	/// Nodes straight in the direction are preferred over closer nodes off to the side, and the first in Tab order
+	/// wins between nodes as close. Returns `false` if there is no node in that direction. Without a focused node,
+	/// the first node in Tab order is focused.
+	pub fn focus_in_direction(&mut self, direction: FocusDirection) -> bool {
+		let Some(current) = self.current else {
+			self.focus_next();
+			return self.current.is_some();
+		};
+		let Some(from) = self.bounds.get(&current) else {
+			return false;
+		};
+		let closest = self
+			.focus_order()
+			.into_iter()
+			.filter(|&id| id != current && self.is_navigable(id))
+			.filter_map(|id| {
+				Some((
+					id,
+					directional_distance(from, self.bounds.get(&id)?, direction)?,
+				))
+			})
+			.min_by(|(_, a), (_, b)| a.total_cmp(b));
+		if let Some((id, _)) = closest {
+			self.current = Some(id);
 		}
+		closest.is_some()
+	}
 
-		self.current = None;
+	/// Returns `true` if the focused node handles the arrow keys itself.
+	pub fn focused_captures_arrows(&self) -> bool {
+		self
+			.current
+			.and_then(|id| self.focus_nodes.get(&id))
+			.is_some_and(|node| node.captures_arrows)
 	}
 
 	pub fn focused(&self) -> Option<Uuid> {
 		self.current
 	}
 
+	/// Scrolls the containers around the element of the node `id`, so it becomes visible.
+	///
+	/// The focused element is brought into view automatically whenever focus moves.
+	pub fn scroll_into_view(&self, id: Uuid) {
+		crate::scroll::scroll_into_view(id);
+	}
+
 	pub fn has_focused_child(&self, parent_id: Uuid) -> bool {
 		let Some(current) = self.current else {
 			return false;
@@ -188,8 +327,288 @@ impl FocusManager {
 			}
 		}
 	}
+
+	/// Returns every registered focus node in Tab order, including nodes that are skipped
+	/// by keyboard navigation (focus containers).
+	///
+	/// Nodes are registered while the tree is being built, so during a frame this only contains
+	/// the nodes created so far.
+	pub fn focus_order(&self) -> Vec<Uuid> {
+		let mut order = Vec::with_capacity(self.focus_nodes.len());
+		let mut next = self.first;
+		while let Some(id) = next {
+			order.push(id);
+			next = self.focus_nodes.get(&id).and_then(|n| n.next);
+		}
+		order
+	}
+
+	/// Returns the focus nodes that [`focus_next`](Self::focus_next) and
+	/// [`focus_prev`](Self::focus_prev) can land on, in Tab order.
+	pub fn focusable_nodes(&self) -> Vec<Uuid> {
+		self
+			.focus_order()
+			.into_iter()
+			.filter(|id| !self.focus_nodes[id].skip)
+			.collect()
+	}
+
+	/// Returns `true` if `id` is registered in the focus system.
+	pub fn contains(&self, id: Uuid) -> bool {
+		self.focus_nodes.contains_key(&id)
+	}
+
+	/// Returns `true` if `id` is a focus container that is skipped by keyboard navigation.
+	pub fn is_skipped(&self, id: Uuid) -> bool {
+		self.focus_nodes.get(&id).is_some_and(|n| n.skip)
+	}
+
+	/// Returns the parent focus node of `id`, if it has one.
+	pub fn parent(&self, id: Uuid) -> Option<Uuid> {
+		match self.focus_nodes.get(&id)?.parent {
+			Parent::Parent(parent) => Some(parent),
+			Parent::Root | Parent::Undefined => None,
+		}
+	}
+
+	/// Returns the ancestor chain of `id`, starting with its direct parent and ending at the root.
+	pub fn ancestors(&self, id: Uuid) -> Vec<Uuid> {
+		let mut ancestors = Vec::new();
+		let mut cur = id;
+		while let Some(parent) = self.parent(cur) {
+			if ancestors.contains(&parent) {
+				break;
+			}
+			ancestors.push(parent);
+			cur = parent;
+		}
+		ancestors
+	}
+
+	/// Returns the ancestor chain of the focused node, or an empty list if nothing is focused.
+	pub fn focused_ancestors(&self) -> Vec<Uuid> {
+		self.current.map(|id| self.ancestors(id)).unwrap_or_default()
+	}
+
+	/// Returns `true` if a call to [`focus_next`](Self::focus_next) or [`focus_prev`](Self::focus_prev) in this
+	/// frame wrapped around the end of the focus order.
+	pub fn did_wrap(&self) -> bool {
+		self.wrapped
+	}
+}
+
+/// Runs `f` with a shared reference to the focus manager of the current thread.
+///
+/// Useful for debugging tools and widgets that need to inspect the focus order.
+pub fn with_focus_manager<R>(f: impl FnOnce(&FocusManager) -> R) -> R {
+	GLOBAL_FOCUS_MANAGER.with_borrow(f)
+}
+
+/// Runs `f` with a mutable reference to the focus manager of the current thread.
+///
+/// Useful for implementing custom navigation schemes on top of the focus system.
+/// Must not be called while the focus manager is already borrowed (e.g. from inside [`with_focus_manager`]).
+pub fn with_focus_manager_mut<R>(f: impl FnOnce(&mut FocusManager) -> R) -> R {
+	GLOBAL_FOCUS_MANAGER.with_borrow_mut(f)
+}
+
+/// Returns the start and end of `bounds` along `direction` and across it, oriented so that `direction` goes toward
+/// greater values.
+fn oriented(bounds: &BoundingBox, direction: FocusDirection) -> ((f32, f32), (f32, f32)) {
+	let x = (bounds.x, bounds.x + bounds.width);
+	let y = (bounds.y, bounds.y + bounds.height);
+	match direction {
+		FocusDirection::Right => (x, y),
+		FocusDirection::Left => ((-x.1, -x.0), y),
+		FocusDirection::Down => (y, x),
+		FocusDirection::Up => ((-y.1, -y.0), x),
+	}
+}
+
+/// Returns how far `to` is from `from` in `direction`, counting the distance to the side twice, or `None` if it
+/// is not in that direction.
+fn directional_distance(
+	from: &BoundingBox,
+	to: &BoundingBox,
+	direction: FocusDirection,
+) -> Option<f32> {
+	let (from_along, from_across) = oriented(from, direction);
+	let (to_along, to_across) = oriented(to, direction);
+	// The center of the node must be past the center of the focused one
+	if to_along.0 + to_along.1 <= from_along.0 + from_along.1 {
+		return None;
+	}
+	let along = (to_along.0 - from_along.1).max(0.);
+	let across = (to_across.0 - from_across.1)
+		.max(from_across.0 - to_across.1)
+		.max(0.);
+	Some(along + 2. * across)
 }
 
 thread_local! {
 		pub static GLOBAL_FOCUS_MANAGER: RefCell<FocusManager> = RefCell::new(FocusManager::new());
 }
+
+#[cfg(test)]
+mod tests {
+	use std::rc::Rc;
+
+	use super::*;
+	use crate::{Container, Element, Key, NamedKey, testing::TestHarness, use_state};
+
+	fn manager_with_nodes(skips: &[bool]) -> (FocusManager, Vec<Uuid>) {
+		let mut manager = FocusManager::new();
+		let ids = skips
+			.iter()
+			.map(|&skip| manager.add_node(Uuid::new_v4(), skip))
+			.collect();
+		(manager, ids)
+	}
+
+	#[test]
+	fn test_focus_order_follows_registration() {
+		let (manager, ids) = manager_with_nodes(&[false, true, false]);
+		assert_eq!(manager.focus_order(), ids);
+		assert_eq!(manager.focusable_nodes(), vec![ids[0], ids[2]]);
+		assert!(manager.is_skipped(ids[1]));
+	}
+
+	#[test]
+	fn test_focused_ancestors() {
+		let (mut manager, ids) = manager_with_nodes(&[true, true, false]);
+		manager.set_parent([ids[2]], ids[1]);
+		manager.set_parent([ids[1]], ids[0]);
+		manager.add_root();
+		manager.set_focus(ids[2]);
+		assert_eq!(manager.focused_ancestors(), vec![ids[1], ids[0]]);
+		assert_eq!(manager.parent(ids[0]), None);
+	}
+
+	#[test]
+	fn test_did_wrap() {
+		let (mut manager, ids) = manager_with_nodes(&[false, false]);
+		manager.focus_next();
+		assert_eq!(manager.focused(), Some(ids[0]));
+		assert!(!manager.did_wrap());
+		manager.focus_next();
+		assert_eq!(manager.focused(), Some(ids[1]));
+		assert!(!manager.did_wrap());
+		manager.focus_next();
+		assert_eq!(manager.focused(), Some(ids[0]));
+		assert!(manager.did_wrap());
+		manager.focus_prev();
+		assert_eq!(manager.focused(), Some(ids[1]));
+		assert!(manager.did_wrap());
+		// The next frame doesn't remember it
+		manager.begin_frame();
+		assert!(!manager.did_wrap());
+	}
+
+	#[test]
+	fn test_focus_in_direction() {
+		// A 2x2 grid of 10x10 nodes with a gap of 10, and a wide node below it
+		let (mut manager, ids) = manager_with_nodes(&[false; 5]);
+		let cells = [
+			(0., 0., 10.),
+			(20., 0., 10.),
+			(0., 20., 10.),
+			(20., 20., 10.),
+			(0., 40., 30.),
+		];
+		for (id, (x, y, width)) in ids.iter().zip(cells) {
+			let bounds = BoundingBox {
+				x,
+				y,
+				width,
+				height: 10.,
+			};
+			manager.bounds.insert(*id, bounds);
+		}
+		assert!(manager.focus_in_direction(FocusDirection::Down));
+		assert_eq!(manager.focused(), Some(ids[0]));
+		manager.focus_in_direction(FocusDirection::Right);
+		assert_eq!(manager.focused(), Some(ids[1]));
+		manager.focus_in_direction(FocusDirection::Down);
+		assert_eq!(manager.focused(), Some(ids[3]));
+		manager.focus_in_direction(FocusDirection::Down);
+		assert_eq!(manager.focused(), Some(ids[4]));
+		assert!(!manager.focus_in_direction(FocusDirection::Down));
+		manager.focus_in_direction(FocusDirection::Up);
+		assert_eq!(manager.focused(), Some(ids[2]));
+	}
+
+	#[test]
+	fn test_focus_scope_traps_and_restores_focus() {
+		let (mut manager, ids) = manager_with_nodes(&[false, false, true, false]);
+		manager.set_parent([ids[1]], ids[2]);
+		manager.add_root();
+		manager.set_focus(ids[0]);
+		manager.update_scopes();
+		assert_eq!(manager.focused(), Some(ids[0]));
+		// The menu opens
+		manager.set_node_scope(ids[2]);
+		manager.update_scopes();
+		assert_eq!(manager.focused(), Some(ids[1]));
+		manager.focus_next();
+		assert_eq!(manager.focused(), Some(ids[1]));
+		assert!(manager.did_wrap());
+		// The menu closes
+		manager.focus_nodes.get_mut(&ids[2]).unwrap().scope = false;
+		manager.update_scopes();
+		assert_eq!(manager.focused(), Some(ids[0]));
+	}
+
+	/// Two buttons in a row, the second one opening a menu of two items below them.
+	fn menu_bar(_: ()) -> Box<dyn Element> {
+		let (open, set_open) = use_state(false);
+		let set_open = Rc::new(set_open);
+		let button = |label: &str, open: bool| {
+			let set_open = set_open.clone();
+			Container::new()
+				.label(label)
+				.min_width(40.)
+				.min_height(20.)
+				.focusable()
+				.on_click(move || set_open(open))
+		};
+		let mut root = Container::column().child(
+			Container::row()
+				.child(button("file", false))
+				.child(button("edit", true)),
+		);
+		if open {
+			root = root.child(
+				Container::column()
+					.focus_scope()
+					.child(button("undo", false))
+					.child(button("redo", false)),
+			);
+		}
+		Box::new(root)
+	}
+
+	#[test]
+	fn test_arrow_keys_and_focus_scopes() {
+		let focused = |harness: &TestHarness<_, _>| harness.focused().and_then(|node| node.label);
+		let mut harness = TestHarness::new(menu_bar, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.press_key(Key::Named(NamedKey::ArrowRight));
+		assert_eq!(focused(&harness).as_deref(), Some("edit"));
+		// Opening the menu moves focus into it, and keeps it there
+		harness.press_key(Key::Named(NamedKey::Enter));
+		harness.frames(2);
+		assert_eq!(focused(&harness).as_deref(), Some("undo"));
+		harness.press_key(Key::Named(NamedKey::ArrowDown));
+		assert_eq!(focused(&harness).as_deref(), Some("redo"));
+		harness.press_key(Key::Named(NamedKey::ArrowUp));
+		harness.press_key(Key::Named(NamedKey::ArrowUp));
+		assert_eq!(focused(&harness).as_deref(), Some("undo"));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		assert_eq!(focused(&harness).as_deref(), Some("undo"));
+		// Closing it gives focus back to the button that opened it
+		harness.press_key(Key::Named(NamedKey::Enter));
+		harness.frames(2);
+		assert_eq!(focused(&harness).as_deref(), Some("edit"));
+	}
+}
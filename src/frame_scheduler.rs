@@ -0,0 +1,259 @@
+use std::{
+	cell::Cell,
+	time::{Duration, Instant},
+};
+
+use crate::use_ref;
+
+thread_local! {
+	/// When the current frame started.
+	static FRAME_START: Cell<Option<Instant>> = const { Cell::new(None) };
+	/// Time between the start of the previous frame and the current one.
+	static FRAME_DELTA: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+	/// Number of [`use_tick`] calls that asked for the next frame during this one.
+	static SUBSCRIBERS: Cell<usize> = const { Cell::new(0) };
+	/// Earliest time a component asked to be rendered again, like the next blink of a caret.
+	static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+	/// How far the test harness moved the clock forward, see [`advance_clock`].
+	static CLOCK_OFFSET: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Longest frame delta reported to ticks, so animations don't jump after the window was idle.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
+
+/// Returns the current time as seen by frames, which is what delays and animations are measured with.
+pub(crate) fn now() -> Instant {
+	Instant::now() + CLOCK_OFFSET.get()
+}
+
+/// Returns the time since `since`, measured like [`now`].
+pub(crate) fn elapsed(since: Instant) -> Duration {
+	now().saturating_duration_since(since)
+}
+
+/// Moves the clock of the frames forward by `duration` without waiting, for tests of delays and animations.
+pub(crate) fn advance_clock(duration: Duration) {
+	CLOCK_OFFSET.set(CLOCK_OFFSET.get() + duration);
+}
+
+/// Called at the start of every frame, before the components are built.
+pub(crate) fn begin_frame() {
+	let now = now();
+	let delta = FRAME_START
+		.replace(Some(now))
+		.map_or(Duration::ZERO, |start| now - start);
+	FRAME_DELTA.set(delta.min(MAX_FRAME_DELTA));
+	SUBSCRIBERS.set(0);
+	DEADLINE.set(None);
+}
+
+/// Time since the previous frame, capped like the delta of [`Tick`].
+pub(crate) fn frame_delta() -> Duration {
+	FRAME_DELTA.get()
+}
+
+/// Returns `true` if a component asked to be ticked on the next frame.
+///
+/// The window then requests the next frame right after presenting this one, so frames follow the
+/// display refresh rate. Once nobody ticks anymore, it goes back to waiting for events.
+pub(crate) fn wants_next_frame() -> bool {
+	SUBSCRIBERS.get() > 0
+}
+
+/// Asks for a frame at `deadline`, for changes that happen at a known time without any event.
+pub(crate) fn request_frame_at(deadline: Instant) {
+	DEADLINE.set(Some(DEADLINE.get().map_or(deadline, |current| current.min(deadline))));
+}
+
+/// Returns when the window should wake up to render the next frame, if no event wakes it up before.
+pub(crate) fn next_frame_deadline() -> Option<Instant> {
+	DEADLINE.get()
+}
+
+/// Timing of an animation frame, returned by [`use_tick`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tick {
+	/// Time since the previous frame, capped at 100 ms.
+	pub delta: Duration,
+	/// Time since the tick became active.
+	pub elapsed: Duration,
+}
+
+/// Renders the component on every frame while `active` is `true`, in sync with the display.
+///
+/// Use it for animations that are driven by time instead of state changes. When no component is
+/// ticking, the window only redraws when something happens.
+///
+/// # Example
+/// ```rust,no_run
+/// # use hyprui::use_tick;
+/// # let loading = true;
+/// let tick = use_tick(loading);
+/// let rotation = tick.elapsed.as_secs_f32() * 360.;
+/// ```
+pub fn use_tick(active: bool) -> Tick {
+	let started = use_ref(None::<Instant>);
+	if !active {
+		*started.borrow_mut() = None;
+		return Tick::default();
+	}
+	SUBSCRIBERS.set(SUBSCRIBERS.get() + 1);
+	let now = FRAME_START.get().unwrap_or_else(now);
+	let mut started = started.borrow_mut();
+	let Some(start) = *started else {
+		// The first frame of the animation
+		*started = Some(now);
+		return Tick::default();
+	};
+	Tick {
+		delta: FRAME_DELTA.get(),
+		elapsed: now - start,
+	}
+}
+
+/// Calls `callback` every `period` while the component is mounted, waking the window up when it is due.
+///
+/// The callback runs while the component is built, on the first frame after each period ends; periods missed
+/// while the app was busy are skipped rather than caught up. The first call is one period after the first render,
+/// or after `period` changes, and a zero period pauses the interval.
+///
+/// # Example
+/// ```rust,no_run
+/// # use hyprui::{use_interval, use_state};
+/// # use std::time::Duration;
+/// let (seconds, set_seconds) = use_state(0);
+/// use_interval(Duration::from_secs(1), || set_seconds(seconds + 1));
+/// ```
+pub fn use_interval(period: Duration, callback: impl FnOnce()) {
+	// The period and when it next ends
+	let due = use_ref(None::<(Duration, Instant)>);
+	if period.is_zero() {
+		*due.borrow_mut() = None;
+		return;
+	}
+	let now = FRAME_START.get().unwrap_or_else(now);
+	let next = match *due.borrow() {
+		Some((last_period, next)) if last_period == period => next,
+		_ => now + period,
+	};
+	let fired = now >= next;
+	let next = if fired {
+		// The next end of a period after now, computed in nanoseconds so no count of missed periods overflows
+		let late = (now - next).as_nanos() % period.as_nanos();
+		let late = Duration::new((late / 1_000_000_000) as u64, (late % 1_000_000_000) as u32);
+		now + (period - late)
+	} else {
+		next
+	};
+	*due.borrow_mut() = Some((period, next));
+	request_frame_at(next);
+	// Called last, so the callback can use hooks of the component
+	if fired {
+		callback();
+	}
+}
+
+/// Calls `callback` once, `delay` after the first render of the component, like hiding a toast after a while.
+///
+/// Changing `delay` starts waiting again, even if the callback was already called.
+pub fn use_timeout(delay: Duration, callback: impl FnOnce()) {
+	// The delay and when it ends, or `None` once the callback was called
+	let due = use_ref(None::<(Duration, Option<Instant>)>);
+	let now = FRAME_START.get().unwrap_or_else(now);
+	let deadline = match *due.borrow() {
+		Some((last_delay, deadline)) if last_delay == delay => deadline,
+		_ => Some(now + delay),
+	};
+	let Some(deadline) = deadline else {
+		return;
+	};
+	if now < deadline {
+		*due.borrow_mut() = Some((delay, Some(deadline)));
+		request_frame_at(deadline);
+		return;
+	}
+	*due.borrow_mut() = Some((delay, None));
+	callback();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{begin_component, end_component};
+
+	fn frame(active: bool) -> Tick {
+		begin_frame();
+		begin_component("ticking");
+		let tick = use_tick(active);
+		end_component();
+		tick
+	}
+
+	#[test]
+	fn test_ticks_only_while_active() {
+		assert_eq!(frame(true), Tick::default());
+		assert!(wants_next_frame());
+		advance_clock(Duration::from_millis(5));
+		let tick = frame(true);
+		assert!(tick.elapsed >= Duration::from_millis(5));
+		assert_eq!(tick.delta, tick.elapsed);
+		frame(false);
+		assert!(!wants_next_frame());
+		assert_eq!(frame(true).elapsed, Duration::ZERO);
+	}
+
+	/// Runs a frame of a component with an interval and a timeout, returning which of them fired.
+	fn timers_frame(period: Duration, delay: Duration) -> (bool, bool) {
+		let mut fired = (false, false);
+		begin_frame();
+		begin_component("timers");
+		use_interval(period, || fired.0 = true);
+		use_timeout(delay, || fired.1 = true);
+		end_component();
+		fired
+	}
+
+	#[test]
+	fn test_interval_and_timeout_fire_when_due() {
+		let period = Duration::from_millis(10);
+		let delay = Duration::from_millis(25);
+		assert_eq!(timers_frame(period, delay), (false, false));
+		let deadline = next_frame_deadline().unwrap();
+		assert!(deadline <= now() + period);
+
+		advance_clock(deadline.saturating_duration_since(now()));
+		assert_eq!(timers_frame(period, delay), (true, false));
+		assert_eq!(timers_frame(period, delay), (false, false));
+
+		advance_clock(delay);
+		assert_eq!(timers_frame(period, delay), (true, true));
+		// The timeout only fires once, the paused interval no longer wakes the window up
+		assert_eq!(timers_frame(Duration::ZERO, delay), (false, false));
+		assert_eq!(next_frame_deadline(), None);
+	}
+
+	#[test]
+	fn test_interval_skips_missed_periods() {
+		let period = Duration::from_millis(10);
+		let delay = Duration::from_secs(60);
+		assert_eq!(timers_frame(period, delay), (false, false));
+		let due = next_frame_deadline().unwrap();
+		// Five periods and a half go by before the next frame, which only fires once
+		advance_clock(due.saturating_duration_since(now()) + period * 4 + period / 2);
+		assert_eq!(timers_frame(period, delay), (true, false));
+		assert_eq!(timers_frame(period, delay), (false, false));
+		let next = next_frame_deadline().unwrap();
+		assert_eq!(next - due, period * 5);
+
+		// More periods than a u32 counts
+		let period = Duration::from_nanos(3);
+		assert_eq!(timers_frame(period, delay), (false, false));
+		let due = next_frame_deadline().unwrap();
+		advance_clock(Duration::from_secs(20));
+		assert_eq!(timers_frame(period, delay), (true, false));
+		let start = FRAME_START.get().unwrap();
+		let next = next_frame_deadline().unwrap();
+		assert!(next > start && next <= start + period);
+		assert_eq!((next - due).as_nanos() % period.as_nanos(), 0);
+	}
+}
@@ -0,0 +1,125 @@
+//! Fuzzy matching of search queries, like `ffx` finding `Firefox`, ranked by how well the text matches.
+
+/// Score of every character of the query found in the text.
+const MATCH: i32 = 16;
+/// Added when a character follows the previous one of the query in the text.
+const CONSECUTIVE: i32 = 8;
+/// Added when a character starts a word of the text, like the `T` of `Terminal` or of `GnomeTerminal`.
+const WORD_START: i32 = 10;
+/// Removed for every character of the text skipped between the first and last matched ones.
+const GAP: i32 = 1;
+
+/// Where a query was found in a text, see [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+	/// Higher for better matches: consecutive characters and the starts of words count more.
+	pub score: i32,
+	/// The indices of the characters of the text that matched, in order, counted in `char`s.
+	pub indices: Vec<usize>,
+}
+
+/// Returns `true` if the character at `index` of `chars` starts a word.
+fn is_word_start(chars: &[char], index: usize) -> bool {
+	let Some(previous) = index.checked_sub(1).map(|i| chars[i]) else {
+		return true;
+	};
+	let current = chars[index];
+	!previous.is_alphanumeric() && current.is_alphanumeric()
+		|| previous.is_lowercase() && current.is_uppercase()
+		|| !previous.is_numeric() && current.is_numeric()
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+	a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Finds the characters of `query` in `text`, in order but not necessarily next to each other, ignoring case.
+///
+/// Returns `None` if a character of the query isn't in the text. Among the places the query could match, the
+/// shortest one ending first is picked. An empty query matches every text with a score of `0`.
+///
+/// ```rust
+/// # use hyprui::fuzzy_match;
+/// let firefox = fuzzy_match("ffx", "Firefox").unwrap();
+/// assert_eq!(firefox.indices, vec![0, 4, 6]);
+/// assert!(fuzzy_match("fire", "Firefox").unwrap().score > firefox.score);
+/// ```
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+	let query = query
+		.chars()
+		.filter(|c| !c.is_whitespace())
+		.collect::<Vec<_>>();
+	if query.is_empty() {
+		return Some(FuzzyMatch {
+			score: 0,
+			indices: Vec::new(),
+		});
+	}
+	let chars = text.chars().collect::<Vec<_>>();
+	// Finds where the earliest match ends...
+	let mut next = 0;
+	let mut end = None;
+	for (index, &c) in chars.iter().enumerate() {
+		if eq_ignore_case(c, query[next]) {
+			next += 1;
+			if next == query.len() {
+				end = Some(index);
+				break;
+			}
+		}
+	}
+	let end = end?;
+	// ...then goes back from there to the latest start, for the shortest match
+	let mut indices = vec![0; query.len()];
+	let mut remaining = query.len();
+	for index in (0..=end).rev() {
+		if eq_ignore_case(chars[index], query[remaining - 1]) {
+			remaining -= 1;
+			indices[remaining] = index;
+			if remaining == 0 {
+				break;
+			}
+		}
+	}
+
+	let mut score = 0;
+	for (i, &index) in indices.iter().enumerate() {
+		score += MATCH;
+		if i > 0 && indices[i - 1] + 1 == index {
+			score += CONSECUTIVE;
+		}
+		if is_word_start(&chars, index) {
+			score += WORD_START;
+		}
+	}
+	let span = indices[indices.len() - 1] - indices[0] + 1;
+	score -= (span - indices.len()) as i32 * GAP;
+	Some(FuzzyMatch { score, indices })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_characters_are_found_in_order() {
+		let found = fuzzy_match("frfx", "Firefox").unwrap();
+		assert_eq!(found.indices, vec![0, 2, 4, 6]);
+		assert_eq!(
+			fuzzy_match("FIRE", "firefox").unwrap().indices,
+			vec![0, 1, 2, 3]
+		);
+		assert_eq!(fuzzy_match("xf", "Firefox"), None);
+		assert_eq!(fuzzy_match("", "Firefox").unwrap().score, 0);
+	}
+
+	#[test]
+	fn test_better_matches_score_higher() {
+		let score = |query, text| fuzzy_match(query, text).unwrap().score;
+		// Consecutive characters
+		assert!(score("code", "Visual Studio Code") > score("code", "Color Designer"));
+		// Starts of words
+		assert!(score("gt", "GnomeTerminal") > score("gt", "Getting"));
+		assert!(score("vsc", "Visual Studio Code") > score("vsc", "Visicalc"));
+	}
+}
@@ -1,16 +1,67 @@
-use std::any::Any;
-use std::cell::RefCell;
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::marker::PhantomData;
 use std::rc::Rc;
 
 use crate::GlobalClosure;
 
+#[cfg(feature = "pulseaudio")]
+mod audio;
+#[cfg(feature = "dbus")]
+mod dbus;
+mod future;
+mod hyprland;
+#[cfg(feature = "dbus")]
+mod power;
+mod screen_capture;
+mod system;
+#[cfg(feature = "pulseaudio")]
+pub use audio::{AudioDevice, AudioVolume, VolumeState, use_audio_volume};
+#[cfg(feature = "dbus")]
+pub use dbus::{Bus, DbusObject, use_dbus_property, use_dbus_signal};
+pub use future::{FutureState, use_future};
+pub(crate) use future::{spawn, wait_for_futures};
+pub use hyprland::{
+	HyprWindow, HyprWorkspace, HyprlandState, hyprctl, hyprland_dispatch, use_hyprland,
+	use_window_thumbnail,
+};
+#[cfg(feature = "dbus")]
+pub use power::{Battery, ChargingState, PowerProfile, use_battery, use_power_profile};
+pub use screen_capture::{CaptureSource, use_screen_capture};
+pub use system::{
+	CpuUsage, DiskThroughput, MemoryUsage, NetworkThroughput, use_cpu_usage, use_disk_throughput,
+	use_memory, use_network_throughput,
+};
+
 thread_local! {
 	pub(crate) static HOOK_PATH: RefCell<Vec<(usize, String)>> = RefCell::new(Vec::new());
 	pub(crate) static HOOK_INDEX: RefCell<usize> = RefCell::new(0);
-	pub(crate) static HOOK_STATES: RefCell<HashMap<HookKey, Box<dyn Any>>> = RefCell::new(HashMap::new());
+	pub(crate) static HOOK_STATES: RefCell<HashMap<HookKey, HookState>> = RefCell::new(HashMap::new());
 	pub(crate) static HOOK_VISITED_STATES: RefCell<HashSet<HookKey>> = RefCell::new(HashSet::new());
+	/// The values provided with [`provide_context`], with the depth of the component providing them.
+	static CONTEXTS: RefCell<Vec<(usize, Rc<dyn Any>)>> = const { RefCell::new(Vec::new()) };
+	/// Set by [`build_roots`] while the root components are built.
+	static BUILDING_ROOTS: Cell<bool> = const { Cell::new(false) };
+	static HOOK_DEBUG_FORMATTERS: RefCell<HashMap<TypeId, DebugFormatter>> = RefCell::new(default_debug_formatters());
+}
+
+type DebugFormatter = fn(&dyn Any) -> String;
+
+pub(crate) struct HookState {
+	value: Box<dyn Any>,
+	type_name: &'static str,
+}
+
+impl HookState {
+	fn new<T: 'static>(value: T) -> Self {
+		Self {
+			value: Box::new(value),
+			type_name: std::any::type_name::<T>(),
+		}
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -19,6 +70,20 @@ struct HookKey {
 	hook_index: usize,
 }
 
+/// Returns the key of the next hook of the component being rendered, and keeps its state alive this frame.
+fn next_hook_key() -> HookKey {
+	let hook_index = HOOK_INDEX.with_borrow_mut(|index| {
+		*index += 1;
+		*index - 1
+	});
+	let key = HookKey {
+		path: HOOK_PATH.with_borrow(Clone::clone),
+		hook_index,
+	};
+	HOOK_VISITED_STATES.with_borrow_mut(|visited| visited.insert(key.clone()));
+	key
+}
+
 /// Must be called at the start of every component render.
 /// This sets up the internal path and hook index for the current component.
 /// Should be paired with [`end_component`] at the end of the component render.
@@ -40,20 +105,60 @@ pub fn begin_component(key: impl Into<String>) {
 pub fn end_component() {
 	HOOK_PATH.with(|path| {
 		path.borrow_mut().pop();
+		// The contexts provided by the component are only seen by the components it renders
+		let depth = path.borrow().len();
+		CONTEXTS.with_borrow_mut(|contexts| contexts.retain(|(provided_at, _)| *provided_at <= depth));
 		if path.borrow().is_empty() {
-			// Garbage collect states that were not visited this frame
-			HOOK_STATES.with(|states| {
-				HOOK_VISITED_STATES.with(|visited| {
-					let mut states = states.borrow_mut();
-					let visited = visited.borrow();
-					states.retain(|k, _| visited.contains(k));
-				});
-			});
-			HOOK_VISITED_STATES.with(|visited| visited.borrow_mut().clear());
+			CONTEXTS.with_borrow_mut(Vec::clear);
+			if !BUILDING_ROOTS.get() {
+				collect_unmounted_states();
+			}
 		}
 	});
 }
 
+/// Garbage collects the states that were not visited this frame.
+fn collect_unmounted_states() {
+	let unmounted = HOOK_STATES.with_borrow_mut(|states| {
+		HOOK_VISITED_STATES.with_borrow_mut(|visited| {
+			let unmounted = states
+				.extract_if(|k, _| !visited.contains(k))
+				.collect::<Vec<_>>();
+			visited.clear();
+			unmounted
+		})
+	});
+	// Dropped once the states are no longer borrowed, so `on_unmount` callbacks can use state setters
+	drop(unmounted);
+}
+
+/// Builds several root components, like the app and the [on-screen display](crate::show_osd), garbage collecting
+/// the states once all of them are built rather than at the end of each one.
+pub(crate) fn build_roots<R>(build: impl FnOnce() -> R) -> R {
+	BUILDING_ROOTS.set(true);
+	let roots = build();
+	BUILDING_ROOTS.set(false);
+	collect_unmounted_states();
+	roots
+}
+
+/// Returns the keys of the components currently being rendered, from the root.
+pub(crate) fn current_component_path() -> Vec<String> {
+	HOOK_PATH.with_borrow(|path| path.iter().map(|(_, key)| key.clone()).collect())
+}
+
+/// Resets the component stack after a panic interrupted a render.
+///
+/// Every existing state is kept alive for this frame, so the app keeps its state once the panic is fixed.
+pub(crate) fn recover_from_panic() {
+	HOOK_PATH.with_borrow_mut(|path| path.clear());
+	HOOK_INDEX.with(|idx| *idx.borrow_mut() = 0);
+	CONTEXTS.with_borrow_mut(Vec::clear);
+	HOOK_STATES.with_borrow(|states| {
+		HOOK_VISITED_STATES.with_borrow_mut(|visited| visited.extend(states.keys().cloned()));
+	});
+}
+
 pub type State<T> = (T, Box<dyn Fn(T)>);
 
 pub type Entity<T> = (Rc<RefCell<T>>, Box<dyn Fn(&dyn Fn(&mut T))>);
@@ -68,26 +173,18 @@ pub type Entity<T> = (Rc<RefCell<T>>, Box<dyn Fn(&dyn Fn(&mut T))>);
 /// let (count, set_count) = use_state(0);
 /// set_count(count + 1);
 /// ```
+///
+/// The value is the one of the render, so calling the setter twice with `count + 1` before the next render only
+/// adds one. Use [`use_state_handle`] when the new value depends on the current one.
 pub fn use_state<T: Clone + 'static>(initial: T) -> State<T> {
-	let path = HOOK_PATH.with(|p| p.borrow().clone());
-	let idx = HOOK_INDEX.with(|i| {
-		let v = *i.borrow();
-		*i.borrow_mut() += 1;
-		v
-	});
-	let key = HookKey {
-		path,
-		hook_index: idx,
-	};
-	HOOK_VISITED_STATES.with(|visited| {
-		visited.borrow_mut().insert(key.clone());
-	});
+	let key = next_hook_key();
 	let current_value = HOOK_STATES.with(|states| {
 		let mut states = states.borrow_mut();
 
 		states
 			.entry(key.clone())
-			.or_insert_with(|| Box::new(initial.clone()))
+			.or_insert_with(|| HookState::new(initial.clone()))
+			.value
 			.downcast_ref::<T>()
 			.unwrap()
 			.clone()
@@ -96,7 +193,7 @@ pub fn use_state<T: Clone + 'static>(initial: T) -> State<T> {
 	let setter = move |new_value: T| {
 		HOOK_STATES.with(|states| {
 			let mut states = states.borrow_mut();
-			states.insert(key.clone(), Box::new(new_value));
+			states.insert(key.clone(), HookState::new(new_value));
 		});
 
 		crate::REQUEST_REDRAW.call();
@@ -105,6 +202,143 @@ pub fn use_state<T: Clone + 'static>(initial: T) -> State<T> {
 	(current_value, Box::new(setter))
 }
 
+/// Like [`use_state`], but the setter can be sent to other threads.
+///
+/// Values sent with [`StateSender::send`] are applied on the UI thread through the [`Dispatcher`](crate::Dispatcher),
+/// and dropped if the component was unmounted in the meantime.
+pub fn use_state_sender<T: Clone + Send + 'static>(initial: T) -> (T, StateSender<T>) {
+	let key = next_hook_key();
+	let current_value = HOOK_STATES.with_borrow_mut(|states| {
+		states
+			.entry(key.clone())
+			.or_insert_with(|| HookState::new(initial))
+			.value
+			.downcast_ref::<T>()
+			.unwrap()
+			.clone()
+	});
+	(
+		current_value,
+		StateSender {
+			key,
+			dispatcher: crate::Dispatcher::new(),
+			_value: PhantomData,
+		},
+	)
+}
+
+/// The thread-safe setter of [`use_state_sender`].
+pub struct StateSender<T> {
+	key: HookKey,
+	dispatcher: crate::Dispatcher,
+	_value: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for StateSender<T> {
+	fn clone(&self) -> Self {
+		Self {
+			key: self.key.clone(),
+			dispatcher: self.dispatcher.clone(),
+			_value: PhantomData,
+		}
+	}
+}
+
+impl<T: Send + 'static> StateSender<T> {
+	/// Sets the state from any thread and re-renders the window.
+	pub fn send(&self, value: T) {
+		let sender = self.clone();
+		self.dispatcher.dispatch(move || {
+			sender.store(value);
+			crate::REQUEST_REDRAW.call();
+		});
+	}
+
+	/// Replaces the state on the UI thread, without re-rendering. Does nothing if the component was unmounted.
+	fn store(&self, value: T) {
+		HOOK_STATES.with_borrow_mut(|states| {
+			if let Some(state) = states.get_mut(&self.key) {
+				*state = HookState::new(value);
+			}
+		});
+	}
+}
+
+/// Like [`use_state`], but with a [`StateHandle`] that can compute the new value from the latest one.
+///
+/// ```rust,no_run
+/// # use hyprui::use_state_handle;
+/// let (count, counter) = use_state_handle(0);
+/// // Adds two, even though both updates happen before the next render
+/// counter.update(|count| count + 1);
+/// counter.update(|count| count + 1);
+/// ```
+pub fn use_state_handle<T: Clone + 'static>(initial: T) -> (T, StateHandle<T>) {
+	let key = next_hook_key();
+	let current_value = HOOK_STATES.with_borrow_mut(|states| {
+		states
+			.entry(key.clone())
+			.or_insert_with(|| HookState::new(initial))
+			.value
+			.downcast_ref::<T>()
+			.unwrap()
+			.clone()
+	});
+	(
+		current_value,
+		StateHandle {
+			key,
+			_value: PhantomData,
+		},
+	)
+}
+
+/// The setter of [`use_state_handle`], reading the state when called rather than when rendered.
+pub struct StateHandle<T> {
+	key: HookKey,
+	_value: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for StateHandle<T> {
+	fn clone(&self) -> Self {
+		Self {
+			key: self.key.clone(),
+			_value: PhantomData,
+		}
+	}
+}
+
+impl<T: Clone + 'static> StateHandle<T> {
+	/// Returns the latest value of the state, including the changes made since the render. `None` once the
+	/// component was unmounted.
+	pub fn get(&self) -> Option<T> {
+		HOOK_STATES.with_borrow(|states| {
+			states
+				.get(&self.key)
+				.and_then(|state| state.value.downcast_ref::<T>())
+				.cloned()
+		})
+	}
+
+	/// Sets the state and re-renders the window, like the setter of [`use_state`].
+	pub fn set(&self, value: T) {
+		HOOK_STATES.with_borrow_mut(|states| {
+			states.insert(self.key.clone(), HookState::new(value));
+		});
+		crate::REQUEST_REDRAW.call();
+	}
+
+	/// Replaces the state with `f` applied to its latest value, and re-renders the window. Does nothing if the
+	/// component was unmounted.
+	pub fn update(&self, f: impl FnOnce(&T) -> T) {
+		// `f` runs without the states borrowed, so it can use other state setters
+		let Some(current) = self.get() else {
+			return;
+		};
+		self.set(f(&current));
+	}
+}
+
 pub fn use_entity<T: 'static>(initial: impl FnOnce() -> T) -> Entity<T> {
 	let value = use_memo(|| RefCell::new(initial()), ());
 	let setter_rc = value.clone();
@@ -116,11 +350,73 @@ pub fn use_entity<T: 'static>(initial: impl FnOnce() -> T) -> Entity<T> {
 	(value, Box::new(setter))
 }
 
-/// Runs side effects when the `deps` hash changes
+/// Makes `value` available to [`use_context`] in the components rendered by the current one, without passing it
+/// through their props, like the theme or the services of the app.
+///
+/// Only the components created after the call see the value, and a component providing a value of the same type
+/// hides it from the components it renders. Unlike the other hooks, it can be called conditionally.
+///
+/// ```rust,ignore
+/// fn app(_: ()) -> Box<dyn Element> {
+///     provide_context(Config::load());
+///     Box::new(Component::new(settings_page, ()))
+/// }
+///
+/// fn settings_page(_: ()) -> Box<dyn Element> {
+///     let config = use_context::<Config>().expect("provided by the app");
+///     // ...
+/// }
+/// ```
+pub fn provide_context<T: 'static>(value: T) {
+	let depth = HOOK_PATH.with_borrow(Vec::len);
+	CONTEXTS.with_borrow_mut(|contexts| contexts.push((depth, Rc::new(value))));
+}
+
+/// Returns the value of type `T` provided by the nearest enclosing component with [`provide_context`], or `None`
+/// if no component provides one.
+pub fn use_context<T: 'static>() -> Option<Rc<T>> {
+	CONTEXTS.with_borrow(|contexts| {
+		contexts
+			.iter()
+			.rev()
+			.find_map(|(_, value)| value.clone().downcast::<T>().ok())
+	})
+}
+
+/// Undoes what an effect did, returned by the effect given to [`use_effect`].
+pub type EffectCleanup = Option<Box<dyn FnOnce()>>;
+
+/// Runs side effects when the `deps` hash changes.
+///
+/// The effect can return a cleanup, which runs before the effect runs again with new deps and when the component
+/// is unmounted, to stop the timers, subscriptions and threads the effect started.
+///
+/// # Example
+/// ```rust,no_run
+/// # use hyprui::use_effect;
+/// use std::sync::{
+///     Arc,
+///     atomic::{AtomicBool, Ordering},
+/// };
+/// let path = String::from("/var/log/syslog");
+/// use_effect(
+///     move || {
+///         let stopped = Arc::new(AtomicBool::new(false));
+///         let watcher_stopped = stopped.clone();
+///         std::thread::spawn(move || {
+///             while !watcher_stopped.load(Ordering::Relaxed) {
+///                 // Watch the file at `path`
+///             }
+///         });
+///         Some(Box::new(move || stopped.store(true, Ordering::Relaxed)))
+///     },
+///     &path,
+/// );
+/// ```
 pub fn use_effect<D, F>(effect: F, deps: &D)
 where
 	D: Hash + 'static,
-	F: FnOnce() + 'static,
+	F: FnOnce() -> EffectCleanup + 'static,
 {
 	let hash = {
 		let mut hasher = DefaultHasher::new();
@@ -128,35 +424,28 @@ where
 		hasher.finish()
 	};
 
-	let (last_hash, set_last_hash) = crate::hooks::use_state(None);
-
-	if last_hash != Some(hash) {
-		effect();
-		set_last_hash(Some(hash));
+	// The cleanup runs when dropped, so garbage collecting the state of the component runs it
+	let state = use_ref((None::<u64>, UnmountCallback(None)));
+	if state.borrow().0 == Some(hash) {
+		return;
+	}
+	// Not borrowed while the cleanup and the effect run, as they may use hooks of the component
+	let cleanup = state.borrow_mut().1.0.take();
+	if let Some(cleanup) = cleanup {
+		cleanup();
 	}
+	let cleanup = effect();
+	*state.borrow_mut() = (Some(hash), UnmountCallback(cleanup));
 }
 
 pub fn use_ref<T: 'static>(initial: T) -> Rc<RefCell<T>> {
-	let path = HOOK_PATH.with(|p| p.borrow().clone());
-	let idx = HOOK_INDEX.with(|i| {
-		let v = *i.borrow();
-		*i.borrow_mut() += 1;
-		v
-	});
-	let key = HookKey {
-		path,
-		hook_index: idx,
-	};
-
-	HOOK_VISITED_STATES.with(|visited| {
-		visited.borrow_mut().insert(key.clone());
-	});
+	let key = next_hook_key();
 	HOOK_STATES.with(|states| {
 		let mut states = states.borrow_mut();
 		let entry = states
 			.entry(key.clone())
-			.or_insert_with(|| Box::new(Rc::new(RefCell::new(initial))));
-		entry.downcast_ref::<Rc<RefCell<T>>>().unwrap().clone()
+			.or_insert_with(|| HookState::new(Rc::new(RefCell::new(initial))));
+		entry.value.downcast_ref::<Rc<RefCell<T>>>().unwrap().clone()
 	})
 }
 
@@ -181,6 +470,118 @@ where
 	}
 	memoized_value.borrow().as_ref().unwrap().1.clone()
 }
+/// Runs `callback` the first time the component is rendered.
+///
+/// Like the other hooks, it runs again if the component is removed and added back later.
+pub fn on_mount(callback: impl FnOnce()) {
+	let mounted = use_ref(false);
+	if !mounted.replace(true) {
+		callback();
+	}
+}
+
+/// Calls the callback when dropped, so it runs when its hook state is garbage collected.
+struct UnmountCallback(Option<Box<dyn FnOnce()>>);
+
+impl Drop for UnmountCallback {
+	fn drop(&mut self) {
+		if let Some(callback) = self.0.take() {
+			callback();
+		}
+	}
+}
+
+/// Runs `callback` when the component is no longer rendered, to stop timers, subscriptions
+/// and threads started by the component.
+///
+/// The callback from the latest render is the one that runs, so it sees up to date values.
+///
+/// # Example
+/// ```rust,no_run
+/// # use hyprui::{on_mount, on_unmount};
+/// on_mount(|| println!("mounted"));
+/// on_unmount(|| println!("unmounted"));
+/// ```
+pub fn on_unmount(callback: impl FnOnce() + 'static) {
+	let unmount = use_ref(UnmountCallback(None));
+	unmount.borrow_mut().0 = Some(Box::new(callback));
+}
+
+fn debug_formatter<T: Debug + 'static>(value: &dyn Any) -> String {
+	format!("{:?}", value.downcast_ref::<T>().unwrap())
+}
+
+fn default_debug_formatters() -> HashMap<TypeId, DebugFormatter> {
+	let mut formatters = HashMap::new();
+	macro_rules! register {
+		($($ty:ty),*) => {
+			$(
+				formatters.insert(TypeId::of::<$ty>(), debug_formatter::<$ty> as DebugFormatter);
+				formatters.insert(TypeId::of::<Option<$ty>>(), debug_formatter::<Option<$ty>>);
+			)*
+		};
+	}
+	register!(bool, char, i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, f32, f64, String);
+	formatters
+}
+
+/// Makes the values of type `T` (and `Rc<RefCell<T>>`, as stored by [`use_ref`]) readable in [`hook_states`] and the inspector.
+///
+/// Primitive types, `String` and their `Option`s are registered by default.
+pub fn register_hook_debug<T: Debug + 'static>() {
+	HOOK_DEBUG_FORMATTERS.with_borrow_mut(|formatters| {
+		formatters.insert(TypeId::of::<T>(), debug_formatter::<T>);
+		formatters.insert(TypeId::of::<Rc<RefCell<T>>>(), |value| {
+			format!("{:?}", value.downcast_ref::<Rc<RefCell<T>>>().unwrap().borrow())
+		});
+	});
+}
+
+/// A live hook state, as returned by [`hook_states`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookInfo {
+	/// The path of the component that owns the state, from the root, as `(position among siblings, component key)`.
+	pub path: Vec<(usize, String)>,
+	/// The call order of the hook inside its component.
+	pub hook_index: usize,
+	pub type_name: &'static str,
+	/// The `Debug` rendering of the value, if its type was registered with [`register_hook_debug`].
+	pub value: Option<String>,
+}
+
+/// Lists every hook state kept alive by the last frame, sorted by component path.
+pub fn hook_states() -> Vec<HookInfo> {
+	let mut infos = HOOK_STATES.with_borrow(|states| {
+		HOOK_DEBUG_FORMATTERS.with_borrow(|formatters| {
+			states
+				.iter()
+				.map(|(key, state)| HookInfo {
+					path: key.path.clone(),
+					hook_index: key.hook_index,
+					type_name: state.type_name,
+					value: formatters
+						.get(&(*state.value).type_id())
+						.map(|format| format(&*state.value)),
+				})
+				.collect::<Vec<_>>()
+		})
+	});
+	infos.sort_by(|a, b| (&a.path, a.hook_index).cmp(&(&b.path, b.hook_index)));
+	infos
+}
+
+/// Drops the state of the component at `path` (as found in [`HookInfo::path`]) and of all its children,
+/// so they start over from their initial values on the next frame.
+pub fn reset_component_state(path: &[(usize, String)]) {
+	let removed = HOOK_STATES.with_borrow_mut(|states| {
+		states
+			.extract_if(|key, _| key.path.starts_with(path))
+			.collect::<Vec<_>>()
+	});
+	drop(removed);
+	crate::REQUEST_REDRAW.call();
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -288,5 +689,257 @@ mod tests {
 			assert_eq!(a2, 111);
 			assert_eq!(b2, 222);
 		}
+
+		#[test]
+		fn test_updates_use_the_latest_value() {
+			reset_all();
+
+			begin_component("component-a");
+			let (count, counter) = use_state_handle(0);
+			end_component();
+			assert_eq!(count, 0);
+
+			counter.update(|count| count + 1);
+			counter.clone().update(|count| count + 1);
+			assert_eq!(counter.get(), Some(2));
+
+			begin_component("component-a");
+			let (count, _) = use_state_handle(0);
+			end_component();
+			assert_eq!(count, 2);
+		}
+
+		#[test]
+		fn test_state_sender_from_another_thread() {
+			reset_all();
+
+			begin_component("component-a");
+			let (value, send_value) = use_state_sender(String::from("loading"));
+			end_component();
+			assert_eq!(value, "loading");
+
+			std::thread::spawn(move || send_value.send(String::from("done")))
+				.join()
+				.unwrap();
+			crate::dispatcher::run_pending_tasks();
+
+			begin_component("component-a");
+			let (value, _) = use_state_sender(String::from("loading"));
+			end_component();
+			assert_eq!(value, "done");
+		}
+	}
+	mod use_context {
+		use super::*;
+
+		#[derive(Debug, PartialEq)]
+		struct Theme(&'static str);
+
+		#[test]
+		fn test_nearest_provider_is_used() {
+			reset_all();
+			let mut seen = Vec::new();
+
+			begin_component("app");
+			provide_context(Theme("light"));
+			begin_component("sidebar");
+			provide_context(Theme("dark"));
+			begin_component("button");
+			seen.push(use_context::<Theme>());
+			end_component();
+			end_component();
+			begin_component("page");
+			seen.push(use_context::<Theme>());
+			assert_eq!(use_context::<String>(), None);
+			end_component();
+			end_component();
+
+			// Contexts don't outlive the frame
+			begin_component("app");
+			seen.push(use_context::<Theme>());
+			end_component();
+
+			assert_eq!(
+				seen,
+				vec![
+					Some(Rc::new(Theme("dark"))),
+					Some(Rc::new(Theme("light"))),
+					None
+				]
+			);
+		}
+	}
+	mod use_future {
+		use std::{
+			sync::{Arc, Mutex},
+			time::Duration,
+		};
+
+		use super::*;
+
+		fn render(query: &'static str) -> FutureState<usize, String> {
+			begin_component("search");
+			let state = use_future(
+				move || async move {
+					if query.is_empty() {
+						Err(String::from("empty query"))
+					} else {
+						Ok(query.len())
+					}
+				},
+				&query,
+			);
+			end_component();
+			state
+		}
+
+		/// Renders once the future resolved and its result was handed to the UI thread.
+		fn wait(query: &'static str) -> FutureState<usize, String> {
+			assert!(crate::hooks::wait_for_futures(Duration::from_secs(5)));
+			crate::dispatcher::run_pending_tasks();
+			render(query)
+		}
+
+		#[test]
+		fn test_future_resolves_and_restarts_on_deps_change() {
+			reset_all();
+			assert_eq!(render("dock"), FutureState::Pending);
+			assert_eq!(wait("dock"), FutureState::Ready(4));
+
+			assert_eq!(render("launcher"), FutureState::Pending);
+			assert_eq!(wait("launcher"), FutureState::Ready(8));
+
+			assert_eq!(render(""), FutureState::Pending);
+			assert_eq!(wait(""), FutureState::Error(String::from("empty query")));
+		}
+
+		fn mount<Fut>(task: impl FnOnce() -> Fut + 'static) -> FutureState<usize, String>
+		where
+			Fut: std::future::Future<Output = Result<usize, String>> + Send + 'static,
+		{
+			begin_component("search");
+			let state = use_future(task, &());
+			end_component();
+			state
+		}
+
+		#[test]
+		fn test_result_is_dropped_once_unmounted() {
+			reset_all();
+			let gate = Arc::new(Mutex::new(()));
+			let closed = gate.lock().unwrap();
+			assert_eq!(
+				mount(move || async move {
+					drop(gate.lock());
+					Ok(1)
+				}),
+				FutureState::Pending
+			);
+			// Mounted again before the first future resolves
+			begin_component("other");
+			end_component();
+			assert_eq!(mount(|| async { Ok(2) }), FutureState::Pending);
+			drop(closed);
+			assert!(crate::hooks::wait_for_futures(Duration::from_secs(5)));
+			crate::dispatcher::run_pending_tasks();
+			assert_eq!(mount(|| async { Ok(3) }), FutureState::Ready(2));
+		}
+	}
+	mod devtools {
+		use super::*;
+
+		#[test]
+		fn test_hook_states_lists_values() {
+			reset_all();
+			begin_component("root");
+			let _ = use_state(5);
+			use_ref(vec![1]);
+			end_component();
+
+			let states = hook_states();
+			assert_eq!(states.len(), 2);
+			assert_eq!(states[0].path, vec![(0, "root".to_string())]);
+			assert_eq!(states[0].value.as_deref(), Some("5"));
+			assert_eq!(states[1].value, None);
+
+			register_hook_debug::<Vec<i32>>();
+			assert_eq!(hook_states()[1].value.as_deref(), Some("[1]"));
+		}
+
+		#[test]
+		fn test_mount_and_unmount_callbacks() {
+			reset_all();
+			let events = Rc::new(RefCell::new(Vec::new()));
+			let frame = |with_child: bool, value: u32| {
+				begin_component("root");
+				if with_child {
+					begin_component("child");
+					let events_mount = events.clone();
+					on_mount(move || events_mount.borrow_mut().push(format!("mount {value}")));
+					let events_unmount = events.clone();
+					on_unmount(move || events_unmount.borrow_mut().push(format!("unmount {value}")));
+					end_component();
+				}
+				end_component();
+			};
+			frame(true, 1);
+			frame(true, 2);
+			assert_eq!(*events.borrow(), ["mount 1"]);
+			frame(false, 3);
+			assert_eq!(*events.borrow(), ["mount 1", "unmount 2"]);
+			frame(true, 4);
+			assert_eq!(*events.borrow(), ["mount 1", "unmount 2", "mount 4"]);
+		}
+
+		#[test]
+		fn test_effect_cleanup_runs_on_change_and_unmount() {
+			reset_all();
+			let events = Rc::new(RefCell::new(Vec::new()));
+			let frame = |with_child: bool, interval: u32| {
+				begin_component("root");
+				if with_child {
+					begin_component("child");
+					let events = events.clone();
+					use_effect(
+						move || {
+							events.borrow_mut().push(format!("start {interval}"));
+							Some(Box::new(move || {
+								events.borrow_mut().push(format!("stop {interval}"))
+							}))
+						},
+						&interval,
+					);
+					end_component();
+				}
+				end_component();
+			};
+			frame(true, 1);
+			frame(true, 1);
+			assert_eq!(*events.borrow(), ["start 1"]);
+			frame(true, 2);
+			assert_eq!(*events.borrow(), ["start 1", "stop 1", "start 2"]);
+			frame(false, 2);
+			assert_eq!(*events.borrow(), ["start 1", "stop 1", "start 2", "stop 2"]);
+		}
+
+		#[test]
+		fn test_reset_component_state() {
+			reset_all();
+			begin_component("root");
+			begin_component("child");
+			let (_, set_value) = use_state(1);
+			end_component();
+			end_component();
+			set_value(2);
+
+			reset_component_state(&hook_states()[0].path);
+
+			begin_component("root");
+			begin_component("child");
+			let (value, _) = use_state(1);
+			end_component();
+			end_component();
+			assert_eq!(value, 1);
+		}
 	}
 }
@@ -0,0 +1,263 @@
+//! Volume of the default audio devices, through the PulseAudio protocol (also served by PipeWire).
+use std::sync::{Mutex, Once, mpsc};
+
+use libpulse_binding::{
+	callbacks::ListResult,
+	context::{
+		Context, FlagSet, State,
+		introspect::Introspector,
+		subscribe::{Facility, InterestMaskSet},
+	},
+	mainloop::threaded::Mainloop,
+	volume::{ChannelVolumes, Volume},
+};
+
+/// One of the default audio devices of the sound server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioDevice {
+	/// The default output, like speakers or headphones.
+	Sink,
+	/// The default input, like a microphone.
+	Source,
+}
+
+impl AudioDevice {
+	/// The name the sound server gives to the default device.
+	fn target(&self) -> &'static str {
+		match self {
+			AudioDevice::Sink => "@DEFAULT_SINK@",
+			AudioDevice::Source => "@DEFAULT_SOURCE@",
+		}
+	}
+}
+
+/// The volume of an audio device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeState {
+	/// Volume of the loudest channel, where `1.0` is 100%. It can go above `1.0` when amplified.
+	pub volume: f32,
+	pub muted: bool,
+}
+
+/// Returned by [`use_audio_volume`].
+pub struct AudioVolume {
+	/// `None` until the volume is read, or if no sound server is running.
+	pub sink: Option<VolumeState>,
+	pub source: Option<VolumeState>,
+	/// Sets the volume of a device, where `1.0` is 100%.
+	pub set_volume: Box<dyn Fn(AudioDevice, f32)>,
+	pub set_muted: Box<dyn Fn(AudioDevice, bool)>,
+}
+
+/// The volumes of every channel of a device, kept to change the volume without changing the balance.
+#[derive(Clone, Copy, PartialEq)]
+struct DeviceVolume {
+	channels: ChannelVolumes,
+	muted: bool,
+}
+
+impl DeviceVolume {
+	fn state(&self) -> VolumeState {
+		VolumeState {
+			volume: fraction(self.channels.max()),
+			muted: self.muted,
+		}
+	}
+}
+
+/// The connection to the sound server, whose mainloop runs on its own thread.
+///
+/// libpulse lets any thread use the objects of a threaded mainloop while holding its lock, which every use of
+/// the connection does, so it can be shared with the UI thread.
+struct Connection {
+	mainloop: Mainloop,
+	context: Context,
+}
+
+// SAFETY: the mainloop and the context are only used with the lock of the mainloop held, see above.
+unsafe impl Send for Connection {}
+
+static CONNECTION: Mutex<Option<Connection>> = Mutex::new(None);
+/// The sink and the source.
+static VOLUMES: Mutex<(Option<DeviceVolume>, Option<DeviceVolume>)> = Mutex::new((None, None));
+static WATCH: Once = Once::new();
+
+fn fraction(volume: Volume) -> f32 {
+	volume.0 as f32 / Volume::NORMAL.0 as f32
+}
+
+fn to_volume(fraction: f32) -> Volume {
+	Volume((fraction.max(0.) * Volume::NORMAL.0 as f32).round() as u32)
+}
+
+fn update(device: AudioDevice, volume: DeviceVolume) {
+	let mut volumes = VOLUMES.lock().unwrap();
+	let current = match device {
+		AudioDevice::Sink => &mut volumes.0,
+		AudioDevice::Source => &mut volumes.1,
+	};
+	if *current != Some(volume) {
+		*current = Some(volume);
+		crate::winit::wake_up();
+	}
+}
+
+/// Reads the volumes of the default devices again, which arrive on the thread of the mainloop.
+fn read_volumes(introspect: &Introspector) {
+	introspect.get_sink_info_by_name(AudioDevice::Sink.target(), |result| {
+		if let ListResult::Item(info) = result {
+			update(
+				AudioDevice::Sink,
+				DeviceVolume {
+					channels: info.volume,
+					muted: info.mute,
+				},
+			);
+		}
+	});
+	introspect.get_source_info_by_name(AudioDevice::Source.target(), |result| {
+		if let ListResult::Item(info) = result {
+			update(
+				AudioDevice::Source,
+				DeviceVolume {
+					channels: info.volume,
+					muted: info.mute,
+				},
+			);
+		}
+	});
+}
+
+/// Connects to the sound server and follows the changes of the devices and of which ones are the default.
+fn connect() -> Result<Connection, String> {
+	let mut mainloop = Mainloop::new().ok_or("can't create the mainloop")?;
+	let mut context = Context::new(&mainloop, "hyprui").ok_or("can't create the context")?;
+	let (state_changed, state_changes) = mpsc::channel();
+	context.set_state_callback(Some(Box::new(move || {
+		let _ = state_changed.send(());
+	})));
+	context
+		.connect(None, FlagSet::NOAUTOSPAWN, None)
+		.map_err(|err| format!("{err}"))?;
+	mainloop.start().map_err(|err| format!("{err}"))?;
+	loop {
+		mainloop.lock();
+		let state = context.get_state();
+		mainloop.unlock();
+		match state {
+			State::Ready => break,
+			State::Failed | State::Terminated => {
+				mainloop.stop();
+				return Err("can't connect to the sound server".to_string());
+			}
+			_ => {
+				if state_changes.recv().is_err() {
+					return Err("the connection closed".to_string());
+				}
+			}
+		}
+	}
+
+	mainloop.lock();
+	context.set_state_callback(None);
+	let introspect = context.introspect();
+	read_volumes(&introspect);
+	// A change of the default device is a change of the server
+	context.set_subscribe_callback(Some(Box::new(move |facility, _, _| {
+		if matches!(
+			facility,
+			Some(Facility::Sink | Facility::Source | Facility::Server)
+		) {
+			read_volumes(&introspect);
+		}
+	})));
+	context.subscribe(
+		InterestMaskSet::SINK | InterestMaskSet::SOURCE | InterestMaskSet::SERVER,
+		|_| {},
+	);
+	mainloop.unlock();
+	Ok(Connection { mainloop, context })
+}
+
+fn watch() {
+	WATCH.call_once(|| {
+		std::thread::spawn(|| match connect() {
+			Ok(connection) => *CONNECTION.lock().unwrap() = Some(connection),
+			Err(err) => log::error!("failed to follow the audio volume: {err}"),
+		});
+	});
+}
+
+/// Runs `f` with the introspector of the connection, with the lock of the mainloop held.
+fn with_introspector(f: impl FnOnce(&mut Introspector)) {
+	let mut connection = CONNECTION.lock().unwrap();
+	let Some(Connection { mainloop, context }) = connection.as_mut() else {
+		return;
+	};
+	mainloop.lock();
+	f(&mut context.introspect());
+	mainloop.unlock();
+}
+
+/// Returns the volume and mute state of the default output and input, updated whenever they change,
+/// with setters to change them.
+///
+/// It talks to the sound server with libpulse, so it works with both PulseAudio and PipeWire (through
+/// `pipewire-pulse`). Only available with the `pulseaudio` feature.
+///
+/// ```rust,ignore
+/// let audio = use_audio_volume();
+/// let volume = audio.sink.map_or(0., |sink| sink.volume);
+/// (audio.set_volume)(AudioDevice::Sink, volume + 0.05);
+/// ```
+pub fn use_audio_volume() -> AudioVolume {
+	watch();
+	let (sink, source) = *VOLUMES.lock().unwrap();
+	AudioVolume {
+		sink: sink.as_ref().map(DeviceVolume::state),
+		source: source.as_ref().map(DeviceVolume::state),
+		set_volume: Box::new(|device, volume| {
+			let (sink, source) = *VOLUMES.lock().unwrap();
+			let current = match device {
+				AudioDevice::Sink => sink,
+				AudioDevice::Source => source,
+			};
+			// The volume of the channels is needed to keep their balance
+			let Some(mut channels) = current.map(|current| current.channels) else {
+				return;
+			};
+			channels.scale(to_volume(volume));
+			with_introspector(|introspect| match device {
+				AudioDevice::Sink => {
+					introspect.set_sink_volume_by_name(device.target(), &channels, None);
+				}
+				AudioDevice::Source => {
+					introspect.set_source_volume_by_name(device.target(), &channels, None);
+				}
+			});
+		}),
+		set_muted: Box::new(|device, muted| {
+			with_introspector(|introspect| match device {
+				AudioDevice::Sink => {
+					introspect.set_sink_mute_by_name(device.target(), muted, None);
+				}
+				AudioDevice::Source => {
+					introspect.set_source_mute_by_name(device.target(), muted, None);
+				}
+			});
+		}),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_volume_fractions() {
+		assert_eq!(to_volume(1.), Volume::NORMAL);
+		assert_eq!(to_volume(-0.5), Volume::MUTED);
+		assert_eq!(fraction(to_volume(0.5)), 0.5);
+		assert!((fraction(to_volume(1.5)) - 1.5).abs() < 1e-4);
+	}
+}
@@ -0,0 +1,168 @@
+//! Hooks that follow D-Bus properties and signals.
+use std::sync::{Arc, Mutex, Weak};
+
+use zbus::{
+	blocking::{Connection, Proxy},
+	export::serde::de::DeserializeOwned,
+	zvariant::{OwnedValue, Type},
+};
+
+use crate::use_memo;
+
+/// The message bus an object lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+	/// The bus of the user session, used by desktop services like notifications and media players.
+	Session,
+	/// The system-wide bus, used by hardware services like UPower and NetworkManager.
+	System,
+}
+
+/// An interface of a D-Bus object, watched with [`use_dbus_property`] or [`use_dbus_signal`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DbusObject {
+	pub bus: Bus,
+	/// The well-known name of the service, like `org.freedesktop.UPower`.
+	pub service: String,
+	pub path: String,
+	pub interface: String,
+}
+
+impl DbusObject {
+	pub fn session(
+		service: impl Into<String>,
+		path: impl Into<String>,
+		interface: impl Into<String>,
+	) -> Self {
+		Self {
+			bus: Bus::Session,
+			service: service.into(),
+			path: path.into(),
+			interface: interface.into(),
+		}
+	}
+
+	pub fn system(
+		service: impl Into<String>,
+		path: impl Into<String>,
+		interface: impl Into<String>,
+	) -> Self {
+		Self {
+			bus: Bus::System,
+			..Self::session(service, path, interface)
+		}
+	}
+
+	fn connection(&self) -> zbus::Result<Connection> {
+		match self.bus {
+			Bus::Session => crate::dbus::session_bus(),
+			Bus::System => crate::dbus::system_bus(),
+		}
+	}
+
+	pub(crate) fn proxy(&self) -> zbus::Result<Proxy<'static>> {
+		Proxy::new(
+			&self.connection()?,
+			self.service.clone(),
+			self.path.clone(),
+			self.interface.clone(),
+		)
+	}
+}
+
+/// Stores a value received by a watcher thread and redraws the window.
+///
+/// Returns `false` once the component that started the watcher is gone, so the thread can stop.
+fn publish<T>(latest: &Weak<Mutex<Option<T>>>, value: T) -> bool {
+	let Some(latest) = latest.upgrade() else {
+		return false;
+	};
+	*latest.lock().unwrap() = Some(value);
+	crate::winit::wake_up();
+	true
+}
+
+/// Starts `watch` on a new thread the first time it's called with `deps`, and returns the latest value it published.
+fn use_watcher<T, D>(
+	deps: D,
+	watch: impl FnOnce(D, Weak<Mutex<Option<T>>>) -> zbus::Result<()> + Send + 'static,
+) -> Option<T>
+where
+	T: Clone + Send + 'static,
+	D: std::hash::Hash + std::fmt::Debug + Clone + Send + 'static,
+{
+	let watched = deps.clone();
+	let latest = use_memo(
+		move || {
+			let latest = Arc::new(Mutex::new(None));
+			let weak = Arc::downgrade(&latest);
+			std::thread::spawn(move || {
+				let description = format!("{watched:?}");
+				if let Err(err) = watch(watched, weak) {
+					log::error!("failed to watch D-Bus {description}: {err}");
+				}
+			});
+			latest
+		},
+		deps,
+	);
+	latest.lock().unwrap().clone()
+}
+
+/// Returns the value of a D-Bus property and redraws the window whenever it changes.
+///
+/// The property is read and watched on a background thread, so the value is `None` until it is first received.
+/// It is watched until the component is unmounted or `object` or `property` change.
+///
+/// ```rust,ignore
+/// let display = DbusObject::system(
+///     "org.freedesktop.UPower",
+///     "/org/freedesktop/UPower/devices/DisplayDevice",
+///     "org.freedesktop.UPower.Device",
+/// );
+/// let percentage = use_dbus_property::<f64>(&display, "Percentage");
+/// ```
+pub fn use_dbus_property<T>(object: &DbusObject, property: &str) -> Option<T>
+where
+	T: TryFrom<OwnedValue> + Clone + Send + Unpin + 'static,
+	T::Error: Into<zbus::Error>,
+{
+	use_watcher(
+		(object.clone(), property.to_string()),
+		|(object, property), latest| {
+			let proxy = object.proxy()?;
+			let changes = proxy.receive_property_changed::<T>(&property);
+			if !publish(&latest, proxy.get_property::<T>(&property)?) {
+				return Ok(());
+			}
+			for change in changes {
+				if !publish(&latest, change.get()?) {
+					break;
+				}
+			}
+			Ok(())
+		},
+	)
+}
+
+/// Returns the arguments of the last emission of a D-Bus signal, and redraws the window whenever it's emitted.
+///
+/// The value is `None` until the signal is first received. It is watched until the component is unmounted or
+/// `object` or `signal` change.
+pub fn use_dbus_signal<T>(object: &DbusObject, signal: &str) -> Option<T>
+where
+	T: DeserializeOwned + Type + Clone + Send + 'static,
+{
+	use_watcher(
+		(object.clone(), signal.to_string()),
+		|(object, signal), latest| {
+			let proxy = object.proxy()?;
+			for message in proxy.receive_signal(signal)? {
+				if !publish(&latest, message.body().deserialize::<T>()?) {
+					break;
+				}
+			}
+			Ok(())
+		},
+	)
+}
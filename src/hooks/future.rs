@@ -0,0 +1,237 @@
+//! Running async work from components, like loading a file or querying a server, without blocking the UI.
+use std::{
+	collections::VecDeque,
+	future::Future,
+	hash::{DefaultHasher, Hash, Hasher},
+	panic::AssertUnwindSafe,
+	pin::Pin,
+	sync::{
+		Arc, Condvar, Mutex, Once,
+		atomic::{AtomicU64, Ordering},
+	},
+	task::{Context, Wake, Waker},
+	time::Duration,
+};
+
+use super::{use_ref, use_state_sender};
+
+/// The progress of the future of a [`use_future`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FutureState<T, E> {
+	/// The future is still running.
+	Pending,
+	Ready(T),
+	Error(E),
+}
+
+impl<T, E> FutureState<T, E> {
+	pub fn is_pending(&self) -> bool {
+		matches!(self, FutureState::Pending)
+	}
+
+	/// Returns the value the future resolved to, if it succeeded.
+	pub fn value(&self) -> Option<&T> {
+		match self {
+			FutureState::Ready(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Returns the error the future failed with.
+	pub fn error(&self) -> Option<&E> {
+		match self {
+			FutureState::Error(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl<T, E> From<Result<T, E>> for FutureState<T, E> {
+	fn from(result: Result<T, E>) -> Self {
+		match result {
+			Ok(value) => FutureState::Ready(value),
+			Err(error) => FutureState::Error(error),
+		}
+	}
+}
+
+/// A future spawned on the [`Executor`], put back in its queue when it is woken.
+struct Task {
+	/// `None` once the future resolved.
+	future: Mutex<Option<Pin<Box<dyn Future<Output = ()> + Send>>>>,
+}
+
+impl Wake for Task {
+	fn wake(self: Arc<Self>) {
+		EXECUTOR.schedule(self);
+	}
+}
+
+/// A few threads shared by every future of the app, polling the futures that were woken.
+struct Executor {
+	queue: Mutex<VecDeque<Arc<Task>>>,
+	queued: Condvar,
+	/// How many spawned futures didn't resolve yet.
+	running: Mutex<usize>,
+	idle: Condvar,
+	workers: Once,
+}
+
+static EXECUTOR: Executor = Executor {
+	queue: Mutex::new(VecDeque::new()),
+	queued: Condvar::new(),
+	running: Mutex::new(0),
+	idle: Condvar::new(),
+	workers: Once::new(),
+};
+
+impl Executor {
+	fn schedule(&self, task: Arc<Task>) {
+		self.queue.lock().unwrap().push_back(task);
+		self.queued.notify_one();
+	}
+
+	/// Polls the woken futures, forever.
+	fn work(&self) {
+		loop {
+			let task = {
+				let queue = self.queue.lock().unwrap();
+				let mut queue = self
+					.queued
+					.wait_while(queue, |queue| queue.is_empty())
+					.unwrap();
+				queue.pop_front().unwrap()
+			};
+			let mut future = task.future.lock().unwrap();
+			// Woken again after it resolved
+			let Some(pending) = future.as_mut() else {
+				continue;
+			};
+			let waker = Waker::from(task.clone());
+			let polled = std::panic::catch_unwind(AssertUnwindSafe(|| {
+				pending.as_mut().poll(&mut Context::from_waker(&waker))
+			}));
+			let resolved = match polled {
+				Ok(poll) => poll.is_ready(),
+				Err(_) => {
+					log::error!("a future panicked, its result is dropped");
+					true
+				}
+			};
+			if resolved {
+				*future = None;
+				let mut running = self.running.lock().unwrap();
+				*running -= 1;
+				if *running == 0 {
+					self.idle.notify_all();
+				}
+			}
+		}
+	}
+}
+
+/// Runs `future` on the threads of the executor, which are started with the first future.
+///
+/// The futures are polled without a runtime like tokio around, a future that needs one should enter it itself.
+/// A future blocking in a system call holds one of the threads until it returns.
+pub(crate) fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+	EXECUTOR.workers.call_once(|| {
+		let count = std::thread::available_parallelism().map_or(2, |count| count.get().clamp(2, 4));
+		for index in 0..count {
+			let spawned = std::thread::Builder::new()
+				.name(format!("hyprui-future-{index}"))
+				.spawn(|| EXECUTOR.work());
+			if let Err(err) = spawned {
+				log::error!("failed to spawn a thread for the futures: {err}");
+			}
+		}
+	});
+	*EXECUTOR.running.lock().unwrap() += 1;
+	EXECUTOR.schedule(Arc::new(Task {
+		future: Mutex::new(Some(Box::pin(future))),
+	}));
+}
+
+/// Blocks until every future spawned on the executor resolved, or `timeout` passed. Returns `false` on timeout.
+pub(crate) fn wait_for_futures(timeout: Duration) -> bool {
+	let running = EXECUTOR.running.lock().unwrap();
+	let (_running, result) = EXECUTOR
+		.idle
+		.wait_timeout_while(running, timeout, |running| *running > 0)
+		.unwrap();
+	!result.timed_out()
+}
+
+/// Runs the future returned by `task` on the threads shared by the futures of the app, and returns its progress.
+///
+/// The future is started on the first render and again when `deps` change, going back to
+/// [`FutureState::Pending`]. The window is re-rendered when it resolves. Futures can't be cancelled: one that is
+/// still running when `deps` change or the component is unmounted keeps running, but its result is dropped, even
+/// if the component was mounted again since.
+///
+/// The future is polled with a minimal executor, so it can't rely on a runtime like tokio being around; a future
+/// that needs one should enter it itself. Blocking work, like reading a file, holds one of the few threads of the
+/// executor while it runs.
+///
+/// ```rust,ignore
+/// let readme = use_future(
+///     {
+///         let path = path.clone();
+///         move || async move { std::fs::read_to_string(path).map_err(|err| err.to_string()) }
+///     },
+///     &path,
+/// );
+/// match readme {
+///     FutureState::Pending => Text::new("Loading…"),
+///     FutureState::Ready(text) => Text::new(text),
+///     FutureState::Error(err) => Text::new(format!("Failed to open {path}: {err}")),
+/// }
+/// ```
+pub fn use_future<T, E, F, Fut, D>(task: F, deps: &D) -> FutureState<T, E>
+where
+	T: Clone + Send + 'static,
+	E: Clone + Send + 'static,
+	F: FnOnce() -> Fut + 'static,
+	Fut: Future<Output = Result<T, E>> + Send + 'static,
+	D: Hash + 'static,
+{
+	let hash = {
+		let mut hasher = DefaultHasher::new();
+		deps.hash(&mut hasher);
+		hasher.finish()
+	};
+
+	let (state, sender) = use_state_sender(FutureState::<T, E>::Pending);
+	// The hash of the deps the running future was started with, and how many futures were started
+	let started = use_ref((None::<u64>, Arc::new(AtomicU64::new(0))));
+	if started.borrow().0 == Some(hash) {
+		return state;
+	}
+
+	// Only a weak reference leaves the component, so a result arriving after it was unmounted finds nothing
+	let generation = {
+		let mut started = started.borrow_mut();
+		started.0 = Some(hash);
+		Arc::downgrade(&started.1)
+	};
+	let run = started.borrow().1.fetch_add(1, Ordering::Relaxed) + 1;
+	sender.store(FutureState::Pending);
+
+	let future = task();
+	let dispatcher = crate::Dispatcher::new();
+	spawn(async move {
+		let result = FutureState::from(future.await);
+		dispatcher.dispatch(move || {
+			// The component was unmounted, or a future started with newer deps replaced this one
+			if generation
+				.upgrade()
+				.is_none_or(|generation| generation.load(Ordering::Relaxed) != run)
+			{
+				return;
+			}
+			sender.store(result);
+			crate::REQUEST_REDRAW.call();
+		});
+	});
+	FutureState::Pending
+}
@@ -0,0 +1,580 @@
+//! Hooks following the workspaces and windows of Hyprland, through its IPC sockets.
+use std::{
+	collections::HashMap,
+	io::{BufRead, BufReader, Read, Write},
+	os::unix::net::UnixStream,
+	path::PathBuf,
+	sync::{Arc, Mutex, Weak},
+	time::Duration,
+};
+
+use skia_safe::Image;
+
+use crate::{CaptureSource, use_memo, use_screen_capture};
+
+/// A workspace of Hyprland.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyprWorkspace {
+	/// Negative for special workspaces, like the scratchpad.
+	pub id: i32,
+	pub name: String,
+	/// The name of the monitor the workspace is on, like `DP-1`.
+	pub monitor: String,
+	/// How many windows are on the workspace.
+	pub windows: u32,
+}
+
+/// A window managed by Hyprland.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyprWindow {
+	/// Identifies the window in dispatchers, like `focuswindow address:0x55d0c0a0d3b0`.
+	pub address: String,
+	/// The app id of the window, like `org.gnome.Nautilus`.
+	pub class: String,
+	pub title: String,
+	/// The id of the workspace the window is on.
+	pub workspace: i32,
+	/// Where the window is on the layout of the monitors, in logical pixels.
+	pub position: (i32, i32),
+	pub size: (i32, i32),
+	pub floating: bool,
+	/// Windows of hidden groups, which aren't drawn.
+	pub hidden: bool,
+	/// The order the windows were last focused in, `0` for the focused one.
+	pub focus_history: i32,
+}
+
+/// The workspaces and windows of Hyprland, see [`use_hyprland`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HyprlandState {
+	/// The workspaces, by id.
+	pub workspaces: Vec<HyprWorkspace>,
+	/// The windows, in the order they were opened.
+	pub windows: Vec<HyprWindow>,
+	/// The id of the workspace shown on the focused monitor.
+	pub active_workspace: i32,
+	/// The address of the focused window, if any.
+	pub active_window: Option<String>,
+}
+
+impl HyprlandState {
+	/// Returns the windows on the workspace `id`.
+	pub fn windows_on(&self, id: i32) -> impl Iterator<Item = &HyprWindow> {
+		self
+			.windows
+			.iter()
+			.filter(move |window| window.workspace == id)
+	}
+}
+
+/// Returns the directory of the sockets of the running Hyprland instance.
+fn socket_directory() -> Option<PathBuf> {
+	let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+	let runtime = std::env::var("XDG_RUNTIME_DIR").ok()?;
+	Some(PathBuf::from(runtime).join("hypr").join(signature))
+}
+
+/// Sends `command` to Hyprland, like `hyprctl` does, and returns its answer.
+///
+/// ```rust,ignore
+/// let monitors = hyprctl("monitors")?;
+/// ```
+pub fn hyprctl(command: &str) -> std::io::Result<String> {
+	let directory = socket_directory()
+		.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Hyprland isn't running"))?;
+	let mut socket = UnixStream::connect(directory.join(".socket.sock"))?;
+	socket.write_all(command.as_bytes())?;
+	let mut answer = String::new();
+	socket.read_to_string(&mut answer)?;
+	Ok(answer)
+}
+
+/// Runs a Hyprland dispatcher with its arguments, like `workspace 2` or `focuswindow address:0x55d0c0a0d3b0`.
+///
+/// Errors are logged.
+pub fn hyprland_dispatch(dispatcher: &str) {
+	match hyprctl(&format!("dispatch {dispatcher}")) {
+		Ok(answer) if answer.trim() == "ok" => {}
+		Ok(answer) => log::error!("hyprland: dispatch {dispatcher} failed: {}", answer.trim()),
+		Err(err) => log::error!("hyprland: dispatch {dispatcher} failed: {err}"),
+	}
+}
+
+/// Splits the answer of a request into the header line of each item and its `key: value` lines, which are indented.
+fn blocks(answer: &str) -> Vec<(&str, HashMap<&str, &str>)> {
+	let mut blocks: Vec<(&str, HashMap<&str, &str>)> = Vec::new();
+	for line in answer.lines() {
+		if let Some(field) = line.strip_prefix('\t') {
+			if let Some((_, fields)) = blocks.last_mut() {
+				let (key, value) = field.split_once(':').unwrap_or((field, ""));
+				fields.insert(key, value.strip_prefix(' ').unwrap_or(value));
+			}
+		} else if !line.trim().is_empty() {
+			blocks.push((line, HashMap::new()));
+		}
+	}
+	blocks
+}
+
+/// Parses two numbers separated by a comma, like `1920,1080`.
+fn pair(value: &str) -> (i32, i32) {
+	let (x, y) = value.split_once(',').unwrap_or((value, ""));
+	(x.trim().parse().unwrap_or(0), y.trim().parse().unwrap_or(0))
+}
+
+/// Parses the header of a workspace, `workspace ID 1 (1) on monitor DP-1:`, into its id, name and monitor.
+fn parse_workspace_header(header: &str) -> Option<(i32, String, String)> {
+	let rest = header.strip_prefix("workspace ID ")?.strip_suffix(':')?;
+	let (id, rest) = rest.split_once(' ')?;
+	let (name, monitor) = rest.rsplit_once(") on monitor ")?;
+	Some((
+		id.parse().ok()?,
+		name.strip_prefix('(')?.to_string(),
+		monitor.to_string(),
+	))
+}
+
+/// Parses the header of a window, `Window 55d0c0a0d3b0 -> kitty:`, into its address.
+fn parse_window_header(header: &str) -> Option<String> {
+	let rest = header.strip_prefix("Window ")?;
+	let (address, _) = rest.split_once(' ')?;
+	Some(format!("0x{address}"))
+}
+
+fn parse_workspaces(answer: &str) -> Vec<HyprWorkspace> {
+	let mut workspaces = blocks(answer)
+		.into_iter()
+		.filter_map(|(header, fields)| {
+			let (id, name, monitor) = parse_workspace_header(header)?;
+			Some(HyprWorkspace {
+				id,
+				name,
+				monitor,
+				windows: fields.get("windows")?.parse().unwrap_or(0),
+			})
+		})
+		.collect::<Vec<_>>();
+	workspaces.sort_by_key(|workspace| workspace.id);
+	workspaces
+}
+
+fn parse_windows(answer: &str) -> Vec<HyprWindow> {
+	blocks(answer)
+		.into_iter()
+		.filter_map(|(header, fields)| {
+			let field = |key| fields.get(key).copied().unwrap_or_default();
+			// Windows being opened or closed aren't mapped
+			if field("mapped") == "0" {
+				return None;
+			}
+			Some(HyprWindow {
+				address: parse_window_header(header)?,
+				class: field("class").to_string(),
+				title: field("title").to_string(),
+				workspace: field("workspace")
+					.split(' ')
+					.next()
+					.and_then(|id| id.parse().ok())
+					.unwrap_or(0),
+				position: pair(field("at")),
+				size: pair(field("size")),
+				floating: field("floating") == "1",
+				hidden: field("hidden") == "1",
+				focus_history: field("focusHistoryID").parse().unwrap_or(-1),
+			})
+		})
+		.collect()
+}
+
+/// Reads the workspaces and windows of Hyprland.
+fn read_state() -> std::io::Result<HyprlandState> {
+	let active_workspace = hyprctl("activeworkspace")?;
+	let active_window = hyprctl("activewindow")?;
+	Ok(HyprlandState {
+		workspaces: parse_workspaces(&hyprctl("workspaces")?),
+		windows: parse_windows(&hyprctl("clients")?),
+		active_workspace: blocks(&active_workspace)
+			.first()
+			.and_then(|(header, _)| parse_workspace_header(header))
+			.map_or(1, |(id, _, _)| id),
+		active_window: blocks(&active_window)
+			.first()
+			.and_then(|(header, _)| parse_window_header(header)),
+	})
+}
+
+/// What an event of Hyprland changed in its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+	None,
+	State,
+	/// The windows may have moved or been resized, which the events don't tell.
+	Layout,
+}
+
+/// Returns the id of the workspace named `name`, which is its id for most workspaces.
+fn workspace_id(state: &HyprlandState, name: &str) -> i32 {
+	state
+		.workspaces
+		.iter()
+		.find(|workspace| workspace.name == name)
+		.map_or_else(|| name.parse().unwrap_or(0), |workspace| workspace.id)
+}
+
+/// Updates `state` with an event of the socket of events, like `openwindow>>55d0c0a0d3b0,2,kitty,~`.
+///
+/// The events give the addresses of windows without their `0x`.
+fn apply_event(state: &mut HyprlandState, event: &str) -> Change {
+	let Some((name, data)) = event.split_once(">>") else {
+		return Change::None;
+	};
+	let window = |state: &HyprlandState, address: &str| {
+		let address = format!("0x{address}");
+		state
+			.windows
+			.iter()
+			.position(|window| window.address == address)
+	};
+	let change = match name {
+		"workspacev2" => {
+			let (id, _) = data.split_once(',').unwrap_or((data, ""));
+			state.active_workspace = id.parse().unwrap_or(state.active_workspace);
+			Change::State
+		}
+		"focusedmon" => {
+			let (_, workspace) = data.split_once(',').unwrap_or(("", data));
+			state.active_workspace = workspace_id(state, workspace);
+			Change::State
+		}
+		"activewindowv2" => {
+			let address = data.trim_matches(',');
+			state.active_window = (!address.is_empty()).then(|| format!("0x{address}"));
+			if let Some(focused) = window(state, address) {
+				// The windows focused since the newly focused one was go one step further in the history
+				let previous = match state.windows[focused].focus_history {
+					-1 => i32::MAX,
+					history => history,
+				};
+				for window in &mut state.windows {
+					if (0..previous).contains(&window.focus_history) {
+						window.focus_history += 1;
+					}
+				}
+				state.windows[focused].focus_history = 0;
+			}
+			Change::State
+		}
+		"openwindow" => {
+			let mut fields = data.splitn(4, ',');
+			let (Some(address), Some(workspace), Some(class), Some(title)) =
+				(fields.next(), fields.next(), fields.next(), fields.next())
+			else {
+				return Change::None;
+			};
+			let workspace = workspace_id(state, workspace);
+			state.windows.push(HyprWindow {
+				address: format!("0x{address}"),
+				class: class.to_string(),
+				title: title.to_string(),
+				workspace,
+				position: (0, 0),
+				size: (0, 0),
+				floating: false,
+				hidden: false,
+				focus_history: -1,
+			});
+			Change::Layout
+		}
+		"closewindow" => {
+			let Some(closed) = window(state, data) else {
+				return Change::None;
+			};
+			let closed = state.windows.remove(closed);
+			for window in &mut state.windows {
+				if closed.focus_history >= 0 && window.focus_history > closed.focus_history {
+					window.focus_history -= 1;
+				}
+			}
+			if state.active_window.as_ref() == Some(&closed.address) {
+				state.active_window = None;
+			}
+			Change::Layout
+		}
+		"movewindowv2" => {
+			let mut fields = data.splitn(3, ',');
+			let (Some(address), Some(workspace)) = (fields.next(), fields.next()) else {
+				return Change::None;
+			};
+			let (Some(moved), Ok(workspace)) = (window(state, address), workspace.parse()) else {
+				return Change::None;
+			};
+			state.windows[moved].workspace = workspace;
+			Change::Layout
+		}
+		"windowtitlev2" => {
+			let Some((address, title)) = data.split_once(',') else {
+				return Change::None;
+			};
+			let Some(renamed) = window(state, address) else {
+				return Change::None;
+			};
+			state.windows[renamed].title = title.to_string();
+			Change::State
+		}
+		"changefloatingmode" => {
+			let Some((address, floating)) = data.split_once(',') else {
+				return Change::None;
+			};
+			let Some(changed) = window(state, address) else {
+				return Change::None;
+			};
+			state.windows[changed].floating = floating == "1";
+			Change::Layout
+		}
+		"fullscreen" => Change::Layout,
+		"createworkspacev2" => {
+			let Some((id, name)) = data.split_once(',') else {
+				return Change::None;
+			};
+			let Ok(id) = id.parse() else {
+				return Change::None;
+			};
+			// Workspaces are created on the focused monitor, before they are shown on it
+			let monitor = state
+				.workspaces
+				.iter()
+				.find(|workspace| workspace.id == state.active_workspace)
+				.map(|workspace| workspace.monitor.clone())
+				.unwrap_or_default();
+			state.workspaces.retain(|workspace| workspace.id != id);
+			state.workspaces.push(HyprWorkspace {
+				id,
+				name: name.to_string(),
+				monitor,
+				windows: 0,
+			});
+			state.workspaces.sort_by_key(|workspace| workspace.id);
+			Change::State
+		}
+		"destroyworkspacev2" => {
+			let (id, _) = data.split_once(',').unwrap_or((data, ""));
+			let Ok(id) = id.parse::<i32>() else {
+				return Change::None;
+			};
+			state.workspaces.retain(|workspace| workspace.id != id);
+			Change::State
+		}
+		"moveworkspacev2" => {
+			let mut fields = data.splitn(3, ',');
+			let (Some(id), Some(_), Some(monitor)) = (fields.next(), fields.next(), fields.next()) else {
+				return Change::None;
+			};
+			let id = id.parse().unwrap_or(0);
+			for workspace in &mut state.workspaces {
+				if workspace.id == id {
+					workspace.monitor = monitor.to_string();
+				}
+			}
+			Change::Layout
+		}
+		"renameworkspace" => {
+			let Some((id, name)) = data.split_once(',') else {
+				return Change::None;
+			};
+			let id = id.parse().unwrap_or(0);
+			for workspace in &mut state.workspaces {
+				if workspace.id == id {
+					workspace.name = name.to_string();
+				}
+			}
+			Change::State
+		}
+		_ => Change::None,
+	};
+	for workspace in &mut state.workspaces {
+		workspace.windows = state
+			.windows
+			.iter()
+			.filter(|window| window.workspace == workspace.id)
+			.count() as u32;
+	}
+	change
+}
+
+/// Reads where the windows are again, as the events don't tell it.
+fn read_layout(state: &mut HyprlandState) -> std::io::Result<()> {
+	let windows = parse_windows(&hyprctl("clients")?);
+	for window in &mut state.windows {
+		if let Some(read) = windows.iter().find(|read| read.address == window.address) {
+			window.position = read.position;
+			window.size = read.size;
+			window.hidden = read.hidden;
+		}
+	}
+	Ok(())
+}
+
+/// Publishes the state of Hyprland again on every event that changes it, until the component is gone.
+///
+/// The state is read once, then updated with the events. Only the windows being opened, closed, moved between
+/// workspaces or made floating have their geometry read again, so a floating window dragged or resized with the
+/// mouse keeps its previous position and size until then.
+fn watch(latest: Weak<Mutex<Option<HyprlandState>>>) -> std::io::Result<()> {
+	let directory = socket_directory()
+		.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Hyprland isn't running"))?;
+	// Connected before reading, so no event is missed in between
+	let events = UnixStream::connect(directory.join(".socket2.sock"))?;
+	let publish = |state: &HyprlandState| {
+		let Some(latest) = latest.upgrade() else {
+			return false;
+		};
+		*latest.lock().unwrap() = Some(state.clone());
+		crate::winit::wake_up();
+		true
+	};
+	let mut state = read_state()?;
+	if !publish(&state) {
+		return Ok(());
+	}
+	for event in BufReader::new(events).lines() {
+		match apply_event(&mut state, &event?) {
+			Change::None => continue,
+			Change::State => {}
+			Change::Layout => read_layout(&mut state)?,
+		}
+		if !publish(&state) {
+			break;
+		}
+	}
+	Ok(())
+}
+
+/// Returns the workspaces and windows of Hyprland, and redraws the window whenever they change.
+///
+/// They are read and followed on a background thread, so the value is `None` until they are first read, and
+/// stays `None` if the app doesn't run under Hyprland. Switch workspaces and focus windows with
+/// [`hyprland_dispatch`].
+///
+/// ```rust,ignore
+/// let Some(hyprland) = use_hyprland() else {
+///     return Box::new(Container::new());
+/// };
+/// Box::new(WorkspaceIndicator::new(&hyprland, None))
+/// ```
+pub fn use_hyprland() -> Option<HyprlandState> {
+	let latest = use_memo(
+		|| {
+			let latest = Arc::new(Mutex::new(None));
+			let weak = Arc::downgrade(&latest);
+			std::thread::spawn(move || {
+				if let Err(err) = watch(weak) {
+					log::error!("hyprland: can't follow the workspaces and windows: {err}");
+				}
+			});
+			latest
+		},
+		(),
+	);
+	latest.lock().unwrap().clone()
+}
+
+/// Returns a capture of `window`, taken again every `interval` while it is given, like the preview of a taskbar
+/// button while it is hovered.
+///
+/// The window is copied by Hyprland with [`use_screen_capture`](crate::use_screen_capture), so all of it is
+/// captured even when other windows cover it or it is on another workspace, unless the `wayland` feature is off (see
+/// [`CaptureSource::Window`]). `None` is returned until the first capture is taken, and if the window can't be
+/// captured.
+pub fn use_window_thumbnail(window: Option<&HyprWindow>, interval: Duration) -> Option<Image> {
+	let source = window.map(|window| CaptureSource::Window(window.clone()));
+	use_screen_capture(source.as_ref(), interval)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_answers_are_parsed() {
+		let workspaces = "workspace ID 2 (web) on monitor DP-1:\n\tmonitorID: 0\n\twindows: 1\n\n\
+			workspace ID 1 (1) on monitor DP-1:\n\tmonitorID: 0\n\twindows: 0\n\n";
+		let workspaces = parse_workspaces(workspaces);
+		assert_eq!(
+			workspaces
+				.iter()
+				.map(|workspace| (workspace.id, workspace.name.as_str(), workspace.windows))
+				.collect::<Vec<_>>(),
+			vec![(1, "1", 0), (2, "web", 1)]
+		);
+		assert_eq!(workspaces[0].monitor, "DP-1");
+
+		let clients = "Window 55d0c0a0d3b0 -> kitty:\n\tmapped: 1\n\thidden: 0\n\tat: 10,50\n\t\
+			size: 1900,1020\n\tworkspace: 2 (web)\n\tfloating: 0\n\tclass: kitty\n\ttitle: vim: notes.md\n\t\
+			focusHistoryID: 0\n\ttags: \n\n";
+		let windows = parse_windows(clients);
+		assert_eq!(windows.len(), 1);
+		assert_eq!(windows[0].address, "0x55d0c0a0d3b0");
+		assert_eq!(windows[0].title, "vim: notes.md");
+		assert_eq!(windows[0].workspace, 2);
+		assert_eq!(windows[0].position, (10, 50));
+		assert_eq!(windows[0].size, (1900, 1020));
+	}
+
+	#[test]
+	fn test_events_update_the_state() {
+		let mut state = HyprlandState {
+			workspaces: parse_workspaces("workspace ID 1 (1) on monitor DP-1:\n\twindows: 0\n\n"),
+			active_workspace: 1,
+			..HyprlandState::default()
+		};
+		assert_eq!(
+			apply_event(&mut state, "activelayout>>keyboard,English (US)"),
+			Change::None
+		);
+		assert_eq!(
+			apply_event(&mut state, "createworkspacev2>>2,web"),
+			Change::State
+		);
+		assert_eq!(state.workspaces[1].monitor, "DP-1");
+		assert_eq!(apply_event(&mut state, "workspacev2>>2,web"), Change::State);
+		assert_eq!(state.active_workspace, 2);
+
+		assert_eq!(
+			apply_event(&mut state, "openwindow>>a1,web,kitty,~"),
+			Change::Layout
+		);
+		apply_event(&mut state, "openwindow>>b2,1,foot,vim: a, b");
+		assert_eq!(state.windows[1].title, "vim: a, b");
+		assert_eq!(state.windows[1].workspace, 1);
+		assert_eq!(state.workspaces[1].windows, 1);
+
+		apply_event(&mut state, "activewindowv2>>a1");
+		apply_event(&mut state, "activewindowv2>>b2");
+		assert_eq!(state.active_window.as_deref(), Some("0xb2"));
+		assert_eq!(state.windows_on(1).next().unwrap().focus_history, 0);
+		assert_eq!(state.windows[0].focus_history, 1);
+
+		apply_event(&mut state, "windowtitlev2>>a1,htop");
+		apply_event(&mut state, "movewindowv2>>a1,1,1");
+		assert_eq!(state.windows[0].title, "htop");
+		assert_eq!(
+			state
+				.workspaces
+				.iter()
+				.map(|workspace| workspace.windows)
+				.collect::<Vec<_>>(),
+			vec![2, 0]
+		);
+
+		apply_event(&mut state, "closewindow>>b2");
+		assert_eq!(state.windows.len(), 1);
+		assert_eq!(state.windows[0].focus_history, 0);
+		assert_eq!(state.active_window, None);
+		apply_event(&mut state, "renameworkspace>>2,mail");
+		assert_eq!(state.workspaces[1].name, "mail");
+		apply_event(&mut state, "focusedmon>>DP-1,1");
+		assert_eq!(state.active_workspace, 1);
+		apply_event(&mut state, "destroyworkspacev2>>2,mail");
+		assert_eq!(state.workspaces.len(), 1);
+	}
+}
@@ -0,0 +1,136 @@
+//! Battery and power profile hooks, backed by UPower and power-profiles-daemon.
+use std::time::Duration;
+
+use super::dbus::{DbusObject, use_dbus_property};
+
+/// What the battery is doing, as reported by UPower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargingState {
+	Unknown,
+	Charging,
+	Discharging,
+	Empty,
+	FullyCharged,
+	PendingCharge,
+	PendingDischarge,
+}
+
+impl From<u32> for ChargingState {
+	fn from(state: u32) -> Self {
+		match state {
+			1 => ChargingState::Charging,
+			2 => ChargingState::Discharging,
+			3 => ChargingState::Empty,
+			4 => ChargingState::FullyCharged,
+			5 => ChargingState::PendingCharge,
+			6 => ChargingState::PendingDischarge,
+			_ => ChargingState::Unknown,
+		}
+	}
+}
+
+/// The combined state of the batteries of the system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Battery {
+	/// Charge level, from `0` to `100`.
+	pub percentage: f64,
+	pub state: ChargingState,
+	/// Time until the battery is full while charging, or empty while discharging, if UPower could estimate it.
+	pub time_remaining: Option<Duration>,
+}
+
+fn display_device() -> DbusObject {
+	DbusObject::system(
+		"org.freedesktop.UPower",
+		"/org/freedesktop/UPower/devices/DisplayDevice",
+		"org.freedesktop.UPower.Device",
+	)
+}
+
+/// Returns the state of the battery, updated whenever UPower reports a change.
+///
+/// Returns `None` while it's being read, if UPower isn't running, or if the system has no battery.
+pub fn use_battery() -> Option<Battery> {
+	let device = display_device();
+	let present = use_dbus_property::<bool>(&device, "IsPresent");
+	let percentage = use_dbus_property::<f64>(&device, "Percentage");
+	let state = use_dbus_property::<u32>(&device, "State").map(ChargingState::from);
+	let time_to_empty = use_dbus_property::<i64>(&device, "TimeToEmpty");
+	let time_to_full = use_dbus_property::<i64>(&device, "TimeToFull");
+	if present != Some(true) {
+		return None;
+	}
+	let state = state.unwrap_or(ChargingState::Unknown);
+	let seconds = match state {
+		ChargingState::Charging | ChargingState::PendingCharge => time_to_full,
+		_ => time_to_empty,
+	};
+	Some(Battery {
+		percentage: percentage?,
+		state,
+		// UPower reports 0 when it has no estimate.
+		time_remaining: seconds
+			.filter(|s| *s > 0)
+			.map(|s| Duration::from_secs(s as u64)),
+	})
+}
+
+/// A power profile of power-profiles-daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerProfile {
+	PowerSaver,
+	Balanced,
+	Performance,
+}
+
+impl PowerProfile {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PowerProfile::PowerSaver => "power-saver",
+			PowerProfile::Balanced => "balanced",
+			PowerProfile::Performance => "performance",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		[
+			PowerProfile::PowerSaver,
+			PowerProfile::Balanced,
+			PowerProfile::Performance,
+		]
+		.into_iter()
+		.find(|profile| profile.as_str() == name)
+	}
+}
+
+fn power_profiles() -> DbusObject {
+	DbusObject::system(
+		"net.hadess.PowerProfiles",
+		"/net/hadess/PowerProfiles",
+		"net.hadess.PowerProfiles",
+	)
+}
+
+/// Returns the active power profile and a setter to switch it, updated whenever it changes.
+///
+/// The profile is `None` while it's being read or if power-profiles-daemon isn't running.
+/// Switching profiles may require the user to authenticate, depending on the polkit policy.
+pub fn use_power_profile() -> (Option<PowerProfile>, Box<dyn Fn(PowerProfile)>) {
+	let active = use_dbus_property::<String>(&power_profiles(), "ActiveProfile")
+		.and_then(|name| PowerProfile::from_name(&name));
+	let set_profile = |profile: PowerProfile| {
+		// The daemon may wait for polkit, so the call doesn't block the UI thread.
+		std::thread::spawn(move || {
+			let result = power_profiles()
+				.proxy()
+				.and_then(|proxy| Ok(proxy.set_property("ActiveProfile", profile.as_str())?));
+			if let Err(err) = result {
+				log::error!(
+					"failed to switch to the {} power profile: {err}",
+					profile.as_str()
+				);
+			}
+		});
+	};
+	(active, Box::new(set_profile))
+}
@@ -0,0 +1,216 @@
+//! Live captures of the screen, taken with `grim` through the `wlr-screencopy` protocol of the compositor, and of
+//! the windows of Hyprland, copied by Hyprland itself.
+use std::{
+	process::Command,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::Duration,
+};
+
+use skia_safe::{Data, Image};
+
+use crate::{HyprWindow, use_interval, use_memo, use_ref};
+
+/// What [`use_screen_capture`] captures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureSource {
+	/// A whole monitor, by its name like `DP-1`.
+	Output(String),
+	/// A window of Hyprland, all of it even when it is covered or on another workspace.
+	///
+	/// It is copied with the `hyprland-toplevel-export` protocol of Hyprland. Without the `wayland` feature, the part
+	/// of the screen it is drawn on is captured with `grim` instead, so only what is visible of it.
+	Window(HyprWindow),
+	/// A rectangle of the layout of the monitors, in logical pixels.
+	Region {
+		position: (i32, i32),
+		size: (i32, i32),
+	},
+}
+
+impl CaptureSource {
+	/// Identifies what is captured, so a window that moved or was renamed keeps its capture.
+	fn key(&self) -> String {
+		match self {
+			CaptureSource::Output(name) => format!("output {name}"),
+			CaptureSource::Window(window) => format!("window {}", window.address),
+			CaptureSource::Region { position, size } => {
+				format!("region {},{} {}x{}", position.0, position.1, size.0, size.1)
+			}
+		}
+	}
+
+	/// Returns the arguments of `grim` writing a capture of the source as a PNG to its output.
+	fn grim_args(&self) -> Vec<String> {
+		let mut args = match self {
+			CaptureSource::Output(name) => vec!["-o".to_string(), name.clone()],
+			CaptureSource::Window(HyprWindow { position, size, .. })
+			| CaptureSource::Region { position, size } => vec![
+				"-g".to_string(),
+				format!("{},{} {}x{}", position.0, position.1, size.0, size.1),
+			],
+		};
+		// Not compressed, as the capture is decoded right away
+		args.extend(["-t", "png", "-l", "0", "-"].map(String::from));
+		args
+	}
+}
+
+/// A capture, made into an image on the UI thread.
+enum Captured {
+	/// The PNG written by `grim`.
+	Png(Vec<u8>),
+	#[cfg(feature = "wayland")]
+	Window(crate::toplevel_export::Capture),
+}
+
+impl Captured {
+	fn to_image(&self) -> Option<Image> {
+		match self {
+			Captured::Png(png) => Image::from_encoded(Data::new_copy(png)),
+			#[cfg(feature = "wayland")]
+			Captured::Window(capture) => capture.to_image(),
+		}
+	}
+}
+
+/// The captures of a [`use_screen_capture`], taken one at a time.
+#[derive(Default)]
+struct Captures {
+	/// The last capture taken, until it is made into an image.
+	latest: Mutex<Option<(String, Captured)>>,
+	/// Set while a capture is being taken.
+	taking: AtomicBool,
+	/// Set when a capture was asked for while another was being taken, to take it once that one is done.
+	missed: AtomicBool,
+}
+
+/// Takes a capture of `source`, blocking until it is taken.
+fn take_capture(source: &CaptureSource) -> Result<Captured, String> {
+	#[cfg(feature = "wayland")]
+	if let CaptureSource::Window(window) = source {
+		return crate::toplevel_export::capture(&window.address).map(Captured::Window);
+	}
+	match Command::new("grim").args(source.grim_args()).output() {
+		Ok(output) if output.status.success() => Ok(Captured::Png(output.stdout)),
+		Ok(output) => Err(format!(
+			"grim failed: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)),
+		Err(err) => Err(format!("can't run grim: {err}")),
+	}
+}
+
+/// Returns a capture of `source`, taken again every `interval` while it is given, like a live view of the monitors
+/// in a workspace overview or a screen-sharing picker.
+///
+/// The captures are taken on a background thread. Outputs and regions are captured with `grim`, so it must be
+/// installed and the compositor must support `wlr-screencopy`, like Hyprland and Sway do. `None` is returned until
+/// the first capture of the source is taken, and if it can't be captured. Capturing a whole monitor takes a few
+/// milliseconds, so intervals under 100 ms are only worth it for small regions: a capture is only started once the
+/// previous one is done, so a slow capture lowers the rate instead of piling up.
+///
+/// ```rust,ignore
+/// let capture = use_screen_capture(Some(&CaptureSource::Output("DP-1".into())), Duration::from_millis(500));
+/// ```
+pub fn use_screen_capture(source: Option<&CaptureSource>, interval: Duration) -> Option<Image> {
+	// The source being captured, and the last capture with the source it shows
+	let latest = use_ref((None::<String>, None::<(String, Image)>));
+	let captures = use_memo(|| Arc::new(Captures::default()), ());
+	let take = {
+		let source = source.cloned();
+		let captures = captures.clone();
+		move || {
+			let Some(source) = source else {
+				return;
+			};
+			if captures.taking.swap(true, Ordering::AcqRel) {
+				captures.missed.store(true, Ordering::Release);
+				return;
+			}
+			let captures = captures.clone();
+			std::thread::spawn(move || {
+				let key = source.key();
+				match take_capture(&source) {
+					Ok(captured) => *captures.latest.lock().unwrap() = Some((key, captured)),
+					Err(err) => log::warn!("can't capture the {key}: {err}"),
+				}
+				captures.taking.store(false, Ordering::Release);
+				crate::winit::wake_up();
+			});
+		}
+	};
+	let mut latest = latest.borrow_mut();
+	if let Some((key, captured)) = captures.latest.lock().unwrap().take()
+		&& let Some(image) = captured.to_image()
+	{
+		latest.1 = Some((key, image));
+	}
+	let key = source.map(CaptureSource::key);
+	// The first capture of a source is taken right away, or once the capture being taken is done
+	if latest.0 != key {
+		latest.0 = key.clone();
+		take.clone()();
+	} else if !captures.taking.load(Ordering::Acquire)
+		&& captures.missed.swap(false, Ordering::AcqRel)
+	{
+		take.clone()();
+	}
+	let period = if key.is_some() {
+		interval
+	} else {
+		Duration::ZERO
+	};
+	use_interval(period, take);
+	latest
+		.1
+		.as_ref()
+		.filter(|(captured, _)| Some(captured) == key.as_ref())
+		.map(|(_, image)| image.clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sources_are_captured_with_grim() {
+		let args = |source: CaptureSource| source.grim_args().join(" ");
+		assert_eq!(
+			args(CaptureSource::Output("DP-1".to_string())),
+			"-o DP-1 -t png -l 0 -"
+		);
+		assert_eq!(
+			args(CaptureSource::Region {
+				position: (-1920, 0),
+				size: (800, 600)
+			}),
+			"-g -1920,0 800x600 -t png -l 0 -"
+		);
+		let window = HyprWindow {
+			address: "0x55d0c0a0d3b0".to_string(),
+			class: "foot".to_string(),
+			title: "~".to_string(),
+			workspace: 1,
+			position: (10, 20),
+			size: (300, 200),
+			floating: false,
+			hidden: false,
+			focus_history: 0,
+		};
+		assert_eq!(
+			args(CaptureSource::Window(window.clone())),
+			"-g 10,20 300x200 -t png -l 0 -"
+		);
+		let moved = HyprWindow {
+			position: (40, 20),
+			..window.clone()
+		};
+		assert_eq!(
+			CaptureSource::Window(moved).key(),
+			CaptureSource::Window(window).key()
+		);
+	}
+}
@@ -0,0 +1,312 @@
+//! CPU, memory, network and disk usage hooks, sampled from `/proc` for system monitors.
+use std::{
+	fs,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use crate::{use_memo, use_state_sender};
+
+/// How busy the processors were during the last interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuUsage {
+	/// Fraction of the time all the cores were busy, from `0` to `1`.
+	pub total: f32,
+	/// Fraction of the time each core was busy, from `0` to `1`.
+	pub cores: Vec<f32>,
+}
+
+/// The memory and swap of the system, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+	pub total: u64,
+	/// Memory that can be given to programs without swapping, including caches the kernel can drop.
+	pub available: u64,
+	pub swap_total: u64,
+	pub swap_free: u64,
+}
+
+impl MemoryUsage {
+	pub fn used(&self) -> u64 {
+		self.total.saturating_sub(self.available)
+	}
+
+	/// Fraction of the memory in use, from `0` to `1`.
+	pub fn fraction_used(&self) -> f32 {
+		fraction(self.used(), self.total)
+	}
+
+	pub fn swap_used(&self) -> u64 {
+		self.swap_total.saturating_sub(self.swap_free)
+	}
+}
+
+/// Bytes per second received and sent by the network interfaces, other than loopback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkThroughput {
+	pub received: f64,
+	pub sent: f64,
+}
+
+/// Bytes per second read from and written to the disks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskThroughput {
+	pub read: f64,
+	pub written: f64,
+}
+
+fn fraction(part: u64, whole: u64) -> f32 {
+	if whole == 0 {
+		0.
+	} else {
+		(part as f64 / whole as f64) as f32
+	}
+}
+
+/// Returns the busy and total time of every `cpu` line of `/proc/stat`, the sum of all the cores first.
+fn parse_cpu_times(stat: &str) -> Vec<(u64, u64)> {
+	stat
+		.lines()
+		.filter(|line| line.starts_with("cpu"))
+		.map(|line| {
+			// user nice system idle iowait irq softirq steal, guest time is already counted in user and nice
+			let times: Vec<u64> = line
+				.split_whitespace()
+				.skip(1)
+				.take(8)
+				.map(|time| time.parse().unwrap_or(0))
+				.collect();
+			let total: u64 = times.iter().sum();
+			let idle = times.get(3).unwrap_or(&0) + times.get(4).unwrap_or(&0);
+			(total - idle, total)
+		})
+		.collect()
+}
+
+/// Returns how busy the processors were between two readings of [`parse_cpu_times`].
+fn cpu_usage(previous: &[(u64, u64)], current: &[(u64, u64)]) -> Option<CpuUsage> {
+	let mut usages =
+		previous
+			.iter()
+			.zip(current)
+			.map(|(&(busy_before, total_before), &(busy, total))| {
+				fraction(
+					busy.saturating_sub(busy_before),
+					total.saturating_sub(total_before),
+				)
+			});
+	Some(CpuUsage {
+		total: usages.next()?,
+		cores: usages.collect(),
+	})
+}
+
+fn parse_meminfo(meminfo: &str) -> MemoryUsage {
+	let field = |name: &str| {
+		meminfo
+			.lines()
+			.find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+			.and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+			.map_or(0, |kib| kib * 1024)
+	};
+	MemoryUsage {
+		total: field("MemTotal"),
+		available: field("MemAvailable"),
+		swap_total: field("SwapTotal"),
+		swap_free: field("SwapFree"),
+	}
+}
+
+/// Returns the bytes received and sent by all the interfaces of `/proc/net/dev` but loopback.
+fn parse_net_dev(net_dev: &str) -> (u64, u64) {
+	net_dev
+		.lines()
+		.filter_map(|line| line.split_once(':'))
+		.filter(|(interface, _)| interface.trim() != "lo")
+		.fold((0, 0), |(received, sent), (_, counters)| {
+			let counters: Vec<u64> = counters
+				.split_whitespace()
+				.map(|counter| counter.parse().unwrap_or(0))
+				.collect();
+			(
+				received + counters.first().unwrap_or(&0),
+				sent + counters.get(8).unwrap_or(&0),
+			)
+		})
+}
+
+/// Returns the bytes read and written by the devices of `/proc/diskstats` in `disks`.
+fn parse_diskstats(diskstats: &str, disks: &[String]) -> (u64, u64) {
+	// The sectors of the statistics are always 512 bytes, whatever the disk uses
+	const SECTOR: u64 = 512;
+	diskstats
+		.lines()
+		.map(|line| line.split_whitespace().collect::<Vec<_>>())
+		.filter(|fields| fields.len() > 9 && disks.iter().any(|disk| disk == fields[2]))
+		.fold((0, 0), |(read, written), fields| {
+			(
+				read + fields[5].parse::<u64>().unwrap_or(0) * SECTOR,
+				written + fields[9].parse::<u64>().unwrap_or(0) * SECTOR,
+			)
+		})
+}
+
+/// Returns the whole disks of `/sys/block`, skipping the virtual devices that would count the same bytes twice.
+fn physical_disks() -> Vec<String> {
+	let Ok(entries) = fs::read_dir("/sys/block") else {
+		return Vec::new();
+	};
+	entries
+		.filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+		.filter(|name| {
+			!["loop", "ram", "zram", "dm-", "md"]
+				.iter()
+				.any(|prefix| name.starts_with(prefix))
+		})
+		.collect()
+}
+
+/// Returns the change per second of two counters between two readings, which are `None` the first time.
+fn per_second(
+	previous: &mut Option<(Instant, (u64, u64))>,
+	current: (u64, u64),
+) -> Option<(f64, f64)> {
+	let now = Instant::now();
+	let (then, before) = previous.replace((now, current))?;
+	let seconds = now.duration_since(then).as_secs_f64();
+	if seconds == 0. {
+		return None;
+	}
+	Some((
+		current.0.saturating_sub(before.0) as f64 / seconds,
+		current.1.saturating_sub(before.1) as f64 / seconds,
+	))
+}
+
+/// Calls `sample` on a new thread every `interval`, and returns the latest value it returned.
+///
+/// The values are sent to the component through the dispatcher. The thread stops after the component is
+/// unmounted or the interval changes.
+fn use_sampler<T: Clone + Send + 'static>(
+	interval: Duration,
+	mut sample: impl FnMut() -> Option<T> + Send + 'static,
+) -> Option<T> {
+	let (latest, sender) = use_state_sender(None);
+	use_memo(
+		move || {
+			let alive = Arc::new(());
+			let weak = Arc::downgrade(&alive);
+			std::thread::spawn(move || {
+				while weak.strong_count() > 0 {
+					if let Some(value) = sample() {
+						sender.send(Some(value));
+					}
+					std::thread::sleep(interval);
+				}
+			});
+			alive
+		},
+		interval,
+	);
+	latest
+}
+
+/// Returns how busy the processors are, sampled from `/proc/stat` every `interval`.
+///
+/// The usage is measured over the interval, so it is `None` until the end of the first one.
+///
+/// ```rust,ignore
+/// let cpu = use_cpu_usage(Duration::from_secs(2));
+/// Text::new(match cpu {
+///     Some(cpu) => format!("CPU {:.0}%", cpu.total * 100.),
+///     None => "CPU".to_string(),
+/// })
+/// ```
+pub fn use_cpu_usage(interval: Duration) -> Option<CpuUsage> {
+	let mut previous: Option<Vec<(u64, u64)>> = None;
+	use_sampler(interval, move || {
+		let current = parse_cpu_times(&fs::read_to_string("/proc/stat").ok()?);
+		cpu_usage(&previous.replace(current.clone())?, &current)
+	})
+}
+
+/// Returns the memory and swap of the system, read from `/proc/meminfo` every `interval`.
+pub fn use_memory(interval: Duration) -> Option<MemoryUsage> {
+	use_sampler(interval, || {
+		Some(parse_meminfo(&fs::read_to_string("/proc/meminfo").ok()?))
+	})
+}
+
+/// Returns the bytes per second received and sent over the network, sampled from `/proc/net/dev` every
+/// `interval`.
+///
+/// The throughput is measured over the interval, so it is `None` until the end of the first one.
+pub fn use_network_throughput(interval: Duration) -> Option<NetworkThroughput> {
+	let mut previous = None;
+	use_sampler(interval, move || {
+		let counters = parse_net_dev(&fs::read_to_string("/proc/net/dev").ok()?);
+		let (received, sent) = per_second(&mut previous, counters)?;
+		Some(NetworkThroughput { received, sent })
+	})
+}
+
+/// Returns the bytes per second read from and written to the disks, sampled from `/proc/diskstats` every
+/// `interval`.
+///
+/// The throughput is measured over the interval, so it is `None` until the end of the first one.
+pub fn use_disk_throughput(interval: Duration) -> Option<DiskThroughput> {
+	let mut previous = None;
+	use_sampler(interval, move || {
+		let disks = physical_disks();
+		let counters = parse_diskstats(&fs::read_to_string("/proc/diskstats").ok()?, &disks);
+		let (read, written) = per_second(&mut previous, counters)?;
+		Some(DiskThroughput { read, written })
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_system_usage_is_parsed_from_proc() {
+		let before = parse_cpu_times(
+			"cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 50 0 50 350 50 0 0 0 0 0\ncpu1 50 0 50 350 50 0 0 0 0 0\nintr 1 2 3\n",
+		);
+		let after = parse_cpu_times(
+			"cpu  250 0 150 900 100 0 0 0 0 0\ncpu0 150 0 100 400 50 0 0 0 0 0\ncpu1 100 0 50 500 50 0 0 0 0 0\nintr 1 2 3\n",
+		);
+		assert_eq!(before, [(200, 1000), (100, 500), (100, 500)]);
+		assert_eq!(
+			cpu_usage(&before, &after),
+			Some(CpuUsage {
+				total: 0.5,
+				cores: vec![0.75, 0.25],
+			})
+		);
+
+		let memory = parse_meminfo(
+			"MemTotal:        8000 kB\nMemFree:          1000 kB\nMemAvailable:    6000 kB\nSwapTotal:       2000 kB\nSwapFree:        1500 kB\n",
+		);
+		assert_eq!(memory.used(), 2000 * 1024);
+		assert_eq!(memory.fraction_used(), 0.25);
+		assert_eq!(memory.swap_used(), 500 * 1024);
+
+		let net_dev = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:  900000   100    0    0    0     0          0         0   900000   100    0    0    0     0       0          0
+  eth0:    1000    10    0    0    0     0          0         0      200     2    0    0    0     0       0          0
+ wlan0:      24     1    0    0    0     0          0         0        6     1    0    0    0     0       0          0
+";
+		assert_eq!(parse_net_dev(net_dev), (1024, 206));
+
+		let diskstats = "   7       0 loop0 10 0 80 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 253       0 vda 100 0 2 50 40 0 4 30 0 60 80 0 0 0 0 0 0
+ 253       1 vda1 90 0 2 40 30 0 4 20 0 50 60 0 0 0 0 0 0
+";
+		assert_eq!(
+			parse_diskstats(diskstats, &["vda".to_string()]),
+			(1024, 2048)
+		);
+	}
+}
@@ -0,0 +1,134 @@
+//! Reloads the root component from a dynamic library whenever it is rebuilt, without restarting the app.
+//!
+//! The component code lives in a separate crate built as a `dylib` that exports the root component:
+//!
+//! ```rust,ignore
+//! #[unsafe(no_mangle)]
+//! pub fn root(props: ()) -> Box<dyn hyprui::Element> {
+//!     // ...
+//! }
+//! ```
+//!
+//! The app then runs it with [`create_hot_reload_window`] and `cargo watch -x build` (or similar) on the component crate.
+//! Hook states are kept by hyprui across reloads, so the UI doesn't lose its state, as long as the types stored in
+//! hooks don't change their layout. Both crates must link to the same `hyprui` (build with `-C prefer-dynamic`),
+//! otherwise the library gets its own copy of the hook states.
+use std::{
+	cell::RefCell,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+
+use libloading::Library;
+
+use crate::{Element, WindowOptions, create_window};
+
+/// How often the library file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+struct HotLibrary {
+	path: PathBuf,
+	symbol: String,
+	modified: Option<SystemTime>,
+	/// Loaded libraries are never unloaded, because hook states may still point to code from older versions.
+	libraries: Vec<Library>,
+	/// The root component, as a `fn(Props) -> Box<dyn Element>`.
+	component: Option<Box<dyn std::any::Any>>,
+}
+
+thread_local! {
+	static HOT_LIBRARY: RefCell<Option<HotLibrary>> = const { RefCell::new(None) };
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl HotLibrary {
+	/// Loads the library again if the file changed since the last load.
+	fn reload_if_changed<Props: 'static>(&mut self) {
+		let modified = modified(&self.path);
+		if self.component.is_some() && modified == self.modified {
+			return;
+		}
+		self.modified = modified;
+		// The dynamic loader caches libraries by path, so every version is loaded from its own copy. The copy is
+		// removed once loaded, the loaded library stays mapped.
+		let result = tempfile::Builder::new()
+			.prefix("hyprui-hot-")
+			.suffix(&format!(
+				"-{}",
+				self.path.file_name().unwrap_or_default().to_string_lossy()
+			))
+			.tempfile()
+			.and_then(|copy| std::fs::copy(&self.path, copy.path()).map(|_| copy))
+			.map_err(|e| e.to_string())
+			.and_then(|copy| unsafe { Library::new(copy.path()) }.map_err(|e| e.to_string()))
+			.and_then(|library| {
+				let component = unsafe {
+					*library
+						.get::<fn(Props) -> Box<dyn Element>>(self.symbol.as_bytes())
+						.map_err(|e| e.to_string())?
+				};
+				Ok((library, component))
+			});
+		match result {
+			Ok((library, component)) => {
+				log::info!("hot reload: loaded {}", self.path.display());
+				self.libraries.push(library);
+				self.component = Some(Box::new(component));
+			}
+			// Keep running the previous version, the library may still be being written.
+			Err(err) => log::error!("hot reload: failed to load {}: {err}", self.path.display()),
+		}
+	}
+}
+
+fn hot_component<Props: 'static>(props: Props) -> Box<dyn Element> {
+	let component = HOT_LIBRARY.with_borrow_mut(|library| {
+		let library = library.as_mut().expect("hot reload library not initialized");
+		library.reload_if_changed::<Props>();
+		library
+			.component
+			.as_ref()
+			.and_then(|c| c.downcast_ref::<fn(Props) -> Box<dyn Element>>())
+			.copied()
+	});
+	match component {
+		Some(component) => component(props),
+		None => Box::new(crate::Text::new("hot reload: the component library could not be loaded")),
+	}
+}
+
+/// Like [`create_window`], but the root component is the function `symbol` exported by the dynamic library at `library`.
+///
+/// The library is loaded again every time the file changes. See the [module documentation](self).
+pub fn create_hot_reload_window<Props: Clone + 'static>(
+	library: impl Into<PathBuf>,
+	symbol: &str,
+	props: Props,
+	options: WindowOptions,
+) {
+	let path = library.into();
+	HOT_LIBRARY.with_borrow_mut(|library| {
+		*library = Some(HotLibrary {
+			path: path.clone(),
+			symbol: symbol.to_string(),
+			modified: None,
+			libraries: Vec::new(),
+			component: None,
+		});
+	});
+	std::thread::spawn(move || {
+		let mut last = modified(&path);
+		loop {
+			std::thread::sleep(POLL_INTERVAL);
+			let current = modified(&path);
+			if current != last {
+				last = current;
+				crate::winit::wake_up();
+			}
+		}
+	});
+	create_window(hot_component::<Props>, props, options);
+}
@@ -0,0 +1,196 @@
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use uuid::Uuid;
+
+use crate::{GlobalClosure, InputManager, use_ref};
+
+/// A container under the pointer.
+struct Target {
+	container: Uuid,
+	/// Index of the closest container around it that is under the pointer too.
+	parent: Option<usize>,
+	/// Whether the containers below it stay hovered, see [`Container::hover_through`](crate::Container::hover_through).
+	through: bool,
+}
+
+thread_local! {
+	/// Containers under the pointer this frame, in paint order.
+	static TARGETS: RefCell<Vec<Target>> = const { RefCell::new(Vec::new()) };
+	/// Indices in `TARGETS` of the containers being declared.
+	static OPEN: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+	/// Containers under the pointer in the last frame, and the ones of them that were hovered.
+	static LAST_TARGETS: RefCell<HashSet<Uuid>> = RefCell::new(HashSet::new());
+	static LAST_HOVERED: RefCell<HashSet<Uuid>> = RefCell::new(HashSet::new());
+}
+
+/// Records that `container` is under the pointer and returns whether it is hovered. Call [`leave`] after its children.
+///
+/// A container is hovered if it is the topmost one under the pointer or one of its ancestors. Which one is on top
+/// is known once the frame is declared, so the result of the last frame is used: a container that just came under
+/// the pointer is hovered until the next frame tells otherwise.
+pub(crate) fn enter(container: Uuid, through: bool) -> bool {
+	let parent = OPEN.with_borrow(|open| open.last().copied());
+	let index = TARGETS.with_borrow_mut(|targets| {
+		targets.push(Target {
+			container,
+			parent,
+			through,
+		});
+		targets.len() - 1
+	});
+	OPEN.with_borrow_mut(|open| open.push(index));
+	through
+		|| !LAST_TARGETS.with_borrow(|targets| targets.contains(&container))
+		|| LAST_HOVERED.with_borrow(|hovered| hovered.contains(&container))
+}
+
+/// Closes `container` after its children were declared, if [`enter`] was called for it.
+pub(crate) fn leave(container: Uuid) {
+	let entered = OPEN.with_borrow(|open| {
+		open
+			.last()
+			.is_some_and(|&index| TARGETS.with_borrow(|targets| targets[index].container == container))
+	});
+	if entered {
+		OPEN.with_borrow_mut(|open| open.pop());
+	}
+}
+
+/// Resolves which of the containers under the pointer this frame are hovered, for the next frame.
+pub(crate) fn end_frame() {
+	let targets = TARGETS.with_borrow_mut(std::mem::take);
+	OPEN.with_borrow_mut(|open| open.clear());
+	let mut hovered = targets
+		.iter()
+		.filter(|target| target.through)
+		.map(|target| target.container)
+		.collect::<HashSet<_>>();
+	// Later containers are drawn above earlier ones, and overlays are declared last
+	let mut topmost = targets.iter().rposition(|target| !target.through);
+	while let Some(index) = topmost {
+		hovered.insert(targets[index].container);
+		topmost = targets[index].parent;
+	}
+	LAST_TARGETS.set(targets.iter().map(|target| target.container).collect());
+	LAST_HOVERED.set(hovered);
+}
+
+/// Whether the pointer is over a container and pressing it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct PointerFlags {
+	hovered: bool,
+	pressed: bool,
+}
+
+/// Follows the pointer over the container it is given to with [`Container::pointer_ref`](crate::Container::pointer_ref),
+/// returned by [`use_hover`] and [`use_pressed`].
+#[derive(Clone)]
+pub struct PointerRef {
+	flags: Rc<RefCell<PointerFlags>>,
+}
+
+impl PointerRef {
+	/// Called by the container on every frame, with whether it is hovered. Re-renders when the state changes.
+	pub(crate) fn update(&self, input: &dyn InputManager, hovered: bool) {
+		let mut flags = self.flags.borrow_mut();
+		// A press started over the container lasts until the button is released, even if the pointer leaves
+		let pressed = input.is_mouse_button_pressed(0)
+			&& (flags.pressed || (hovered && input.is_mouse_button_just_pressed(0)));
+		let new_flags = PointerFlags { hovered, pressed };
+		if *flags != new_flags {
+			*flags = new_flags;
+			crate::REQUEST_REDRAW.call();
+		}
+	}
+}
+
+/// Returns whether the pointer is over the container given the returned [`PointerRef`].
+///
+/// Unlike [`Container::on_mouse_enter`](crate::Container::on_mouse_enter), the container doesn't become interactive,
+/// so it suits visual effects like a glow following the pointer. The value is the one of the last frame, and the
+/// component is rendered again when it changes.
+///
+/// ```rust,ignore
+/// let (hovered, pointer) = use_hover();
+/// Container::new()
+///     .pointer_ref(&pointer)
+///     .elevation(if hovered { 4 } else { 1 })
+/// ```
+pub fn use_hover() -> (bool, PointerRef) {
+	let pointer = PointerRef {
+		flags: use_ref(PointerFlags::default()),
+	};
+	let hovered = pointer.flags.borrow().hovered;
+	(hovered, pointer)
+}
+
+/// Returns whether the left mouse button was pressed over the container given the returned [`PointerRef`] and is
+/// still held, like [`use_hover`].
+pub fn use_pressed() -> (bool, PointerRef) {
+	let pointer = PointerRef {
+		flags: use_ref(PointerFlags::default()),
+	};
+	let pressed = pointer.flags.borrow().pressed;
+	(pressed, pointer)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Element, Text, testing::TestHarness};
+
+	#[test]
+	fn test_only_the_topmost_container_stays_hovered() {
+		let [parent, below, above, through] = [(); 4].map(|_| Uuid::new_v4());
+		let declare = || {
+			let parent_hovered = enter(parent, false);
+			let below_hovered = enter(below, false);
+			leave(below);
+			let above_hovered = enter(above, false);
+			leave(above);
+			let through_hovered = enter(through, true);
+			leave(through);
+			leave(parent);
+			end_frame();
+			[
+				parent_hovered,
+				below_hovered,
+				above_hovered,
+				through_hovered,
+			]
+		};
+		assert_eq!(declare(), [true; 4]);
+		assert_eq!(declare(), [true, false, true, true]);
+	}
+
+	fn glow(_: ()) -> Box<dyn Element> {
+		let (hovered, hover) = crate::use_hover();
+		let (pressed, press) = crate::use_pressed();
+		Box::new(
+			Container::new()
+				.label("glow")
+				.pointer_ref(&hover)
+				.pointer_ref(&press)
+				.child(Text::new(format!("hovered {hovered} pressed {pressed}"))),
+		)
+	}
+
+	#[test]
+	fn test_pointer_hooks_without_click_handlers() {
+		let mut harness = TestHarness::new(glow, (), (400., 300.));
+		let (x, y) = harness.expect_label("glow").center().unwrap();
+		harness.move_pointer(x, y);
+		harness.frame();
+		harness.expect_label("hovered true pressed false");
+		harness.mouse_down(0);
+		harness.frame();
+		harness.expect_label("hovered true pressed true");
+		// The press lasts while the button is held outside of the container
+		harness.move_pointer(399., 299.);
+		harness.frame();
+		harness.expect_label("hovered false pressed true");
+		harness.mouse_up(0);
+		harness.frame();
+		harness.expect_label("hovered false pressed false");
+	}
+}
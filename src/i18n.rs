@@ -0,0 +1,227 @@
+//! Localization with [Fluent](https://projectfluent.org).
+//!
+//! Translations are `.ftl` files, one bundle per locale:
+//!
+//! ```ftl
+//! # locales/fr-FR/app.ftl
+//! greeting = Bonjour, { $name } !
+//! unread = { $count ->
+//!     [one] Un message non lu
+//!    *[other] { $count } messages non lus
+//! }
+//! ```
+//!
+//! They are loaded with [`load_translations`] or [`add_translations`], and looked up with the [`t!`](crate::t) macro,
+//! which also works inside RSML expressions:
+//!
+//! ```rust,ignore
+//! hyprui::i18n::load_translations("locales")?;
+//! rsml! {
+//!     <text>{t!("greeting", name = user_name)}</text>
+//! }
+//! ```
+//!
+//! The locale is detected from `LC_ALL`, `LC_MESSAGES` and `LANG`, and can be switched at runtime with [`set_locale`].
+use std::{cell::RefCell, collections::HashMap, io, path::Path};
+
+use fluent_bundle::{FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+#[doc(hidden)]
+pub use fluent_bundle::{FluentArgs, FluentValue};
+
+use crate::GlobalClosure;
+
+struct Localization {
+	bundles: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
+	locale: LanguageIdentifier,
+	/// Used for the messages missing from the current locale.
+	fallback: LanguageIdentifier,
+}
+
+thread_local! {
+	static LOCALIZATION: RefCell<Localization> = RefCell::new(Localization {
+		bundles: HashMap::new(),
+		locale: detect_locale().unwrap_or_default(),
+		fallback: "en-US".parse().unwrap(),
+	});
+}
+
+/// Converts a POSIX locale like `pt_BR.UTF-8@euro` to a language identifier like `pt-BR`.
+fn parse_posix_locale(locale: &str) -> Option<LanguageIdentifier> {
+	let locale = locale.split(['.', '@']).next()?;
+	if locale.is_empty() || locale == "C" || locale == "POSIX" {
+		return None;
+	}
+	locale.replace('_', "-").parse().ok()
+}
+
+fn detect_locale() -> Option<LanguageIdentifier> {
+	["LC_ALL", "LC_MESSAGES", "LANG"]
+		.into_iter()
+		.filter_map(|var| std::env::var(var).ok())
+		.find_map(|locale| parse_posix_locale(&locale))
+}
+
+/// Adds the messages of a `.ftl` source to the bundle of `locale`.
+///
+/// Fails with the syntax errors of the source, or if it redefines a message of the bundle.
+pub fn add_translations(locale: &str, source: &str) -> Result<(), String> {
+	let locale: LanguageIdentifier = locale
+		.parse()
+		.map_err(|err| format!("invalid locale {locale:?}: {err}"))?;
+	let resource = FluentResource::try_new(source.to_string())
+		.map_err(|(_, errors)| format!("invalid translations for {locale}: {errors:?}"))?;
+	LOCALIZATION.with_borrow_mut(|localization| {
+		let bundle = localization
+			.bundles
+			.entry(locale.clone())
+			.or_insert_with(|| {
+				let mut bundle = FluentBundle::new(vec![locale.clone()]);
+				// Isolation marks around arguments would show up as boxes in fonts that don't have them.
+				bundle.set_use_isolating(false);
+				bundle
+			});
+		bundle
+			.add_resource(resource)
+			.map_err(|errors| format!("invalid translations for {locale}: {errors:?}"))
+	})?;
+	crate::REQUEST_REDRAW.call();
+	Ok(())
+}
+
+/// Loads every `<dir>/<locale>/*.ftl` file, like `locales/fr-FR/app.ftl`.
+pub fn load_translations(dir: impl AsRef<Path>) -> io::Result<()> {
+	for locale_dir in std::fs::read_dir(dir)? {
+		let locale_dir = locale_dir?.path();
+		if !locale_dir.is_dir() {
+			continue;
+		}
+		let locale = locale_dir
+			.file_name()
+			.unwrap_or_default()
+			.to_string_lossy()
+			.into_owned();
+		for file in std::fs::read_dir(&locale_dir)? {
+			let file = file?.path();
+			if file.extension().is_some_and(|ext| ext == "ftl") {
+				add_translations(&locale, &std::fs::read_to_string(&file)?)
+					.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Returns the current locale, like `fr-FR`.
+pub fn locale() -> String {
+	LOCALIZATION.with_borrow(|localization| localization.locale.to_string())
+}
+
+/// Switches the locale and re-renders the window. The locale doesn't need to have translations.
+pub fn set_locale(locale: &str) -> Result<(), String> {
+	let locale = locale
+		.parse()
+		.map_err(|err| format!("invalid locale {locale:?}: {err}"))?;
+	LOCALIZATION.with_borrow_mut(|localization| localization.locale = locale);
+	crate::REQUEST_REDRAW.call();
+	Ok(())
+}
+
+/// Sets the locale used for messages missing from the current locale. Defaults to `en-US`.
+pub fn set_fallback_locale(locale: &str) -> Result<(), String> {
+	let locale = locale
+		.parse()
+		.map_err(|err| format!("invalid locale {locale:?}: {err}"))?;
+	LOCALIZATION.with_borrow_mut(|localization| localization.fallback = locale);
+	crate::REQUEST_REDRAW.call();
+	Ok(())
+}
+
+impl Localization {
+	/// Returns the bundles to look a message up in: the exact locale, the same language in another region, then the fallback.
+	fn candidates(&self) -> Vec<&FluentBundle<FluentResource>> {
+		let mut candidates = Vec::new();
+		candidates.extend(self.bundles.get(&self.locale));
+		candidates.extend(
+			self
+				.bundles
+				.iter()
+				.filter(|(locale, _)| **locale != self.locale && locale.language == self.locale.language)
+				.map(|(_, bundle)| bundle),
+		);
+		candidates.extend(self.bundles.get(&self.fallback));
+		candidates
+	}
+}
+
+/// Returns the message `key` in the current locale, or `key` itself if no bundle has it.
+///
+/// Prefer the [`t!`](crate::t) macro, which builds the arguments.
+pub fn translate(key: &str, args: Option<&FluentArgs>) -> String {
+	LOCALIZATION.with_borrow(|localization| {
+		for bundle in localization.candidates() {
+			let Some(pattern) = bundle.get_message(key).and_then(|message| message.value()) else {
+				continue;
+			};
+			let mut errors = Vec::new();
+			let text = bundle.format_pattern(pattern, args, &mut errors);
+			if !errors.is_empty() {
+				log::warn!("failed to format message {key:?}: {errors:?}");
+			}
+			return text.into_owned();
+		}
+		log::warn!("missing translation for {key:?} in {}", localization.locale);
+		key.to_string()
+	})
+}
+
+/// Translates a message in the current locale, with optional named arguments.
+///
+/// ```rust,ignore
+/// let title = t!("settings-title");
+/// let greeting = t!("greeting", name = "Ana", count = 3);
+/// ```
+///
+/// See the [`i18n`](crate::i18n) module.
+#[macro_export]
+macro_rules! t {
+	($key:expr) => {
+		$crate::i18n::translate($key, None)
+	};
+	($key:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+		let mut args = $crate::i18n::FluentArgs::new();
+		$(args.set(stringify!($name), $crate::i18n::FluentValue::from($value));)+
+		$crate::i18n::translate($key, Some(&args))
+	}};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_posix_locale() {
+		assert_eq!(
+			parse_posix_locale("pt_BR.UTF-8"),
+			Some("pt-BR".parse().unwrap())
+		);
+		assert_eq!(
+			parse_posix_locale("de_DE@euro"),
+			Some("de-DE".parse().unwrap())
+		);
+		assert_eq!(parse_posix_locale("C.UTF-8"), None);
+	}
+
+	#[test]
+	fn test_translate_with_fallback() {
+		add_translations("en-US", "greeting = Hello, { $name }!\nfarewell = Bye").unwrap();
+		add_translations("fr-FR", "greeting = Bonjour, { $name } !").unwrap();
+		set_locale("fr-CA").unwrap();
+		assert_eq!(crate::t!("greeting", name = "Ana"), "Bonjour, Ana !");
+		assert_eq!(crate::t!("farewell"), "Bye");
+		assert_eq!(crate::t!("missing"), "missing");
+		set_locale("en-US").unwrap();
+		assert_eq!(crate::t!("greeting", name = "Ana"), "Hello, Ana!");
+	}
+}
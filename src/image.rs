@@ -0,0 +1,347 @@
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	hash::{DefaultHasher, Hash, Hasher},
+	path::PathBuf,
+	sync::Arc,
+};
+
+use skia_safe::{Data, Image, Rect};
+
+/// Where an image is loaded from.
+///
+/// PNG, JPEG and WebP images are supported. Decoded images are cached, so the same source can be
+/// loaded on every frame without reading or decoding it again.
+///
+/// ```rust,ignore
+/// let logo = ImageSource::bytes(include_bytes!("logo.png").as_slice());
+/// let wallpaper = ImageSource::path("/usr/share/backgrounds/default.webp");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImageSource {
+	Path(PathBuf),
+	/// An encoded image, like one embedded with `include_bytes!`.
+	Bytes(Arc<[u8]>),
+	/// An `http` or `https` URL, downloaded in the background. Requires the `image-url` feature.
+	Url(String),
+}
+
+/// How far loading an [`ImageSource`] got, see [`ImageSource::load_state`].
+#[derive(Debug, Clone)]
+pub enum ImageLoadState {
+	/// The image is being downloaded, and a redraw will be requested once it is done.
+	Loading,
+	Loaded(Image),
+	/// Loading failed, the error was logged.
+	Failed,
+}
+
+enum CacheEntry {
+	Loaded(Image),
+	/// The image is being downloaded.
+	Loading,
+	/// Loading failed, the error was already logged.
+	Failed,
+}
+
+/// Identifies a source in the cache. Bytes are identified by their hash, to avoid keeping a second copy.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+	Path(PathBuf),
+	Bytes(u64),
+	Url(String),
+}
+
+thread_local! {
+	static IMAGE_CACHE: RefCell<HashMap<CacheKey, CacheEntry>> = RefCell::new(HashMap::new());
+	/// How the images declared this frame fit their element, by the address of the image they render.
+	static IMAGE_FITS: RefCell<HashMap<usize, ImageFit>> = RefCell::new(HashMap::new());
+}
+
+fn decode(bytes: &[u8]) -> Result<Image, String> {
+	Image::from_encoded(Data::new_copy(bytes))
+		.ok_or_else(|| "unsupported or corrupted image".to_string())
+}
+
+impl ImageSource {
+	pub fn path(path: impl Into<PathBuf>) -> Self {
+		Self::Path(path.into())
+	}
+
+	pub fn bytes(bytes: impl Into<Arc<[u8]>>) -> Self {
+		Self::Bytes(bytes.into())
+	}
+
+	pub fn url(url: impl Into<String>) -> Self {
+		Self::Url(url.into())
+	}
+
+	fn cache_key(&self) -> CacheKey {
+		match self {
+			ImageSource::Path(path) => CacheKey::Path(path.clone()),
+			ImageSource::Bytes(bytes) => {
+				let mut hasher = DefaultHasher::new();
+				bytes.hash(&mut hasher);
+				CacheKey::Bytes(hasher.finish())
+			}
+			ImageSource::Url(url) => CacheKey::Url(url.clone()),
+		}
+	}
+
+	/// Returns the decoded image, or `None` if it failed to load or is still being downloaded.
+	///
+	/// Errors are logged once per source. A redraw is requested when a download finishes.
+	pub fn load(&self) -> Option<Image> {
+		match self.load_state() {
+			ImageLoadState::Loaded(image) => Some(image),
+			ImageLoadState::Loading | ImageLoadState::Failed => None,
+		}
+	}
+
+	/// Like [`load`](Self::load), but tells apart images still being downloaded from the ones that failed to load,
+	/// to show a placeholder or a fallback.
+	pub fn load_state(&self) -> ImageLoadState {
+		#[cfg(feature = "image-url")]
+		url::apply_finished_downloads();
+		let key = self.cache_key();
+		if let Some(entry) = IMAGE_CACHE.with_borrow(|cache| {
+			cache.get(&key).map(|entry| match entry {
+				CacheEntry::Loaded(image) => ImageLoadState::Loaded(image.clone()),
+				CacheEntry::Loading => ImageLoadState::Loading,
+				CacheEntry::Failed => ImageLoadState::Failed,
+			})
+		}) {
+			return entry;
+		}
+		let entry = match self {
+			ImageSource::Path(path) => std::fs::read(path)
+				.map_err(|err| err.to_string())
+				.and_then(|bytes| decode(&bytes)),
+			ImageSource::Bytes(bytes) => decode(bytes),
+			#[cfg(feature = "image-url")]
+			ImageSource::Url(url) => {
+				url::download(key.clone(), url.clone());
+				IMAGE_CACHE.with_borrow_mut(|cache| cache.insert(key, CacheEntry::Loading));
+				return ImageLoadState::Loading;
+			}
+			#[cfg(not(feature = "image-url"))]
+			ImageSource::Url(_) => {
+				Err("loading images from URLs requires the `image-url` feature".to_string())
+			}
+		};
+		let image = entry
+			.inspect_err(|err| log::error!("failed to load image {self}: {err}"))
+			.ok();
+		IMAGE_CACHE.with_borrow_mut(|cache| {
+			cache.insert(
+				key,
+				image.clone().map_or(CacheEntry::Failed, CacheEntry::Loaded),
+			)
+		});
+		image.map_or(ImageLoadState::Failed, ImageLoadState::Loaded)
+	}
+}
+
+/// How an [`Image`](crate::Image) is drawn when its element doesn't have the aspect ratio of the image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ImageFit {
+	/// Stretches the image to the size of the element.
+	#[default]
+	Fill,
+	/// Scales the image to fit inside the element, leaving empty space on two sides.
+	Contain,
+	/// Scales the image to cover the element, cropping it on two sides.
+	Cover,
+}
+
+pub(crate) fn begin_frame() {
+	IMAGE_FITS.with_borrow_mut(|fits| fits.clear());
+}
+
+/// Sets how `image` is drawn this frame. The image is identified by its address, so it must not move until it is drawn.
+pub(crate) fn set_fit(image: &Image, fit: ImageFit) {
+	IMAGE_FITS.with_borrow_mut(|fits| fits.insert(image as *const Image as usize, fit));
+}
+
+pub(crate) fn fit_of(image: &Image) -> ImageFit {
+	IMAGE_FITS.with_borrow(|fits| {
+		fits
+			.get(&(image as *const Image as usize))
+			.copied()
+			.unwrap_or_default()
+	})
+}
+
+/// Returns the part of an image of `size` to draw, or `None` for all of it, and where to draw it inside `bounds`.
+pub(crate) fn fit_rects(fit: ImageFit, size: (f32, f32), bounds: Rect) -> (Option<Rect>, Rect) {
+	let image_ratio = size.0 / size.1;
+	let bounds_ratio = bounds.width() / bounds.height();
+	if !image_ratio.is_finite() || !bounds_ratio.is_finite() {
+		return (None, bounds);
+	}
+	match fit {
+		ImageFit::Fill => (None, bounds),
+		ImageFit::Contain => {
+			let (width, height) = if image_ratio > bounds_ratio {
+				(bounds.width(), bounds.width() / image_ratio)
+			} else {
+				(bounds.height() * image_ratio, bounds.height())
+			};
+			let x = bounds.left + (bounds.width() - width) / 2.;
+			let y = bounds.top + (bounds.height() - height) / 2.;
+			(None, Rect::from_xywh(x, y, width, height))
+		}
+		ImageFit::Cover => {
+			let (width, height) = if image_ratio > bounds_ratio {
+				(size.1 * bounds_ratio, size.1)
+			} else {
+				(size.0, size.0 / bounds_ratio)
+			};
+			let x = (size.0 - width) / 2.;
+			let y = (size.1 - height) / 2.;
+			(Some(Rect::from_xywh(x, y, width, height)), bounds)
+		}
+	}
+}
+
+impl std::fmt::Display for ImageSource {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ImageSource::Path(path) => write!(f, "{}", path.display()),
+			ImageSource::Bytes(bytes) => write!(f, "<{} bytes>", bytes.len()),
+			ImageSource::Url(url) => f.write_str(url),
+		}
+	}
+}
+
+impl From<PathBuf> for ImageSource {
+	fn from(path: PathBuf) -> Self {
+		Self::Path(path)
+	}
+}
+
+/// Strings starting with `http://` or `https://` are URLs, anything else is a path.
+impl From<&str> for ImageSource {
+	fn from(source: &str) -> Self {
+		if source.starts_with("http://") || source.starts_with("https://") {
+			Self::Url(source.to_string())
+		} else {
+			Self::Path(source.into())
+		}
+	}
+}
+
+impl From<String> for ImageSource {
+	fn from(source: String) -> Self {
+		source.as_str().into()
+	}
+}
+
+impl From<&'static [u8]> for ImageSource {
+	fn from(bytes: &'static [u8]) -> Self {
+		Self::Bytes(bytes.into())
+	}
+}
+
+impl From<Vec<u8>> for ImageSource {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self::Bytes(bytes.into())
+	}
+}
+
+/// Removes every image from the cache, so they are loaded again the next time they are used.
+pub fn clear_image_cache() {
+	IMAGE_CACHE.with_borrow_mut(|cache| cache.clear());
+	crate::element::svg::clear_cache();
+}
+
+#[cfg(feature = "image-url")]
+mod url {
+	use std::sync::Mutex;
+
+	use super::{CacheEntry, CacheKey, IMAGE_CACHE, decode};
+
+	/// Downloads that finished since the last frame. Images are decoded on the UI thread, because skia images can't be sent across threads.
+	static FINISHED_DOWNLOADS: Mutex<Vec<(CacheKey, String, Result<Vec<u8>, String>)>> =
+		Mutex::new(Vec::new());
+
+	pub(super) fn download(key: CacheKey, url: String) {
+		std::thread::spawn(move || {
+			let result = ureq::get(&url)
+				.call()
+				.and_then(|mut response| response.body_mut().read_to_vec())
+				.map_err(|err| err.to_string());
+			FINISHED_DOWNLOADS.lock().unwrap().push((key, url, result));
+			crate::winit::wake_up();
+		});
+	}
+
+	pub(super) fn apply_finished_downloads() {
+		let finished = std::mem::take(&mut *FINISHED_DOWNLOADS.lock().unwrap());
+		if finished.is_empty() {
+			return;
+		}
+		IMAGE_CACHE.with_borrow_mut(|cache| {
+			for (key, url, result) in finished {
+				let entry = match result.and_then(|bytes| decode(&bytes)) {
+					Ok(image) => CacheEntry::Loaded(image),
+					Err(err) => {
+						log::error!("failed to load image {url}: {err}");
+						CacheEntry::Failed
+					}
+				};
+				cache.insert(key, entry);
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use skia_safe::{Color, EncodedImageFormat, surfaces};
+
+	use super::*;
+
+	#[test]
+	fn test_load_from_bytes() {
+		let mut surface = surfaces::raster_n32_premul((4, 3)).unwrap();
+		surface.canvas().clear(Color::RED);
+		let png = surface
+			.image_snapshot()
+			.encode(None, EncodedImageFormat::PNG, None)
+			.unwrap()
+			.as_bytes()
+			.to_vec();
+		let image = ImageSource::from(png.clone()).load().unwrap();
+		assert_eq!((image.width(), image.height()), (4, 3));
+		let cached = ImageSource::bytes(png).load().unwrap();
+		assert_eq!(cached.unique_id(), image.unique_id());
+	}
+
+	#[test]
+	fn test_fit_rects() {
+		let bounds = Rect::from_xywh(10., 10., 100., 50.);
+		assert_eq!(fit_rects(ImageFit::Fill, (20., 20.), bounds), (None, bounds));
+		assert_eq!(
+			fit_rects(ImageFit::Contain, (20., 20.), bounds),
+			(None, Rect::from_xywh(35., 10., 50., 50.))
+		);
+		assert_eq!(
+			fit_rects(ImageFit::Cover, (20., 20.), bounds),
+			(Some(Rect::from_xywh(0., 5., 20., 10.)), bounds)
+		);
+		// Nothing to fit in an empty element
+		let empty = Rect::from_xywh(0., 0., 0., 0.);
+		assert_eq!(fit_rects(ImageFit::Cover, (20., 20.), empty), (None, empty));
+	}
+
+	#[test]
+	fn test_invalid_image_fails() {
+		assert!(ImageSource::bytes(&b"not an image"[..]).load().is_none());
+		assert!(matches!(
+			ImageSource::bytes(&b"not an image"[..]).load_state(),
+			ImageLoadState::Failed
+		));
+		assert!(ImageSource::path("/nonexistent/image.png").load().is_none());
+	}
+}
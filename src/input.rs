@@ -1,3 +1,4 @@
+pub(crate) mod recording;
 pub(crate) mod winit_impl;
 
 pub type Key = winit::keyboard::Key;
@@ -38,6 +39,13 @@ pub trait InputManager {
 	/// Check if the user is currently using an IME
 	fn ime_is_editing(&self) -> bool;
 
+	/// Get the distance scrolled this frame in logical pixels, positive towards the end of the content
+	///
+	/// Defaults to no scrolling, for input managers that don't get scroll events.
+	fn scroll_delta(&self) -> (f32, f32) {
+		(0., 0.)
+	}
+
 
 	fn set_cursor_clicked_something(&self);
 	fn cursor_hit_something(&self) -> bool;
@@ -0,0 +1,333 @@
+use std::{
+	fmt,
+	fs::File,
+	io::{self, Write},
+	path::Path,
+	time::{Duration, Instant},
+};
+
+use super::{Key, NamedKey};
+
+/// An input event as seen by the [`InputManager`](crate::InputManager), independent of the windowing backend.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+	PointerMoved { x: f32, y: f32 },
+	MouseButton { button: u16, pressed: bool },
+	Key { key: Key, pressed: bool },
+	/// Committed text, from the keyboard or an IME.
+	Text(String),
+	/// Scrolling by a distance in logical pixels, positive towards the end of the content.
+	Scroll { dx: f32, dy: f32 },
+}
+
+/// A list of timestamped input events that can be saved to a file and replayed.
+///
+/// Set `HYPRUI_RECORD_INPUT=<file>` to record a session and `HYPRUI_REPLAY_INPUT=<file>` to play it back
+/// in a window, or replay it in a test with [`TestHarness::replay`](crate::testing::TestHarness::replay).
+///
+/// The file has one event per line: the time in milliseconds since the start of the recording, followed by the event:
+///
+/// ```text
+/// 0 move 120.5 48
+/// 130 button 0 down
+/// 210 button 0 up
+/// 900 key named:Tab down
+/// 1000 text hello\n
+/// 1200 scroll 0 40
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+	pub events: Vec<(Duration, InputEvent)>,
+}
+
+/// Named keys that can be recorded. Other named keys are left out of recordings.
+const NAMED_KEYS: &[NamedKey] = &[
+	NamedKey::Enter,
+	NamedKey::Tab,
+	NamedKey::Space,
+	NamedKey::Escape,
+	NamedKey::Backspace,
+	NamedKey::Delete,
+	NamedKey::Insert,
+	NamedKey::ArrowUp,
+	NamedKey::ArrowDown,
+	NamedKey::ArrowLeft,
+	NamedKey::ArrowRight,
+	NamedKey::Home,
+	NamedKey::End,
+	NamedKey::PageUp,
+	NamedKey::PageDown,
+	NamedKey::Shift,
+	NamedKey::Control,
+	NamedKey::Alt,
+	NamedKey::Super,
+	NamedKey::ContextMenu,
+	NamedKey::F1,
+	NamedKey::F2,
+	NamedKey::F3,
+	NamedKey::F4,
+	NamedKey::F5,
+	NamedKey::F6,
+	NamedKey::F7,
+	NamedKey::F8,
+	NamedKey::F9,
+	NamedKey::F10,
+	NamedKey::F11,
+	NamedKey::F12,
+];
+
+fn escape(text: &str) -> String {
+	text
+		.replace('\\', "\\\\")
+		.replace('\n', "\\n")
+		.replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+	let mut output = String::with_capacity(text.len());
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		if c == '\\' {
+			match chars.next() {
+				Some('n') => output.push('\n'),
+				Some('r') => output.push('\r'),
+				Some(other) => output.push(other),
+				None => output.push('\\'),
+			}
+		} else {
+			output.push(c);
+		}
+	}
+	output
+}
+
+fn format_key(key: &Key) -> Option<String> {
+	match key {
+		Key::Named(named) if NAMED_KEYS.contains(named) => Some(format!("named:{named:?}")),
+		Key::Character(c) => Some(format!("char:{}", escape(c))),
+		_ => None,
+	}
+}
+
+fn parse_key(key: &str) -> Option<Key> {
+	if let Some(name) = key.strip_prefix("named:") {
+		NAMED_KEYS
+			.iter()
+			.find(|named| format!("{named:?}") == name)
+			.map(|named| Key::Named(*named))
+	} else {
+		key
+			.strip_prefix("char:")
+			.map(|c| Key::Character(unescape(c).into()))
+	}
+}
+
+impl InputEvent {
+	/// Formats the event as a line of a recording, or `None` if it can't be recorded.
+	fn to_line(&self) -> Option<String> {
+		Some(match self {
+			InputEvent::PointerMoved { x, y } => format!("move {x} {y}"),
+			InputEvent::MouseButton { button, pressed } => {
+				format!("button {button} {}", if *pressed { "down" } else { "up" })
+			}
+			InputEvent::Key { key, pressed } => {
+				format!("key {} {}", format_key(key)?, if *pressed { "down" } else { "up" })
+			}
+			InputEvent::Text(text) => format!("text {}", escape(text)),
+			InputEvent::Scroll { dx, dy } => format!("scroll {dx} {dy}"),
+		})
+	}
+
+	fn parse(line: &str) -> Option<Self> {
+		let (kind, args) = line.split_once(' ')?;
+		let pressed = |state: &str| match state {
+			"down" => Some(true),
+			"up" => Some(false),
+			_ => None,
+		};
+		match kind {
+			"move" => {
+				let (x, y) = args.split_once(' ')?;
+				Some(InputEvent::PointerMoved {
+					x: x.parse().ok()?,
+					y: y.parse().ok()?,
+				})
+			}
+			"button" => {
+				let (button, state) = args.split_once(' ')?;
+				Some(InputEvent::MouseButton {
+					button: button.parse().ok()?,
+					pressed: pressed(state)?,
+				})
+			}
+			"key" => {
+				let (key, state) = args.rsplit_once(' ')?;
+				Some(InputEvent::Key {
+					key: parse_key(key)?,
+					pressed: pressed(state)?,
+				})
+			}
+			"text" => Some(InputEvent::Text(unescape(args))),
+			"scroll" => {
+				let (dx, dy) = args.split_once(' ')?;
+				Some(InputEvent::Scroll {
+					dx: dx.parse().ok()?,
+					dy: dy.parse().ok()?,
+				})
+			}
+			_ => None,
+		}
+	}
+}
+
+impl InputRecording {
+	/// Parses a recording, failing with the number of the first invalid line.
+	pub fn parse(recording: &str) -> Result<Self, String> {
+		let events = recording
+			.lines()
+			.enumerate()
+			.filter(|(_, line)| !line.trim().is_empty())
+			.map(|(number, line)| {
+				line
+					.split_once(' ')
+					.and_then(|(time, event)| {
+						Some((
+							Duration::from_millis(time.parse().ok()?),
+							InputEvent::parse(event)?,
+						))
+					})
+					.ok_or_else(|| format!("invalid input event on line {}: {line:?}", number + 1))
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { events })
+	}
+
+	pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+		Self::parse(&std::fs::read_to_string(path)?)
+			.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+	}
+
+	pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		std::fs::write(path, self.to_string())
+	}
+}
+
+impl fmt::Display for InputRecording {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (time, event) in &self.events {
+			if let Some(line) = event.to_line() {
+				writeln!(f, "{} {line}", time.as_millis())?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Writes input events to a file as they happen, so the recording survives a crash.
+pub(crate) struct InputRecorder {
+	file: File,
+	start: Instant,
+}
+
+impl InputRecorder {
+	pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+		Ok(Self {
+			file: File::create(path)?,
+			start: Instant::now(),
+		})
+	}
+
+	pub fn record(&mut self, event: &InputEvent) {
+		if let Some(line) = event.to_line() {
+			let time = self.start.elapsed().as_millis();
+			if let Err(err) = writeln!(self.file, "{time} {line}") {
+				log::error!("failed to record input event: {err}");
+			}
+		}
+	}
+}
+
+/// Feeds the events of a recording back at the pace they were recorded.
+pub(crate) struct InputReplay {
+	recording: InputRecording,
+	start: Instant,
+	next: usize,
+}
+
+impl InputReplay {
+	pub fn new(recording: InputRecording) -> Self {
+		Self {
+			recording,
+			start: Instant::now(),
+			next: 0,
+		}
+	}
+
+	/// Returns the events that are due since the last call.
+	pub fn due_events(&mut self) -> Vec<InputEvent> {
+		let elapsed = self.start.elapsed();
+		let due = self.recording.events[self.next..]
+			.iter()
+			.take_while(|(time, _)| *time <= elapsed)
+			.map(|(_, event)| event.clone())
+			.collect::<Vec<_>>();
+		self.next += due.len();
+		due
+	}
+
+	/// Wakes the event loop up whenever an event is due, so it is applied even if nothing else happens.
+	pub fn spawn_wake_up_thread(&self) {
+		let start = self.start;
+		let times = self
+			.recording
+			.events
+			.iter()
+			.map(|(time, _)| *time)
+			.collect::<Vec<_>>();
+		std::thread::spawn(move || {
+			for time in times {
+				std::thread::sleep(time.saturating_sub(start.elapsed()));
+				crate::winit::wake_up();
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_recording_round_trip() {
+		let recording = InputRecording {
+			events: vec![
+				(Duration::ZERO, InputEvent::PointerMoved { x: 12.5, y: 40. }),
+				(
+					Duration::from_millis(100),
+					InputEvent::MouseButton { button: 0, pressed: true },
+				),
+				(
+					Duration::from_millis(150),
+					InputEvent::Key {
+						key: Key::Named(NamedKey::Tab),
+						pressed: true,
+					},
+				),
+				(
+					Duration::from_millis(160),
+					InputEvent::Key {
+						key: Key::Character(" ".into()),
+						pressed: false,
+					},
+				),
+				(Duration::from_millis(200), InputEvent::Text("a\\b\nc".into())),
+			],
+		};
+		assert_eq!(InputRecording::parse(&recording.to_string()), Ok(recording));
+	}
+
+	#[test]
+	fn test_invalid_line_is_reported() {
+		assert!(InputRecording::parse("0 move 1 2\nnonsense").is_err());
+	}
+}
@@ -5,7 +5,10 @@ use winit::{
 	keyboard::Key,
 };
 
-use crate::input::InputManager;
+use crate::input::{
+	InputManager,
+	recording::{InputEvent, InputRecorder},
+};
 
 pub struct WinitInputManager {
 	mouse_position: (f32, f32),
@@ -19,7 +22,9 @@ pub struct WinitInputManager {
 	text_ime_buffer_cursor: (usize, usize),
 	ime_editing: bool,
 	bytes_to_remove: (usize, usize),
-	has_clicked_on_something: AtomicBool
+	scroll_delta: (f32, f32),
+	has_clicked_on_something: AtomicBool,
+	recorder: Option<InputRecorder>,
 }
 
 impl WinitInputManager {
@@ -36,7 +41,9 @@ impl WinitInputManager {
 			text_ime_buffer_cursor: (0, 0),
 			ime_editing: false,
 			bytes_to_remove: (0, 0),
-			has_clicked_on_something: Default::default()
+			scroll_delta: (0., 0.),
+			has_clicked_on_something: Default::default(),
+			recorder: None,
 		}
 	}
 
@@ -48,17 +55,48 @@ impl WinitInputManager {
 		self.keys_previous = self.keys_current.clone();
 		self.text_input.clear();
 		self.bytes_to_remove = (0, 0);
+		self.scroll_delta = (0., 0.);
+	}
+
+	/// Writes every input event received from now on to a recording file.
+	pub fn start_recording(&mut self, recorder: InputRecorder) {
+		self.recorder = Some(recorder);
+	}
+
+	fn record(&mut self, event: InputEvent) {
+		if let Some(recorder) = &mut self.recorder {
+			recorder.record(&event);
+		}
+	}
+
+	/// Applies an event coming from a recording or a test.
+	pub fn apply_event(&mut self, event: InputEvent) {
+		match event {
+			InputEvent::PointerMoved { x, y } => self.set_mouse_position(x, y),
+			InputEvent::MouseButton { button, pressed } => self.set_mouse_button(button, pressed),
+			InputEvent::Key { key, pressed } => self.set_key(key, pressed),
+			InputEvent::Text(text) => self.push_text_input(&text),
+			InputEvent::Scroll { dx, dy } => self.scroll(dx, dy),
+		}
 	}
 
 	pub fn set_mouse_position(&mut self, x: f32, y: f32) {
+		self.record(InputEvent::PointerMoved { x, y });
 		self.mouse_position = (x, y);
 	}
 
 	pub fn set_mouse_button(&mut self, button: u16, pressed: bool) {
+		self.record(InputEvent::MouseButton { button, pressed });
 		self.mouse_buttons_current.insert(button, pressed);
 		self.mouse_buttons_pressed.insert(button, pressed);
 	}
 
+	pub fn scroll(&mut self, dx: f32, dy: f32) {
+		self.record(InputEvent::Scroll { dx, dy });
+		self.scroll_delta.0 += dx;
+		self.scroll_delta.1 += dy;
+	}
+
 	pub fn handle_key_event(&mut self, event: KeyEvent) {
 		// Block the app from trying to handle keyboard shortcuts while IME is active (for example Tab for focus)
 		if self.ime_editing {
@@ -71,7 +109,18 @@ impl WinitInputManager {
 			ElementState::Pressed => true,
 			ElementState::Released => false,
 		};
-		self.keys_current.insert(event.logical_key, pressed);
+		self.set_key(event.logical_key, pressed);
+	}
+	pub fn set_key(&mut self, key: Key, pressed: bool) {
+		self.record(InputEvent::Key {
+			key: key.clone(),
+			pressed,
+		});
+		self.keys_current.insert(key, pressed);
+	}
+	pub fn push_text_input(&mut self, text: &str) {
+		self.record(InputEvent::Text(text.to_string()));
+		self.text_input.push_str(text);
 	}
 	pub fn handle_ime_event(&mut self, ime: Ime) {
 		match ime {
@@ -84,7 +133,7 @@ impl WinitInputManager {
 			}
 			Ime::Commit(text) => {
 				self.ime_editing = false;
-				self.text_input.push_str(&text);
+				self.push_text_input(&text);
 			}
 			Ime::DeleteSurrounding {
 				before_bytes,
@@ -186,4 +235,8 @@ impl InputManager for WinitInputManager {
 	fn bytes_to_remove(&self) -> (usize, usize) {
 		self.bytes_to_remove
 	}
+
+	fn scroll_delta(&self) -> (f32, f32) {
+		self.scroll_delta
+	}
 }
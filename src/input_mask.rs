@@ -0,0 +1,265 @@
+//! Formatting text fields as the user types.
+//!
+//! An [`InputMask`] turns what was typed into the text a field shows, dropping the characters that don't fit and
+//! adding separators, like the commas of `1,234,567`. [`TextInput::mask`](crate::TextInput::mask) applies it after
+//! every edit. The caret stays after the same character it was after before formatting, found by counting the
+//! characters the mask keeps from the typed text, which it tells apart from the separators it adds with
+//! [`InputMask::is_significant`].
+
+/// Formats the text of a field after each edit.
+///
+/// Functions from `&str` to `String` are masks whose separators are every character that isn't a letter or a digit.
+pub trait InputMask {
+	/// Returns `text` formatted, without the characters that don't fit.
+	///
+	/// Formatting text that is already formatted must not change it.
+	fn format(&self, text: &str) -> String;
+
+	/// Returns `true` for characters kept from the typed text, and `false` for the separators the mask adds.
+	fn is_significant(&self, c: char) -> bool {
+		c.is_alphanumeric()
+	}
+
+	/// Formats `text` and moves `caret`, a byte offset, to the same place in the formatted text.
+	fn apply(&self, text: &str, caret: usize) -> (String, usize) {
+		let before = &text[..caret];
+		let kept = before.chars().filter(|&c| self.is_significant(c)).count();
+		// The caret stays after a separator that was typed, so the next digit goes after it
+		let after_separator = before
+			.chars()
+			.next_back()
+			.is_some_and(|c| !self.is_significant(c));
+
+		let formatted = self.format(text);
+		let mut seen = 0;
+		let mut caret = formatted.len();
+		for (i, c) in formatted.char_indices() {
+			let significant = self.is_significant(c);
+			if seen == kept && (significant || !after_separator) {
+				caret = i;
+				break;
+			}
+			if significant {
+				seen += 1;
+			}
+		}
+		(formatted, caret)
+	}
+}
+
+impl<F: Fn(&str) -> String> InputMask for F {
+	fn format(&self, text: &str) -> String {
+		self(text)
+	}
+}
+
+/// A decimal number with the digits of its integer part grouped by three, like `-1,234,567.89`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThousandsMask {
+	separator: char,
+	decimal_mark: char,
+	decimals: Option<usize>,
+}
+
+impl ThousandsMask {
+	/// Groups digits with commas, with a dot before the decimals.
+	pub fn new() -> Self {
+		Self {
+			separator: ',',
+			decimal_mark: '.',
+			decimals: None,
+		}
+	}
+
+	/// The character between groups of digits, like `.` or a space in most of Europe.
+	pub fn separator(mut self, separator: char) -> Self {
+		self.separator = separator;
+		self
+	}
+
+	/// The character before the decimals, like `,` in most of Europe.
+	pub fn decimal_mark(mut self, decimal_mark: char) -> Self {
+		self.decimal_mark = decimal_mark;
+		self
+	}
+
+	/// Limits the number of decimals, with `0` for whole numbers.
+	pub fn decimals(mut self, decimals: usize) -> Self {
+		self.decimals = Some(decimals);
+		self
+	}
+}
+
+impl Default for ThousandsMask {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl InputMask for ThousandsMask {
+	fn format(&self, text: &str) -> String {
+		let mut negative = false;
+		let mut integer = Vec::new();
+		let mut fraction: Option<String> = None;
+		for c in text.chars() {
+			match (&mut fraction, c) {
+				(None, '-') if !negative && integer.is_empty() => negative = true,
+				(None, c) if c.is_ascii_digit() => integer.push(c),
+				(None, c) if c == self.decimal_mark && self.decimals != Some(0) => {
+					fraction = Some(String::new());
+				}
+				(Some(fraction), c)
+					if c.is_ascii_digit() && self.decimals.is_none_or(|max| fraction.len() < max) =>
+				{
+					fraction.push(c);
+				}
+				_ => {}
+			}
+		}
+
+		let mut formatted = String::new();
+		if negative {
+			formatted.push('-');
+		}
+		for (i, digit) in integer.iter().enumerate() {
+			if i > 0 && (integer.len() - i) % 3 == 0 {
+				formatted.push(self.separator);
+			}
+			formatted.push(*digit);
+		}
+		if let Some(fraction) = fraction {
+			formatted.push(self.decimal_mark);
+			formatted.push_str(&fraction);
+		}
+		formatted
+	}
+
+	fn is_significant(&self, c: char) -> bool {
+		c.is_ascii_digit() || c == '-' || c == self.decimal_mark
+	}
+}
+
+/// An IPv4 address, like `192.168.1.10`.
+///
+/// A dot is added when a number can't take another digit, so an address can be typed without dots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IpAddressMask;
+
+impl InputMask for IpAddressMask {
+	fn format(&self, text: &str) -> String {
+		let mut groups = vec![String::new()];
+		for c in text.chars() {
+			let group = groups.last_mut().unwrap();
+			if c.is_ascii_digit() {
+				let full = group.len() == 3
+					|| group == "0"
+					|| format!("{group}{c}").parse::<u16>().is_ok_and(|n| n > 255);
+				if !full {
+					group.push(c);
+				} else if groups.len() < 4 {
+					groups.push(c.to_string());
+				}
+			} else if c == '.' && !group.is_empty() && groups.len() < 4 {
+				groups.push(String::new());
+			}
+		}
+		groups.join(".")
+	}
+
+	fn is_significant(&self, c: char) -> bool {
+		c.is_ascii_digit()
+	}
+}
+
+/// A time of the day, `HH:MM`.
+///
+/// The colon is added once the minutes are typed, or kept when typed after the hours. Hours above 23 and minutes
+/// above 59 are clamped once both of their digits are typed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeMask;
+
+impl InputMask for TimeMask {
+	fn format(&self, text: &str) -> String {
+		let mut digits = text
+			.chars()
+			.filter(char::is_ascii_digit)
+			.take(4)
+			.collect::<String>();
+		// Clamping keeps the number of digits, so the caret stays in place
+		if digits.len() >= 2 && digits[..2].parse::<u8>().is_ok_and(|hours| hours > 23) {
+			digits.replace_range(..2, "23");
+		}
+		if digits.len() == 4 && digits[2..].parse::<u8>().is_ok_and(|minutes| minutes > 59) {
+			digits.replace_range(2.., "59");
+		}
+		if digits.len() > 2 || (digits.len() == 2 && text.trim_end().ends_with(':')) {
+			format!("{}:{}", &digits[..2], &digits[2..])
+		} else {
+			digits
+		}
+	}
+
+	fn is_significant(&self, c: char) -> bool {
+		c.is_ascii_digit()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Types `typed` at the end of `text`, as a field with `mask` would.
+	fn type_at_end(mask: &dyn InputMask, text: &str, typed: &str) -> (String, usize) {
+		let text = format!("{text}{typed}");
+		mask.apply(&text, text.len())
+	}
+
+	#[test]
+	fn test_masks_format_typed_text() {
+		let number = ThousandsMask::new();
+		assert_eq!(number.format("1234567.891"), "1,234,567.891");
+		assert_eq!(number.format("-12a34.5.6"), "-1,234.56");
+		assert_eq!(
+			ThousandsMask::new()
+				.separator('.')
+				.decimal_mark(',')
+				.decimals(1)
+				.format("12345,678"),
+			"12.345,6"
+		);
+
+		assert_eq!(IpAddressMask.format("19216801"), "192.168.0.1");
+		assert_eq!(IpAddressMask.format("10.0.0.255.1"), "10.0.0.255");
+		assert_eq!(
+			type_at_end(&IpAddressMask, "10", "."),
+			("10.".to_string(), 3)
+		);
+
+		assert_eq!(TimeMask.format("0930"), "09:30");
+		assert_eq!(TimeMask.format("9:"), "9");
+		assert_eq!(type_at_end(&TimeMask, "12", ":"), ("12:".to_string(), 3));
+		assert_eq!(TimeMask.format("2975"), "23:59");
+		assert_eq!(TimeMask.format("23:59"), "23:59");
+		assert_eq!(
+			type_at_end(&TimeMask, "12:6", "1"),
+			("12:59".to_string(), 5)
+		);
+	}
+
+	#[test]
+	fn test_caret_stays_after_the_same_character() {
+		let number = ThousandsMask::new();
+		// Typing 5 at the start of 1,234
+		assert_eq!(number.apply("51,234", 1), ("51,234".to_string(), 1));
+		// Typing 0 after the 2 of 1,234
+		assert_eq!(number.apply("1,2034", 4), ("12,034".to_string(), 4));
+		// Deleting the 3 of 12,345 with the caret after it
+		assert_eq!(number.apply("12,45", 3), ("1,245".to_string(), 3));
+		assert_eq!(type_at_end(&number, "999", "9"), ("9,999".to_string(), 5));
+		// A letter is dropped, the caret stays at the end
+		assert_eq!(type_at_end(&number, "12", "x"), ("12".to_string(), 2));
+
+		// Deleting the 3 of 12:34
+		assert_eq!(TimeMask.apply("12:4", 3), ("12:4".to_string(), 3));
+	}
+}
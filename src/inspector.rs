@@ -0,0 +1,394 @@
+use std::cell::{Cell, RefCell};
+
+use clay_layout::{id::Id, math::BoundingBox};
+use skia_safe::{Canvas, Color, Font, FontMgr, FontStyle, Paint, Point, RRect, Rect, Typeface};
+
+use crate::{Direction, GlobalClosure, InputManager, NamedKey, input::Key};
+
+/// Layout properties of a container, used to draw the padding, gaps and sizing modes.
+#[derive(Default, Clone)]
+pub(crate) struct EntryLayout {
+	/// `(left, right, top, bottom)`.
+	pub padding: (u16, u16, u16, u16),
+	pub gap: u16,
+	pub direction: Option<Direction>,
+	/// Short description of the sizing mode, like `grow × fit`.
+	pub sizing: Option<String>,
+}
+
+/// An element of the tree shown by the inspector.
+pub(crate) struct InspectorEntry {
+	pub depth: usize,
+	pub name: String,
+	/// Resolved style or text config, one property per line.
+	pub details: Vec<String>,
+	pub layout: EntryLayout,
+	/// The id the element was declared with in clay, if any.
+	pub element_id: Option<String>,
+	pub clay_id: Option<Id>,
+	pub bounds: Option<BoundingBox>,
+}
+
+thread_local! {
+	static ENABLED: Cell<bool> = Cell::new(std::env::var_os("HYPRUI_INSPECTOR").is_some());
+	static SHOW_HOOKS: Cell<bool> = const { Cell::new(false) };
+	static OUTLINES_ENABLED: Cell<bool> = Cell::new(std::env::var_os("HYPRUI_DEBUG_OUTLINES").is_some());
+	static ENTRIES: RefCell<Vec<InspectorEntry>> = RefCell::new(Vec::new());
+	static LAST_ENTRIES: RefCell<Vec<InspectorEntry>> = RefCell::new(Vec::new());
+	static DEPTH: Cell<usize> = Cell::new(0);
+	static LAYOUT_RECORDING: Cell<bool> = Cell::new(std::env::var_os("HYPRUI_DUMP_LAYOUT").is_some());
+	static PRINT_LAYOUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The key that toggles the inspector.
+const TOGGLE_KEY: NamedKey = NamedKey::F12;
+/// The key that switches the inspector panel between the element tree and the hook states.
+const HOOKS_TOGGLE_KEY: NamedKey = NamedKey::F10;
+/// The key that toggles the layout debug outlines.
+const OUTLINES_TOGGLE_KEY: NamedKey = NamedKey::F11;
+/// The key that prints the layout of the next frame to stderr.
+const DUMP_LAYOUT_KEY: NamedKey = NamedKey::F9;
+
+/// Returns `true` if the inspector overlay is shown.
+///
+/// It starts enabled when `HYPRUI_INSPECTOR` is set, and is toggled with `F12`.
+/// While it is shown, `F10` switches between the element tree and the live hook states.
+pub fn inspector_enabled() -> bool {
+	ENABLED.get()
+}
+
+/// Shows or hides the inspector overlay.
+pub fn set_inspector_enabled(enabled: bool) {
+	ENABLED.set(enabled);
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Returns `true` if layout debug outlines are drawn.
+///
+/// When enabled, every container is outlined, its padding and the gaps between its children are
+/// filled with translucent colors and its sizing mode is written in its corner.
+/// It starts enabled when `HYPRUI_DEBUG_OUTLINES` is set, and is toggled with `F11`.
+pub fn debug_outlines_enabled() -> bool {
+	OUTLINES_ENABLED.get()
+}
+
+/// Shows or hides the layout debug outlines.
+pub fn set_debug_outlines(enabled: bool) {
+	OUTLINES_ENABLED.set(enabled);
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Returns `true` if the layout of every frame is recorded for [`dump_layout`].
+pub fn layout_recording_enabled() -> bool {
+	LAYOUT_RECORDING.get()
+}
+
+/// Records the layout of every frame, so it can be read with [`dump_layout`].
+///
+/// It starts enabled when `HYPRUI_DUMP_LAYOUT` is set. The layout is also recorded while the inspector
+/// or the debug outlines are shown.
+pub fn set_layout_recording(enabled: bool) {
+	LAYOUT_RECORDING.set(enabled);
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Returns the computed layout of the last recorded frame as an indented tree.
+///
+/// Each line has the element, its id, its bounding box and the sizing decisions that produced it:
+///
+/// ```text
+/// Container Button "Save" #5f0c…  at (16, 16) 80 × 32  sizing: fit × fit  padding: (8, 8, 4, 4)  gap: 0  Row
+///   Text "Save"
+/// ```
+///
+/// Frames are only recorded while [`set_layout_recording`], the inspector or the debug outlines are enabled,
+/// otherwise the tree is empty. Pressing `F9` in a window prints the layout of the current frame to stderr.
+pub fn dump_layout() -> String {
+	LAST_ENTRIES.with_borrow(|entries| format_layout(entries))
+}
+
+fn format_layout(entries: &[InspectorEntry]) -> String {
+	let mut output = String::new();
+	for entry in entries {
+		output += &"  ".repeat(entry.depth);
+		output += &entry.name;
+		if let Some(id) = &entry.element_id {
+			output += &format!(" #{id}");
+		}
+		if let Some(bounds) = &entry.bounds {
+			output += &format!(
+				"  at ({}, {}) {} × {}",
+				bounds.x, bounds.y, bounds.width, bounds.height
+			);
+		}
+		let layout = &entry.layout;
+		if let Some(sizing) = &layout.sizing {
+			output += &format!("  sizing: {sizing}");
+		}
+		if let Some(direction) = layout.direction {
+			output += &format!("  padding: {:?}  gap: {}  {direction:?}", layout.padding, layout.gap);
+		}
+		output.push('\n');
+	}
+	output
+}
+
+/// Returns `true` if elements should record their layout for the inspector, the debug outlines or [`dump_layout`].
+pub(crate) fn is_collecting() -> bool {
+	inspector_enabled() || debug_outlines_enabled() || LAYOUT_RECORDING.get() || PRINT_LAYOUT.get()
+}
+
+pub(crate) fn begin_frame(input_manager: &dyn InputManager) {
+	if input_manager.is_key_just_pressed(Key::Named(TOGGLE_KEY)) {
+		ENABLED.set(!ENABLED.get());
+	}
+	if inspector_enabled() && input_manager.is_key_just_pressed(Key::Named(HOOKS_TOGGLE_KEY)) {
+		SHOW_HOOKS.set(!SHOW_HOOKS.get());
+	}
+	if input_manager.is_key_just_pressed(Key::Named(OUTLINES_TOGGLE_KEY)) {
+		OUTLINES_ENABLED.set(!OUTLINES_ENABLED.get());
+	}
+	if input_manager.is_key_just_pressed(Key::Named(DUMP_LAYOUT_KEY)) {
+		PRINT_LAYOUT.set(true);
+	}
+	ENTRIES.with_borrow_mut(|entries| entries.clear());
+	DEPTH.set(0);
+}
+
+/// Adds an element at the current depth. Elements pushed until the matching [`pop_entry`] are its children.
+///
+/// Returns the index of the entry, to fill in its details once they are resolved.
+pub(crate) fn push_entry(name: String, element_id: Option<&str>, clay_id: Option<Id>) -> usize {
+	let depth = DEPTH.get();
+	DEPTH.set(depth + 1);
+	ENTRIES.with_borrow_mut(|entries| {
+		entries.push(InspectorEntry {
+			depth,
+			name,
+			details: Vec::new(),
+			layout: EntryLayout::default(),
+			element_id: element_id.map(String::from),
+			clay_id,
+			bounds: None,
+		});
+		entries.len() - 1
+	})
+}
+
+pub(crate) fn pop_entry() {
+	DEPTH.set(DEPTH.get().saturating_sub(1));
+}
+
+pub(crate) fn set_details(index: usize, details: Vec<String>, layout: EntryLayout) {
+	ENTRIES.with_borrow_mut(|entries| {
+		if let Some(entry) = entries.get_mut(index) {
+			entry.details = details;
+			entry.layout = layout;
+		}
+	});
+}
+
+/// Resolves the bounds of every entry after layout.
+pub(crate) fn end_frame(bounding_box: impl Fn(Id) -> Option<BoundingBox>) {
+	let mut entries = ENTRIES.with_borrow_mut(std::mem::take);
+	for entry in &mut entries {
+		entry.bounds = entry.clay_id.clone().and_then(&bounding_box);
+	}
+	if PRINT_LAYOUT.replace(false) {
+		eprint!("{}", format_layout(&entries));
+	}
+	LAST_ENTRIES.with_borrow_mut(|last| *last = entries);
+}
+
+fn to_rect(bounds: &BoundingBox) -> Rect {
+	Rect::from_xywh(bounds.x, bounds.y, bounds.width, bounds.height)
+}
+
+/// Returns the content box of an element, without its padding.
+fn content_rect(outer: Rect, (left, right, top, bottom): (u16, u16, u16, u16)) -> Rect {
+	Rect::new(
+		outer.left + left as f32,
+		outer.top + top as f32,
+		(outer.right - right as f32).max(outer.left + left as f32),
+		(outer.bottom - bottom as f32).max(outer.top + top as f32),
+	)
+}
+
+fn overlay_font(fonts: &[Typeface], size: f32) -> Option<Font> {
+	let typeface = fonts
+		.first()
+		.cloned()
+		.or_else(|| FontMgr::new().legacy_make_typeface(None, FontStyle::normal()))?;
+	Some(Font::new(typeface, size))
+}
+
+const OUTLINE_COLORS: [Color; 4] = [
+	Color::from_argb(255, 239, 68, 68),
+	Color::from_argb(255, 59, 130, 246),
+	Color::from_argb(255, 34, 197, 94),
+	Color::from_argb(255, 234, 179, 8),
+];
+
+/// Outlines every container, fills its padding and the gaps between its children and labels its sizing mode.
+fn draw_outlines(canvas: &Canvas, entries: &[InspectorEntry], fonts: &[Typeface]) {
+	let font = overlay_font(fonts, 10.);
+	let mut paint = Paint::default();
+	paint.set_anti_alias(true);
+	for (i, entry) in entries.iter().enumerate() {
+		let Some(bounds) = &entry.bounds else {
+			continue;
+		};
+		let outer = to_rect(bounds);
+		let inner = content_rect(outer, entry.layout.padding);
+
+		paint.set_stroke(false);
+		paint.set_color(Color::from_argb(50, 147, 196, 125));
+		canvas.draw_drrect(RRect::new_rect(outer), RRect::new_rect(inner), &paint);
+
+		// Children are the following entries one level deeper, until the next sibling.
+		if let Some(direction) = entry.layout.direction.filter(|_| entry.layout.gap > 0) {
+			let children = entries[i + 1..]
+				.iter()
+				.take_while(|child| child.depth > entry.depth)
+				.filter(|child| child.depth == entry.depth + 1)
+				.filter_map(|child| child.bounds.as_ref().map(to_rect))
+				.collect::<Vec<_>>();
+			paint.set_color(Color::from_argb(60, 217, 70, 239));
+			for pair in children.windows(2) {
+				let gap = match direction {
+					Direction::Row => Rect::new(pair[0].right, inner.top, pair[1].left, inner.bottom),
+					Direction::Column => Rect::new(inner.left, pair[0].bottom, inner.right, pair[1].top),
+				};
+				if gap.width() > 0. && gap.height() > 0. {
+					canvas.draw_rect(gap, &paint);
+				}
+			}
+		}
+
+		paint.set_stroke(true);
+		paint.set_stroke_width(1.);
+		paint.set_color(OUTLINE_COLORS[entry.depth % OUTLINE_COLORS.len()]);
+		canvas.draw_rect(outer, &paint);
+
+		if let (Some(font), Some(sizing)) = (&font, &entry.layout.sizing) {
+			paint.set_stroke(false);
+			canvas.draw_str(
+				sizing,
+				Point::new(outer.left + 2., outer.top + 10.),
+				font,
+				&paint,
+			);
+		}
+	}
+}
+
+fn contains(bounds: &BoundingBox, (x, y): (f32, f32)) -> bool {
+	x >= bounds.x && y >= bounds.y && x < bounds.x + bounds.width && y < bounds.y + bounds.height
+}
+
+/// Returns `true` if [`draw`] draws anything, which isn't tracked by the damage of the frame.
+pub(crate) fn draws_over_frame() -> bool {
+	inspector_enabled() || debug_outlines_enabled()
+}
+
+/// Draws the debug overlays on top of the frame: the layout outlines, then the inspector with the hovered element, the element tree and its resolved style.
+pub(crate) fn draw(canvas: &Canvas, mouse_position: (f32, f32), fonts: &[Typeface]) {
+	if debug_outlines_enabled() {
+		LAST_ENTRIES.with_borrow(|entries| draw_outlines(canvas, entries, fonts));
+	}
+	if !inspector_enabled() {
+		return;
+	}
+	LAST_ENTRIES.with_borrow(|entries| {
+		// Entries are in tree order, so the last one under the pointer is the innermost.
+		let hovered = entries
+			.iter()
+			.rposition(|e| e.bounds.as_ref().is_some_and(|b| contains(b, mouse_position)));
+
+		if let Some(entry) = hovered.map(|i| &entries[i]) {
+			let outer = to_rect(entry.bounds.as_ref().unwrap());
+			let inner = content_rect(outer, entry.layout.padding);
+			let mut paint = Paint::default();
+			paint.set_anti_alias(true);
+			paint.set_color(Color::from_argb(90, 147, 196, 125));
+			canvas.draw_drrect(RRect::new_rect(outer), RRect::new_rect(inner), &paint);
+			paint.set_color(Color::from_argb(90, 111, 168, 220));
+			canvas.draw_rect(inner, &paint);
+			paint.set_color(Color::from_argb(255, 111, 168, 220));
+			paint.set_stroke(true);
+			canvas.draw_rect(outer, &paint);
+		}
+
+		let Some(font) = overlay_font(fonts, 12.) else {
+			return;
+		};
+		let line_height = 16.;
+		let panel_width = 320.;
+		let canvas_size = canvas.base_layer_size();
+		let panel_height = canvas_size.height as f32;
+		// Keep the panel on the opposite side of the pointer.
+		let panel_x = if mouse_position.0 > canvas_size.width as f32 / 2. {
+			0.
+		} else {
+			(canvas_size.width as f32 - panel_width).max(0.)
+		};
+		let mut paint = Paint::default();
+		paint.set_color(Color::from_argb(220, 24, 24, 27));
+		canvas.draw_rect(
+			Rect::from_xywh(panel_x, 0., panel_width, panel_height),
+			&paint,
+		);
+
+		if SHOW_HOOKS.get() {
+			draw_hook_states(canvas, panel_x, panel_height, line_height, &font);
+			return;
+		}
+
+		let details = hovered
+			.map(|i| entries[i].details.as_slice())
+			.unwrap_or_default();
+		let max_lines = ((panel_height / line_height) as usize).saturating_sub(details.len() + 2);
+		// Scroll the tree so that the hovered element stays visible.
+		let first = hovered
+			.unwrap_or(0)
+			.saturating_sub(max_lines / 2)
+			.min(entries.len().saturating_sub(max_lines));
+		let mut y = line_height;
+		for (i, entry) in entries.iter().enumerate().skip(first).take(max_lines) {
+			paint.set_color(if Some(i) == hovered {
+				Color::from_rgb(111, 168, 220)
+			} else {
+				Color::from_rgb(228, 228, 231)
+			});
+			let line = format!("{}{}", "  ".repeat(entry.depth), entry.name);
+			canvas.draw_str(&line, Point::new(panel_x + 8., y), &font, &paint);
+			y += line_height;
+		}
+
+		y += line_height;
+		paint.set_color(Color::from_rgb(250, 204, 21));
+		for line in details {
+			canvas.draw_str(line, Point::new(panel_x + 8., y), &font, &paint);
+			y += line_height;
+		}
+	});
+}
+
+/// Lists the live hook states in the inspector panel, one per line.
+fn draw_hook_states(canvas: &Canvas, panel_x: f32, panel_height: f32, line_height: f32, font: &Font) {
+	let mut paint = Paint::default();
+	let max_lines = (panel_height / line_height) as usize;
+	let mut y = line_height;
+	for info in crate::hook_states().into_iter().take(max_lines) {
+		let component = info.path.last().map(|(_, key)| key.as_str()).unwrap_or_default();
+		paint.set_color(Color::from_rgb(228, 228, 231));
+		let line = format!(
+			"{}{component}[{}] {}",
+			"  ".repeat(info.path.len().saturating_sub(1)),
+			info.hook_index,
+			info.value.unwrap_or_else(|| info.type_name.to_string())
+		);
+		canvas.draw_str(&line, Point::new(panel_x + 8., y), font, &paint);
+		y += line_height;
+	}
+}
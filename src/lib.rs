@@ -1,32 +1,142 @@
 use std::{cell::RefCell, ops::Deref, rc::Rc};
 
+mod accessibility;
 mod clay_renderer;
+mod clipboard;
+mod damage;
+#[cfg(feature = "wayland")]
+mod data_device;
+mod date;
+#[cfg(feature = "dbus")]
+mod dbus;
+mod desktop_entries;
+mod dispatcher;
 mod element;
+mod element_ids;
+mod error_overlay;
 mod focus_system;
 mod font_manager;
+mod frame_scheduler;
+mod fuzzy;
+mod hover;
+mod image;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+#[cfg(feature = "i18n")]
+pub mod i18n;
 mod input;
+mod input_mask;
+mod inspector;
+#[cfg(feature = "dbus")]
+mod notifications;
+mod open;
+mod osd;
+mod overlay;
+mod profiling;
 mod render_context;
+#[cfg(feature = "rsml-interpreter")]
+pub mod rsml_interpreter;
+mod runtime;
+mod scroll;
+mod selection;
+mod settings;
+mod shortcuts;
+mod sticky;
+pub mod testing;
+mod text_editing;
+mod theme;
+#[cfg(feature = "wayland")]
+mod toplevel_export;
+#[cfg(feature = "dbus")]
+mod tray;
+mod undo;
 mod window_options;
 mod winit;
-use clay_layout::{
-	Declaration, grow,
-	layout::Alignment,
-	math::{Dimensions, Vector2},
-};
+use clay_layout::math::{Dimensions, Vector2};
 mod hooks;
-pub use element::{Element, component::Component, container::*, text::Text};
+pub use accessibility::{Politeness, Role, announce};
+pub use date::{Date, DateTime, Weekday};
+pub use desktop_entries::{DesktopEntry, reload_desktop_entries, use_desktop_entries};
+pub use dispatcher::Dispatcher;
+#[cfg(feature = "dbus")]
+pub use element::tray_host::TrayHost;
+pub use element::{
+	Element,
+	avatar::Avatar,
+	breadcrumbs::{Breadcrumb, breadcrumbs},
+	calendar::Calendar,
+	caret::{CaretStyle, TextCursor, TextLayout, use_caret_blink},
+	clock::Clock,
+	component::{
+		CheckRequiredProps, Component, PropNames, Props, PropsCheck, SkipRequiredProps, props_for,
+	},
+	container::*,
+	context_menu::{MenuItem, context_menu},
+	custom_draw::CustomDraw,
+	dock::{DockArea, DockHandle, DockLayout, DockNode, DockPanel, DockSide, use_dock_layout},
+	form::{FieldError, Form, FormField, FormState, use_form},
+	fuzzy_list::{FuzzyList, FuzzyResult, use_fuzzy_filter},
+	image::Image,
+	pagination::pagination,
+	portal::{Overlay, Portal, use_overlay},
+	rich_text::{RichText, Span},
+	router::{Navigator, PageTransition, RouteParams, Router, use_navigate},
+	screen_capture::ScreenCapture,
+	svg::Svg,
+	taskbar::{Taskbar, WorkspaceIndicator},
+	text::Text,
+	text_input::TextInput,
+	window::{Window, WindowArea},
+};
+pub use focus_system::{FocusDirection, FocusManager, with_focus_manager, with_focus_manager_mut};
+pub use frame_scheduler::{Tick, use_interval, use_tick, use_timeout};
+pub use fuzzy::{FuzzyMatch, fuzzy_match};
+pub use hover::{PointerRef, use_hover, use_pressed};
 pub use hooks::*;
-pub use hyprui_rsml_compiler::rsml;
+pub use image::{ImageFit, ImageLoadState, ImageSource, clear_image_cache};
+pub use hyprui_rsml_compiler::{Props, rsml, rsml_file};
 pub(crate) use input::winit_impl::WinitInputManager;
-pub use input::{InputManager, NamedKey, NativeKey};
+pub use input::{
+	InputManager, Key, NamedKey, NativeKey,
+	recording::{InputEvent, InputRecording},
+};
+pub use input_mask::{InputMask, IpAddressMask, ThousandsMask, TimeMask};
+pub use inspector::{
+	debug_outlines_enabled, dump_layout, inspector_enabled, layout_recording_enabled, set_debug_outlines,
+	set_inspector_enabled, set_layout_recording,
+};
+#[cfg(feature = "dbus")]
+pub use notifications::{Notification, NotificationAction, close_notification, notify};
+pub use open::{open_path, open_url};
+pub use osd::{hide_osd, show_osd};
+pub use overlay::push_overlay;
 pub use render_context::RenderContext;
+pub use scroll::{
+	Overscroll, ScrollHandle, ScrollPhysics, ScrollProgress, ScrollSnap, SnapPoints, SnapStrictness,
+	use_scroll_handle, use_scroll_progress,
+};
+pub use selection::{Selection, SelectionMode, SelectionModel, TypeAhead, use_selection};
+pub use settings::{
+	DesktopFont, DesktopSettings, HighContrastColors, animation_duration, desktop_settings,
+	high_contrast_colors, prefers_high_contrast, prefers_reduced_motion, set_high_contrast,
+	set_high_contrast_colors, set_reduced_motion, set_text_scale, text_scale, use_accent_color,
+	use_cursor_size, use_desktop_fonts,
+};
+pub use shortcuts::use_shortcut;
+pub use text_editing::{
+	EditAction, KeyChord, Keymap, TextEditor, TextSnapshot, set_text_keymap, text_keymap,
+};
+pub use theme::{Theme, ThemeColors, set_theme, use_theme};
+#[cfg(feature = "dbus")]
+pub use tray::{TrayItem, TrayMenuItem, TrayStatus, use_tray_items, use_tray_menu};
+pub use undo::{UndoHistory, use_undo_history};
 pub use window_options::WindowOptions;
 
 use crate::{
-	clay_renderer::clay_skia_render,
-	focus_system::GLOBAL_FOCUS_MANAGER,
+	clay_renderer::{clay_skia_render, draw_custom_element},
+	damage::{Damage, DamageTracker},
 	font_manager::FontManager,
-	input::Key,
+	input::recording::{InputRecorder, InputReplay},
 	winit::{Callbacks, WinitApp},
 };
 
@@ -111,10 +221,35 @@ pub fn create_window<Props: Clone + 'static>(
 	options: WindowOptions,
 ) {
 	color_eyre::install().ok();
+	if let Some(scale) = options.text_scale {
+		settings::set_text_scale(Some(scale));
+	}
+	if let Some(high_contrast) = options.high_contrast {
+		settings::set_high_contrast(Some(high_contrast));
+	}
 
 	let clay = Rc::new(RefCell::new(clay_layout::Clay::new((0.0, 0.0).into())));
-	let mut font_manager = FontManager::new();
+	let font_manager = Rc::new(RefCell::new(FontManager::new()));
 	let input_manager = Rc::new(RefCell::new(WinitInputManager::new()));
+	if let Some(path) = std::env::var_os("HYPRUI_RECORD_INPUT") {
+		match InputRecorder::create(&path) {
+			Ok(recorder) => input_manager.borrow_mut().start_recording(recorder),
+			Err(err) => log::error!("failed to record input to {}: {err}", path.to_string_lossy()),
+		}
+	}
+	let mut input_replay = std::env::var_os("HYPRUI_REPLAY_INPUT").and_then(|path| {
+		InputRecording::load(&path)
+			.inspect_err(|err| log::error!("failed to load input recording {}: {err}", path.to_string_lossy()))
+			.ok()
+			.map(InputReplay::new)
+	});
+	if let Some(replay) = &input_replay {
+		replay.spawn_wake_up_thread();
+	}
+	#[cfg(feature = "accesskit")]
+	let accesskit_adapter = Rc::new(RefCell::new(accessibility::AccessKitAdapter::new(
+		options.title.clone(),
+	)));
 
 	let winit_app = WinitApp::new(
 		options,
@@ -123,40 +258,68 @@ pub fn create_window<Props: Clone + 'static>(
 				let clay = Rc::clone(&clay);
 				let props = props.clone();
 				let input_manager = Rc::clone(&input_manager);
-				Box::new(move |canvas| {
+				let font_manager = Rc::clone(&font_manager);
+				#[cfg(feature = "accesskit")]
+				let accesskit_adapter = Rc::clone(&accesskit_adapter);
+				let mut damage_tracker = DamageTracker::default();
+				Box::new(move |canvas, buffer_age, set_damage_region| {
 					let mut clay = clay.borrow_mut();
+					let mut font_manager = font_manager.borrow_mut();
 					let mut input_manager_ref = input_manager.borrow_mut();
-					GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| {
-						f.add_root();
-						if input_manager_ref.is_key_just_pressed(Key::Named(NamedKey::Tab)) {
-							if input_manager_ref.is_key_pressed(Key::Named(NamedKey::Shift)) {
-								f.focus_prev();
-							} else {
-								f.focus_next();
-							}
+					if let Some(replay) = &mut input_replay {
+						for event in replay.due_events() {
+							input_manager_ref.apply_event(event);
 						}
-
-						if (!input_manager_ref.cursor_hit_something() && (input_manager_ref.is_mouse_button_just_pressed(0) || input_manager_ref.is_mouse_button_just_pressed(1))) || input_manager_ref.is_key_just_pressed(Key::Named(NamedKey::Escape)) {
-							f.blur();
-						}
-						f.new_frame();
-					});
-					font_manager.update_clay_measure_function(&mut clay);
-					let root_component = Component::new(component, props.clone());
-
-					{
-						let mut c = clay.begin();
-
-						let mut render_ctx = RenderContext {
-							c: &mut c,
-							font_manager: &mut font_manager,
-							input_manager: input_manager_ref.deref(),
-						};
-						root_component.render(&mut render_ctx);
-
-						clay_skia_render::<()>(canvas, c.end(), |_, _, _| {}, &font_manager.get_fonts());
 					}
+					let mut damage = Damage::Full;
+					runtime::run_frame(
+						&mut clay,
+						&mut font_manager,
+						input_manager_ref.deref(),
+						component,
+						props.clone(),
+						|commands, font_manager| {
+							let commands = commands.collect::<Vec<_>>();
+							damage = damage_tracker.track(&commands, canvas.base_layer_size(), buffer_age);
+							if inspector::draws_over_frame() {
+								damage = damage_tracker.invalidate();
+							}
+							set_damage_region(&damage);
+							damage::paint(canvas, &damage, || {
+								clay_skia_render(
+									canvas,
+									commands.into_iter(),
+									draw_custom_element,
+									font_manager.get_fonts(),
+								);
+							});
+						},
+					);
+					inspector::draw(canvas, input_manager_ref.mouse_position(), font_manager.get_fonts());
+					#[cfg(feature = "accesskit")]
+					accesskit_adapter.borrow_mut().update();
 					input_manager_ref.update();
+					damage
+				})
+			},
+			on_render_osd: {
+				let mut clay = clay_layout::Clay::new(osd::MAX_SIZE.into());
+				Box::new(move |canvas| {
+					canvas.clear(skia_safe::Color::TRANSPARENT);
+					osd::render(
+						&mut clay,
+						&mut font_manager.borrow_mut(),
+						|commands, font_manager, opacity| {
+							canvas.save_layer_alpha_f(None, opacity);
+							clay_skia_render(
+								canvas,
+								commands,
+								draw_custom_element,
+								font_manager.get_fonts(),
+							);
+							canvas.restore();
+						},
+					)
 				})
 			},
 			on_mouse_move: {
@@ -185,6 +348,12 @@ pub fn create_window<Props: Clone + 'static>(
 					clay.pointer_state(Vector2::new(mx, my), pressed);
 				})
 			},
+			on_mouse_wheel: {
+				let input_manager = Rc::clone(&input_manager);
+				Box::new(move |dx, dy| {
+					input_manager.borrow_mut().scroll(dx, dy);
+				})
+			},
 			on_key_event: {
 				let input_manager = Rc::clone(&input_manager);
 				Box::new(move |event| {
@@ -197,6 +366,14 @@ pub fn create_window<Props: Clone + 'static>(
 					input_manager.borrow_mut().handle_ime_event(ime);
 				})
 			},
+			on_window_focus: {
+				#[cfg(feature = "accesskit")]
+				let accesskit_adapter = Rc::clone(&accesskit_adapter);
+				Box::new(move |_focused| {
+					#[cfg(feature = "accesskit")]
+					accesskit_adapter.borrow_mut().set_window_focused(_focused);
+				})
+			},
 			on_window_resize: {
 				let clay = Rc::clone(&clay);
 				Box::new(move |width, height| {
@@ -0,0 +1,171 @@
+//! Desktop notifications through `org.freedesktop.Notifications`.
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	rc::Rc,
+	sync::{Mutex, Once},
+};
+
+use zbus::{blocking::Proxy, zvariant::Value};
+
+use crate::dbus::session_bus;
+
+/// A button shown on a notification.
+pub struct NotificationAction {
+	pub label: String,
+	/// Called on the UI thread when the user clicks the action.
+	pub on_invoke: Rc<dyn Fn()>,
+}
+
+impl NotificationAction {
+	pub fn new(label: impl Into<String>, on_invoke: impl Fn() + 'static) -> Self {
+		Self {
+			label: label.into(),
+			on_invoke: Rc::new(on_invoke),
+		}
+	}
+}
+
+/// A notification shown by the desktop notification daemon. See [`notify`].
+#[derive(Default)]
+pub struct Notification {
+	pub title: String,
+	pub body: String,
+	/// An icon name from the icon theme, or a `file://` URI.
+	pub icon: Option<String>,
+	pub actions: Vec<NotificationAction>,
+}
+
+enum NotificationEvent {
+	ActionInvoked { id: u32, action: String },
+	Closed { id: u32 },
+}
+
+/// Signals received by the listener thread, waiting to be dispatched on the UI thread.
+static PENDING_EVENTS: Mutex<Vec<NotificationEvent>> = Mutex::new(Vec::new());
+static LISTEN: Once = Once::new();
+
+thread_local! {
+	/// Action callbacks of the notifications that are still open, by notification id.
+	static ACTIONS: RefCell<HashMap<u32, Vec<NotificationAction>>> = RefCell::new(HashMap::new());
+}
+
+fn notifications_proxy() -> zbus::Result<Proxy<'static>> {
+	Proxy::new(
+		&session_bus()?,
+		"org.freedesktop.Notifications",
+		"/org/freedesktop/Notifications",
+		"org.freedesktop.Notifications",
+	)
+}
+
+/// Listens to the action and close signals of the notification daemon on a background thread.
+fn listen() {
+	LISTEN.call_once(|| {
+		let signals = match notifications_proxy().and_then(|proxy| proxy.receive_all_signals()) {
+			Ok(signals) => signals,
+			Err(err) => {
+				log::error!("failed to listen to notification actions: {err}");
+				return;
+			}
+		};
+		std::thread::spawn(move || {
+			for message in signals {
+				let header = message.header();
+				let body = message.body();
+				let event = match header.member().map(|m| m.as_str()) {
+					Some("ActionInvoked") => body
+						.deserialize::<(u32, String)>()
+						.map(|(id, action)| NotificationEvent::ActionInvoked { id, action }),
+					Some("NotificationClosed") => body
+						.deserialize::<(u32, u32)>()
+						.map(|(id, _reason)| NotificationEvent::Closed { id }),
+					_ => continue,
+				};
+				if let Ok(event) = event {
+					PENDING_EVENTS.lock().unwrap().push(event);
+					crate::winit::wake_up();
+				}
+			}
+		});
+	});
+}
+
+/// Shows a desktop notification and returns its id.
+///
+/// Action callbacks run on the UI thread, at the start of the next frame after the user clicks them.
+///
+/// ```rust,ignore
+/// hyprui::notify(Notification {
+///     title: "Download finished".into(),
+///     body: "report.pdf".into(),
+///     icon: Some("document-save".into()),
+///     actions: vec![NotificationAction::new("Open", || open("report.pdf"))],
+/// })?;
+/// ```
+pub fn notify(notification: Notification) -> Result<u32, String> {
+	listen();
+	let app_name = std::env::current_exe()
+		.ok()
+		.and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+		.unwrap_or_default();
+	// Actions are sent as a flat list of `[key, label, key, label, ...]`, the key is the action index.
+	let actions = notification
+		.actions
+		.iter()
+		.enumerate()
+		.flat_map(|(i, action)| [i.to_string(), action.label.clone()])
+		.collect::<Vec<_>>();
+	let hints = HashMap::<&str, Value>::new();
+	let id: u32 = notifications_proxy()
+		.and_then(|proxy| {
+			proxy.call(
+				"Notify",
+				&(
+					app_name,
+					0u32,
+					notification.icon.unwrap_or_default(),
+					notification.title,
+					notification.body,
+					actions,
+					hints,
+					-1i32,
+				),
+			)
+		})
+		.map_err(|err| err.to_string())?;
+	if !notification.actions.is_empty() {
+		ACTIONS.with_borrow_mut(|actions| actions.insert(id, notification.actions));
+	}
+	Ok(id)
+}
+
+/// Closes a notification shown with [`notify`].
+pub fn close_notification(id: u32) -> Result<(), String> {
+	notifications_proxy()
+		.and_then(|proxy| proxy.call::<_, _, ()>("CloseNotification", &(id,)))
+		.map_err(|err| err.to_string())
+}
+
+/// Runs the callbacks of the actions the user clicked since the last frame.
+pub(crate) fn dispatch_events() {
+	let events = std::mem::take(&mut *PENDING_EVENTS.lock().unwrap());
+	for event in events {
+		match event {
+			NotificationEvent::ActionInvoked { id, action } => {
+				let Ok(index) = action.parse::<usize>() else {
+					continue;
+				};
+				// Cloned out, because the callback may show another notification.
+				let callback =
+					ACTIONS.with_borrow(|actions| Some(actions.get(&id)?.get(index)?.on_invoke.clone()));
+				if let Some(callback) = callback {
+					callback();
+				}
+			}
+			NotificationEvent::Closed { id } => {
+				ACTIONS.with_borrow_mut(|actions| actions.remove(&id));
+			}
+		}
+	}
+}
@@ -0,0 +1,63 @@
+//! Opens files and URLs with the default application of the desktop.
+use std::{
+	path::Path,
+	process::{Command, Stdio},
+	sync::Mutex,
+};
+
+/// Commands waiting for an activation token from the compositor.
+static PENDING: Mutex<Vec<Vec<String>>> = Mutex::new(Vec::new());
+
+/// Opens a URL, like `https://example.com` or `mailto:someone@example.com`, in the default application.
+///
+/// On Wayland, the window asks the compositor for an xdg-activation token first and hands it to the launched
+/// application, so it gets focused instead of just flashing in the taskbar. Errors are logged.
+pub fn open_url(url: impl Into<String>) {
+	spawn(vec!["xdg-open".to_string(), url.into()]);
+}
+
+/// Opens a file or a directory in the default application. See [`open_url`].
+pub fn open_path(path: impl AsRef<Path>) {
+	open_url(path.as_ref().to_string_lossy().into_owned());
+}
+
+/// Runs `command`, a program followed by its arguments, handing it an activation token like [`open_url`].
+pub(crate) fn spawn(command: Vec<String>) {
+	if crate::winit::is_running() {
+		PENDING.lock().unwrap().push(command);
+		crate::winit::wake_up();
+	} else {
+		launch(&command, None);
+	}
+}
+
+/// Takes the commands that need an activation token.
+pub(crate) fn take_pending() -> Vec<Vec<String>> {
+	std::mem::take(&mut *PENDING.lock().unwrap())
+}
+
+/// Runs `command`, passing the activation token through the environment variables launchers read it from.
+pub(crate) fn launch(command: &[String], activation_token: Option<String>) {
+	let Some((program, args)) = command.split_first() else {
+		return;
+	};
+	let target = command.join(" ");
+	let mut command = Command::new(program);
+	command
+		.args(args)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null());
+	if let Some(token) = activation_token {
+		command
+			.env("XDG_ACTIVATION_TOKEN", &token)
+			.env("DESKTOP_STARTUP_ID", token);
+	}
+	match command.spawn() {
+		// Waiting avoids leaving a zombie process once it exits, which xdg-open does as soon as the
+		// application is started.
+		Ok(mut child) => {
+			std::thread::spawn(move || child.wait());
+		}
+		Err(err) => log::error!("failed to run {target}: {err}"),
+	}
+}
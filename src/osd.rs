@@ -0,0 +1,191 @@
+//! On-screen displays, the popups showing the volume or the brightness for a moment after they changed.
+//!
+//! The OSD gets a layer-shell surface of its own, centered on the screen above the windows, created when an OSD
+//! is shown and destroyed once it faded out, so a bar can show one without room for it in its window. Its
+//! component is built with the app, so it can use hooks, and laid out on its own at the top left corner of the
+//! surface, which takes the size of the content.
+//!
+//! The OSD fades in and out with the opacity of the whole surface, so its images and shadows stay opaque.
+use std::{
+	cell::RefCell,
+	rc::Rc,
+	time::{Duration, Instant},
+};
+
+use clay_layout::{Clay, Declaration, render_commands::RenderCommand};
+use skia_safe::Image;
+
+use crate::{
+	Component, Element, GlobalClosure, RenderContext, WinitInputManager,
+	clay_renderer::CustomElement, font_manager::FontManager,
+};
+
+/// The largest the surface of an OSD gets, content beyond it is cut.
+pub(crate) const MAX_SIZE: (f32, f32) = (1024., 1024.);
+
+/// How long the OSD takes to fade in, and to fade out.
+const FADE_DURATION: Duration = Duration::from_millis(200);
+
+struct Osd {
+	content: Rc<dyn Fn() -> Box<dyn Element>>,
+	shown_at: Instant,
+	duration: Duration,
+}
+
+thread_local! {
+	static OSD: RefCell<Option<Osd>> = const { RefCell::new(None) };
+	/// The OSD built this frame and its opacity, drawn on its surface after the window.
+	static SHOWN: RefCell<Option<(Component, f32)>> = const { RefCell::new(None) };
+}
+
+/// Returns how opaque the OSD is `elapsed` after being shown, or `None` once it faded out.
+fn opacity(elapsed: Duration, duration: Duration, fade: Duration) -> Option<f32> {
+	if elapsed >= duration {
+		return None;
+	}
+	if fade.is_zero() {
+		return Some(1.);
+	}
+	let fade_in = elapsed.as_secs_f32() / fade.as_secs_f32();
+	let fade_out = (duration - elapsed).as_secs_f32() / fade.as_secs_f32();
+	Some(fade_in.min(fade_out).min(1.))
+}
+
+/// Shows the element built by `content` centered on the screen for `duration`, fading in and out, like the volume
+/// after pressing a volume key.
+///
+/// Showing another OSD replaces the current one without fading it in again, so holding the key keeps it on
+/// screen. `content` is called on every frame the OSD is shown, as the component of the OSD, so it can use
+/// hooks. OSDs aren't meant to be clicked, the surface gets no input.
+///
+/// ```rust,ignore
+/// let audio = use_audio_volume();
+/// let percent = audio.sink.map_or(0, |sink| (sink.volume * 100.).round() as u32);
+/// use_effect(
+///     move || {
+///         show_osd(move || Box::new(Text::new(format!("Volume {percent}%"))), Duration::from_secs(2));
+///         None
+///     },
+///     &percent,
+/// );
+/// ```
+pub fn show_osd(content: impl Fn() -> Box<dyn Element> + 'static, duration: Duration) {
+	let fade = crate::animation_duration(FADE_DURATION);
+	OSD.with_borrow_mut(|osd| {
+		let now = crate::frame_scheduler::now();
+		// Starts as opaque as the OSD it replaces
+		let visible = osd
+			.as_ref()
+			.and_then(|osd| opacity(now - osd.shown_at, osd.duration, fade))
+			.unwrap_or(0.);
+		*osd = Some(Osd {
+			content: Rc::new(content),
+			shown_at: now - fade.mul_f32(visible),
+			duration,
+		});
+	});
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Fades the OSD out now, if one is shown.
+pub fn hide_osd() {
+	let fade = crate::animation_duration(FADE_DURATION);
+	OSD.with_borrow_mut(|osd| {
+		if let Some(osd) = osd {
+			let elapsed = crate::frame_scheduler::elapsed(osd.shown_at);
+			osd.duration = osd.duration.min(elapsed + fade);
+		}
+	});
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Builds the OSD as a root component, dropping it once it faded out. Called after the app is built, with
+/// [`build_roots`](crate::hooks::build_roots).
+pub(crate) fn build() {
+	SHOWN.set(None);
+	let fade = crate::animation_duration(FADE_DURATION);
+	let shown = OSD.with_borrow_mut(|slot| {
+		let osd = slot.as_ref()?;
+		match opacity(
+			crate::frame_scheduler::elapsed(osd.shown_at),
+			osd.duration,
+			fade,
+		) {
+			Some(opacity) => Some((osd.content.clone(), opacity)),
+			None => {
+				*slot = None;
+				None
+			}
+		}
+	});
+	let Some((content, opacity)) = shown else {
+		return;
+	};
+	// Rendered again until it faded out
+	crate::REQUEST_REDRAW.call();
+	let component = Component::new_with_key(move |_: ()| content(), (), "hyprui osd".to_string());
+	SHOWN.set(Some((component, opacity)));
+}
+
+/// Returns `true` if an OSD was built this frame, so its surface must be shown.
+pub(crate) fn is_shown() -> bool {
+	SHOWN.with_borrow(Option::is_some)
+}
+
+/// Lays out the OSD built this frame in `clay` and draws it with `draw`, with its opacity. Returns the size of
+/// the OSD, or `None` if none is shown.
+pub(crate) fn render(
+	clay: &mut Clay,
+	font_manager: &mut FontManager,
+	draw: impl FnOnce(
+		&mut dyn Iterator<Item = RenderCommand<'_, Image, CustomElement>>,
+		&FontManager,
+		f32,
+	),
+) -> Option<(f32, f32)> {
+	SHOWN.with_borrow(|shown| {
+		let (component, opacity) = shown.as_ref()?;
+		font_manager.update_clay_measure_function(clay);
+		// Nothing points at the OSD
+		let input_manager = WinitInputManager::new();
+		let mut c = clay.begin();
+		let id = c.id("hyprui-osd");
+		c.with_styling(
+			|_| {
+				let mut declaration = Declaration::new();
+				declaration.id(id.clone());
+				declaration
+			},
+			|c| {
+				let mut ctx = RenderContext {
+					c,
+					font_manager: &mut *font_manager,
+					input_manager: &input_manager,
+				};
+				component.render(&mut ctx);
+			},
+		);
+		let mut commands = c.end();
+		draw(&mut commands, font_manager, *opacity);
+		let bounds = c.bounding_box(id)?;
+		Some((bounds.width, bounds.height))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_osd_fades_in_and_out() {
+		let ms = Duration::from_millis;
+		let fade = ms(200);
+		assert_eq!(opacity(ms(0), ms(2000), fade), Some(0.));
+		assert_eq!(opacity(ms(100), ms(2000), fade), Some(0.5));
+		assert_eq!(opacity(ms(1000), ms(2000), fade), Some(1.));
+		assert_eq!(opacity(ms(1900), ms(2000), fade), Some(0.5));
+		assert_eq!(opacity(ms(2000), ms(2000), fade), None);
+		// Without animations, it only appears and disappears
+		assert_eq!(opacity(ms(0), ms(2000), Duration::ZERO), Some(1.));
+	}
+}
@@ -0,0 +1,241 @@
+use std::{
+	cell::{Cell, RefCell},
+	rc::Rc,
+};
+
+use clay_layout::{
+	Color, Declaration,
+	elements::{FloatingAttachToElement, PointerCaptureMode},
+	layout::{Alignment, Sizing},
+};
+
+use crate::{Align, Element, InputManager, Justify, Key, NamedKey, RenderContext};
+
+/// A layer closed by clicking outside of it or by pressing Escape, see [`Portal`](crate::Portal).
+struct Dismissible {
+	layer: usize,
+	on_dismiss: Rc<dyn Fn()>,
+	click_outside: bool,
+	escape: bool,
+}
+
+thread_local! {
+	/// Overlays pushed this frame, rendered in order above the main tree.
+	static OVERLAYS: RefCell<Vec<Box<dyn Element>>> = RefCell::new(Vec::new());
+	/// Layer being declared: `0` for the main tree, `n` for the n-th layer declared above it.
+	static CURRENT_LAYER: Cell<usize> = const { Cell::new(0) };
+	/// Layers declared so far this frame, in the order they are declared.
+	static LAYER_COUNT: Cell<usize> = const { Cell::new(0) };
+	/// Topmost layer with an element under the pointer, in this frame and the last one.
+	static POINTER_LAYER: Cell<usize> = const { Cell::new(0) };
+	static LAST_POINTER_LAYER: Cell<usize> = const { Cell::new(0) };
+	/// Layers that can be dismissed, declared this frame and the last one.
+	static DISMISSIBLE: RefCell<Vec<Dismissible>> = const { RefCell::new(Vec::new()) };
+	static LAST_DISMISSIBLE: RefCell<Vec<Dismissible>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Shows `element` above the rest of the window for this frame.
+///
+/// Call it while a component renders, on every render while the overlay should stay visible, like from
+/// a modal or a menu component. Overlays are laid out in a layer covering the whole window, so they can
+/// position themselves with alignment and padding, and later overlays are drawn on top of earlier ones.
+///
+/// The containers of an overlay catch the pointer: the elements below them are neither hovered nor clicked.
+/// [`Portal`](crate::Portal) does the same from where it is declared, and can be dismissed.
+///
+/// ```rust,ignore
+/// if open {
+///     push_overlay(
+///         Container::new()
+///             .w_expand()
+///             .h_expand()
+///             .center()
+///             .background_color((0, 0, 0, 120))
+///             .child(dialog),
+///     );
+/// }
+/// ```
+pub fn push_overlay(element: impl Element + 'static) {
+	OVERLAYS.with_borrow_mut(|overlays| overlays.push(Box::new(element)));
+}
+
+pub(crate) fn begin_frame() {
+	OVERLAYS.with_borrow_mut(|overlays| overlays.clear());
+	LAST_POINTER_LAYER.set(POINTER_LAYER.replace(0));
+	CURRENT_LAYER.set(0);
+	LAYER_COUNT.set(0);
+	LAST_DISMISSIBLE.set(DISMISSIBLE.take());
+}
+
+/// Calls the dismiss handlers of the layers declared in the last frame: of the ones the pointer is pressed outside
+/// of, and of the topmost one when Escape is pressed.
+///
+/// Returns `true` if Escape closed a layer, so it doesn't blur the focused element too.
+pub(crate) fn dismiss(input: &dyn InputManager) -> bool {
+	let dismissible = LAST_DISMISSIBLE.take();
+	let pressed = input.is_mouse_button_just_pressed(0) || input.is_mouse_button_just_pressed(1);
+	let pointer_layer = LAST_POINTER_LAYER.get();
+	// Topmost first, so nested popups close before the ones they were opened from
+	for layer in dismissible.iter().rev() {
+		if pressed && layer.click_outside && layer.layer > pointer_layer {
+			(layer.on_dismiss)();
+		}
+	}
+	let escaped = dismissible
+		.iter()
+		.rev()
+		.find(|layer| layer.escape)
+		.filter(|_| input.is_key_just_pressed(Key::Named(NamedKey::Escape)));
+	if let Some(layer) = escaped {
+		(layer.on_dismiss)();
+	}
+	escaped.is_some()
+}
+
+/// Records that an element of the layer being declared is under the pointer.
+pub(crate) fn hit(hovered: bool) {
+	if hovered {
+		POINTER_LAYER.set(POINTER_LAYER.get().max(CURRENT_LAYER.get()));
+	}
+}
+
+/// Returns `true` if an overlay above the layer being declared was under the pointer in the last frame,
+/// so the pointer should be ignored by this layer.
+pub(crate) fn is_pointer_blocked() -> bool {
+	LAST_POINTER_LAYER.get() > CURRENT_LAYER.get()
+}
+
+/// Returns the layer being declared, to check later whether the pointer reaches it.
+pub(crate) fn current_layer() -> usize {
+	CURRENT_LAYER.get()
+}
+
+/// Returns `true` if the pointer reached `layer` in the last frame, without an overlay above it.
+pub(crate) fn pointer_reaches(layer: usize) -> bool {
+	LAST_POINTER_LAYER.get() <= layer
+}
+
+/// Takes the overlays pushed while the components were built, to be rendered with [`render`].
+pub(crate) fn take_overlays() -> Vec<Box<dyn Element>> {
+	OVERLAYS.with_borrow_mut(std::mem::take)
+}
+
+/// Declares `overlays` after the main tree, each in a layer of its own above the previous one.
+pub(crate) fn render<'clay: 'render, 'render>(
+	ctx: &mut RenderContext<'clay, 'render, '_>,
+	overlays: &'render [Box<dyn Element>],
+) {
+	for overlay in overlays {
+		render_layer(
+			ctx,
+			None,
+			(Justify::Left, Align::Top),
+			Some(overlay.as_ref()),
+		);
+	}
+}
+
+/// Declares a layer covering the window above the ones declared before it, with `content` laid out in it at
+/// `alignment`, and returns its number.
+///
+/// The containers of `content` catch the pointer, not the whole layer, unless it has a `background`, like the
+/// backdrop of a modal.
+pub(crate) fn render_layer<'clay: 'render, 'render>(
+	ctx: &mut RenderContext<'clay, 'render, '_>,
+	background: Option<Color>,
+	alignment: (Justify, Align),
+	content: Option<&'render dyn Element>,
+) -> usize {
+	let layer = LAYER_COUNT.get() + 1;
+	LAYER_COUNT.set(layer);
+	let parent = CURRENT_LAYER.replace(layer);
+	ctx.c.with_styling(
+		|c| {
+			if background.is_some() {
+				hit(c.hovered() && !is_pointer_blocked());
+			}
+			let mut declaration = Declaration::new();
+			declaration
+				.layout()
+				.width(Sizing::Grow(0., f32::MAX))
+				.height(Sizing::Grow(0., f32::MAX))
+				.child_alignment(Alignment::new(alignment.0, alignment.1))
+				.end()
+				.floating()
+				.attach_to(FloatingAttachToElement::Root)
+				.z_index(layer.min(i16::MAX as usize) as i16)
+				.pointer_capture_mode(if background.is_some() {
+					PointerCaptureMode::Capture
+				} else {
+					PointerCaptureMode::Passthrough
+				})
+				.end();
+			if let Some(background) = background {
+				declaration.background_color(background);
+			}
+			declaration
+		},
+		|c| {
+			if let Some(content) = content {
+				let mut layer_ctx = RenderContext {
+					c,
+					font_manager: &mut *ctx.font_manager,
+					input_manager: ctx.input_manager,
+				};
+				content.render(&mut layer_ctx);
+			}
+		},
+	);
+	CURRENT_LAYER.set(parent);
+	layer
+}
+
+/// Closes `layer` with `on_dismiss` when the pointer is pressed outside of it or Escape is pressed, from the
+/// next frame.
+pub(crate) fn dismissible(
+	layer: usize,
+	on_dismiss: Rc<dyn Fn()>,
+	click_outside: bool,
+	escape: bool,
+) {
+	DISMISSIBLE.with_borrow_mut(|dismissible| {
+		dismissible.push(Dismissible {
+			layer,
+			on_dismiss,
+			click_outside,
+			escape,
+		})
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Text, testing::TestHarness, use_state};
+
+	fn app(_: ()) -> Box<dyn Element> {
+		let (clicked, set_clicked) = use_state("nothing");
+		let set_clicked = std::rc::Rc::new(set_clicked);
+		let set_overlay_clicked = set_clicked.clone();
+		push_overlay(
+			Container::new()
+				.label("overlay")
+				.on_click(move || set_overlay_clicked("overlay"))
+				.child(Text::new("Overlay")),
+		);
+		Box::new(
+			Container::new()
+				.label("content")
+				.on_click(move || set_clicked("content"))
+				.child(Text::new(format!("Clicked {clicked}"))),
+		)
+	}
+
+	#[test]
+	fn test_overlays_get_the_pointer_first() {
+		let mut harness = TestHarness::new(app, (), (400., 300.));
+		let (x, y) = harness.expect_label("overlay").center().unwrap();
+		harness.click(x, y);
+		assert!(harness.find_by_label("Clicked overlay").is_some());
+	}
+}
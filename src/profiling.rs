@@ -0,0 +1,36 @@
+//! Instrumentation of the frame pipeline with `tracing` spans, enabled with the `tracing` feature.
+//!
+//! Every frame is a `frame` span containing `build` (component functions), `declare` (elements
+//! declaring their layout), `layout` (clay), `paint` (skia commands), `flush` and `swap`, with one
+//! `component` span per function component. Any `tracing` subscriber can consume them, for
+//! example `tracing-tracy` or `tracing-perfetto`, to find which component blows the frame budget.
+
+/// Enters a span until the end of the current block. Compiles to nothing without the `tracing` feature.
+#[cfg(feature = "tracing")]
+macro_rules! profile_span {
+	($($args:tt)*) => {
+		let _profile_span = tracing::info_span!($($args)*).entered();
+	};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! profile_span {
+	($($args:tt)*) => {};
+}
+
+/// Emits a counter value as a `tracing` event. Compiles to nothing without the `tracing` feature.
+#[cfg(feature = "tracing")]
+macro_rules! profile_counter {
+	($name:ident, $value:expr) => {
+		tracing::info!($name = $value);
+	};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! profile_counter {
+	($name:ident, $value:expr) => {
+		let _ = $value;
+	};
+}
+
+pub(crate) use {profile_counter, profile_span};
@@ -1,9 +1,24 @@
-use crate::{InputManager, font_manager::FontManager};
-use clay_layout::ClayLayoutScope;
+use crate::{InputManager, clay_renderer::CustomElement, font_manager::FontManager};
+use clay_layout::{ClayLayoutScope, math::BoundingBox};
 use skia_safe::Image;
 
 pub struct RenderContext<'clay: 'render, 'render: 'a, 'a> {
-	pub c: &'a mut ClayLayoutScope<'clay, 'render, Image, ()>,
+	pub c: &'a mut ClayLayoutScope<'clay, 'render, Image, CustomElement>,
 	pub font_manager: &'a mut FontManager,
 	pub input_manager: &'a dyn InputManager,
 }
+
+impl RenderContext<'_, '_, '_> {
+	/// Returns where the element with the given [id](crate::Container::id) was laid out in the last frame.
+	///
+	/// Layout happens after all elements are declared, so the bounds of the current frame are not known yet.
+	/// Useful to anchor a popup to an element.
+	pub fn bounding_box(&self, id: &str) -> Option<BoundingBox> {
+		crate::element_ids::bounding_box(id)
+	}
+
+	/// Returns the id of the topmost element with an id at `point` in the last frame, like a drop target under the pointer.
+	pub fn element_at(&self, point: (f32, f32)) -> Option<String> {
+		crate::element_ids::element_at(point)
+	}
+}
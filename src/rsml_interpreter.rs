@@ -0,0 +1,827 @@
+//! Builds elements from RSML at runtime, to see changes to a layout without rebuilding the app.
+//!
+//! [`use_rsml_file`] interprets an `.rsml` file and renders it again whenever the file changes. The interpreter can't
+//! run Rust code, so the values and callbacks used in braces are given by name in a [`Scope`]:
+//!
+//! ```rust,ignore
+//! fn counter(_: ()) -> Box<dyn Element> {
+//!     let (count, set_count) = use_state(0);
+//!     use_rsml_file(
+//!         "src/counter.rsml",
+//!         Scope::new()
+//!             .value("count", count)
+//!             .callback("increment", move || set_count(count + 1)),
+//!     )
+//! }
+//! ```
+//!
+//! ```rsml
+//! <container padding_all={16} gap={8} on_click={increment}>
+//!     <text font_size={18}>Clicked {count} times</text>
+//! </container>
+//! ```
+//!
+//! Braces can hold numbers, strings, `true` and `false`, tuples like colors, variants like `Direction::Column`, names
+//! from the scope, and several of them separated by commas. `<container>`, `<text>` and `<span>` take their most
+//! common methods, and components are the elements given to the scope with [`Scope::element`]. Once the layout is
+//! done, the same file can be compiled with [`rsml_file!`](crate::rsml_file), where braces hold any Rust code.
+use std::{
+	collections::HashMap,
+	fmt,
+	path::Path,
+	rc::Rc,
+	sync::{Arc, Mutex},
+};
+
+use clay_layout::Color;
+use hyprui_rsml_parser::{Attribute, AttributeValue, Node, Parser};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+pub use hyprui_rsml_parser::ParseError;
+
+use crate::{Align, Container, Direction, Element, Justify, RichText, Span, Text, use_memo};
+
+/// A value used in the braces of interpreted RSML.
+#[derive(Clone)]
+pub enum Value {
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Tuple(Vec<Value>),
+	/// A path like `Align::Center`, by its last segment.
+	Variant(String),
+	Callback(Rc<dyn Fn()>),
+}
+
+impl fmt::Debug for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Bool(value) => write!(f, "{value}"),
+			Self::Number(value) => write!(f, "{value}"),
+			Self::String(value) => write!(f, "{value:?}"),
+			Self::Tuple(values) => f.debug_list().entries(values).finish(),
+			Self::Variant(name) => write!(f, "{name}"),
+			Self::Callback(_) => write!(f, "<callback>"),
+		}
+	}
+}
+
+impl Value {
+	/// Returns the value as it is shown in a `<text>`.
+	fn display(&self) -> Result<String, String> {
+		Ok(match self {
+			Self::Bool(value) => value.to_string(),
+			Self::Number(value) => value.to_string(),
+			Self::String(value) => value.clone(),
+			Self::Tuple(values) => {
+				let values = values
+					.iter()
+					.map(Value::display)
+					.collect::<Result<Vec<_>, _>>()?;
+				format!("({})", values.join(", "))
+			}
+			Self::Variant(name) => name.clone(),
+			Self::Callback(_) => return Err("a callback can't be shown as text".to_string()),
+		})
+	}
+}
+
+impl From<bool> for Value {
+	fn from(value: bool) -> Self {
+		Self::Bool(value)
+	}
+}
+
+impl From<String> for Value {
+	fn from(value: String) -> Self {
+		Self::String(value)
+	}
+}
+
+impl From<&str> for Value {
+	fn from(value: &str) -> Self {
+		Self::String(value.to_string())
+	}
+}
+
+macro_rules! impl_from_number {
+	($($number:ty),*) => {
+		$(impl From<$number> for Value {
+			fn from(value: $number) -> Self {
+				Self::Number(value as f64)
+			}
+		})*
+	};
+}
+
+impl_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+impl<A: Into<Value>, B: Into<Value>, C: Into<Value>> From<(A, B, C)> for Value {
+	fn from((a, b, c): (A, B, C)) -> Self {
+		Self::Tuple(vec![a.into(), b.into(), c.into()])
+	}
+}
+
+impl<A: Into<Value>, B: Into<Value>, C: Into<Value>, D: Into<Value>> From<(A, B, C, D)> for Value {
+	fn from((a, b, c, d): (A, B, C, D)) -> Self {
+		Self::Tuple(vec![a.into(), b.into(), c.into(), d.into()])
+	}
+}
+
+/// The names interpreted RSML can use: values and callbacks in braces, and elements as components.
+#[derive(Clone, Default)]
+pub struct Scope {
+	values: HashMap<String, Value>,
+	elements: HashMap<String, Rc<dyn Fn() -> Box<dyn Element>>>,
+}
+
+impl Scope {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Lets braces use `value` as `name`, like `{count}`.
+	pub fn value(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+		self.values.insert(name.into(), value.into());
+		self
+	}
+
+	/// Lets handlers use `callback` as `name`, like `on_click={increment}`.
+	pub fn callback(self, name: impl Into<String>, callback: impl Fn() + 'static) -> Self {
+		self.value(name, Value::Callback(Rc::new(callback)))
+	}
+
+	/// Shows the element built by `element` for the component `name`, like `<UserList />`. The component can't take
+	/// attributes or children.
+	pub fn element(
+		mut self,
+		name: impl Into<String>,
+		element: impl Fn() -> Box<dyn Element> + 'static,
+	) -> Self {
+		self.elements.insert(name.into(), Rc::new(element));
+		self
+	}
+}
+
+/// Reads the values written in braces: literals, tuples, variants and names from the scope, separated by commas.
+struct Evaluator<'a> {
+	input: Vec<char>,
+	position: usize,
+	scope: &'a Scope,
+}
+
+impl Evaluator<'_> {
+	fn current(&self) -> Option<char> {
+		self.input.get(self.position).copied()
+	}
+
+	fn skip_whitespace(&mut self) {
+		while self.current().is_some_and(char::is_whitespace) {
+			self.position += 1;
+		}
+	}
+
+	fn unsupported(&self) -> String {
+		let code = self.input.iter().collect::<String>();
+		format!(
+			"`{}` can't be run by the interpreter, give its value to the scope instead",
+			code.trim()
+		)
+	}
+
+	/// Reads values separated by commas up to `end`, or to the end of the braces without one.
+	fn values(&mut self, end: Option<char>) -> Result<Vec<Value>, String> {
+		let mut values = Vec::new();
+		loop {
+			self.skip_whitespace();
+			if self.current() == end {
+				break;
+			}
+			values.push(self.value()?);
+			self.skip_whitespace();
+			match self.current() {
+				Some(',') => self.position += 1,
+				current if current == end => break,
+				_ => return Err(self.unsupported()),
+			}
+		}
+		// Skips the closing parenthesis
+		self.position += 1;
+		Ok(values)
+	}
+
+	fn value(&mut self) -> Result<Value, String> {
+		match self.current() {
+			Some('(') => {
+				self.position += 1;
+				let mut values = self.values(Some(')'))?;
+				Ok(if values.len() == 1 {
+					values.remove(0)
+				} else {
+					Value::Tuple(values)
+				})
+			}
+			Some('"') => self.string(),
+			Some(ch) if ch == '-' || ch.is_ascii_digit() => self.number(),
+			Some(ch) if ch.is_alphabetic() || ch == '_' => self.path(),
+			_ => Err(self.unsupported()),
+		}
+	}
+
+	fn string(&mut self) -> Result<Value, String> {
+		// Skips the opening quote
+		self.position += 1;
+		let mut string = String::new();
+		loop {
+			match self.current() {
+				None => return Err(self.unsupported()),
+				Some('"') => break,
+				Some('\\') => {
+					self.position += 1;
+					match self.current() {
+						Some('n') => string.push('\n'),
+						Some('t') => string.push('\t'),
+						Some(ch) => string.push(ch),
+						None => return Err(self.unsupported()),
+					}
+				}
+				Some(ch) => string.push(ch),
+			}
+			self.position += 1;
+		}
+		self.position += 1;
+		Ok(Value::String(string))
+	}
+
+	fn number(&mut self) -> Result<Value, String> {
+		let start = self.position;
+		self.position += 1;
+		while self
+			.current()
+			.is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch == '_')
+		{
+			self.position += 1;
+		}
+		let literal = self.input[start..self.position]
+			.iter()
+			.filter(|&&ch| ch != '_')
+			.collect::<String>();
+		let (negative, literal) = match literal.strip_prefix('-') {
+			Some(literal) => (true, literal),
+			None => (false, literal.as_str()),
+		};
+		let value = match literal.strip_prefix("0x") {
+			Some(hex) => i64::from_str_radix(hex, 16).ok().map(|value| value as f64),
+			None => {
+				// Type suffixes like `16u16` or `1.5f32` don't change the value
+				let end = literal.find(['u', 'i', 'f']).unwrap_or(literal.len());
+				literal[..end].parse::<f64>().ok()
+			}
+		};
+		match value {
+			Some(value) if negative => Ok(Value::Number(-value)),
+			Some(value) => Ok(Value::Number(value)),
+			None => Err(self.unsupported()),
+		}
+	}
+
+	fn path(&mut self) -> Result<Value, String> {
+		let mut segments = Vec::new();
+		loop {
+			let start = self.position;
+			while self
+				.current()
+				.is_some_and(|ch| ch.is_alphanumeric() || ch == '_')
+			{
+				self.position += 1;
+			}
+			segments.push(self.input[start..self.position].iter().collect::<String>());
+			if self.input[self.position..].starts_with(&[':', ':']) {
+				self.position += 2;
+			} else {
+				break;
+			}
+		}
+		match segments.as_slice() {
+			[name] if name == "true" => Ok(Value::Bool(true)),
+			[name] if name == "false" => Ok(Value::Bool(false)),
+			[name] => self
+				.scope
+				.values
+				.get(name)
+				.cloned()
+				.ok_or_else(|| format!("`{name}` isn't in the scope")),
+			[.., variant] => Ok(Value::Variant(variant.clone())),
+			[] => unreachable!("paths have a segment"),
+		}
+	}
+}
+
+/// Evaluates the code in braces to the values separated by commas in it.
+fn evaluate(code: &str, scope: &Scope) -> Result<Vec<Value>, String> {
+	let mut evaluator = Evaluator {
+		input: code.chars().collect(),
+		position: 0,
+		scope,
+	};
+	evaluator.values(None)
+}
+
+/// The values of an attribute, given to the method it calls.
+struct Arguments<'a> {
+	method: &'a str,
+	values: Vec<Value>,
+}
+
+impl Arguments<'_> {
+	fn get(&self, index: usize) -> Result<&Value, String> {
+		self
+			.values
+			.get(index)
+			.ok_or_else(|| format!("`{}` takes {} arguments", self.method, index + 1))
+	}
+
+	fn number(&self, index: usize) -> Result<f64, String> {
+		match self.get(index)? {
+			Value::Number(value) => Ok(*value),
+			value => Err(format!("`{}` takes a number, not {value:?}", self.method)),
+		}
+	}
+
+	fn f32(&self, index: usize) -> Result<f32, String> {
+		self.number(index).map(|value| value as f32)
+	}
+
+	fn u16(&self, index: usize) -> Result<u16, String> {
+		self.number(index).map(|value| value as u16)
+	}
+
+	fn bool(&self, index: usize) -> Result<bool, String> {
+		match self.get(index)? {
+			Value::Bool(value) => Ok(*value),
+			value => Err(format!("`{}` takes a bool, not {value:?}", self.method)),
+		}
+	}
+
+	fn string(&self, index: usize) -> Result<String, String> {
+		match self.get(index)? {
+			Value::String(value) => Ok(value.clone()),
+			value => Err(format!("`{}` takes a string, not {value:?}", self.method)),
+		}
+	}
+
+	fn callback(&self, index: usize) -> Result<Rc<dyn Fn()>, String> {
+		match self.get(index)? {
+			Value::Callback(callback) => Ok(callback.clone()),
+			value => Err(format!("`{}` takes a callback, not {value:?}", self.method)),
+		}
+	}
+
+	/// Reads `(r, g, b)` or `(r, g, b, a)`.
+	fn color(&self, index: usize) -> Result<Color, String> {
+		let components = match self.get(index)? {
+			Value::Tuple(values) if values.len() == 3 || values.len() == 4 => values
+				.iter()
+				.map(|value| match value {
+					Value::Number(value) => Some(*value as f32),
+					_ => None,
+				})
+				.collect::<Option<Vec<_>>>(),
+			_ => None,
+		};
+		match components.as_deref() {
+			Some(&[r, g, b]) => Ok(Color::rgba(r, g, b, 255.)),
+			Some(&[r, g, b, a]) => Ok(Color::rgba(r, g, b, a)),
+			_ => Err(format!("`{}` takes a color like (r, g, b, a)", self.method)),
+		}
+	}
+
+	fn variant<T: Copy>(&self, index: usize, variants: &[(&str, T)]) -> Result<T, String> {
+		let name = match self.get(index)? {
+			Value::Variant(name) => name,
+			value => return Err(format!("`{}` takes a variant, not {value:?}", self.method)),
+		};
+		variants
+			.iter()
+			.find_map(|(variant, value)| (variant == name).then_some(*value))
+			.ok_or_else(|| format!("`{name}` isn't a variant taken by `{}`", self.method))
+	}
+
+	/// Reads an attribute like `center` or `italic`, which is `true` when it is written without a value.
+	fn flag(&self) -> Result<bool, String> {
+		if self.values.is_empty() {
+			Ok(true)
+		} else {
+			self.bool(0)
+		}
+	}
+}
+
+/// Calls `apply`, a method without arguments like `center`, on `element` if the attribute is `true`.
+fn flag<T>(element: T, args: &Arguments, apply: fn(T) -> T) -> Result<T, String> {
+	Ok(if args.flag()? {
+		apply(element)
+	} else {
+		element
+	})
+}
+
+fn container_method(container: Container, args: &Arguments) -> Result<Container, String> {
+	Ok(match args.method {
+		"w_expand" => flag(container, args, Container::w_expand)?,
+		"h_expand" => flag(container, args, Container::h_expand)?,
+		"w_fit" => flag(container, args, Container::w_fit)?,
+		"center" => flag(container, args, Container::center)?,
+		"scroll_x" => flag(container, args, Container::scroll_x)?,
+		"scroll_y" => flag(container, args, Container::scroll_y)?,
+		"hover_through" => flag(container, args, Container::hover_through)?,
+		"focusable" => flag(container, args, Container::focusable)?,
+		"gap" => container.gap(args.u16(0)?),
+		"padding_all" => container.padding_all(args.u16(0)?),
+		"symmetric_padding" => container.symmetric_padding(args.u16(0)?, args.u16(1)?),
+		"weird_padding" => {
+			container.weird_padding(args.u16(0)?, args.u16(1)?, args.u16(2)?, args.u16(3)?)
+		}
+		"w_fixed" => container.w_fixed(args.f32(0)?),
+		"h_fixed" => container.h_fixed(args.f32(0)?),
+		"size" => container.size(args.f32(0)?, args.f32(1)?),
+		"min_width" => container.min_width(args.f32(0)?),
+		"min_height" => container.min_height(args.f32(0)?),
+		"max_width" => container.max_width(args.f32(0)?),
+		"max_height" => container.max_height(args.f32(0)?),
+		"rounded" => container.rounded(args.f32(0)?),
+		"background_color" => container.background_color(args.color(0)?),
+		"border_color" => container.border_color(args.color(0)?),
+		"border_width" => container.border_width(args.u16(0)?),
+		"elevation" => container.elevation(args.number(0)? as u8),
+		"label" => container.label(args.string(0)?),
+		"tooltip" => container.tooltip(args.string(0)?),
+		"tooltip_delay" => container.tooltip_delay(args.number(0)? as u64),
+		"direction" => container
+			.direction(args.variant(0, &[("Row", Direction::Row), ("Column", Direction::Column)])?),
+		"align" => container.align(args.variant(
+			0,
+			&[
+				("Top", Align::Top),
+				("Center", Align::Center),
+				("Bottom", Align::Bottom),
+			],
+		)?),
+		"justify" => container.justify(args.variant(
+			0,
+			&[
+				("Left", Justify::Left),
+				("Center", Justify::Center),
+				("Right", Justify::Right),
+			],
+		)?),
+		"on_click" => {
+			let callback = args.callback(0)?;
+			container.on_click(move || callback())
+		}
+		"on_right_click" => {
+			let callback = args.callback(0)?;
+			container.on_right_click(move || callback())
+		}
+		method => {
+			return Err(format!(
+				"<container> has no `{method}` the interpreter knows"
+			));
+		}
+	})
+}
+
+fn text_method(text: Text, args: &Arguments) -> Result<Text, String> {
+	Ok(match args.method {
+		"text_left" => flag(text, args, Text::text_left)?,
+		"text_center" => flag(text, args, Text::text_center)?,
+		"text_right" => flag(text, args, Text::text_right)?,
+		"ellipsis" => flag(text, args, Text::ellipsis)?,
+		"font_size" => text.font_size(args.u16(0)?),
+		"color" => text.color(args.color(0)?),
+		"italic" => text.italic(args.flag()?),
+		"font_family" => text.font_family(args.string(0)?),
+		"wrap" => text.wrap(args.bool(0)?),
+		"max_lines" => text.max_lines(args.number(0)? as u32),
+		method => return Err(format!("<text> has no `{method}` the interpreter knows")),
+	})
+}
+
+fn rich_text_method(text: RichText, args: &Arguments) -> Result<RichText, String> {
+	Ok(match args.method {
+		"text_left" => flag(text, args, RichText::text_left)?,
+		"text_center" => flag(text, args, RichText::text_center)?,
+		"text_right" => flag(text, args, RichText::text_right)?,
+		"font_size" => text.font_size(args.u16(0)?),
+		"color" => text.color(args.color(0)?),
+		"italic" => text.italic(args.flag()?),
+		"font_family" => text.font_family(args.string(0)?),
+		method => return Err(format!("<text> has no `{method}` the interpreter knows")),
+	})
+}
+
+fn span_method(span: Span, args: &Arguments) -> Result<Span, String> {
+	Ok(match args.method {
+		"bold" => flag(span, args, Span::bold)?,
+		"font_weight" => span.font_weight(args.number(0)? as i32),
+		"italic" => span.italic(args.flag()?),
+		"font_size" => span.font_size(args.u16(0)?),
+		"color" => span.color(args.color(0)?),
+		method => return Err(format!("<span> has no `{method}` the interpreter knows")),
+	})
+}
+
+/// Builds elements from the DOM, with the values of `scope`.
+struct Interpreter<'a> {
+	scope: &'a Scope,
+}
+
+impl Interpreter<'_> {
+	fn node(&self, node: &Node) -> Result<Box<dyn Element>, ParseError> {
+		match node {
+			Node::Element(element) => self.element(element),
+			Node::Text(text) => Ok(Box::new(Text::new(text.clone()))),
+			Node::Expression(code) => {
+				let name = code.code.trim();
+				if let Some(element) = self.scope.elements.get(name) {
+					return Ok(element());
+				}
+				let text = self.text_content(std::slice::from_ref(node))?;
+				Ok(Box::new(Text::new(text)))
+			}
+		}
+	}
+
+	/// Calls the methods of the attributes on `element`.
+	fn apply_attributes<T>(
+		&self,
+		mut element: T,
+		attributes: &[Attribute],
+		method: fn(T, &Arguments) -> Result<T, String>,
+	) -> Result<T, ParseError> {
+		for attribute in attributes {
+			let error = |message| ParseError {
+				message,
+				position: attribute.position,
+			};
+			let values = match &attribute.value {
+				None => Vec::new(),
+				Some(AttributeValue::String(value)) => {
+					evaluate(&format!("\"{value}\""), self.scope).map_err(error)?
+				}
+				Some(AttributeValue::Expression(code)) => {
+					evaluate(&code.code, self.scope).map_err(|message| ParseError {
+						message,
+						position: code.position,
+					})?
+				}
+			};
+			let args = Arguments {
+				method: &attribute.name,
+				values,
+			};
+			element = method(element, &args).map_err(error)?;
+		}
+		Ok(element)
+	}
+
+	/// Returns the text of a `<text>` or a `<span>`, with the values of its expressions.
+	fn text_content(&self, children: &[Node]) -> Result<String, ParseError> {
+		let mut text = String::new();
+		for child in children {
+			match child {
+				Node::Text(content) => text.push_str(content),
+				Node::Expression(code) => {
+					let error = |message| ParseError {
+						message,
+						position: code.position,
+					};
+					for value in evaluate(&code.code, self.scope).map_err(error)? {
+						text.push_str(&value.display().map_err(error)?);
+					}
+				}
+				Node::Element(element) => {
+					return Err(ParseError {
+						message: "<span> can only contain text and expressions".to_string(),
+						position: element.position,
+					});
+				}
+			}
+		}
+		Ok(text)
+	}
+
+	fn element(&self, element: &hyprui_rsml_parser::Element) -> Result<Box<dyn Element>, ParseError> {
+		let error = |message: String| ParseError {
+			message,
+			position: element.position,
+		};
+		let is_rich_text = element
+			.children
+			.iter()
+			.any(|child| matches!(child, Node::Element(_)));
+		match element.tag_name.as_str() {
+			"container" => {
+				let mut container =
+					self.apply_attributes(Container::new(), &element.attributes, container_method)?;
+				for child in &element.children {
+					if matches!(child, Node::Text(text) if text.trim().is_empty()) {
+						continue;
+					}
+					container = container.child(self.node(child)?);
+				}
+				Ok(Box::new(container))
+			}
+			"text" if is_rich_text => {
+				// A span per <span>, and per run of text and expressions between them
+				let mut text = RichText::new();
+				let mut run = Vec::new();
+				let mut children = element.children.iter().peekable();
+				while let Some(child) = children.next() {
+					match child {
+						Node::Element(child) if child.tag_name != "span" => {
+							return Err(ParseError {
+								message: "<text> can only contain text, expressions and <span>".to_string(),
+								position: child.position,
+							});
+						}
+						Node::Element(span) => {
+							let content = self.text_content(&span.children)?;
+							let span =
+								self.apply_attributes(Span::new(content), &span.attributes, span_method)?;
+							text = text.span(span);
+						}
+						_ => {
+							run.push(child.clone());
+							if matches!(children.peek(), None | Some(Node::Element(_))) {
+								let content = self.text_content(&std::mem::take(&mut run))?;
+								text = text.span(Span::new(content));
+							}
+						}
+					}
+				}
+				Ok(Box::new(self.apply_attributes(
+					text,
+					&element.attributes,
+					rich_text_method,
+				)?))
+			}
+			"text" => {
+				let text = Text::new(self.text_content(&element.children)?);
+				Ok(Box::new(self.apply_attributes(
+					text,
+					&element.attributes,
+					text_method,
+				)?))
+			}
+			name if name.starts_with(char::is_uppercase) => {
+				let Some(component) = self.scope.elements.get(name) else {
+					return Err(error(format!("<{name}> isn't in the scope")));
+				};
+				let has_children = element
+					.children
+					.iter()
+					.any(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()));
+				if !element.attributes.is_empty() || has_children {
+					return Err(error(format!(
+						"<{name}> comes from the scope, it can't take attributes or children"
+					)));
+				}
+				Ok(component())
+			}
+			name => Err(error(format!(
+				"<{name}> isn't supported by the interpreter"
+			))),
+		}
+	}
+}
+
+/// Builds the element written in `rsml`, with the values of `scope`.
+pub fn interpret(rsml: &str, scope: &Scope) -> Result<Box<dyn Element>, ParseError> {
+	let dom = Parser::new(rsml).parse()?;
+	Interpreter { scope }.node(&dom)
+}
+
+/// An RSML file, read again whenever it changes.
+struct WatchedFile {
+	text: Arc<Mutex<Result<String, String>>>,
+	/// Stops watching the file once dropped, with the component.
+	_watcher: Option<RecommendedWatcher>,
+}
+
+fn read(path: &Path) -> Result<String, String> {
+	std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+impl WatchedFile {
+	fn new(path: &Path) -> Self {
+		let text = Arc::new(Mutex::new(read(path)));
+		let watched_text = text.clone();
+		let file = path.to_path_buf();
+		let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+			let Ok(event) = event else {
+				return;
+			};
+			if event.kind.is_access() || !event.paths.contains(&file) {
+				return;
+			}
+			*watched_text.lock().unwrap() = read(&file);
+			crate::winit::wake_up();
+		})
+		.and_then(|mut watcher| {
+			// Editors often save by replacing the file, which ends a watch on the file itself
+			let directory = path.parent().unwrap_or(Path::new("."));
+			watcher.watch(directory, RecursiveMode::NonRecursive)?;
+			Ok(watcher)
+		});
+		let watcher = watcher
+			.inspect_err(|e| log::error!("rsml: can't watch {}: {e}", path.display()))
+			.ok();
+		Self {
+			text,
+			_watcher: watcher,
+		}
+	}
+}
+
+/// Builds the element written in the RSML file at `path`, and renders it again whenever the file changes. See the
+/// [module documentation](self).
+///
+/// Errors in the file are shown in place of the element, with their line and column, until the file is fixed.
+pub fn use_rsml_file(path: impl AsRef<Path>, scope: Scope) -> Box<dyn Element> {
+	let path = std::path::absolute(path.as_ref()).unwrap_or_else(|_| path.as_ref().to_path_buf());
+	let file = use_memo(|| WatchedFile::new(&path), path.clone());
+	let text = file.text.lock().unwrap().clone();
+	let element = text.and_then(|text| {
+		interpret(&text, &scope).map_err(|e| {
+			let (line, column) = e.line_column(&text);
+			format!("{}:{line}:{column}: {}", path.display(), e.message)
+		})
+	});
+	element.unwrap_or_else(|message| Box::new(error_message(message)))
+}
+
+fn error_message(message: String) -> Container {
+	Container::new()
+		.w_expand()
+		.padding_all(16)
+		.background_color(Color::rgb(127., 29., 29.))
+		.child(Text::new(message).color(Color::rgb(255., 255., 255.)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{testing::TestHarness, use_state};
+
+	#[test]
+	fn test_braces_hold_literals_and_names_from_the_scope() {
+		let scope = Scope::new().value("count", 3);
+		let code = r#"12, -1.5f32, (255, 0, 0x10), "say \"hi\"", Direction::Column, count"#;
+		let values = evaluate(code, &scope).unwrap();
+		assert_eq!(
+			format!("{values:?}"),
+			r#"[12, -1.5, [255, 0, 16], "say \"hi\"", Column, 3]"#
+		);
+		assert!(evaluate("count + 1", &scope).is_err());
+		assert!(evaluate("missing", &scope).is_err());
+	}
+
+	fn counter(_: ()) -> Box<dyn Element> {
+		let (count, set_count) = use_state(0);
+		let scope = Scope::new()
+			.value("count", count)
+			.callback("increment", move || set_count(count + 1));
+		let rsml = r#"<container label="increment" padding_all={8} on_click={increment}>
+			<text>Count: {count}</text>
+		</container>"#;
+		interpret(rsml, &scope).unwrap()
+	}
+
+	#[test]
+	fn test_interpreted_rsml_is_rendered_and_clicked() {
+		let mut harness = TestHarness::new(counter, (), (400., 300.));
+		assert!(harness.find_by_label("Count: 0").is_some());
+		harness.click_label("increment");
+		assert!(harness.find_by_label("Count: 1").is_some());
+	}
+
+	#[test]
+	fn test_errors_point_at_the_attribute() {
+		let rsml = "<container gap={8}>\n\t<text size={14}>Hi</text>\n</container>";
+		let Err(error) = interpret(rsml, &Scope::new()) else {
+			panic!("<text> has no size method");
+		};
+		assert_eq!(error.line_column(rsml), (2, 8));
+		assert!(error.message.contains("`size`"), "{}", error.message);
+	}
+
+	#[test]
+	fn test_text_only_takes_spans() {
+		let rsml = "<text>Hi <container /></text>";
+		let Err(error) = interpret(rsml, &Scope::new()) else {
+			panic!("<text> can't contain a <container>");
+		};
+		assert_eq!(error.line_column(rsml), (1, 11));
+	}
+}
@@ -0,0 +1,133 @@
+use clay_layout::{Clay, math::Vector2, render_commands::RenderCommand};
+use skia_safe::Image;
+
+use crate::{
+	Component, Element, InputManager, NamedKey, RenderContext, accessibility,
+	clay_renderer::CustomElement, error_overlay,
+	focus_system::{FocusDirection, GLOBAL_FOCUS_MANAGER}, font_manager::FontManager, input::Key, inspector,
+	profiling::{profile_counter, profile_span},
+};
+
+/// Runs one frame of the UI, independently of where it is displayed.
+///
+/// Handles keyboard focus navigation, lays out the root component and publishes the accessibility tree.
+/// The render commands of the frame are passed to `draw`, which is where a window draws them to its canvas.
+pub(crate) fn run_frame<Props: Clone + 'static>(
+	clay: &mut Clay,
+	font_manager: &mut FontManager,
+	input_manager: &dyn InputManager,
+	component: impl Clone + Copy + Fn(Props) -> Box<dyn Element> + 'static,
+	props: Props,
+	draw: impl FnOnce(&mut dyn Iterator<Item = RenderCommand<'_, Image, CustomElement>>, &FontManager),
+) {
+	profile_span!("frame");
+	let (x, y) = input_manager.mouse_position();
+	clay.pointer_state(Vector2::new(x, y), input_manager.is_mouse_button_pressed(0));
+	accessibility::begin_frame();
+	inspector::begin_frame(input_manager);
+	crate::frame_scheduler::begin_frame();
+	crate::image::begin_frame();
+	crate::overlay::begin_frame();
+	crate::sticky::begin_frame();
+	crate::selection::begin_frame(input_manager);
+	crate::shortcuts::begin_frame();
+	crate::dispatcher::run_pending_tasks();
+	#[cfg(feature = "dbus")]
+	crate::notifications::dispatch_events();
+	// Popups are dismissed before the focus manager is borrowed, their handlers may move the focus
+	let escape_dismissed = crate::overlay::dismiss(input_manager);
+	font_manager.update_clay_measure_function(clay);
+	let root_component = {
+		profile_span!("build");
+		crate::hooks::build_roots(|| {
+			let root = error_overlay::build_root(|| Component::new(component, props));
+			crate::osd::build();
+			root
+		})
+	};
+	let overlays = crate::overlay::take_overlays();
+	// Shortcuts match the registrations of this frame, and their keys don't reach the focused element
+	let handled = crate::shortcuts::dispatch(input_manager);
+	let input_manager = &crate::shortcuts::Unhandled::new(input_manager, handled);
+	GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| {
+		f.begin_frame();
+		f.add_root();
+		f.update_scopes();
+		if input_manager.is_key_just_pressed(Key::Named(NamedKey::Tab)) {
+			if input_manager.is_key_pressed(Key::Named(NamedKey::Shift)) {
+				f.focus_prev();
+			} else {
+				f.focus_next();
+			}
+		} else if let Some(direction) = focus_direction(input_manager)
+			&& f.focused().is_some()
+			&& !f.focused_captures_arrows()
+		{
+			f.focus_in_direction(direction);
+		}
+
+		if (!input_manager.cursor_hit_something() && (input_manager.is_mouse_button_just_pressed(0) || input_manager.is_mouse_button_just_pressed(1))) || (input_manager.is_key_just_pressed(Key::Named(NamedKey::Escape)) && !escape_dismissed) {
+			f.blur();
+		}
+		f.new_frame();
+		crate::scroll::begin_frame(f.focused());
+	});
+
+	let mut c = clay.begin();
+
+	{
+		profile_span!("declare");
+		let mut render_ctx = RenderContext {
+			c: &mut c,
+			font_manager: &mut *font_manager,
+			input_manager,
+		};
+		root_component.render(&mut render_ctx);
+		crate::overlay::render(&mut render_ctx, &overlays);
+	}
+
+	{
+		let mut commands = {
+			profile_span!("layout");
+			c.end()
+		};
+		profile_span!("paint");
+		let mut command_count = 0usize;
+		if crate::sticky::is_active() {
+			// Sticky elements are moved once everything is laid out
+			let mut commands = commands.collect::<Vec<_>>();
+			crate::sticky::apply(&mut commands, |id| c.bounding_box(id));
+			draw(&mut commands.into_iter().inspect(|_| command_count += 1), font_manager);
+		} else {
+			draw(&mut commands.by_ref().inspect(|_| command_count += 1), font_manager);
+		}
+		profile_counter!(render_commands, command_count);
+	}
+	accessibility::end_frame(|id| c.bounding_box(id));
+	crate::element_ids::end_frame(|id| c.bounding_box(id));
+	crate::element::text::end_frame(|id| c.bounding_box(id));
+	crate::hover::end_frame();
+	GLOBAL_FOCUS_MANAGER.with_borrow_mut(|f| f.end_frame(|id| c.bounding_box(id)));
+	inspector::end_frame(|id| c.bounding_box(id));
+	crate::scroll::end_frame(input_manager, |id| c.bounding_box(id));
+	crate::element::container::drag_and_drop::end_frame(input_manager);
+}
+
+/// Returns the direction of the arrow key pressed this frame without modifiers, which moves focus.
+fn focus_direction(input_manager: &dyn InputManager) -> Option<FocusDirection> {
+	let modified = [NamedKey::Control, NamedKey::Alt, NamedKey::Shift]
+		.into_iter()
+		.any(|key| input_manager.is_key_pressed(Key::Named(key)));
+	if modified {
+		return None;
+	}
+	[
+		(NamedKey::ArrowUp, FocusDirection::Up),
+		(NamedKey::ArrowDown, FocusDirection::Down),
+		(NamedKey::ArrowLeft, FocusDirection::Left),
+		(NamedKey::ArrowRight, FocusDirection::Right),
+	]
+	.into_iter()
+	.find(|(key, _)| input_manager.is_key_just_pressed(Key::Named(*key)))
+	.map(|(_, direction)| direction)
+}
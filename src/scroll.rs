@@ -0,0 +1,1038 @@
+use std::{
+	cell::{Cell, RefCell},
+	rc::Rc,
+};
+
+use clay_layout::{id::Id, math::BoundingBox};
+use uuid::Uuid;
+
+use crate::{GlobalClosure, InputManager, element::container::PullGesture, use_ref};
+
+/// Fastest fling, in logical pixels per second.
+const MAX_VELOCITY: f32 = 8000.;
+/// A fling slower than this stops.
+const MIN_VELOCITY: f32 = 10.;
+/// Farthest the content goes past its ends with [`Overscroll::Bounce`].
+const MAX_OVERSCROLL: f32 = 80.;
+/// Fraction of the overscroll left after one second, as the content springs back.
+const SPRING_BACK: f32 = 1e-4;
+
+/// What happens when scrolling reaches an end of the content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overscroll {
+	/// The content stops at its ends.
+	#[default]
+	Clamp,
+	/// The content can be dragged and flung a little past its ends, then springs back.
+	Bounce,
+}
+
+/// How a scrollable [`Container`](crate::Container) moves, set with
+/// [`Container::scroll_physics`](crate::Container::scroll_physics).
+///
+/// By default, content only moves with the mouse wheel or the touchpad, and stops as soon as they do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollPhysics {
+	/// The content keeps moving after a fling of the touchpad or a drag, slowing down with `friction`.
+	pub kinetic: bool,
+	/// Fraction of the speed of a fling lost every second, from `0` to `1`.
+	pub friction: f32,
+	pub overscroll: Overscroll,
+	/// The content follows the pointer when dragged with the left button or a finger.
+	pub drag: bool,
+}
+
+impl Default for ScrollPhysics {
+	fn default() -> Self {
+		Self {
+			kinetic: false,
+			friction: 0.95,
+			overscroll: Overscroll::Clamp,
+			drag: false,
+		}
+	}
+}
+
+impl ScrollPhysics {
+	/// Scrolling like on a phone: the content is dragged, flung and bounces at its ends.
+	pub fn kinetic() -> Self {
+		Self {
+			kinetic: true,
+			overscroll: Overscroll::Bounce,
+			drag: true,
+			..Default::default()
+		}
+	}
+
+	pub fn friction(mut self, friction: f32) -> Self {
+		self.friction = friction.clamp(0., 1.);
+		self
+	}
+
+	pub fn overscroll(mut self, overscroll: Overscroll) -> Self {
+		self.overscroll = overscroll;
+		self
+	}
+
+	pub fn drag(mut self, drag: bool) -> Self {
+		self.drag = drag;
+		self
+	}
+
+	/// Returns these physics without the motion that goes on after the input stops, flings and bounces, used
+	/// when the user [prefers reduced motion](crate::prefers_reduced_motion).
+	fn reduced(self) -> Self {
+		Self {
+			kinetic: false,
+			overscroll: Overscroll::Clamp,
+			..self
+		}
+	}
+}
+
+/// Where the content of a scrollable container can come to rest, see [`ScrollSnap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapPoints {
+	/// The start of each child, so a carousel settles with an item at its start.
+	Children,
+	/// Every multiple of a distance in logical pixels, like the rows of a grid.
+	Interval(f32),
+}
+
+/// How strongly the content of a container is pulled to its snap points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapStrictness {
+	/// The content always comes to rest on a snap point.
+	Mandatory,
+	/// The content comes to rest on a snap point only when it would stop closer to it than this distance.
+	Proximity(f32),
+}
+
+/// Snap points of a scrollable [`Container`](crate::Container), set with
+/// [`Container::scroll_snap`](crate::Container::scroll_snap).
+///
+/// Once the wheel, a drag or a fling stops, the content eases to the snap point it would have stopped closest to,
+/// ahead of where it was going, or jumps to it when the user [prefers reduced
+/// motion](crate::prefers_reduced_motion). The end of the content is always a snap point, so it can be reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollSnap {
+	pub points: SnapPoints,
+	pub strictness: SnapStrictness,
+}
+
+impl ScrollSnap {
+	/// Snaps to the start of each child.
+	pub fn children() -> Self {
+		Self {
+			points: SnapPoints::Children,
+			strictness: SnapStrictness::Mandatory,
+		}
+	}
+
+	/// Snaps to every multiple of `distance`.
+	pub fn interval(distance: f32) -> Self {
+		Self {
+			points: SnapPoints::Interval(distance),
+			strictness: SnapStrictness::Mandatory,
+		}
+	}
+
+	/// Only snaps when the content would stop within `distance` of a snap point.
+	pub fn proximity(mut self, distance: f32) -> Self {
+		self.strictness = SnapStrictness::Proximity(distance);
+		self
+	}
+
+	/// Returns the point among `points` the content comes to rest on, when it would stop at `rest` after last
+	/// moving by `moved` from `offset`.
+	fn point(&self, points: &[f32], offset: f32, rest: f32, moved: f32) -> Option<f32> {
+		let nearest = |points: &mut dyn Iterator<Item = f32>| {
+			points.min_by(|a, b| (a - rest).abs().total_cmp(&(b - rest).abs()))
+		};
+		// The wheel and slow drags move towards the next point instead of falling back to the last one
+		let point = nearest(
+			&mut points
+				.iter()
+				.copied()
+				.filter(|point| moved == 0. || (point - offset) * moved.signum() > -0.5),
+		)
+		.or_else(|| nearest(&mut points.iter().copied()))?;
+		match self.strictness {
+			SnapStrictness::Mandatory => Some(point),
+			SnapStrictness::Proximity(distance) => ((point - rest).abs() <= distance).then_some(point),
+		}
+	}
+}
+
+/// Scroll position of a scrollable [`Container`](crate::Container), kept between frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ScrollState {
+	/// How far the content is scrolled, from `(0, 0)` to the size of the content minus the viewport.
+	pub offset: (f32, f32),
+	/// Size of the visible area in the last frame.
+	pub viewport: (f32, f32),
+	/// Size of the content in the last frame.
+	pub content: (f32, f32),
+	/// How far the content is pulled down, see [`Container::pull_to_refresh`](crate::Container::pull_to_refresh).
+	pub pull: PullGesture,
+	/// Speed of a fling, in logical pixels per second.
+	pub velocity: (f32, f32),
+	/// Where the pointer was in the last frame, while it drags the content.
+	pub drag: Option<(f32, f32)>,
+	/// The wheel scrolled the content in the last frame, so the next wheel event is part of a fling.
+	pub wheeling: bool,
+	/// How far the last frame of the wheel or of a drag moved the content, to snap in that direction.
+	pub moved: (f32, f32),
+	/// The snap points the content is easing to.
+	pub snap_target: (Option<f32>, Option<f32>),
+	/// Size of the content when its end was last reached, see
+	/// [`Container::on_reach_end`](crate::Container::on_reach_end).
+	pub end_reached: Option<(f32, f32)>,
+}
+
+impl ScrollState {
+	pub fn max_offset(&self) -> (f32, f32) {
+		(
+			(self.content.0 - self.viewport.0).max(0.),
+			(self.content.1 - self.viewport.1).max(0.),
+		)
+	}
+
+	fn clamp(&mut self) {
+		let max = self.max_offset();
+		self.offset = (
+			self.offset.0.clamp(0., max.0),
+			self.offset.1.clamp(0., max.1),
+		);
+	}
+
+	/// Follows the content moving by `moved` in a frame lasting `dt` seconds, to fling it at that speed.
+	fn track(&mut self, moved: (f32, f32), dt: f32) {
+		if dt <= 0. {
+			return;
+		}
+		// Smoothed, so a last frame of a drag that barely moved doesn't stop the fling
+		let track = |velocity: f32, moved: f32| {
+			(0.8 * moved / dt + 0.2 * velocity).clamp(-MAX_VELOCITY, MAX_VELOCITY)
+		};
+		self.velocity = (
+			track(self.velocity.0, moved.0),
+			track(self.velocity.1, moved.1),
+		);
+	}
+
+	/// Moves the content by a frame of `dt` seconds of its fling, or back inside its bounds after an overscroll.
+	/// Returns `true` while it moves.
+	fn step(&mut self, physics: &ScrollPhysics, axes: (bool, bool), dt: f32) -> bool {
+		let max = self.max_offset();
+		let horizontal =
+			axes.0 && step_axis(&mut self.offset.0, &mut self.velocity.0, max.0, physics, dt);
+		let vertical =
+			axes.1 && step_axis(&mut self.offset.1, &mut self.velocity.1, max.1, physics, dt);
+		horizontal || vertical
+	}
+
+	/// Moves the content by a frame of `dt` seconds towards the snap points in `points`, see [`ScrollSnap`], or
+	/// straight onto them with `reduced_motion`. Axes without a snap point to rest on move like
+	/// [`step`](Self::step). Returns `true` while it moves.
+	fn settle(
+		&mut self,
+		snap: &ScrollSnap,
+		points: [&[f32]; 2],
+		physics: &ScrollPhysics,
+		axes: (bool, bool),
+		dt: f32,
+		reduced_motion: bool,
+	) -> bool {
+		let max = self.max_offset();
+		// How fast a fling slows down, to tell where it would stop
+		let decay = if physics.kinetic {
+			(-(1. - physics.friction).ln()).max(f32::EPSILON)
+		} else {
+			f32::INFINITY
+		};
+		let mut offset = [self.offset.0, self.offset.1];
+		let mut velocity = [self.velocity.0, self.velocity.1];
+		let mut target = [self.snap_target.0, self.snap_target.1];
+		let moved = [self.moved.0, self.moved.1];
+		let mut moving = false;
+		for (axis, enabled) in [axes.0, axes.1].into_iter().enumerate() {
+			if !enabled {
+				continue;
+			}
+			if target[axis].is_none() {
+				let rest = offset[axis] + velocity[axis] / decay;
+				target[axis] = snap.point(points[axis], offset[axis], rest, moved[axis]);
+			}
+			let Some(point) = target[axis] else {
+				moving |= step_axis(
+					&mut offset[axis],
+					&mut velocity[axis],
+					[max.0, max.1][axis],
+					physics,
+					dt,
+				);
+				continue;
+			};
+			velocity[axis] = 0.;
+			let left = (offset[axis] - point) * SPRING_BACK.powf(dt);
+			let before = offset[axis];
+			offset[axis] = if reduced_motion || left.abs() < 0.5 {
+				point
+			} else {
+				point + left
+			};
+			if offset[axis] == point {
+				target[axis] = None;
+			}
+			moving |= offset[axis] != before;
+		}
+		self.offset = (offset[0], offset[1]);
+		self.velocity = (velocity[0], velocity[1]);
+		self.snap_target = (target[0], target[1]);
+		moving
+	}
+
+	/// Scrolls by `delta` on the enabled axes, returning the part of `delta` that could not be used.
+	fn scroll_by(&mut self, delta: (f32, f32), axes: (bool, bool)) -> (f32, f32) {
+		let before = self.offset;
+		if axes.0 {
+			self.offset.0 += delta.0;
+		}
+		if axes.1 {
+			self.offset.1 += delta.1;
+		}
+		self.clamp();
+		(
+			delta.0 - (self.offset.0 - before.0),
+			delta.1 - (self.offset.1 - before.1),
+		)
+	}
+}
+
+/// The scroll position of a scrollable [`Container`](crate::Container), shared with the component that declares it.
+///
+/// Created with [`use_scroll_handle`] and given to the container with
+/// [`Container::scroll_handle`](crate::Container::scroll_handle).
+#[derive(Clone)]
+pub struct ScrollHandle {
+	pub(crate) state: Rc<RefCell<ScrollState>>,
+}
+
+impl ScrollHandle {
+	/// How far the content is scrolled, in logical pixels.
+	pub fn offset(&self) -> (f32, f32) {
+		self.state.borrow().offset
+	}
+
+	/// The largest offset, reached when the end of the content is visible.
+	pub fn max_offset(&self) -> (f32, f32) {
+		self.state.borrow().max_offset()
+	}
+
+	/// Scrolls the content to `offset`, clamped to the content, from the next frame on.
+	pub fn scroll_to(&self, offset: (f32, f32)) {
+		let mut state = self.state.borrow_mut();
+		state.offset = offset;
+		state.snap_target = (None, None);
+		state.clamp();
+		crate::REQUEST_REDRAW.call();
+	}
+}
+
+/// Keeps the scroll position of a container across renders, to read it or scroll from code.
+pub fn use_scroll_handle() -> ScrollHandle {
+	ScrollHandle {
+		state: use_ref(ScrollState::default()),
+	}
+}
+
+/// How far a container is scrolled, returned by [`use_scroll_progress`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollProgress {
+	/// How far the content is scrolled, in logical pixels.
+	pub offset: (f32, f32),
+	/// Horizontal progress, from `0` at the start of the content to `1` at its end.
+	pub x: f32,
+	/// Vertical progress, from `0` at the top of the content to `1` at its bottom.
+	pub y: f32,
+}
+
+impl ScrollProgress {
+	/// Returns the horizontal progress over the first `distance` pixels, from `0` to `1`.
+	pub fn x_over(&self, distance: f32) -> f32 {
+		progress(self.offset.0, distance)
+	}
+
+	/// Returns the vertical progress over the first `distance` pixels, from `0` to `1`, like a header that is
+	/// collapsed once the content moved by its height.
+	pub fn y_over(&self, distance: f32) -> f32 {
+		progress(self.offset.1, distance)
+	}
+}
+
+fn progress(offset: f32, distance: f32) -> f32 {
+	if distance <= 0. {
+		return 1.;
+	}
+	(offset / distance).clamp(0., 1.)
+}
+
+/// Returns how far the container of `handle` is scrolled, to animate elements along with it.
+///
+/// The progress is the one of the last frame, as the content is measured after layout; the component is rendered
+/// again whenever the container scrolls. Content that fits the container has a progress of `0`.
+///
+/// ```rust,ignore
+/// let scroll = use_scroll_handle();
+/// let collapsed = use_scroll_progress(&scroll).y_over(80.);
+/// Container::column()
+///     .child(
+///         Container::new()
+///             .min_height(120. - 60. * collapsed)
+///             .max_height(120. - 60. * collapsed)
+///             .child(Text::new("Inbox")),
+///     )
+///     .child(Container::column().scroll_y().scroll_handle(&scroll).child(rows))
+/// ```
+pub fn use_scroll_progress(handle: &ScrollHandle) -> ScrollProgress {
+	let state = *handle.state.borrow();
+	let max = state.max_offset();
+	let fraction = |offset: f32, max: f32| {
+		if max > 0. {
+			(offset / max).clamp(0., 1.)
+		} else {
+			0.
+		}
+	};
+	ScrollProgress {
+		offset: state.offset,
+		x: fraction(state.offset.0, max.0),
+		y: fraction(state.offset.1, max.1),
+	}
+}
+
+/// Moves `offset` along one axis by a frame of its fling, see [`ScrollState::step`].
+fn step_axis(
+	offset: &mut f32,
+	velocity: &mut f32,
+	max: f32,
+	physics: &ScrollPhysics,
+	dt: f32,
+) -> bool {
+	let overscroll = *offset - offset.clamp(0., max);
+	if overscroll != 0. {
+		*velocity = 0.;
+		let left = overscroll * SPRING_BACK.powf(dt);
+		if left.abs() < 0.5 {
+			*offset = offset.clamp(0., max);
+			return false;
+		}
+		*offset += left - overscroll;
+		return true;
+	}
+	if velocity.abs() < MIN_VELOCITY {
+		*velocity = 0.;
+		return false;
+	}
+	*offset += *velocity * dt;
+	*velocity *= (1. - physics.friction).powf(dt);
+	if *offset < 0. || *offset > max {
+		// A bounce springs back from where the fling took the content
+		*offset = match physics.overscroll {
+			Overscroll::Clamp => offset.clamp(0., max),
+			Overscroll::Bounce => offset.clamp(-MAX_OVERSCROLL, max + MAX_OVERSCROLL),
+		};
+		*velocity = 0.;
+	}
+	true
+}
+
+/// A scrollable container declared this frame.
+#[derive(Clone)]
+pub(crate) struct ScrollContainer {
+	pub state: Rc<RefCell<ScrollState>>,
+	pub axes: (bool, bool),
+	pub viewport: Id,
+	pub content: Id,
+	/// The overlay layer of the container, see [`crate::overlay`].
+	pub layer: usize,
+	pub physics: ScrollPhysics,
+	pub snap: Option<ScrollSnap>,
+	/// The children of the container, declared with ids for [`SnapPoints::Children`].
+	pub snap_children: Vec<Id>,
+	/// Distance from the end under which `on_reach_end` is called.
+	pub on_reach_end: Option<(f32, Rc<dyn Fn()>)>,
+}
+
+thread_local! {
+	/// Scroll containers declared this frame, in tree order.
+	static CONTAINERS: RefCell<Vec<ScrollContainer>> = const { RefCell::new(Vec::new()) };
+	/// Scroll containers being declared with their index in `CONTAINERS`, innermost last.
+	static OPEN: RefCell<Vec<(usize, ScrollContainer)>> = const { RefCell::new(Vec::new()) };
+	/// The element being declared is a child of a container snapping to its children.
+	static SNAP_CHILD: Cell<bool> = const { Cell::new(false) };
+	/// The focus node to bring into view.
+	static TARGET: Cell<Option<Uuid>> = const { Cell::new(None) };
+	/// The element of the target and the scroll containers around it, innermost first.
+	static TARGET_ELEMENT: RefCell<Option<(Id, Vec<ScrollContainer>)>> = const { RefCell::new(None) };
+	static LAST_FOCUSED: Cell<Option<Uuid>> = const { Cell::new(None) };
+}
+
+/// Scrolls the containers around the element of the focus node `id`, so it becomes visible.
+pub(crate) fn scroll_into_view(id: Uuid) {
+	TARGET.set(Some(id));
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Brings the focused element into view when focus moved since the last frame.
+pub(crate) fn begin_frame(focused: Option<Uuid>) {
+	CONTAINERS.with_borrow_mut(|containers| containers.clear());
+	OPEN.with_borrow_mut(|open| open.clear());
+	if LAST_FOCUSED.replace(focused) != focused
+		&& let Some(focused) = focused
+	{
+		TARGET.set(Some(focused));
+	}
+}
+
+/// Returns `true` if the element with the focus node `id` should be scrolled into view.
+pub(crate) fn is_target(id: Uuid) -> bool {
+	TARGET.get() == Some(id)
+}
+
+/// Records the element of the target, called while it is declared.
+pub(crate) fn set_target_element(element: Id) {
+	let containers = OPEN.with_borrow(|open| {
+		open
+			.iter()
+			.rev()
+			.map(|(_, container)| container.clone())
+			.collect()
+	});
+	TARGET_ELEMENT.with_borrow_mut(|target| *target = Some((element, containers)));
+}
+
+/// Opens a scroll container, so elements declared until [`pop_container`] are inside it.
+pub(crate) fn push_container(container: ScrollContainer) {
+	let index = CONTAINERS.with_borrow_mut(|containers| {
+		containers.push(container.clone());
+		containers.len() - 1
+	});
+	OPEN.with_borrow_mut(|open| open.push((index, container)));
+}
+
+pub(crate) fn pop_container() {
+	OPEN.with_borrow_mut(|open| open.pop());
+}
+
+/// Returns the viewport of the innermost scroll container being declared.
+pub(crate) fn innermost_viewport() -> Option<Id> {
+	OPEN.with_borrow(|open| open.last().map(|(_, container)| container.viewport.clone()))
+}
+
+/// Marks the elements declared next as children of a container snapping to its children, until the first of
+/// them takes the mark with [`take_snap_child`].
+pub(crate) fn mark_snap_child(snap: bool) {
+	SNAP_CHILD.set(snap);
+}
+
+/// Returns `true` if the element being declared is a child of a container snapping to its children, which must
+/// then give its id to [`add_snap_child`].
+pub(crate) fn take_snap_child() -> bool {
+	SNAP_CHILD.replace(false)
+}
+
+/// Records the element of a child of the innermost scroll container, whose start is a snap point.
+pub(crate) fn add_snap_child(element: Id) {
+	let Some(index) = OPEN.with_borrow(|open| open.last().map(|(index, _)| *index)) else {
+		return;
+	};
+	CONTAINERS.with_borrow_mut(|containers| containers[index].snap_children.push(element));
+}
+
+/// Returns the snap points of `container` on both axes, relative to the start of its content.
+fn snap_points(
+	container: &ScrollContainer,
+	snap: &ScrollSnap,
+	max: (f32, f32),
+	bounding_box: &impl Fn(Id) -> Option<BoundingBox>,
+) -> (Vec<f32>, Vec<f32>) {
+	let mut points = match snap.points {
+		SnapPoints::Children => {
+			let Some(content) = bounding_box(container.content.clone()) else {
+				return (Vec::new(), Vec::new());
+			};
+			container
+				.snap_children
+				.iter()
+				.filter_map(|child| bounding_box(child.clone()))
+				.map(|child| {
+					(
+						(child.x - content.x).clamp(0., max.0),
+						(child.y - content.y).clamp(0., max.1),
+					)
+				})
+				.unzip()
+		}
+		SnapPoints::Interval(distance) if distance > 0. => {
+			let multiples = |max: f32| {
+				(0..)
+					.map(|i| i as f32 * distance)
+					.take_while(|point| *point < max)
+					.collect::<Vec<_>>()
+			};
+			(multiples(max.0), multiples(max.1))
+		}
+		SnapPoints::Interval(_) => (Vec::new(), Vec::new()),
+	};
+	points.0.push(max.0);
+	points.1.push(max.1);
+	points
+}
+
+fn contains(bounds: &BoundingBox, (x, y): (f32, f32)) -> bool {
+	x >= bounds.x && y >= bounds.y && x < bounds.x + bounds.width && y < bounds.y + bounds.height
+}
+
+/// Returns the offset that makes `start..start + size` visible in a viewport at `viewport_start`, moving as little as possible.
+fn reveal(offset: f32, viewport_start: f32, viewport_size: f32, start: f32, size: f32) -> f32 {
+	if start < viewport_start || size > viewport_size {
+		offset - (viewport_start - start)
+	} else if start + size > viewport_start + viewport_size {
+		offset + (start + size) - (viewport_start + viewport_size)
+	} else {
+		offset
+	}
+}
+
+/// Moves the content by `moved` on the enabled axes while it is dragged, resisting past its ends.
+fn drag_axis(offset: &mut f32, moved: f32, max: f32, physics: &ScrollPhysics) {
+	let outside = *offset < 0. || *offset > max;
+	*offset += if outside { moved * 0.5 } else { moved };
+	*offset = match physics.overscroll {
+		Overscroll::Clamp => offset.clamp(0., max),
+		Overscroll::Bounce => offset.clamp(-MAX_OVERSCROLL, max + MAX_OVERSCROLL),
+	};
+}
+
+/// Measures the scroll containers after layout, applies the wheel to the innermost hovered one and
+/// scrolls the target into view. Drags and flings move the containers with [`ScrollPhysics`] for them, which
+/// neither fling nor bounce when the user prefers reduced motion.
+pub(crate) fn end_frame(
+	input_manager: &dyn InputManager,
+	bounding_box: impl Fn(Id) -> Option<BoundingBox>,
+) {
+	let mut containers = CONTAINERS.with_borrow_mut(std::mem::take);
+	let reduced_motion = crate::prefers_reduced_motion();
+	if reduced_motion {
+		for container in &mut containers {
+			container.physics = container.physics.reduced();
+		}
+	}
+	let dt = crate::frame_scheduler::frame_delta().as_secs_f32();
+	let mut changed = false;
+	for container in &containers {
+		let mut state = container.state.borrow_mut();
+		let before = *state;
+		if let Some(viewport) = bounding_box(container.viewport.clone()) {
+			state.viewport = (viewport.width, viewport.height);
+		}
+		if let Some(content) = bounding_box(container.content.clone()) {
+			state.content = (content.width, content.height);
+		}
+		// Content past its ends springs back instead
+		if container.physics.overscroll == Overscroll::Clamp {
+			state.clamp();
+		}
+		changed |= *state != before;
+	}
+	for container in &containers {
+		let Some((threshold, on_reach_end)) = &container.on_reach_end else {
+			continue;
+		};
+		let near_end = {
+			let mut state = container.state.borrow_mut();
+			let max = state.max_offset();
+			let near = |enabled: bool, offset: f32, max: f32| enabled && max - offset <= *threshold;
+			// Called once per size of the content, so again only once more items were loaded
+			let near_end = (near(container.axes.0, state.offset.0, max.0)
+				|| near(container.axes.1, state.offset.1, max.1))
+				&& state.end_reached != Some(state.content);
+			if near_end {
+				state.end_reached = Some(state.content);
+			}
+			near_end
+		};
+		if near_end {
+			on_reach_end();
+		}
+	}
+
+	let pointer = input_manager.mouse_position();
+	let under_pointer = |container: &ScrollContainer| {
+		crate::overlay::pointer_reaches(container.layer)
+			&& bounding_box(container.viewport.clone()).is_some_and(|bounds| contains(&bounds, pointer))
+	};
+
+	// A press grabs the content of the innermost draggable container under the pointer
+	let mut grabbing = input_manager.is_mouse_button_just_pressed(0);
+	for container in containers.iter().rev() {
+		if !container.physics.drag {
+			continue;
+		}
+		let mut state = container.state.borrow_mut();
+		if grabbing && under_pointer(container) {
+			grabbing = false;
+			state.drag = Some(pointer);
+			state.velocity = (0., 0.);
+		} else if let Some(last) = state.drag {
+			if input_manager.is_mouse_button_pressed(0) {
+				let moved = (
+					if container.axes.0 {
+						last.0 - pointer.0
+					} else {
+						0.
+					},
+					if container.axes.1 {
+						last.1 - pointer.1
+					} else {
+						0.
+					},
+				);
+				let max = state.max_offset();
+				drag_axis(&mut state.offset.0, moved.0, max.0, &container.physics);
+				drag_axis(&mut state.offset.1, moved.1, max.1, &container.physics);
+				state.track(moved, dt);
+				state.drag = Some(pointer);
+				state.moved = moved;
+				state.snap_target = (None, None);
+			} else {
+				state.drag = None;
+				if !container.physics.kinetic {
+					state.velocity = (0., 0.);
+				}
+			}
+			changed = true;
+		}
+	}
+
+	let mut delta = input_manager.scroll_delta();
+	let mut wheeled = vec![false; containers.len()];
+	// The innermost container scrolls first, and passes what it can't use to the ones around it
+	for (index, container) in containers.iter().enumerate().rev() {
+		if delta == (0., 0.) {
+			break;
+		}
+		if under_pointer(container) {
+			let mut state = container.state.borrow_mut();
+			let remaining = state.scroll_by(delta, container.axes);
+			if remaining != delta {
+				changed = true;
+				wheeled[index] = true;
+				state.moved = (delta.0 - remaining.0, delta.1 - remaining.1);
+				state.snap_target = (None, None);
+				// A single notch of a mouse wheel doesn't fling, the stream of events of a touchpad does
+				if container.physics.kinetic && state.wheeling {
+					state.track((delta.0 - remaining.0, delta.1 - remaining.1), dt);
+				} else {
+					state.velocity = (0., 0.);
+				}
+			}
+			delta = remaining;
+		}
+	}
+
+	for (container, wheeled) in containers.iter().zip(wheeled) {
+		let mut state = container.state.borrow_mut();
+		state.wheeling = wheeled;
+		if wheeled || state.drag.is_some() {
+			continue;
+		}
+		let physics = container.physics;
+		if let Some(snap) = &container.snap {
+			let points = snap_points(container, snap, state.max_offset(), &bounding_box);
+			changed |= state.settle(
+				snap,
+				[&points.0, &points.1],
+				&physics,
+				container.axes,
+				dt,
+				reduced_motion,
+			);
+		} else if physics.kinetic || physics.overscroll == Overscroll::Bounce {
+			changed |= state.step(&physics, container.axes, dt);
+		}
+	}
+
+	if let Some((element, containers)) = TARGET_ELEMENT.take() {
+		TARGET.set(None);
+		if let Some(mut target) = bounding_box(element) {
+			for container in containers {
+				let Some(viewport) = bounding_box(container.viewport.clone()) else {
+					continue;
+				};
+				let mut state = container.state.borrow_mut();
+				let before = state.offset;
+				state.velocity = (0., 0.);
+				state.snap_target = (None, None);
+				if container.axes.0 {
+					state.offset.0 = reveal(
+						state.offset.0,
+						viewport.x,
+						viewport.width,
+						target.x,
+						target.width,
+					);
+				}
+				if container.axes.1 {
+					state.offset.1 = reveal(
+						state.offset.1,
+						viewport.y,
+						viewport.height,
+						target.y,
+						target.height,
+					);
+				}
+				state.clamp();
+				// The target moves with the content, which matters to the containers around this one
+				target.x -= state.offset.0 - before.0;
+				target.y -= state.offset.1 - before.1;
+				changed |= state.offset != before;
+			}
+		}
+	}
+	if changed {
+		crate::REQUEST_REDRAW.call();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+	use crate::{Container, Element, Text, testing::TestHarness, use_state};
+
+	#[test]
+	fn test_reveal() {
+		// Already visible
+		assert_eq!(reveal(10., 0., 100., 20., 30.), 10.);
+		// Above the viewport
+		assert_eq!(reveal(50., 0., 100., -20., 10.), 30.);
+		// Below the viewport
+		assert_eq!(reveal(0., 0., 100., 120., 30.), 50.);
+		// Taller than the viewport, aligned to its start
+		assert_eq!(reveal(0., 0., 100., 40., 200.), 40.);
+	}
+
+	#[test]
+	fn test_scroll_by_passes_the_rest_on() {
+		let mut state = ScrollState {
+			offset: (0., 90.),
+			viewport: (100., 100.),
+			content: (100., 200.),
+			..Default::default()
+		};
+		assert_eq!(state.scroll_by((0., 30.), (false, true)), (0., 20.));
+		assert_eq!(state.offset, (0., 100.));
+		assert_eq!(state.scroll_by((15., -40.), (false, true)), (15., 0.));
+		assert_eq!(state.offset, (0., 60.));
+	}
+
+	#[test]
+	fn test_fling_slows_down_and_springs_back() {
+		let mut state = ScrollState {
+			viewport: (100., 100.),
+			content: (100., 1000.),
+			velocity: (0., 1000.),
+			..Default::default()
+		};
+		let physics = ScrollPhysics::kinetic();
+		let mut frames = 0;
+		while state.step(&physics, (false, true), 1. / 60.) {
+			frames += 1;
+			assert!(frames < 600, "the fling never stopped");
+		}
+		assert_eq!(state.velocity, (0., 0.));
+		assert!(state.offset.1 > 100. && state.offset.1 < 900.);
+
+		// Flung past the end, the content bounces back to it
+		state.velocity = (0., MAX_VELOCITY);
+		let mut overscrolled = false;
+		while state.step(&physics, (false, true), 1. / 60.) {
+			overscrolled |= state.offset.1 > 900.;
+		}
+		assert!(overscrolled);
+		assert_eq!(state.offset.1, 900.);
+
+		// Clamped, it stops at the end
+		state.offset.1 = 0.;
+		state.velocity = (0., MAX_VELOCITY);
+		let physics = physics.overscroll(Overscroll::Clamp);
+		while state.step(&physics, (false, true), 1. / 60.) {
+			assert!(state.offset.1 <= 900.);
+		}
+		assert_eq!(state.offset.1, 900.);
+	}
+
+	#[test]
+	fn test_snap_points() {
+		let snap = ScrollSnap::interval(100.);
+		let points = [0., 100., 200., 250.];
+		assert_eq!(snap.point(&points, 40., 40., 0.), Some(0.));
+		// The wheel moved the content forward, so it goes on to the next point
+		assert_eq!(snap.point(&points, 40., 40., 10.), Some(100.));
+		assert_eq!(snap.point(&points, 140., 140., -10.), Some(100.));
+		// A fling rests at the point nearest to where it would have stopped
+		assert_eq!(snap.point(&points, 10., 190., 10.), Some(200.));
+		assert_eq!(snap.point(&points, 240., 240., 10.), Some(250.));
+		let snap = snap.proximity(20.);
+		assert_eq!(snap.point(&points, 150., 150., 0.), None);
+		assert_eq!(snap.point(&points, 115., 115., 0.), Some(100.));
+
+		let mut state = ScrollState {
+			offset: (0., 30.),
+			viewport: (100., 100.),
+			content: (100., 350.),
+			moved: (0., 10.),
+			..Default::default()
+		};
+		let snap = ScrollSnap::interval(100.);
+		let mut frames = 0;
+		while state.settle(
+			&snap,
+			[&[], &points],
+			&ScrollPhysics::default(),
+			(false, true),
+			1. / 60.,
+			false,
+		) {
+			frames += 1;
+			assert!(frames < 600, "the content never settled");
+		}
+		assert_eq!(state.offset.1, 100.);
+		assert_eq!(state.snap_target, (None, None));
+
+		// With reduced motion, the content jumps onto the snap point
+		state.offset.1 = 130.;
+		state.moved = (0., 10.);
+		assert!(state.settle(
+			&snap,
+			[&[], &points],
+			&ScrollPhysics::default(),
+			(false, true),
+			1. / 60.,
+			true,
+		));
+		assert_eq!(state.offset.1, 200.);
+		assert_eq!(state.snap_target, (None, None));
+	}
+
+	fn collapsing_header(_: ()) -> Box<dyn Element> {
+		let scroll = crate::use_scroll_handle();
+		let progress = crate::use_scroll_progress(&scroll);
+		Box::new(
+			Container::new()
+				.column()
+				.child(Text::new(format!(
+					"progress {:.1} collapsed {:.1}",
+					progress.y,
+					progress.y_over(100.)
+				)))
+				.child(
+					Container::new()
+						.id("list")
+						.scroll_y()
+						.scroll_handle(&scroll)
+						.min_height(100.)
+						.max_height(100.)
+						.child(
+							Container::new()
+								.min_width(50.)
+								.min_height(400.)
+								.max_height(400.),
+						),
+				),
+		)
+	}
+
+	#[test]
+	fn test_scroll_progress() {
+		let mut harness = TestHarness::new(collapsing_header, (), (400., 300.));
+		harness.expect_label("progress 0.0 collapsed 0.0");
+		let (x, y, _, _) = harness.bounds_of("list").unwrap();
+		harness.scroll(x + 10., y + 10., 0., 150.);
+		harness.frame();
+		harness.expect_label("progress 0.5 collapsed 1.0");
+	}
+
+	fn carousel(_: ()) -> Box<dyn Element> {
+		let scroll = crate::use_scroll_handle();
+		let offset = crate::use_scroll_progress(&scroll).offset.0;
+		let mut items = Container::new()
+			.id("carousel")
+			.scroll_x()
+			.scroll_handle(&scroll)
+			.scroll_snap(crate::ScrollSnap::children())
+			.min_width(200.)
+			.max_width(200.);
+		for _ in 0..3 {
+			items = items.child(Container::new().min_width(200.).min_height(50.));
+		}
+		Box::new(
+			Container::new()
+				.column()
+				.child(Text::new(format!("offset {offset:.0}")))
+				.child(items),
+		)
+	}
+
+	#[test]
+	fn test_scroll_snaps_to_children() {
+		let mut harness = TestHarness::new(carousel, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("carousel").unwrap();
+		harness.scroll(x + 10., y + 10., 50., 0.);
+
+		// The content settles on the next child within two seconds of 60 Hz frames
+		for _ in 0..120 {
+			if harness.find_by_label("offset 200").is_some() {
+				break;
+			}
+			harness.advance_time(Duration::from_millis(16));
+		}
+		harness.expect_label("offset 200");
+	}
+
+	fn infinite_feed(_: ()) -> Box<dyn Element> {
+		let (items, set_items) = use_state(1);
+		let mut feed = Container::new()
+			.column()
+			.id("feed")
+			.scroll_y()
+			.min_height(200.)
+			.max_height(200.)
+			.on_reach_end(50., move || set_items(items + 1));
+		for _ in 0..items {
+			feed = feed.child(Container::new().min_height(100.).max_height(100.));
+		}
+		Box::new(
+			Container::new()
+				.column()
+				.child(Text::new(format!("items {items}")))
+				.child(feed),
+		)
+	}
+
+	#[test]
+	fn test_on_reach_end_loads_more() {
+		let mut harness = TestHarness::new(infinite_feed, (), (400., 400.));
+		harness.frames(5);
+		// Loaded until the content is taller than the feed by more than the threshold
+		harness.expect_label("items 3");
+		let (x, y, _, _) = harness.bounds_of("feed").unwrap();
+		harness.scroll(x + 10., y + 10., 0., 60.);
+		harness.frames(2);
+		harness.expect_label("items 4");
+	}
+}
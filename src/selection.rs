@@ -0,0 +1,417 @@
+//! Selection of the items of lists, tables and trees.
+//!
+//! A [`SelectionModel`] keeps the indices of the selected items, and follows the usual desktop conventions: a
+//! click selects an item, Ctrl+click adds or removes one and Shift+click selects the range from the last item
+//! clicked. Components keep one with [`use_selection`] and mark each item with
+//! [`Container::selectable`](crate::Container::selectable), which clicks the model and styles selected items.
+//! [`Container::list_navigation`](crate::Container::list_navigation) moves through the items with the arrow keys
+//! and jumps to the item starting with the letters typed. [`FuzzyList`](crate::FuzzyList) keeps its highlighted
+//! result in one.
+use std::{
+	cell::{Cell, RefCell},
+	collections::BTreeSet,
+	ops::RangeInclusive,
+	rc::Rc,
+	time::{Duration, Instant},
+};
+
+use crate::{GlobalClosure, InputManager, KeyAction, NamedKey, input::Key, use_ref};
+
+/// Number of items [`KeyAction::PageUp`] and [`KeyAction::PageDown`] move by.
+const PAGE: usize = 10;
+/// Time after which typing starts a new [`TypeAhead`] prefix instead of extending the last one.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+thread_local! {
+	/// Whether Ctrl and Shift are held this frame, read when an item is clicked.
+	static MODIFIERS: Cell<(bool, bool)> = const { Cell::new((false, false)) };
+}
+
+/// Records the modifiers held this frame.
+pub(crate) fn begin_frame(input: &dyn InputManager) {
+	MODIFIERS.set((
+		input.is_key_pressed(Key::Named(NamedKey::Control)),
+		input.is_key_pressed(Key::Named(NamedKey::Shift)),
+	));
+}
+
+/// How many items a [`SelectionModel`] can select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+	/// At most one item, replaced by every click.
+	#[default]
+	Single,
+	/// Any number of items, added with Ctrl and Shift.
+	Multiple,
+}
+
+/// The selected items of a list, by index.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SelectionModel {
+	mode: SelectionMode,
+	selected: BTreeSet<usize>,
+	/// Where Shift ranges start: the last item clicked without Shift.
+	anchor: Option<usize>,
+	/// The last item clicked or moved to with the keyboard.
+	cursor: Option<usize>,
+}
+
+impl SelectionModel {
+	pub fn new(mode: SelectionMode) -> Self {
+		Self {
+			mode,
+			..Default::default()
+		}
+	}
+
+	pub fn mode(&self) -> SelectionMode {
+		self.mode
+	}
+
+	/// Returns the selected indices, in increasing order.
+	pub fn selected(&self) -> Vec<usize> {
+		self.selected.iter().copied().collect()
+	}
+
+	/// Returns the first selected index, the only one in [`SelectionMode::Single`].
+	pub fn first(&self) -> Option<usize> {
+		self.selected.first().copied()
+	}
+
+	pub fn is_selected(&self, index: usize) -> bool {
+		self.selected.contains(&index)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.selected.is_empty()
+	}
+
+	/// The item moved from with the keyboard.
+	pub fn cursor(&self) -> Option<usize> {
+		self.cursor
+	}
+
+	/// Selects only `index`.
+	pub fn select(&mut self, index: usize) {
+		self.selected.clear();
+		self.selected.insert(index);
+		self.anchor = Some(index);
+		self.cursor = Some(index);
+	}
+
+	/// Adds `index` to the selection, or removes it if it is selected. In [`SelectionMode::Single`], toggling an
+	/// item that isn't selected selects only it.
+	pub fn toggle(&mut self, index: usize) {
+		if !self.selected.remove(&index) {
+			if self.mode == SelectionMode::Single {
+				self.selected.clear();
+			}
+			self.selected.insert(index);
+		}
+		self.anchor = Some(index);
+		self.cursor = Some(index);
+	}
+
+	/// Selects the items from the anchor, the last item clicked without Shift, to `index`. Keeps the items
+	/// selected before when `add` is set. Without an anchor, or in [`SelectionMode::Single`], selects only `index`.
+	pub fn extend_to(&mut self, index: usize, add: bool) {
+		let Some(anchor) = self.anchor.filter(|_| self.mode == SelectionMode::Multiple) else {
+			self.select(index);
+			return;
+		};
+		if !add {
+			self.selected.clear();
+		}
+		self.selected.extend(anchor.min(index)..=anchor.max(index));
+		self.cursor = Some(index);
+	}
+
+	/// Adds the items of `range` to the selection. In [`SelectionMode::Single`], selects only its last item.
+	pub fn select_range(&mut self, range: RangeInclusive<usize>) {
+		match self.mode {
+			SelectionMode::Single => {
+				if !range.is_empty() {
+					self.select(*range.end());
+				}
+			}
+			SelectionMode::Multiple => self.selected.extend(range),
+		}
+	}
+
+	/// Selects the first `len` items. Does nothing in [`SelectionMode::Single`].
+	pub fn select_all(&mut self, len: usize) {
+		if self.mode == SelectionMode::Multiple {
+			self.selected.extend(0..len);
+		}
+	}
+
+	pub fn clear(&mut self) {
+		self.selected.clear();
+		self.anchor = None;
+		self.cursor = None;
+	}
+
+	/// Clicks the item at `index`, with Ctrl and Shift held or not.
+	///
+	/// A click selects only the item, Ctrl+click toggles it and Shift+click selects the range from the anchor,
+	/// adding it to the selection with Ctrl also held.
+	pub fn click(&mut self, index: usize, ctrl: bool, shift: bool) {
+		match (ctrl, shift) {
+			(_, true) => self.extend_to(index, ctrl),
+			(true, false) => self.toggle(index),
+			(false, false) => self.select(index),
+		}
+	}
+
+	/// Moves the cursor of a vertical list of `len` items with the keyboard, selecting the item it lands on, or the
+	/// range from the anchor with Shift held. Returns `false` for actions that don't move along the list.
+	pub fn key_action(&mut self, action: KeyAction, len: usize, shift: bool) -> bool {
+		if len == 0 {
+			return false;
+		}
+		let last = len - 1;
+		let cursor = self.cursor.map(|cursor| cursor.min(last));
+		let index = match (action, cursor) {
+			(KeyAction::First, _) | (KeyAction::Down | KeyAction::PageDown, None) => 0,
+			(KeyAction::Last, _) | (KeyAction::Up | KeyAction::PageUp, None) => last,
+			(KeyAction::Up, Some(cursor)) => cursor.saturating_sub(1),
+			(KeyAction::Down, Some(cursor)) => (cursor + 1).min(last),
+			(KeyAction::PageUp, Some(cursor)) => cursor.saturating_sub(PAGE),
+			(KeyAction::PageDown, Some(cursor)) => (cursor + PAGE).min(last),
+			(KeyAction::Left | KeyAction::Right, _) => return false,
+		};
+		self.click(index, false, shift);
+		true
+	}
+
+	/// Forgets the items from `len` on, after items were removed from the end of the list.
+	pub fn truncate(&mut self, len: usize) {
+		self.selected.retain(|&index| index < len);
+		self.anchor = self.anchor.filter(|&anchor| anchor < len);
+		self.cursor = self.cursor.filter(|&cursor| cursor < len);
+	}
+}
+
+/// Finds the item of a list starting with the letters typed, like in file managers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeAhead {
+	prefix: String,
+	last_typed: Option<Instant>,
+}
+
+impl TypeAhead {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The letters typed so far.
+	pub fn prefix(&self) -> &str {
+		&self.prefix
+	}
+
+	/// Adds `text` to the prefix, or starts a new prefix if the last letters were typed over a second before `now`.
+	pub fn type_text(&mut self, text: &str, now: Instant) {
+		if self
+			.last_typed
+			.is_none_or(|last_typed| now.duration_since(last_typed) > TYPE_AHEAD_TIMEOUT)
+		{
+			self.prefix.clear();
+		}
+		self.prefix.push_str(text);
+		self.last_typed = Some(now);
+	}
+
+	/// Returns the first of `labels` starting with the prefix, without case, searching from `current` on and
+	/// wrapping around.
+	///
+	/// A single letter searches from the item after `current`, so typing the same letter again cycles through the
+	/// items starting with it.
+	pub fn find<S: AsRef<str>>(&self, labels: &[S], current: Option<usize>) -> Option<usize> {
+		let prefix = self.prefix.to_lowercase();
+		let first = prefix.chars().next()?;
+		let start = current.unwrap_or(0);
+		let find_from = |prefix: &str, skip: usize| {
+			(0..labels.len())
+				.map(|offset| (start + skip + offset) % labels.len())
+				.find(|&index| labels[index].as_ref().to_lowercase().starts_with(prefix))
+		};
+		let next = usize::from(current.is_some());
+		if prefix.chars().count() == 1 {
+			return find_from(&prefix, next);
+		}
+		// Repeating a letter no item starts with twice cycles through the items starting with it
+		find_from(&prefix, 0).or_else(|| {
+			prefix
+				.chars()
+				.all(|c| c == first)
+				.then(|| find_from(&first.to_string(), next))
+				.flatten()
+		})
+	}
+}
+
+/// A [`SelectionModel`] shared by a component and its items, created with [`use_selection`].
+///
+/// Changing the selection through it renders the component again.
+#[derive(Clone)]
+pub struct Selection {
+	model: Rc<RefCell<SelectionModel>>,
+	type_ahead: Rc<RefCell<TypeAhead>>,
+}
+
+impl Selection {
+	/// Returns the selected indices, in increasing order.
+	pub fn selected(&self) -> Vec<usize> {
+		self.model.borrow().selected()
+	}
+
+	pub fn is_selected(&self, index: usize) -> bool {
+		self.model.borrow().is_selected(index)
+	}
+
+	/// Returns the model, to read it.
+	pub fn get(&self) -> SelectionModel {
+		self.model.borrow().clone()
+	}
+
+	/// Changes the model with `f`, and renders again if the selection changed.
+	pub fn update<R>(&self, f: impl FnOnce(&mut SelectionModel) -> R) -> R {
+		let mut model = self.model.borrow_mut();
+		let before = model.selected.clone();
+		let result = f(&mut model);
+		if model.selected != before {
+			crate::REQUEST_REDRAW.call();
+		}
+		result
+	}
+
+	/// Clicks the item at `index` with the modifiers held this frame, see [`SelectionModel::click`].
+	pub fn click(&self, index: usize) {
+		let (ctrl, shift) = MODIFIERS.get();
+		self.update(|model| model.click(index, ctrl, shift));
+	}
+
+	/// Moves through a vertical list of `len` items with the keyboard, see [`SelectionModel::key_action`]. Meant
+	/// for the [`on_key_action`](crate::Container::on_key_action) of the list:
+	///
+	/// ```rust,ignore
+	/// Container::column()
+	///     .role(Role::List)
+	///     .on_key_action(move |action| {
+	///         selection.key_action(action, files.len());
+	///     })
+	/// ```
+	pub fn key_action(&self, action: KeyAction, len: usize) -> bool {
+		let (_, shift) = MODIFIERS.get();
+		self.update(|model| model.key_action(action, len, shift))
+	}
+
+	/// Adds `text` to the letters typed and selects the first of `labels` starting with them, see [`TypeAhead`].
+	/// Returns `false` if no label matches.
+	pub fn type_text<S: AsRef<str>>(&self, text: &str, labels: &[S]) -> bool {
+		let mut type_ahead = self.type_ahead.borrow_mut();
+		type_ahead.type_text(text, crate::frame_scheduler::now());
+		let current = self.model.borrow().cursor();
+		let Some(index) = type_ahead.find(labels, current) else {
+			return false;
+		};
+		self.update(|model| model.select(index));
+		true
+	}
+}
+
+/// Keeps the selected items of a list across renders.
+///
+/// ```rust,ignore
+/// let selection = use_selection(SelectionMode::Multiple);
+/// files.iter().enumerate().fold(Container::column(), |list, (index, file)| {
+///     list.child(
+///         Container::new()
+///             .selectable(&selection, index)
+///             .child(Text::new(&file.name)),
+///     )
+/// })
+/// ```
+pub fn use_selection(mode: SelectionMode) -> Selection {
+	Selection {
+		model: use_ref(SelectionModel::new(mode)),
+		type_ahead: use_ref(TypeAhead::new()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ctrl_and_shift_clicks() {
+		let mut model = SelectionModel::new(SelectionMode::Multiple);
+		model.click(2, false, false);
+		model.click(5, false, true);
+		assert_eq!(model.selected(), [2, 3, 4, 5]);
+		// The anchor stays, so the range shrinks
+		model.click(3, false, true);
+		assert_eq!(model.selected(), [2, 3]);
+		model.click(8, true, false);
+		assert_eq!(model.selected(), [2, 3, 8]);
+		model.click(3, true, false);
+		assert_eq!(model.selected(), [2, 8]);
+		// Ctrl+Shift adds the range from the new anchor
+		model.click(5, true, true);
+		assert_eq!(model.selected(), [2, 3, 4, 5, 8]);
+		model.click(4, false, false);
+		assert_eq!(model.selected(), [4]);
+	}
+
+	#[test]
+	fn test_single_selection_keeps_one_item() {
+		let mut model = SelectionModel::new(SelectionMode::Single);
+		model.click(1, false, false);
+		model.click(4, false, true);
+		assert_eq!(model.selected(), [4]);
+		model.click(6, true, false);
+		assert_eq!(model.selected(), [6]);
+		model.click(6, true, false);
+		assert!(model.is_empty());
+		model.select_all(10);
+		assert!(model.is_empty());
+	}
+
+	#[test]
+	fn test_keyboard_moves_and_extends() {
+		let mut model = SelectionModel::new(SelectionMode::Multiple);
+		assert!(model.key_action(KeyAction::Down, 5, false));
+		assert_eq!(model.selected(), [0]);
+		model.key_action(KeyAction::Down, 5, true);
+		model.key_action(KeyAction::Down, 5, true);
+		assert_eq!(model.selected(), [0, 1, 2]);
+		model.key_action(KeyAction::Last, 5, false);
+		assert_eq!(model.selected(), [4]);
+		model.key_action(KeyAction::Down, 5, false);
+		assert_eq!(model.selected(), [4]);
+		assert!(!model.key_action(KeyAction::Left, 5, false));
+		model.truncate(3);
+		assert!(model.is_empty());
+		assert_eq!(model.cursor(), None);
+	}
+
+	#[test]
+	fn test_type_ahead_finds_prefixes_and_cycles() {
+		let labels = ["Apple", "Banana", "Blueberry", "Cherry", "apricot"];
+		let start = Instant::now();
+		let mut type_ahead = TypeAhead::new();
+		type_ahead.type_text("b", start);
+		assert_eq!(type_ahead.find(&labels, None), Some(1));
+		type_ahead.type_text("l", start);
+		assert_eq!(type_ahead.find(&labels, Some(1)), Some(2));
+		// After the timeout, a new prefix starts
+		let later = start + TYPE_AHEAD_TIMEOUT * 2;
+		type_ahead.type_text("a", later);
+		assert_eq!(type_ahead.prefix(), "a");
+		assert_eq!(type_ahead.find(&labels, Some(0)), Some(4));
+		type_ahead.type_text("a", later);
+		assert_eq!(type_ahead.find(&labels, Some(4)), Some(0));
+		type_ahead.type_text("x", later);
+		assert_eq!(type_ahead.find(&labels, Some(0)), None);
+	}
+}
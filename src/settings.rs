@@ -0,0 +1,282 @@
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use clay_layout::Color;
+
+use crate::GlobalClosure;
+
+#[cfg(feature = "dbus")]
+mod portal;
+
+/// Reads a boolean from an environment variable, accepting `1/0`, `true/false` and `yes/no`.
+fn env_flag(name: &str) -> Option<bool> {
+	match std::env::var(name).ok()?.trim().to_lowercase().as_str() {
+		"1" | "true" | "yes" => Some(true),
+		"0" | "false" | "no" => Some(false),
+		_ => None,
+	}
+}
+
+/// Reads a key from the desktop settings through `gsettings`, which most desktops (and xdg-desktop-portal-gtk) keep up to date.
+fn gsettings(schema: &str, key: &str) -> Option<String> {
+	let output = Command::new("gsettings")
+		.args(["get", schema, key])
+		.output()
+		.ok()
+		.filter(|o| o.status.success())?;
+	Some(
+		String::from_utf8_lossy(&output.stdout)
+			.trim()
+			.trim_matches('\'')
+			.to_string(),
+	)
+}
+
+static REDUCED_MOTION_OVERRIDE: Mutex<Option<bool>> = Mutex::new(None);
+static SYSTEM_REDUCED_MOTION: OnceLock<bool> = OnceLock::new();
+
+/// Returns `true` if the user asked the desktop to minimize non-essential motion.
+///
+/// The value is read from `HYPRUI_REDUCED_MOTION` if set, otherwise from the desktop
+/// `enable-animations` setting, and can be forced by the app with [`set_reduced_motion`].
+/// Built-in animations use [`animation_duration`] to honor it.
+pub fn prefers_reduced_motion() -> bool {
+	if let Some(forced) = *REDUCED_MOTION_OVERRIDE.lock().unwrap() {
+		return forced;
+	}
+	*SYSTEM_REDUCED_MOTION.get_or_init(|| {
+		env_flag("HYPRUI_REDUCED_MOTION").unwrap_or_else(|| {
+			gsettings("org.gnome.desktop.interface", "enable-animations").as_deref() == Some("false")
+		})
+	})
+}
+
+/// Forces the reduced-motion preference, or restores the desktop setting with `None`.
+pub fn set_reduced_motion(reduced_motion: Option<bool>) {
+	*REDUCED_MOTION_OVERRIDE.lock().unwrap() = reduced_motion;
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Returns the duration an animation should take, taking the reduced-motion preference into account.
+///
+/// Animations are disabled (zero duration) when [`prefers_reduced_motion`] is set, so they jump straight to their end state.
+pub fn animation_duration(duration: Duration) -> Duration {
+	if prefers_reduced_motion() {
+		Duration::ZERO
+	} else {
+		duration
+	}
+}
+
+static TEXT_SCALE_OVERRIDE: Mutex<Option<f32>> = Mutex::new(None);
+static SYSTEM_TEXT_SCALE: OnceLock<f32> = OnceLock::new();
+
+/// Returns the factor applied to every [`Text`](crate::Text) font size.
+///
+/// The value is read from `HYPRUI_TEXT_SCALE` if set, otherwise from the desktop `text-scaling-factor`
+/// setting, and can be forced with [`WindowOptions::text_scale`](crate::WindowOptions::text_scale) or [`set_text_scale`].
+pub fn text_scale() -> f32 {
+	if let Some(forced) = *TEXT_SCALE_OVERRIDE.lock().unwrap() {
+		return forced;
+	}
+	*SYSTEM_TEXT_SCALE.get_or_init(|| {
+		std::env::var("HYPRUI_TEXT_SCALE")
+			.ok()
+			.or_else(|| gsettings("org.gnome.desktop.interface", "text-scaling-factor"))
+			.and_then(|s| s.trim().parse::<f32>().ok())
+			.filter(|s| s.is_finite() && *s > 0.)
+			.unwrap_or(1.)
+	})
+}
+
+/// Forces the text scale factor, or restores the desktop setting with `None`.
+pub fn set_text_scale(scale: Option<f32>) {
+	*TEXT_SCALE_OVERRIDE.lock().unwrap() = scale;
+	crate::REQUEST_REDRAW.call();
+}
+
+static HIGH_CONTRAST_OVERRIDE: Mutex<Option<bool>> = Mutex::new(None);
+static SYSTEM_HIGH_CONTRAST: OnceLock<bool> = OnceLock::new();
+static HIGH_CONTRAST_COLORS: Mutex<Option<HighContrastColors>> = Mutex::new(None);
+
+/// Returns `true` if built-in elements should render in high-contrast mode.
+///
+/// The value is read from `HYPRUI_HIGH_CONTRAST` if set, otherwise from the desktop `high-contrast`
+/// accessibility setting or a high-contrast GTK theme, and can be forced with
+/// [`WindowOptions::high_contrast`](crate::WindowOptions::high_contrast) or [`set_high_contrast`].
+pub fn prefers_high_contrast() -> bool {
+	if let Some(forced) = *HIGH_CONTRAST_OVERRIDE.lock().unwrap() {
+		return forced;
+	}
+	*SYSTEM_HIGH_CONTRAST.get_or_init(|| {
+		env_flag("HYPRUI_HIGH_CONTRAST").unwrap_or_else(|| {
+			gsettings("org.gnome.desktop.a11y.interface", "high-contrast").as_deref() == Some("true")
+				|| std::env::var("GTK_THEME").is_ok_and(|t| t.to_lowercase().contains("highcontrast"))
+		})
+	})
+}
+
+/// Forces high-contrast mode on or off, or restores the desktop setting with `None`.
+pub fn set_high_contrast(high_contrast: Option<bool>) {
+	*HIGH_CONTRAST_OVERRIDE.lock().unwrap() = high_contrast;
+	crate::REQUEST_REDRAW.call();
+}
+
+/// The colors that replace the app colors while high-contrast mode is active.
+#[derive(Debug, Clone)]
+pub struct HighContrastColors {
+	/// Used for text and borders.
+	pub foreground: Color,
+	/// Used for every non-transparent background.
+	pub background: Color,
+	/// Used for the outline of the focused element.
+	pub focus: Color,
+}
+
+impl Default for HighContrastColors {
+	fn default() -> Self {
+		Self {
+			foreground: Color::rgb(255., 255., 255.),
+			background: Color::rgb(0., 0., 0.),
+			focus: Color::rgb(255., 255., 0.),
+		}
+	}
+}
+
+/// Returns the colors used in high-contrast mode.
+pub fn high_contrast_colors() -> HighContrastColors {
+	HIGH_CONTRAST_COLORS
+		.lock()
+		.unwrap()
+		.clone()
+		.unwrap_or_default()
+}
+
+/// Replaces the colors used in high-contrast mode.
+pub fn set_high_contrast_colors(colors: HighContrastColors) {
+	*HIGH_CONTRAST_COLORS.lock().unwrap() = Some(colors);
+	crate::REQUEST_REDRAW.call();
+}
+
+/// A font chosen in the desktop settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopFont {
+	pub family: String,
+	/// Size in points, if the setting includes one.
+	pub size: Option<f32>,
+}
+
+/// Parses a font setting in the `Family Name 11` format used by GTK.
+fn parse_font(font: &str) -> Option<DesktopFont> {
+	let font = font.trim();
+	let (family, size) = match font.rsplit_once(' ') {
+		Some((family, size)) if size.parse::<f32>().is_ok() => (family, size.parse().ok()),
+		_ => (font, None),
+	};
+	(!family.is_empty()).then(|| DesktopFont {
+		family: family.to_string(),
+		size,
+	})
+}
+
+/// Converts the portal accent color, where components outside of `0..=1` mean that no accent color is set.
+fn accent_color((r, g, b): (f64, f64, f64)) -> Option<Color> {
+	[r, g, b]
+		.iter()
+		.all(|c| (0. ..=1.).contains(c))
+		.then(|| Color::rgb(r as f32 * 255., g as f32 * 255., b as f32 * 255.))
+}
+
+/// Appearance settings chosen by the user for the whole desktop.
+#[derive(Debug, Clone, Default)]
+pub struct DesktopSettings {
+	pub accent_color: Option<Color>,
+	/// The default interface font.
+	pub font: Option<DesktopFont>,
+	pub monospace_font: Option<DesktopFont>,
+	/// The size of the mouse cursor, in pixels.
+	pub cursor_size: Option<u32>,
+}
+
+static DESKTOP_SETTINGS: OnceLock<Mutex<DesktopSettings>> = OnceLock::new();
+
+fn read_desktop_settings() -> DesktopSettings {
+	#[cfg(feature = "dbus")]
+	match portal::watch(|change| {
+		if let Some(settings) = DESKTOP_SETTINGS.get() {
+			change(&mut settings.lock().unwrap());
+			crate::winit::wake_up();
+		}
+	}) {
+		Ok(settings) => return settings,
+		Err(err) => log::warn!("the settings portal is unavailable, falling back to gsettings: {err}"),
+	}
+	DesktopSettings {
+		accent_color: None,
+		font: gsettings("org.gnome.desktop.interface", "font-name").and_then(|f| parse_font(&f)),
+		monospace_font: gsettings("org.gnome.desktop.interface", "monospace-font-name")
+			.and_then(|f| parse_font(&f)),
+		cursor_size: gsettings("org.gnome.desktop.interface", "cursor-size")
+			.and_then(|s| s.trim_start_matches("int32 ").parse().ok()),
+	}
+}
+
+/// Returns the appearance settings of the desktop.
+///
+/// They are read from the XDG settings portal (`org.freedesktop.portal.Settings`) with the `dbus` feature,
+/// and the window is redrawn whenever the user changes them. Without the feature, or if no portal is running,
+/// they are read once through `gsettings`.
+pub fn desktop_settings() -> DesktopSettings {
+	DESKTOP_SETTINGS
+		.get_or_init(|| Mutex::new(read_desktop_settings()))
+		.lock()
+		.unwrap()
+		.clone()
+}
+
+/// Returns the accent color chosen by the user, updated live when it changes.
+pub fn use_accent_color() -> Option<Color> {
+	desktop_settings().accent_color
+}
+
+/// Returns the default interface and monospace fonts of the desktop, updated live when they change.
+pub fn use_desktop_fonts() -> (Option<DesktopFont>, Option<DesktopFont>) {
+	let settings = desktop_settings();
+	(settings.font, settings.monospace_font)
+}
+
+/// Returns the size of the mouse cursor, in pixels, updated live when it changes.
+pub fn use_cursor_size() -> Option<u32> {
+	desktop_settings().cursor_size
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_font() {
+		assert_eq!(
+			parse_font("Cantarell 11"),
+			Some(DesktopFont {
+				family: "Cantarell".to_string(),
+				size: Some(11.)
+			})
+		);
+		assert_eq!(
+			parse_font("Source Code Pro"),
+			Some(DesktopFont {
+				family: "Source Code Pro".to_string(),
+				size: None
+			})
+		);
+		assert_eq!(parse_font(" "), None);
+	}
+
+	#[test]
+	fn test_unset_accent_color() {
+		assert!(accent_color((2., 2., 2.)).is_none());
+		assert!(accent_color((0.2, 0.5, 1.)).is_some());
+	}
+}
@@ -0,0 +1,80 @@
+//! Reads the appearance settings from the XDG desktop portal and follows their changes.
+use std::collections::HashMap;
+
+use zbus::{
+	blocking::{Connection, Proxy},
+	zvariant::OwnedValue,
+};
+
+use super::DesktopSettings;
+use crate::dbus::session_bus;
+
+const APPEARANCE: &str = "org.freedesktop.appearance";
+const INTERFACE: &str = "org.gnome.desktop.interface";
+
+fn settings_proxy(connection: &Connection) -> zbus::Result<Proxy<'_>> {
+	Proxy::new(
+		connection,
+		"org.freedesktop.portal.Desktop",
+		"/org/freedesktop/portal/desktop",
+		"org.freedesktop.portal.Settings",
+	)
+}
+
+/// Updates `settings` with a value of the portal, ignoring keys and types it doesn't know.
+fn apply(settings: &mut DesktopSettings, namespace: &str, key: &str, value: &OwnedValue) {
+	let Ok(value) = value.try_clone() else {
+		return;
+	};
+	match (namespace, key) {
+		(APPEARANCE, "accent-color") => {
+			settings.accent_color = <(f64, f64, f64)>::try_from(value)
+				.ok()
+				.and_then(super::accent_color);
+		}
+		(INTERFACE, "font-name") => {
+			settings.font = String::try_from(value)
+				.ok()
+				.and_then(|f| super::parse_font(&f));
+		}
+		(INTERFACE, "monospace-font-name") => {
+			settings.monospace_font = String::try_from(value)
+				.ok()
+				.and_then(|f| super::parse_font(&f));
+		}
+		(INTERFACE, "cursor-size") => {
+			settings.cursor_size = i32::try_from(value)
+				.ok()
+				.and_then(|s| u32::try_from(s).ok());
+		}
+		_ => {}
+	}
+}
+
+/// Reads the current settings, then spawns a thread that applies every change with `on_change`.
+pub(super) fn watch(
+	on_change: impl Fn(&dyn Fn(&mut DesktopSettings)) + Send + 'static,
+) -> zbus::Result<DesktopSettings> {
+	let connection = session_bus()?;
+	let proxy = settings_proxy(&connection)?;
+	let all: HashMap<String, HashMap<String, OwnedValue>> =
+		proxy.call("ReadAll", &(vec![APPEARANCE, INTERFACE],))?;
+	let mut settings = DesktopSettings::default();
+	for (namespace, values) in all {
+		for (key, value) in values {
+			apply(&mut settings, &namespace, &key, &value);
+		}
+	}
+	let changes = proxy.receive_signal("SettingChanged")?;
+	std::thread::spawn(move || {
+		for message in changes {
+			match message.body().deserialize::<(String, String, OwnedValue)>() {
+				Ok((namespace, key, value)) => {
+					on_change(&|settings| apply(settings, &namespace, &key, &value));
+				}
+				Err(err) => log::warn!("invalid SettingChanged signal from the settings portal: {err}"),
+			}
+		}
+	});
+	Ok(settings)
+}
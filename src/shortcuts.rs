@@ -0,0 +1,230 @@
+//! Keyboard shortcuts handled wherever the focus is, like Ctrl+K opening the search of a launcher.
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{InputManager, Key, KeyChord};
+
+/// A shortcut registered by a component.
+struct Shortcut {
+	chord: KeyChord,
+	callback: Rc<dyn Fn()>,
+	/// Tells if the shortcut is handled when it's pressed, `None` for shortcuts handled wherever the focus is.
+	enabled: Option<Rc<dyn Fn() -> bool>>,
+}
+
+thread_local! {
+	/// The shortcuts registered by the components of this frame, in render order.
+	static SHORTCUTS: RefCell<Vec<Shortcut>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Forgets the shortcuts of the last frame, before the components of this frame register theirs.
+pub(crate) fn begin_frame() {
+	SHORTCUTS.take();
+}
+
+/// Calls the handlers of the shortcuts just pressed, once the components of this frame registered theirs, and
+/// returns the keys they used, which the focused element must not handle again.
+///
+/// When several components register the same shortcut, only the one rendered last gets it, like a dialog
+/// rendered over the page that opened it.
+pub(crate) fn dispatch(input: &dyn InputManager) -> Vec<Key> {
+	let shortcuts = SHORTCUTS.take();
+	let mut fired: Vec<&KeyChord> = Vec::new();
+	for shortcut in shortcuts.iter().rev() {
+		let chord = &shortcut.chord;
+		if fired.contains(&chord)
+			|| !chord.just_pressed(input, false)
+			|| shortcut.enabled.as_ref().is_some_and(|enabled| !enabled())
+		{
+			continue;
+		}
+		fired.push(chord);
+		(shortcut.callback)();
+	}
+	fired
+		.into_iter()
+		.flat_map(|chord| match &chord.key {
+			// Letters are matched without case
+			Key::Character(c) => vec![chord.key.clone(), Key::Character(c.to_uppercase().into())],
+			key => vec![key.clone()],
+		})
+		.collect()
+}
+
+/// The input of a frame without the keys of the shortcuts that fired in it.
+pub(crate) struct Unhandled<'a> {
+	input: &'a dyn InputManager,
+	handled: Vec<Key>,
+}
+
+impl<'a> Unhandled<'a> {
+	pub(crate) fn new(input: &'a dyn InputManager, handled: Vec<Key>) -> Self {
+		Self { input, handled }
+	}
+}
+
+impl InputManager for Unhandled<'_> {
+	fn mouse_position(&self) -> (f32, f32) {
+		self.input.mouse_position()
+	}
+
+	fn is_mouse_button_pressed(&self, button: u16) -> bool {
+		self.input.is_mouse_button_pressed(button)
+	}
+
+	fn is_mouse_button_just_pressed(&self, button: u16) -> bool {
+		self.input.is_mouse_button_just_pressed(button)
+	}
+
+	fn is_mouse_button_just_released(&self, button: u16) -> bool {
+		self.input.is_mouse_button_just_released(button)
+	}
+
+	fn is_key_pressed(&self, key: Key) -> bool {
+		self.input.is_key_pressed(key)
+	}
+
+	fn is_key_just_pressed(&self, key: Key) -> bool {
+		!self.handled.contains(&key) && self.input.is_key_just_pressed(key)
+	}
+
+	fn is_key_just_released(&self, key: Key) -> bool {
+		self.input.is_key_just_released(key)
+	}
+
+	fn text_input(&self) -> &str {
+		// The letter of a shortcut isn't typed too
+		if self
+			.handled
+			.iter()
+			.any(|key| matches!(key, Key::Character(_)))
+		{
+			""
+		} else {
+			self.input.text_input()
+		}
+	}
+
+	fn ime_buffer(&self) -> &str {
+		self.input.ime_buffer()
+	}
+
+	fn bytes_to_remove(&self) -> (usize, usize) {
+		self.input.bytes_to_remove()
+	}
+
+	fn ime_is_editing(&self) -> bool {
+		self.input.ime_is_editing()
+	}
+
+	fn scroll_delta(&self) -> (f32, f32) {
+		self.input.scroll_delta()
+	}
+
+	fn set_cursor_clicked_something(&self) {
+		self.input.set_cursor_clicked_something()
+	}
+
+	fn cursor_hit_something(&self) -> bool {
+		self.input.cursor_hit_something()
+	}
+}
+
+/// Calls `callback` when `shortcut` is pressed, whichever element has the focus.
+///
+/// The shortcut is written like `"ctrl+k"` or `"ctrl+shift+page_down"`, see [`KeyChord::parse`]. It fires once
+/// per press, for the component rendered last when several register it. The keys of a shortcut that fired aren't
+/// seen by the focused element, so a text input doesn't also type or edit with them.
+///
+/// ```rust,ignore
+/// let (searching, set_searching) = use_state(false);
+/// use_shortcut("ctrl+k", move || set_searching(true));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `shortcut` can't be parsed.
+pub fn use_shortcut(shortcut: &str, callback: impl Fn() + 'static) {
+	register(shortcut, Rc::new(callback), None);
+}
+
+/// Like [`use_shortcut`], but only handled while `enabled` returns `true` when the shortcut is pressed, like
+/// while a widget has the focus. Otherwise its keys reach the focused element as usual.
+pub(crate) fn use_shortcut_if(
+	shortcut: &str,
+	enabled: impl Fn() -> bool + 'static,
+	callback: impl Fn() + 'static,
+) {
+	register(shortcut, Rc::new(callback), Some(Rc::new(enabled)));
+}
+
+fn register(shortcut: &str, callback: Rc<dyn Fn()>, enabled: Option<Rc<dyn Fn() -> bool>>) {
+	let chord =
+		KeyChord::parse(shortcut).unwrap_or_else(|| panic!("invalid keyboard shortcut {shortcut:?}"));
+	SHORTCUTS.with_borrow_mut(|shortcuts| {
+		shortcuts.push(Shortcut {
+			chord,
+			callback,
+			enabled,
+		})
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		Component, Container, Element, NamedKey, Text, TextInput, testing::TestHarness, use_state,
+	};
+
+	fn search_shortcut(label: &'static str) -> Box<dyn Element> {
+		let (count, set_count) = use_state(0);
+		use_shortcut("ctrl+k", move || set_count(count + 1));
+		Box::new(Text::new(format!("{label} {count}")))
+	}
+
+	fn page_and_dialog(_: ()) -> Box<dyn Element> {
+		Box::new(
+			Container::column()
+				.child(Component::new(search_shortcut, "page"))
+				.child(Component::new(search_shortcut, "dialog")),
+		)
+	}
+
+	#[test]
+	fn test_shortcuts_fire_once_for_the_last_component() {
+		let mut harness = TestHarness::new(page_and_dialog, (), (400., 300.));
+		harness.key_down(Key::Named(NamedKey::Control));
+		harness.press_key(Key::Character("k".into()));
+		harness.expect_label("dialog 1");
+		harness.expect_label("page 0");
+		harness.key_up(Key::Named(NamedKey::Control));
+		harness.press_key(Key::Character("k".into()));
+		harness.expect_label("dialog 1");
+	}
+
+	fn field_with_shortcut(_: ()) -> Box<dyn Element> {
+		let (text, set_text) = use_state(String::new());
+		let (count, set_count) = use_state(0);
+		use_shortcut("ctrl+a", move || set_count(count + 1));
+		Box::new(
+			Container::column()
+				.child(Text::new(format!("shortcut {count}")))
+				.child(TextInput::new(&text).on_change(move |text| set_text(text))),
+		)
+	}
+
+	#[test]
+	fn test_shortcuts_are_not_handled_by_the_focused_input() {
+		let mut harness = TestHarness::new(field_with_shortcut, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		harness.type_text("ab");
+		harness.key_down(Key::Named(NamedKey::Control));
+		harness.press_key(Key::Character("a".into()));
+		harness.key_up(Key::Named(NamedKey::Control));
+		harness.expect_label("shortcut 1");
+		// Select All would have replaced the text with what is typed next
+		harness.type_text("c");
+		harness.frame();
+		assert_eq!(harness.focused().unwrap().value.as_deref(), Some("abc"));
+	}
+}
@@ -0,0 +1,193 @@
+//! Sticky elements, pinned to the top of the scroll container around them while their section is in view.
+//!
+//! Pinning an element doesn't change the layout: once clay laid the frame out, the render commands of the
+//! element are moved down by how far it went above the viewport, and drawn after the rest of the content of the
+//! scroll container so the content passes under it. The commands are found by the ids of the clips clay opens for
+//! the element and the scroll container.
+use std::{cell::RefCell, collections::HashSet};
+
+use clay_layout::{
+	id::Id,
+	math::BoundingBox,
+	render_commands::{RenderCommand, RenderCommandConfig},
+};
+use uuid::Uuid;
+
+/// A sticky element declared this frame.
+struct Sticky {
+	element: Id,
+	/// The element around it, which it stays inside of.
+	section: Id,
+	/// The viewport of the scroll container it is pinned to.
+	viewport: Id,
+}
+
+thread_local! {
+	static STICKY: RefCell<Vec<Sticky>> = const { RefCell::new(Vec::new()) };
+	/// The container whose children are being declared, with the element they are laid out in.
+	static PARENT: RefCell<Option<(Uuid, Option<Id>)>> = const { RefCell::new(None) };
+	/// Containers with sticky children this frame and in the last one, which declare an element for them.
+	static SECTIONS: RefCell<HashSet<Uuid>> = RefCell::new(HashSet::new());
+	static LAST_SECTIONS: RefCell<HashSet<Uuid>> = RefCell::new(HashSet::new());
+}
+
+pub(crate) fn begin_frame() {
+	STICKY.with_borrow_mut(|sticky| sticky.clear());
+	PARENT.set(None);
+	LAST_SECTIONS.set(SECTIONS.take());
+}
+
+/// Returns `true` if `container` had sticky children in the last frame, so it must declare an element.
+pub(crate) fn is_section(container: Uuid) -> bool {
+	LAST_SECTIONS.with_borrow(|sections| sections.contains(&container))
+}
+
+/// Opens `container` while its children are declared in `element`, returning the container to restore with
+/// [`leave_parent`].
+pub(crate) fn enter_parent(container: Uuid, element: Option<Id>) -> Option<(Uuid, Option<Id>)> {
+	PARENT.replace(Some((container, element)))
+}
+
+pub(crate) fn leave_parent(previous: Option<(Uuid, Option<Id>)>) {
+	PARENT.set(previous);
+}
+
+/// Pins `element` to the innermost scroll container while its parent is in view.
+///
+/// The parent only declares an element once it knows it has sticky children, so the element starts being pinned
+/// from the second frame on.
+pub(crate) fn declare(element: Id) {
+	let Some((parent, section)) = PARENT.with_borrow(Clone::clone) else {
+		return;
+	};
+	SECTIONS.with_borrow_mut(|sections| sections.insert(parent));
+	if let (Some(section), Some(viewport)) = (section, crate::scroll::innermost_viewport()) {
+		STICKY.with_borrow_mut(|sticky| {
+			sticky.push(Sticky {
+				element,
+				section,
+				viewport,
+			})
+		});
+	}
+}
+
+/// Returns `true` if sticky elements were declared this frame, which must then be moved with [`apply`].
+pub(crate) fn is_active() -> bool {
+	STICKY.with_borrow(|sticky| !sticky.is_empty())
+}
+
+/// Returns the index of the command closing the clip opened by the command at `start`.
+fn clip_end<I, C>(commands: &[RenderCommand<'_, I, C>], start: usize) -> Option<usize> {
+	let mut depth = 0;
+	for (index, command) in commands.iter().enumerate().skip(start + 1) {
+		match command.config {
+			RenderCommandConfig::ScissorStart() => depth += 1,
+			RenderCommandConfig::ScissorEnd() if depth == 0 => return Some(index),
+			RenderCommandConfig::ScissorEnd() => depth -= 1,
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Returns the index of the command opening the clip of the element `id`.
+fn clip_start<I, C>(commands: &[RenderCommand<'_, I, C>], id: u32) -> Option<usize> {
+	commands.iter().position(|command| {
+		command.id == id && matches!(command.config, RenderCommandConfig::ScissorStart())
+	})
+}
+
+/// Moves the render commands of the sticky elements scrolled above their viewport back into it.
+///
+/// The commands of an element and its descendants are told apart by the clip clay opens for the element, which
+/// every sticky element declares.
+pub(crate) fn apply<I, C>(
+	commands: &mut [RenderCommand<'_, I, C>],
+	bounding_box: impl Fn(Id) -> Option<BoundingBox>,
+) {
+	for sticky in STICKY.with_borrow_mut(std::mem::take) {
+		let (element_id, viewport_id) = (sticky.element.id.id, sticky.viewport.id.id);
+		let (Some(element), Some(section), Some(viewport)) = (
+			bounding_box(sticky.element),
+			bounding_box(sticky.section),
+			bounding_box(sticky.viewport),
+		) else {
+			continue;
+		};
+		// The element stops at the bottom of its section, pushed away by the next one
+		let room = section.y + section.height - (element.y + element.height);
+		let shift = (viewport.y - element.y).min(room);
+		if shift <= 0. {
+			continue;
+		}
+
+		// The background of the element comes before its clip, and its descendants inside of it
+		let (Some(start), Some(clip), Some(viewport_clip)) = (
+			commands.iter().position(|command| command.id == element_id),
+			clip_start(commands, element_id),
+			clip_start(commands, viewport_id),
+		) else {
+			continue;
+		};
+		let (Some(end), Some(viewport_end)) =
+			(clip_end(commands, clip), clip_end(commands, viewport_clip))
+		else {
+			continue;
+		};
+		for command in &mut commands[start..=end] {
+			command.bounding_box.y += shift;
+		}
+
+		// Drawn last inside the clip of the scroll container, above the content
+		commands[start..viewport_end].rotate_left(end + 1 - start);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, Element, Text, testing::TestHarness};
+
+	fn sectioned_list(_: ()) -> Box<dyn Element> {
+		let mut list = Container::new()
+			.column()
+			.id("list")
+			.scroll_y()
+			.min_height(100.)
+			.max_height(100.);
+		for title in ["Fruits", "Vegetables"] {
+			list = list.child(
+				Container::new()
+					.column()
+					.child(Container::new().sticky().child(Text::new(title)))
+					.child(
+						Container::new()
+							.min_height(200.)
+							.background_color((200, 200, 200, 255)),
+					),
+			);
+		}
+		Box::new(list)
+	}
+
+	#[test]
+	fn test_sticky_header_stays_at_the_top() {
+		let mut harness = TestHarness::new(sectioned_list, (), (400., 300.));
+		let (x, y, _, _) = harness.bounds_of("list").unwrap();
+		harness.scroll(x + 10., y + 10., 0., 50.);
+		harness.frame();
+		let commands = harness.render_commands();
+		let header = commands
+			.iter()
+			.position(|command| command.contains("\"Fruits\""))
+			.unwrap();
+		assert!(commands[header].starts_with(&format!("text {x} {y} ")));
+		// Drawn over the content of its section
+		let content = commands
+			.iter()
+			.position(|command| command.starts_with("rect ") && command.contains("color=rgba(200"))
+			.unwrap();
+		assert!(header > content);
+	}
+}
@@ -0,0 +1,501 @@
+//! Headless harness to test components without a window or a compositor.
+//!
+//! A [`TestHarness`] mounts a root component, lays it out at a fixed size and lets the test
+//! drive it with simulated input, one frame at a time. Elements are found through the
+//! accessibility tree, by their [`label`](crate::Container::label) or text content.
+//!
+//! The render commands of the last frame can be compared against a golden file with
+//! [`TestHarness::assert_snapshot`] to catch visual regressions, and the pixels themselves
+//! with [`TestHarness::assert_golden_image`] or [`render_to_png`].
+//!
+//! ```rust,no_run
+//! use hyprui::{Container, Element, Text, use_state, testing::TestHarness};
+//!
+//! fn counter(_: ()) -> Box<dyn Element> {
+//!     let (count, set_count) = use_state(0);
+//!     Box::new(
+//!         Container::new()
+//!             .label("increment")
+//!             .on_click(move || set_count(count + 1))
+//!             .child(Text::new(format!("Count: {count}"))),
+//!     )
+//! }
+//!
+//! let mut harness = TestHarness::new(counter, (), (400., 300.));
+//! harness.click_label("increment");
+//! assert!(harness.find_by_label("Count: 1").is_some());
+//! ```
+use std::{path::PathBuf, time::Duration};
+
+use clay_layout::{Clay, math::Dimensions};
+use skia_safe::{Canvas, Color, surfaces};
+
+use crate::{
+	Element, InputEvent, InputRecording, Role, WinitInputManager, accessibility, focus_system::GLOBAL_FOCUS_MANAGER,
+	clay_renderer::{clay_skia_render, draw_custom_element}, element::container::drag_and_drop, font_manager::FontManager, frame_scheduler, input::Key, inspector, runtime,
+};
+
+mod golden;
+mod snapshot;
+
+pub use golden::{ImageComparison, compare_images};
+
+/// An element of the last frame, as seen by assistive technologies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestNode {
+	pub role: Role,
+	pub label: Option<String>,
+	pub value: Option<String>,
+	/// Position and size in logical pixels as `(x, y, width, height)`.
+	pub bounds: Option<(f32, f32, f32, f32)>,
+	pub focused: bool,
+	/// Whether the element is a selected item.
+	pub selected: bool,
+}
+
+impl TestNode {
+	/// Returns the center of the element, where simulated clicks land.
+	pub fn center(&self) -> Option<(f32, f32)> {
+		self
+			.bounds
+			.map(|(x, y, width, height)| (x + width / 2., y + height / 2.))
+	}
+}
+
+/// Runs a component tree without a window. See the [module documentation](self).
+pub struct TestHarness<Props: Clone + 'static, C: Clone + Copy + Fn(Props) -> Box<dyn Element> + 'static> {
+	clay: Clay,
+	font_manager: FontManager,
+	input_manager: WinitInputManager,
+	component: C,
+	props: Props,
+	size: (f32, f32),
+	render_commands: Vec<String>,
+}
+
+impl<Props: Clone + 'static, C: Clone + Copy + Fn(Props) -> Box<dyn Element> + 'static> TestHarness<Props, C> {
+	/// Mounts `component` with `props` in a viewport of the given size and runs the first frame.
+	pub fn new(component: C, props: Props, size: (f32, f32)) -> Self {
+		// Tests must not touch the clipboard of the desktop
+		crate::clipboard::set_clipboard_provider(crate::clipboard::MemoryClipboard::default());
+		let mut harness = Self {
+			clay: Clay::new(Dimensions::new(size.0, size.1)),
+			font_manager: FontManager::new(),
+			input_manager: WinitInputManager::new(),
+			component,
+			props,
+			size,
+			render_commands: Vec::new(),
+		};
+		harness.frame();
+		harness
+	}
+
+	/// Replaces the props of the root component. They are used from the next frame on.
+	pub fn set_props(&mut self, props: Props) {
+		self.props = props;
+	}
+
+	/// Changes the size of the viewport and runs a frame.
+	pub fn resize(&mut self, width: f32, height: f32) {
+		self.clay.set_layout_dimensions(Dimensions::new(width, height));
+		self.size = (width, height);
+		self.frame();
+	}
+
+	/// Runs one frame: input received since the last frame is processed, state updates are applied and the layout is computed.
+	pub fn frame(&mut self) {
+		self.run_frame(None);
+	}
+
+	/// Runs a frame, drawing it on `canvas` if given.
+	fn run_frame(&mut self, canvas: Option<&Canvas>) {
+		let render_commands = &mut self.render_commands;
+		render_commands.clear();
+		runtime::run_frame(
+			&mut self.clay,
+			&mut self.font_manager,
+			&self.input_manager,
+			self.component,
+			self.props.clone(),
+			|commands, font_manager| {
+				let commands =
+					commands.inspect(|command| render_commands.push(snapshot::describe_command(command)));
+				match canvas {
+					Some(canvas) => {
+						clay_skia_render(canvas, commands, draw_custom_element, font_manager.get_fonts())
+					}
+					None => commands.for_each(drop),
+				}
+			},
+		);
+		self.input_manager.update();
+	}
+
+	/// Runs a frame and returns it drawn as a PNG image.
+	pub fn render_png(&mut self) -> Vec<u8> {
+		let mut surface =
+			surfaces::raster_n32_premul((self.size.0.ceil() as i32, self.size.1.ceil() as i32))
+				.expect("failed to create a raster surface");
+		surface.canvas().clear(Color::TRANSPARENT);
+		self.run_frame(Some(surface.canvas()));
+		golden::encode_png(&surface.image_snapshot())
+	}
+
+	/// Runs a frame and compares it with the golden image `tests/golden/<name>.png`, panicking if more than
+	/// `tolerance` separates a pixel channel.
+	///
+	/// On mismatch, `<name>.actual.png` and `<name>.diff.png` are written next to the golden image. A missing
+	/// golden image fails too, set `HYPRUI_UPDATE_SNAPSHOTS=1` to create it or accept intended changes.
+	pub fn assert_golden_image(&mut self, name: &str, tolerance: u8) {
+		let png = self.render_png();
+		golden::assert_golden_image(name, &png, tolerance);
+	}
+
+	/// Runs `count` frames.
+	pub fn frames(&mut self, count: usize) {
+		for _ in 0..count {
+			self.frame();
+		}
+	}
+
+	/// Moves the clock forward by `duration` without waiting and runs a frame, for what happens after a delay,
+	/// like long presses, tooltips and animations.
+	///
+	/// The clock is the one of every harness on the thread, and it never goes back.
+	pub fn advance_time(&mut self, duration: Duration) {
+		frame_scheduler::advance_clock(duration);
+		self.frame();
+	}
+
+	/// Waits for the futures of [`use_future`](crate::use_future) and of pull to refresh to resolve, then runs a
+	/// frame, which applies what they sent to the UI thread.
+	///
+	/// Panics if they are still running after 5 seconds.
+	pub fn wait_for_futures(&mut self) {
+		assert!(
+			crate::hooks::wait_for_futures(Duration::from_secs(5)),
+			"futures are still running after 5 seconds"
+		);
+		self.frame();
+	}
+
+	/// Moves the pointer to `(x, y)` and runs a frame.
+	pub fn move_pointer(&mut self, x: f32, y: f32) {
+		self.input_manager.set_mouse_position(x, y);
+		self.frame();
+	}
+
+	/// Presses and releases the left mouse button at `(x, y)`, running a frame after each step and one more
+	/// so the state changes made by the click show.
+	pub fn click(&mut self, x: f32, y: f32) {
+		self.click_button(x, y, 0);
+	}
+
+	/// Presses and releases the right mouse button at `(x, y)`, like [`click`](Self::click).
+	pub fn right_click(&mut self, x: f32, y: f32) {
+		self.click_button(x, y, 1);
+	}
+
+	/// Presses `button` where the pointer is and runs a frame, `0` being the left button. The button stays down
+	/// until [`mouse_up`](Self::mouse_up).
+	pub fn mouse_down(&mut self, button: u16) {
+		self.input_manager.set_mouse_button(button, true);
+		self.frame();
+	}
+
+	/// Releases `button` and runs a frame.
+	pub fn mouse_up(&mut self, button: u16) {
+		self.input_manager.set_mouse_button(button, false);
+		self.frame();
+	}
+
+	fn click_button(&mut self, x: f32, y: f32, button: u16) {
+		self.move_pointer(x, y);
+		self.input_manager.set_mouse_button(button, true);
+		self.frame();
+		self.input_manager.set_mouse_button(button, false);
+		self.frame();
+		self.frame();
+	}
+
+	/// Moves the pointer to `(x, y)` and scrolls by `(dx, dy)` logical pixels, running a frame after each step.
+	///
+	/// Positive distances scroll towards the end of the content. Scrolling is applied after layout, so the new
+	/// position shows from the next frame on.
+	pub fn scroll(&mut self, x: f32, y: f32, dx: f32, dy: f32) {
+		self.move_pointer(x, y);
+		self.input_manager.scroll(dx, dy);
+		self.frame();
+		self.frame();
+	}
+
+	/// Drags `paths` from another app to `(x, y)` and drops them there, running a frame after each step and one
+	/// more so the state changes made by the drop show.
+	pub fn drop_files(&mut self, x: f32, y: f32, paths: Vec<PathBuf>) {
+		self.drop_offer(x, y, drag_and_drop::Offer::files(&paths));
+	}
+
+	/// Drags data from another app to `(x, y)` and drops it there, offered in each of the MIME types of `offers`,
+	/// running a frame after each step and one more so the state changes made by the drop show.
+	pub fn drop_data(&mut self, x: f32, y: f32, offers: &[(&str, &[u8])]) {
+		self.drop_offer(
+			x,
+			y,
+			drag_and_drop::Offer {
+				mime_types: offers
+					.iter()
+					.map(|(mime_type, _)| mime_type.to_string())
+					.collect(),
+				data: Some(offers.iter().map(|(_, data)| data.to_vec()).collect()),
+			},
+		);
+	}
+
+	fn drop_offer(&mut self, x: f32, y: f32, offer: drag_and_drop::Offer) {
+		self.input_manager.set_mouse_position(x, y);
+		drag_and_drop::enter_from_other_app(offer);
+		self.frame();
+		drag_and_drop::drop_from_other_app();
+		self.frame();
+		self.frame();
+	}
+
+	/// Clicks the center of the element with the given label.
+	///
+	/// # Panics
+	///
+	/// Panics if no element has this label, or if it has no size.
+	pub fn click_label(&mut self, label: &str) {
+		let node = self.expect_label(label);
+		let (x, y) = node
+			.center()
+			.unwrap_or_else(|| panic!("element labeled {label:?} has no bounds"));
+		self.click(x, y);
+	}
+
+	/// Clicks the center of the element with the given [id](crate::Container::id).
+	///
+	/// # Panics
+	///
+	/// Panics if no element has this id.
+	pub fn click_id(&mut self, id: &str) {
+		let (x, y, width, height) = self
+			.bounds_of(id)
+			.unwrap_or_else(|| panic!("no element with the id {id:?}"));
+		self.click(x + width / 2., y + height / 2.);
+	}
+
+	/// Returns the bounds of the element with the given [id](crate::Container::id) in the last frame, as
+	/// `(x, y, width, height)`.
+	pub fn bounds_of(&self, id: &str) -> Option<(f32, f32, f32, f32)> {
+		crate::element_ids::bounding_box(id)
+			.map(|bounds| (bounds.x, bounds.y, bounds.width, bounds.height))
+	}
+
+	/// Presses and releases `key`, running a frame after each step and one more so the state changes made on
+	/// release show.
+	pub fn press_key(&mut self, key: Key) {
+		self.key_down(key.clone());
+		self.key_up(key);
+		self.frame();
+	}
+
+	/// Presses `key` and runs a frame. The key stays down until [`key_up`](Self::key_up).
+	pub fn key_down(&mut self, key: Key) {
+		self.input_manager.set_key(key, true);
+		self.frame();
+	}
+
+	/// Releases `key` and runs a frame.
+	pub fn key_up(&mut self, key: Key) {
+		self.input_manager.set_key(key, false);
+		self.frame();
+	}
+
+	/// Sends `text` as committed text input and runs a frame.
+	pub fn type_text(&mut self, text: &str) {
+		self.input_manager.push_text_input(text);
+		self.frame();
+	}
+
+	/// Feeds the events of a recording, running a frame whenever the recorded time advances.
+	///
+	/// Real time is ignored, so replays are deterministic.
+	pub fn replay(&mut self, recording: &InputRecording) {
+		let mut events = recording.events.iter().peekable();
+		while let Some((time, event)) = events.next() {
+			self.input_manager.apply_event(event.clone());
+			if events.peek().is_none_or(|(next, _)| next != time) {
+				self.frame();
+			}
+		}
+	}
+
+	/// Applies a single input event and runs a frame.
+	pub fn send_event(&mut self, event: InputEvent) {
+		self.input_manager.apply_event(event);
+		self.frame();
+	}
+
+	/// Returns every element of the last frame, in tree order.
+	pub fn nodes(&self) -> Vec<TestNode> {
+		let focused = GLOBAL_FOCUS_MANAGER.with_borrow(|f| f.focused());
+		accessibility::with_last_tree(|tree| {
+			tree
+				.nodes
+				.iter()
+				.map(|node| TestNode {
+					role: node.role,
+					label: node.label.clone(),
+					value: node.value.clone(),
+					bounds: node.bounds.map(|b| (b.x, b.y, b.width, b.height)),
+					focused: focused.is_some() && node.focus_node == focused,
+					selected: node.selected == Some(true),
+				})
+				.collect()
+		})
+	}
+
+	/// Returns the first element whose label (or text content) is `label`.
+	pub fn find_by_label(&self, label: &str) -> Option<TestNode> {
+		self
+			.nodes()
+			.into_iter()
+			.find(|node| {
+				// Text is exposed as a label node with the text as its value
+				node.label.as_deref() == Some(label)
+					|| (node.role == Role::Label && node.value.as_deref() == Some(label))
+			})
+	}
+
+	/// Returns every element with the given role.
+	pub fn find_all_by_role(&self, role: Role) -> Vec<TestNode> {
+		self
+			.nodes()
+			.into_iter()
+			.filter(|node| node.role == role)
+			.collect()
+	}
+
+	/// Returns the focused element, if it is exposed in the accessibility tree.
+	pub fn focused(&self) -> Option<TestNode> {
+		self.nodes().into_iter().find(|node| node.focused)
+	}
+
+	/// Returns the render commands of the last frame, one line per command.
+	pub fn render_commands(&self) -> &[String] {
+		&self.render_commands
+	}
+
+	/// Returns the render commands of the last frame as a snapshot text.
+	pub fn snapshot(&self) -> String {
+		let mut snapshot = self.render_commands.join("\n");
+		snapshot.push('\n');
+		snapshot
+	}
+
+	/// Compares the last frame with the golden file `tests/snapshots/<name>.snap`, panicking with a diff if they differ.
+	///
+	/// A missing file fails like a mismatch. Set `HYPRUI_UPDATE_SNAPSHOTS=1` to create it or accept intended changes.
+	pub fn assert_snapshot(&self, name: &str) {
+		snapshot::assert_snapshot(name, &self.snapshot());
+	}
+
+	/// Runs a frame with layout recording enabled and returns its [`dump_layout`](crate::dump_layout).
+	pub fn dump_layout(&mut self) -> String {
+		let recording = inspector::layout_recording_enabled();
+		inspector::set_layout_recording(true);
+		self.frame();
+		inspector::set_layout_recording(recording);
+		crate::dump_layout()
+	}
+
+	/// Like [`find_by_label`](Self::find_by_label), but panics with the labels of the frame if the element is missing.
+	pub fn expect_label(&self, label: &str) -> TestNode {
+		self.find_by_label(label).unwrap_or_else(|| {
+			let labels = self
+				.nodes()
+				.into_iter()
+				.filter_map(|node| node.label)
+				.collect::<Vec<_>>();
+			panic!("no element labeled {label:?}, found {labels:?}")
+		})
+	}
+}
+
+/// Renders a component in a viewport of the given size and returns it as a PNG image.
+pub fn render_to_png(
+	component: impl Clone + Copy + Fn(()) -> Box<dyn Element> + 'static,
+	size: (f32, f32),
+) -> Vec<u8> {
+	TestHarness::new(component, (), size).render_png()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Container, NamedKey, Text, use_state};
+
+	fn counter(_: ()) -> Box<dyn Element> {
+		let (count, set_count) = use_state(0);
+		Box::new(
+			Container::new()
+				.label("increment")
+				.focusable()
+				.on_click(move || set_count(count + 1))
+				.child(Text::new(format!("Count: {count}"))),
+		)
+	}
+
+	#[test]
+	fn test_click_updates_state() {
+		let mut harness = TestHarness::new(counter, (), (400., 300.));
+		assert!(harness.find_by_label("Count: 0").is_some());
+		harness.click_label("increment");
+		assert!(harness.find_by_label("Count: 1").is_some());
+	}
+
+	#[test]
+	fn test_identical_renders_match() {
+		let first = render_to_png(counter, (200., 100.));
+		let second = render_to_png(counter, (200., 100.));
+		let comparison = compare_images(&first, &second, 0);
+		assert!(comparison.matches());
+		assert!(comparison.diff_png.is_none());
+	}
+
+	#[test]
+	fn test_snapshot_contains_text() {
+		let harness = TestHarness::new(counter, (), (400., 300.));
+		assert!(
+			harness
+				.render_commands()
+				.iter()
+				.any(|command| command.starts_with("text ") && command.contains("\"Count: 0\""))
+		);
+	}
+
+	#[test]
+	fn test_keyboard_activation() {
+		let mut harness = TestHarness::new(counter, (), (400., 300.));
+		harness.press_key(Key::Named(NamedKey::Tab));
+		assert_eq!(
+			harness.focused().and_then(|node| node.label),
+			Some("increment".to_string())
+		);
+		harness.press_key(Key::Named(NamedKey::Enter));
+		assert!(harness.find_by_label("Count: 1").is_some());
+	}
+
+	#[test]
+	fn test_dump_layout() {
+		let mut harness = TestHarness::new(counter, (), (400., 300.));
+		let dump = harness.dump_layout();
+		let mut lines = dump.lines();
+		let container = lines.next().unwrap();
+		assert!(container.starts_with("Container ") && container.contains("\"increment\" #"));
+		assert!(container.contains(" at (0, 0) "));
+		assert_eq!(lines.next(), Some("  Text \"Count: 0\""));
+	}
+}
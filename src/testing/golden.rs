@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use skia_safe::{
+	AlphaType, ColorType, Data, EncodedImageFormat, Image, ImageInfo, image::CachingHint, images,
+};
+
+use super::snapshot::{golden_path, update_requested, write_golden};
+
+/// The result of comparing two images with [`compare_images`].
+#[derive(Debug, Clone)]
+pub struct ImageComparison {
+	/// Number of pixels where a channel differs by more than the tolerance.
+	pub different_pixels: usize,
+	/// Largest difference found in a single channel.
+	pub max_difference: u8,
+	/// A PNG with the differing pixels in red over a faded copy of the actual image, if the images differ.
+	pub diff_png: Option<Vec<u8>>,
+}
+
+impl ImageComparison {
+	pub fn matches(&self) -> bool {
+		self.different_pixels == 0
+	}
+}
+
+/// Decodes an encoded image into unpremultiplied RGBA pixels.
+fn decode_rgba(png: &[u8]) -> Option<(ImageInfo, Vec<u8>)> {
+	let image = Image::from_encoded(Data::new_copy(png))?;
+	let info = ImageInfo::new(
+		image.dimensions(),
+		ColorType::RGBA8888,
+		AlphaType::Unpremul,
+		None,
+	);
+	let mut pixels = vec![0u8; info.compute_min_byte_size()];
+	image
+		.read_pixels(
+			&info,
+			&mut pixels,
+			info.min_row_bytes(),
+			(0, 0),
+			CachingHint::Allow,
+		)
+		.then_some((info, pixels))
+}
+
+/// Encodes an image to PNG.
+pub(crate) fn encode_png(image: &Image) -> Vec<u8> {
+	image
+		.encode(None, EncodedImageFormat::PNG, None)
+		.expect("failed to encode PNG")
+		.as_bytes()
+		.to_vec()
+}
+
+/// Compares two PNG images pixel by pixel, ignoring channel differences up to `tolerance`.
+///
+/// Images of different sizes never match.
+///
+/// # Panics
+///
+/// Panics if one of the images can't be decoded.
+pub fn compare_images(expected_png: &[u8], actual_png: &[u8], tolerance: u8) -> ImageComparison {
+	let (expected_info, expected) = decode_rgba(expected_png).expect("failed to decode expected image");
+	let (actual_info, actual) = decode_rgba(actual_png).expect("failed to decode actual image");
+	if expected_info.dimensions() != actual_info.dimensions() {
+		return ImageComparison {
+			different_pixels: actual.len().max(expected.len()) / 4,
+			max_difference: u8::MAX,
+			diff_png: None,
+		};
+	}
+	let mut different_pixels = 0;
+	let mut max_difference = 0;
+	let mut diff = Vec::with_capacity(actual.len());
+	for (expected, actual) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+		let difference = expected
+			.iter()
+			.zip(actual)
+			.map(|(e, a)| e.abs_diff(*a))
+			.max()
+			.unwrap_or(0);
+		max_difference = max_difference.max(difference);
+		if difference > tolerance {
+			different_pixels += 1;
+			diff.extend_from_slice(&[255, 0, 0, 255]);
+		} else {
+			let gray = ((actual[0] as u16 + actual[1] as u16 + actual[2] as u16) / 3) as u8;
+			diff.extend_from_slice(&[gray, gray, gray, 64]);
+		}
+	}
+	let diff_png = (different_pixels > 0).then(|| {
+		let row_bytes = actual_info.min_row_bytes();
+		let image = images::raster_from_data(&actual_info, Data::new_copy(&diff), row_bytes)
+			.expect("failed to create diff image");
+		encode_png(&image)
+	});
+	ImageComparison {
+		different_pixels,
+		max_difference,
+		diff_png,
+	}
+}
+
+/// Compares `png` with the golden image `tests/golden/<name>.png`, panicking if more than
+/// `tolerance` separates a pixel channel.
+///
+/// On mismatch, `<name>.actual.png` and `<name>.diff.png` are written next to the golden image.
+/// A missing golden image fails the test too, it is only written when `HYPRUI_UPDATE_SNAPSHOTS` is set.
+pub(crate) fn assert_golden_image(name: &str, png: &[u8], tolerance: u8) {
+	compare_golden_image(
+		&golden_path("golden", &format!("{name}.png")),
+		name,
+		png,
+		tolerance,
+		update_requested(),
+	);
+}
+
+fn compare_golden_image(path: &Path, name: &str, png: &[u8], tolerance: u8, update: bool) {
+	if update {
+		write_golden(path, png);
+		return;
+	}
+	let Ok(expected) = std::fs::read(path) else {
+		panic!(
+			"golden image {name:?} is missing at {}, run with HYPRUI_UPDATE_SNAPSHOTS=1 to create it",
+			path.display()
+		);
+	};
+	let comparison = compare_images(&expected, png, tolerance);
+	if !comparison.matches() {
+		let actual_path = path.with_extension("actual.png");
+		std::fs::write(&actual_path, png).unwrap();
+		let diff_path = path.with_extension("diff.png");
+		if let Some(diff) = &comparison.diff_png {
+			std::fs::write(&diff_path, diff).unwrap();
+		}
+		panic!(
+			"golden image {name:?} doesn't match {}: {} pixels differ (max channel difference {}, tolerance {tolerance})\nsee {} and {}, rerun with HYPRUI_UPDATE_SNAPSHOTS=1 to accept",
+			path.display(),
+			comparison.different_pixels,
+			comparison.max_difference,
+			actual_path.display(),
+			diff_path.display()
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_missing_golden_image_fails() {
+		// A directory of this test alone, so no file left by another run can be found there
+		let directory = std::env::temp_dir().join(format!(
+			"hyprui-{}-test_missing_golden_image_fails",
+			std::process::id()
+		));
+		std::fs::create_dir_all(&directory).unwrap();
+		let path = directory.join("missing.png");
+		let result = std::panic::catch_unwind(|| {
+			compare_golden_image(&path, "missing", &[], 0, false);
+		});
+		std::fs::remove_dir_all(&directory).unwrap();
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("is missing"), "{message}");
+	}
+}
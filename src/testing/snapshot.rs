@@ -0,0 +1,214 @@
+use std::{
+	fmt::Write,
+	path::{Path, PathBuf},
+};
+
+use clay_layout::{
+	Color,
+	math::BoundingBox,
+	render_commands::{RenderCommand, RenderCommandConfig},
+};
+use skia_safe::Image;
+
+use crate::clay_renderer::CustomElement;
+
+fn color(color: Color) -> String {
+	format!(
+		"rgba({}, {}, {}, {})",
+		color.r.round(),
+		color.g.round(),
+		color.b.round(),
+		color.a.round()
+	)
+}
+
+fn bounds(bounds: BoundingBox) -> String {
+	format!(
+		"{} {} {}x{}",
+		bounds.x, bounds.y, bounds.width, bounds.height
+	)
+}
+
+macro_rules! radii {
+	($radii:expr) => {
+		format!(
+			"{} {} {} {}",
+			$radii.top_left, $radii.top_right, $radii.bottom_left, $radii.bottom_right
+		)
+	};
+}
+
+/// Describes a render command on a single line, leaving out data that can't be compared, like images.
+pub(crate) fn describe_command(command: &RenderCommand<'_, Image, CustomElement>) -> String {
+	let at = bounds(command.bounding_box);
+	match &command.config {
+		RenderCommandConfig::Rectangle(rect) => format!(
+			"rect {at} color={} radius={}",
+			color(rect.color),
+			radii!(rect.corner_radii)
+		),
+		RenderCommandConfig::Border(border) => format!(
+			"border {at} color={} width={} {} {} {} radius={}",
+			color(border.color),
+			border.width.left,
+			border.width.right,
+			border.width.top,
+			border.width.bottom,
+			radii!(border.corner_radii)
+		),
+		RenderCommandConfig::Text(text) => format!(
+			"text {at} {:?} color={} size={} font={}",
+			text.text,
+			color(text.color),
+			text.font_size,
+			text.font_id
+		),
+		RenderCommandConfig::Image(image) => {
+			format!("image {at} radius={}", radii!(image.corner_radii))
+		}
+		RenderCommandConfig::ScissorStart() => format!("clip {at}"),
+		RenderCommandConfig::ScissorEnd() => "end clip".to_string(),
+		RenderCommandConfig::Custom(custom) => match custom.data {
+			CustomElement::Background { shadow, ring } => {
+				let mut description = format!("background {at} color={}", color(custom.background_color));
+				if let Some(shadow) = shadow {
+					let _ = write!(
+						description,
+						" shadow={} {} blur={} spread={} color={}",
+						shadow.offset.0,
+						shadow.offset.1,
+						shadow.blur,
+						shadow.spread,
+						color(shadow.color)
+					);
+				}
+				if let Some(ring) = ring {
+					let _ = write!(
+						description,
+						" ring={} thickness={} color={} track={}",
+						ring.fraction,
+						ring.thickness,
+						color(ring.color),
+						color(ring.track_color)
+					);
+				}
+				description
+			}
+			CustomElement::Draw(_) => format!("draw {at}"),
+		},
+		RenderCommandConfig::None() => format!("none {at}"),
+	}
+}
+
+/// Returns a unified-style line diff between two snapshots.
+fn diff(expected: &str, actual: &str) -> String {
+	let expected = expected.lines().collect::<Vec<_>>();
+	let actual = actual.lines().collect::<Vec<_>>();
+	// Longest common subsequence table, filled from the end.
+	let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+	for i in (0..expected.len()).rev() {
+		for j in (0..actual.len()).rev() {
+			lcs[i][j] = if expected[i] == actual[j] {
+				lcs[i + 1][j + 1] + 1
+			} else {
+				lcs[i + 1][j].max(lcs[i][j + 1])
+			};
+		}
+	}
+	let mut output = String::new();
+	let (mut i, mut j) = (0, 0);
+	while i < expected.len() || j < actual.len() {
+		if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+			writeln!(output, "  {}", expected[i]).unwrap();
+			i += 1;
+			j += 1;
+		} else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+			writeln!(output, "- {}", expected[i]).unwrap();
+			i += 1;
+		} else {
+			writeln!(output, "+ {}", actual[j]).unwrap();
+			j += 1;
+		}
+	}
+	output
+}
+
+/// Returns the path of a golden file in the `tests/<directory>` folder of the crate being tested.
+pub(crate) fn golden_path(directory: &str, file: &str) -> PathBuf {
+	PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+		.join("tests")
+		.join(directory)
+		.join(file)
+}
+
+/// Returns `true` if golden files should be overwritten with the current output (`HYPRUI_UPDATE_SNAPSHOTS` is set).
+pub(crate) fn update_requested() -> bool {
+	std::env::var_os("HYPRUI_UPDATE_SNAPSHOTS").is_some()
+}
+
+/// Writes `contents` to the golden file at `path`, creating its folder.
+pub(crate) fn write_golden(path: &Path, contents: &[u8]) {
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(path, contents).unwrap();
+}
+
+/// Compares `snapshot` with the golden file `tests/snapshots/<name>.snap` of the crate being tested.
+///
+/// A missing file fails the test like a mismatch does, the file is only written when `HYPRUI_UPDATE_SNAPSHOTS`
+/// is set.
+pub(crate) fn assert_snapshot(name: &str, snapshot: &str) {
+	compare_snapshot(
+		&golden_path("snapshots", &format!("{name}.snap")),
+		name,
+		snapshot,
+		update_requested(),
+	);
+}
+
+fn compare_snapshot(path: &Path, name: &str, snapshot: &str, update: bool) {
+	if update {
+		write_golden(path, snapshot.as_bytes());
+		return;
+	}
+	let Ok(expected) = std::fs::read_to_string(path) else {
+		panic!(
+			"snapshot {name:?} is missing at {}, run with HYPRUI_UPDATE_SNAPSHOTS=1 to create it",
+			path.display()
+		);
+	};
+	if expected != snapshot {
+		panic!(
+			"snapshot {name:?} doesn't match {}\n(- expected, + actual, rerun with HYPRUI_UPDATE_SNAPSHOTS=1 to accept)\n{}",
+			path.display(),
+			diff(&expected, snapshot)
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_diff_marks_changed_lines() {
+		let diff = diff("a\nb\nc\n", "a\nx\nc\n");
+		assert_eq!(diff, "  a\n- b\n+ x\n  c\n");
+	}
+
+	#[test]
+	fn test_missing_snapshot_fails() {
+		// A directory of this test alone, so no file left by another run can be found there
+		let directory = std::env::temp_dir().join(format!(
+			"hyprui-{}-test_missing_snapshot_fails",
+			std::process::id()
+		));
+		std::fs::create_dir_all(&directory).unwrap();
+		let path = directory.join("missing.snap");
+		let result = std::panic::catch_unwind(|| {
+			compare_snapshot(&path, "missing", "rect 0 0 10x10\n", false);
+		});
+		std::fs::remove_dir_all(&directory).unwrap();
+		let message = result.unwrap_err().downcast::<String>().unwrap();
+		assert!(message.contains("is missing"), "{message}");
+	}
+}
@@ -0,0 +1,725 @@
+//! Keyboard editing of text, shared by the text editing widgets.
+//!
+//! A widget keeps its text in a [`TextEditor`] and calls [`TextEditor::handle_input`] on every frame while it
+//! is focused. Keys are looked up in the [`Keymap`] of the app, which defaults to the usual readline bindings and
+//! can be replaced with [`set_text_keymap`], then typed text is inserted at the caret. Edits are recorded in an
+//! [`UndoHistory`], with consecutive typing or deleting undone a word at a time. Copy, cut and paste go through
+//! the clipboard of the desktop, and pasted text is inserted once it is read, which can be a few frames later.
+use std::{ops::Range, sync::Mutex};
+
+use crate::{
+	InputManager, Key, NamedKey, UndoHistory,
+	clipboard::{PendingText, read_clipboard_text, set_clipboard_text},
+};
+
+/// Something a key does to the text being edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditAction {
+	MoveLeft,
+	MoveRight,
+	MoveWordLeft,
+	MoveWordRight,
+	MoveLineStart,
+	MoveLineEnd,
+	MoveTextStart,
+	MoveTextEnd,
+	SelectAll,
+	DeleteBackward,
+	DeleteForward,
+	DeleteWordBackward,
+	DeleteWordForward,
+	DeleteToLineStart,
+	DeleteToLineEnd,
+	Undo,
+	Redo,
+	Copy,
+	Cut,
+	Paste,
+}
+
+impl EditAction {
+	/// Returns `true` for the actions that move the caret, which select text when Shift is held.
+	pub fn is_movement(self) -> bool {
+		matches!(
+			self,
+			Self::MoveLeft
+				| Self::MoveRight
+				| Self::MoveWordLeft
+				| Self::MoveWordRight
+				| Self::MoveLineStart
+				| Self::MoveLineEnd
+				| Self::MoveTextStart
+				| Self::MoveTextEnd
+		)
+	}
+}
+
+/// A key with the modifiers held with it.
+///
+/// Letters are matched without case, so `KeyChord::ctrl("w")` also matches when Caps Lock is on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+	pub key: Key,
+	pub ctrl: bool,
+	pub alt: bool,
+	pub shift: bool,
+}
+
+impl KeyChord {
+	pub fn new(key: Key) -> Self {
+		let key = match key {
+			Key::Character(c) => Key::Character(c.to_lowercase().into()),
+			key => key,
+		};
+		Self {
+			key,
+			ctrl: false,
+			alt: false,
+			shift: false,
+		}
+	}
+
+	pub fn ctrl(key: impl Into<KeyChord>) -> Self {
+		key.into().with_ctrl()
+	}
+
+	pub fn alt(key: impl Into<KeyChord>) -> Self {
+		key.into().with_alt()
+	}
+
+	pub fn with_ctrl(mut self) -> Self {
+		self.ctrl = true;
+		self
+	}
+
+	pub fn with_alt(mut self) -> Self {
+		self.alt = true;
+		self
+	}
+
+	pub fn with_shift(mut self) -> Self {
+		self.shift = true;
+		self
+	}
+
+	/// Parses a chord written like `"ctrl+k"` or `"ctrl+shift+page_down"`: modifiers (`ctrl`, `alt`, `shift`) then a
+	/// key, separated by `+` and without case.
+	///
+	/// The key is a character, `plus` for `+`, or the name of a named key in snake case like `enter`, `escape`,
+	/// `space`, `arrow_up` (or `up`) and `f5`. Returns `None` for unknown modifiers and keys.
+	pub fn parse(chord: &str) -> Option<Self> {
+		let chord = chord.to_lowercase();
+		let mut parts = chord.split('+').map(str::trim).collect::<Vec<_>>();
+		let key = parts.pop()?;
+		let key = match key {
+			"plus" => Key::Character("+".into()),
+			key if key.chars().count() == 1 => Key::Character(key.into()),
+			key => Key::Named(named_key(key)?),
+		};
+		let mut parsed = Self::new(key);
+		for modifier in parts {
+			match modifier {
+				"ctrl" | "control" => parsed.ctrl = true,
+				"alt" => parsed.alt = true,
+				"shift" => parsed.shift = true,
+				_ => return None,
+			}
+		}
+		Some(parsed)
+	}
+
+	/// Returns `true` if the key of the chord was just pressed with exactly its modifiers, ignoring Shift if `ignore_shift`.
+	pub(crate) fn just_pressed(&self, input: &dyn InputManager, ignore_shift: bool) -> bool {
+		let held = |key| input.is_key_pressed(Key::Named(key));
+		let just_pressed = match &self.key {
+			Key::Character(c) => {
+				input.is_key_just_pressed(self.key.clone())
+					|| input.is_key_just_pressed(Key::Character(c.to_uppercase().into()))
+			}
+			key => input.is_key_just_pressed(key.clone()),
+		};
+		just_pressed
+			&& held(NamedKey::Control) == self.ctrl
+			&& held(NamedKey::Alt) == self.alt
+			&& (ignore_shift || held(NamedKey::Shift) == self.shift)
+	}
+}
+
+/// Returns the named key written in snake case, like `page_down`.
+fn named_key(name: &str) -> Option<NamedKey> {
+	let key = match name {
+		"enter" | "return" => NamedKey::Enter,
+		"escape" | "esc" => NamedKey::Escape,
+		"space" => NamedKey::Space,
+		"tab" => NamedKey::Tab,
+		"backspace" => NamedKey::Backspace,
+		"delete" | "del" => NamedKey::Delete,
+		"insert" => NamedKey::Insert,
+		"home" => NamedKey::Home,
+		"end" => NamedKey::End,
+		"page_up" => NamedKey::PageUp,
+		"page_down" => NamedKey::PageDown,
+		"arrow_up" | "up" => NamedKey::ArrowUp,
+		"arrow_down" | "down" => NamedKey::ArrowDown,
+		"arrow_left" | "left" => NamedKey::ArrowLeft,
+		"arrow_right" | "right" => NamedKey::ArrowRight,
+		"f1" => NamedKey::F1,
+		"f2" => NamedKey::F2,
+		"f3" => NamedKey::F3,
+		"f4" => NamedKey::F4,
+		"f5" => NamedKey::F5,
+		"f6" => NamedKey::F6,
+		"f7" => NamedKey::F7,
+		"f8" => NamedKey::F8,
+		"f9" => NamedKey::F9,
+		"f10" => NamedKey::F10,
+		"f11" => NamedKey::F11,
+		"f12" => NamedKey::F12,
+		_ => return None,
+	};
+	Some(key)
+}
+
+impl From<NamedKey> for KeyChord {
+	fn from(key: NamedKey) -> Self {
+		Self::new(Key::Named(key))
+	}
+}
+
+impl From<&str> for KeyChord {
+	fn from(key: &str) -> Self {
+		Self::new(Key::Character(key.into()))
+	}
+}
+
+/// The keys of the text editing actions.
+///
+/// ```rust,ignore
+/// // Ctrl+A selects everything instead of moving to the start of the line
+/// set_text_keymap(Keymap::default().bind(KeyChord::ctrl("a"), EditAction::SelectAll));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+	bindings: Vec<(KeyChord, EditAction)>,
+}
+
+impl Keymap {
+	/// A keymap without any binding.
+	pub fn empty() -> Self {
+		Self {
+			bindings: Vec::new(),
+		}
+	}
+
+	/// Makes `chord` do `action`, replacing what it did before.
+	pub fn bind(mut self, chord: impl Into<KeyChord>, action: EditAction) -> Self {
+		let chord = chord.into();
+		self.bindings.retain(|(bound, _)| *bound != chord);
+		self.bindings.push((chord, action));
+		self
+	}
+
+	/// Removes what `chord` does.
+	pub fn unbind(mut self, chord: impl Into<KeyChord>) -> Self {
+		let chord = chord.into();
+		self.bindings.retain(|(bound, _)| *bound != chord);
+		self
+	}
+
+	/// Returns the chords bound to `action`, to show them in menus or tooltips.
+	pub fn chords_for(&self, action: EditAction) -> impl Iterator<Item = &KeyChord> {
+		self
+			.bindings
+			.iter()
+			.filter(move |(_, bound)| *bound == action)
+			.map(|(chord, _)| chord)
+	}
+
+	/// Returns the actions of the chords pressed this frame, with `true` when a movement should select.
+	///
+	/// A movement bound without Shift selects when pressed with Shift, unless that chord has a binding of its own.
+	pub fn pressed_actions(&self, input: &dyn InputManager) -> Vec<(EditAction, bool)> {
+		let shift = input.is_key_pressed(Key::Named(NamedKey::Shift));
+		let mut actions = self
+			.bindings
+			.iter()
+			.filter(|(chord, _)| chord.just_pressed(input, false))
+			.map(|(_, action)| (*action, false))
+			.collect::<Vec<_>>();
+		if shift && actions.is_empty() {
+			actions = self
+				.bindings
+				.iter()
+				.filter(|(chord, action)| {
+					!chord.shift && action.is_movement() && chord.just_pressed(input, true)
+				})
+				.map(|(_, action)| (*action, true))
+				.collect();
+		}
+		actions
+	}
+}
+
+impl Default for Keymap {
+	/// Arrows, Home and End, with the readline bindings found in terminals and most Linux text fields, and Ctrl+Z
+	/// to undo with Ctrl+Shift+Z or Ctrl+Y to redo. The clipboard is used with Ctrl+C, Ctrl+X and Ctrl+V, or the
+	/// older Ctrl+Insert, Shift+Delete and Shift+Insert.
+	fn default() -> Self {
+		use EditAction::*;
+		use NamedKey::*;
+		Self::empty()
+			.bind(ArrowLeft, MoveLeft)
+			.bind(ArrowRight, MoveRight)
+			.bind(KeyChord::ctrl(ArrowLeft), MoveWordLeft)
+			.bind(KeyChord::ctrl(ArrowRight), MoveWordRight)
+			.bind(KeyChord::alt("b"), MoveWordLeft)
+			.bind(KeyChord::alt("f"), MoveWordRight)
+			.bind(Home, MoveLineStart)
+			.bind(End, MoveLineEnd)
+			.bind(KeyChord::ctrl("a"), MoveLineStart)
+			.bind(KeyChord::ctrl("e"), MoveLineEnd)
+			.bind(KeyChord::ctrl(Home), MoveTextStart)
+			.bind(KeyChord::ctrl(End), MoveTextEnd)
+			.bind(Backspace, DeleteBackward)
+			.bind(Delete, DeleteForward)
+			.bind(KeyChord::ctrl(Backspace), DeleteWordBackward)
+			.bind(KeyChord::alt(Backspace), DeleteWordBackward)
+			.bind(KeyChord::ctrl("w"), DeleteWordBackward)
+			.bind(KeyChord::ctrl(Delete), DeleteWordForward)
+			.bind(KeyChord::alt("d"), DeleteWordForward)
+			.bind(KeyChord::ctrl("u"), DeleteToLineStart)
+			.bind(KeyChord::ctrl("k"), DeleteToLineEnd)
+			.bind(KeyChord::ctrl("z"), Undo)
+			.bind(KeyChord::ctrl("z").with_shift(), Redo)
+			.bind(KeyChord::ctrl("y"), Redo)
+			.bind(KeyChord::ctrl("c"), Copy)
+			.bind(KeyChord::ctrl("x"), Cut)
+			.bind(KeyChord::ctrl("v"), Paste)
+			.bind(KeyChord::ctrl(Insert), Copy)
+			.bind(KeyChord::from(Delete).with_shift(), Cut)
+			.bind(KeyChord::from(Insert).with_shift(), Paste)
+	}
+}
+
+static KEYMAP: Mutex<Option<Keymap>> = Mutex::new(None);
+
+/// Returns the keymap used by the text editing widgets.
+pub fn text_keymap() -> Keymap {
+	KEYMAP.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Replaces the keymap of every text editing widget of the app.
+pub fn set_text_keymap(keymap: Keymap) {
+	*KEYMAP.lock().unwrap() = Some(keymap);
+}
+
+fn is_word_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_'
+}
+
+/// Text with a caret and a selection, edited with [`EditAction`]s and typed text.
+///
+/// Offsets are in bytes and always on character boundaries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextEditor {
+	text: String,
+	caret: usize,
+	/// The other end of the selection, equal to the caret when nothing is selected.
+	anchor: usize,
+	history: UndoHistory<TextSnapshot>,
+	/// The text can't be copied out, like a password.
+	secret: bool,
+	/// Line breaks are replaced with spaces when inserted.
+	single_line: bool,
+	/// Text being read from the clipboard, inserted at the caret once it arrives.
+	paste: Option<PendingText>,
+}
+
+/// The text and selection of a [`TextEditor`], as kept in its history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSnapshot {
+	pub text: String,
+	pub caret: usize,
+	pub anchor: usize,
+}
+
+impl TextEditor {
+	/// Starts editing `text` with the caret at its end.
+	pub fn new(text: impl Into<String>) -> Self {
+		let text = text.into();
+		let end = text.len();
+		Self {
+			text,
+			caret: end,
+			anchor: end,
+			history: UndoHistory::new(),
+			secret: false,
+			single_line: false,
+			paste: None,
+		}
+	}
+
+	/// Prevents copying or cutting the text to the clipboard, for passwords.
+	pub fn set_secret(&mut self, secret: bool) {
+		self.secret = secret;
+	}
+
+	/// Keeps the text on one line, replacing the line breaks of inserted text with spaces.
+	pub fn set_single_line(&mut self, single_line: bool) {
+		self.single_line = single_line;
+	}
+
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn caret(&self) -> usize {
+		self.caret
+	}
+
+	/// Returns the selected bytes, empty when nothing is selected.
+	pub fn selection(&self) -> Range<usize> {
+		self.caret.min(self.anchor)..self.caret.max(self.anchor)
+	}
+
+	/// Moves the caret to `index`, clamped to the text, extending the selection if `select`.
+	pub fn set_caret(&mut self, index: usize, select: bool) {
+		self.history.break_group();
+		self.place_caret(index, select);
+	}
+
+	fn place_caret(&mut self, index: usize, select: bool) {
+		let mut index = index.min(self.text.len());
+		while !self.text.is_char_boundary(index) {
+			index -= 1;
+		}
+		self.caret = index;
+		if !select {
+			self.anchor = index;
+		}
+	}
+
+	/// Replaces the selection with `text`, as a step of its own in the undo history.
+	pub fn insert(&mut self, text: &str) {
+		self.replace_selection(text, None);
+	}
+
+	/// Inserts text typed on the keyboard, undone together with the text typed right before.
+	pub fn type_text(&mut self, text: &str) {
+		// Replacing a selection is a step of its own
+		let group = self.selection().is_empty().then_some("typing");
+		self.replace_selection(text, group);
+	}
+
+	/// Replaces the whole text, keeping the old one to undo, for example when a widget formats what was typed.
+	pub fn set_text(&mut self, text: impl Into<String>) {
+		let text = text.into();
+		if text == self.text {
+			return;
+		}
+		self.history.push(self.snapshot(), None);
+		self.text = text;
+		self.place_caret(self.caret, false);
+	}
+
+	/// Replaces the text right after an edit, like a field formatting what was typed, moving the caret to `caret`.
+	///
+	/// Unlike [`TextEditor::set_text`], the change isn't a step of its own: undoing goes back to before the edit.
+	pub fn reformat(&mut self, text: impl Into<String>, caret: usize) {
+		self.text = text.into();
+		self.place_caret(caret, false);
+	}
+
+	/// Replaces the selection with `text`, recording the change in the undo history under `group`.
+	fn replace_selection(&mut self, text: &str, group: Option<&'static str>) {
+		let single_line;
+		let text = if self.single_line && text.contains(['\r', '\n']) {
+			single_line = text.replace("\r\n", " ").replace(['\r', '\n'], " ");
+			&single_line
+		} else {
+			text
+		};
+		let selection = self.selection();
+		if selection.is_empty() && text.is_empty() {
+			return;
+		}
+		self.history.push(self.snapshot(), group);
+		self.text.replace_range(selection.clone(), text);
+		self.place_caret(selection.start + text.len(), false);
+		// A space ends the step, so typed text is undone a word at a time
+		if text.contains(char::is_whitespace) {
+			self.history.break_group();
+		}
+	}
+
+	pub fn snapshot(&self) -> TextSnapshot {
+		TextSnapshot {
+			text: self.text.clone(),
+			caret: self.caret,
+			anchor: self.anchor,
+		}
+	}
+
+	fn restore(&mut self, snapshot: TextSnapshot) {
+		self.text = snapshot.text;
+		self.caret = snapshot.caret;
+		self.anchor = snapshot.anchor;
+	}
+
+	/// Goes back to the text before the last edit, returning `false` if there is nothing to undo.
+	pub fn undo(&mut self) -> bool {
+		let Some(snapshot) = self.history.undo(self.snapshot()) else {
+			return false;
+		};
+		self.restore(snapshot);
+		true
+	}
+
+	/// Redoes the last undone edit, returning `false` if there is nothing to redo.
+	pub fn redo(&mut self) -> bool {
+		let Some(snapshot) = self.history.redo(self.snapshot()) else {
+			return false;
+		};
+		self.restore(snapshot);
+		true
+	}
+
+	/// The undo history of the text, for widgets that record changes of their own with [`UndoHistory::push`].
+	pub fn history_mut(&mut self) -> &mut UndoHistory<TextSnapshot> {
+		&mut self.history
+	}
+
+	fn previous_char(&self, index: usize) -> usize {
+		self.text[..index]
+			.char_indices()
+			.next_back()
+			.map_or(0, |(i, _)| i)
+	}
+
+	fn next_char(&self, index: usize) -> usize {
+		self.text[index..]
+			.chars()
+			.next()
+			.map_or(index, |c| index + c.len_utf8())
+	}
+
+	fn previous_word(&self, index: usize) -> usize {
+		let before = &self.text[..index];
+		let word_end = before.trim_end_matches(|c| !is_word_char(c)).len();
+		before[..word_end].trim_end_matches(is_word_char).len()
+	}
+
+	fn next_word(&self, index: usize) -> usize {
+		let after = &self.text[index..];
+		let word_start = after.len() - after.trim_start_matches(|c| !is_word_char(c)).len();
+		let rest = &after[word_start..];
+		index + word_start + rest.len() - rest.trim_start_matches(is_word_char).len()
+	}
+
+	fn line_start(&self, index: usize) -> usize {
+		self.text[..index].rfind('\n').map_or(0, |i| i + 1)
+	}
+
+	fn line_end(&self, index: usize) -> usize {
+		self.text[index..]
+			.find('\n')
+			.map_or(self.text.len(), |i| index + i)
+	}
+
+	/// Applies `action`, extending the selection instead of moving the caret if `select`.
+	pub fn apply(&mut self, action: EditAction, select: bool) {
+		use EditAction::*;
+		let selection = self.selection();
+		let caret = self.caret;
+		// Without Shift, arrows collapse the selection to the side they point to
+		match action {
+			MoveLeft if !select && !selection.is_empty() => self.set_caret(selection.start, false),
+			MoveRight if !select && !selection.is_empty() => self.set_caret(selection.end, false),
+			MoveLeft => self.set_caret(self.previous_char(caret), select),
+			MoveRight => self.set_caret(self.next_char(caret), select),
+			MoveWordLeft => self.set_caret(self.previous_word(caret), select),
+			MoveWordRight => self.set_caret(self.next_word(caret), select),
+			MoveLineStart => self.set_caret(self.line_start(caret), select),
+			MoveLineEnd => self.set_caret(self.line_end(caret), select),
+			MoveTextStart => self.set_caret(0, select),
+			MoveTextEnd => self.set_caret(self.text.len(), select),
+			SelectAll => {
+				self.history.break_group();
+				self.anchor = 0;
+				self.caret = self.text.len();
+			}
+			// Deleting with a selection deletes the selection
+			DeleteBackward | DeleteForward | DeleteWordBackward | DeleteWordForward
+			| DeleteToLineStart | DeleteToLineEnd
+				if !selection.is_empty() =>
+			{
+				self.insert("");
+			}
+			DeleteBackward => self.delete(self.previous_char(caret)..caret, Some("delete")),
+			DeleteForward => self.delete(caret..self.next_char(caret), Some("delete")),
+			DeleteWordBackward => self.delete(self.previous_word(caret)..caret, None),
+			DeleteWordForward => self.delete(caret..self.next_word(caret), None),
+			DeleteToLineStart => self.delete(self.line_start(caret)..caret, None),
+			DeleteToLineEnd => self.delete(caret..self.line_end(caret), None),
+			Undo => {
+				self.undo();
+			}
+			Redo => {
+				self.redo();
+			}
+			Copy => self.copy(),
+			// Cutting a secret would only delete it
+			Cut if self.secret => {}
+			Cut => {
+				self.copy();
+				self.insert("");
+			}
+			Paste => {
+				self.paste = Some(read_clipboard_text());
+				self.finish_paste();
+			}
+		}
+	}
+
+	/// Inserts the pasted text if it was read from the clipboard.
+	fn finish_paste(&mut self) {
+		if let Some(paste) = self.paste.take_if(|paste| paste.get().is_some())
+			&& let Some(Some(text)) = paste.get()
+		{
+			self.insert(text);
+		}
+	}
+
+	/// Puts the selection on the clipboard, unless the text is [secret](Self::set_secret).
+	fn copy(&self) {
+		let selection = self.selection();
+		if !self.secret && !selection.is_empty() {
+			set_clipboard_text(&self.text[selection]);
+		}
+	}
+
+	/// Deletes `range`, undone together with the previous deletions of the same `group`.
+	fn delete(&mut self, range: Range<usize>, group: Option<&'static str>) {
+		self.anchor = range.start;
+		self.caret = range.end;
+		self.replace_selection("", group);
+	}
+
+	/// Applies the keys of [`text_keymap`] pressed this frame, then inserts the typed text.
+	///
+	/// Returns `true` if the text changed.
+	pub fn handle_input(&mut self, input: &dyn InputManager) -> bool {
+		let old = self.text.clone();
+		self.finish_paste();
+		for (action, select) in text_keymap().pressed_actions(input) {
+			self.apply(action, select);
+		}
+		let typed = input
+			.text_input()
+			.chars()
+			.filter(|c| !c.is_control())
+			.collect::<String>();
+		if !typed.is_empty() {
+			self.type_text(&typed);
+		}
+		self.text != old
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::WinitInputManager;
+
+	#[test]
+	fn test_word_and_line_actions() {
+		let mut editor = TextEditor::new("hello big_world\nsecond line");
+		editor.apply(EditAction::MoveWordLeft, false);
+		assert_eq!(editor.caret(), "hello big_world\nsecond ".len());
+		editor.apply(EditAction::DeleteToLineStart, false);
+		assert_eq!(editor.text(), "hello big_world\nline");
+		editor.apply(EditAction::MoveLeft, false);
+		editor.apply(EditAction::DeleteWordBackward, false);
+		assert_eq!(editor.text(), "hello \nline");
+		editor.apply(EditAction::MoveLineStart, true);
+		assert_eq!(editor.selection(), 0.."hello ".len());
+		editor.insert("é");
+		assert_eq!(editor.text(), "é\nline");
+		editor.apply(EditAction::DeleteBackward, false);
+		assert_eq!(editor.text(), "\nline");
+	}
+
+	#[test]
+	fn test_typing_is_undone_a_word_at_a_time() {
+		let mut editor = TextEditor::new("");
+		for c in "hello world".chars() {
+			editor.type_text(&c.to_string());
+		}
+		editor.apply(EditAction::DeleteBackward, false);
+		editor.apply(EditAction::DeleteBackward, false);
+		assert_eq!(editor.text(), "hello wor");
+		editor.apply(EditAction::Undo, false);
+		assert_eq!(editor.text(), "hello world");
+		editor.apply(EditAction::Undo, false);
+		assert_eq!(editor.text(), "hello ");
+		assert!(editor.undo());
+		assert_eq!(editor.text(), "");
+		assert!(!editor.undo());
+		editor.apply(EditAction::Redo, false);
+		assert_eq!((editor.text(), editor.caret()), ("hello ", 6));
+		editor.type_text("!");
+		assert!(!editor.redo());
+	}
+
+	#[test]
+	fn test_clipboard_actions() {
+		crate::clipboard::set_clipboard_provider(crate::clipboard::MemoryClipboard::default());
+		let mut editor = TextEditor::new("hello world");
+		editor.set_single_line(true);
+		editor.apply(EditAction::MoveWordLeft, true);
+		editor.apply(EditAction::Cut, false);
+		assert_eq!(editor.text(), "hello ");
+		crate::clipboard::set_clipboard_text("big\nwide ");
+		editor.apply(EditAction::Paste, false);
+		assert_eq!(editor.text(), "hello big wide ");
+		editor.set_secret(true);
+		editor.apply(EditAction::SelectAll, false);
+		editor.apply(EditAction::Copy, false);
+		assert_eq!(
+			crate::clipboard::read_clipboard_text().get(),
+			Some(Some("big\nwide "))
+		);
+	}
+
+	#[test]
+	fn test_keymap_selects_with_shift() {
+		let mut input = WinitInputManager::new();
+		input.set_key(Key::Named(NamedKey::Shift), true);
+		input.set_key(Key::Named(NamedKey::Home), true);
+		let actions = Keymap::default().pressed_actions(&input);
+		assert_eq!(actions, [(EditAction::MoveLineStart, true)]);
+		input.update();
+		input.set_key(Key::Named(NamedKey::Shift), false);
+		input.set_key(Key::Named(NamedKey::Control), true);
+		input.set_key(Key::Character("W".into()), true);
+		let keymap = Keymap::default().bind(KeyChord::ctrl("w"), EditAction::SelectAll);
+		assert_eq!(
+			keymap.pressed_actions(&input),
+			[(EditAction::SelectAll, false)]
+		);
+	}
+
+	#[test]
+	fn test_chords_are_parsed() {
+		assert_eq!(KeyChord::parse("Ctrl+K"), Some(KeyChord::ctrl("k")));
+		assert_eq!(
+			KeyChord::parse("ctrl + shift + page_down"),
+			Some(KeyChord::ctrl(NamedKey::PageDown).with_shift())
+		);
+		assert_eq!(KeyChord::parse("alt+plus"), Some(KeyChord::alt("+")));
+		assert_eq!(KeyChord::parse("f5"), Some(KeyChord::from(NamedKey::F5)));
+		assert_eq!(KeyChord::parse("hyper+k"), None);
+		assert_eq!(KeyChord::parse("ctrl+kk"), None);
+		assert_eq!(KeyChord::parse(""), None);
+	}
+}
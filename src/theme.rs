@@ -0,0 +1,154 @@
+//! The colors, spacing and fonts shared by the widgets of an app, so the panels, docks and launchers of a shell
+//! look alike.
+//!
+//! The theme of the app is set with [`set_theme`], and a component can give the components it renders another
+//! one with [`provide_context`](crate::provide_context). [`Text`](crate::Text) and [`Container`](crate::Container)
+//! take their defaults from it, and components read it with [`use_theme`].
+use std::{cell::RefCell, rc::Rc};
+
+use clay_layout::Color;
+
+use crate::GlobalClosure;
+
+thread_local! {
+	static APP_THEME: RefCell<Rc<Theme>> = RefCell::new(Rc::new(Theme::light()));
+}
+
+/// The colors of a [`Theme`].
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeColors {
+	/// The background of windows and pages.
+	pub background: Color,
+	/// The background of cards, menus and dialogs, drawn over the background.
+	pub surface: Color,
+	pub text: Color,
+	/// Text of lesser importance, like descriptions and placeholders.
+	pub secondary_text: Color,
+	/// The color of focused, selected and active elements.
+	pub accent: Color,
+	/// Text drawn over the accent color.
+	pub on_accent: Color,
+	pub border: Color,
+	pub error: Color,
+}
+
+/// The look shared by the widgets of an app.
+#[derive(Debug, Clone)]
+pub struct Theme {
+	pub dark: bool,
+	pub colors: ThemeColors,
+	/// Gaps and paddings in logical pixels, from the smallest to the largest, see [`space`](Self::space).
+	pub spacing: [u16; 6],
+	/// The font family of texts, the default font of the system when empty.
+	pub font_family: String,
+	pub font_size: u16,
+	pub small_font_size: u16,
+	pub heading_font_size: u16,
+}
+
+impl Theme {
+	/// Dark text on a light background. The default theme.
+	pub fn light() -> Self {
+		Self {
+			dark: false,
+			colors: ThemeColors {
+				background: Color::rgb(250., 250., 250.),
+				surface: Color::rgb(255., 255., 255.),
+				text: Color::rgb(0., 0., 0.),
+				secondary_text: Color::rgb(110., 110., 110.),
+				accent: Color::rgb(53., 132., 228.),
+				on_accent: Color::rgb(255., 255., 255.),
+				border: Color::rgb(200., 200., 200.),
+				error: Color::rgb(192., 28., 40.),
+			},
+			spacing: [2, 4, 8, 12, 16, 24],
+			font_family: String::new(),
+			font_size: 14,
+			small_font_size: 12,
+			heading_font_size: 20,
+		}
+	}
+
+	/// Light text on a dark background.
+	pub fn dark() -> Self {
+		let light = Self::light();
+		Self {
+			dark: true,
+			colors: ThemeColors {
+				background: Color::rgb(30., 30., 30.),
+				surface: Color::rgb(48., 48., 48.),
+				text: Color::rgb(255., 255., 255.),
+				secondary_text: Color::rgb(170., 170., 170.),
+				accent: Color::rgb(120., 174., 237.),
+				on_accent: Color::rgb(0., 0., 0.),
+				border: Color::rgb(70., 70., 70.),
+				error: Color::rgb(237., 51., 59.),
+			},
+			..light
+		}
+	}
+
+	/// Returns the space of the given step of the [`spacing`](Self::spacing) scale, the largest one past its end.
+	///
+	/// ```rust,ignore
+	/// let theme = use_theme();
+	/// Container::column().gap(theme.space(2)).padding_all(theme.space(4))
+	/// ```
+	pub fn space(&self, step: usize) -> u16 {
+		self.spacing[step.min(self.spacing.len() - 1)]
+	}
+
+	/// Returns `color` with its alpha replaced by `alpha`, from `0` to `255`, like a translucent accent.
+	pub fn with_alpha(color: Color, alpha: f32) -> Color {
+		Color::rgba(color.r, color.g, color.b, alpha)
+	}
+}
+
+impl Default for Theme {
+	fn default() -> Self {
+		Self::light()
+	}
+}
+
+/// Replaces the theme of the app and renders the whole window again with it.
+///
+/// ```rust,ignore
+/// let (dark, set_dark) = use_state(false);
+/// Container::new().on_click(move || {
+///     set_theme(if dark { Theme::light() } else { Theme::dark() });
+///     set_dark(!dark);
+/// })
+/// ```
+pub fn set_theme(theme: Theme) {
+	APP_THEME.set(Rc::new(theme));
+	crate::REQUEST_REDRAW.call();
+}
+
+/// Returns the theme provided by the nearest enclosing component, or the one of the app.
+pub fn use_theme() -> Rc<Theme> {
+	crate::use_context::<Theme>().unwrap_or_else(|| APP_THEME.with_borrow(Rc::clone))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{begin_component, end_component, provide_context};
+
+	#[test]
+	fn test_provided_theme_overrides_the_app_one() {
+		set_theme(Theme::light());
+		begin_component("app");
+		assert!(!use_theme().dark);
+		begin_component("sidebar");
+		provide_context(Theme::dark());
+		begin_component("button");
+		assert!(use_theme().dark);
+		end_component();
+		end_component();
+		end_component();
+
+		set_theme(Theme::dark());
+		assert!(use_theme().dark);
+		set_theme(Theme::light());
+	}
+}
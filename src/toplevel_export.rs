@@ -0,0 +1,271 @@
+//! Captures of the windows of Hyprland, through its `hyprland-toplevel-export-v1` protocol.
+//!
+//! The compositor draws the window alone into a buffer of the app, so the capture shows all of it even when other
+//! windows cover it or it is on a workspace that isn't shown. Each capture opens a Wayland connection of its own
+//! and blocks until the compositor copied the window, so it is taken on a background thread.
+use std::{
+	fs::{File, OpenOptions},
+	os::{fd::AsFd, unix::fs::FileExt},
+	path::PathBuf,
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+use skia_safe::{AlphaType, ColorType, Data, Image, ImageInfo, images};
+use wayland_client::{
+	Connection, Dispatch, Proxy, QueueHandle, WEnum,
+	globals::{GlobalListContents, registry_queue_init},
+	protocol::{
+		wl_buffer::WlBuffer,
+		wl_registry::WlRegistry,
+		wl_shm::{self, WlShm},
+		wl_shm_pool::WlShmPool,
+	},
+};
+
+use protocol::{
+	hyprland_toplevel_export_frame_v1::{self, HyprlandToplevelExportFrameV1},
+	hyprland_toplevel_export_manager_v1::HyprlandToplevelExportManagerV1,
+};
+
+#[allow(non_upper_case_globals, non_camel_case_types, clippy::all)]
+mod protocol {
+	use wayland_client;
+	use wayland_client::protocol::*;
+	use wayland_protocols_wlr::foreign_toplevel::v1::client::*;
+
+	pub mod __interfaces {
+		use wayland_client::protocol::__interfaces::*;
+		use wayland_protocols_wlr::foreign_toplevel::v1::client::__interfaces::*;
+		wayland_scanner::generate_interfaces!("protocols/hyprland-toplevel-export-v1.xml");
+	}
+	use self::__interfaces::*;
+
+	wayland_scanner::generate_client_code!("protocols/hyprland-toplevel-export-v1.xml");
+}
+
+/// The pixels of a captured window, in the byte order of [`ColorType::BGRA8888`].
+pub(crate) struct Capture {
+	width: u32,
+	height: u32,
+	stride: u32,
+	opaque: bool,
+	pixels: Vec<u8>,
+}
+
+impl Capture {
+	/// Makes an image of the capture, on the thread it is drawn on.
+	pub(crate) fn to_image(&self) -> Option<Image> {
+		let alpha = if self.opaque {
+			AlphaType::Opaque
+		} else {
+			AlphaType::Premul
+		};
+		let info = ImageInfo::new(
+			(self.width as i32, self.height as i32),
+			ColorType::BGRA8888,
+			alpha,
+			None,
+		);
+		images::raster_from_data(&info, Data::new_copy(&self.pixels), self.stride as usize)
+	}
+}
+
+#[derive(Default)]
+struct State {
+	/// The format, width, height and stride of the shared memory buffer the window is copied to.
+	buffer: Option<(wl_shm::Format, u32, u32, u32)>,
+	y_invert: bool,
+	/// `true` once the window was copied, `false` if it can't be.
+	copied: Option<bool>,
+}
+
+/// Captures the window of Hyprland at `address`, like `0x55d0c0a0d3b0`, blocking until the compositor copied it.
+pub(crate) fn capture(address: &str) -> Result<Capture, String> {
+	// Windows are identified by the lower 32 bits of their address
+	let handle = u64::from_str_radix(address.trim_start_matches("0x"), 16)
+		.map_err(|_| format!("{address} isn't the address of a window"))? as u32;
+	let connection = Connection::connect_to_env().map_err(|err| err.to_string())?;
+	let (globals, mut queue) =
+		registry_queue_init::<State>(&connection).map_err(|err| err.to_string())?;
+	let qh = queue.handle();
+	let manager: HyprlandToplevelExportManagerV1 = globals
+		.bind(&qh, 1..=1, ())
+		.map_err(|err| format!("the compositor can't export windows: {err}"))?;
+	let shm: WlShm = globals
+		.bind(&qh, 1..=1, ())
+		.map_err(|err| err.to_string())?;
+
+	let mut state = State::default();
+	let frame = manager.capture_toplevel(0, handle, &qh, ());
+	// The buffers the window can be copied to are sent right away
+	queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+	let result = match state.buffer {
+		Some(buffer) if state.copied.is_none() => copy(&frame, &shm, buffer, &mut state, &mut queue),
+		_ => Err(format!("the compositor can't copy the window {address}")),
+	};
+	frame.destroy();
+	manager.destroy();
+	let _ = connection.flush();
+	result
+}
+
+/// Copies the window of `frame` to a shared memory buffer, and reads it back.
+fn copy(
+	frame: &HyprlandToplevelExportFrameV1,
+	shm: &WlShm,
+	(format, width, height, stride): (wl_shm::Format, u32, u32, u32),
+	state: &mut State,
+	queue: &mut wayland_client::EventQueue<State>,
+) -> Result<Capture, String> {
+	let qh = queue.handle();
+	let size = stride as usize * height as usize;
+	let file = shm_file(size as u64).map_err(|err| err.to_string())?;
+	let pool = shm.create_pool(file.as_fd(), size as i32, &qh, ());
+	let buffer = pool.create_buffer(
+		0,
+		width as i32,
+		height as i32,
+		stride as i32,
+		format,
+		&qh,
+		(),
+	);
+	frame.copy(&buffer, 1);
+	let dispatched = loop {
+		if state.copied.is_some() {
+			break Ok(());
+		}
+		if let Err(err) = queue.blocking_dispatch(state) {
+			break Err(err.to_string());
+		}
+	};
+	buffer.destroy();
+	pool.destroy();
+	dispatched?;
+	if state.copied != Some(true) {
+		return Err("the compositor failed to copy the window".to_string());
+	}
+
+	let mut pixels = vec![0; size];
+	file
+		.read_exact_at(&mut pixels, 0)
+		.map_err(|err| err.to_string())?;
+	if state.y_invert {
+		let rows = pixels.chunks(stride as usize).rev().flatten().copied();
+		pixels = rows.collect();
+	}
+	Ok(Capture {
+		width,
+		height,
+		stride,
+		opaque: format == wl_shm::Format::Xrgb8888,
+		pixels,
+	})
+}
+
+/// Creates a file of `size` bytes to share with the compositor, removed from its directory right away.
+fn shm_file(size: u64) -> std::io::Result<File> {
+	static COUNT: AtomicU32 = AtomicU32::new(0);
+	let directory =
+		std::env::var_os("XDG_RUNTIME_DIR").map_or_else(std::env::temp_dir, PathBuf::from);
+	let path = directory.join(format!(
+		"hyprui-capture-{}-{}",
+		std::process::id(),
+		COUNT.fetch_add(1, Ordering::Relaxed)
+	));
+	let file = OpenOptions::new()
+		.read(true)
+		.write(true)
+		.create_new(true)
+		.open(&path)?;
+	std::fs::remove_file(&path)?;
+	file.set_len(size)?;
+	Ok(file)
+}
+
+impl Dispatch<HyprlandToplevelExportFrameV1, ()> for State {
+	fn event(
+		state: &mut Self,
+		_: &HyprlandToplevelExportFrameV1,
+		event: hyprland_toplevel_export_frame_v1::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+		use hyprland_toplevel_export_frame_v1::{Event, Flags};
+		match event {
+			Event::Buffer {
+				format: WEnum::Value(format @ (wl_shm::Format::Argb8888 | wl_shm::Format::Xrgb8888)),
+				width,
+				height,
+				stride,
+			} if state.buffer.is_none() => state.buffer = Some((format, width, height, stride)),
+			Event::Flags { flags } => {
+				state.y_invert = matches!(flags, WEnum::Value(flags) if flags.contains(Flags::YInvert));
+			}
+			Event::Ready { .. } => state.copied = Some(true),
+			Event::Failed => state.copied = Some(false),
+			_ => {}
+		}
+	}
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlRegistry,
+		_: <WlRegistry as Proxy>::Event,
+		_: &GlobalListContents,
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<HyprlandToplevelExportManagerV1, ()> for State {
+	fn event(
+		_: &mut Self,
+		_: &HyprlandToplevelExportManagerV1,
+		_: <HyprlandToplevelExportManagerV1 as Proxy>::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<WlShm, ()> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlShm,
+		_: wl_shm::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<WlShmPool, ()> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlShmPool,
+		_: <WlShmPool as Proxy>::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
+
+impl Dispatch<WlBuffer, ()> for State {
+	fn event(
+		_: &mut Self,
+		_: &WlBuffer,
+		_: <WlBuffer as Proxy>::Event,
+		_: &(),
+		_: &Connection,
+		_: &QueueHandle<Self>,
+	) {
+	}
+}
@@ -0,0 +1,742 @@
+//! The host side of the system tray, showing the icons of other apps through `org.kde.StatusNotifierItem`.
+//!
+//! The host registers with the `org.kde.StatusNotifierWatcher` of the session, which keeps the list of the items
+//! apps register, and follows the signals of the watcher and of the items on background threads. When no other app
+//! runs the watcher, the app runs it, and registers again with the watcher that replaces it. Menus are read with
+//! `com.canonical.dbusmenu` when they are opened.
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::{Mutex, Once},
+	time::Duration,
+};
+
+use skia_safe::{AlphaType, ColorType, Data, EncodedImageFormat, ImageInfo, images};
+use zbus::{
+	MatchRule,
+	blocking::{Connection, MessageIterator, Proxy, fdo::DBusProxy, proxy},
+	fdo::{RequestNameFlags, RequestNameReply},
+	message,
+	object_server::SignalEmitter,
+	proxy::CacheProperties,
+	zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+use crate::{FutureState, ImageSource, dbus::session_bus, use_future};
+
+const WATCHER: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+/// Icons are looked up at this size or larger, to stay sharp on scaled outputs.
+const ICON_SIZE: i32 = 48;
+/// How long to wait before hosting the tray again after the connection to the session bus failed.
+const HOST_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// An icon as sent by an item: width, height and ARGB pixels in network byte order.
+type Pixmap = (i32, i32, Vec<u8>);
+/// A DBusMenu entry: its id, its properties and its children.
+type MenuLayout = (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>);
+
+/// The items registered with the watcher, in the order they were registered.
+static ITEMS: Mutex<Vec<TrayItem>> = Mutex::new(Vec::new());
+static HOST: Once = Once::new();
+
+/// Whether a tray item wants to be seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+	/// The app is idle, the tray may hide the item.
+	Passive,
+	Active,
+	/// Something needs the attention of the user, like an unread message.
+	NeedsAttention,
+}
+
+/// An icon an app shows in the system tray, from [`use_tray_items`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayItem {
+	/// The bus name of the app that registered the item.
+	pub service: String,
+	/// The object path of the item in the app.
+	pub path: String,
+	/// A name of the app that stays the same across runs, like `nm-applet`.
+	pub id: String,
+	pub title: String,
+	/// The text to show when the pointer rests on the icon, the title if the app has none.
+	pub tooltip: String,
+	pub status: TrayStatus,
+	/// The icon from the icon theme or drawn by the app, the attention icon while the item needs attention.
+	pub icon: Option<ImageSource>,
+	/// Whether the item only has a menu, so clicking it should open the menu instead of calling
+	/// [`activate`](Self::activate).
+	pub item_is_menu: bool,
+	/// The object path of the DBusMenu of the item, if it has one.
+	pub menu: Option<String>,
+	/// The unique bus name of the app, which sends the signals of the item.
+	owner: String,
+}
+
+impl TrayItem {
+	/// Calls `method` of the item on a background thread, so a busy app doesn't freeze the UI.
+	fn call(&self, method: &'static str, args: (i32, i32)) {
+		let (service, path) = (self.service.clone(), self.path.clone());
+		std::thread::spawn(move || {
+			let result = session_bus()
+				.and_then(|connection| Proxy::new(&connection, service.clone(), path, ITEM_INTERFACE))
+				.and_then(|item| item.call::<_, _, ()>(method, &args));
+			if let Err(err) = result {
+				log::warn!("failed to call {method} on the tray item of {service}: {err}");
+			}
+		});
+	}
+
+	/// Does what the app does when its icon is clicked, like showing its window. `x` and `y` are where on the
+	/// screen, which most apps ignore.
+	pub fn activate(&self, x: i32, y: i32) {
+		self.call("Activate", (x, y));
+	}
+
+	/// Does what the app does when its icon is middle-clicked.
+	pub fn secondary_activate(&self, x: i32, y: i32) {
+		self.call("SecondaryActivate", (x, y));
+	}
+
+	/// Asks the app to show its own menu, for the items without a [`menu`](Self::menu).
+	pub fn context_menu(&self, x: i32, y: i32) {
+		self.call("ContextMenu", (x, y));
+	}
+}
+
+/// An entry of the menu of a tray item, from [`use_tray_menu`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayMenuItem {
+	pub id: i32,
+	/// The label, without the underscores marking access keys.
+	pub label: String,
+	pub enabled: bool,
+	/// Whether the entry is a line between groups of entries.
+	pub separator: bool,
+	/// Whether the entry is checked, if it can be toggled.
+	pub checked: Option<bool>,
+	/// Whether checking the entry unchecks the others of its group.
+	pub radio: bool,
+	/// The entries of the submenu opened by the entry.
+	pub children: Vec<TrayMenuItem>,
+	service: String,
+	menu: String,
+}
+
+impl TrayMenuItem {
+	/// Tells the app the entry was clicked, on a background thread.
+	pub fn activate(&self) {
+		let (id, service, menu) = (self.id, self.service.clone(), self.menu.clone());
+		std::thread::spawn(move || {
+			let result = session_bus()
+				.and_then(|connection| Proxy::new(&connection, service.clone(), menu, MENU_INTERFACE))
+				.and_then(|menu| menu.call::<_, _, ()>("Event", &(id, "clicked", Value::from(0i32), 0u32)));
+			if let Err(err) = result {
+				log::warn!("failed to click the tray menu entry {id} of {service}: {err}");
+			}
+		});
+	}
+}
+
+/// Removes the value of `name` from `properties` and converts it to `T`.
+fn take<T>(properties: &mut HashMap<String, OwnedValue>, name: &str) -> Option<T>
+where
+	T: TryFrom<Value<'static>>,
+	T::Error: Into<zbus::zvariant::Error>,
+{
+	Value::from(properties.remove(name)?).downcast().ok()
+}
+
+/// Splits an item registered with the watcher, a bus name optionally followed by an object path.
+fn split_item(item: &str) -> (String, String) {
+	match item.find('/') {
+		Some(slash) => (item[..slash].to_string(), item[slash..].to_string()),
+		None => (item.to_string(), "/StatusNotifierItem".to_string()),
+	}
+}
+
+/// Removes the underscores marking access keys from a DBusMenu label, two underscores being a literal one.
+fn strip_mnemonics(label: &str) -> String {
+	let mut stripped = String::with_capacity(label.len());
+	let mut chars = label.chars();
+	while let Some(c) = chars.next() {
+		if c != '_' {
+			stripped.push(c);
+		} else if let Some(next) = chars.next() {
+			stripped.push(next);
+		}
+	}
+	stripped
+}
+
+/// Picks the smallest pixmap at least [`ICON_SIZE`] wide, or the largest one.
+fn pick_pixmap(pixmaps: Vec<Pixmap>) -> Option<Pixmap> {
+	let valid = pixmaps.into_iter().filter(|(width, height, pixels)| {
+		*width > 0 && *height > 0 && pixels.len() == *width as usize * *height as usize * 4
+	});
+	valid.min_by_key(|(width, _, _)| {
+		if *width >= ICON_SIZE {
+			(0, *width)
+		} else {
+			(1, -*width)
+		}
+	})
+}
+
+/// Converts ARGB pixels in network byte order to RGBA.
+fn argb_to_rgba(argb: &[u8]) -> Vec<u8> {
+	argb
+		.chunks_exact(4)
+		.flat_map(|pixel| [pixel[1], pixel[2], pixel[3], pixel[0]])
+		.collect()
+}
+
+/// Encodes the best of `pixmaps` as a PNG, so it's cached like other images.
+fn pixmap_image(pixmaps: Vec<Pixmap>) -> Option<ImageSource> {
+	let (width, height, argb) = pick_pixmap(pixmaps)?;
+	let info = ImageInfo::new(
+		(width, height),
+		ColorType::RGBA8888,
+		AlphaType::Unpremul,
+		None,
+	);
+	let image = images::raster_from_data(
+		&info,
+		Data::new_copy(&argb_to_rgba(&argb)),
+		width as usize * 4,
+	)?;
+	let png = image.encode(None, EncodedImageFormat::PNG, None)?;
+	Some(ImageSource::bytes(png.as_bytes()))
+}
+
+/// Returns the name of the icon theme set in the GTK settings.
+fn icon_theme() -> Option<String> {
+	let config = std::env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+	let settings = std::fs::read_to_string(config.join("gtk-3.0/settings.ini")).ok()?;
+	settings.lines().find_map(|line| {
+		let (key, value) = line.split_once('=')?;
+		(key.trim() == "gtk-icon-theme-name").then(|| value.trim().to_string())
+	})
+}
+
+/// Finds the PNG file of the icon `name` in `theme_path`, then in the icon themes of the system.
+fn find_icon(name: &str, theme_path: &str) -> Option<PathBuf> {
+	const SIZES: [&str; 9] = [
+		"48x48", "64x64", "32x32", "96x96", "128x128", "256x256", "24x24", "22x22", "16x16",
+	];
+	const CONTEXTS: [&str; 7] = [
+		"apps",
+		"status",
+		"devices",
+		"panel",
+		"actions",
+		"categories",
+		"legacy",
+	];
+	if name.is_empty() {
+		return None;
+	}
+	if Path::new(name).is_absolute() {
+		return Path::new(name).is_file().then(|| PathBuf::from(name));
+	}
+	let home = std::env::var_os("HOME").map(PathBuf::from);
+	let data_home = std::env::var_os("XDG_DATA_HOME")
+		.map(PathBuf::from)
+		.or_else(|| Some(home.as_ref()?.join(".local/share")));
+	let data_dirs =
+		std::env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+	let bases = (!theme_path.is_empty())
+		.then(|| PathBuf::from(theme_path))
+		.into_iter()
+		.chain(home.map(|home| home.join(".icons")))
+		.chain(data_home.map(|data_home| data_home.join("icons")))
+		.chain(data_dirs.split(':').map(|dir| Path::new(dir).join("icons")))
+		.collect::<Vec<_>>();
+	let themes = icon_theme()
+		.into_iter()
+		.chain(["hicolor".to_string()])
+		.collect::<Vec<_>>();
+	let file = format!("{name}.png");
+	for base in &bases {
+		let candidates = std::iter::once(base.join(&file)).chain(themes.iter().flat_map(|theme| {
+			SIZES.iter().flat_map(move |size| {
+				CONTEXTS
+					.iter()
+					.map(move |context| base.join(theme).join(size).join(context).join(&file))
+			})
+		}));
+		if let Some(path) = candidates.into_iter().find(|path| path.is_file()) {
+			return Some(path);
+		}
+	}
+	Some(Path::new("/usr/share/pixmaps").join(&file)).filter(|path| path.is_file())
+}
+
+fn load_item(connection: &Connection, service: &str, path: &str) -> zbus::Result<TrayItem> {
+	let owner: String = Proxy::new(
+		connection,
+		"org.freedesktop.DBus",
+		"/org/freedesktop/DBus",
+		"org.freedesktop.DBus",
+	)?
+	.call("GetNameOwner", &(service,))?;
+	let mut properties: HashMap<String, OwnedValue> = Proxy::new(
+		connection,
+		service.to_string(),
+		path.to_string(),
+		"org.freedesktop.DBus.Properties",
+	)?
+	.call("GetAll", &(ITEM_INTERFACE,))?;
+	let status = match take::<String>(&mut properties, "Status").as_deref() {
+		Some("Passive") => TrayStatus::Passive,
+		Some("NeedsAttention") => TrayStatus::NeedsAttention,
+		_ => TrayStatus::Active,
+	};
+	let theme_path = take::<String>(&mut properties, "IconThemePath").unwrap_or_default();
+	let mut icon = |name: &str, pixmap: &str| {
+		take::<String>(&mut properties, name)
+			.and_then(|name| find_icon(&name, &theme_path))
+			.map(ImageSource::Path)
+			.or_else(|| pixmap_image(take::<Vec<Pixmap>>(&mut properties, pixmap)?))
+	};
+	let attention_icon = icon("AttentionIconName", "AttentionIconPixmap");
+	let icon = icon("IconName", "IconPixmap");
+	let id = take::<String>(&mut properties, "Id").unwrap_or_default();
+	let title = take::<String>(&mut properties, "Title")
+		.filter(|title| !title.is_empty())
+		.unwrap_or_else(|| id.clone());
+	let tooltip = take::<(String, Vec<Pixmap>, String, String)>(&mut properties, "ToolTip")
+		.map(|(_, _, title, _)| title)
+		.filter(|tooltip| !tooltip.is_empty())
+		.unwrap_or_else(|| title.clone());
+	Ok(TrayItem {
+		service: service.to_string(),
+		path: path.to_string(),
+		id,
+		title,
+		tooltip,
+		icon: match status {
+			TrayStatus::NeedsAttention => attention_icon.or(icon),
+			_ => icon,
+		},
+		status,
+		item_is_menu: take(&mut properties, "ItemIsMenu").unwrap_or(false),
+		menu: take::<OwnedObjectPath>(&mut properties, "Menu")
+			.map(|menu| menu.as_str().to_string())
+			.filter(|menu| menu != "/"),
+		owner,
+	})
+}
+
+/// Reads the list of items of the watcher, loading the ones that weren't known.
+fn refresh(connection: &Connection, watcher: &Proxy) {
+	let registered: Vec<String> = match watcher.get_property("RegisteredStatusNotifierItems") {
+		Ok(registered) => registered,
+		Err(err) => {
+			log::warn!("failed to list the tray items: {err}");
+			return;
+		}
+	};
+	let known = ITEMS.lock().unwrap().clone();
+	let items = registered
+		.iter()
+		.map(|item| split_item(item))
+		.filter_map(|(service, path)| {
+			// Known items reload themselves when they change
+			let item = known
+				.iter()
+				.find(|item| item.service == service && item.path == path);
+			match item {
+				Some(item) => Some(item.clone()),
+				None => load_item(connection, &service, &path)
+					.inspect_err(|err| log::warn!("failed to load the tray item {service}{path}: {err}"))
+					.ok(),
+			}
+		})
+		.collect();
+	*ITEMS.lock().unwrap() = items;
+	crate::winit::wake_up();
+}
+
+/// Reloads the items of the app with the unique bus name `owner`, after it sent a signal.
+fn reload(connection: &Connection, owner: &str) {
+	let changed = ITEMS
+		.lock()
+		.unwrap()
+		.iter()
+		.filter(|item| item.owner == owner)
+		.map(|item| (item.service.clone(), item.path.clone()))
+		.collect::<Vec<_>>();
+	for (service, path) in changed {
+		let item = match load_item(connection, &service, &path) {
+			Ok(item) => item,
+			Err(err) => {
+				log::warn!("failed to reload the tray item {service}{path}: {err}");
+				continue;
+			}
+		};
+		let mut items = ITEMS.lock().unwrap();
+		if let Some(known) = items
+			.iter_mut()
+			.find(|known| known.service == service && known.path == path)
+		{
+			*known = item;
+		}
+	}
+	crate::winit::wake_up();
+}
+
+/// Returns the name an item is listed with by the watcher, from the `service` it registered with: its bus name,
+/// or its object path on the bus name `sender` of the app.
+fn item_name(service: &str, sender: &str) -> String {
+	if service.starts_with('/') {
+		format!("{sender}{service}")
+	} else {
+		format!("{service}/StatusNotifierItem")
+	}
+}
+
+/// The `org.kde.StatusNotifierWatcher` the app runs when no other app does, like when it is the only bar of the
+/// session.
+#[derive(Default)]
+struct Watcher {
+	/// The items registered by apps, as returned by [`item_name`].
+	items: Vec<String>,
+	/// The bus names of the hosts.
+	hosts: Vec<String>,
+}
+
+#[zbus::interface(name = "org.kde.StatusNotifierWatcher")]
+impl Watcher {
+	async fn register_status_notifier_item(
+		&mut self,
+		service: &str,
+		#[zbus(header)] header: message::Header<'_>,
+		#[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+	) -> zbus::fdo::Result<()> {
+		let sender = header.sender().map(|sender| sender.to_string());
+		let item = item_name(service, sender.as_deref().unwrap_or_default());
+		if !self.items.contains(&item) {
+			self.items.push(item.clone());
+			Self::status_notifier_item_registered(&emitter, &item).await?;
+		}
+		Ok(())
+	}
+
+	async fn register_status_notifier_host(
+		&mut self,
+		service: &str,
+		#[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+	) -> zbus::fdo::Result<()> {
+		if !self.hosts.iter().any(|host| host == service) {
+			self.hosts.push(service.to_string());
+			Self::status_notifier_host_registered(&emitter).await?;
+		}
+		Ok(())
+	}
+
+	#[zbus(property(emits_changed_signal = "false"))]
+	fn registered_status_notifier_items(&self) -> Vec<String> {
+		self.items.clone()
+	}
+
+	#[zbus(property(emits_changed_signal = "false"))]
+	fn is_status_notifier_host_registered(&self) -> bool {
+		!self.hosts.is_empty()
+	}
+
+	#[zbus(property(emits_changed_signal = "const"))]
+	fn protocol_version(&self) -> i32 {
+		0
+	}
+
+	#[zbus(signal)]
+	async fn status_notifier_item_registered(
+		emitter: &SignalEmitter<'_>,
+		service: &str,
+	) -> zbus::Result<()>;
+
+	#[zbus(signal)]
+	async fn status_notifier_item_unregistered(
+		emitter: &SignalEmitter<'_>,
+		service: &str,
+	) -> zbus::Result<()>;
+
+	#[zbus(signal)]
+	async fn status_notifier_host_registered(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+	#[zbus(signal)]
+	async fn status_notifier_host_unregistered(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// Runs the watcher of the app if no other app owns its name, and returns whether the app is the watcher now.
+///
+/// Another watcher can take the name over, like the one of a desktop started after the app.
+fn serve_watcher(connection: &Connection) -> zbus::Result<bool> {
+	let object_server = connection.object_server();
+	// Items registered with an earlier watcher of the app register again when they see the new one
+	if !object_server.at(WATCHER_PATH, Watcher::default())? {
+		*object_server
+			.interface::<_, Watcher>(WATCHER_PATH)?
+			.get_mut() = Watcher::default();
+	}
+	let reply = connection.request_name_with_flags(
+		WATCHER,
+		RequestNameFlags::DoNotQueue | RequestNameFlags::AllowReplacement,
+	)?;
+	Ok(matches!(
+		reply,
+		RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner
+	))
+}
+
+/// Forgets the items and hosts of an app that left the bus, as the watcher of the app.
+fn forget(connection: &Connection, name: &str) -> zbus::Result<()> {
+	let watcher = connection
+		.object_server()
+		.interface::<_, Watcher>(WATCHER_PATH)?;
+	let mut watcher = watcher.get_mut();
+	let mut removed = Vec::new();
+	watcher.items.retain(|item| {
+		let keep = split_item(item).0 != name;
+		if !keep {
+			removed.push(item.clone());
+		}
+		keep
+	});
+	let had_hosts = !watcher.hosts.is_empty();
+	watcher.hosts.retain(|host| host != name);
+	let no_hosts = had_hosts && watcher.hosts.is_empty();
+	drop(watcher);
+	for item in removed {
+		connection.emit_signal(
+			None::<()>,
+			WATCHER_PATH,
+			WATCHER,
+			"StatusNotifierItemUnregistered",
+			&(item,),
+		)?;
+	}
+	if no_hosts {
+		connection.emit_signal(
+			None::<()>,
+			WATCHER_PATH,
+			WATCHER,
+			"StatusNotifierHostUnregistered",
+			&(),
+		)?;
+	}
+	Ok(())
+}
+
+/// Registers the app as a host with the watcher, running the watcher first if no other app does.
+fn register_host(connection: &Connection, watcher: &Proxy, host: &str) -> zbus::Result<()> {
+	if serve_watcher(connection)? {
+		log::info!("no other app runs the {WATCHER}, the app runs it");
+	}
+	watcher.call::<_, _, ()>("RegisterStatusNotifierHost", &(host,))?;
+	refresh(connection, watcher);
+	Ok(())
+}
+
+fn host() -> zbus::Result<()> {
+	let connection = session_bus()?;
+	let name = format!("org.kde.StatusNotifierHost-{}", std::process::id());
+	connection.request_name(name.as_str())?;
+	// Read again on every change, as watchers don't all tell when the list of items changed
+	let watcher: Proxy = proxy::Builder::new(&connection)
+		.destination(WATCHER)?
+		.path(WATCHER_PATH)?
+		.interface(WATCHER)?
+		.cache_properties(CacheProperties::No)
+		.build()?;
+	let watcher_signals = watcher.receive_all_signals()?;
+	let owners = DBusProxy::new(&connection)?.receive_name_owner_changed()?;
+	let rule = MatchRule::builder()
+		.msg_type(message::Type::Signal)
+		.interface(ITEM_INTERFACE)?
+		.build();
+	let item_signals = MessageIterator::for_match_rule(rule, &connection, None)?;
+	register_host(&connection, &watcher, &name)?;
+	let items_connection = connection.clone();
+	std::thread::spawn(move || {
+		for message in item_signals.flatten() {
+			if let Some(sender) = message.header().sender() {
+				reload(&items_connection, sender.as_str());
+			}
+		}
+	});
+	let owners_connection = connection.clone();
+	let owners_watcher = watcher.clone();
+	std::thread::spawn(move || {
+		let unique_name = owners_connection.unique_name().map(|name| name.to_string());
+		for signal in owners {
+			let Ok(args) = signal.args() else {
+				continue;
+			};
+			let new_owner = args.new_owner().as_ref().map(|owner| owner.to_string());
+			if args.name() == WATCHER {
+				// The app became the watcher itself
+				if new_owner.is_some() && new_owner == unique_name {
+					continue;
+				}
+				// The items of the watcher that left register with the next one
+				ITEMS.lock().unwrap().clear();
+				crate::winit::wake_up();
+				if let Err(err) = register_host(&owners_connection, &owners_watcher, &name) {
+					log::error!("failed to register the tray host with the new watcher: {err}");
+				}
+			} else if new_owner.is_none()
+				&& let Err(err) = forget(&owners_connection, args.name())
+			{
+				log::warn!("failed to remove the tray items of {}: {err}", args.name());
+			}
+		}
+	});
+	// Items registered and unregistered
+	for _ in watcher_signals {
+		refresh(&connection, &watcher);
+	}
+	Ok(())
+}
+
+/// Returns the items of the system tray, and redraws the window whenever they change.
+///
+/// The first call registers the app as a tray host with the `org.kde.StatusNotifierWatcher` of the session, so
+/// apps show their items in it. The watcher is run by the desktop or by a bar like Waybar, and by the app itself
+/// when no other app runs it. The host registers again with every new watcher, and retries every few seconds if
+/// the session bus can't be reached. See [`TrayHost`](crate::TrayHost) for a ready-made tray.
+pub fn use_tray_items() -> Vec<TrayItem> {
+	HOST.call_once(|| {
+		std::thread::spawn(|| {
+			loop {
+				if let Err(err) = host() {
+					log::error!("failed to host the system tray: {err}");
+				}
+				std::thread::sleep(HOST_RETRY_DELAY);
+			}
+		});
+	});
+	ITEMS.lock().unwrap().clone()
+}
+
+/// Returns the entries parsed from the children of a DBusMenu layout, leaving out the hidden ones.
+fn menu_items(children: Vec<OwnedValue>, service: &str, menu: &str) -> Vec<TrayMenuItem> {
+	fn layout(value: Value<'static>) -> Option<MenuLayout> {
+		match value {
+			Value::Value(value) => layout(*value),
+			value => value.downcast().ok(),
+		}
+	}
+	children
+		.into_iter()
+		.filter_map(|child| layout(Value::from(child)))
+		.filter_map(|(id, mut properties, children)| {
+			if take::<bool>(&mut properties, "visible") == Some(false) {
+				return None;
+			}
+			let toggle_type = take::<String>(&mut properties, "toggle-type").unwrap_or_default();
+			Some(TrayMenuItem {
+				id,
+				label: strip_mnemonics(&take::<String>(&mut properties, "label").unwrap_or_default()),
+				enabled: take(&mut properties, "enabled").unwrap_or(true),
+				separator: take::<String>(&mut properties, "type").as_deref() == Some("separator"),
+				checked: (!toggle_type.is_empty())
+					.then(|| take::<i32>(&mut properties, "toggle-state") == Some(1)),
+				radio: toggle_type == "radio",
+				children: menu_items(children, service, menu),
+				service: service.to_string(),
+				menu: menu.to_string(),
+			})
+		})
+		.collect()
+}
+
+/// Reads the entries of the DBusMenu at `menu` of `service`.
+fn read_menu(service: &str, menu: &str) -> zbus::Result<Vec<TrayMenuItem>> {
+	let proxy = Proxy::new(
+		&session_bus()?,
+		service.to_string(),
+		menu.to_string(),
+		MENU_INTERFACE,
+	)?;
+	// Some apps only fill their menu when it's about to be shown
+	let _ = proxy.call::<_, _, bool>("AboutToShow", &(0i32,));
+	let (_revision, (_, _, children)): (u32, MenuLayout) =
+		proxy.call("GetLayout", &(0i32, -1i32, Vec::<String>::new()))?;
+	Ok(menu_items(children, service, menu))
+}
+
+/// Returns the menu of `item`, read on a background thread when `item` becomes `Some`.
+///
+/// Pass the item only while its menu is open, so the entries are read again the next time it is opened. Returns
+/// `None` while the menu is being read, if it failed to be read, or if the item has no menu.
+pub fn use_tray_menu(item: Option<&TrayItem>) -> Option<Vec<TrayMenuItem>> {
+	let menu = item.and_then(|item| Some((item.service.clone(), item.menu.clone()?)));
+	let state = use_future(
+		{
+			let menu = menu.clone();
+			move || async move {
+				let Some((service, menu)) = menu else {
+					return Ok(Vec::new());
+				};
+				read_menu(&service, &menu).map_err(|err| {
+					log::warn!("failed to read the tray menu of {service}: {err}");
+					err.to_string()
+				})
+			}
+		},
+		&menu,
+	);
+	match state {
+		FutureState::Ready(entries) if menu.is_some() => Some(entries),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_tray_items_are_parsed() {
+		assert_eq!(
+			split_item(":1.42/org/ayatana/NotificationItem/nm"),
+			(
+				":1.42".to_string(),
+				"/org/ayatana/NotificationItem/nm".to_string()
+			)
+		);
+		assert_eq!(
+			split_item("org.kde.StatusNotifierItem-1234-1"),
+			(
+				"org.kde.StatusNotifierItem-1234-1".to_string(),
+				"/StatusNotifierItem".to_string()
+			)
+		);
+		assert_eq!(
+			item_name("/org/ayatana/NotificationItem/nm", ":1.42"),
+			":1.42/org/ayatana/NotificationItem/nm"
+		);
+		assert_eq!(
+			item_name("org.kde.StatusNotifierItem-1234-1", ":1.7"),
+			"org.kde.StatusNotifierItem-1234-1/StatusNotifierItem"
+		);
+		assert_eq!(strip_mnemonics("_Quit"), "Quit");
+		assert_eq!(strip_mnemonics("Save__as"), "Save_as");
+		assert_eq!(
+			argb_to_rgba(&[255, 1, 2, 3, 128, 4, 5, 6]),
+			[1, 2, 3, 255, 4, 5, 6, 128]
+		);
+		let pixmap = |size: i32| (size, size, vec![0; size as usize * size as usize * 4]);
+		let sizes = |pixmaps: Vec<Pixmap>| pick_pixmap(pixmaps).map(|(width, _, _)| width);
+		assert_eq!(sizes(vec![pixmap(16), pixmap(64), pixmap(256)]), Some(64));
+		assert_eq!(sizes(vec![pixmap(16), pixmap(22)]), Some(22));
+		assert_eq!(sizes(vec![(16, 16, vec![0; 3])]), None);
+	}
+}
@@ -0,0 +1,175 @@
+//! Undo and redo.
+//!
+//! An [`UndoHistory`] keeps the states a value went through. Before changing the value, push its current state;
+//! undoing gives the last pushed state back and keeps the current one to redo. [`TextEditor`](crate::TextEditor)
+//! keeps one for its text, and components keep their own with [`use_undo_history`].
+use std::{
+	cell::RefCell,
+	rc::Rc,
+	time::{Duration, Instant},
+};
+
+use crate::use_ref;
+
+/// Edits of the same group pushed within this delay of each other are undone together.
+const COALESCE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step<T> {
+	state: T,
+	group: Option<&'static str>,
+	/// When the last edit of the step was pushed.
+	at: Instant,
+}
+
+/// The undo and redo stacks of a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoHistory<T> {
+	undo: Vec<Step<T>>,
+	redo: Vec<T>,
+	limit: usize,
+	/// Whether the next edit may join the last step.
+	open: bool,
+}
+
+impl<T> Default for UndoHistory<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> UndoHistory<T> {
+	/// A history keeping the last 100 steps.
+	pub fn new() -> Self {
+		Self::with_limit(100)
+	}
+
+	/// A history keeping the last `limit` steps, forgetting older ones.
+	pub fn with_limit(limit: usize) -> Self {
+		Self {
+			undo: Vec::new(),
+			redo: Vec::new(),
+			limit,
+			open: false,
+		}
+	}
+
+	/// Records `before`, the state about to be changed, as a step to undo, and clears the redo stack.
+	///
+	/// Consecutive edits with the same `group`, like the letters of a typed word, make a single step: `before` is
+	/// dropped and undoing goes back to the state before the first of them. Edits without a group are always
+	/// steps of their own.
+	pub fn push(&mut self, before: T, group: Option<&'static str>) {
+		self.redo.clear();
+		let now = Instant::now();
+		if let Some(last) = self.undo.last_mut()
+			&& self.open
+			&& group.is_some()
+			&& last.group == group
+			&& now.duration_since(last.at) < COALESCE_DELAY
+		{
+			last.at = now;
+			return;
+		}
+		self.undo.push(Step {
+			state: before,
+			group,
+			at: now,
+		});
+		self.open = true;
+		if self.undo.len() > self.limit {
+			self.undo.remove(0);
+		}
+	}
+
+	/// Makes the next edit a step of its own, for example after the caret was moved.
+	pub fn break_group(&mut self) {
+		self.open = false;
+	}
+
+	/// Returns the state before the last step, keeping `current` to redo it.
+	pub fn undo(&mut self, current: T) -> Option<T> {
+		let step = self.undo.pop()?;
+		self.redo.push(current);
+		self.open = false;
+		Some(step.state)
+	}
+
+	/// Returns the state the last undo went back from, keeping `current` to undo again.
+	pub fn redo(&mut self, current: T) -> Option<T> {
+		let state = self.redo.pop()?;
+		self.undo.push(Step {
+			state: current,
+			group: None,
+			at: Instant::now(),
+		});
+		self.open = false;
+		Some(state)
+	}
+
+	pub fn can_undo(&self) -> bool {
+		!self.undo.is_empty()
+	}
+
+	pub fn can_redo(&self) -> bool {
+		!self.redo.is_empty()
+	}
+
+	pub fn clear(&mut self) {
+		self.undo.clear();
+		self.redo.clear();
+		self.open = false;
+	}
+}
+
+/// Keeps an [`UndoHistory`] for the state of a component across renders.
+///
+/// ```rust,ignore
+/// let (shapes, set_shapes) = use_state(Vec::new());
+/// let history = use_undo_history();
+/// // Before adding a shape
+/// history.borrow_mut().push(shapes.clone(), None);
+/// // On Ctrl+Z
+/// if let Some(previous) = history.borrow_mut().undo(shapes.clone()) {
+///     set_shapes(previous);
+/// }
+/// ```
+pub fn use_undo_history<T: 'static>() -> Rc<RefCell<UndoHistory<T>>> {
+	use_ref(UndoHistory::new())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_groups_coalesce_until_broken() {
+		let mut history = UndoHistory::new();
+		history.push("", Some("typing"));
+		history.push("a", Some("typing"));
+		history.push("ab", None);
+		history.push("abc", Some("typing"));
+		history.break_group();
+		history.push("abcd", Some("typing"));
+		assert_eq!(history.undo("abcde"), Some("abcd"));
+		assert_eq!(history.undo("abcd"), Some("abc"));
+		assert_eq!(history.undo("abc"), Some("ab"));
+		assert_eq!(history.undo("ab"), Some(""));
+		assert_eq!(history.undo(""), None);
+		assert_eq!(history.redo(""), Some("ab"));
+		assert_eq!(history.redo("ab"), Some("abc"));
+		history.push("abc", None);
+		assert!(!history.can_redo());
+	}
+
+	#[test]
+	fn test_limit_forgets_old_steps() {
+		let mut history = UndoHistory::with_limit(2);
+		for state in 0..5 {
+			history.push(state, None);
+		}
+		assert_eq!(history.undo(5), Some(4));
+		assert_eq!(history.undo(4), Some(3));
+		assert_eq!(history.undo(3), None);
+	}
+}
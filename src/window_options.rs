@@ -1,9 +1,10 @@
-use winit::dpi::LogicalSize;
+use winit::dpi::{LogicalPosition, LogicalSize};
 use winit::icon::RgbaIcon;
 use winit::monitor::Fullscreen;
 pub use winit::platform::wayland::Anchor;
 pub use winit::platform::wayland::KeyboardInteractivity;
 use winit::platform::wayland::WindowAttributesWayland;
+use winit::platform::x11::WindowAttributesX11;
 use winit::window::WindowAttributes;
 
 #[derive(Clone)]
@@ -12,6 +13,7 @@ pub struct LayerShellOptions {
 	pub exclusive_zone: i32,
 	pub margin: (i32, i32, i32, i32),
 	pub keyboard_interactivity: KeyboardInteractivity,
+	/// Native id of the output the surface is shown on. [`WindowOptions::monitor`] picks it by name.
 	pub output: Option<u64>,
 }
 impl Default for LayerShellOptions {
@@ -25,20 +27,209 @@ impl Default for LayerShellOptions {
 		}
 	}
 }
-#[derive(Default, Clone)]
+/// Options of the window created by [`create_window`](crate::create_window).
+///
+/// Can be written as a struct literal with `..Default::default()`, or with the builder methods:
+///
+/// ```rust,no_run
+/// # use hyprui::WindowOptions;
+/// let options = WindowOptions::new("Settings")
+///     .size(800., 600.)
+///     .resizable(false)
+///     .app_id("org.example.Settings");
+/// ```
+#[derive(Clone)]
 pub struct WindowOptions<'a> {
 	pub title: String,
 	pub min_size: (f64, f64),
 	pub preferred_size: (f64, f64),
 	pub max_size: (f64, f64),
+	/// Initial position of the window, in logical pixels from the top left corner of the monitor.
+	/// Most Wayland compositors ignore it.
+	pub position: Option<(f64, f64)>,
+	/// Whether the user can resize the window. Defaults to `true`.
+	pub resizable: bool,
 	pub enable_layer_shell: Option<LayerShellOptions>,
 	pub opaque: bool,
 	pub allow_backdrop_blur: bool,
+	/// Wayland app id. Prefer [`app_id`](Self::app_id), which also sets the X11 class.
 	pub wayland_name: Option<&'a str>,
+	/// Identifies the app to the desktop, like `org.example.App`. Sets the Wayland app id and the X11 `WM_CLASS`,
+	/// and should match the name of the desktop entry of the app.
+	pub app_id: Option<&'a str>,
+	/// Name of the monitor to open the window on, like `DP-1`. The window is opened on the
+	/// default monitor if no monitor has this name. Layer-shell surfaces are given to the compositor on this
+	/// monitor, unless [`LayerShellOptions::output`] is set.
+	pub monitor: Option<String>,
 	pub no_border: bool,
 	pub fullscreen: bool,
 	pub icon: Option<RgbaIcon>,
+	/// Scale factor applied to all text, overriding the desktop text scaling setting.
+	pub text_scale: Option<f32>,
+	/// Forces high-contrast mode on or off, overriding the desktop setting.
+	pub high_contrast: Option<bool>,
+	/// Closes the window when Escape is pressed, instead of passing the key to the app. Defaults to `false`.
+	pub exit_on_escape: bool,
+	/// Renders a frame on every refresh of the display, instead of only when something changes. Defaults to
+	/// `false`.
+	pub continuous_rendering: bool,
+}
+
+impl Default for WindowOptions<'_> {
+	fn default() -> Self {
+		Self {
+			title: String::new(),
+			min_size: (0., 0.),
+			preferred_size: (0., 0.),
+			max_size: (0., 0.),
+			position: None,
+			resizable: true,
+			enable_layer_shell: None,
+			opaque: false,
+			allow_backdrop_blur: false,
+			wayland_name: None,
+			app_id: None,
+			monitor: None,
+			no_border: false,
+			fullscreen: false,
+			icon: None,
+			text_scale: None,
+			high_contrast: None,
+			exit_on_escape: false,
+			continuous_rendering: false,
+		}
+	}
 }
+
+impl<'a> WindowOptions<'a> {
+	pub fn new(title: impl Into<String>) -> Self {
+		Self {
+			title: title.into(),
+			..Default::default()
+		}
+	}
+
+	pub fn title(mut self, title: impl Into<String>) -> Self {
+		self.title = title.into();
+		self
+	}
+
+	/// Sets the preferred size of the window.
+	pub fn size(mut self, width: f64, height: f64) -> Self {
+		self.preferred_size = (width, height);
+		self
+	}
+
+	pub fn min_size(mut self, width: f64, height: f64) -> Self {
+		self.min_size = (width, height);
+		self
+	}
+
+	pub fn max_size(mut self, width: f64, height: f64) -> Self {
+		self.max_size = (width, height);
+		self
+	}
+
+	pub fn position(mut self, x: f64, y: f64) -> Self {
+		self.position = Some((x, y));
+		self
+	}
+
+	pub fn resizable(mut self, resizable: bool) -> Self {
+		self.resizable = resizable;
+		self
+	}
+
+	pub fn layer_shell(mut self, options: LayerShellOptions) -> Self {
+		self.enable_layer_shell = Some(options);
+		self
+	}
+
+	pub fn opaque(mut self) -> Self {
+		self.opaque = true;
+		self
+	}
+
+	pub fn backdrop_blur(mut self) -> Self {
+		self.allow_backdrop_blur = true;
+		self
+	}
+
+	pub fn app_id(mut self, app_id: &'a str) -> Self {
+		self.app_id = Some(app_id);
+		self
+	}
+
+	pub fn monitor(mut self, name: impl Into<String>) -> Self {
+		self.monitor = Some(name.into());
+		self
+	}
+
+	pub fn no_border(mut self) -> Self {
+		self.no_border = true;
+		self
+	}
+
+	pub fn fullscreen(mut self) -> Self {
+		self.fullscreen = true;
+		self
+	}
+
+	pub fn icon(mut self, icon: RgbaIcon) -> Self {
+		self.icon = Some(icon);
+		self
+	}
+
+	pub fn text_scale(mut self, scale: f32) -> Self {
+		self.text_scale = Some(scale);
+		self
+	}
+
+	pub fn high_contrast(mut self, high_contrast: bool) -> Self {
+		self.high_contrast = Some(high_contrast);
+		self
+	}
+
+	/// Opens the window as a transparent layer-shell surface of one pixel in a corner, without keyboard focus, for
+	/// an app whose only UI is [on-screen displays](crate::show_osd), like a volume or brightness daemon. The OSDs
+	/// get surfaces of their own.
+	///
+	/// ```rust,no_run
+	/// # use hyprui::WindowOptions;
+	/// let options = WindowOptions::new("Volume").osd();
+	/// ```
+	pub fn osd(mut self) -> Self {
+		self.enable_layer_shell = Some(LayerShellOptions {
+			anchor: Anchor::TOP | Anchor::LEFT,
+			..Default::default()
+		});
+		self.preferred_size = (1., 1.);
+		self.opaque = false;
+		self.no_border = true;
+		self.resizable = false;
+		self
+	}
+
+	/// Closes the window when Escape is pressed, for throwaway tools and launchers.
+	///
+	/// Escape then never reaches the app, so it can't blur the focused element or close popups.
+	pub fn exit_on_escape(mut self) -> Self {
+		self.exit_on_escape = true;
+		self
+	}
+
+	/// Renders a frame on every refresh of the display even when nothing changes, for apps animating most of the
+	/// time like games and visualizers.
+	///
+	/// By default the window waits for input, a redraw requested by a state change or [`use_tick`](crate::use_tick),
+	/// or a frame scheduled by a timer, and uses no CPU in between, which is what panels and other components
+	/// running all the time need.
+	pub fn continuous_rendering(mut self) -> Self {
+		self.continuous_rendering = true;
+		self
+	}
+}
+
 impl From<WindowOptions<'_>> for WindowAttributes {
 	fn from(options: WindowOptions) -> Self {
 		let mut winit_opt = WindowAttributes::default()
@@ -55,7 +246,11 @@ impl From<WindowOptions<'_>> for WindowAttributes {
 			} else {
 				options.title
 			})
-			.with_window_icon(options.icon.map(|i| i.into()));
+			.with_window_icon(options.icon.map(|i| i.into()))
+			.with_resizable(options.resizable);
+		if let Some((x, y)) = options.position {
+			winit_opt = winit_opt.with_position(LogicalPosition::new(x, y));
+		}
 		if options.min_size != (0., 0.) {
 			winit_opt =
 				winit_opt.with_min_surface_size(LogicalSize::new(options.min_size.0, options.min_size.1));
@@ -71,26 +266,40 @@ impl From<WindowOptions<'_>> for WindowAttributes {
 				winit_opt.with_max_surface_size(LogicalSize::new(options.max_size.0, options.max_size.1))
 		}
 
-		let mut wayland_opts = WindowAttributesWayland::default();
-		let mut has_wl_opts = false;
-		if let Some(l) = options.enable_layer_shell {
-			wayland_opts = wayland_opts
-				.with_layer_shell()
-				.with_margin(l.margin.0, l.margin.1, l.margin.2, l.margin.3)
-				.with_anchor(l.anchor)
-				.with_exclusive_zone(l.exclusive_zone);
-			if let Some(output) = l.output {
-				wayland_opts = wayland_opts.with_output(output);
-			}
-			has_wl_opts = true;
-		}
-		if let Some(wayland_name) = options.wayland_name {
-			wayland_opts = wayland_opts.with_name(wayland_name, "");
-			has_wl_opts = true;
-		}
-		if has_wl_opts {
+		let app_id = options.app_id.or(options.wayland_name);
+		let wayland_opts = wayland_attributes(options.enable_layer_shell.as_ref(), app_id);
+		// Only one set of platform attributes can be given, so pick the backend winit will use
+		if std::env::var_os("WAYLAND_DISPLAY").is_none()
+			&& let Some(app_id) = app_id
+		{
+			winit_opt = winit_opt.with_platform_attributes(Box::new(
+				WindowAttributesX11::default().with_name(app_id, app_id),
+			));
+		} else if let Some(wayland_opts) = wayland_opts {
 			winit_opt = winit_opt.with_platform_attributes(Box::new(wayland_opts));
 		}
 		winit_opt
 	}
 }
+
+/// Returns the Wayland attributes of a window with these layer-shell options and app id, if it needs any.
+pub(crate) fn wayland_attributes(
+	layer_shell: Option<&LayerShellOptions>,
+	app_id: Option<&str>,
+) -> Option<WindowAttributesWayland> {
+	let mut wayland_opts = WindowAttributesWayland::default();
+	if let Some(l) = layer_shell {
+		wayland_opts = wayland_opts
+			.with_layer_shell()
+			.with_margin(l.margin.0, l.margin.1, l.margin.2, l.margin.3)
+			.with_anchor(l.anchor)
+			.with_exclusive_zone(l.exclusive_zone);
+		if let Some(output) = l.output {
+			wayland_opts = wayland_opts.with_output(output);
+		}
+	}
+	if let Some(app_id) = app_id {
+		wayland_opts = wayland_opts.with_name(app_id, "");
+	}
+	(layer_shell.is_some() || app_id.is_some()).then_some(wayland_opts)
+}
@@ -3,29 +3,77 @@ use glutin::config::{ColorBufferType, Config, ConfigTemplateBuilder, GetGlConfig
 use glutin::context::{
 	ContextApi, ContextAttributesBuilder, NotCurrentContext, PossiblyCurrentContext, Version,
 };
-use glutin::display::GetGlDisplay;
+use glutin::display::{AsRawDisplay, GetGlDisplay, RawDisplay};
 use glutin::prelude::{GlDisplay, NotCurrentGlContext, PossiblyCurrentGlContext};
-use glutin::surface::{GlSurface, Surface, SwapInterval, WindowSurface};
+use glutin::surface::{
+	AsRawSurface, GlSurface, RawSurface, Rect, Surface, SwapInterval, WindowSurface,
+};
 use glutin_winit::DisplayBuilder;
 use glutin_winit::GlWindow;
+use skia_safe::ColorType;
 use skia_safe::gpu::direct_contexts::make_gl;
 use skia_safe::gpu::ganesh::gl::backend_render_targets;
 use skia_safe::gpu::gl::Format;
 use skia_safe::gpu::{self, DirectContext};
-use skia_safe::{Color, ColorType};
+use std::ffi::{CStr, c_char, c_void};
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::sync::OnceLock;
 use winit::application::ApplicationHandler;
-use winit::event::{ButtonSource, ElementState, Ime, KeyEvent, MouseButton, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::dpi::{LogicalSize, Position};
+use winit::event::{
+	ButtonSource, ElementState, Ime, KeyEvent, MouseButton, MouseScrollDelta, StartCause, WindowEvent,
+};
+use winit::event_loop::{
+	ActiveEventLoop, AsyncRequestSerial, ControlFlow, EventLoop, EventLoopProxy,
+};
+use winit::monitor::Fullscreen;
+use winit::platform::startup_notify::WindowExtStartupNotify;
 use winit::raw_window_handle::HasWindowHandle;
 use winit::window::{Window, WindowAttributes, WindowId};
 
-use crate::REQUEST_REDRAW;
+use crate::damage::Damage;
+use crate::element::container::drag_and_drop::{self, Offer};
+use crate::profiling::profile_span;
+use crate::window_options::{LayerShellOptions, wayland_attributes};
+use crate::{Key, NamedKey, REQUEST_REDRAW, WindowOptions};
+
+static EVENT_LOOP_PROXY: OnceLock<EventLoopProxy> = OnceLock::new();
+
+/// Distance scrolled by one notch of a mouse wheel, in logical pixels.
+const SCROLL_LINE_HEIGHT: f32 = 40.;
+
+/// Wakes up the event loop from any thread and schedules a redraw of the window.
+pub(crate) fn wake_up() {
+	if let Some(proxy) = EVENT_LOOP_PROXY.get() {
+		proxy.wake_up();
+	}
+}
+
+/// Returns `true` once a window event loop is running.
+pub(crate) fn is_running() -> bool {
+	EVENT_LOOP_PROXY.get().is_some()
+}
+
 impl ApplicationHandler for WinitApp {
+	fn proxy_wake_up(&mut self, _event_loop: &dyn ActiveEventLoop) {
+		if let Some(SurfaceAndWindow { window, .. }) = self.window.as_ref() {
+			window.request_redraw();
+		}
+	}
+
+	fn new_events(&mut self, _event_loop: &dyn ActiveEventLoop, cause: StartCause) {
+		// A frame was requested for this time, see `frame_scheduler::request_frame_at`
+		if let StartCause::ResumeTimeReached { .. } = cause
+			&& let Some(SurfaceAndWindow { window, .. }) = self.window.as_ref()
+		{
+			window.request_redraw();
+		}
+	}
+
 	fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
 		let (window, gl_config) = match DisplayBuilder::new()
-			.with_window_attributes(Some(self.window_options.clone()))
+			.with_window_attributes(Some(self.window_attributes(event_loop)))
 			.build(event_loop, self.template.clone(), gl_config_picker)
 		{
 			Ok((window, gl_config)) => (window.unwrap(), gl_config),
@@ -42,21 +90,34 @@ impl ApplicationHandler for WinitApp {
 		log::trace!("Recreating window in `resumed`");
 		// Pick the config which we already use for the context.
 		let gl_config = self.gl_context.as_ref().unwrap().config();
-		let window =
-			match glutin_winit::finalize_window(event_loop, self.window_options.clone(), &gl_config) {
-				Ok(window) => window,
-				Err(err) => {
-					self.exit_state = Err(err.into());
-					event_loop.exit();
-					return;
-				}
-			};
+		let window = match glutin_winit::finalize_window(
+			event_loop,
+			self.window_attributes(event_loop),
+			&gl_config,
+		) {
+			Ok(window) => window,
+			Err(err) => {
+				self.exit_state = Err(err.into());
+				event_loop.exit();
+				return;
+			}
+		};
 
 		self.post_opengl_init(window, gl_config);
 	}
 
+	fn about_to_wait(&mut self, _event_loop: &dyn ActiveEventLoop) {
+		#[cfg(feature = "wayland")]
+		for event in crate::data_device::dispatch() {
+			self.drag_event(event);
+		}
+	}
+
 	fn suspended(&mut self, _event_loop: &dyn ActiveEventLoop) {
 		log::trace!("Android window removed");
+		#[cfg(feature = "wayland")]
+		crate::data_device::disconnect();
+		self.osd = None;
 		self.window = None;
 
 		// Make context not current.
@@ -74,9 +135,24 @@ impl ApplicationHandler for WinitApp {
 	fn window_event(
 		&mut self,
 		event_loop: &dyn ActiveEventLoop,
-		_window_id: WindowId,
+		window_id: WindowId,
 		event: WindowEvent,
 	) {
+		if self
+			.osd
+			.as_ref()
+			.is_some_and(|osd| osd.window.id() == window_id)
+		{
+			match event {
+				WindowEvent::SurfaceResized(size) if size.width != 0 && size.height != 0 => {
+					self.resize_osd(size.width, size.height);
+				}
+				WindowEvent::RedrawRequested => self.draw_osd(),
+				// The OSD gets no input
+				_ => {}
+			}
+			return;
+		}
 		match event {
 			WindowEvent::Ime(ime) => {
 				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
@@ -89,6 +165,13 @@ impl ApplicationHandler for WinitApp {
 				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
 					return;
 				};
+				if self.exit_on_escape
+					&& event.state == ElementState::Pressed
+					&& event.logical_key == Key::Named(NamedKey::Escape)
+				{
+					event_loop.exit();
+					return;
+				}
 				(self.callbacks.on_key_event)(event);
 				window.request_redraw();
 			}
@@ -98,13 +181,14 @@ impl ApplicationHandler for WinitApp {
 					window,
 					mut skia_context,
 					skia_surface: _,
+					partial_update,
 				}) = self.window.take()
 				else {
 					return;
 				};
 
 				let gl_context = self.gl_context.take().unwrap();
-				let skia_surface = self.make_skia_surface(
+				let skia_surface = Self::make_skia_surface(
 					&gl_surface,
 					&gl_context.config(),
 					&mut skia_context,
@@ -124,26 +208,82 @@ impl ApplicationHandler for WinitApp {
 					skia_surface,
 					skia_context,
 					window,
+					partial_update,
 				}
 				.into();
 			}
+			WindowEvent::Focused(focused) => {
+				(self.callbacks.on_window_focus)(focused);
+			}
 			WindowEvent::CloseRequested => event_loop.exit(),
+			WindowEvent::ActivationTokenDone { serial, token } => {
+				if let Some(i) = self
+					.pending_activations
+					.iter()
+					.position(|(s, _)| *s == serial)
+				{
+					let (_, command) = self.pending_activations.remove(i);
+					crate::open::launch(&command, Some(token.into_raw()));
+				}
+			}
 			WindowEvent::RedrawRequested => {
 				let Some(SurfaceAndWindow {
 					skia_surface,
 					skia_context,
 					gl_surface,
-					..
+					window,
+					partial_update,
 				}) = self.window.as_mut()
 				else {
 					return;
 				};
-				skia_surface.canvas().clear(Color::TRANSPARENT);
-				(self.callbacks.on_render_callback)(skia_surface.canvas());
-				skia_context.flush_and_submit();
-				gl_surface
-					.swap_buffers(self.gl_context.as_ref().unwrap())
-					.unwrap();
+				// EGL_KHR_partial_update takes the damage before the frame draws anything on the back buffer
+				let height = gl_surface.height().unwrap_or(0);
+				let set_damage_region = |damage: &Damage| {
+					if let (Some(partial_update), Damage::Rects(rects)) = (partial_update.as_ref(), damage) {
+						partial_update.set_damage_region(&egl_rects(rects, height));
+					}
+				};
+				// The callback repaints what changed since the frame still in the back buffer
+				let damage = (self.callbacks.on_render_callback)(
+					skia_surface.canvas(),
+					gl_surface.buffer_age(),
+					&set_damage_region,
+				);
+				for command in crate::open::take_pending() {
+					match window.request_activation_token() {
+						Ok(serial) => self.pending_activations.push((serial, command)),
+						// Not supported by the compositor, open it without focus handoff.
+						Err(_) => crate::open::launch(&command, None),
+					}
+				}
+				let damage = match damage {
+					Damage::Full => None,
+					Damage::Rects(rects) => Some(egl_rects(&rects, height)),
+				};
+				{
+					profile_span!("flush");
+					skia_context.flush_and_submit();
+				}
+				profile_span!("swap");
+				let gl_context = self.gl_context.as_ref().unwrap();
+				match (&*gl_surface, gl_context, &damage) {
+					// Tells the compositor which parts of the window changed, when EGL supports it
+					(Surface::Egl(surface), PossiblyCurrentContext::Egl(context), Some(rects)) => {
+						surface.swap_buffers_with_damage(context, rects)
+					}
+					_ => gl_surface.swap_buffers(gl_context),
+				}
+				.unwrap();
+				// Swapping waits for vsync, so ticking components get one frame per refresh
+				if self.continuous_rendering || crate::frame_scheduler::wants_next_frame() {
+					window.request_redraw();
+				}
+				self.update_osd(event_loop);
+				event_loop.set_control_flow(match crate::frame_scheduler::next_frame_deadline() {
+					Some(deadline) => ControlFlow::WaitUntil(deadline),
+					None => ControlFlow::Wait,
+				});
 
 				log::debug!("Render");
 			}
@@ -165,28 +305,80 @@ impl ApplicationHandler for WinitApp {
 				state,
 				position: _,
 				primary: true,
-				button: ButtonSource::Mouse(button),
+				button,
 			} => {
 				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
 					return;
 				};
 				use MouseButton as B;
+				let button = match button {
+					ButtonSource::Mouse(B::Left) => 0,
+					ButtonSource::Mouse(B::Right) => 1,
+					ButtonSource::Mouse(B::Middle) => 2,
+					ButtonSource::Mouse(B::Back) => 3,
+					ButtonSource::Mouse(B::Forward) => 4,
+					ButtonSource::Mouse(B::Other(b)) => b,
+					// The first finger on a touchscreen presses like the left button, so touch can tap and drag
+					ButtonSource::Touch { .. } => 0,
+					_ => return,
+				};
 				(self.callbacks.on_mouse_button)(
 					match state {
 						ElementState::Pressed => true,
 						ElementState::Released => false,
 					},
-					match button {
-						B::Left => 0,
-						B::Right => 1,
-						B::Middle => 2,
-						B::Back => 3,
-						B::Forward => 4,
-						B::Other(b) => b,
-					},
+					button,
 				);
 				window.request_redraw();
 			}
+			// Files dragged from other apps move the pointer while they are over the window. The data device tells
+			// about them too when it is connected, with all the types they are offered in.
+			WindowEvent::DragEntered { paths, position } if !data_device_connected() => {
+				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
+					return;
+				};
+				let position = position.to_logical(window.scale_factor());
+				(self.callbacks.on_mouse_move)(position.x, position.y);
+				drag_and_drop::enter_from_other_app(Offer::files(&paths));
+				window.request_redraw();
+			}
+			WindowEvent::DragMoved { position } if !data_device_connected() => {
+				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
+					return;
+				};
+				let position = position.to_logical(window.scale_factor());
+				(self.callbacks.on_mouse_move)(position.x, position.y);
+				window.request_redraw();
+			}
+			WindowEvent::DragDropped { paths, position } if !data_device_connected() => {
+				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
+					return;
+				};
+				let position = position.to_logical(window.scale_factor());
+				(self.callbacks.on_mouse_move)(position.x, position.y);
+				// The paths are only final once dropped
+				drag_and_drop::enter_from_other_app(Offer::files(&paths));
+				drag_and_drop::drop_from_other_app();
+				window.request_redraw();
+			}
+			WindowEvent::DragLeft { .. } if !data_device_connected() => {
+				drag_and_drop::leave_from_other_app();
+			}
+			WindowEvent::MouseWheel { delta, .. } => {
+				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
+					return;
+				};
+				// Winit reports how far the content moves, the opposite of how far it is scrolled
+				let (dx, dy) = match delta {
+					MouseScrollDelta::LineDelta(x, y) => (-x * SCROLL_LINE_HEIGHT, -y * SCROLL_LINE_HEIGHT),
+					MouseScrollDelta::PixelDelta(position) => {
+						let position = position.to_logical::<f32>(window.scale_factor());
+						(-position.x, -position.y)
+					}
+				};
+				(self.callbacks.on_mouse_wheel)(dx, dy);
+				window.request_redraw();
+			}
 			_ => {
 				let Some(SurfaceAndWindow { window, .. }) = self.window.as_mut() else {
 					return;
@@ -198,7 +390,10 @@ impl ApplicationHandler for WinitApp {
 
 	fn destroy_surfaces(&mut self, _event_loop: &dyn ActiveEventLoop) {
 		let _gl_display = self.gl_context.take().unwrap().display();
+		#[cfg(feature = "wayland")]
+		crate::data_device::disconnect();
 
+		self.osd = None;
 		self.window = None;
 		if let glutin::display::Display::Egl(display) = _gl_display {
 			unsafe {
@@ -208,6 +403,29 @@ impl ApplicationHandler for WinitApp {
 	}
 }
 
+/// Follows the drags over `window` through the Wayland data device, until it is disconnected when the window is
+/// dropped.
+#[cfg(feature = "wayland")]
+fn connect_data_device(window: &dyn Window) {
+	use winit::raw_window_handle::{HasDisplayHandle, RawDisplayHandle, RawWindowHandle};
+	if let (Ok(display), Ok(handle)) = (window.display_handle(), window.window_handle())
+		&& let (RawDisplayHandle::Wayland(display), RawWindowHandle::Wayland(handle)) =
+			(display.as_raw(), handle.as_raw())
+	{
+		// Both stay alive until the window is dropped, after the data device is disconnected
+		unsafe { crate::data_device::connect(display.display, handle.surface) };
+	}
+}
+
+/// Returns `true` when the drags over the window are followed through the data device, whose events replace the
+/// drag events of winit.
+fn data_device_connected() -> bool {
+	#[cfg(feature = "wayland")]
+	return crate::data_device::is_connected();
+	#[cfg(not(feature = "wayland"))]
+	false
+}
+
 fn create_gl_context(window: &dyn Window, gl_config: &Config) -> NotCurrentContext {
 	let raw_window_handle = window.window_handle().ok().map(|wh| wh.as_raw());
 
@@ -238,36 +456,109 @@ fn create_gl_context(window: &dyn Window, gl_config: &Config) -> NotCurrentConte
 	}
 }
 pub(crate) struct Callbacks {
-	pub on_render_callback: Box<dyn FnMut(&skia_safe::Canvas)>,
+	/// Draws a frame on a canvas whose back buffer holds the frame drawn that many frames ago, or 0 if unknown,
+	/// and returns the parts of the window it repainted.
+	///
+	/// The parts to repaint must be given to the last argument before anything is drawn on the canvas, so the
+	/// driver knows which parts of the back buffer it can skip loading.
+	pub on_render_callback: Box<dyn FnMut(&skia_safe::Canvas, u32, &dyn Fn(&Damage)) -> Damage>,
+	/// Draws the [OSD](crate::show_osd) built by the last frame on the canvas of its surface, and returns its
+	/// size in logical pixels, or `None` if none is shown.
+	pub on_render_osd: Box<dyn FnMut(&skia_safe::Canvas) -> Option<(f32, f32)>>,
 	pub on_mouse_move: Box<dyn FnMut(f64, f64)>,
 	pub on_window_resize: Box<dyn FnMut(f64, f64)>,
 	pub on_mouse_button: Box<dyn FnMut(bool, u16)>,
+	pub on_mouse_wheel: Box<dyn FnMut(f32, f32)>,
 	pub on_key_event: Box<dyn FnMut(KeyEvent)>,
 	pub on_ime_event: Box<dyn FnMut(Ime)>,
+	pub on_window_focus: Box<dyn FnMut(bool)>,
 }
 pub(crate) struct WinitApp {
 	template: ConfigTemplateBuilder,
 	gl_context: Option<PossiblyCurrentContext>,
 	exit_state: color_eyre::Result<()>,
 	window_options: WindowAttributes,
+	/// Name of the monitor to open the window on, see [`WindowOptions::monitor`].
+	monitor: Option<String>,
+	/// The layer-shell options and app id, to give the layer surface the output of the monitor.
+	layer_shell: Option<(LayerShellOptions, Option<String>)>,
+	/// See [`WindowOptions::exit_on_escape`].
+	exit_on_escape: bool,
+	/// See [`WindowOptions::continuous_rendering`].
+	continuous_rendering: bool,
 	window: Option<SurfaceAndWindow>,
+	/// The layer surface of the [OSD](crate::show_osd) while one is shown.
+	osd: Option<OsdSurface>,
 	callbacks: Callbacks,
+	/// Commands of [`open_url`](crate::open_url) and [`DesktopEntry::launch`](crate::DesktopEntry::launch)
+	/// waiting for their activation token.
+	pending_activations: Vec<(AsyncRequestSerial, Vec<String>)>,
 }
 
 impl WinitApp {
-	pub(crate) fn new(options: impl Into<WindowAttributes>, callbacks: Callbacks) -> Self {
-		let options = options.into();
+	pub(crate) fn new(options: WindowOptions, callbacks: Callbacks) -> Self {
+		let monitor = options.monitor.clone();
+		let layer_shell = options.enable_layer_shell.clone().map(|layer_shell| {
+			let app_id = options.app_id.or(options.wayland_name);
+			(layer_shell, app_id.map(String::from))
+		});
+		let exit_on_escape = options.exit_on_escape;
+		let continuous_rendering = options.continuous_rendering;
 		Self {
 			template: ConfigTemplateBuilder::new()
 				.with_alpha_size(8)
 				.with_transparency(true),
-			window_options: options.clone(),
+			window_options: options.into(),
+			monitor,
+			layer_shell,
+			exit_on_escape,
+			continuous_rendering,
 			exit_state: Ok(()),
 			gl_context: None,
 			window: None,
+			osd: None,
 			callbacks,
+			pending_activations: Vec::new(),
+		}
+	}
+	/// Returns the window attributes, placed on the requested monitor if it is connected.
+	fn window_attributes(&self, event_loop: &dyn ActiveEventLoop) -> WindowAttributes {
+		let mut attributes = self.window_options.clone();
+		let Some(name) = &self.monitor else {
+			return attributes;
+		};
+		let Some(monitor) = event_loop
+			.available_monitors()
+			.find(|monitor| monitor.name().is_some_and(|n| n == *name))
+		else {
+			log::warn!("monitor {name} not found, opening the window on the default monitor");
+			return attributes;
+		};
+		if let Some((layer_shell, app_id)) = &self.layer_shell {
+			// Layer surfaces are put on an output by the compositor, they have no position
+			let layer_shell = LayerShellOptions {
+				output: layer_shell.output.or(Some(monitor.native_id())),
+				..layer_shell.clone()
+			};
+			if let Some(wayland) = wayland_attributes(Some(&layer_shell), app_id.as_deref()) {
+				attributes = attributes.with_platform_attributes(Box::new(wayland));
+			}
+		} else if attributes.fullscreen.is_some() {
+			attributes = attributes.with_fullscreen(Some(Fullscreen::Borderless(Some(monitor))));
+		} else if let Some(origin) = monitor.position() {
+			// Offsets the requested position by the origin of the monitor
+			let position = match attributes.position {
+				Some(position) => {
+					let offset = position.to_physical::<i32>(monitor.scale_factor());
+					Position::Physical((origin.x + offset.x, origin.y + offset.y).into())
+				}
+				None => Position::Physical(origin),
+			};
+			attributes = attributes.with_position(position);
 		}
+		attributes
 	}
+
 	fn post_opengl_init(&mut self, window: Box<dyn Window>, gl_config: Config) {
 		// Create gl context.
 		self.gl_context =
@@ -295,6 +586,8 @@ impl WinitApp {
 			log::error!("Error setting vsync: {res:?}");
 		}
 		let window: Rc<dyn Window> = window.into();
+		#[cfg(feature = "wayland")]
+		connect_data_device(window.as_ref());
 		REQUEST_REDRAW.set({
 			let window = Rc::downgrade(&window);
 			Box::new(move || {
@@ -305,13 +598,163 @@ impl WinitApp {
 			})
 		});
 		let (skia_surface, skia_context) = self.initialize_skia(&gl_config, &gl_surface);
+		let partial_update = PartialUpdate::new(&gl_surface);
 		self.window = Some(SurfaceAndWindow {
 			gl_surface,
 			window,
 			skia_surface,
 			skia_context,
+			partial_update,
 		});
 	}
+	/// Moves the pointer with a drag followed through the data device, like the drag events of winit.
+	#[cfg(feature = "wayland")]
+	fn drag_event(&mut self, event: crate::data_device::DragEvent) {
+		use crate::data_device::DragEvent;
+		let Some(SurfaceAndWindow { window, .. }) = self.window.as_ref() else {
+			return;
+		};
+		match event {
+			DragEvent::Enter {
+				position,
+				mime_types,
+			} => {
+				(self.callbacks.on_mouse_move)(position.0, position.1);
+				// The drags of the app are already followed by the app
+				if let Some(mime_types) = mime_types {
+					drag_and_drop::enter_from_other_app(Offer {
+						mime_types,
+						data: None,
+					});
+				}
+			}
+			DragEvent::Motion { position } => (self.callbacks.on_mouse_move)(position.0, position.1),
+			DragEvent::Leave => drag_and_drop::leave_from_other_app(),
+			DragEvent::Drop => drag_and_drop::drop_from_other_app(),
+			DragEvent::Ended { on_window } => {
+				if !on_window {
+					drag_and_drop::end_out_of_window();
+				}
+				// The compositor held the button during the drag, the app never saw it released
+				(self.callbacks.on_mouse_button)(false, 0);
+			}
+		}
+		window.request_redraw();
+	}
+
+	/// Opens the surface of the OSD when one is shown and asks for it to be drawn with this frame, or closes it
+	/// once the OSD faded out.
+	fn update_osd(&mut self, event_loop: &dyn ActiveEventLoop) {
+		if !crate::osd::is_shown() {
+			self.osd = None;
+			return;
+		}
+		if self.osd.is_none() {
+			self.osd = self.open_osd(event_loop);
+		}
+		if let Some(osd) = &self.osd {
+			osd.window.request_redraw();
+		}
+	}
+
+	/// Opens a layer surface centered on the screen for the OSD, sharing the GL context of the window.
+	fn open_osd(&mut self, event_loop: &dyn ActiveEventLoop) -> Option<OsdSurface> {
+		let (Some(main), Some(gl_context)) = (self.window.as_mut(), self.gl_context.as_ref()) else {
+			return None;
+		};
+		let gl_config = gl_context.config();
+		// Sized to the OSD once it's laid out
+		let mut attributes = WindowAttributes::default()
+			.with_title("OSD")
+			.with_transparent(true)
+			.with_decorations(false)
+			.with_resizable(false)
+			.with_surface_size(LogicalSize::new(1., 1.));
+		if std::env::var_os("WAYLAND_DISPLAY").is_some()
+			&& let Some(wayland) = wayland_attributes(Some(&LayerShellOptions::default()), None)
+		{
+			attributes = attributes.with_platform_attributes(Box::new(wayland));
+		}
+		let window = glutin_winit::finalize_window(event_loop, attributes, &gl_config)
+			.inspect_err(|err| log::error!("failed to open the surface of the OSD: {err}"))
+			.ok()?;
+		let attrs = window.build_surface_attributes(Default::default()).ok()?;
+		let gl_surface = unsafe {
+			gl_config
+				.display()
+				.create_window_surface(&gl_config, &attrs)
+				.inspect_err(|err| log::error!("failed to create the GL surface of the OSD: {err}"))
+				.ok()?
+		};
+		gl_context.make_current(&gl_surface).ok()?;
+		// The window already waits for vsync, the OSD is drawn right after it
+		if let Err(err) = gl_surface.set_swap_interval(gl_context, SwapInterval::DontWait) {
+			log::debug!("failed to turn off vsync for the OSD: {err:?}");
+		}
+		let skia_surface =
+			Self::make_skia_surface(&gl_surface, &gl_config, &mut main.skia_context, 0, 0);
+		gl_context.make_current(&main.gl_surface).ok()?;
+		Some(OsdSurface {
+			skia_surface,
+			gl_surface,
+			window,
+			size: (1., 1.),
+		})
+	}
+
+	/// Draws the OSD built by the last frame on its surface, resizing the surface to it.
+	fn draw_osd(&mut self) {
+		let (Some(osd), Some(main), Some(gl_context)) = (
+			self.osd.as_mut(),
+			self.window.as_mut(),
+			self.gl_context.as_ref(),
+		) else {
+			return;
+		};
+		if gl_context.make_current(&osd.gl_surface).is_err() {
+			return;
+		}
+		let size = (self.callbacks.on_render_osd)(osd.skia_surface.canvas());
+		main.skia_context.flush_and_submit();
+		if let Err(err) = osd.gl_surface.swap_buffers(gl_context) {
+			log::error!("failed to present the OSD: {err}");
+		}
+		// Back to the window, which the next frame draws on
+		gl_context.make_current(&main.gl_surface).ok();
+		if let Some(size) = size
+			&& size != osd.size
+		{
+			osd.size = size;
+			let _ = osd
+				.window
+				.request_surface_size(LogicalSize::new(size.0 as f64, size.1 as f64).into());
+		}
+	}
+
+	/// Gives the buffers of the OSD its new size, like the window's when it is resized.
+	fn resize_osd(&mut self, width: u32, height: u32) {
+		let (Some(osd), Some(main), Some(gl_context)) = (
+			self.osd.as_mut(),
+			self.window.as_mut(),
+			self.gl_context.as_ref(),
+		) else {
+			return;
+		};
+		osd.gl_surface.resize(
+			gl_context,
+			NonZeroU32::new(width).unwrap(),
+			NonZeroU32::new(height).unwrap(),
+		);
+		osd.skia_surface = Self::make_skia_surface(
+			&osd.gl_surface,
+			&gl_context.config(),
+			&mut main.skia_context,
+			width,
+			height,
+		);
+		osd.window.request_redraw();
+	}
+
 	pub(crate) fn initialize_skia(
 		&mut self,
 		gl_config: &Config,
@@ -330,12 +773,11 @@ impl WinitApp {
 		let mut gr_context = make_gl(interface, None).expect("Failed to create Skia DirectContext");
 
 		return (
-			self.make_skia_surface(gl_surface, gl_config, &mut gr_context, 0, 0),
+			Self::make_skia_surface(gl_surface, gl_config, &mut gr_context, 0, 0),
 			gr_context,
 		);
 	}
 	fn make_skia_surface(
-		&self,
 		gl_surface: &Surface<WindowSurface>,
 		gl_config: &Config,
 		gr_context: &mut DirectContext,
@@ -391,6 +833,7 @@ impl WinitApp {
 	pub(crate) fn run(mut self) {
 		let event_loop = EventLoop::new().unwrap();
 		event_loop.set_control_flow(ControlFlow::Wait);
+		EVENT_LOOP_PROXY.set(event_loop.create_proxy()).ok();
 		event_loop.run_app(&mut self).unwrap();
 		self.exit_state.unwrap();
 	}
@@ -403,6 +846,104 @@ struct SurfaceAndWindow {
 	// NOTE: Window should be dropped after all resources created using its
 	// raw-window-handle.
 	window: Rc<dyn Window>,
+	partial_update: Option<PartialUpdate>,
+}
+
+/// The layer surface an [OSD](crate::show_osd) is drawn on, with the GL context and Skia context of the window.
+struct OsdSurface {
+	skia_surface: skia_safe::Surface,
+	gl_surface: Surface<WindowSurface>,
+	// Dropped after the surfaces drawing to it
+	window: Box<dyn Window>,
+	/// The size the surface was last given, in logical pixels.
+	size: (f32, f32),
+}
+
+type EglQueryString = unsafe extern "system" fn(display: *const c_void, name: i32) -> *const c_char;
+type EglSetDamageRegion = unsafe extern "system" fn(
+	display: *const c_void,
+	surface: *const c_void,
+	rects: *const i32,
+	n_rects: i32,
+) -> u32;
+
+/// `eglSetDamageRegionKHR` of EGL_KHR_partial_update, which lets the driver skip loading the parts of the back
+/// buffer that aren't repainted, a large part of the cost of a frame on tiled mobile GPUs.
+struct PartialUpdate {
+	set_damage_region: EglSetDamageRegion,
+	display: *const c_void,
+	surface: *const c_void,
+}
+
+impl PartialUpdate {
+	/// Returns `None` if the surface isn't an EGL surface or the driver doesn't have the extension.
+	fn new(gl_surface: &Surface<WindowSurface>) -> Option<Self> {
+		const EGL_EXTENSIONS: i32 = 0x3055;
+		let display = gl_surface.display();
+		let (RawDisplay::Egl(egl_display), RawSurface::Egl(egl_surface)) =
+			(display.raw_display(), gl_surface.raw_surface())
+		else {
+			return None;
+		};
+		let query_string = display.get_proc_address(c"eglQueryString");
+		let set_damage_region = display.get_proc_address(c"eglSetDamageRegionKHR");
+		if query_string.is_null() || set_damage_region.is_null() {
+			return None;
+		}
+		let query_string: EglQueryString = unsafe { std::mem::transmute(query_string) };
+		let extensions = unsafe { query_string(egl_display, EGL_EXTENSIONS) };
+		if extensions.is_null() {
+			return None;
+		}
+		let extensions = unsafe { CStr::from_ptr(extensions) }.to_string_lossy();
+		if !extensions
+			.split_whitespace()
+			.any(|extension| extension == "EGL_KHR_partial_update")
+		{
+			return None;
+		}
+		Some(Self {
+			set_damage_region: unsafe { std::mem::transmute(set_damage_region) },
+			display: egl_display,
+			surface: egl_surface,
+		})
+	}
+
+	/// Limits the drawing of the frame to `rects`, which must be called before anything is drawn on the back buffer.
+	fn set_damage_region(&self, rects: &[Rect]) {
+		let rects: Vec<i32> = rects
+			.iter()
+			.flat_map(|rect| [rect.x, rect.y, rect.width, rect.height])
+			.collect();
+		unsafe {
+			(self.set_damage_region)(
+				self.display,
+				self.surface,
+				rects.as_ptr(),
+				(rects.len() / 4) as i32,
+			);
+		}
+	}
+}
+
+/// Converts `rects` from the top left corner of the canvas to EGL rectangles, from the bottom left corner of a
+/// surface `height` pixels high.
+fn egl_rects(rects: &[skia_safe::IRect], height: u32) -> Vec<Rect> {
+	if rects.is_empty() {
+		// EGL takes no rectangles to mean the whole surface
+		return vec![Rect::new(0, 0, 1, 1)];
+	}
+	rects
+		.iter()
+		.map(|rect| {
+			Rect::new(
+				rect.left,
+				height as i32 - rect.bottom,
+				rect.width(),
+				rect.height(),
+			)
+		})
+		.collect()
 }
 
 fn gl_config_picker(configs: Box<dyn Iterator<Item = Config> + '_>) -> Config {